@@ -15,35 +15,21 @@
 //! cargo run -p controller --bin crdgen | kubectl apply -f -
 //! ```
 //!
+//! Same output as `secret-manager-controller crd generate`.
+//!
 //! The generated CRD includes:
 //! - OpenAPI schema validation
 //! - Required fields
 //! - Default values
 //! - Status subresource
 
-use controller::crd::SecretManagerConfig;
-use kube::core::CustomResourceExt;
+use controller::crd::manifests::crd_yaml;
 
 fn main() {
-    // Generate CRD YAML
-    let crd = SecretManagerConfig::crd();
-
-    // Serialize to YAML
-    match serde_yaml::to_string(&crd) {
-        Ok(yaml) => {
-            // Print header comments warning that this file should not be edited manually
-            println!("# This file is auto-generated by crdgen");
-            println!("# DO NOT EDIT THIS FILE MANUALLY");
-            println!(
-                "# If there are malformed YAML issues, fix them in the Rust code (src/crd/mod.rs)"
-            );
-            println!("# This file will be overwritten on every code update");
-            println!("#");
-            println!("---");
-            print!("{yaml}");
-        }
+    match crd_yaml() {
+        Ok(yaml) => print!("{yaml}"),
         Err(e) => {
-            eprintln!("Failed to serialize CRD to YAML: {e}");
+            eprintln!("{e:#}");
             std::process::exit(1);
         }
     }
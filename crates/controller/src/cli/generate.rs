@@ -0,0 +1,120 @@
+//! # Generate Command
+//!
+//! Commands that print manifests derived from the controller's code.
+//!
+//! `generate policies` prints a ValidatingAdmissionPolicy and binding with CEL rules for
+//! SecretManagerConfig, for clusters that should reject invalid resources at admission.
+//!
+//! `generate argo-health` prints the Argo CD health customization for SecretManagerConfig, so
+//! Application trees show whether secrets synced.
+
+use anyhow::Result;
+use clap::{Subcommand, ValueEnum};
+use controller::constants::{MIN_GITREPOSITORY_PULL_INTERVAL_SECS, MIN_RECONCILE_INTERVAL_SECS};
+use controller::crd::admission_policy::{
+    AdmissionPolicyOptions, validating_admission_policy, validating_admission_policy_binding,
+};
+use controller::crd::health_check::{argo_health_config_map, argo_health_lua};
+
+/// Manifests `msmctl generate` can print
+#[derive(Subcommand)]
+pub enum GenerateTarget {
+    /// ValidatingAdmissionPolicy and binding with CEL rules for SecretManagerConfig (Kubernetes 1.30+)
+    Policies {
+        /// Minimum reconcileInterval in seconds (match the controller's MIN_RECONCILE_INTERVAL_SECS)
+        #[arg(long, default_value_t = MIN_RECONCILE_INTERVAL_SECS)]
+        min_reconcile_interval_secs: u64,
+
+        /// Minimum gitRepositoryPullInterval in seconds (match MIN_GITREPOSITORY_PULL_INTERVAL_SECS)
+        #[arg(long, default_value_t = MIN_GITREPOSITORY_PULL_INTERVAL_SECS)]
+        min_git_repository_pull_interval_secs: u64,
+
+        /// What the binding does with violations (repeatable; default: deny)
+        #[arg(long = "validation-action", value_enum)]
+        validation_actions: Vec<ValidationAction>,
+    },
+    /// Argo CD health check (Lua) for SecretManagerConfig, as an argocd-cm patch
+    /// Apply with `kubectl patch configmap argocd-cm -n argocd --type merge --patch-file`
+    ArgoHealth {
+        /// Namespace Argo CD is installed in
+        #[arg(long, default_value = "argocd")]
+        argocd_namespace: String,
+
+        /// Print the Lua script only, for Argo CD installs managed by Helm or an operator
+        #[arg(long)]
+        lua: bool,
+    },
+}
+
+/// ValidatingAdmissionPolicyBinding validation actions
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ValidationAction {
+    /// Reject invalid resources
+    Deny,
+    /// Return a warning to the client
+    Warn,
+    /// Record violations in the API server audit log
+    Audit,
+}
+
+impl ValidationAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            ValidationAction::Deny => "Deny",
+            ValidationAction::Warn => "Warn",
+            ValidationAction::Audit => "Audit",
+        }
+    }
+}
+
+/// Print the requested manifests to stdout
+pub fn generate_command(target: GenerateTarget) -> Result<()> {
+    match target {
+        GenerateTarget::Policies {
+            min_reconcile_interval_secs,
+            min_git_repository_pull_interval_secs,
+            validation_actions,
+        } => {
+            let options = AdmissionPolicyOptions {
+                min_reconcile_interval_secs,
+                min_git_repository_pull_interval_secs,
+            };
+            let actions = if validation_actions.is_empty() {
+                vec!["Deny".to_string()]
+            } else {
+                validation_actions
+                    .iter()
+                    .map(|action| action.as_str().to_string())
+                    .collect()
+            };
+
+            println!("# Generated by msmctl generate policies");
+            println!("---");
+            print!(
+                "{}",
+                serde_yaml::to_string(&validating_admission_policy(&options))?
+            );
+            println!("---");
+            print!(
+                "{}",
+                serde_yaml::to_string(&validating_admission_policy_binding(actions))?
+            );
+            Ok(())
+        }
+        GenerateTarget::ArgoHealth {
+            argocd_namespace,
+            lua,
+        } => {
+            if lua {
+                print!("{}", argo_health_lua());
+                return Ok(());
+            }
+            println!("# Generated by msmctl generate argo-health");
+            print!(
+                "{}",
+                serde_yaml::to_string(&argo_health_config_map(&argocd_namespace))?
+            );
+            Ok(())
+        }
+    }
+}
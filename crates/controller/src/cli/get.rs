@@ -0,0 +1,232 @@
+//! # Get Command
+//!
+//! Command to show SecretManagerConfig resources as a table, JSON or YAML.
+//!
+//! The table shows the status fields needed during incidents (phase, Ready condition,
+//! reconcile times, drift) that `kubectl get` print columns leave out.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use controller::crd::SecretManagerConfig;
+use kube::{Client, api::Api};
+
+/// Output format for `msmctl get`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Full resources as JSON
+    Json,
+    /// Full resources as YAML
+    Yaml,
+    /// Table with provider, source and revision columns
+    Wide,
+}
+
+/// Show one or all SecretManagerConfig resources
+pub async fn get_command(
+    client: Client,
+    name: Option<String>,
+    namespace: Option<String>,
+    all_namespaces: bool,
+    output: Option<OutputFormat>,
+) -> Result<()> {
+    let api: Api<SecretManagerConfig> = if all_namespaces {
+        Api::all(client)
+    } else {
+        Api::namespaced(client, namespace.as_deref().unwrap_or("default"))
+    };
+
+    let mut configs = if let Some(name) = &name {
+        vec![api.get(name).await.with_context(|| {
+            format!(
+                "Failed to get SecretManagerConfig '{}/{name}'",
+                namespace.as_deref().unwrap_or("default")
+            )
+        })?]
+    } else {
+        api.list(&kube::api::ListParams::default())
+            .await
+            .context("Failed to list SecretManagerConfig resources")?
+            .items
+    };
+
+    // managedFields are noise when reading status, kubectl hides them too
+    for config in &mut configs {
+        config.metadata.managed_fields = None;
+    }
+
+    match output {
+        Some(OutputFormat::Json) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&as_document(configs, name.is_some()))?
+            );
+        }
+        Some(OutputFormat::Yaml) => {
+            print!(
+                "{}",
+                serde_yaml::to_string(&as_document(configs, name.is_some()))?
+            );
+        }
+        Some(OutputFormat::Wide) => print_table(&configs, all_namespaces, true),
+        None => print_table(&configs, all_namespaces, false),
+    }
+
+    Ok(())
+}
+
+/// A single resource when one was requested by name, otherwise a `List` like kubectl prints
+fn as_document(mut configs: Vec<SecretManagerConfig>, single: bool) -> serde_json::Value {
+    if single && configs.len() == 1 {
+        return serde_json::to_value(configs.remove(0)).unwrap_or_default();
+    }
+    serde_json::json!({
+        "apiVersion": "v1",
+        "kind": "List",
+        "items": configs,
+    })
+}
+
+fn print_table(configs: &[SecretManagerConfig], all_namespaces: bool, wide: bool) {
+    if configs.is_empty() {
+        println!("No SecretManagerConfig resources found.");
+        return;
+    }
+
+    let now = Utc::now();
+    let mut headers = vec![];
+    if all_namespaces {
+        headers.push("NAMESPACE");
+    }
+    headers.extend([
+        "NAME",
+        "PHASE",
+        "READY",
+        "SECRETS",
+        "LAST RECONCILE",
+        "NEXT RECONCILE",
+        "DRIFT",
+    ]);
+    if wide {
+        headers.extend(["PROVIDER", "SOURCE", "REVISION", "SUSPENDED", "REASON"]);
+    }
+
+    let rows: Vec<Vec<String>> = configs
+        .iter()
+        .map(|config| {
+            let status = config.status.as_ref();
+            let ready = status.and_then(|s| s.conditions.iter().find(|c| c.r#type == "Ready"));
+
+            let mut row = vec![];
+            if all_namespaces {
+                row.push(config.metadata.namespace.clone().unwrap_or_default());
+            }
+            row.extend([
+                config.metadata.name.clone().unwrap_or_default(),
+                status
+                    .and_then(|s| s.phase.clone())
+                    .unwrap_or_else(|| "-".to_string()),
+                ready.map_or_else(|| "Unknown".to_string(), |c| c.status.clone()),
+                status
+                    .and_then(|s| s.secrets_synced)
+                    .map_or_else(|| "-".to_string(), |n| n.to_string()),
+                relative_time(status.and_then(|s| s.last_reconcile_time.as_deref()), now),
+                relative_time(status.and_then(|s| s.next_reconcile_time.as_deref()), now),
+                drift_state(config),
+            ]);
+            if wide {
+                let source = &config.spec.source_ref;
+                row.extend([
+                    match &config.spec.provider {
+                        controller::crd::ProviderConfig::Gcp(_) => "gcp",
+                        controller::crd::ProviderConfig::Aws(_) => "aws",
+                        controller::crd::ProviderConfig::Azure(_) => "azure",
+                    }
+                    .to_string(),
+                    format!("{}/{}/{}", source.kind, source.namespace, source.name),
+                    status
+                        .and_then(|s| s.last_synced_revision.clone())
+                        .unwrap_or_else(|| "-".to_string()),
+                    if config.spec.suspend { "Yes" } else { "No" }.to_string(),
+                    ready
+                        .and_then(|c| c.reason.clone())
+                        .unwrap_or_else(|| "-".to_string()),
+                ]);
+            }
+            row
+        })
+        .collect();
+
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(header.len())
+        })
+        .collect();
+
+    let format_row = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("   ")
+            .trim_end()
+            .to_string()
+    };
+    println!("{}", format_row(headers.clone()));
+    for row in &rows {
+        println!("{}", format_row(row.iter().map(String::as_str).collect()));
+    }
+}
+
+/// Whether the provider is known to match the source, derived from status
+fn drift_state(config: &SecretManagerConfig) -> String {
+    let Some(status) = config.status.as_ref() else {
+        return "Unknown".to_string();
+    };
+    if let Some(pending) = &status.pending_changes {
+        return format!("Pending ({})", pending.operations.len());
+    }
+    if status.observed_generation != config.metadata.generation {
+        return "SpecChanged".to_string();
+    }
+    if let Some(failed) = status.failed_services.as_ref().filter(|f| !f.is_empty()) {
+        return format!("ServiceFailed ({})", failed.len());
+    }
+    if let Some(failed) = status.failed_secrets.as_ref().filter(|f| !f.is_empty()) {
+        return format!("Failed ({})", failed.len());
+    }
+    match status.phase.as_deref() {
+        Some("Ready") => "InSync".to_string(),
+        _ => "Unknown".to_string(),
+    }
+}
+
+/// Render an RFC3339 timestamp relative to now ("5m ago", "in 30s")
+fn relative_time(timestamp: Option<&str>, now: DateTime<Utc>) -> String {
+    let Some(time) = timestamp.and_then(|t| DateTime::parse_from_rfc3339(t).ok()) else {
+        return "-".to_string();
+    };
+    let seconds = (time.with_timezone(&Utc) - now).num_seconds();
+    let magnitude = seconds.unsigned_abs();
+    let amount = if magnitude < 60 {
+        format!("{magnitude}s")
+    } else if magnitude < 3600 {
+        format!("{}m", magnitude / 60)
+    } else if magnitude < 86400 {
+        format!("{}h", magnitude / 3600)
+    } else {
+        format!("{}d", magnitude / 86400)
+    };
+    if seconds < 0 {
+        format!("{amount} ago")
+    } else {
+        format!("in {amount}")
+    }
+}
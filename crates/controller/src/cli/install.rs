@@ -1,96 +1,21 @@
 //! Installation command for Secret Manager Controller
 //!
-//! Embeds Kubernetes manifests at compile time and applies them to the cluster.
+//! Applies the controller's install manifests to the cluster (or prints them with `--export`).
 //! Similar to `flux install`, this command installs the controller CRDs, RBAC, and deployment.
+//! The CRD is generated from the compiled schema; RBAC and deployment manifests are embedded
+//! from `config/` at build time (see `controller::crd::manifests`).
 //!
 //! Uses kubectl apply via subprocess for reliable manifest application, similar to FluxCD's approach.
 
 use anyhow::{Context, Result};
-use controller::crd::SecretManagerConfig;
-use kube::{Client, core::CustomResourceExt};
+use controller::crd::manifests::{DEFAULT_INSTALL_NAMESPACE, install_manifests};
 use std::io::Write;
 use std::process::Command;
 use tempfile::NamedTempFile;
 
-/// Embedded manifests - embedded at compile time from config/ directory
-///
-/// These manifests are read from disk at COMPILE TIME using include_str! macro.
-/// This means:
-/// - The manifests are baked into the binary when it's built
-/// - Changes to config/*.yaml files after building won't affect the binary
-/// - This is the same approach FluxCD uses (Go's embed directive)
-///
-/// Note: CRD is NOT included here - it's generated dynamically from Rust code
-/// at runtime using SecretManagerConfig::crd() to avoid stale CRD files.
-///
-/// Static manifests (RBAC, Deployment, Service) are fine to embed because:
-/// - They don't change based on code changes
-/// - They're versioned with the binary
-/// - They're idempotent (kubectl apply handles updates)
-mod manifests {
-    // Include namespace
-    const NAMESPACE: &str = include_str!("../../../../config/namespace.yaml");
-
-    // Include RBAC resources
-    const SERVICE_ACCOUNT: &str = include_str!("../../../../config/rbac/serviceaccount.yaml");
-    const ROLE: &str = include_str!("../../../../config/rbac/role.yaml");
-    const ROLEBINDING: &str = include_str!("../../../../config/rbac/rolebinding.yaml");
-    const CLUSTER_ROLE: &str = include_str!("../../../../config/rbac/clusterrole.yaml");
-    const CLUSTER_ROLE_BINDING: &str =
-        include_str!("../../../../config/rbac/clusterrolebinding.yaml");
-    // Note: role-sops-secret.yaml and rolebinding-sops-secret.yaml were removed as redundant
-    // They duplicated permissions already granted by role.yaml and rolebinding.yaml
-
-    // Include deployment
-    const DEPLOYMENT: &str = include_str!("../../../../config/deployment/deployment.yaml");
-
-    // Include service
-    const METRICS_SERVICE: &str = include_str!("../../../../config/service/metrics-service.yaml");
-
-    /// Get all embedded manifests (excluding CRD, which is generated dynamically)
-    pub fn get_static_manifests() -> Vec<&'static str> {
-        vec![
-            NAMESPACE,
-            SERVICE_ACCOUNT,
-            ROLE,
-            ROLEBINDING,
-            CLUSTER_ROLE,
-            CLUSTER_ROLE_BINDING,
-            // Note: ROLE_SOPS_SECRET and ROLEBINDING_SOPS_SECRET removed (redundant)
-            DEPLOYMENT,
-            METRICS_SERVICE,
-        ]
-    }
-}
-
-/// Generate CRD YAML dynamically from Rust code
-/// This ensures we always use the latest CRD definition, not a stale file from disk
-fn generate_crd_yaml() -> Result<String> {
-    // Generate CRD from Rust type definition
-    let crd = SecretManagerConfig::crd();
-
-    // Serialize to YAML
-    let yaml = serde_yaml::to_string(&crd).context("Failed to serialize CRD to YAML")?;
-
-    // Add header comment
-    let mut crd_yaml = String::from("# This CRD is auto-generated from Rust code at runtime\n");
-    crd_yaml.push_str("# DO NOT EDIT THIS FILE MANUALLY\n");
-    crd_yaml.push_str("# Generated by: msmctl install\n");
-    crd_yaml.push_str("#\n");
-    crd_yaml.push_str("---\n");
-    crd_yaml.push_str(&yaml);
-
-    Ok(crd_yaml)
-}
-
 /// Install the Secret Manager Controller to the cluster
-pub async fn install_command(
-    _client: Client, // Keep for API compatibility, but we use kubectl directly
-    namespace: Option<String>,
-    export: bool,
-    dry_run: bool,
-) -> Result<()> {
-    let ns = namespace.as_deref().unwrap_or("octopilot-system");
+pub fn install_command(namespace: Option<String>, export: bool, dry_run: bool) -> Result<()> {
+    let ns = namespace.as_deref().unwrap_or(DEFAULT_INSTALL_NAMESPACE);
 
     if export {
         // Export mode: just print manifests to stdout
@@ -109,26 +34,9 @@ pub async fn install_command(
     println!("   Namespace: {ns}");
     println!();
 
-    // Generate CRD dynamically from Rust code
-    println!("   [1/11] Generating CRD from Rust code...");
-    let crd_yaml = generate_crd_yaml().context("Failed to generate CRD from Rust code")?;
-
-    // Parse CRD to get name for logging
-    let crd_doc: serde_yaml::Value =
-        serde_yaml::from_str(&crd_yaml).context("Failed to parse generated CRD YAML")?;
-    let crd_name = crd_doc
-        .get("metadata")
-        .and_then(|m| m.get("name"))
-        .and_then(|n| n.as_str())
-        .unwrap_or("SecretManagerConfig CRD");
-
-    println!("   [1/11] Applying CustomResourceDefinition: {}", crd_name);
-    apply_manifest(&crd_yaml, ns).with_context(|| format!("Failed to apply CRD: {}", crd_name))?;
-
-    // Apply static manifests
-    let static_manifests = manifests::get_static_manifests();
-
-    for (idx, manifest_yaml) in static_manifests.iter().enumerate() {
+    let manifests = install_manifests(ns).context("Failed to render install manifests")?;
+    let total = manifests.len();
+    for (idx, manifest_yaml) in manifests.iter().enumerate() {
         // Parse YAML to get kind and name for logging
         let doc: serde_yaml::Value =
             serde_yaml::from_str(manifest_yaml).context("Failed to parse manifest YAML")?;
@@ -143,11 +51,10 @@ pub async fn install_command(
             .and_then(|n| n.as_str())
             .unwrap_or("<unknown>");
 
-        let manifest_num = idx + 2; // Start from 2 since CRD is 1
-        println!("   [{}/11] Applying {}: {}", manifest_num, kind, name);
+        println!("   [{}/{total}] Applying {}: {}", idx + 1, kind, name);
 
         // Apply using kubectl apply
-        apply_manifest(manifest_yaml, ns)
+        apply_manifest(manifest_yaml)
             .with_context(|| format!("Failed to apply {}: {}", kind, name))?;
     }
 
@@ -166,14 +73,11 @@ pub async fn install_command(
 
 /// Apply a single manifest to the cluster using kubectl apply
 /// This is simpler and more reliable than using kube-rs for all resource types
-fn apply_manifest(manifest_yaml: &str, default_namespace: &str) -> Result<()> {
+fn apply_manifest(manifest_yaml: &str) -> Result<()> {
     // Write manifest to temporary file
     let mut temp_file = NamedTempFile::new().context("Failed to create temporary file")?;
-
-    // Replace namespace placeholder if needed
-    let manifest_with_ns = manifest_yaml.replace("octopilot-system", default_namespace);
     temp_file
-        .write_all(manifest_with_ns.as_bytes())
+        .write_all(manifest_yaml.as_bytes())
         .context("Failed to write manifest to temporary file")?;
 
     let temp_path = temp_file.path();
@@ -201,20 +105,9 @@ fn apply_manifest(manifest_yaml: &str, default_namespace: &str) -> Result<()> {
 /// Get combined manifests as a single YAML string (for export/dry-run)
 fn get_combined_manifests(namespace: &str) -> Result<String> {
     let mut combined = String::new();
-
-    // Generate CRD dynamically
-    let crd_yaml = generate_crd_yaml()?;
-    combined.push_str(&crd_yaml);
-    combined.push_str("\n---\n");
-
-    // Add static manifests
-    let static_manifests = manifests::get_static_manifests();
-    for manifest in static_manifests {
-        // Replace namespace placeholder if present
-        let manifest_with_ns = manifest.replace("octopilot-system", namespace);
-        combined.push_str(&manifest_with_ns);
+    for manifest in install_manifests(namespace)? {
+        combined.push_str(&manifest);
         combined.push_str("\n---\n");
     }
-
     Ok(combined)
 }
@@ -0,0 +1,129 @@
+//! # Logs Command
+//!
+//! Command to show the controller's reconcile logs for a single SecretManagerConfig.
+//!
+//! Reads the logs of every controller replica through the Kubernetes API and keeps the
+//! lines logged inside the resource's reconcile spans, which carry `resource.name` and
+//! `resource.namespace` fields (text and JSON log formats are both recognised).
+
+use anyhow::{Context, Result};
+use controller::controller::reconciler::validation::parse_kubernetes_duration;
+use futures::{AsyncBufReadExt, StreamExt, TryStreamExt};
+use k8s_openapi::api::core::v1::Pod;
+use kube::{
+    Client,
+    api::{Api, ListParams, LogParams},
+};
+
+/// Label selecting controller pods
+const CONTROLLER_POD_SELECTOR: &str = "app=secret-manager-controller";
+
+/// Show the reconcile logs of one SecretManagerConfig resource
+pub async fn logs_command(
+    client: Client,
+    name: String,
+    namespace: Option<String>,
+    controller_namespace: Option<String>,
+    follow: bool,
+    since: Option<String>,
+) -> Result<()> {
+    let ns = namespace.as_deref().unwrap_or("default");
+    let controller_ns = controller_namespace
+        .as_deref()
+        .unwrap_or("octopilot-system");
+
+    let since_seconds = since
+        .as_deref()
+        .map(|since| {
+            parse_kubernetes_duration(since)
+                .map(|d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX))
+                .with_context(|| format!("Invalid --since duration '{since}'"))
+        })
+        .transpose()?;
+
+    let pods: Api<Pod> = Api::namespaced(client, controller_ns);
+    let pod_names: Vec<String> = pods
+        .list(&ListParams::default().labels(CONTROLLER_POD_SELECTOR))
+        .await
+        .with_context(|| format!("Failed to list controller pods in '{controller_ns}'"))?
+        .items
+        .into_iter()
+        .filter_map(|pod| pod.metadata.name)
+        .collect();
+
+    if pod_names.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No controller pods found in namespace '{controller_ns}' (selector {CONTROLLER_POD_SELECTOR}).\n\
+             Use --controller-namespace if the controller is installed elsewhere."
+        ));
+    }
+
+    let params = LogParams {
+        follow,
+        since_seconds,
+        ..LogParams::default()
+    };
+    // Prefix lines with the pod when several replicas are logging
+    let prefix_pod = pod_names.len() > 1;
+
+    let mut streams = Vec::with_capacity(pod_names.len());
+    for pod_name in pod_names {
+        let lines = pods
+            .log_stream(&pod_name, &params)
+            .await
+            .with_context(|| format!("Failed to read logs of controller pod '{pod_name}'"))?
+            .lines()
+            .map_ok(move |line| (pod_name.clone(), line));
+        streams.push(lines.boxed());
+    }
+
+    let mut lines = futures::stream::select_all(streams);
+    let mut matched = 0usize;
+    while let Some((pod_name, line)) = lines
+        .try_next()
+        .await
+        .context("Failed to read controller logs")?
+    {
+        if !mentions_resource(&line, ns, &name) {
+            continue;
+        }
+        matched += 1;
+        if prefix_pod {
+            println!("[{pod_name}] {line}");
+        } else {
+            println!("{line}");
+        }
+    }
+
+    if matched == 0 && !follow {
+        eprintln!(
+            "No log lines found for SecretManagerConfig '{ns}/{name}' in controller pods in '{controller_ns}'"
+        );
+    }
+
+    Ok(())
+}
+
+/// Whether a log line was logged for the given resource
+fn mentions_resource(line: &str, namespace: &str, name: &str) -> bool {
+    has_field(line, "resource.name", name) && has_field(line, "resource.namespace", namespace)
+}
+
+/// Whether `line` contains `key=value`, `key="value"` (text format) or `"key":"value"` (JSON)
+fn has_field(line: &str, key: &str, value: &str) -> bool {
+    if line.contains(&format!("\"{key}\":\"{value}\"")) {
+        return true;
+    }
+    let needle = format!("{key}=");
+    line.match_indices(&needle).any(|(index, _)| {
+        let rest = &line[index + needle.len()..];
+        let token = match rest.strip_prefix('"') {
+            Some(quoted) => quoted.split('"').next().unwrap_or_default(),
+            None => rest
+                .split(|c: char| c.is_whitespace() || c == '}' || c == ',')
+                .next()
+                .unwrap_or_default(),
+        };
+        token == value
+    })
+}
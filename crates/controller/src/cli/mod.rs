@@ -14,6 +14,10 @@
 //! # List all SecretManagerConfig resources
 //! msmctl list secretmanagerconfig
 //!
+//! # Show resources with phase, reconcile times and drift state
+//! msmctl get smc -A
+//! msmctl get smc my-secrets -o yaml
+//!
 //! # Show status of a SecretManagerConfig
 //! msmctl status secretmanagerconfig my-secrets
 //!
@@ -29,6 +33,25 @@
 //! # Resume Git pulls
 //! msmctl resume-git-pulls secretmanagerconfig my-secrets
 //!
+//! # Show reconcile logs of one resource (across controller replicas)
+//! msmctl logs my-secrets --namespace default --follow
+//!
+//! # Review changes awaiting approval (approvalPolicy: Manual)
+//! msmctl plan secretmanagerconfig my-secrets
+//!
+//! # Approve the reviewed change set
+//! msmctl apply secretmanagerconfig my-secrets --hash 3f2a9c1
+//!
+//! # Validate manifests offline (e.g. in CI)
+//! msmctl validate -f config.yaml
+//!
+//! # Print a ValidatingAdmissionPolicy for SecretManagerConfig
+//! msmctl generate policies | kubectl apply -f -
+//!
+//! # Add the SecretManagerConfig health check to Argo CD
+//! msmctl generate argo-health > patch.yaml
+//! kubectl patch configmap argocd-cm -n argocd --type merge --patch-file patch.yaml
+//!
 //! # Install the controller (similar to flux install)
 //! msmctl install
 //!
@@ -52,12 +75,16 @@ use kube::Client;
 // Import from the library
 
 mod check;
+mod generate;
+mod get;
 mod git_pulls;
 mod install;
-mod list;
+mod logs;
+mod plan;
 mod reconcile;
 mod status;
 mod suspend;
+mod validate;
 
 /// Microscaler Secret Manager Controller CLI
 #[derive(Parser)]
@@ -71,8 +98,11 @@ Available resource types:
 
 Examples:
   msmctl list secretmanagerconfig
+  msmctl get smc -A -o wide
   msmctl reconcile smc my-secrets
   msmctl status secretmanagerconfig my-secrets --namespace default
+  msmctl plan smc my-secrets
+  msmctl validate -f config.yaml
 "
 )]
 struct Cli {
@@ -122,6 +152,29 @@ enum Commands {
         )]
         resource_type: Option<ResourceType>,
     },
+    /// Show SecretManagerConfig resources with phase, reconcile times and drift state
+    Get {
+        /// Resource type
+        /// Available types: secretmanagerconfig (or 'smc' for short)
+        #[arg(
+            value_enum,
+            value_name = "RESOURCE_TYPE",
+            help = "Resource type\nAvailable types:\n  secretmanagerconfig (or 'smc') - SecretManagerConfig resource"
+        )]
+        resource_type: ResourceType,
+
+        /// Name of the SecretManagerConfig resource (all resources if omitted)
+        #[arg(value_name = "NAME")]
+        name: Option<String>,
+
+        /// List resources across all namespaces
+        #[arg(short = 'A', long, conflicts_with = "name")]
+        all_namespaces: bool,
+
+        /// Output format: json, yaml or wide (default: table)
+        #[arg(short, long, value_enum)]
+        output: Option<get::OutputFormat>,
+    },
     /// Show status of a SecretManagerConfig resource
     Status {
         /// Resource type
@@ -200,6 +253,73 @@ enum Commands {
         #[arg(value_name = "NAME")]
         name: String,
     },
+    /// Show the controller's reconcile logs for a single SecretManagerConfig resource
+    /// Reads the logs of all controller replicas and keeps the lines for this resource
+    Logs {
+        /// Name of the SecretManagerConfig resource
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Keep streaming new log lines
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Only show logs newer than this duration (e.g. 10m, 1h)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Namespace the controller runs in (default: octopilot-system)
+        #[arg(long)]
+        controller_namespace: Option<String>,
+    },
+    /// Show changes awaiting approval for a SecretManagerConfig resource
+    /// Prints the change set staged in status when approvalPolicy is Manual
+    Plan {
+        /// Resource type
+        /// Available types: secretmanagerconfig (or 'smc' for short)
+        #[arg(
+            value_enum,
+            value_name = "RESOURCE_TYPE",
+            help = "Resource type\nAvailable types:\n  secretmanagerconfig (or 'smc') - SecretManagerConfig resource"
+        )]
+        resource_type: ResourceType,
+
+        /// Name of the SecretManagerConfig resource
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+    /// Approve the staged changes of a SecretManagerConfig resource
+    /// The hash must match the change set shown by `msmctl plan`
+    Apply {
+        /// Resource type
+        /// Available types: secretmanagerconfig (or 'smc' for short)
+        #[arg(
+            value_enum,
+            value_name = "RESOURCE_TYPE",
+            help = "Resource type\nAvailable types:\n  secretmanagerconfig (or 'smc') - SecretManagerConfig resource"
+        )]
+        resource_type: ResourceType,
+
+        /// Name of the SecretManagerConfig resource
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Hash of the reviewed change set (full hash or a prefix of at least 7 characters)
+        #[arg(long)]
+        hash: String,
+    },
+    /// Validate SecretManagerConfig manifests without contacting the cluster
+    /// Runs the controller's validation and exits non-zero if any resource is invalid
+    Validate {
+        /// Manifest files to validate (`-` reads stdin); other kinds are skipped
+        #[arg(short = 'f', long = "filename", value_name = "FILE", required = true)]
+        files: Vec<std::path::PathBuf>,
+    },
+    /// Print manifests generated from the controller's validation rules and status conditions
+    Generate {
+        #[command(subcommand)]
+        target: generate::GenerateTarget,
+    },
     /// Install the Secret Manager Controller to the cluster
     /// Similar to `flux install`, this command installs CRDs, RBAC, and deployment manifests
     Install {
@@ -257,12 +377,25 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    // Offline commands don't need a cluster
+    let command = match cli.command {
+        Commands::Validate { files } => return validate::validate_command(files),
+        Commands::Generate { target } => return generate::generate_command(target),
+        // Manifests are rendered locally and applied with kubectl
+        Commands::Install {
+            namespace,
+            export,
+            dry_run,
+        } => return install::install_command(namespace, export, dry_run),
+        command => command,
+    };
+
     // Create Kubernetes client
     let client = Client::try_default()
         .await
         .context("Failed to create Kubernetes client. Ensure kubeconfig is configured.")?;
 
-    match cli.command {
+    match command {
         Commands::Reconcile {
             resource_type,
             name,
@@ -282,7 +415,18 @@ async fn main() -> Result<()> {
                 )
             })?;
             validate_resource_type(&rt)?;
-            list::list_command(client, cli.namespace).await
+            // Without a namespace, list across all namespaces
+            let all_namespaces = cli.namespace.is_none();
+            get::get_command(client, None, cli.namespace, all_namespaces, None).await
+        }
+        Commands::Get {
+            resource_type,
+            name,
+            all_namespaces,
+            output,
+        } => {
+            validate_resource_type(&resource_type)?;
+            get::get_command(client, name, cli.namespace, all_namespaces, output).await
         }
         Commands::Status {
             resource_type,
@@ -319,11 +463,40 @@ async fn main() -> Result<()> {
             validate_resource_type(&resource_type)?;
             git_pulls::resume_git_pulls_command(client, name, cli.namespace).await
         }
-        Commands::Install {
-            namespace,
-            export,
-            dry_run,
-        } => install::install_command(client, namespace, export, dry_run).await,
+        Commands::Logs {
+            name,
+            follow,
+            since,
+            controller_namespace,
+        } => {
+            logs::logs_command(
+                client,
+                name,
+                cli.namespace,
+                controller_namespace,
+                follow,
+                since,
+            )
+            .await
+        }
+        Commands::Plan {
+            resource_type,
+            name,
+        } => {
+            validate_resource_type(&resource_type)?;
+            plan::plan_command(client, name, cli.namespace).await
+        }
+        Commands::Apply {
+            resource_type,
+            name,
+            hash,
+        } => {
+            validate_resource_type(&resource_type)?;
+            plan::apply_command(client, name, cli.namespace, hash).await
+        }
+        Commands::Validate { .. } | Commands::Generate { .. } | Commands::Install { .. } => {
+            unreachable!("offline commands are handled before creating the client")
+        }
         Commands::Check { namespace, pre } => check::check_command(client, namespace, pre).await,
     }
 }
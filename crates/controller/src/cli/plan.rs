@@ -0,0 +1,150 @@
+//! # Plan/Apply Commands
+//!
+//! Review and approve changes staged by `approvalPolicy: Manual`.
+//!
+//! `plan` prints the change set the controller staged in `status.pendingChanges`;
+//! `apply` approves it by setting the approve annotation to its hash.
+
+use anyhow::{Context, Result};
+use controller::controller::reconciler::approval::APPROVE_ANNOTATION;
+use controller::crd::{ApprovalPolicy, SecretManagerConfig};
+use kube::{Client, api::Api, api::Patch, api::PatchParams};
+use serde_json::json;
+
+/// Shortest hash prefix accepted by `apply --hash`
+const MIN_HASH_PREFIX_LEN: usize = 7;
+
+/// Print the change set awaiting approval for a SecretManagerConfig resource
+pub async fn plan_command(client: Client, name: String, namespace: Option<String>) -> Result<()> {
+    let ns = namespace.as_deref().unwrap_or("default");
+
+    let api: Api<SecretManagerConfig> = Api::namespaced(client, ns);
+
+    let config = api
+        .get(&name)
+        .await
+        .with_context(|| format!("Failed to get SecretManagerConfig '{ns}/{name}'"))?;
+
+    println!("📋 Plan for SecretManagerConfig '{ns}/{name}'");
+    println!();
+
+    let Some(pending) = config
+        .status
+        .as_ref()
+        .and_then(|s| s.pending_changes.as_ref())
+    else {
+        println!("No changes awaiting approval.");
+        if config.spec.approval_policy != Some(ApprovalPolicy::Manual) {
+            println!(
+                "   ℹ️  approvalPolicy is Automatic: changes are applied as soon as they are detected"
+            );
+        }
+        return Ok(());
+    };
+
+    if let Some(revision) = &pending.revision {
+        println!("Revision: {revision}");
+    }
+    if let Some(planned_at) = &pending.planned_at {
+        println!("Planned At: {planned_at}");
+    }
+    println!();
+
+    for operation in &pending.operations {
+        let symbol = match operation.action.as_str() {
+            "Create" => "+",
+            "Update" => "~",
+            "Delete" => "-",
+            "Disable" => "!",
+            "Enable" => "^",
+            _ => "?",
+        };
+        println!("  {symbol} {:<8} {}", operation.action, operation.key);
+    }
+    println!();
+    println!("Plan: {}", pending.summary);
+    println!("Hash: {}", pending.hash);
+    println!();
+
+    let approved = config
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(APPROVE_ANNOTATION))
+        .is_some_and(|approved| approved == &pending.hash);
+    if approved {
+        println!("✅ Approved - the controller will apply these changes shortly.");
+    } else {
+        println!("To apply these changes, run:");
+        println!(
+            "   msmctl apply secretmanagerconfig {name} --hash {} --namespace {ns}",
+            pending.hash
+        );
+    }
+
+    Ok(())
+}
+
+/// Approve the staged change set of a SecretManagerConfig resource
+pub async fn apply_command(
+    client: Client,
+    name: String,
+    namespace: Option<String>,
+    hash: String,
+) -> Result<()> {
+    let ns = namespace.as_deref().unwrap_or("default");
+
+    println!("🚀 Approving changes for SecretManagerConfig '{ns}/{name}'...");
+
+    let api: Api<SecretManagerConfig> = Api::namespaced(client, ns);
+
+    let config = api
+        .get(&name)
+        .await
+        .with_context(|| format!("Failed to get SecretManagerConfig '{ns}/{name}'"))?;
+
+    let pending = config
+        .status
+        .as_ref()
+        .and_then(|s| s.pending_changes.as_ref())
+        .ok_or_else(|| {
+            anyhow::anyhow!("SecretManagerConfig '{ns}/{name}' has no changes awaiting approval")
+        })?;
+
+    // Accept an unambiguous prefix, like git does for commit hashes
+    let hash = hash.trim().to_lowercase();
+    if hash.len() < MIN_HASH_PREFIX_LEN || !pending.hash.starts_with(&hash) {
+        return Err(anyhow::anyhow!(
+            "Hash '{hash}' does not match the staged change set {} ({}).\n\
+             The plan may have changed since you reviewed it; run:\n   \
+             msmctl plan secretmanagerconfig {name} --namespace {ns}",
+            pending.hash,
+            pending.summary
+        ));
+    }
+
+    // Always annotate with the full hash - the controller compares it exactly
+    let patch = json!({
+        "metadata": {
+            "annotations": {
+                APPROVE_ANNOTATION: pending.hash
+            }
+        }
+    });
+
+    // Use Patch::Merge for annotations - simpler and more reliable
+    api.patch(&name, &PatchParams::default(), &Patch::Merge(patch))
+        .await
+        .with_context(|| {
+            format!("Failed to approve changes for SecretManagerConfig '{ns}/{name}'")
+        })?;
+
+    println!("✅ Changes approved");
+    println!("   Resource: {ns}/{name}");
+    println!("   Plan: {}", pending.summary);
+    println!("   Annotation: {APPROVE_ANNOTATION}={}", pending.hash);
+    println!("\nThe controller will apply the changes shortly.");
+    println!("If the source changed in the meantime, a new plan is staged instead.");
+
+    Ok(())
+}
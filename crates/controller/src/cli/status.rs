@@ -4,6 +4,7 @@
 
 use anyhow::{Context, Result};
 use controller::crd::SecretManagerConfig;
+use controller::crd::health_check::kstatus;
 use kube::{Client, api::Api};
 
 /// Show detailed status of a SecretManagerConfig resource
@@ -93,6 +94,7 @@ pub async fn status_command(client: Client, name: String, namespace: Option<Stri
         if let Some(observed_generation) = status.observed_generation {
             println!("  Observed Generation: {}", observed_generation);
         }
+        println!("  Health (kstatus): {}", kstatus(&config));
         if let Some(last_reconcile_time) = &status.last_reconcile_time {
             println!("  Last Reconcile Time: {}", last_reconcile_time);
         }
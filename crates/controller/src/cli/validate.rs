@@ -0,0 +1,91 @@
+//! # Validate Command
+//!
+//! Command to validate SecretManagerConfig manifests offline, e.g. in CI pipelines.
+//!
+//! Runs the same checks the controller runs before reconciling a resource (sourceRef,
+//! environments, prefix/suffix and name template rules, paths, provider and configs settings,
+//! reconcile and GitRepository pull intervals) without contacting the cluster.
+//! Documents of other kinds in the same files are skipped.
+
+use anyhow::{Context, Result};
+use controller::controller::reconciler::validation::validation_errors;
+use controller::crd::SecretManagerConfig;
+use serde::Deserialize;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Validate every SecretManagerConfig in the given files (`-` reads stdin)
+/// Fails if any resource is invalid or no SecretManagerConfig was found
+pub fn validate_command(files: Vec<PathBuf>) -> Result<()> {
+    let mut checked = 0usize;
+    let mut failed = 0usize;
+
+    for file in files {
+        let (label, content) = read_input(&file)?;
+
+        for (index, document) in serde_yaml::Deserializer::from_str(&content).enumerate() {
+            let value = serde_yaml::Value::deserialize(document)
+                .with_context(|| format!("{label}: document {} is not valid YAML", index + 1))?;
+            if value.is_null()
+                || value.get("kind").and_then(|k| k.as_str()) != Some("SecretManagerConfig")
+            {
+                continue;
+            }
+            checked += 1;
+
+            let resource = value
+                .get("metadata")
+                .and_then(|m| m.get("name"))
+                .and_then(|n| n.as_str())
+                .map_or_else(|| format!("document {}", index + 1), str::to_string);
+
+            let errors = match serde_yaml::from_value::<SecretManagerConfig>(value) {
+                Ok(config) => validation_errors(&config)
+                    .into_iter()
+                    .map(|error| error.message)
+                    .collect(),
+                Err(e) => vec![format!(
+                    "does not match the SecretManagerConfig schema: {e}"
+                )],
+            };
+
+            if errors.is_empty() {
+                println!("✅ {label}: SecretManagerConfig '{resource}' is valid");
+            } else {
+                failed += 1;
+                println!("❌ {label}: SecretManagerConfig '{resource}' is invalid");
+                for error in errors {
+                    println!("   - {error}");
+                }
+            }
+        }
+    }
+
+    if checked == 0 {
+        return Err(anyhow::anyhow!(
+            "No SecretManagerConfig resources found in the given files"
+        ));
+    }
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "{failed} of {checked} SecretManagerConfig resources failed validation"
+        ));
+    }
+
+    println!("\nAll {checked} SecretManagerConfig resources are valid");
+    Ok(())
+}
+
+/// Read a manifest file, or stdin for `-`
+fn read_input(file: &Path) -> Result<(String, String)> {
+    if file.as_os_str() == "-" {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .context("Failed to read manifests from stdin")?;
+        return Ok(("<stdin>".to_string(), content));
+    }
+    let content = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read '{}'", file.display()))?;
+    Ok((file.display().to_string(), content))
+}
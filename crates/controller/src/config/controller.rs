@@ -1,14 +1,22 @@
 //! # Controller Configuration
 //!
-//! Controller-level settings loaded from environment variables.
+//! Controller-level settings loaded from environment variables, a config file, or ConfigMap data.
 
+use super::source::{
+    ConfigLookup, env_lookup, lookup_optional_str, lookup_or_default, lookup_or_default_bool,
+    lookup_or_default_str,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 /// Controller-level configuration
 ///
 /// All settings have sensible defaults and can be overridden via environment variables.
 /// Environment variables are populated from a ConfigMap using `envFrom` in the deployment.
-#[derive(Debug, Clone)]
+/// Serialized as-is by the `/config` debug endpoint, so it must not hold credentials.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ControllerConfig {
     /// Reconciliation error requeue interval (seconds)
     /// How long to wait before retrying a failed reconciliation
@@ -34,8 +42,9 @@ pub struct ControllerConfig {
     /// SOPS private key secret name
     /// Name of the Kubernetes secret containing the SOPS GPG private key
     pub sops_private_key_secret_name: String,
-    /// Enable SOPS key watch for hot-reload
-    /// When true, watches for changes to SOPS key secret and reloads without restart
+    /// Enable the referenced Secret watch
+    /// When true, changes to SOPS key secrets reload the key ring, and changes to any referenced
+    /// Secret reconcile the resources that use it
     pub sops_key_watch_enabled: bool,
     /// Controller namespace
     /// Namespace where the controller is deployed (for SOPS key lookup)
@@ -52,14 +61,104 @@ pub struct ControllerConfig {
     /// Enable distributed tracing
     pub enable_tracing: bool,
     /// Maximum concurrent reconciliations
-    /// Limits how many resources can be reconciled simultaneously
+    /// Limits how many resources can be reconciled simultaneously (0 = unbounded)
+    /// Applied when the watch (re)starts; `--max-concurrent-reconciles` takes precedence
     pub max_concurrent_reconciliations: usize,
-    /// Maximum secrets per resource
-    /// Prevents resource exhaustion from overly large secret lists
-    pub max_secrets_per_resource: usize,
-    /// Maximum secret size in bytes
-    /// Enforced by validation (64KB default)
-    pub max_secret_size_bytes: usize,
+    /// Maximum concurrent reconciliations per cloud provider (gcp, aws, azure)
+    /// Keeps a burst of reconciles (e.g. after a restart) from stampeding one provider's API
+    /// (0 = unbounded). Fixed at startup; `--max-concurrent-provider-reconciles` takes precedence
+    pub max_concurrent_provider_reconciliations: usize,
+    /// Maximum concurrent reconciliations per cloud project (GCP project, AWS account and
+    /// region, Azure vault). 1 serializes each project's writes while other projects proceed
+    /// in parallel (0 = unbounded, fixed at startup)
+    pub max_concurrent_project_reconciliations: usize,
+    /// Default provider operations per second for each resource (0 = unlimited)
+    /// Overridden per resource by `spec.providerRateLimit.requestsPerSecond`
+    pub provider_rate_limit_rps: f64,
+    /// Default provider operation burst for each resource
+    /// Overridden per resource by `spec.providerRateLimit.burst`
+    pub provider_rate_limit_burst: u32,
+    /// Azure Key Vault operations per second, shared by all resources using the same vault
+    /// (0 = unlimited). Applied in addition to the per-resource limit
+    pub azure_vault_rate_limit_rps: f64,
+    /// Azure Key Vault operation burst per vault
+    pub azure_vault_rate_limit_burst: u32,
+    /// Extracted FluxCD artifacts kept in the shared artifact cache
+    /// Least recently used revisions are evicted beyond this (fixed at startup)
+    pub artifact_cache_max_entries: usize,
+    /// Maximum total size of an extracted artifact in bytes (0 = unlimited)
+    /// Artifacts over the limit fail with reason `ArtifactCorrupted` (fixed at startup)
+    pub artifact_max_extracted_bytes: u64,
+    /// Maximum number of entries in an artifact tarball (0 = unlimited, fixed at startup)
+    pub artifact_max_files: u64,
+    /// Log events kept per resource for `/api/v1/resources/{namespace}/{name}/logs`
+    /// (0 disables the buffer, fixed at startup)
+    pub resource_log_buffer_size: usize,
+    /// Delay between starting startup reconciles of existing resources (milliseconds)
+    /// Existing resources are queued failed-first at startup and reconciled in the background
+    /// at this rate while the watch runs (0 = no rate limit)
+    pub startup_reconcile_interval_ms: u64,
+    /// Default maximum keys per resource (0 = unlimited)
+    /// Checked before provider writes; overridden per resource by `spec.secrets.limits.maxKeys`
+    pub max_secrets_per_resource: u64,
+    /// Default maximum size of a single value in bytes (0 = unlimited)
+    /// Overridden per resource by `spec.secrets.limits.maxValueBytes`
+    pub max_secret_size_bytes: u64,
+    /// Default maximum total size of a resource's values in bytes (0 = unlimited)
+    /// Overridden per resource by `spec.secrets.limits.maxTotalBytes`
+    pub max_total_secret_bytes: u64,
+    /// Config file poll interval (seconds)
+    /// How often `CONTROLLER_CONFIG_FILE` is checked for changes
+    pub config_file_poll_interval_secs: u64,
+    /// Feature gates (`FEATURE_GATES=GateA=true,GateB=false`)
+    /// Unknown gates are kept so a newer ConfigMap doesn't break an older controller
+    pub feature_gates: BTreeMap<String, bool>,
+    /// Provider API endpoint overrides (private endpoints, proxies, emulators)
+    pub provider_endpoints: ProviderEndpoints,
+    /// Audit log of provider mutations (fixed at startup)
+    pub audit: AuditConfig,
+}
+
+/// Provider API endpoint overrides
+///
+/// When unset, each provider uses its public endpoint (or the Pact mock server in `PACT_MODE`).
+/// Azure Key Vault URLs are derived from `vaultName` per resource and are not overridden here.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderEndpoints {
+    /// GCP Secret Manager base URL (`GCP_SECRET_MANAGER_ENDPOINT`)
+    pub gcp_secret_manager: Option<String>,
+    /// AWS Secrets Manager endpoint URL (`AWS_SECRETS_MANAGER_ENDPOINT`)
+    pub aws_secrets_manager: Option<String>,
+    /// Domains a resource's `provider.gcp.endpointOverride` may point to, in addition to
+    /// `googleapis.com` (`GCP_ENDPOINT_ALLOWED_DOMAINS`, comma-separated)
+    pub gcp_allowed_domains: Vec<String>,
+}
+
+/// Audit log sink settings
+///
+/// Every secret/config create, update, delete, disable, and enable is appended to the sink
+/// as a hash-chained JSON record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditConfig {
+    /// Sink type (`AUDIT_SINK`): none, stdout, file, or http
+    pub sink: String,
+    /// JSON-lines file appended to by the file sink (`AUDIT_FILE_PATH`)
+    pub file_path: String,
+    /// URL each record is POSTed to by the http sink (`AUDIT_HTTP_ENDPOINT`)
+    /// Must not embed credentials, as it is served by `/config`
+    pub http_endpoint: Option<String>,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            sink: crate::constants::DEFAULT_AUDIT_SINK.to_string(),
+            file_path: crate::constants::DEFAULT_AUDIT_FILE_PATH.to_string(),
+            http_endpoint: None,
+        }
+    }
 }
 
 impl Default for ControllerConfig {
@@ -81,9 +180,26 @@ impl Default for ControllerConfig {
             log_enable_color: false,
             enable_metrics: true,
             enable_tracing: true,
-            max_concurrent_reconciliations: 10,
-            max_secrets_per_resource: 1000,
-            max_secret_size_bytes: 65536, // 64KB
+            max_concurrent_reconciliations: DEFAULT_MAX_CONCURRENT_RECONCILIATIONS,
+            max_concurrent_provider_reconciliations:
+                DEFAULT_MAX_CONCURRENT_PROVIDER_RECONCILIATIONS,
+            max_concurrent_project_reconciliations: DEFAULT_MAX_CONCURRENT_PROJECT_RECONCILIATIONS,
+            provider_rate_limit_rps: DEFAULT_PROVIDER_RATE_LIMIT_RPS,
+            provider_rate_limit_burst: DEFAULT_PROVIDER_RATE_LIMIT_BURST,
+            azure_vault_rate_limit_rps: DEFAULT_AZURE_VAULT_RATE_LIMIT_RPS,
+            azure_vault_rate_limit_burst: DEFAULT_AZURE_VAULT_RATE_LIMIT_BURST,
+            artifact_cache_max_entries: DEFAULT_ARTIFACT_CACHE_MAX_ENTRIES,
+            artifact_max_extracted_bytes: DEFAULT_ARTIFACT_MAX_EXTRACTED_BYTES,
+            artifact_max_files: DEFAULT_ARTIFACT_MAX_FILES,
+            resource_log_buffer_size: DEFAULT_RESOURCE_LOG_BUFFER_SIZE,
+            startup_reconcile_interval_ms: DEFAULT_STARTUP_RECONCILE_INTERVAL_MS,
+            max_secrets_per_resource: DEFAULT_MAX_SECRETS_PER_RESOURCE,
+            max_secret_size_bytes: DEFAULT_MAX_SECRET_SIZE_BYTES,
+            max_total_secret_bytes: DEFAULT_MAX_TOTAL_SECRET_BYTES,
+            config_file_poll_interval_secs: DEFAULT_CONFIG_FILE_POLL_INTERVAL_SECS,
+            feature_gates: BTreeMap::new(),
+            provider_endpoints: ProviderEndpoints::default(),
+            audit: AuditConfig::default(),
         }
     }
 }
@@ -91,50 +207,175 @@ impl Default for ControllerConfig {
 impl ControllerConfig {
     /// Load configuration from environment variables with defaults
     pub fn from_env() -> Self {
+        Self::from_lookup(&env_lookup)
+    }
+
+    /// Load configuration from a key lookup with defaults
+    /// Keys are upper-case setting names (e.g. `BACKOFF_START_MS`)
+    pub fn from_lookup(lookup: &ConfigLookup<'_>) -> Self {
         use crate::constants::*;
         Self {
-            reconciliation_error_requeue_secs: env_var_or_default(
+            reconciliation_error_requeue_secs: lookup_or_default(
+                lookup,
                 "RECONCILIATION_ERROR_REQUEUE_SECS",
                 DEFAULT_RECONCILIATION_ERROR_REQUEUE_SECS,
             ),
-            backoff_start_ms: env_var_or_default("BACKOFF_START_MS", DEFAULT_BACKOFF_START_MS),
-            backoff_max_ms: env_var_or_default("BACKOFF_MAX_MS", DEFAULT_BACKOFF_MAX_MS),
-            watch_restart_delay_secs: env_var_or_default(
+            backoff_start_ms: lookup_or_default(
+                lookup,
+                "BACKOFF_START_MS",
+                DEFAULT_BACKOFF_START_MS,
+            ),
+            backoff_max_ms: lookup_or_default(lookup, "BACKOFF_MAX_MS", DEFAULT_BACKOFF_MAX_MS),
+            watch_restart_delay_secs: lookup_or_default(
+                lookup,
                 "WATCH_RESTART_DELAY_SECS",
                 DEFAULT_WATCH_RESTART_DELAY_SECS,
             ),
-            watch_restart_delay_after_end_secs: env_var_or_default(
+            watch_restart_delay_after_end_secs: lookup_or_default(
+                lookup,
                 "WATCH_RESTART_DELAY_AFTER_END_SECS",
                 DEFAULT_WATCH_RESTART_DELAY_AFTER_END_SECS,
             ),
-            min_gitrepository_pull_interval_secs: env_var_or_default(
+            min_gitrepository_pull_interval_secs: lookup_or_default(
+                lookup,
                 "MIN_GITREPOSITORY_PULL_INTERVAL_SECS",
                 MIN_GITREPOSITORY_PULL_INTERVAL_SECS,
             ),
-            min_reconcile_interval_secs: env_var_or_default(
+            min_reconcile_interval_secs: lookup_or_default(
+                lookup,
                 "MIN_RECONCILE_INTERVAL_SECS",
                 MIN_RECONCILE_INTERVAL_SECS,
             ),
-            sops_private_key_secret_name: env_var_or_default_str(
+            sops_private_key_secret_name: lookup_or_default_str(
+                lookup,
                 "SOPS_PRIVATE_KEY_SECRET_NAME",
                 "sops-private-key",
             ),
-            sops_key_watch_enabled: env_var_or_default_bool("SOPS_KEY_WATCH_ENABLED", true),
-            controller_namespace: env_var_or_default_str("POD_NAMESPACE", "octopilot-system"),
-            log_level: env_var_or_default_str("LOG_LEVEL", "INFO"),
-            log_format: env_var_or_default_str("LOG_FORMAT", "json"),
-            log_enable_color: env_var_or_default_bool("LOG_ENABLE_COLOR", false),
-            enable_metrics: env_var_or_default_bool("ENABLE_METRICS", true),
-            enable_tracing: env_var_or_default_bool("ENABLE_TRACING", true),
-            max_concurrent_reconciliations: env_var_or_default(
+            sops_key_watch_enabled: lookup_or_default_bool(lookup, "SOPS_KEY_WATCH_ENABLED", true),
+            controller_namespace: lookup_or_default_str(
+                lookup,
+                "POD_NAMESPACE",
+                "octopilot-system",
+            ),
+            log_level: lookup_or_default_str(lookup, "LOG_LEVEL", "INFO"),
+            log_format: lookup_or_default_str(lookup, "LOG_FORMAT", "json"),
+            log_enable_color: lookup_or_default_bool(lookup, "LOG_ENABLE_COLOR", false),
+            enable_metrics: lookup_or_default_bool(lookup, "ENABLE_METRICS", true),
+            enable_tracing: lookup_or_default_bool(lookup, "ENABLE_TRACING", true),
+            max_concurrent_reconciliations: lookup_or_default(
+                lookup,
                 "MAX_CONCURRENT_RECONCILIATIONS",
-                10,
+                DEFAULT_MAX_CONCURRENT_RECONCILIATIONS,
+            ),
+            max_concurrent_provider_reconciliations: lookup_or_default(
+                lookup,
+                "MAX_CONCURRENT_PROVIDER_RECONCILIATIONS",
+                DEFAULT_MAX_CONCURRENT_PROVIDER_RECONCILIATIONS,
+            ),
+            max_concurrent_project_reconciliations: lookup_or_default(
+                lookup,
+                "MAX_CONCURRENT_PROJECT_RECONCILIATIONS",
+                DEFAULT_MAX_CONCURRENT_PROJECT_RECONCILIATIONS,
+            ),
+            provider_rate_limit_rps: lookup_or_default(
+                lookup,
+                "PROVIDER_RATE_LIMIT_RPS",
+                DEFAULT_PROVIDER_RATE_LIMIT_RPS,
+            ),
+            provider_rate_limit_burst: lookup_or_default(
+                lookup,
+                "PROVIDER_RATE_LIMIT_BURST",
+                DEFAULT_PROVIDER_RATE_LIMIT_BURST,
+            ),
+            azure_vault_rate_limit_rps: lookup_or_default(
+                lookup,
+                "AZURE_VAULT_RATE_LIMIT_RPS",
+                DEFAULT_AZURE_VAULT_RATE_LIMIT_RPS,
             ),
-            max_secrets_per_resource: env_var_or_default("MAX_SECRETS_PER_RESOURCE", 1000),
-            max_secret_size_bytes: env_var_or_default("MAX_SECRET_SIZE_BYTES", 65536),
+            azure_vault_rate_limit_burst: lookup_or_default(
+                lookup,
+                "AZURE_VAULT_RATE_LIMIT_BURST",
+                DEFAULT_AZURE_VAULT_RATE_LIMIT_BURST,
+            ),
+            artifact_cache_max_entries: lookup_or_default(
+                lookup,
+                "ARTIFACT_CACHE_MAX_ENTRIES",
+                DEFAULT_ARTIFACT_CACHE_MAX_ENTRIES,
+            ),
+            artifact_max_extracted_bytes: lookup_or_default(
+                lookup,
+                "ARTIFACT_MAX_EXTRACTED_BYTES",
+                DEFAULT_ARTIFACT_MAX_EXTRACTED_BYTES,
+            ),
+            artifact_max_files: lookup_or_default(
+                lookup,
+                "ARTIFACT_MAX_FILES",
+                DEFAULT_ARTIFACT_MAX_FILES,
+            ),
+            resource_log_buffer_size: lookup_or_default(
+                lookup,
+                "RESOURCE_LOG_BUFFER_SIZE",
+                DEFAULT_RESOURCE_LOG_BUFFER_SIZE,
+            ),
+            startup_reconcile_interval_ms: lookup_or_default(
+                lookup,
+                "STARTUP_RECONCILE_INTERVAL_MS",
+                DEFAULT_STARTUP_RECONCILE_INTERVAL_MS,
+            ),
+            max_secrets_per_resource: lookup_or_default(
+                lookup,
+                "MAX_SECRETS_PER_RESOURCE",
+                DEFAULT_MAX_SECRETS_PER_RESOURCE,
+            ),
+            max_secret_size_bytes: lookup_or_default(
+                lookup,
+                "MAX_SECRET_SIZE_BYTES",
+                DEFAULT_MAX_SECRET_SIZE_BYTES,
+            ),
+            max_total_secret_bytes: lookup_or_default(
+                lookup,
+                "MAX_TOTAL_SECRET_BYTES",
+                DEFAULT_MAX_TOTAL_SECRET_BYTES,
+            ),
+            config_file_poll_interval_secs: lookup_or_default(
+                lookup,
+                "CONFIG_FILE_POLL_INTERVAL_SECS",
+                DEFAULT_CONFIG_FILE_POLL_INTERVAL_SECS,
+            ),
+            feature_gates: lookup("FEATURE_GATES")
+                .map(|gates| parse_feature_gates(&gates))
+                .unwrap_or_default(),
+            provider_endpoints: ProviderEndpoints {
+                gcp_secret_manager: lookup_optional_str(lookup, "GCP_SECRET_MANAGER_ENDPOINT"),
+                aws_secrets_manager: lookup_optional_str(lookup, "AWS_SECRETS_MANAGER_ENDPOINT"),
+                gcp_allowed_domains: lookup_optional_str(lookup, "GCP_ENDPOINT_ALLOWED_DOMAINS")
+                    .map(|domains| {
+                        domains
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|domain| !domain.is_empty())
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+            audit: AuditConfig {
+                sink: lookup_or_default_str(lookup, "AUDIT_SINK", DEFAULT_AUDIT_SINK),
+                file_path: lookup_or_default_str(
+                    lookup,
+                    "AUDIT_FILE_PATH",
+                    DEFAULT_AUDIT_FILE_PATH,
+                ),
+                http_endpoint: lookup_optional_str(lookup, "AUDIT_HTTP_ENDPOINT"),
+            },
         }
     }
 
+    /// Check whether a feature gate is enabled (gates default to disabled)
+    pub fn feature_enabled(&self, gate: &str) -> bool {
+        self.feature_gates.get(gate).copied().unwrap_or(false)
+    }
+
     /// Get reconciliation error requeue duration
     pub fn reconciliation_error_requeue_duration(&self) -> Duration {
         Duration::from_secs(self.reconciliation_error_requeue_secs)
@@ -165,35 +406,74 @@ impl ControllerConfig {
         Duration::from_secs(self.min_gitrepository_pull_interval_secs)
     }
 
+    /// Get config file poll interval duration (at least one second)
+    pub fn config_file_poll_interval_duration(&self) -> Duration {
+        Duration::from_secs(self.config_file_poll_interval_secs.max(1))
+    }
+
     /// Get minimum reconcile interval duration
     pub fn min_reconcile_interval_duration(&self) -> Duration {
         Duration::from_secs(self.min_reconcile_interval_secs)
     }
 }
 
-/// Read environment variable or return default value
-fn env_var_or_default<T: std::str::FromStr>(key: &str, default: T) -> T
-where
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
-{
-    std::env::var(key)
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(default)
-}
-
-/// Read environment variable as boolean or return default
-fn env_var_or_default_bool(key: &str, default: bool) -> bool {
-    std::env::var(key)
-        .ok()
-        .map(|v| {
-            let v_lower = v.to_lowercase();
-            v_lower == "true" || v_lower == "1" || v_lower == "yes" || v_lower == "on"
+/// Parse Kubernetes-style feature gates: `GateA=true,GateB=false`
+/// A gate without a value is enabled; entries with an unparseable value are ignored
+pub fn parse_feature_gates(value: &str) -> BTreeMap<String, bool> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| match entry.split_once('=') {
+            Some((gate, enabled)) => enabled
+                .trim()
+                .parse::<bool>()
+                .ok()
+                .map(|enabled| (gate.trim().to_string(), enabled)),
+            None => Some((entry.to_string(), true)),
         })
-        .unwrap_or(default)
+        .collect()
 }
 
-/// Read environment variable as string or return default
-fn env_var_or_default_str(key: &str, default: &str) -> String {
-    std::env::var(key).unwrap_or_else(|_| default.to_string())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::source::{ConfigData, overlay_lookup};
+
+    #[test]
+    fn test_parse_feature_gates() {
+        let gates = parse_feature_gates("DeltaReconcile=true, AuditLog=false,Bare,Bad=maybe");
+        assert_eq!(gates.get("DeltaReconcile"), Some(&true));
+        assert_eq!(gates.get("AuditLog"), Some(&false));
+        assert_eq!(gates.get("Bare"), Some(&true));
+        assert_eq!(gates.get("Bad"), None);
+    }
+
+    #[test]
+    fn test_from_lookup_reads_data() {
+        let mut data = ConfigData::new();
+        data.insert("BACKOFF_MAX_MS".to_string(), "45000".to_string());
+        data.insert("FEATURE_GATES".to_string(), "AuditLog=true".to_string());
+        data.insert(
+            "GCP_SECRET_MANAGER_ENDPOINT".to_string(),
+            "https://secretmanager.p.googleapis.com".to_string(),
+        );
+        data.insert(
+            "GCP_ENDPOINT_ALLOWED_DOMAINS".to_string(),
+            "corp.example.com, , internal.example.net".to_string(),
+        );
+        let config = ControllerConfig::from_lookup(&overlay_lookup(&data));
+
+        assert_eq!(config.backoff_max_ms, 45000);
+        assert!(config.feature_enabled("AuditLog"));
+        assert!(!config.feature_enabled("DeltaReconcile"));
+        assert_eq!(
+            config.provider_endpoints.gcp_secret_manager.as_deref(),
+            Some("https://secretmanager.p.googleapis.com")
+        );
+        assert_eq!(
+            config.provider_endpoints.gcp_allowed_domains,
+            vec!["corp.example.com", "internal.example.net"]
+        );
+    }
 }
@@ -4,21 +4,26 @@
 //!
 //! All configuration has sensible defaults and can be overridden via environment variables.
 //! Environment variables are populated from a ConfigMap using `envFrom` in the deployment.
+//! Settings can also be read from the file or mounted ConfigMap directory named by
+//! `CONTROLLER_CONFIG_FILE`, which takes precedence over the environment.
 //!
-//! The configuration can be hot-reloaded by watching the ConfigMap for changes.
+//! The configuration can be hot-reloaded by watching the ConfigMap (or config file) for changes.
 
 mod controller;
 mod pact_mode;
 mod server;
+mod source;
 mod watch;
 
-pub use controller::ControllerConfig;
+pub use controller::{AuditConfig, ControllerConfig, ProviderEndpoints, parse_feature_gates};
 pub use pact_mode::{PactModeAPIOverride, PactModeConfig, ProviderId, ProviderPactConfig};
 pub use server::ServerConfig;
-pub use watch::start_configmap_watch;
+pub use source::{CONFIG_FILE_ENV, ConfigData, load_config_path, parse_config_yaml};
+pub use watch::{start_config_file_watch, start_configmap_watch};
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tracing::warn;
 
 /// Global controller configuration
 /// This is updated when the ConfigMap changes (hot-reload)
@@ -28,9 +33,29 @@ pub type SharedControllerConfig = Arc<RwLock<ControllerConfig>>;
 /// This is updated when the ConfigMap changes (hot-reload)
 pub type SharedServerConfig = Arc<RwLock<ServerConfig>>;
 
-/// Load configuration from environment variables with defaults
+/// Load configuration from the config file (if `CONTROLLER_CONFIG_FILE` is set) and
+/// environment variables, with defaults
 pub fn load_config() -> (ControllerConfig, ServerConfig) {
-    (ControllerConfig::from_env(), ServerConfig::from_env())
+    match source::load_config_file_from_env() {
+        Ok(Some(data)) => load_config_from_data(&data),
+        Ok(None) => (ControllerConfig::from_env(), ServerConfig::from_env()),
+        Err(e) => {
+            warn!(
+                "Failed to load {}: {:#}, using environment variables",
+                CONFIG_FILE_ENV, e
+            );
+            (ControllerConfig::from_env(), ServerConfig::from_env())
+        }
+    }
+}
+
+/// Load configuration from ConfigMap/file data, falling back to environment variables
+pub fn load_config_from_data(data: &ConfigData) -> (ControllerConfig, ServerConfig) {
+    let lookup = source::overlay_lookup(data);
+    (
+        ControllerConfig::from_lookup(&lookup),
+        ServerConfig::from_lookup(&lookup),
+    )
 }
 
 /// Create shared configuration instances
@@ -1,11 +1,15 @@
 //! # Server Configuration
 //!
-//! HTTP server settings loaded from environment variables.
+//! HTTP server settings loaded from environment variables, a config file, or ConfigMap data.
+
+use super::source::{ConfigLookup, env_lookup, lookup_or_default};
+use serde::Serialize;
 
 /// HTTP server configuration
 ///
 /// All settings have sensible defaults and can be overridden via environment variables.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ServerConfig {
     /// HTTP server port for metrics and health probes
     pub metrics_port: u16,
@@ -31,28 +35,24 @@ impl Default for ServerConfig {
 impl ServerConfig {
     /// Load configuration from environment variables with defaults
     pub fn from_env() -> Self {
+        Self::from_lookup(&env_lookup)
+    }
+
+    /// Load configuration from a key lookup with defaults
+    pub fn from_lookup(lookup: &ConfigLookup<'_>) -> Self {
         use crate::constants::*;
         Self {
-            metrics_port: env_var_or_default("METRICS_PORT", DEFAULT_METRICS_PORT),
-            startup_timeout_secs: env_var_or_default(
+            metrics_port: lookup_or_default(lookup, "METRICS_PORT", DEFAULT_METRICS_PORT),
+            startup_timeout_secs: lookup_or_default(
+                lookup,
                 "SERVER_STARTUP_TIMEOUT_SECS",
                 DEFAULT_SERVER_STARTUP_TIMEOUT_SECS,
             ),
-            poll_interval_ms: env_var_or_default(
+            poll_interval_ms: lookup_or_default(
+                lookup,
                 "SERVER_POLL_INTERVAL_MS",
                 DEFAULT_SERVER_POLL_INTERVAL_MS,
             ),
         }
     }
 }
-
-/// Read environment variable or return default value
-fn env_var_or_default<T: std::str::FromStr>(key: &str, default: T) -> T
-where
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
-{
-    std::env::var(key)
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(default)
-}
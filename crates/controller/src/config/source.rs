@@ -0,0 +1,206 @@
+//! # Configuration Sources
+//!
+//! Key lookup shared by `ControllerConfig` and `ServerConfig`.
+//!
+//! Values are resolved from (highest precedence first):
+//! 1. ConfigMap data delivered by the watch, or the file/directory at `CONTROLLER_CONFIG_FILE`
+//! 2. Environment variables (populated from the ConfigMap using `envFrom`)
+//! 3. Built-in defaults
+//!
+//! Keys are matched case-insensitively so `metrics_port` (ConfigMap style) and
+//! `METRICS_PORT` (environment style) refer to the same setting.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Environment variable naming a config file or a mounted ConfigMap directory
+pub const CONFIG_FILE_ENV: &str = "CONTROLLER_CONFIG_FILE";
+
+/// Configuration values keyed by upper-case setting name
+pub type ConfigData = BTreeMap<String, String>;
+
+/// Resolves a setting by its upper-case name
+pub type ConfigLookup<'a> = dyn Fn(&str) -> Option<String> + 'a;
+
+/// Look up a setting in the process environment
+/// Tries the upper-case name first, then the lower-case ConfigMap key kept by `envFrom`
+pub fn env_lookup(key: &str) -> Option<String> {
+    std::env::var(key)
+        .or_else(|_| std::env::var(key.to_lowercase()))
+        .ok()
+}
+
+/// Normalize ConfigMap/file keys to upper-case setting names
+pub fn normalize_data<'a>(data: impl IntoIterator<Item = (&'a String, &'a String)>) -> ConfigData {
+    data.into_iter()
+        .map(|(key, value)| (key.to_uppercase(), value.clone()))
+        .collect()
+}
+
+/// Look up a setting in `data` first, falling back to the environment
+pub fn overlay_lookup(data: &ConfigData) -> impl Fn(&str) -> Option<String> + '_ {
+    move |key: &str| data.get(key).cloned().or_else(|| env_lookup(key))
+}
+
+/// Load settings from a file or directory
+///
+/// - Directory: every regular file is a setting named after the file (a mounted ConfigMap);
+///   hidden entries such as the kubelet's `..data` symlink are skipped
+/// - File: a flat YAML mapping of setting name to scalar value
+///
+/// # Errors
+/// Returns an error if the path cannot be read or the file is not a flat YAML mapping
+pub fn load_config_path(path: &Path) -> Result<ConfigData> {
+    if path.is_dir() {
+        let mut data = ConfigData::new();
+        let entries = std::fs::read_dir(path).context(format!(
+            "Failed to read config directory {}",
+            path.display()
+        ))?;
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || !entry.path().is_file() {
+                continue;
+            }
+            let value = std::fs::read_to_string(entry.path()).context(format!(
+                "Failed to read config file {}",
+                entry.path().display()
+            ))?;
+            data.insert(name.to_uppercase(), value.trim_end().to_string());
+        }
+        return Ok(data);
+    }
+
+    let content = std::fs::read_to_string(path)
+        .context(format!("Failed to read config file {}", path.display()))?;
+    parse_config_yaml(&content).context(format!("Invalid config file {}", path.display()))
+}
+
+/// Parse a flat YAML mapping of setting name to scalar value
+///
+/// # Errors
+/// Returns an error if the document is not a mapping or a value is not a scalar
+pub fn parse_config_yaml(content: &str) -> Result<ConfigData> {
+    let value: serde_yaml::Value = serde_yaml::from_str(content)?;
+    let mapping = match value {
+        serde_yaml::Value::Null => return Ok(ConfigData::new()),
+        serde_yaml::Value::Mapping(mapping) => mapping,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "expected a mapping of setting name to value"
+            ));
+        }
+    };
+
+    let mut data = ConfigData::new();
+    for (key, value) in mapping {
+        let key = key
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("setting names must be strings"))?
+            .to_uppercase();
+        let value = match value {
+            serde_yaml::Value::String(s) => s,
+            serde_yaml::Value::Bool(b) => b.to_string(),
+            serde_yaml::Value::Number(n) => n.to_string(),
+            _ => return Err(anyhow::anyhow!("setting '{key}' must be a scalar value")),
+        };
+        data.insert(key, value);
+    }
+    Ok(data)
+}
+
+/// Load the file at `CONTROLLER_CONFIG_FILE`, if set
+/// Returns None when the variable is unset; read errors are returned so the caller can
+/// keep the previous configuration
+///
+/// # Errors
+/// Returns an error if the configured path cannot be loaded
+pub fn load_config_file_from_env() -> Result<Option<ConfigData>> {
+    match std::env::var(CONFIG_FILE_ENV) {
+        Ok(path) if !path.is_empty() => load_config_path(Path::new(&path)).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Parse a value or return the default
+pub fn lookup_or_default<T: std::str::FromStr>(
+    lookup: &ConfigLookup<'_>,
+    key: &str,
+    default: T,
+) -> T {
+    lookup(key)
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(default)
+}
+
+/// Parse a boolean value or return the default
+pub fn lookup_or_default_bool(lookup: &ConfigLookup<'_>, key: &str, default: bool) -> bool {
+    lookup(key)
+        .map(|v| {
+            let v_lower = v.trim().to_lowercase();
+            v_lower == "true" || v_lower == "1" || v_lower == "yes" || v_lower == "on"
+        })
+        .unwrap_or(default)
+}
+
+/// Read a string value or return the default
+pub fn lookup_or_default_str(lookup: &ConfigLookup<'_>, key: &str, default: &str) -> String {
+    lookup(key).unwrap_or_else(|| default.to_string())
+}
+
+/// Read an optional, non-empty string value
+pub fn lookup_optional_str(lookup: &ConfigLookup<'_>, key: &str) -> Option<String> {
+    lookup(key)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_yaml_scalars() {
+        let data = parse_config_yaml(
+            "metrics_port: 5000\nsops_key_watch_enabled: false\nlog_level: DEBUG\n",
+        )
+        .unwrap_or_default();
+        assert_eq!(data.get("METRICS_PORT").map(String::as_str), Some("5000"));
+        assert_eq!(
+            data.get("SOPS_KEY_WATCH_ENABLED").map(String::as_str),
+            Some("false")
+        );
+        assert_eq!(data.get("LOG_LEVEL").map(String::as_str), Some("DEBUG"));
+    }
+
+    #[test]
+    fn test_parse_config_yaml_rejects_nested_values() {
+        assert!(parse_config_yaml("feature_gates:\n  a: true\n").is_err());
+        assert!(parse_config_yaml("- a\n- b\n").is_err());
+        assert!(parse_config_yaml("").unwrap_or_default().is_empty());
+    }
+
+    #[test]
+    fn test_overlay_lookup_prefers_data() {
+        let mut data = ConfigData::new();
+        data.insert("SMC_TEST_OVERLAY_KEY".to_string(), "from-data".to_string());
+        let lookup = overlay_lookup(&data);
+        assert_eq!(lookup("SMC_TEST_OVERLAY_KEY").as_deref(), Some("from-data"));
+        assert_eq!(lookup("SMC_TEST_OVERLAY_MISSING"), None);
+    }
+
+    #[test]
+    fn test_lookup_helpers() {
+        let mut data = ConfigData::new();
+        data.insert("PORT".to_string(), " 8080 ".to_string());
+        data.insert("ENABLED".to_string(), "Yes".to_string());
+        data.insert("EMPTY".to_string(), "  ".to_string());
+        let lookup = overlay_lookup(&data);
+        assert_eq!(lookup_or_default(&lookup, "PORT", 0u16), 8080);
+        assert_eq!(lookup_or_default(&lookup, "SMC_TEST_MISSING_PORT", 5u16), 5);
+        assert!(lookup_or_default_bool(&lookup, "ENABLED", false));
+        assert_eq!(lookup_optional_str(&lookup, "EMPTY"), None);
+    }
+}
@@ -1,13 +1,16 @@
 //! # ConfigMap Watch
 //!
-//! Watches for ConfigMap changes and hot-reloads controller configuration.
+//! Watches for ConfigMap (or config file) changes and hot-reloads controller configuration.
 
-use crate::config::{ControllerConfig, ServerConfig, SharedControllerConfig, SharedServerConfig};
+use crate::config::source::{ConfigData, load_config_path, normalize_data};
+use crate::config::{SharedControllerConfig, SharedServerConfig, load_config_from_data};
 use futures::{StreamExt, pin_mut};
 use k8s_openapi::api::core::v1::ConfigMap;
 use kube::Api;
 use kube_runtime::watcher;
-use tracing::{error, info, warn};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
 
 /// Start watching for ConfigMap changes and hot-reload configuration
 ///
@@ -99,65 +102,88 @@ pub fn start_configmap_watch(
     });
 }
 
+/// Start polling a config file (or mounted ConfigMap directory) and hot-reload on change
+///
+/// Kubelet refreshes mounted ConfigMaps in place, so polling the contents picks up
+/// edits without a pod restart. Read or parse errors keep the previous configuration.
+pub fn start_config_file_watch(
+    path: PathBuf,
+    poll_interval: Duration,
+    controller_config: SharedControllerConfig,
+    server_config: SharedServerConfig,
+) {
+    tokio::spawn(async move {
+        info!(
+            "Watching config file '{}' for changes (every {}s)",
+            path.display(),
+            poll_interval.as_secs()
+        );
+        // The initial contents were applied at startup
+        let mut last_data = load_config_path(&path).ok();
+        let mut last_error: Option<String> = None;
+        let mut interval = tokio::time::interval(poll_interval);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            match load_config_path(&path) {
+                Ok(data) => {
+                    last_error = None;
+                    if last_data.as_ref() == Some(&data) {
+                        debug!("Config file '{}' unchanged", path.display());
+                        continue;
+                    }
+                    info!(
+                        "Config file '{}' changed, reloading configuration...",
+                        path.display()
+                    );
+                    apply_config(&data, &controller_config, &server_config).await;
+                    last_data = Some(data);
+                }
+                Err(e) => {
+                    let message = format!("{e:#}");
+                    if last_error.as_deref() != Some(message.as_str()) {
+                        warn!(
+                            "Failed to reload config file '{}', keeping previous configuration: {}",
+                            path.display(),
+                            message
+                        );
+                        last_error = Some(message);
+                    }
+                }
+            }
+        }
+    });
+}
+
 /// Reload configuration from ConfigMap data
 ///
-/// Updates environment variables temporarily, reloads config, then restores env vars.
-/// This allows the config module to read the new values.
+/// ConfigMap keys take precedence over environment variables; missing keys fall back to
+/// the environment and then to defaults.
 async fn reload_config_from_configmap(
     configmap: &ConfigMap,
     controller_config: &SharedControllerConfig,
     server_config: &SharedServerConfig,
 ) {
-    // Get current environment variables to restore later
-    let mut env_backup = std::collections::HashMap::new();
-    let env_vars_to_backup = vec![
-        "METRICS_PORT",
-        "SERVER_STARTUP_TIMEOUT_SECS",
-        "SERVER_POLL_INTERVAL_MS",
-        "RECONCILIATION_ERROR_REQUEUE_SECS",
-        "BACKOFF_START_MS",
-        "BACKOFF_MAX_MS",
-        "WATCH_RESTART_DELAY_SECS",
-        "WATCH_RESTART_DELAY_AFTER_END_SECS",
-        "MIN_GITREPOSITORY_PULL_INTERVAL_SECS",
-        "MIN_RECONCILE_INTERVAL_SECS",
-        "SOPS_PRIVATE_KEY_SECRET_NAME",
-        "SOPS_KEY_WATCH_ENABLED",
-        "POD_NAMESPACE",
-        "LOG_LEVEL",
-        "LOG_FORMAT",
-        "LOG_ENABLE_COLOR",
-        "ENABLE_METRICS",
-        "ENABLE_TRACING",
-        "MAX_CONCURRENT_RECONCILIATIONS",
-        "MAX_SECRETS_PER_RESOURCE",
-        "MAX_SECRET_SIZE_BYTES",
-    ];
-
-    // Backup current env vars
-    for key in &env_vars_to_backup {
-        if let Ok(value) = std::env::var(key) {
-            env_backup.insert(key.to_string(), value);
-        }
-    }
+    let data = configmap
+        .data
+        .as_ref()
+        .map(normalize_data)
+        .unwrap_or_default();
+    apply_config(&data, controller_config, server_config).await;
+}
 
-    // Set environment variables from ConfigMap data.
-    // SAFETY: This runs inside the controller's single reconciler task while
-    // holding an exclusive write lock on the config.  No other task mutates
-    // env vars at the same time.
-    if let Some(data) = &configmap.data {
-        for (key, value) in data {
-            let env_key = key.to_uppercase();
-            unsafe {
-                std::env::set_var(&env_key, value);
-            }
-            info!("  Set {}={}", env_key, value);
-        }
+/// Replace the shared configuration with values loaded from `data`
+async fn apply_config(
+    data: &ConfigData,
+    controller_config: &SharedControllerConfig,
+    server_config: &SharedServerConfig,
+) {
+    for (key, value) in data {
+        info!("  Set {}={}", key, value);
     }
 
-    // Reload configuration
-    let new_controller_config = ControllerConfig::from_env();
-    let new_server_config = ServerConfig::from_env();
+    let (new_controller_config, new_server_config) = load_config_from_data(data);
 
     // Update shared configuration
     {
@@ -180,18 +206,4 @@ async fn reload_config_from_configmap(
         "  Server config: metrics_port={}, startup_timeout={}s",
         new_server_config.metrics_port, new_server_config.startup_timeout_secs
     );
-
-    // Restore original environment variables.
-    // SAFETY: Same single-task context as the set above.
-    for key in &env_vars_to_backup {
-        if let Some(value) = env_backup.get(*key) {
-            unsafe {
-                std::env::set_var(key, value);
-            }
-        } else {
-            unsafe {
-                std::env::remove_var(key);
-            }
-        }
-    }
 }
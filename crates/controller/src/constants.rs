@@ -29,6 +29,70 @@ pub const DEFAULT_WATCH_RESTART_DELAY_SECS: u64 = 5;
 /// Default delay before restarting watch stream after it ends (seconds)
 pub const DEFAULT_WATCH_RESTART_DELAY_AFTER_END_SECS: u64 = 1;
 
+/// Default maximum number of resources reconciled concurrently by the controller runtime
+pub const DEFAULT_MAX_CONCURRENT_RECONCILIATIONS: usize = 10;
+
+/// Default maximum number of concurrent reconciliations calling the same cloud provider
+pub const DEFAULT_MAX_CONCURRENT_PROVIDER_RECONCILIATIONS: usize = 5;
+
+/// Default maximum concurrent reconciliations per cloud project (0 = unbounded)
+pub const DEFAULT_MAX_CONCURRENT_PROJECT_RECONCILIATIONS: usize = 0;
+
+/// Default provider operations per second for each resource (0 = unlimited)
+pub const DEFAULT_PROVIDER_RATE_LIMIT_RPS: f64 = 0.0;
+
+/// Default provider operation burst for each resource
+pub const DEFAULT_PROVIDER_RATE_LIMIT_BURST: u32 = 10;
+
+/// Default Azure Key Vault operations per second, shared by all resources using a vault
+/// (0 = unlimited). Key Vault allows 4000 secret transactions per 10 seconds per vault.
+pub const DEFAULT_AZURE_VAULT_RATE_LIMIT_RPS: f64 = 200.0;
+
+/// Default Azure Key Vault operation burst per vault
+pub const DEFAULT_AZURE_VAULT_RATE_LIMIT_BURST: u32 = 100;
+
+/// Default delay between starting startup reconciles of existing resources (milliseconds)
+pub const DEFAULT_STARTUP_RECONCILE_INTERVAL_MS: u64 = 200;
+
+/// Default number of extracted artifacts kept in the artifact cache
+pub const DEFAULT_ARTIFACT_CACHE_MAX_ENTRIES: usize = 20;
+
+/// Default maximum total size of an extracted artifact (512 MiB)
+pub const DEFAULT_ARTIFACT_MAX_EXTRACTED_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Default maximum number of entries in an artifact tarball
+pub const DEFAULT_ARTIFACT_MAX_FILES: u64 = 100_000;
+
+/// Default maximum number of keys per resource
+pub const DEFAULT_MAX_SECRETS_PER_RESOURCE: u64 = 1000;
+
+/// Default maximum size of a single secret value (64 KiB, the GCP and AWS payload limit)
+pub const DEFAULT_MAX_SECRET_SIZE_BYTES: u64 = 64 * 1024;
+
+/// Default maximum total size of all values of a resource (10 MiB)
+pub const DEFAULT_MAX_TOTAL_SECRET_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default number of log events kept per resource in the in-memory log buffer
+pub const DEFAULT_RESOURCE_LOG_BUFFER_SIZE: usize = 200;
+
+/// Number of reconciliations kept in `status.recentReconciles`
+pub const MAX_RECENT_RECONCILES: usize = 10;
+
+/// Maximum length of the error message recorded in `status.recentReconciles`
+pub const MAX_RECENT_RECONCILE_MESSAGE_LEN: usize = 256;
+
+/// Default audit log sink (none, stdout, file, http)
+pub const DEFAULT_AUDIT_SINK: &str = "none";
+
+/// Default audit log file for the file sink
+pub const DEFAULT_AUDIT_FILE_PATH: &str = "/var/log/secret-manager-controller/audit.jsonl";
+
+/// Default interval for polling `CONTROLLER_CONFIG_FILE` for changes (seconds)
+pub const DEFAULT_CONFIG_FILE_POLL_INTERVAL_SECS: u64 = 10;
+
+/// Interval for sampling tokio runtime and process memory metrics (seconds)
+pub const RUNTIME_METRICS_INTERVAL_SECS: u64 = 15;
+
 /// Minimum GitRepository pull interval (seconds)
 /// Shorter intervals may hit API rate limits
 pub const MIN_GITREPOSITORY_PULL_INTERVAL_SECS: u64 = 60;
@@ -0,0 +1,191 @@
+//! # Resource Health
+//!
+//! Aggregate sync health across all `SecretManagerConfig` resources.
+//!
+//! Computed from the resource status written by the reconciler (phase, Ready condition,
+//! last reconcile time), read from the controller's watch cache so no API calls are made.
+
+use crate::crd::SecretManagerConfig;
+use chrono::{DateTime, Utc};
+use kube_runtime::reflector::Store;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Watch cache of SecretManagerConfig resources, replaced each time the watch (re)starts
+/// `None` until the first watch has started
+pub type SharedResourceStore = Arc<RwLock<Option<Store<SecretManagerConfig>>>>;
+
+/// Aggregate sync health of all resources
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceHealthSummary {
+    /// Number of SecretManagerConfig resources
+    pub total: usize,
+    /// Resources whose last reconcile succeeded (phase Ready)
+    pub ready: usize,
+    /// Resources whose last reconcile failed (Failed, PartialFailure, Retrying, or Ready=False)
+    pub failing: usize,
+    /// Resources with `spec.suspend: true`
+    pub suspended: usize,
+    /// Seconds since the most recent successful reconcile of any resource
+    pub last_successful_reconcile_age_secs: Option<i64>,
+}
+
+impl ResourceHealthSummary {
+    /// Whether every non-suspended resource is in sync
+    pub fn is_healthy(&self) -> bool {
+        self.failing == 0
+    }
+}
+
+/// Whether a resource's last reconcile failed
+pub fn is_failing(config: &SecretManagerConfig) -> bool {
+    config.status.as_ref().is_some_and(|status| {
+        matches!(
+            status.phase.as_deref(),
+            Some("Failed" | "PartialFailure" | "Retrying")
+        ) || status
+            .conditions
+            .iter()
+            .any(|c| c.r#type == "Ready" && c.status == "False")
+    })
+}
+
+/// Whether a resource's last reconcile succeeded
+pub fn is_ready(config: &SecretManagerConfig) -> bool {
+    config
+        .status
+        .as_ref()
+        .is_some_and(|status| status.phase.as_deref() == Some("Ready"))
+}
+
+/// Summarize sync health across resources
+/// Suspended resources are counted as suspended only, not as ready or failing
+pub fn summarize(
+    resources: &[Arc<SecretManagerConfig>],
+    now: DateTime<Utc>,
+) -> ResourceHealthSummary {
+    let mut summary = ResourceHealthSummary {
+        total: resources.len(),
+        ..Default::default()
+    };
+    let mut last_success: Option<DateTime<Utc>> = None;
+
+    for resource in resources {
+        if resource.spec.suspend {
+            summary.suspended += 1;
+            continue;
+        }
+        if is_failing(resource) {
+            summary.failing += 1;
+        } else if is_ready(resource) {
+            summary.ready += 1;
+            let reconciled_at = resource
+                .status
+                .as_ref()
+                .and_then(|s| s.last_reconcile_time.as_deref())
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc));
+            last_success = last_success.max(reconciled_at);
+        }
+    }
+
+    summary.last_successful_reconcile_age_secs =
+        last_success.map(|t| (now - t).num_seconds().max(0));
+    summary
+}
+
+/// Summarize the current watch cache, or `None` if the watch hasn't started yet
+pub async fn summarize_store(store: &SharedResourceStore) -> Option<ResourceHealthSummary> {
+    let store = store.read().await;
+    store
+        .as_ref()
+        .map(|store| summarize(&store.state(), Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::{Condition, SecretManagerConfigStatus};
+
+    fn resource(
+        name: &str,
+        suspend: bool,
+        status: Option<SecretManagerConfigStatus>,
+    ) -> Arc<SecretManagerConfig> {
+        let yaml = format!(
+            r#"
+apiVersion: secret-management.octopilot.io/v1beta1
+kind: SecretManagerConfig
+metadata:
+  name: {name}
+  namespace: default
+spec:
+  sourceRef:
+    name: repo
+    namespace: octopilot-system
+  provider:
+    gcp:
+      projectId: p
+      location: us-central1
+  secrets:
+    environment: dev
+  suspend: {suspend}
+"#
+        );
+        let mut config: SecretManagerConfig =
+            serde_yaml::from_str(&yaml).unwrap_or_else(|e| panic!("invalid fixture: {e}"));
+        config.status = status;
+        Arc::new(config)
+    }
+
+    fn status(phase: &str, last_reconcile_time: Option<&str>) -> SecretManagerConfigStatus {
+        SecretManagerConfigStatus {
+            phase: Some(phase.to_string()),
+            last_reconcile_time: last_reconcile_time.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let now = Utc::now();
+        let recent = (now - chrono::Duration::seconds(30)).to_rfc3339();
+        let older = (now - chrono::Duration::seconds(300)).to_rfc3339();
+
+        let mut not_ready = status("Updating", None);
+        not_ready.conditions = vec![Condition {
+            r#type: "Ready".to_string(),
+            status: "False".to_string(),
+            observed_generation: None,
+            last_transition_time: None,
+            reason: None,
+            message: None,
+        }];
+
+        let resources = vec![
+            resource("a", false, Some(status("Ready", Some(&older)))),
+            resource("b", false, Some(status("Ready", Some(&recent)))),
+            resource("c", false, Some(status("Failed", Some(&recent)))),
+            resource("d", false, Some(not_ready)),
+            resource("e", true, Some(status("Failed", None))),
+            resource("f", false, None),
+        ];
+
+        let summary = summarize(&resources, now);
+        assert_eq!(summary.total, 6);
+        assert_eq!(summary.ready, 2);
+        assert_eq!(summary.failing, 2);
+        assert_eq!(summary.suspended, 1);
+        assert_eq!(summary.last_successful_reconcile_age_secs, Some(30));
+        assert!(!summary.is_healthy());
+    }
+
+    #[test]
+    fn test_summarize_empty() {
+        let summary = summarize(&[], Utc::now());
+        assert_eq!(summary, ResourceHealthSummary::default());
+        assert!(summary.is_healthy());
+    }
+}
@@ -44,6 +44,7 @@ mod tests {
     mod parse_kustomize_output_tests {
         use super::*;
         use crate::controller::kustomize::parse::parse_secrets_from_yaml;
+        use crate::secret::SecretString;
         use base64::{Engine as _, engine::general_purpose};
 
         #[test]
@@ -67,7 +68,7 @@ mod tests {
 
             assert_eq!(
                 result.get("database-url"),
-                Some(&"postgres://localhost:5432/mydb".to_string())
+                Some(&SecretString::from("postgres://localhost:5432/mydb"))
             );
         }
 
@@ -105,8 +106,8 @@ mod tests {
 
             let result = parse_secrets_from_yaml(&yaml_output);
 
-            assert_eq!(result.get("key1"), Some(&"value1".to_string()));
-            assert_eq!(result.get("key2"), Some(&"value2".to_string()));
+            assert_eq!(result.get("key1"), Some(&SecretString::from("value1")));
+            assert_eq!(result.get("key2"), Some(&SecretString::from("value2")));
         }
 
         #[test]
@@ -166,7 +167,7 @@ data:
             let result = parse_secrets_from_yaml(&yaml);
 
             // Should still parse even without --- separator
-            assert_eq!(result.get("key"), Some(&"value".to_string()));
+            assert_eq!(result.get("key"), Some(&SecretString::from("value")));
         }
 
         #[test]
@@ -235,7 +236,7 @@ data:
             let result = parse_secrets_from_yaml(&yaml_output);
 
             // Last value should win
-            assert_eq!(result.get("key"), Some(&"value2".to_string()));
+            assert_eq!(result.get("key"), Some(&SecretString::from("value2")));
         }
 
         #[test]
@@ -284,7 +285,7 @@ data:
             let result = parse_secrets_from_yaml(&yaml_output);
 
             // Should only extract from Secret, not ConfigMap
-            assert_eq!(result.get("key"), Some(&"value".to_string()));
+            assert_eq!(result.get("key"), Some(&SecretString::from("value")));
             assert!(!result.contains_key("config-key"));
         }
 
@@ -304,7 +305,7 @@ data:
 
             let result = parse_secrets_from_yaml(&yaml_output);
 
-            assert_eq!(result.get("key"), Some(&"value".to_string()));
+            assert_eq!(result.get("key"), Some(&SecretString::from("value")));
         }
     }
 }
@@ -2,12 +2,13 @@
 //!
 //! Parses YAML output from kustomize build to extract secrets and properties.
 
+use crate::secret::SecretString;
 use k8s_openapi::api::core::v1::{ConfigMap, Secret};
 use std::collections::HashMap;
 use tracing::{debug, warn};
 
 /// Parse kustomize build output and extract secrets from Secret resources
-pub fn parse_secrets_from_yaml(yaml_output: &str) -> HashMap<String, String> {
+pub fn parse_secrets_from_yaml(yaml_output: &str) -> HashMap<String, SecretString> {
     let mut all_secrets = HashMap::new();
 
     // Split YAML stream by --- separator
@@ -29,7 +30,8 @@ pub fn parse_secrets_from_yaml(yaml_output: &str) -> HashMap<String, String> {
                         match general_purpose::STANDARD.decode(&value.0) {
                             Ok(decoded) => match String::from_utf8(decoded) {
                                 Ok(secret_value) => {
-                                    all_secrets.insert(key.clone(), secret_value);
+                                    all_secrets
+                                        .insert(key.clone(), SecretString::new(secret_value));
                                 }
                                 Err(e) => {
                                     warn!(
@@ -3,6 +3,7 @@
 //! Extracts secrets from Kubernetes Secret resources in kustomize output.
 
 use crate::observability::metrics;
+use crate::secret::SecretString;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::path::Path;
@@ -20,12 +21,12 @@ use super::parse::parse_secrets_from_yaml;
 pub fn extract_secrets_from_kustomize(
     artifact_path: &Path,
     kustomize_path: &str,
-) -> Result<HashMap<String, String>> {
+) -> Result<HashMap<String, SecretString>> {
     let span = info_span!("kustomize.build", kustomize.path = kustomize_path);
     let span_clone = span.clone();
     let start = Instant::now();
 
-    let result = (|| -> Result<HashMap<String, String>> {
+    let result = (|| -> Result<HashMap<String, SecretString>> {
         let yaml_output = run_kustomize_build(artifact_path, kustomize_path)?;
 
         debug!("Kustomize build succeeded, parsing output...");
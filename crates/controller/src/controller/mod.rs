@@ -4,13 +4,20 @@
 //!
 //! - `backoff`: Fibonacci backoff mechanism for retries
 //! - `crdgen`: CRD generation utility
+//! - `health`: Aggregate resource sync health for readiness reporting
 //! - `kustomize`: Kustomize build functionality
 //! - `parser`: Configuration file parsing (application.secrets.env, application.properties)
+//! - `profiling`: CPU and heap profiling endpoints (`profiling` feature)
 //! - `reconciler`: Core reconciliation logic
+//! - `resources_api`: Token-protected JSON API exposing per-resource reconcile state
 //! - `server`: HTTP server for metrics and health checks
 
 pub mod backoff;
+pub mod health;
 pub mod kustomize;
 pub mod parser;
+#[cfg(feature = "profiling")]
+pub mod profiling;
 pub mod reconciler;
+pub mod resources_api;
 pub mod server;
@@ -4,7 +4,8 @@
 
 use crate::controller::parser::types::ApplicationFiles;
 use anyhow::Result;
-use std::path::Path;
+use regex::Regex;
+use std::path::{Path, PathBuf};
 use tracing::warn;
 use walkdir::WalkDir;
 
@@ -29,6 +30,10 @@ fn normalize_base_path_impl(base_path: Option<&str>) -> Option<&str> {
 ///
 /// Only processes the specified environment name - does not scan all environments
 ///
+/// With `merge_base_profile`, files in `deployment-configuration/` itself are attached as base
+/// layers of the profile (see [`ApplicationFiles::base_layers`]), and a profile without its
+/// own directory is still found when base files exist
+///
 /// If `base_path` is None, searches from repository root
 #[allow(
     clippy::unused_async,
@@ -40,6 +45,7 @@ pub async fn find_application_files(
     base_path: Option<&str>,
     environment: &str,
     default_service_name: Option<&str>,
+    merge_base_profile: bool,
 ) -> Result<Vec<ApplicationFiles>> {
     // Normalize base path - handle "." and "" as root
     let normalized_base = normalize_base_path_impl(base_path);
@@ -98,52 +104,52 @@ pub async fn find_application_files(
                 default_service_name.map_or("unknown".to_string(), ToString::to_string)
             };
 
+            // Base files shared by all profiles (secrets.mergeBaseProfile)
+            let base_layers = if merge_base_profile {
+                find_base_layers(&service_name, path, environment)?
+            } else {
+                Vec::new()
+            };
+
             // Look for profiles directory first (Skaffold-compliant structure)
+            // New structure: deployment-configuration/profiles/{env}/
+            // Backward compatibility: deployment-configuration/{env}/ (without profiles)
             let profiles_path = path.join("profiles");
-            if profiles_path.exists() && profiles_path.is_dir() {
-                // New structure: deployment-configuration/profiles/{env}/
-                // Only process the specified environment
-                let env_path = profiles_path.join(environment);
-                if env_path.exists() && env_path.is_dir() {
-                    let app_files = find_files_in_directory(&service_name, &env_path)?;
-                    if app_files.has_any_files() {
-                        application_files.push(app_files);
-                    } else {
-                        warn!(
-                            "No application files found in environment '{}' at {}",
-                            environment,
-                            env_path.display()
-                        );
-                    }
-                } else {
-                    warn!(
-                        "Environment '{}' not found in profiles directory: {}",
-                        environment,
-                        profiles_path.display()
-                    );
-                }
+            let env_root = if profiles_path.exists() && profiles_path.is_dir() {
+                profiles_path
             } else {
-                // Backward compatibility: deployment-configuration/{env}/ (without profiles)
-                // Only process the specified environment
-                let env_path = path.join(environment);
-                if env_path.exists() && env_path.is_dir() {
-                    let app_files = find_files_in_directory(&service_name, &env_path)?;
-                    if app_files.has_any_files() {
-                        application_files.push(app_files);
-                    } else {
-                        warn!(
-                            "No application files found in environment '{}' at {}",
-                            environment,
-                            env_path.display()
-                        );
-                    }
+                path.to_path_buf()
+            };
+            // Only process the specified environment
+            let env_path = env_root.join(environment);
+            if env_path.exists() && env_path.is_dir() {
+                let mut app_files = find_files_in_directory(&service_name, &env_path, None)?;
+                app_files.base_layers = base_layers;
+                if app_files.has_any_files() {
+                    application_files.push(app_files);
                 } else {
                     warn!(
-                        "Environment '{}' not found in deployment-configuration directory: {}",
+                        "No application files found in environment '{}' at {}",
                         environment,
-                        path.display()
+                        env_path.display()
                     );
                 }
+            } else if !base_layers.is_empty() {
+                // Profile without its own directory: base files only
+                application_files.push(ApplicationFiles {
+                    service_name: service_name.clone(),
+                    base_path: path.to_path_buf(),
+                    secrets_env: None,
+                    secrets_yaml: None,
+                    properties: None,
+                    base_layers,
+                });
+            } else {
+                warn!(
+                    "Environment '{}' not found in {}",
+                    environment,
+                    env_root.display()
+                );
             }
         }
     }
@@ -151,13 +157,50 @@ pub async fn find_application_files(
     Ok(application_files)
 }
 
-fn find_files_in_directory(service_name: &str, dir: &Path) -> Result<ApplicationFiles> {
+/// Base layers of a profile in `deployment-configuration/`, lowest precedence first:
+/// the shared base files, then the profile's `application.secrets.{env}.*` overlay
+fn find_base_layers(
+    service_name: &str,
+    config_dir: &Path,
+    environment: &str,
+) -> Result<Vec<ApplicationFiles>> {
+    let layers = [
+        find_files_in_directory(service_name, config_dir, None)?,
+        find_files_in_directory(service_name, config_dir, Some(environment))?,
+    ];
+    Ok(layers
+        .into_iter()
+        .filter(ApplicationFiles::has_any_files)
+        .collect())
+}
+
+/// Application files in `dir`; with `profile`, the Spring-style profile overlays
+/// (`application.secrets.{profile}.env`, `application.secrets.{profile}.yaml`,
+/// `application-{profile}.properties`) instead of the plain file names
+fn find_files_in_directory(
+    service_name: &str,
+    dir: &Path,
+    profile: Option<&str>,
+) -> Result<ApplicationFiles> {
     let mut app_files = ApplicationFiles {
         service_name: service_name.to_string(),
         base_path: dir.to_path_buf(),
         secrets_env: None,
         secrets_yaml: None,
         properties: None,
+        base_layers: Vec::new(),
+    };
+    let (secrets_env, secrets_yaml, properties) = match profile {
+        Some(profile) => (
+            format!("application.secrets.{profile}.env"),
+            format!("application.secrets.{profile}.yaml"),
+            format!("application-{profile}.properties"),
+        ),
+        None => (
+            "application.secrets.env".to_string(),
+            "application.secrets.yaml".to_string(),
+            "application.properties".to_string(),
+        ),
     };
 
     // Look for application files
@@ -165,21 +208,136 @@ fn find_files_in_directory(service_name: &str, dir: &Path) -> Result<Application
         let entry = entry?;
         let path = entry.path();
 
+        if !path.is_file() {
+            continue;
+        }
         if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-            match file_name {
-                "application.secrets.env" => {
-                    app_files.secrets_env = Some(path);
-                }
-                "application.secrets.yaml" => {
-                    app_files.secrets_yaml = Some(path);
-                }
-                "application.properties" => {
-                    app_files.properties = Some(path);
-                }
-                _ => {}
+            if file_name == secrets_env {
+                app_files.secrets_env = Some(path);
+            } else if file_name == secrets_yaml {
+                app_files.secrets_yaml = Some(path);
+            } else if file_name == properties {
+                app_files.properties = Some(path);
             }
         }
     }
 
     Ok(app_files)
 }
+
+/// Find binary files in a profile directory matching any of the given globs
+/// Globs are matched against the file name only and support `*` and `?` wildcards
+/// Returned paths are sorted for deterministic processing order
+#[allow(
+    clippy::missing_errors_doc,
+    reason = "Error documentation is provided in doc comments"
+)]
+pub fn find_binary_files(dir: &Path, patterns: &[String]) -> Result<Vec<PathBuf>> {
+    if patterns.is_empty() || !dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let matchers = patterns
+        .iter()
+        .map(|pattern| glob_to_regex(pattern))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
+            if matchers.iter().any(|m| m.is_match(file_name)) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+/// Convert a file name glob (`*`, `?`) into an anchored regex
+pub(crate) fn glob_to_regex(pattern: &str) -> Result<Regex> {
+    let escaped = regex::escape(pattern)
+        .replace(r"\*", ".*")
+        .replace(r"\?", ".");
+    Regex::new(&format!("^{escaped}$"))
+        .map_err(|e| anyhow::anyhow!("Invalid glob pattern '{pattern}': {e}"))
+}
+
+/// Discover available environment/profile names under the search path
+/// Collects directory names under `deployment-configuration/profiles/` (or directly under
+/// `deployment-configuration/` for the legacy layout). Returned names are sorted and deduplicated
+#[must_use]
+pub fn discover_environments(artifact_path: &Path, base_path: Option<&str>) -> Vec<String> {
+    let search_path = match normalize_base_path_impl(base_path) {
+        None => artifact_path.to_path_buf(),
+        Some(path) => artifact_path.join(path),
+    };
+
+    let mut environments = Vec::new();
+    for entry in WalkDir::new(&search_path)
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if path.file_name().and_then(|n| n.to_str()) != Some("deployment-configuration") {
+            continue;
+        }
+        let profiles_path = path.join("profiles");
+        let env_root = if profiles_path.is_dir() {
+            profiles_path
+        } else {
+            path.to_path_buf()
+        };
+        let Ok(entries) = std::fs::read_dir(&env_root) else {
+            continue;
+        };
+        for env_entry in entries.filter_map(Result::ok) {
+            if env_entry.path().is_dir() {
+                if let Some(name) = env_entry.file_name().to_str() {
+                    environments.push(name.to_string());
+                }
+            }
+        }
+    }
+    environments.sort();
+    environments.dedup();
+    environments
+}
+
+/// Expand environment patterns against the discovered environments
+/// Literal names are kept as-is (so a missing profile is still reported downstream),
+/// globs (`*`, `?`) expand to every matching discovered environment
+#[allow(
+    clippy::missing_errors_doc,
+    reason = "Error documentation is provided in doc comments"
+)]
+pub fn expand_environment_patterns(
+    patterns: &[String],
+    available: &[String],
+) -> Result<Vec<String>> {
+    let mut expanded = Vec::new();
+    for pattern in patterns {
+        if pattern.contains(['*', '?']) {
+            let matcher = glob_to_regex(pattern)?;
+            let matches: Vec<_> = available
+                .iter()
+                .filter(|env| matcher.is_match(env))
+                .cloned()
+                .collect();
+            if matches.is_empty() {
+                warn!("Environment pattern '{}' matched no profiles", pattern);
+            }
+            expanded.extend(matches);
+        } else {
+            expanded.push(pattern.clone());
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    expanded.retain(|env| seen.insert(env.clone()));
+    Ok(expanded)
+}
@@ -5,12 +5,14 @@
 //! ## Supported File Formats
 //!
 //! - **`.env` files**: Key-value pairs in `KEY=value` format
-//! - **`.yaml` files**: YAML format with nested structures (flattened)
+//! - **`.yaml` files**: YAML format with nested structures (flattened); each document of a
+//!   multi-document file can carry an `x-target` header selecting its provider and group
 //! - **`.properties` files**: Java properties format
 //!
 //! ## Features
 //!
 //! - **SOPS decryption**: Automatically detects and decrypts SOPS-encrypted files
+//! - **Placeholders**: Optional `${OTHER_KEY}` resolution in secret values
 //! - **Multi-environment support**: Processes specific environment directories
 //! - **Flexible project structures**: Supports monolith and single-service layouts
 //! - **Skaffold compliance**: Works with `profiles/` directory structure
@@ -25,15 +27,19 @@
 
 pub mod file_finder;
 pub mod parsers;
+pub mod placeholders;
 pub mod sops;
 pub mod types;
 
 // Re-export public API
-pub use file_finder::find_application_files;
+pub use file_finder::{
+    discover_environments, expand_environment_patterns, find_application_files, find_binary_files,
+};
 pub use parsers::{
-    ParseSecretsError, ParsedSecrets, SecretEntry, parse_properties, parse_secrets,
-    parse_secrets_with_state,
+    DocumentTarget, ParseSecretsError, ParsedSecrets, SecretEntry, parse_properties, parse_secrets,
+    parse_secrets_with_credentials, parse_secrets_with_state,
 };
+pub use placeholders::{PlaceholderError, resolve_placeholders};
 pub use types::ApplicationFiles;
 
 // Re-export for backward compatibility (used by tests)
@@ -157,7 +163,7 @@ data:
             fs::write(profile_dir.join("application.properties"), "prop=val")
                 .expect("Failed to write properties file in test");
 
-            let files = find_application_files(base, Some("microservices"), "dev", None)
+            let files = find_application_files(base, Some("microservices"), "dev", None, false)
                 .await
                 .expect("find_application_files should succeed in test");
 
@@ -182,7 +188,7 @@ data:
             fs::write(profile_dir.join("application.secrets.env"), "KEY=value")
                 .expect("Failed to write secrets.env file in test");
 
-            let files = find_application_files(base, None, "dev", Some("my-service"))
+            let files = find_application_files(base, None, "dev", Some("my-service"), false)
                 .await
                 .expect("find_application_files should succeed in test");
 
@@ -203,7 +209,7 @@ data:
             fs::write(env_dir.join("application.secrets.env"), "KEY=value")
                 .expect("Failed to write secrets.env file in test");
 
-            let files = find_application_files(base, None, "dev", Some("my-service"))
+            let files = find_application_files(base, None, "dev", Some("my-service"), false)
                 .await
                 .expect("find_application_files should succeed in test");
 
@@ -212,10 +218,73 @@ data:
         }
     }
 
+    mod merge_base_profile_tests {
+        use super::super::file_finder::find_application_files;
+        use super::super::parsers::{parse_properties, parse_secrets};
+        use super::{TempDir, fs};
+        use crate::secret::SecretString;
+
+        #[tokio::test]
+        async fn test_profile_overrides_base_files() {
+            let temp_dir = TempDir::new().expect("Failed to create temporary directory in test");
+            let config_dir = temp_dir.path().join("deployment-configuration");
+            let dev_dir = config_dir.join("profiles").join("dev");
+            fs::create_dir_all(&dev_dir).expect("Failed to create profile directory in test");
+            fs::create_dir_all(config_dir.join("profiles").join("prod"))
+                .expect("Failed to create profile directory in test");
+
+            fs::write(
+                config_dir.join("application.secrets.env"),
+                "SHARED=base\nOVERRIDDEN=base\nLAYERED=base",
+            )
+            .expect("Failed to write base secrets in test");
+            fs::write(
+                config_dir.join("application.secrets.dev.env"),
+                "LAYERED=overlay",
+            )
+            .expect("Failed to write overlay secrets in test");
+            fs::write(config_dir.join("application.properties"), "timeout=30")
+                .expect("Failed to write base properties in test");
+            fs::write(dev_dir.join("application.secrets.env"), "OVERRIDDEN=dev")
+                .expect("Failed to write profile secrets in test");
+
+            let files = find_application_files(temp_dir.path(), None, "dev", Some("svc"), true)
+                .await
+                .expect("find_application_files should succeed in test");
+            assert_eq!(files.len(), 1);
+            assert_eq!(files[0].base_layers.len(), 2);
+
+            let secrets = parse_secrets(&files[0], None)
+                .await
+                .expect("parse_secrets should succeed in test");
+            assert_eq!(secrets.get("SHARED"), Some(&SecretString::from("base")));
+            assert_eq!(secrets.get("OVERRIDDEN"), Some(&SecretString::from("dev")));
+            assert_eq!(secrets.get("LAYERED"), Some(&SecretString::from("overlay")));
+            let properties = parse_properties(&files[0])
+                .await
+                .expect("parse_properties should succeed in test");
+            assert_eq!(properties.get("timeout"), Some(&"30".to_string()));
+
+            // An empty profile directory still gets the base files
+            let files = find_application_files(temp_dir.path(), None, "prod", Some("svc"), true)
+                .await
+                .expect("find_application_files should succeed in test");
+            assert_eq!(files.len(), 1);
+            assert_eq!(files[0].base_layers.len(), 1);
+
+            // Without merging, base files are ignored
+            let files = find_application_files(temp_dir.path(), None, "dev", Some("svc"), false)
+                .await
+                .expect("find_application_files should succeed in test");
+            assert!(files[0].base_layers.is_empty());
+        }
+    }
+
     mod parse_secrets_tests {
         use super::super::parsers::parse_secrets;
         use super::super::types::ApplicationFiles;
         use super::{TempDir, fs};
+        use crate::secret::SecretString;
 
         #[tokio::test]
         async fn test_parse_secrets_env() {
@@ -230,13 +299,14 @@ data:
                 secrets_env: Some(env_file),
                 secrets_yaml: None,
                 properties: None,
+                base_layers: Vec::new(),
             };
 
             let secrets = parse_secrets(&app_files, None)
                 .await
                 .expect("parse_secrets should succeed in test");
-            assert_eq!(secrets.get("KEY1"), Some(&"value1".to_string()));
-            assert_eq!(secrets.get("KEY2"), Some(&"value2".to_string()));
+            assert_eq!(secrets.get("KEY1"), Some(&SecretString::from("value1")));
+            assert_eq!(secrets.get("KEY2"), Some(&SecretString::from("value2")));
         }
 
         #[tokio::test]
@@ -257,13 +327,82 @@ data:
                 secrets_env: None,
                 secrets_yaml: Some(yaml_file),
                 properties: None,
+                base_layers: Vec::new(),
             };
 
             let secrets = parse_secrets(&app_files, None)
                 .await
                 .expect("parse_secrets should succeed in test");
-            assert_eq!(secrets.get("nested.key1"), Some(&"value1".to_string()));
-            assert_eq!(secrets.get("nested.key2"), Some(&"value2".to_string()));
+            assert_eq!(
+                secrets.get("nested.key1"),
+                Some(&SecretString::from("value1"))
+            );
+            assert_eq!(
+                secrets.get("nested.key2"),
+                Some(&SecretString::from("value2"))
+            );
+        }
+
+        #[tokio::test]
+        async fn test_parse_multi_document_secrets_yaml() {
+            use super::super::parsers::parse_secrets_with_credentials;
+            use super::super::sops::KmsCredentials;
+
+            let temp_dir = TempDir::new().expect("Failed to create temporary directory in test");
+            let yaml_file = temp_dir.path().join("application.secrets.yaml");
+            fs::write(
+                &yaml_file,
+                r#"API_KEY: shared
+---
+x-target:
+  provider: gcp
+  group: database
+DB_USER: app
+DB_PASSWORD: secret
+---
+x-target:
+  provider: aws
+API_KEY: aws-only
+"#,
+            )
+            .expect("Failed to write secrets.yaml file in test");
+
+            let app_files = ApplicationFiles {
+                service_name: "test".to_string(),
+                base_path: temp_dir.path().to_path_buf(),
+                secrets_env: None,
+                secrets_yaml: Some(yaml_file),
+                properties: None,
+                base_layers: Vec::new(),
+            };
+
+            let parsed = parse_secrets_with_credentials(
+                &app_files,
+                None,
+                &KmsCredentials::default(),
+                Some("gcp"),
+            )
+            .await
+            .expect("parse_secrets_with_credentials should succeed in test");
+            assert_eq!(parsed.secrets.len(), 3);
+            assert_eq!(parsed.secrets["API_KEY"].value.expose_secret(), "shared");
+            assert_eq!(parsed.secrets["API_KEY"].group, None);
+            assert_eq!(
+                parsed.secrets["DB_PASSWORD"].group.as_deref(),
+                Some("database")
+            );
+            assert!(!parsed.secrets.contains_key("x-target.provider"));
+
+            let parsed = parse_secrets_with_credentials(
+                &app_files,
+                None,
+                &KmsCredentials::default(),
+                Some("aws"),
+            )
+            .await
+            .expect("parse_secrets_with_credentials should succeed in test");
+            assert_eq!(parsed.secrets.len(), 1);
+            assert_eq!(parsed.secrets["API_KEY"].value.expose_secret(), "aws-only");
         }
     }
 
@@ -286,6 +425,7 @@ data:
                 secrets_env: None,
                 secrets_yaml: None,
                 properties: Some(props_file),
+                base_layers: Vec::new(),
             };
 
             let properties = parse_properties(&app_files)
@@ -303,6 +443,7 @@ data:
                 secrets_env: None,
                 secrets_yaml: None,
                 properties: None,
+                base_layers: Vec::new(),
             };
 
             let properties = parse_properties(&app_files)
@@ -311,4 +452,87 @@ data:
             assert!(properties.is_empty());
         }
     }
+
+    mod find_binary_files_tests {
+        use super::super::file_finder::{find_binary_files, glob_to_regex};
+        use super::{TempDir, fs};
+
+        #[test]
+        fn test_glob_to_regex_wildcards() {
+            let re = glob_to_regex("*.p12").expect("glob should compile");
+            assert!(re.is_match("keystore.p12"));
+            assert!(!re.is_match("keystore.p12.bak"));
+
+            let re = glob_to_regex("tls.cr?").expect("glob should compile");
+            assert!(re.is_match("tls.crt"));
+            assert!(!re.is_match("tlsxcrt"));
+        }
+
+        #[test]
+        fn test_find_binary_files_matches_globs() {
+            let temp_dir = TempDir::new().expect("Failed to create temporary directory in test");
+            fs::write(temp_dir.path().join("keystore.p12"), [0u8, 159, 146, 150])
+                .expect("Failed to write keystore");
+            fs::write(temp_dir.path().join("tls.crt"), "cert").expect("Failed to write cert");
+            fs::write(temp_dir.path().join("application.secrets.env"), "A=b")
+                .expect("Failed to write env file");
+
+            let files =
+                find_binary_files(temp_dir.path(), &["*.p12".to_string(), "*.crt".to_string()])
+                    .expect("find_binary_files should succeed in test");
+            let names: Vec<_> = files
+                .iter()
+                .filter_map(|p| p.file_name().and_then(|n| n.to_str()))
+                .collect();
+            assert_eq!(names, vec!["keystore.p12", "tls.crt"]);
+        }
+
+        #[test]
+        fn test_find_binary_files_no_patterns() {
+            let temp_dir = TempDir::new().expect("Failed to create temporary directory in test");
+            fs::write(temp_dir.path().join("keystore.p12"), "x").expect("Failed to write file");
+            let files = find_binary_files(temp_dir.path(), &[])
+                .expect("find_binary_files should succeed in test");
+            assert!(files.is_empty());
+        }
+    }
+
+    mod environment_patterns_tests {
+        use super::super::file_finder::{discover_environments, expand_environment_patterns};
+        use super::{TempDir, fs};
+
+        #[test]
+        fn test_discover_environments_profiles_layout() {
+            let temp_dir = TempDir::new().expect("Failed to create temporary directory in test");
+            let profiles = temp_dir
+                .path()
+                .join("svc")
+                .join("deployment-configuration")
+                .join("profiles");
+            for env in ["dev", "dev-cf", "prod"] {
+                fs::create_dir_all(profiles.join(env)).expect("Failed to create profile dir");
+            }
+
+            let envs = discover_environments(temp_dir.path(), None);
+            assert_eq!(envs, vec!["dev", "dev-cf", "prod"]);
+        }
+
+        #[test]
+        fn test_expand_environment_patterns_globs_and_literals() {
+            let available = vec!["dev".to_string(), "dev-cf".to_string(), "prod".to_string()];
+            let expanded = expand_environment_patterns(
+                &["dev*".to_string(), "prod".to_string(), "dev".to_string()],
+                &available,
+            )
+            .expect("expand_environment_patterns should succeed in test");
+            assert_eq!(expanded, vec!["dev", "dev-cf", "prod"]);
+        }
+
+        #[test]
+        fn test_expand_environment_patterns_keeps_missing_literal() {
+            let expanded = expand_environment_patterns(&["qa".to_string()], &[])
+                .expect("expand_environment_patterns should succeed in test");
+            assert_eq!(expanded, vec!["qa"]);
+        }
+    }
 }
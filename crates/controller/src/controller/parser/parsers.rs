@@ -3,9 +3,13 @@
 //! Parse application configuration files (env, yaml, properties).
 
 use crate::controller::parser::sops::error::SopsDecryptionError;
-use crate::controller::parser::sops::{decrypt_sops_content, is_sops_encrypted_impl};
+use crate::controller::parser::sops::{
+    KmsCredentials, decrypt_sops_content_with_credentials, is_sops_encrypted_impl,
+};
 use crate::controller::parser::types::ApplicationFiles;
+use crate::secret::SecretString;
 use anyhow::{Context, Result};
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
@@ -15,11 +19,29 @@ use tracing::debug;
 #[derive(Debug, Clone, PartialEq)]
 pub struct SecretEntry {
     /// The secret value
-    pub value: String,
+    pub value: SecretString,
     /// Whether the secret is enabled (true) or disabled (false, i.e., commented out)
     pub enabled: bool,
+    /// Whether the value was read from a SOPS-encrypted file
+    pub encrypted: bool,
+    /// Grouped secret the value is stored in (`x-target.group` of its YAML document)
+    pub group: Option<String>,
 }
 
+/// Header of an application.secrets.yaml document (`x-target: {provider: gcp, group: db}`)
+/// Not a secret itself; removed from the document before its keys are read
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DocumentTarget {
+    /// Provider type ("gcp", "aws", "azure") whose resources read the document; others skip it
+    pub provider: Option<String>,
+    /// Store the document's keys as one JSON secret named `{prefix}-{group}-{suffix}`
+    pub group: Option<String>,
+}
+
+/// Document header key in application.secrets.yaml
+pub const DOCUMENT_TARGET_KEY: &str = "x-target";
+
 /// Collection of secrets with their enabled/disabled state
 #[derive(Debug, Clone, Default)]
 pub struct ParsedSecrets {
@@ -78,13 +100,13 @@ impl ParseSecretsError {
 pub async fn parse_secrets(
     app_files: &ApplicationFiles,
     sops_private_key: Option<&str>,
-) -> Result<HashMap<String, String>, ParseSecretsError> {
+) -> Result<HashMap<String, SecretString>, ParseSecretsError> {
     let parsed = parse_secrets_with_state(app_files, sops_private_key).await?;
     // Return only enabled secrets for backward compatibility
     Ok(parsed
         .secrets
         .into_iter()
-        .filter_map(|(k, v)| if v.enabled { Some((k, v.value)) } else { None })
+        .filter_map(|(k, v)| v.enabled.then_some((k, v.value)))
         .collect())
 }
 
@@ -102,26 +124,53 @@ pub async fn parse_secrets(
 pub async fn parse_secrets_with_state(
     app_files: &ApplicationFiles,
     sops_private_key: Option<&str>,
+) -> Result<ParsedSecrets, ParseSecretsError> {
+    parse_secrets_with_credentials(
+        app_files,
+        sops_private_key,
+        &KmsCredentials::default(),
+        None,
+    )
+    .await
+}
+
+/// Parse secrets with enabled/disabled state, passing cloud KMS credentials to SOPS
+///
+/// Same as `parse_secrets_with_state()`, but SOPS files encrypted with cloud KMS keys are
+/// decrypted using `kms_credentials` (per-resource workload identity overrides).
+/// With `provider` set, application.secrets.yaml documents targeting another provider type
+/// (`x-target.provider`) are skipped.
+#[allow(
+    clippy::missing_errors_doc,
+    reason = "Error documentation is provided in doc comments"
+)]
+pub async fn parse_secrets_with_credentials(
+    app_files: &ApplicationFiles,
+    sops_private_key: Option<&str>,
+    kms_credentials: &KmsCredentials,
+    provider: Option<&str>,
 ) -> Result<ParsedSecrets, ParseSecretsError> {
     let mut parsed = ParsedSecrets::default();
 
-    // Parse application.secrets.env
-    if let Some(ref path) = app_files.secrets_env {
-        debug!("Parsing secrets from: {}", path.display());
-        let env_secrets = parse_env_file_with_state(path, sops_private_key).await?;
-        // Merge secrets (later files override earlier ones)
-        for (key, entry) in env_secrets.secrets {
-            parsed.secrets.insert(key, entry);
+    // Base layers first, so the profile's files override them
+    for layer in app_files.layers() {
+        // Parse application.secrets.env
+        if let Some(ref path) = layer.secrets_env {
+            debug!("Parsing secrets from: {}", path.display());
+            let env_secrets =
+                parse_env_file_with_state(path, sops_private_key, kms_credentials).await?;
+            // Merge secrets (later files override earlier ones)
+            parsed.secrets.extend(env_secrets.secrets);
         }
-    }
 
-    // Parse application.secrets.yaml
-    if let Some(ref path) = app_files.secrets_yaml {
-        debug!("Parsing secrets from: {}", path.display());
-        let yaml_secrets = parse_yaml_secrets_with_state(path, sops_private_key).await?;
-        // Merge secrets (later files override earlier ones)
-        for (key, entry) in yaml_secrets.secrets {
-            parsed.secrets.insert(key, entry);
+        // Parse application.secrets.yaml
+        if let Some(ref path) = layer.secrets_yaml {
+            debug!("Parsing secrets from: {}", path.display());
+            let yaml_secrets =
+                parse_yaml_secrets_with_state(path, sops_private_key, kms_credentials, provider)
+                    .await?;
+            // Merge secrets (later files override earlier ones)
+            parsed.secrets.extend(yaml_secrets.secrets);
         }
     }
 
@@ -134,31 +183,36 @@ pub async fn parse_secrets_with_state(
     reason = "Error documentation is provided in doc comments"
 )]
 pub async fn parse_properties(app_files: &ApplicationFiles) -> Result<HashMap<String, String>> {
-    if let Some(ref path) = app_files.properties {
-        debug!("Parsing properties from: {}", path.display());
-        parse_properties_file(path).await
-    } else {
-        Ok(HashMap::new())
+    let mut properties = HashMap::new();
+    // Base layers first, so the profile's properties override them
+    for layer in app_files.layers() {
+        if let Some(ref path) = layer.properties {
+            debug!("Parsing properties from: {}", path.display());
+            properties.extend(parse_properties_file(path).await?);
+        }
     }
+    Ok(properties)
 }
 
 #[allow(dead_code, reason = "Reserved for future use")]
 pub(crate) async fn parse_env_file(
     path: &Path,
     sops_private_key: Option<&str>,
-) -> Result<HashMap<String, String>, ParseSecretsError> {
-    let parsed = parse_env_file_with_state(path, sops_private_key).await?;
+) -> Result<HashMap<String, SecretString>, ParseSecretsError> {
+    let parsed =
+        parse_env_file_with_state(path, sops_private_key, &KmsCredentials::default()).await?;
     // Return only enabled secrets for backward compatibility
     Ok(parsed
         .secrets
         .into_iter()
-        .filter_map(|(k, v)| if v.enabled { Some((k, v.value)) } else { None })
+        .filter_map(|(k, v)| v.enabled.then_some((k, v.value)))
         .collect())
 }
 
 pub(crate) async fn parse_env_file_with_state(
     path: &Path,
     sops_private_key: Option<&str>,
+    kms_credentials: &KmsCredentials,
 ) -> Result<ParsedSecrets, ParseSecretsError> {
     let content = tokio::fs::read_to_string(path)
         .await
@@ -167,13 +221,19 @@ pub(crate) async fn parse_env_file_with_state(
 
     // Check if file is SOPS-encrypted
     // SECURITY: Decrypted content exists only in memory, never written to disk
-    let content = if is_sops_encrypted_impl(&content) {
+    let encrypted = is_sops_encrypted_impl(&content);
+    let content = if encrypted {
         debug!("Detected SOPS-encrypted file: {}", path.display());
-        decrypt_sops_content(&content, Some(path), sops_private_key)
-            .await
-            .map_err(ParseSecretsError::SopsDecryption)?
+        decrypt_sops_content_with_credentials(
+            &content,
+            Some(path),
+            sops_private_key,
+            kms_credentials,
+        )
+        .await
+        .map_err(ParseSecretsError::SopsDecryption)?
     } else {
-        content
+        SecretString::new(content)
     };
 
     // Parse .env format from in-memory buffer (no disk writes)
@@ -181,7 +241,7 @@ pub(crate) async fn parse_env_file_with_state(
     // SECURITY: All parsing happens in memory, no temp files
     let mut parsed = ParsedSecrets::default();
 
-    for line in content.lines() {
+    for line in content.expose_secret().lines() {
         let line = line.trim();
 
         // Skip empty lines
@@ -201,12 +261,14 @@ pub(crate) async fn parse_env_file_with_state(
         // Parse KEY=VALUE format (works for both enabled and disabled)
         if let Some((key, value)) = line_to_parse.split_once('=') {
             let key = key.trim().to_string();
-            let value = value.trim().to_string();
+            let value = SecretString::from(value.trim());
             parsed.secrets.insert(
                 key,
                 SecretEntry {
                     value,
                     enabled: !is_disabled,
+                    encrypted,
+                    group: None,
                 },
             );
         }
@@ -219,19 +281,23 @@ pub(crate) async fn parse_env_file_with_state(
 pub(crate) async fn parse_yaml_secrets(
     path: &Path,
     sops_private_key: Option<&str>,
-) -> Result<HashMap<String, String>, ParseSecretsError> {
-    let parsed = parse_yaml_secrets_with_state(path, sops_private_key).await?;
+) -> Result<HashMap<String, SecretString>, ParseSecretsError> {
+    let parsed =
+        parse_yaml_secrets_with_state(path, sops_private_key, &KmsCredentials::default(), None)
+            .await?;
     // Return only enabled secrets for backward compatibility
     Ok(parsed
         .secrets
         .into_iter()
-        .filter_map(|(k, v)| if v.enabled { Some((k, v.value)) } else { None })
+        .filter_map(|(k, v)| v.enabled.then_some((k, v.value)))
         .collect())
 }
 
 pub(crate) async fn parse_yaml_secrets_with_state(
     path: &Path,
     sops_private_key: Option<&str>,
+    kms_credentials: &KmsCredentials,
+    provider: Option<&str>,
 ) -> Result<ParsedSecrets, ParseSecretsError> {
     let content = tokio::fs::read_to_string(path)
         .await
@@ -240,34 +306,92 @@ pub(crate) async fn parse_yaml_secrets_with_state(
 
     // Check if file is SOPS-encrypted
     // SECURITY: Decrypted content exists only in memory, never written to disk
-    let content = if is_sops_encrypted_impl(&content) {
+    let encrypted = is_sops_encrypted_impl(&content);
+    let content = if encrypted {
         debug!("Detected SOPS-encrypted file: {}", path.display());
-        decrypt_sops_content(&content, Some(path), sops_private_key)
-            .await
-            .map_err(ParseSecretsError::SopsDecryption)?
+        decrypt_sops_content_with_credentials(
+            &content,
+            Some(path),
+            sops_private_key,
+            kms_credentials,
+        )
+        .await
+        .map_err(ParseSecretsError::SopsDecryption)?
     } else {
-        content
+        SecretString::new(content)
     };
 
     // Parse YAML from in-memory buffer (no disk writes)
-    let yaml: serde_yaml::Value = serde_yaml::from_str(&content)
-        .context("Failed to parse YAML")
-        .map_err(ParseSecretsError::Io)?;
-
     // For YAML, we don't have a direct comment syntax like ENV files.
     // All YAML secrets are considered enabled for now.
     // Future enhancement: Could support a special key prefix like `_disabled_` or use null values
     let mut parsed = ParsedSecrets::default();
-    flatten_yaml_value_with_state(&yaml, String::new(), &mut parsed.secrets);
+    for (index, document) in serde_yaml::Deserializer::from_str(content.expose_secret()).enumerate()
+    {
+        let mut yaml = serde_yaml::Value::deserialize(document)
+            .context("Failed to parse YAML")
+            .map_err(ParseSecretsError::Io)?;
+        if yaml.is_null() {
+            continue;
+        }
+        let target = take_document_target(&mut yaml)
+            .with_context(|| format!("Invalid {DOCUMENT_TARGET_KEY} in document {}", index + 1))
+            .map_err(ParseSecretsError::Io)?;
+        if let (Some(wanted), Some(provider)) = (target.provider.as_deref(), provider)
+            && wanted != provider
+        {
+            debug!(
+                "Skipping document {} of {}: targets provider {wanted}",
+                index + 1,
+                path.display()
+            );
+            continue;
+        }
+
+        // Later documents override earlier ones
+        let mut secrets = HashMap::new();
+        flatten_yaml_value_with_state(&yaml, String::new(), &mut secrets);
+        for (key, mut entry) in secrets {
+            entry.encrypted = encrypted;
+            entry.group.clone_from(&target.group);
+            parsed.secrets.insert(key, entry);
+        }
+    }
 
     Ok(parsed)
 }
 
+/// Remove and parse the `x-target` header of a document (default when absent)
+pub(crate) fn take_document_target(document: &mut serde_yaml::Value) -> Result<DocumentTarget> {
+    let Some(header) = document
+        .as_mapping_mut()
+        .and_then(|map| map.remove(DOCUMENT_TARGET_KEY))
+    else {
+        return Ok(DocumentTarget::default());
+    };
+    let target: DocumentTarget = serde_yaml::from_value(header)?;
+    if let Some(provider) = &target.provider
+        && !matches!(provider.as_str(), "gcp" | "aws" | "azure")
+    {
+        return Err(anyhow::anyhow!(
+            "provider must be gcp, aws or azure, not '{provider}'"
+        ));
+    }
+    if target
+        .group
+        .as_deref()
+        .is_some_and(|group| group.trim().is_empty())
+    {
+        return Err(anyhow::anyhow!("group cannot be empty"));
+    }
+    Ok(target)
+}
+
 #[allow(dead_code, reason = "Reserved for future use")]
 pub(crate) fn flatten_yaml_value(
     value: &serde_yaml::Value,
     prefix: String,
-    result: &mut HashMap<String, String>,
+    result: &mut HashMap<String, SecretString>,
 ) {
     let mut state_result = HashMap::new();
     flatten_yaml_value_with_state(value, prefix, &mut state_result);
@@ -303,8 +427,10 @@ pub(crate) fn flatten_yaml_value_with_state(
             result.insert(
                 prefix,
                 SecretEntry {
-                    value: s.clone(),
+                    value: SecretString::from(s.as_str()),
                     enabled: true, // YAML secrets are always enabled (no comment syntax)
+                    encrypted: false,
+                    group: None,
                 },
             );
         }
@@ -312,8 +438,10 @@ pub(crate) fn flatten_yaml_value_with_state(
             result.insert(
                 prefix,
                 SecretEntry {
-                    value: n.to_string(),
+                    value: SecretString::new(n.to_string()),
                     enabled: true,
+                    encrypted: false,
+                    group: None,
                 },
             );
         }
@@ -321,8 +449,10 @@ pub(crate) fn flatten_yaml_value_with_state(
             result.insert(
                 prefix,
                 SecretEntry {
-                    value: b.to_string(),
+                    value: SecretString::new(b.to_string()),
                     enabled: true,
+                    encrypted: false,
+                    group: None,
                 },
             );
         }
@@ -330,8 +460,10 @@ pub(crate) fn flatten_yaml_value_with_state(
             result.insert(
                 prefix,
                 SecretEntry {
-                    value: String::new(),
+                    value: SecretString::default(),
                     enabled: true,
+                    encrypted: false,
+                    group: None,
                 },
             );
         }
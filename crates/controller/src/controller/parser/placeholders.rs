@@ -0,0 +1,198 @@
+//! # Placeholders
+//!
+//! Resolves `${OTHER_KEY}` placeholders in secret values (`secrets.resolvePlaceholders`).
+//!
+//! Composite values such as connection strings can then be assembled from individual keys
+//! instead of repeating them in Git:
+//!
+//! ```text
+//! DB_USER=app
+//! DB_PASSWORD=secret
+//! DATABASE_URL=postgres://${DB_USER}:${DB_PASSWORD}@${DB_HOST:localhost}/app
+//! ```
+//!
+//! - `${KEY}` is replaced by the resolved value of the enabled secret `KEY`
+//! - `${KEY:default}` falls back to `default` when there is no such secret
+//! - `\${KEY}` is kept as the literal text `${KEY}`
+//!
+//! References are resolved recursively; a reference cycle, an unknown key without default,
+//! or an unterminated `${` fails the whole file set.
+
+use super::parsers::SecretEntry;
+use crate::secret::SecretString;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Reason placeholders in a secret value could not be resolved
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PlaceholderError {
+    #[error("'{key}' references '{reference}', which is not an enabled secret and has no default")]
+    Unresolved { key: String, reference: String },
+    #[error("placeholder cycle: {}", chain.join(" -> "))]
+    Cycle { chain: Vec<String> },
+    #[error("'{key}' has a '${{' without a closing '}}'")]
+    Unterminated { key: String },
+}
+
+/// Resolve placeholders in the values of all enabled secrets, in place
+/// Disabled secrets are neither resolved nor available as references.
+pub fn resolve_placeholders(
+    secrets: &mut HashMap<String, SecretEntry>,
+) -> Result<(), PlaceholderError> {
+    let raw: HashMap<String, SecretString> = secrets
+        .iter()
+        .filter(|(_, entry)| entry.enabled)
+        .map(|(key, entry)| (key.clone(), entry.value.clone()))
+        .collect();
+    let mut resolver = Resolver {
+        raw: &raw,
+        resolved: HashMap::new(),
+        stack: Vec::new(),
+    };
+    for key in raw.keys() {
+        resolver.resolve(key)?;
+    }
+    for (key, value) in resolver.resolved {
+        if let Some(entry) = secrets.get_mut(&key) {
+            entry.value = SecretString::new(value);
+        }
+    }
+    Ok(())
+}
+
+struct Resolver<'a> {
+    raw: &'a HashMap<String, SecretString>,
+    resolved: HashMap<String, String>,
+    /// Keys being resolved, for cycle detection
+    stack: Vec<String>,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self, key: &str) -> Result<String, PlaceholderError> {
+        if let Some(value) = self.resolved.get(key) {
+            return Ok(value.clone());
+        }
+        if let Some(start) = self.stack.iter().position(|k| k == key) {
+            let mut chain = self.stack[start..].to_vec();
+            chain.push(key.to_string());
+            return Err(PlaceholderError::Cycle { chain });
+        }
+        let raw = self.raw.get(key).cloned().unwrap_or_default();
+        self.stack.push(key.to_string());
+        let value = self.expand(key, raw.expose_secret())?;
+        self.stack.pop();
+        self.resolved.insert(key.to_string(), value.clone());
+        Ok(value)
+    }
+
+    fn expand(&mut self, key: &str, value: &str) -> Result<String, PlaceholderError> {
+        let mut expanded = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            // Escaped: keep `${` and continue after it
+            if let Some(before) = rest[..start].strip_suffix('\\') {
+                expanded.push_str(before);
+                expanded.push_str("${");
+                rest = &rest[start + 2..];
+                continue;
+            }
+            expanded.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after
+                .find('}')
+                .ok_or_else(|| PlaceholderError::Unterminated {
+                    key: key.to_string(),
+                })?;
+            let (reference, default) = match after[..end].split_once(':') {
+                Some((reference, default)) => (reference, Some(default)),
+                None => (&after[..end], None),
+            };
+            if self.raw.contains_key(reference) {
+                expanded.push_str(&self.resolve(reference)?);
+            } else if let Some(default) = default {
+                expanded.push_str(default);
+            } else {
+                return Err(PlaceholderError::Unresolved {
+                    key: key.to_string(),
+                    reference: reference.to_string(),
+                });
+            }
+            rest = &after[end + 1..];
+        }
+        expanded.push_str(rest);
+        Ok(expanded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets(entries: &[(&str, &str, bool)]) -> HashMap<String, SecretEntry> {
+        entries
+            .iter()
+            .map(|(key, value, enabled)| {
+                (
+                    (*key).to_string(),
+                    SecretEntry {
+                        value: (*value).into(),
+                        enabled: *enabled,
+                        encrypted: false,
+                        group: None,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resolves_nested_defaults_and_escapes() {
+        let mut values = secrets(&[
+            ("DB_USER", "app", true),
+            ("DB_PASSWORD", "p@ss", true),
+            ("DB_CREDENTIALS", "${DB_USER}:${DB_PASSWORD}", true),
+            (
+                "DATABASE_URL",
+                "postgres://${DB_CREDENTIALS}@${DB_HOST:localhost}/app",
+                true,
+            ),
+            ("TEMPLATE", r"literal \${DB_USER}", true),
+            ("OLD", "${MISSING}", false),
+        ]);
+        assert_eq!(resolve_placeholders(&mut values), Ok(()));
+        assert_eq!(
+            values["DATABASE_URL"].value.expose_secret(),
+            "postgres://app:p@ss@localhost/app"
+        );
+        assert_eq!(
+            values["TEMPLATE"].value.expose_secret(),
+            "literal ${DB_USER}"
+        );
+        assert_eq!(values["OLD"].value.expose_secret(), "${MISSING}");
+    }
+
+    #[test]
+    fn test_reports_cycles_and_unresolved_references() {
+        let mut values = secrets(&[("A", "${B}", true), ("B", "x${A}", true)]);
+        assert!(matches!(
+            resolve_placeholders(&mut values),
+            Err(PlaceholderError::Cycle { chain }) if chain.len() == 3
+        ));
+
+        // Disabled secrets can't be referenced
+        let mut values = secrets(&[("A", "${B}", true), ("B", "b", false)]);
+        assert_eq!(
+            resolve_placeholders(&mut values),
+            Err(PlaceholderError::Unresolved {
+                key: "A".to_string(),
+                reference: "B".to_string()
+            })
+        );
+
+        let mut values = secrets(&[("A", "${B", true)]);
+        assert!(matches!(
+            resolve_placeholders(&mut values),
+            Err(PlaceholderError::Unterminated { .. })
+        ));
+    }
+}
@@ -6,7 +6,11 @@ use crate::controller::parser::sops::error::{
     SopsDecryptionError, SopsDecryptionFailureReason, classify_sops_error,
 };
 use crate::controller::parser::sops::gpg::import_gpg_key;
+use crate::controller::parser::sops::kms::{
+    KmsCredentials, SopsKeySource, classify_kms_error, describe_kms_credentials, detect_key_sources,
+};
 use crate::observability::metrics;
+use crate::secret::SecretString;
 use anyhow::Result;
 use std::path::Path;
 use std::process::Stdio;
@@ -33,9 +37,32 @@ pub async fn decrypt_sops_content(
     content: &str,
     file_path: Option<&Path>,
     sops_private_key: Option<&str>,
-) -> Result<String, SopsDecryptionError> {
+) -> Result<SecretString, SopsDecryptionError> {
+    decrypt_sops_content_with_credentials(
+        content,
+        file_path,
+        sops_private_key,
+        &KmsCredentials::default(),
+    )
+    .await
+}
+
+/// Decrypt SOPS-encrypted content, passing cloud KMS credentials to the sops binary
+///
+/// Files encrypted with GCP KMS, AWS KMS, or Azure Key Vault keys are decrypted using the
+/// controller's workload identity plus any per-resource overrides in `kms_credentials`.
+/// A private key is only needed for PGP-encrypted files.
+pub async fn decrypt_sops_content_with_credentials(
+    content: &str,
+    file_path: Option<&Path>,
+    sops_private_key: Option<&str>,
+    kms_credentials: &KmsCredentials,
+) -> Result<SecretString, SopsDecryptionError> {
     let content_size = content.len();
-    let encryption_method = if sops_private_key.is_some() {
+    let key_sources = detect_key_sources(content);
+    let encryption_method = if key_sources.iter().any(SopsKeySource::is_kms) {
+        "kms"
+    } else if sops_private_key.is_some() {
         "gpg"
     } else {
         "system_keyring"
@@ -52,7 +79,14 @@ pub async fn decrypt_sops_content(
     async move {
         // Use sops binary (current implementation)
         debug!("Attempting SOPS decryption using sops binary");
-        let result = decrypt_with_sops_binary(content, file_path, sops_private_key).await;
+        let result = decrypt_with_sops_binary(
+            content,
+            file_path,
+            sops_private_key,
+            &key_sources,
+            kms_credentials,
+        )
+        .await;
 
         match &result {
             Ok(_) => {
@@ -64,8 +98,8 @@ pub async fn decrypt_sops_content(
                 metrics::observe_sops_decryption_duration(start.elapsed().as_secs_f64());
             }
             Err(e) => {
-                // Classify the error
-                let reason = classify_sops_error(&e.to_string(), None);
+                // Error was classified where it was produced
+                let reason = e.reason;
                 let is_transient = reason.is_transient();
 
                 span_clone.record("decryption.method", "sops_binary");
@@ -96,11 +130,7 @@ pub async fn decrypt_sops_content(
             }
         }
 
-        // Convert anyhow::Error to SopsDecryptionError
-        result.map_err(|e| {
-            let reason = classify_sops_error(&e.to_string(), None);
-            SopsDecryptionError::new(reason, e.to_string())
-        })
+        result
     }
     .instrument(span)
     .await
@@ -120,7 +150,9 @@ async fn decrypt_with_sops_binary(
     content: &str,
     file_path: Option<&Path>,
     sops_private_key: Option<&str>,
-) -> Result<String, SopsDecryptionError> {
+    key_sources: &std::collections::BTreeSet<SopsKeySource>,
+    kms_credentials: &KmsCredentials,
+) -> Result<SecretString, SopsDecryptionError> {
     // Check if sops binary is available
     let sops_path = which::which("sops")
         .map_err(|e| {
@@ -143,6 +175,15 @@ async fn decrypt_with_sops_binary(
         }
     }
 
+    // Log cloud KMS key sources and how their credentials will be resolved
+    let uses_kms = key_sources.iter().any(SopsKeySource::is_kms);
+    if uses_kms {
+        info!(
+            "🔑 SOPS file uses KMS key sources - {}",
+            describe_kms_credentials(key_sources)
+        );
+    }
+
     // Set up GPG keyring if private key is provided
     let gpg_home = if let Some(private_key) = sops_private_key {
         info!("Importing GPG private key into temporary keyring for SOPS decryption");
@@ -166,9 +207,11 @@ async fn decrypt_with_sops_binary(
 
         gpg_result
     } else {
-        warn!(
-            "No SOPS private key provided - SOPS decryption may fail if key is not in system keyring"
-        );
+        if !uses_kms {
+            warn!(
+                "No SOPS private key provided - SOPS decryption may fail if key is not in system keyring"
+            );
+        }
         None
     };
 
@@ -198,6 +241,14 @@ async fn decrypt_with_sops_binary(
         debug!("Using temporary GPG home: {:?}", gpg_home_path);
     }
 
+    // Pass per-resource workload identity through to the cloud SDKs used by sops
+    if uses_kms {
+        for (name, value) in kms_credentials.env() {
+            cmd.env(name, value);
+            debug!("Passing {} to sops for KMS decryption", name);
+        }
+    }
+
     let mut child = cmd.spawn().map_err(|e| {
         SopsDecryptionError::new(
             SopsDecryptionFailureReason::ProviderUnavailable,
@@ -243,7 +294,7 @@ async fn decrypt_with_sops_binary(
             "✅ SOPS decryption succeeded - decrypted {} bytes",
             decrypted.len()
         );
-        Ok(decrypted)
+        Ok(SecretString::new(decrypted))
     } else {
         let error_msg = String::from_utf8_lossy(&output.stderr);
         let stdout_msg = String::from_utf8_lossy(&output.stdout);
@@ -280,7 +331,13 @@ async fn decrypt_with_sops_binary(
         }
 
         // Classify the error based on stderr content and exit code
-        let reason = classify_sops_error(&error_msg, exit_code);
+        // KMS-specific failures (missing credentials, access denied) are checked first
+        let kms_reason = if uses_kms {
+            classify_kms_error(&error_msg)
+        } else {
+            None
+        };
+        let reason = kms_reason.unwrap_or_else(|| classify_sops_error(&error_msg, exit_code));
         let safe_error = if error_msg.len() > 500 {
             format!(
                 "{}... (truncated, see logs for full error)",
@@ -290,13 +347,21 @@ async fn decrypt_with_sops_binary(
             error_msg.to_string()
         };
 
-        Err(SopsDecryptionError::new(
-            reason,
+        let message = if uses_kms {
+            format!(
+                "sops decryption failed: {} (exit code: {:?}; {})",
+                safe_error,
+                exit_code,
+                describe_kms_credentials(key_sources)
+            )
+        } else {
             format!(
                 "sops decryption failed: {} (exit code: {:?})",
                 safe_error, exit_code
-            ),
-        ))
+            )
+        };
+
+        Err(SopsDecryptionError::new(reason, message))
     }
 }
 
@@ -48,6 +48,10 @@ pub enum SopsDecryptionFailureReason {
     ProviderUnavailable,
     /// RBAC/permission denied (may be transient if RBAC is being applied)
     PermissionDenied,
+    /// No cloud credentials available to unwrap a KMS data key (permanent - configuration error)
+    KmsCredentialsMissing,
+    /// Cloud KMS denied the decrypt call (may be transient while IAM bindings propagate)
+    KmsAccessDenied,
     /// Unknown error (assume transient for safety)
     Unknown,
 }
@@ -60,10 +64,22 @@ impl SopsDecryptionFailureReason {
             SopsDecryptionFailureReason::NetworkTimeout
                 | SopsDecryptionFailureReason::ProviderUnavailable
                 | SopsDecryptionFailureReason::PermissionDenied
+                | SopsDecryptionFailureReason::KmsAccessDenied
                 | SopsDecryptionFailureReason::Unknown
         )
     }
 
+    /// Determine if this error is caused by the decryption key itself
+    /// A different key from the key ring may succeed where this one failed
+    pub fn is_key_error(&self) -> bool {
+        matches!(
+            self,
+            SopsDecryptionFailureReason::KeyNotFound
+                | SopsDecryptionFailureReason::WrongKey
+                | SopsDecryptionFailureReason::InvalidKeyFormat
+        )
+    }
+
     /// Get human-readable reason string for metrics
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -75,6 +91,8 @@ impl SopsDecryptionFailureReason {
             SopsDecryptionFailureReason::NetworkTimeout => "network_timeout",
             SopsDecryptionFailureReason::ProviderUnavailable => "provider_unavailable",
             SopsDecryptionFailureReason::PermissionDenied => "permission_denied",
+            SopsDecryptionFailureReason::KmsCredentialsMissing => "kms_credentials_missing",
+            SopsDecryptionFailureReason::KmsAccessDenied => "kms_access_denied",
             SopsDecryptionFailureReason::Unknown => "unknown",
         }
     }
@@ -106,6 +124,12 @@ impl SopsDecryptionFailureReason {
             SopsDecryptionFailureReason::PermissionDenied => {
                 "Permission denied accessing SOPS key secret. Verify RBAC is configured correctly and ServiceAccount has 'get' permission for the secret.".to_string()
             }
+            SopsDecryptionFailureReason::KmsCredentialsMissing => {
+                "No cloud credentials found to decrypt the SOPS data key with KMS. Configure workload identity for the controller (GKE Workload Identity, EKS IRSA/Pod Identity, or AKS Workload Identity) or set the provider auth on the SecretManagerConfig.".to_string()
+            }
+            SopsDecryptionFailureReason::KmsAccessDenied => {
+                "Cloud KMS denied the decrypt request. Grant the controller identity decrypt access to the key (GCP: roles/cloudkms.cryptoKeyDecrypter, AWS: kms:Decrypt, Azure: Key Vault Crypto User / unwrapKey). This may be transient while IAM changes propagate - will retry.".to_string()
+            }
             SopsDecryptionFailureReason::Unknown => {
                 "Unknown SOPS decryption error. Check controller logs for detailed error message.".to_string()
             }
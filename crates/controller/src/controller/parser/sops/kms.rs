@@ -0,0 +1,384 @@
+//! # SOPS Cloud KMS Support
+//!
+//! Detects which key sources a SOPS file was encrypted with (PGP, age, GCP KMS, AWS KMS,
+//! Azure Key Vault, HashiCorp Vault) and prepares the credentials the sops binary needs to
+//! unwrap cloud KMS data keys.
+//!
+//! The sops binary uses each cloud SDK's default credential chain, so the controller's own
+//! workload identity (GKE Workload Identity, EKS IRSA / Pod Identity, AKS Workload Identity)
+//! is inherited by the sops process. Per-resource identities from the provider `auth` config
+//! are passed through as environment overrides.
+
+use crate::controller::parser::sops::error::SopsDecryptionFailureReason;
+use crate::crd::{AwsAuthConfig, AzureAuthConfig, AzureEnvironment, ProviderConfig};
+use crate::provider::azure::AzureCloud;
+use std::collections::BTreeSet;
+
+/// Key source used to encrypt a SOPS data key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SopsKeySource {
+    Pgp,
+    Age,
+    GcpKms,
+    AwsKms,
+    AzureKeyVault,
+    HashicorpVault,
+}
+
+impl SopsKeySource {
+    /// Key used for this source in SOPS metadata
+    pub fn metadata_key(&self) -> &'static str {
+        match self {
+            SopsKeySource::Pgp => "pgp",
+            SopsKeySource::Age => "age",
+            SopsKeySource::GcpKms => "gcp_kms",
+            SopsKeySource::AwsKms => "kms",
+            SopsKeySource::AzureKeyVault => "azure_kv",
+            SopsKeySource::HashicorpVault => "hc_vault",
+        }
+    }
+
+    /// Human-readable name for logs and status messages
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SopsKeySource::Pgp => "PGP",
+            SopsKeySource::Age => "age",
+            SopsKeySource::GcpKms => "GCP KMS",
+            SopsKeySource::AwsKms => "AWS KMS",
+            SopsKeySource::AzureKeyVault => "Azure Key Vault",
+            SopsKeySource::HashicorpVault => "HashiCorp Vault",
+        }
+    }
+
+    /// Whether this source unwraps data keys through a cloud KMS instead of a private key
+    pub fn is_kms(&self) -> bool {
+        !matches!(self, SopsKeySource::Pgp | SopsKeySource::Age)
+    }
+
+    /// Environment variables that indicate explicit credentials for this source
+    fn credential_env_vars(&self) -> &'static [&'static str] {
+        match self {
+            SopsKeySource::GcpKms => &[
+                "GOOGLE_APPLICATION_CREDENTIALS",
+                "GOOGLE_OAUTH_ACCESS_TOKEN",
+            ],
+            SopsKeySource::AwsKms => &[
+                "AWS_WEB_IDENTITY_TOKEN_FILE",
+                "AWS_CONTAINER_CREDENTIALS_FULL_URI",
+                "AWS_ACCESS_KEY_ID",
+                "AWS_PROFILE",
+            ],
+            SopsKeySource::AzureKeyVault => &[
+                "AZURE_FEDERATED_TOKEN_FILE",
+                "AZURE_CLIENT_SECRET",
+                "AZURE_CLIENT_CERTIFICATE_PATH",
+                "IDENTITY_ENDPOINT",
+            ],
+            SopsKeySource::HashicorpVault => &["VAULT_TOKEN"],
+            SopsKeySource::Pgp | SopsKeySource::Age => &[],
+        }
+    }
+
+    /// Fallback credential source when no explicit credentials are configured
+    fn ambient_credentials(&self) -> Option<&'static str> {
+        match self {
+            SopsKeySource::GcpKms => Some("GKE metadata server (Workload Identity)"),
+            SopsKeySource::AwsKms => Some("EC2 instance metadata (node role)"),
+            SopsKeySource::AzureKeyVault => Some("Azure instance metadata (managed identity)"),
+            _ => None,
+        }
+    }
+}
+
+/// How credentials for a KMS key source are expected to be provided
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KmsCredentialStatus {
+    /// Explicit credentials found in the named environment variable
+    Explicit(&'static str),
+    /// No explicit credentials - relies on the named metadata service
+    Ambient(&'static str),
+    /// No credentials can be found for this source
+    Missing,
+}
+
+/// Detect how credentials for a KMS key source will be resolved
+pub fn detect_kms_credentials(source: SopsKeySource) -> KmsCredentialStatus {
+    if let Some(var) = source
+        .credential_env_vars()
+        .iter()
+        .find(|var| std::env::var(var).is_ok_and(|v| !v.is_empty()))
+    {
+        return KmsCredentialStatus::Explicit(var);
+    }
+    source
+        .ambient_credentials()
+        .map_or(KmsCredentialStatus::Missing, KmsCredentialStatus::Ambient)
+}
+
+/// Detect the key sources recorded in a SOPS file's metadata
+/// Supports YAML/JSON (`sops.<source>` lists) and dotenv (`sops_<source>__list_N__...` lines)
+pub fn detect_key_sources(content: &str) -> BTreeSet<SopsKeySource> {
+    const SOURCES: [SopsKeySource; 6] = [
+        SopsKeySource::Pgp,
+        SopsKeySource::Age,
+        SopsKeySource::GcpKms,
+        SopsKeySource::AwsKms,
+        SopsKeySource::AzureKeyVault,
+        SopsKeySource::HashicorpVault,
+    ];
+
+    let mut sources = BTreeSet::new();
+
+    let sops_meta = serde_yaml::from_str::<serde_yaml::Value>(content)
+        .ok()
+        .and_then(|yaml| yaml.get("sops").cloned());
+    if let Some(meta) = sops_meta {
+        for source in SOURCES {
+            if meta
+                .get(source.metadata_key())
+                .and_then(serde_yaml::Value::as_sequence)
+                .is_some_and(|entries| !entries.is_empty())
+            {
+                sources.insert(source);
+            }
+        }
+        return sources;
+    }
+
+    // dotenv files flatten metadata into `sops_<source>__list_<n>__map_<field>=...` lines
+    for line in content.lines() {
+        let line = line.trim();
+        for source in SOURCES {
+            if line.starts_with(&format!("sops_{}__list_", source.metadata_key())) {
+                sources.insert(source);
+            }
+        }
+    }
+    sources
+}
+
+/// Whether decrypting a file with these key sources requires a private key (PGP/age)
+/// Files with no detectable sources are assumed to need one
+pub fn requires_private_key(sources: &BTreeSet<SopsKeySource>) -> bool {
+    sources.is_empty() || !sources.iter().any(SopsKeySource::is_kms)
+}
+
+/// Credentials passed to the sops process for unwrapping cloud KMS data keys
+#[derive(Debug, Clone, Default)]
+pub struct KmsCredentials {
+    env: Vec<(String, String)>,
+}
+
+impl KmsCredentials {
+    /// Build credential overrides from a SecretManagerConfig provider's workload identity
+    ///
+    /// - AWS IRSA: `AWS_ROLE_ARN` so the pod's web identity token assumes the resource's role
+    /// - Azure Workload Identity: `AZURE_CLIENT_ID` (and `AZURE_TENANT_ID` with a tenant override) so
+    ///   the federated token maps to the resource's identity. Client certificates are not passed
+    ///   on; sops decrypts with the pod's identity
+    /// - Azure sovereign clouds: `AZURE_AUTHORITY_HOST` so tokens come from the cloud's authority
+    /// - GCP Workload Identity: the pod's service account is used as-is (no override)
+    pub fn from_provider(provider: &ProviderConfig) -> Self {
+        let mut env = Vec::new();
+        match provider {
+            ProviderConfig::Aws(aws) => {
+                if let Some(AwsAuthConfig::Irsa { role_arn }) = &aws.auth {
+                    env.push(("AWS_ROLE_ARN".to_string(), role_arn.clone()));
+                }
+                env.push(("AWS_REGION".to_string(), aws.region.clone()));
+            }
+            ProviderConfig::Azure(azure) => {
+                if let Some(AzureAuthConfig::WorkloadIdentity {
+                    client_id,
+                    tenant_id,
+                }) = &azure.auth
+                {
+                    env.push(("AZURE_CLIENT_ID".to_string(), client_id.clone()));
+                    if let Some(tenant_id) = tenant_id {
+                        env.push(("AZURE_TENANT_ID".to_string(), tenant_id.clone()));
+                    }
+                }
+                if azure.environment != AzureEnvironment::Public {
+                    let cloud = AzureCloud::for_environment(azure.environment);
+                    env.push((
+                        "AZURE_AUTHORITY_HOST".to_string(),
+                        cloud.authority_host.to_string(),
+                    ));
+                }
+            }
+            ProviderConfig::Gcp(_) => {}
+        }
+        Self { env }
+    }
+
+    /// Environment overrides to set on the sops process
+    pub fn env(&self) -> &[(String, String)] {
+        &self.env
+    }
+}
+
+/// Classify a sops failure caused by cloud KMS access
+/// Returns None if the error does not look KMS related
+pub fn classify_kms_error(error_msg: &str) -> Option<SopsDecryptionFailureReason> {
+    let error_lower = error_msg.to_lowercase();
+
+    let missing_credentials = [
+        "could not find default credentials",
+        "nocredentialproviders",
+        "no valid credential sources",
+        "failed to refresh cached credentials",
+        "defaultazurecredential",
+        "missing environment variable",
+    ];
+    if missing_credentials.iter().any(|m| error_lower.contains(m)) {
+        return Some(SopsDecryptionFailureReason::KmsCredentialsMissing);
+    }
+
+    let access_denied = [
+        "accessdeniedexception",
+        "permission 'cloudkms.cryptokeyversions.usetodecrypt' denied",
+        "permission_denied",
+        "authorizationfailed",
+        "does not have keys decrypt permission",
+        "forbidden",
+    ];
+    if access_denied.iter().any(|m| error_lower.contains(m)) {
+        return Some(SopsDecryptionFailureReason::KmsAccessDenied);
+    }
+
+    None
+}
+
+/// Describe how credentials are resolved for each KMS source, for logs and error messages
+pub fn describe_kms_credentials(sources: &BTreeSet<SopsKeySource>) -> String {
+    sources
+        .iter()
+        .filter(|source| source.is_kms())
+        .map(|source| match detect_kms_credentials(*source) {
+            KmsCredentialStatus::Explicit(var) => {
+                format!("{}: credentials from {var}", source.display_name())
+            }
+            KmsCredentialStatus::Ambient(from) => {
+                format!(
+                    "{}: no explicit credentials, using {from}",
+                    source.display_name()
+                )
+            }
+            KmsCredentialStatus::Missing => {
+                format!("{}: no credentials found", source.display_name())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_key_sources_yaml() {
+        let content = r"
+password: ENC[AES256_GCM,data:abc,iv:def,tag:ghi,type:str]
+sops:
+    gcp_kms:
+        - resource_id: projects/p/locations/global/keyRings/r/cryptoKeys/k
+    kms: []
+    pgp:
+        - fp: ABCDEF
+";
+        let sources = detect_key_sources(content);
+        assert!(sources.contains(&SopsKeySource::GcpKms));
+        assert!(sources.contains(&SopsKeySource::Pgp));
+        assert!(!sources.contains(&SopsKeySource::AwsKms));
+        assert!(!requires_private_key(&sources));
+    }
+
+    #[test]
+    fn test_detect_key_sources_dotenv() {
+        let content = "API_KEY=ENC[AES256_GCM,data:abc,iv:def,tag:ghi,type:str]\n\
+            sops_kms__list_0__map_arn=arn:aws:kms:us-east-1:123456789012:key/abc\n\
+            sops_azure_kv__list_0__map_vault_url=https://vault.vault.azure.net\n\
+            sops_version=3.8.1\n";
+        let sources = detect_key_sources(content);
+        assert_eq!(
+            sources.into_iter().collect::<Vec<_>>(),
+            vec![SopsKeySource::AwsKms, SopsKeySource::AzureKeyVault]
+        );
+    }
+
+    #[test]
+    fn test_requires_private_key() {
+        assert!(requires_private_key(&BTreeSet::new()));
+        assert!(requires_private_key(&BTreeSet::from([SopsKeySource::Pgp])));
+        assert!(!requires_private_key(&BTreeSet::from([
+            SopsKeySource::AwsKms
+        ])));
+    }
+
+    #[test]
+    fn test_classify_kms_error() {
+        assert_eq!(
+            classify_kms_error("google: could not find default credentials"),
+            Some(SopsDecryptionFailureReason::KmsCredentialsMissing)
+        );
+        assert_eq!(
+            classify_kms_error(
+                "AccessDeniedException: User is not authorized to perform kms:Decrypt"
+            ),
+            Some(SopsDecryptionFailureReason::KmsAccessDenied)
+        );
+        assert_eq!(classify_kms_error("Error unmarshalling input yaml"), None);
+    }
+
+    #[test]
+    fn test_kms_credentials_from_provider() {
+        let provider = ProviderConfig::Aws(crate::crd::AwsConfig {
+            region: "eu-west-1".to_string(),
+            auth: Some(AwsAuthConfig::Irsa {
+                role_arn: "arn:aws:iam::123456789012:role/sops".to_string(),
+            }),
+            version_stages: None,
+            endpoint_url: None,
+            use_fips: false,
+            assume_role_arn: None,
+        });
+        let credentials = KmsCredentials::from_provider(&provider);
+        assert_eq!(
+            credentials.env(),
+            &[
+                (
+                    "AWS_ROLE_ARN".to_string(),
+                    "arn:aws:iam::123456789012:role/sops".to_string()
+                ),
+                ("AWS_REGION".to_string(), "eu-west-1".to_string()),
+            ]
+        );
+
+        let provider = ProviderConfig::Azure(crate::crd::AzureConfig {
+            vault_name: "gov-vault".to_string(),
+            location: "usgovvirginia".to_string(),
+            auth: Some(AzureAuthConfig::WorkloadIdentity {
+                client_id: "00000000-0000-0000-0000-000000000000".to_string(),
+                tenant_id: None,
+            }),
+            certificates: None,
+            environment: AzureEnvironment::UsGovernment,
+            role_assignments: None,
+        });
+        let credentials = KmsCredentials::from_provider(&provider);
+        assert_eq!(
+            credentials.env(),
+            &[
+                (
+                    "AZURE_CLIENT_ID".to_string(),
+                    "00000000-0000-0000-0000-000000000000".to_string()
+                ),
+                (
+                    "AZURE_AUTHORITY_HOST".to_string(),
+                    "https://login.microsoftonline.us".to_string()
+                ),
+            ]
+        );
+    }
+}
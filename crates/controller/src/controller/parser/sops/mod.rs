@@ -7,13 +7,19 @@
 //! - `detection.rs` - SOPS encryption detection
 //! - `decrypt.rs` - Main decryption logic
 //! - `gpg.rs` - GPG key management
+//! - `kms.rs` - Cloud KMS key source detection and credentials
+//! - `rules.rs` - `.sops.yaml` creation rule recipient checks
 //! - `error.rs` - Error types and classification
 
 pub mod decrypt;
 pub mod detection;
 pub mod error;
 pub mod gpg;
+pub mod kms;
+pub mod rules;
 
 // Re-export public API
-pub use decrypt::decrypt_sops_content;
+pub use decrypt::{decrypt_sops_content, decrypt_sops_content_with_credentials};
 pub use detection::{is_sops_encrypted, is_sops_encrypted_impl};
+pub use kms::{KmsCredentials, SopsKeySource, detect_key_sources, requires_private_key};
+pub use rules::{RecipientMismatch, check_recipients};
@@ -0,0 +1,452 @@
+//! # SOPS Creation Rules
+//!
+//! Checks that SOPS files were encrypted for the recipients the repository's `.sops.yaml`
+//! expects.
+//!
+//! A file re-encrypted with a personal key, or before a key rotation reached `.sops.yaml`,
+//! still decrypts as long as one of its recipients is available to the controller, so the
+//! mistake goes unnoticed until that key is revoked. The nearest `.sops.yaml` above a file
+//! is found the way the sops binary finds it, the first creation rule whose `path_regex`
+//! matches the file's path (relative to the `.sops.yaml`) is selected, and the rule's keys
+//! (`pgp`, `age`, `kms`, `gcp_kms`, `azure_keyvault`, and `key_groups`) are compared with the
+//! recipients recorded in the file's SOPS metadata.
+//!
+//! Recipients are compared as `<source>:<key>` strings: PGP fingerprints (uppercase), age
+//! recipients, AWS KMS ARNs (without role), GCP KMS resource IDs, and Azure Key Vault keys
+//! (`<vault url>/keys/<name>`, without version). HashiCorp Vault keys are not compared.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// File name of the sops configuration
+pub const SOPS_CONFIG_FILE: &str = ".sops.yaml";
+
+/// A SOPS file whose recipients differ from its `.sops.yaml` creation rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipientMismatch {
+    pub file: PathBuf,
+    /// Recipients the file is encrypted for that the rule doesn't list
+    pub unexpected: Vec<String>,
+    /// Recipients the rule lists that the file isn't encrypted for
+    pub missing: Vec<String>,
+}
+
+impl std::fmt::Display for RecipientMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if !self.unexpected.is_empty() {
+            write!(
+                f,
+                " is encrypted for unexpected recipients [{}]",
+                self.unexpected.join(", ")
+            )?;
+        }
+        if !self.missing.is_empty() {
+            if !self.unexpected.is_empty() {
+                write!(f, " and")?;
+            }
+            write!(f, " is not encrypted for [{}]", self.missing.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SopsConfig {
+    #[serde(default)]
+    creation_rules: Vec<CreationRule>,
+}
+
+/// One entry of `creation_rules`; keys are comma-separated lists
+#[derive(Debug, Default, Deserialize)]
+struct CreationRule {
+    path_regex: Option<String>,
+    #[serde(default)]
+    pgp: String,
+    #[serde(default)]
+    age: String,
+    #[serde(default)]
+    kms: String,
+    #[serde(default)]
+    gcp_kms: String,
+    #[serde(default)]
+    azure_keyvault: String,
+    #[serde(default)]
+    key_groups: Vec<KeyGroup>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct KeyGroup {
+    #[serde(default)]
+    pgp: Vec<String>,
+    #[serde(default)]
+    age: Vec<String>,
+    #[serde(default)]
+    kms: Vec<KmsKey>,
+    #[serde(default)]
+    gcp_kms: Vec<GcpKmsKey>,
+    #[serde(default)]
+    azure_keyvault: Vec<AzureKey>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KmsKey {
+    arn: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GcpKmsKey {
+    resource_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AzureKey {
+    #[serde(rename = "vaultUrl")]
+    vault_url: String,
+    key: String,
+}
+
+impl CreationRule {
+    fn recipients(&self) -> BTreeSet<String> {
+        let list = |keys: &str| {
+            keys.split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        let mut recipients = BTreeSet::new();
+        for fingerprint in list(&self.pgp) {
+            recipients.insert(pgp_recipient(&fingerprint));
+        }
+        for recipient in list(&self.age) {
+            recipients.insert(format!("age:{recipient}"));
+        }
+        for arn in list(&self.kms) {
+            recipients.insert(kms_recipient(&arn));
+        }
+        for resource_id in list(&self.gcp_kms) {
+            recipients.insert(format!("gcp_kms:{resource_id}"));
+        }
+        for key_url in list(&self.azure_keyvault) {
+            recipients.insert(azure_recipient_from_url(&key_url));
+        }
+        for group in &self.key_groups {
+            recipients.extend(group.pgp.iter().map(|fp| pgp_recipient(fp)));
+            recipients.extend(group.age.iter().map(|r| format!("age:{}", r.trim())));
+            recipients.extend(group.kms.iter().map(|k| kms_recipient(&k.arn)));
+            recipients.extend(
+                group
+                    .gcp_kms
+                    .iter()
+                    .map(|k| format!("gcp_kms:{}", k.resource_id.trim())),
+            );
+            recipients.extend(
+                group
+                    .azure_keyvault
+                    .iter()
+                    .map(|k| azure_recipient(&k.vault_url, &k.key)),
+            );
+        }
+        recipients
+    }
+}
+
+fn pgp_recipient(fingerprint: &str) -> String {
+    format!("pgp:{}", fingerprint.replace(' ', "").to_uppercase())
+}
+
+/// `kms` keys may carry an assumed role (`<arn>+<role arn>`)
+fn kms_recipient(arn: &str) -> String {
+    format!("kms:{}", arn.split('+').next().unwrap_or(arn).trim())
+}
+
+fn azure_recipient(vault_url: &str, name: &str) -> String {
+    format!(
+        "azure_kv:{}/keys/{}",
+        vault_url.trim().trim_end_matches('/'),
+        name.trim()
+    )
+}
+
+/// `https://<vault>.vault.azure.net/keys/<name>/<version>`
+fn azure_recipient_from_url(key_url: &str) -> String {
+    match key_url.split_once("/keys/") {
+        Some((vault_url, rest)) => {
+            azure_recipient(vault_url, rest.split('/').next().unwrap_or(rest))
+        }
+        None => format!("azure_kv:{key_url}"),
+    }
+}
+
+/// Recipient for one entry of a file's SOPS metadata (`fields` of a `sops.<source>` item)
+fn metadata_recipient(source: &str, fields: &BTreeMap<String, String>) -> Option<String> {
+    match source {
+        "pgp" => fields.get("fp").map(|fp| pgp_recipient(fp)),
+        "age" => fields.get("recipient").map(|r| format!("age:{}", r.trim())),
+        "kms" => fields.get("arn").map(|arn| kms_recipient(arn)),
+        "gcp_kms" => fields
+            .get("resource_id")
+            .map(|id| format!("gcp_kms:{}", id.trim())),
+        "azure_kv" => Some(azure_recipient(
+            fields.get("vault_url")?,
+            fields.get("name")?,
+        )),
+        _ => None,
+    }
+}
+
+/// Recipients recorded in a SOPS file's metadata, including key groups
+/// Supports YAML/JSON (`sops.<source>` lists) and dotenv (`sops_<source>__list_N__map_<field>`)
+pub fn file_recipients(content: &str) -> BTreeSet<String> {
+    let mut entries: Vec<(String, BTreeMap<String, String>)> = Vec::new();
+
+    let sops_meta = serde_yaml::from_str::<serde_yaml::Value>(content)
+        .ok()
+        .and_then(|yaml| yaml.get("sops").cloned());
+    if let Some(meta) = sops_meta {
+        let groups = meta
+            .get("key_groups")
+            .and_then(serde_yaml::Value::as_sequence)
+            .cloned()
+            .unwrap_or_default();
+        for group in std::iter::once(&meta).chain(groups.iter()) {
+            let Some(group) = group.as_mapping() else {
+                continue;
+            };
+            for (source, items) in group {
+                let (Some(source), Some(items)) = (source.as_str(), items.as_sequence()) else {
+                    continue;
+                };
+                for item in items.iter().filter_map(serde_yaml::Value::as_mapping) {
+                    let fields = item
+                        .iter()
+                        .filter_map(|(k, v)| {
+                            Some((k.as_str()?.to_string(), v.as_str()?.to_string()))
+                        })
+                        .collect();
+                    entries.push((source.to_string(), fields));
+                }
+            }
+        }
+    } else {
+        // `sops_pgp__list_0__map_fp=...`, or within key groups
+        // `sops_key_groups__list_0__map_pgp__list_0__map_fp=...`
+        let mut dotenv: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+        for line in content.lines() {
+            let Some((key, value)) = line.trim().split_once('=') else {
+                continue;
+            };
+            let Some(key) = key.strip_prefix("sops_") else {
+                continue;
+            };
+            if let Some((entry, field)) = key.rsplit_once("__map_") {
+                dotenv
+                    .entry(entry.to_string())
+                    .or_default()
+                    .insert(field.to_string(), value.to_string());
+            }
+        }
+        for (entry, fields) in dotenv {
+            let Some((head, _index)) = entry.rsplit_once("__list_") else {
+                continue;
+            };
+            let source = head.rsplit("__map_").next().unwrap_or(head);
+            entries.push((source.to_string(), fields));
+        }
+    }
+
+    entries
+        .iter()
+        .filter_map(|(source, fields)| metadata_recipient(source, fields))
+        .collect()
+}
+
+/// Nearest `.sops.yaml` in the directory of `file` or above it
+pub fn find_sops_config(file: &Path) -> Option<PathBuf> {
+    file.parent()?
+        .ancestors()
+        .map(|dir| dir.join(SOPS_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Recipients the first creation rule of `config` matching `relative_path` expects
+/// `None` when no rule matches or the matching rule lists no keys
+fn expected_recipients(config: &str, relative_path: &str) -> Result<Option<BTreeSet<String>>> {
+    let config: SopsConfig =
+        serde_yaml::from_str(config).context("Failed to parse creation_rules")?;
+    for rule in config.creation_rules {
+        if let Some(pattern) = &rule.path_regex {
+            let regex =
+                Regex::new(pattern).with_context(|| format!("Invalid path_regex '{pattern}'"))?;
+            if !regex.is_match(relative_path) {
+                continue;
+            }
+        }
+        let recipients = rule.recipients();
+        return Ok((!recipients.is_empty()).then_some(recipients));
+    }
+    Ok(None)
+}
+
+/// Compare the recipients of the SOPS file `file` (with `content`) with its creation rule
+/// `None` when the file matches its rule, or there is no `.sops.yaml` or matching rule
+pub fn check_recipients(file: &Path, content: &str) -> Result<Option<RecipientMismatch>> {
+    let Some(config_path) = find_sops_config(file) else {
+        return Ok(None);
+    };
+    let config = std::fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read {}", config_path.display()))?;
+    let config_dir = config_path.parent().unwrap_or(Path::new(""));
+    let relative_path = file
+        .strip_prefix(config_dir)
+        .unwrap_or(file)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let expected = expected_recipients(&config, &relative_path)
+        .with_context(|| format!("Invalid {}", config_path.display()))?;
+    let Some(expected) = expected else {
+        return Ok(None);
+    };
+
+    let actual = file_recipients(content);
+    // Recipients that can't be compared (e.g. HashiCorp Vault only) are not reported
+    if actual.is_empty() {
+        return Ok(None);
+    }
+    let unexpected: Vec<String> = actual.difference(&expected).cloned().collect();
+    let missing: Vec<String> = expected.difference(&actual).cloned().collect();
+    if unexpected.is_empty() && missing.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(RecipientMismatch {
+        file: file.to_path_buf(),
+        unexpected,
+        missing,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOPS_CONFIG: &str = r"
+creation_rules:
+  - path_regex: prod/.*
+    pgp: 'ab12 cd34, EF56'
+    kms: 'arn:aws:kms:us-east-1:1:key/prod+arn:aws:iam::1:role/sops'
+  - path_regex: .*\.yaml$
+    key_groups:
+      - age: [age1dev]
+        azure_keyvault:
+          - vaultUrl: https://dev.vault.azure.net
+            key: sops
+            version: abc
+  - gcp_kms: projects/p/locations/global/keyRings/r/cryptoKeys/other
+";
+
+    #[test]
+    fn test_first_matching_rule_is_used() {
+        let expected = |path: &str| {
+            expected_recipients(SOPS_CONFIG, path)
+                .unwrap_or_else(|e| panic!("Failed to read rules: {e}"))
+                .map(|recipients| recipients.into_iter().collect::<Vec<_>>())
+        };
+        assert_eq!(
+            expected("prod/application.secrets.yaml"),
+            Some(vec![
+                "kms:arn:aws:kms:us-east-1:1:key/prod".to_string(),
+                "pgp:AB12CD34".to_string(),
+                "pgp:EF56".to_string(),
+            ])
+        );
+        assert_eq!(
+            expected("dev/application.secrets.yaml"),
+            Some(vec![
+                "age:age1dev".to_string(),
+                "azure_kv:https://dev.vault.azure.net/keys/sops".to_string(),
+            ])
+        );
+        assert_eq!(
+            expected("dev/application.secrets.env"),
+            Some(vec![
+                "gcp_kms:projects/p/locations/global/keyRings/r/cryptoKeys/other".to_string()
+            ])
+        );
+        assert!(expected_recipients("creation_rules: []", "a.env").is_ok_and(|r| r.is_none()));
+    }
+
+    #[test]
+    fn test_file_recipients_from_yaml_and_dotenv() {
+        let yaml = r"
+KEY: ENC[AES256_GCM,data:abc]
+sops:
+  age:
+    - recipient: age1dev
+      enc: '...'
+  key_groups:
+    - pgp:
+        - fp: ab12cd34
+      azure_kv:
+        - vault_url: https://dev.vault.azure.net/
+          name: sops
+          version: abc
+";
+        assert_eq!(
+            file_recipients(yaml).into_iter().collect::<Vec<_>>(),
+            vec![
+                "age:age1dev".to_string(),
+                "azure_kv:https://dev.vault.azure.net/keys/sops".to_string(),
+                "pgp:AB12CD34".to_string(),
+            ]
+        );
+
+        let dotenv = "KEY=ENC[AES256_GCM,data:abc]\n\
+                      sops_pgp__list_0__map_fp=EF56\n\
+                      sops_pgp__list_0__map_created_at=2024-01-01T00:00:00Z\n\
+                      sops_kms__list_0__map_arn=arn:aws:kms:us-east-1:1:key/prod\n\
+                      sops_key_groups__list_0__map_age__list_0__map_recipient=age1dev\n";
+        assert_eq!(
+            file_recipients(dotenv).into_iter().collect::<Vec<_>>(),
+            vec![
+                "age:age1dev".to_string(),
+                "kms:arn:aws:kms:us-east-1:1:key/prod".to_string(),
+                "pgp:EF56".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_recipients_against_nearest_config() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("Failed to create dir: {e}"));
+        let service = dir.path().join("prod").join("api");
+        std::fs::create_dir_all(&service).unwrap_or_else(|e| panic!("Failed to create: {e}"));
+        std::fs::write(dir.path().join(SOPS_CONFIG_FILE), SOPS_CONFIG)
+            .unwrap_or_else(|e| panic!("Failed to write config: {e}"));
+        let file = service.join("application.secrets.env");
+
+        let matching = "sops_pgp__list_0__map_fp=AB12CD34\n\
+                        sops_pgp__list_1__map_fp=EF56\n\
+                        sops_kms__list_0__map_arn=arn:aws:kms:us-east-1:1:key/prod\n";
+        assert!(check_recipients(&file, matching).is_ok_and(|m| m.is_none()));
+
+        let personal = "sops_pgp__list_0__map_fp=AB12CD34\n\
+                        sops_pgp__list_1__map_fp=FFFF\n\
+                        sops_kms__list_0__map_arn=arn:aws:kms:us-east-1:1:key/prod\n";
+        let mismatch = check_recipients(&file, personal)
+            .unwrap_or_else(|e| panic!("Failed to check: {e}"))
+            .unwrap_or_else(|| panic!("Expected a mismatch"));
+        assert_eq!(mismatch.unexpected, vec!["pgp:FFFF".to_string()]);
+        assert_eq!(mismatch.missing, vec!["pgp:EF56".to_string()]);
+        assert!(
+            mismatch
+                .to_string()
+                .ends_with("unexpected recipients [pgp:FFFF] and is not encrypted for [pgp:EF56]")
+        );
+    }
+}
@@ -12,12 +12,29 @@ pub struct ApplicationFiles {
     pub secrets_env: Option<PathBuf>,
     pub secrets_yaml: Option<PathBuf>,
     pub properties: Option<PathBuf>,
+    /// Files merged under this set's files (`secrets.mergeBaseProfile`), lowest precedence first
+    pub base_layers: Vec<ApplicationFiles>,
 }
 
 impl ApplicationFiles {
     /// Check if any application files are present
     #[must_use]
     pub fn has_any_files(&self) -> bool {
-        self.secrets_env.is_some() || self.secrets_yaml.is_some() || self.properties.is_some()
+        self.secrets_env.is_some()
+            || self.secrets_yaml.is_some()
+            || self.properties.is_some()
+            || self.base_layers.iter().any(Self::has_any_files)
+    }
+
+    /// Base layers followed by this set, in merge order (later layers override earlier ones)
+    pub fn layers(&self) -> impl Iterator<Item = &ApplicationFiles> {
+        self.base_layers.iter().chain(std::iter::once(self))
+    }
+
+    /// Secrets files of all layers, in merge order
+    pub fn secrets_files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.layers()
+            .flat_map(|layer| [&layer.secrets_env, &layer.secrets_yaml])
+            .flatten()
     }
 }
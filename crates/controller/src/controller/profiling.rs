@@ -0,0 +1,239 @@
+//! # Profiling Endpoints
+//!
+//! pprof-compatible CPU and heap profiles for diagnosing reconcile latency regressions in
+//! production, compiled in only with the `profiling` feature:
+//!
+//! - `/debug/pprof/profile?seconds=30` - CPU profile sampled for `seconds` (default 30, at
+//!   most `MAX_PROFILE_SECONDS`); `format=flamegraph` returns an SVG flamegraph instead of
+//!   the pprof protobuf
+//! - `/debug/pprof/heap` - jemalloc heap profile in pprof format
+//!
+//! Both endpoints use the same bearer token as the resources API and are disabled without
+//! one. Only one CPU profile runs at a time. Heap profiles need the jemalloc allocator that
+//! the controller binary installs when built with the feature.
+
+use crate::controller::resources_api::authorize;
+use crate::controller::server::ServerState;
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use pprof::protos::Message;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Default CPU profile duration in seconds
+pub const DEFAULT_PROFILE_SECONDS: u64 = 30;
+
+/// Longest CPU profile that can be requested, in seconds
+pub const MAX_PROFILE_SECONDS: u64 = 300;
+
+/// CPU sampling frequency in Hz (99 avoids lockstep with timers firing at round frequencies)
+const PROFILE_FREQUENCY_HZ: i32 = 99;
+
+/// Set while a CPU profile is running
+static CPU_PROFILE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Output format of `/debug/pprof/profile`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ProfileFormat {
+    /// Uncompressed pprof protobuf, readable by `go tool pprof`
+    #[default]
+    Pprof,
+    /// SVG flamegraph
+    Flamegraph,
+}
+
+/// Query parameters for `/debug/pprof/profile`
+#[derive(Debug, Default, Deserialize)]
+struct ProfileQuery {
+    seconds: Option<u64>,
+    #[serde(default)]
+    format: ProfileFormat,
+}
+
+/// Requested profile duration, defaulted and clamped to `1..=MAX_PROFILE_SECONDS`
+fn profile_duration(seconds: Option<u64>) -> Duration {
+    Duration::from_secs(
+        seconds
+            .unwrap_or(DEFAULT_PROFILE_SECONDS)
+            .clamp(1, MAX_PROFILE_SECONDS),
+    )
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(serde_json::json!({ "error": message.into() }))).into_response()
+}
+
+/// Clears `CPU_PROFILE_RUNNING` when the profile finishes or the request is dropped
+struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        CPU_PROFILE_RUNNING.store(false, Ordering::Release);
+    }
+}
+
+/// Sample the whole process for `duration` and encode the report
+fn capture_cpu_profile(duration: Duration, format: ProfileFormat) -> anyhow::Result<Vec<u8>> {
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(PROFILE_FREQUENCY_HZ)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()?;
+    std::thread::sleep(duration);
+    let report = guard.report().build()?;
+
+    let mut body = Vec::new();
+    match format {
+        ProfileFormat::Pprof => report.pprof()?.encode(&mut body)?,
+        ProfileFormat::Flamegraph => report.flamegraph(&mut body)?,
+    }
+    Ok(body)
+}
+
+async fn cpu_profile_handler(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<ProfileQuery>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(response) = authorize(state.api_token.as_deref(), &headers) {
+        return response;
+    }
+    if CPU_PROFILE_RUNNING.swap(true, Ordering::AcqRel) {
+        return error_response(
+            StatusCode::CONFLICT,
+            "a CPU profile is already running, try again when it finishes",
+        );
+    }
+    let running = RunningGuard;
+
+    let duration = profile_duration(query.seconds);
+    info!(
+        "Capturing {}s CPU profile ({:?})",
+        duration.as_secs(),
+        query.format
+    );
+    let result =
+        tokio::task::spawn_blocking(move || capture_cpu_profile(duration, query.format)).await;
+    drop(running);
+
+    match result {
+        Ok(Ok(body)) => match query.format {
+            ProfileFormat::Pprof => (
+                [
+                    (header::CONTENT_TYPE, "application/octet-stream"),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        "attachment; filename=\"profile.pb\"",
+                    ),
+                ],
+                body,
+            )
+                .into_response(),
+            ProfileFormat::Flamegraph => {
+                ([(header::CONTENT_TYPE, "image/svg+xml")], body).into_response()
+            }
+        },
+        Ok(Err(e)) => {
+            warn!("CPU profile failed: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("CPU profile failed: {e}"),
+            )
+        }
+        Err(e) => {
+            warn!("CPU profile task failed: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("CPU profile task failed: {e}"),
+            )
+        }
+    }
+}
+
+async fn heap_profile_handler(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(response) = authorize(state.api_token.as_deref(), &headers) {
+        return response;
+    }
+    let Some(prof_ctl) = jemalloc_pprof::PROF_CTL.as_ref() else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "heap profiling unavailable: jemalloc profiling is not enabled",
+        );
+    };
+    let mut prof_ctl = prof_ctl.lock().await;
+    if !prof_ctl.activated() {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "heap profiling is not active",
+        );
+    }
+
+    info!("Capturing heap profile");
+    match prof_ctl.dump_pprof() {
+        Ok(body) => (
+            [
+                (header::CONTENT_TYPE, "application/octet-stream"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"heap.pb.gz\"",
+                ),
+            ],
+            body,
+        )
+            .into_response(),
+        Err(e) => {
+            warn!("Heap profile failed: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("heap profile failed: {e}"),
+            )
+        }
+    }
+}
+
+/// Routes for the profiling endpoints
+pub fn routes() -> Router<Arc<ServerState>> {
+    Router::new()
+        .route("/debug/pprof/profile", get(cpu_profile_handler))
+        .route("/debug/pprof/heap", get(heap_profile_handler))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_duration_is_clamped() {
+        assert_eq!(
+            profile_duration(None),
+            Duration::from_secs(DEFAULT_PROFILE_SECONDS)
+        );
+        assert_eq!(profile_duration(Some(0)), Duration::from_secs(1));
+        assert_eq!(profile_duration(Some(10)), Duration::from_secs(10));
+        assert_eq!(
+            profile_duration(Some(3_600)),
+            Duration::from_secs(MAX_PROFILE_SECONDS)
+        );
+    }
+
+    #[test]
+    fn test_profile_query_format() {
+        let query: ProfileQuery =
+            serde_json::from_value(serde_json::json!({ "format": "flamegraph" }))
+                .unwrap_or_else(|e| panic!("Failed to parse query: {e}"));
+        assert_eq!(query.format, ProfileFormat::Flamegraph);
+        assert_eq!(query.seconds, None);
+        assert_eq!(ProfileQuery::default().format, ProfileFormat::Pprof);
+    }
+}
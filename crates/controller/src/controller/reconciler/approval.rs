@@ -0,0 +1,535 @@
+//! # Approval Gate
+//!
+//! Stages detected changes for manual promotion (`spec.approvalPolicy: Manual`).
+//!
+//! Before syncing, the reconciler runs the normal sync pipeline against a
+//! `PlanningProvider`, which answers reads from the real provider but records every write
+//! instead of performing it. A non-empty plan is stored in `status.pendingChanges` together
+//! with a hash of the operations and values. Nothing is written until the
+//! `secret-management.octopilot.io/approve` annotation is set to that hash (e.g. with
+//! `msmctl apply`); if Git changes again in the meantime, the hash changes and the new
+//! plan needs its own approval.
+
+use crate::crd::{PendingChanges, PlannedOperation, SecretManagerConfig};
+use crate::provider::{
+    ConfigStoreProvider, SecretManagerProvider, SecretMetadata, SecretVersionIds,
+};
+use crate::secret::SecretString;
+use anyhow::Result;
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// Annotation approving the staged change set whose hash it holds
+pub const APPROVE_ANNOTATION: &str = "secret-management.octopilot.io/approve";
+
+/// Operation a sync would have performed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PlannedAction {
+    Create,
+    Update,
+    Delete,
+    Disable,
+    Enable,
+}
+
+impl PlannedAction {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Create => "Create",
+            Self::Update => "Update",
+            Self::Delete => "Delete",
+            Self::Disable => "Disable",
+            Self::Enable => "Enable",
+        }
+    }
+}
+
+/// Collects the operations a sync would perform
+/// Only a SHA-256 of each value is kept, so plans never hold secret material
+#[derive(Debug, Default)]
+pub struct ChangePlanner {
+    /// Value hash per planned (key, action)
+    operations: Mutex<BTreeMap<(String, PlannedAction), Option<String>>>,
+}
+
+impl ChangePlanner {
+    /// Record an operation on `key` (repeating an operation replaces its value)
+    pub fn record(&self, action: PlannedAction, key: &str, value: Option<&[u8]>) {
+        let value_hash = value.map(|value| format!("{:x}", Sha256::digest(value)));
+        self.operations
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert((key.to_string(), action), value_hash);
+    }
+
+    /// Plan a config store write instead of performing it
+    /// Returns `false` (nothing was updated) like an unchanged write would
+    pub async fn plan_config_write(
+        &self,
+        store: &dyn ConfigStoreProvider,
+        key: &str,
+        value: &str,
+    ) -> Result<bool> {
+        match store.get_config_value(key).await? {
+            None => self.record(PlannedAction::Create, key, Some(value.as_bytes())),
+            Some(current) if current != value => {
+                self.record(PlannedAction::Update, key, Some(value.as_bytes()));
+            }
+            Some(_) => {}
+        }
+        Ok(false)
+    }
+
+    /// Plan a batch of config store writes (see `ConfigStoreProvider::bulk_upsert`)
+    pub async fn plan_config_writes(
+        &self,
+        store: &dyn ConfigStoreProvider,
+        entries: Vec<(String, String)>,
+    ) -> Result<HashMap<String, bool>> {
+        let mut results = HashMap::with_capacity(entries.len());
+        for (key, value) in entries {
+            let updated = self.plan_config_write(store, &key, &value).await?;
+            results.insert(key, updated);
+        }
+        Ok(results)
+    }
+
+    /// The staged change set, or `None` when there is nothing to apply
+    #[must_use]
+    pub fn plan(&self, revision: Option<&str>) -> Option<PendingChanges> {
+        let operations = self
+            .operations
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if operations.is_empty() {
+            return None;
+        }
+
+        let mut hasher = Sha256::new();
+        let mut counts: BTreeMap<PlannedAction, usize> = BTreeMap::new();
+        for ((key, action), value_hash) in &*operations {
+            hasher.update(action.as_str());
+            hasher.update(b"\t");
+            hasher.update(key);
+            hasher.update(b"\t");
+            hasher.update(value_hash.as_deref().unwrap_or_default());
+            hasher.update(b"\n");
+            *counts.entry(*action).or_default() += 1;
+        }
+
+        let summary = counts
+            .iter()
+            .map(|(action, count)| format!("{} {}", count, action.as_str().to_lowercase()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Some(PendingChanges {
+            hash: format!("{:x}", hasher.finalize()),
+            revision: revision.map(str::to_string),
+            summary,
+            operations: operations
+                .keys()
+                .map(|(key, action)| PlannedOperation {
+                    action: action.as_str().to_string(),
+                    key: key.clone(),
+                })
+                .collect(),
+            planned_at: Some(chrono::Utc::now().to_rfc3339()),
+        })
+    }
+}
+
+/// Whether the approve annotation matches the staged change set `hash`
+#[must_use]
+pub fn is_approved(config: &SecretManagerConfig, hash: &str) -> bool {
+    config
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(APPROVE_ANNOTATION))
+        .is_some_and(|approved| approved.trim() == hash)
+}
+
+/// Whether the resource carries an approval for its currently staged changes
+#[must_use]
+pub fn has_pending_approval(config: &SecretManagerConfig) -> bool {
+    config
+        .status
+        .as_ref()
+        .and_then(|s| s.pending_changes.as_ref())
+        .is_some_and(|pending| is_approved(config, &pending.hash))
+}
+
+/// Provider that plans writes instead of performing them
+/// Reads go to the wrapped provider so the plan only contains real changes.
+pub struct PlanningProvider<'a> {
+    inner: &'a dyn SecretManagerProvider,
+    planner: ChangePlanner,
+}
+
+impl std::fmt::Debug for PlanningProvider<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlanningProvider")
+            .field("planner", &self.planner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> PlanningProvider<'a> {
+    #[must_use]
+    pub fn new(inner: &'a dyn SecretManagerProvider) -> Self {
+        Self {
+            inner,
+            planner: ChangePlanner::default(),
+        }
+    }
+
+    /// The staged change set, or `None` when there is nothing to apply
+    #[must_use]
+    pub fn plan(&self, revision: Option<&str>) -> Option<PendingChanges> {
+        self.planner.plan(revision)
+    }
+
+    fn plan_write(
+        &self,
+        secret_name: &str,
+        current: std::result::Result<Option<&[u8]>, &anyhow::Error>,
+        value: &[u8],
+    ) {
+        match current {
+            Ok(None) => self
+                .planner
+                .record(PlannedAction::Create, secret_name, Some(value)),
+            Ok(Some(current)) if current == value => {}
+            // Changed, or unreadable (e.g. disabled) - the write would create a new version
+            Ok(Some(_)) | Err(_) => {
+                self.planner
+                    .record(PlannedAction::Update, secret_name, Some(value));
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SecretManagerProvider for PlanningProvider<'_> {
+    async fn create_or_update_secret(
+        &self,
+        secret_name: &str,
+        secret_value: &SecretString,
+        _environment: &str,
+        _location: &str,
+    ) -> Result<bool> {
+        let current = self.inner.get_secret_value(secret_name).await;
+        self.plan_write(
+            secret_name,
+            current
+                .as_ref()
+                .map(|value| value.as_ref().map(|value| value.expose_secret().as_bytes())),
+            secret_value.expose_secret().as_bytes(),
+        );
+        Ok(false)
+    }
+
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
+        self.inner.get_secret_value(secret_name).await
+    }
+
+    async fn get_secret_values(
+        &self,
+        secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        self.inner.get_secret_values(secret_names).await
+    }
+
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        _environment: &str,
+        _location: &str,
+    ) -> Result<bool> {
+        let current = self.inner.get_secret_binary(secret_name).await;
+        self.plan_write(
+            secret_name,
+            current.as_ref().map(Option::as_deref),
+            secret_value,
+        );
+        Ok(false)
+    }
+
+    async fn get_secret_binary(&self, secret_name: &str) -> Result<Option<Vec<u8>>> {
+        self.inner.get_secret_binary(secret_name).await
+    }
+
+    async fn delete_secret(&self, secret_name: &str) -> Result<()> {
+        self.planner
+            .record(PlannedAction::Delete, secret_name, None);
+        Ok(())
+    }
+
+    async fn disable_secret(&self, secret_name: &str) -> Result<bool> {
+        // Disabled secrets can't be read on any provider, so a readable secret is enabled
+        if matches!(self.inner.get_secret_value(secret_name).await, Ok(Some(_))) {
+            self.planner
+                .record(PlannedAction::Disable, secret_name, None);
+        }
+        Ok(false)
+    }
+
+    async fn enable_secret(&self, secret_name: &str) -> Result<bool> {
+        // An existing but unreadable secret is disabled; missing secrets read as Ok(None)
+        if self.inner.get_secret_value(secret_name).await.is_err() {
+            self.planner
+                .record(PlannedAction::Enable, secret_name, None);
+        }
+        Ok(false)
+    }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        self.inner.version_ids(secret_name).await
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        self.inner.list_secrets(prefix).await
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        self.inner.get_secret_metadata(secret_name).await
+    }
+
+    fn rate_limiter(&self) -> Option<&crate::controller::reconciler::rate_limit::TokenBucket> {
+        self.inner.rate_limiter()
+    }
+
+    fn planner(&self) -> Option<&ChangePlanner> {
+        Some(&self.planner)
+    }
+
+    fn router(&self) -> Option<&crate::controller::reconciler::routing::KeyRouter> {
+        self.inner.router()
+    }
+
+    fn last_writer(
+        &self,
+    ) -> Option<&crate::controller::reconciler::last_writer::LastWriterTracker> {
+        self.inner.last_writer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// In-memory secret store; `None` marks a disabled secret
+    #[derive(Debug, Default)]
+    struct MemoryProvider {
+        secrets: HashMap<String, Option<String>>,
+    }
+
+    impl MemoryProvider {
+        fn with(secrets: &[(&str, Option<&str>)]) -> Self {
+            Self {
+                secrets: secrets
+                    .iter()
+                    .map(|(name, value)| ((*name).to_string(), value.map(str::to_string)))
+                    .collect(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SecretManagerProvider for MemoryProvider {
+        async fn create_or_update_secret(
+            &self,
+            _secret_name: &str,
+            _secret_value: &SecretString,
+            _environment: &str,
+            _location: &str,
+        ) -> Result<bool> {
+            panic!("planning must not write")
+        }
+
+        async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
+            match self.secrets.get(secret_name) {
+                Some(Some(value)) => Ok(Some(SecretString::from(value.as_str()))),
+                Some(None) => Err(anyhow::anyhow!("secret {secret_name} is disabled")),
+                None => Ok(None),
+            }
+        }
+
+        async fn create_or_update_secret_binary(
+            &self,
+            _secret_name: &str,
+            _secret_value: &[u8],
+            _environment: &str,
+            _location: &str,
+        ) -> Result<bool> {
+            panic!("planning must not write")
+        }
+
+        async fn delete_secret(&self, _secret_name: &str) -> Result<()> {
+            panic!("planning must not delete")
+        }
+
+        async fn disable_secret(&self, _secret_name: &str) -> Result<bool> {
+            panic!("planning must not disable")
+        }
+
+        async fn enable_secret(&self, _secret_name: &str) -> Result<bool> {
+            panic!("planning must not enable")
+        }
+
+        async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+            Ok(self
+                .secrets
+                .keys()
+                .filter(|name| name.starts_with(prefix))
+                .cloned()
+                .collect())
+        }
+
+        async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+            Ok(self
+                .secrets
+                .contains_key(secret_name)
+                .then(|| SecretMetadata {
+                    name: secret_name.to_string(),
+                    version_count: 1,
+                    ..SecretMetadata::default()
+                }))
+        }
+    }
+
+    fn plan_of(operations: &[(PlannedAction, &str, &str)]) -> PendingChanges {
+        let planner = ChangePlanner::default();
+        for (action, key, value) in operations {
+            planner.record(*action, key, Some(value.as_bytes()));
+        }
+        planner
+            .plan(Some("main@sha1:abc"))
+            .unwrap_or_else(|| panic!("plan expected"))
+    }
+
+    #[test]
+    fn test_plan_hash_is_stable_and_covers_values() {
+        let first = plan_of(&[
+            (PlannedAction::Create, "db-password", "s3cret"),
+            (PlannedAction::Update, "api-key", "k1"),
+        ]);
+        // Recording order does not matter
+        let reordered = plan_of(&[
+            (PlannedAction::Update, "api-key", "k1"),
+            (PlannedAction::Create, "db-password", "s3cret"),
+        ]);
+        assert_eq!(first.hash, reordered.hash);
+        assert_eq!(first.summary, "1 create, 1 update");
+        assert_eq!(first.operations[0].key, "api-key");
+
+        // A different value needs a new approval
+        let changed = plan_of(&[
+            (PlannedAction::Create, "db-password", "other"),
+            (PlannedAction::Update, "api-key", "k1"),
+        ]);
+        assert_ne!(first.hash, changed.hash);
+
+        assert!(ChangePlanner::default().plan(None).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_planning_provider_records_only_changes() {
+        let inner = MemoryProvider::with(&[
+            ("unchanged", Some("same")),
+            ("changed", Some("old")),
+            ("retired", Some("value")),
+            ("disabled", None),
+        ]);
+        let planning = PlanningProvider::new(&inner);
+
+        for (name, value) in [
+            ("unchanged", "same"),
+            ("changed", "new"),
+            ("created", "value"),
+        ] {
+            let updated = planning
+                .create_or_update_secret(name, &SecretString::from(value), "dev", "us-central1")
+                .await
+                .unwrap_or_else(|e| panic!("plan {name}: {e}"));
+            assert!(!updated);
+        }
+        for name in ["retired", "missing"] {
+            planning
+                .disable_secret(name)
+                .await
+                .unwrap_or_else(|e| panic!("plan disable {name}: {e}"));
+        }
+        for name in ["disabled", "changed"] {
+            planning
+                .enable_secret(name)
+                .await
+                .unwrap_or_else(|e| panic!("plan enable {name}: {e}"));
+        }
+
+        let plan = planning
+            .plan(None)
+            .unwrap_or_else(|| panic!("plan expected"));
+        let operations: Vec<(&str, &str)> = plan
+            .operations
+            .iter()
+            .map(|op| (op.action.as_str(), op.key.as_str()))
+            .collect();
+        assert_eq!(
+            operations,
+            vec![
+                ("Update", "changed"),
+                ("Create", "created"),
+                ("Enable", "disabled"),
+                ("Disable", "retired"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_approved() {
+        let mut config: SecretManagerConfig = serde_yaml::from_str(
+            r"
+apiVersion: secret-management.octopilot.io/v1beta1
+kind: SecretManagerConfig
+metadata:
+  name: app
+  namespace: default
+  annotations:
+    secret-management.octopilot.io/approve: abc123
+spec:
+  sourceRef:
+    name: repo
+    namespace: flux-system
+  provider:
+    gcp:
+      projectId: p
+      location: us-central1
+  secrets:
+    environment: dev
+",
+        )
+        .unwrap_or_else(|e| panic!("invalid fixture: {e}"));
+        assert!(is_approved(&config, "abc123"));
+        assert!(!is_approved(&config, "def456"));
+        // Nothing staged yet
+        assert!(!has_pending_approval(&config));
+
+        config.status = Some(crate::crd::SecretManagerConfigStatus {
+            pending_changes: Some(PendingChanges {
+                hash: "abc123".to_string(),
+                revision: None,
+                summary: "1 create".to_string(),
+                operations: vec![],
+                planned_at: None,
+            }),
+            ..Default::default()
+        });
+        assert!(has_pending_approval(&config));
+    }
+}
@@ -0,0 +1,358 @@
+//! # Artifact Cache
+//!
+//! Shared on-disk store of extracted FluxCD artifacts, addressed by artifact digest.
+//!
+//! Every `SecretManagerConfig` pointing at the same GitRepository revision shares one
+//! extracted copy, so the reconcile interval no longer re-downloads identical content.
+//!
+//! Layout under `/tmp/smc/artifact-cache/`:
+//!
+//! ```text
+//! {key}/content/      extracted artifact (returned to the reconciler)
+//! {key}/.last-used    mtime records the last lookup (LRU order)
+//! .staging/{key}-*    in-progress downloads, renamed into place when complete
+//! ```
+//!
+//! Extraction happens in a staging directory and is moved into place with a single rename,
+//! so a reader never sees a partially extracted artifact. Fetches of the same key are
+//! serialized in-process; when two processes share the directory, the first rename wins.
+
+use crate::controller::reconciler::utils::{SMC_BASE_PATH, sanitize_path_component};
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+use tracing::{debug, info, warn};
+
+/// Entries used more recently than this are never evicted, since a reconcile may still be
+/// reading them
+const EVICTION_MIN_IDLE: Duration = Duration::from_secs(600);
+
+/// Staging directories older than this are left over from a crash and are removed
+const STAGING_MAX_AGE: Duration = Duration::from_secs(3600);
+
+const CONTENT_DIR: &str = "content";
+const LAST_USED_FILE: &str = ".last-used";
+const STAGING_DIR: &str = ".staging";
+
+/// Revision-addressed cache of extracted artifacts
+#[derive(Debug)]
+pub struct ArtifactCache {
+    root: PathBuf,
+    /// Maximum cached artifacts before least recently used entries are evicted
+    max_entries: usize,
+    min_idle: Duration,
+    // Per-key fetch locks so concurrent reconciles of the same revision download once
+    locks: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+}
+
+impl Default for ArtifactCache {
+    fn default() -> Self {
+        Self::new(
+            PathBuf::from(SMC_BASE_PATH).join("artifact-cache"),
+            crate::constants::DEFAULT_ARTIFACT_CACHE_MAX_ENTRIES,
+        )
+    }
+}
+
+impl ArtifactCache {
+    #[must_use]
+    pub fn new(root: PathBuf, max_entries: usize) -> Self {
+        Self {
+            root,
+            max_entries: max_entries.max(1),
+            min_idle: EVICTION_MIN_IDLE,
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cache key for an artifact
+    ///
+    /// Uses the artifact digest (content address) when FluxCD reports one, so identical
+    /// content from different GitRepositories shares an entry. Otherwise falls back to a hash
+    /// of the revision and URL.
+    #[must_use]
+    pub fn key(digest: Option<&str>, revision: &str, url: &str) -> String {
+        if let Some((algorithm, hex)) = digest.and_then(|d| d.split_once(':')) {
+            if !hex.is_empty() {
+                return format!(
+                    "{}-{}",
+                    sanitize_path_component(algorithm),
+                    sanitize_path_component(hex)
+                );
+            }
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(revision.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(url.as_bytes());
+        format!("rev-{:x}", hasher.finalize())
+    }
+
+    fn entry_dir(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+
+    /// Extracted content of a cached artifact, marking it as recently used
+    pub fn get(&self, key: &str) -> Option<PathBuf> {
+        let content = self.entry_dir(key).join(CONTENT_DIR);
+        if !content.is_dir() {
+            return None;
+        }
+        touch(&self.entry_dir(key).join(LAST_USED_FILE));
+        Some(content)
+    }
+
+    /// Wait for exclusive permission to fetch `key`
+    /// Re-check `get` after acquiring: another reconcile may have fetched it meanwhile
+    pub async fn lock(&self, key: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self
+                .locks
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            Arc::clone(
+                locks
+                    .entry(key.to_string())
+                    .or_insert_with(|| Arc::new(AsyncMutex::new(()))),
+            )
+        };
+        lock.lock_owned().await
+    }
+
+    /// Fresh staging directory for fetching `key`
+    #[must_use]
+    pub fn staging_dir(&self, key: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        self.root
+            .join(STAGING_DIR)
+            .join(format!("{key}-{}-{nanos}", std::process::id()))
+    }
+
+    /// Move extracted content from `staged_content` into the cache
+    /// Returns the cached content path
+    pub async fn insert(&self, key: &str, staged_content: &Path) -> Result<PathBuf> {
+        let entry = self.entry_dir(key);
+        let content = entry.join(CONTENT_DIR);
+        tokio::fs::create_dir_all(&entry)
+            .await
+            .with_context(|| format!("Failed to create cache entry {}", entry.display()))?;
+
+        if let Err(e) = tokio::fs::rename(staged_content, &content).await {
+            if content.is_dir() {
+                // Another process cached the same artifact first
+                debug!("Artifact {} already cached, discarding duplicate", key);
+                let _ = tokio::fs::remove_dir_all(staged_content).await;
+            } else {
+                return Err(e).with_context(|| {
+                    format!(
+                        "Failed to move {} into artifact cache",
+                        staged_content.display()
+                    )
+                });
+            }
+        }
+        touch(&entry.join(LAST_USED_FILE));
+
+        if let Err(e) = self.evict().await {
+            warn!("Failed to evict artifact cache entries: {}", e);
+        }
+        Ok(content)
+    }
+
+    /// Remove least recently used entries beyond `max_entries` and stale staging directories
+    /// Returns the number of entries evicted
+    pub async fn evict(&self) -> Result<usize> {
+        let mut entries = Vec::new();
+        let mut dir = tokio::fs::read_dir(&self.root)
+            .await
+            .with_context(|| format!("Failed to read artifact cache {}", self.root.display()))?;
+        while let Some(item) = dir.next_entry().await? {
+            let name = item.file_name().to_string_lossy().to_string();
+            if name == STAGING_DIR || !item.path().is_dir() {
+                continue;
+            }
+            let last_used = std::fs::metadata(item.path().join(LAST_USED_FILE))
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((name, item.path(), last_used));
+        }
+
+        let mut evicted: usize = 0;
+        if entries.len() > self.max_entries {
+            // Most recently used first
+            entries.sort_by(|a, b| b.2.cmp(&a.2));
+            let now = SystemTime::now();
+            for (key, path, last_used) in entries.split_off(self.max_entries) {
+                let idle = now.duration_since(last_used).unwrap_or_default();
+                if idle < self.min_idle || self.is_locked(&key) {
+                    continue;
+                }
+                info!("Evicting cached artifact {} (LRU)", key);
+                match tokio::fs::remove_dir_all(&path).await {
+                    Ok(()) => evicted += 1,
+                    Err(e) => warn!("Failed to evict cached artifact {}: {}", path.display(), e),
+                }
+            }
+        }
+        if evicted > 0 {
+            crate::observability::metrics::increment_artifact_cache_evictions_total(evicted as u64);
+        }
+
+        self.remove_stale_staging().await;
+        Ok(evicted)
+    }
+
+    fn is_locked(&self, key: &str) -> bool {
+        self.locks
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(key)
+            .is_some_and(|lock| lock.try_lock().is_err())
+    }
+
+    async fn remove_stale_staging(&self) {
+        let Ok(mut dir) = tokio::fs::read_dir(self.root.join(STAGING_DIR)).await else {
+            return;
+        };
+        let now = SystemTime::now();
+        while let Ok(Some(item)) = dir.next_entry().await {
+            let age = item
+                .metadata()
+                .await
+                .and_then(|m| m.modified())
+                .map(|modified| now.duration_since(modified).unwrap_or_default())
+                .unwrap_or_default();
+            if age > STAGING_MAX_AGE {
+                debug!(
+                    "Removing stale artifact staging dir {}",
+                    item.path().display()
+                );
+                let _ = tokio::fs::remove_dir_all(item.path()).await;
+            }
+        }
+    }
+}
+
+/// Record a use of a cache entry (mtime of the marker file)
+fn touch(path: &Path) {
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()));
+    if let Err(e) = result {
+        debug!("Failed to update {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(root: &Path, max_entries: usize) -> ArtifactCache {
+        ArtifactCache {
+            min_idle: Duration::ZERO,
+            ..ArtifactCache::new(root.to_path_buf(), max_entries)
+        }
+    }
+
+    async fn stage(cache: &ArtifactCache, key: &str) -> PathBuf {
+        let staged = cache.staging_dir(key).join(CONTENT_DIR);
+        tokio::fs::create_dir_all(&staged)
+            .await
+            .unwrap_or_else(|e| panic!("create staging: {e}"));
+        tokio::fs::write(staged.join("application.secrets.env"), "A=1")
+            .await
+            .unwrap_or_else(|e| panic!("write staged file: {e}"));
+        staged
+    }
+
+    #[test]
+    fn test_key_prefers_digest() {
+        let by_digest = ArtifactCache::key(Some("sha256:abc123"), "main@sha1:1", "http://a");
+        assert_eq!(by_digest, "sha256-abc123");
+        assert_eq!(
+            by_digest,
+            ArtifactCache::key(Some("sha256:abc123"), "other@sha1:2", "http://b")
+        );
+
+        let by_revision = ArtifactCache::key(None, "main@sha1:1", "http://a");
+        assert!(by_revision.starts_with("rev-"));
+        assert_ne!(
+            by_revision,
+            ArtifactCache::key(None, "main@sha1:2", "http://a")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_insert_then_get() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir: {e}"));
+        let cache = cache(dir.path(), 5);
+        assert!(cache.get("sha256-aa").is_none());
+
+        let staged = stage(&cache, "sha256-aa").await;
+        let content = cache
+            .insert("sha256-aa", &staged)
+            .await
+            .unwrap_or_else(|e| panic!("insert: {e}"));
+
+        assert_eq!(cache.get("sha256-aa"), Some(content.clone()));
+        assert!(content.join("application.secrets.env").is_file());
+        assert!(!staged.exists());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_insert_keeps_first_copy() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir: {e}"));
+        let cache = cache(dir.path(), 5);
+        let first = stage(&cache, "sha256-bb").await;
+        cache
+            .insert("sha256-bb", &first)
+            .await
+            .unwrap_or_else(|e| panic!("insert: {e}"));
+
+        let second = stage(&cache, "sha256-bb").await;
+        let content = cache
+            .insert("sha256-bb", &second)
+            .await
+            .unwrap_or_else(|e| panic!("insert duplicate: {e}"));
+        assert!(content.join("application.secrets.env").is_file());
+        assert!(!second.exists());
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir: {e}"));
+        let cache = cache(dir.path(), 2);
+        for key in ["sha256-01", "sha256-02"] {
+            let staged = stage(&cache, key).await;
+            cache
+                .insert(key, &staged)
+                .await
+                .unwrap_or_else(|e| panic!("insert: {e}"));
+        }
+        // Mark the first entry as the oldest, then use the second
+        std::fs::File::options()
+            .write(true)
+            .open(dir.path().join("sha256-01").join(LAST_USED_FILE))
+            .and_then(|f| f.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(60)))
+            .unwrap_or_else(|e| panic!("set mtime: {e}"));
+
+        let staged = stage(&cache, "sha256-03").await;
+        cache
+            .insert("sha256-03", &staged)
+            .await
+            .unwrap_or_else(|e| panic!("insert: {e}"));
+
+        assert!(cache.get("sha256-01").is_none());
+        assert!(cache.get("sha256-02").is_some());
+        assert!(cache.get("sha256-03").is_some());
+    }
+}
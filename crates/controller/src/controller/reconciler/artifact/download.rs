@@ -6,6 +6,7 @@
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 use std::time::{Instant, SystemTime};
+use thiserror::Error;
 use tracing::{debug, error, info, info_span, warn};
 
 /// Download artifact from URL to temporary file
@@ -215,15 +216,91 @@ pub async fn download_artifact(artifact_url: &str, temp_file: &Path) -> Result<(
     Ok((temp_file.to_path_buf(), downloaded_size))
 }
 
+/// Artifact failed an integrity or safety check
+///
+/// Unlike download errors these are not transient: retrying the same revision fails the same
+/// way, so the reconciler reports them with a distinct `ArtifactCorrupted` reason.
+#[derive(Debug, Error)]
+pub enum ArtifactIntegrityError {
+    #[error(
+        "Checksum mismatch: expected {expected}, got {actual}. Artifact may be corrupt or tampered."
+    )]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error("Artifact contains unsafe path '{0}' (absolute or outside the extraction directory)")]
+    UnsafePath(String),
+    #[error("Artifact exceeds extraction limit: {0}")]
+    LimitExceeded(String),
+}
+
+/// Limits applied while extracting an artifact (protects against tar bombs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractionLimits {
+    /// Maximum total size of extracted files in bytes (0 = unlimited)
+    pub max_extracted_bytes: u64,
+    /// Maximum number of entries in the archive (0 = unlimited)
+    pub max_files: u64,
+}
+
+impl Default for ExtractionLimits {
+    fn default() -> Self {
+        Self {
+            max_extracted_bytes: crate::constants::DEFAULT_ARTIFACT_MAX_EXTRACTED_BYTES,
+            max_files: crate::constants::DEFAULT_ARTIFACT_MAX_FILES,
+        }
+    }
+}
+
 /// Verify artifact checksum if provided
+///
+/// `expected_digest` is `<algorithm>:<hex>` (sha256, sha384 or sha512). Digests with an
+/// unsupported algorithm are logged and skipped rather than failing the reconcile.
 pub fn verify_checksum(temp_file: &Path, expected_digest: &str) -> Result<()> {
-    use sha2::{Digest, Sha256};
+    use sha2::{Digest, Sha256, Sha384, Sha512};
+
+    let Some((algorithm, expected_hex)) = expected_digest.split_once(':') else {
+        warn!(
+            "Artifact digest '{}' has no algorithm prefix, skipping checksum verification",
+            expected_digest
+        );
+        return Ok(());
+    };
+
+    let computed_hex = match algorithm {
+        "sha256" => hash_file::<Sha256>(temp_file)?,
+        "sha384" => hash_file::<Sha384>(temp_file)?,
+        "sha512" => hash_file::<Sha512>(temp_file)?,
+        other => {
+            warn!(
+                "Unsupported artifact digest algorithm '{}', skipping checksum verification",
+                other
+            );
+            return Ok(());
+        }
+    };
+
+    if !computed_hex.eq_ignore_ascii_case(expected_hex) {
+        // Clean up invalid artifact
+        let _ = std::fs::remove_file(temp_file);
+        return Err(ArtifactIntegrityError::ChecksumMismatch {
+            expected: expected_digest.to_string(),
+            actual: format!("{algorithm}:{computed_hex}"),
+        }
+        .into());
+    }
+    debug!("Checksum verified: {}", expected_digest);
+    Ok(())
+}
+
+/// Hex digest of a file
+fn hash_file<D: sha2::Digest>(path: &Path) -> Result<String>
+where
+    sha2::digest::Output<D>: std::fmt::LowerHex,
+{
     use std::io::Read;
 
-    // Read file and compute SHA256
-    let mut file = std::fs::File::open(temp_file)
+    let mut file = std::fs::File::open(path)
         .context("Failed to open downloaded file for checksum verification")?;
-    let mut hasher = Sha256::new();
+    let mut hasher = D::new();
     let mut buffer = vec![0u8; 8192];
     loop {
         let bytes_read = file.read(&mut buffer)?;
@@ -232,20 +309,7 @@ pub fn verify_checksum(temp_file: &Path, expected_digest: &str) -> Result<()> {
         }
         hasher.update(&buffer[..bytes_read]);
     }
-    let computed_hash = format!("sha256:{:x}", hasher.finalize());
-
-    // Extract hash from digest (format: "sha256:...")
-    if expected_digest != computed_hash {
-        // Clean up invalid artifact
-        let _ = std::fs::remove_file(temp_file);
-        return Err(anyhow::anyhow!(
-            "Checksum mismatch: expected {}, got {}. Artifact may be corrupt or tampered.",
-            expected_digest,
-            computed_hash
-        ));
-    }
-    debug!("Checksum verified: {}", expected_digest);
-    Ok(())
+    Ok(format!("{:x}", hasher.finalize()))
 }
 
 /// Verify file is a valid tar.gz by checking magic bytes
@@ -272,7 +336,14 @@ pub fn verify_tar_gz_format(temp_file: &Path) -> Result<()> {
 }
 
 /// Extract tar.gz file to destination directory
-pub async fn extract_artifact(temp_file: &Path, destination: &Path) -> Result<()> {
+///
+/// Entries with absolute paths or `..` components fail the extraction, links are skipped,
+/// and the archive is rejected once it exceeds `limits`.
+pub async fn extract_artifact(
+    temp_file: &Path,
+    destination: &Path,
+    limits: ExtractionLimits,
+) -> Result<()> {
     let extract_span = info_span!(
         "artifact.extract",
         artifact.destination = destination.display().to_string()
@@ -290,35 +361,26 @@ pub async fn extract_artifact(temp_file: &Path, destination: &Path) -> Result<()
             destination.display()
         ))?;
 
-    // Use tar command to extract with security flags:
-    // - --strip-components=0: Preserve directory structure
-    // - --warning=no-unknown-keyword: Suppress warnings for unknown keywords
-    // - -C: Extract to specific directory (prevents path traversal)
-    // Note: tar automatically prevents extraction outside -C directory on most systems
-    let extract_output = tokio::process::Command::new("tar")
-        .arg("-xzf")
-        .arg(temp_file)
-        .arg("-C")
-        .arg(destination)
-        .arg("--strip-components=0") // Preserve directory structure
-        .arg("--warning=no-unknown-keyword") // Suppress warnings
-        .output()
-        .await
-        .context("Failed to execute tar command")?;
+    let archive_path = temp_file.to_path_buf();
+    let destination_path = destination.to_path_buf();
+    let extract_result = tokio::task::spawn_blocking(move || {
+        unpack_tar_gz(&archive_path, &destination_path, limits)
+    })
+    .await
+    .context("Artifact extraction task panicked")?;
 
-    if !extract_output.status.success() {
-        let stderr = String::from_utf8_lossy(&extract_output.stderr);
+    if let Err(e) = extract_result {
         crate::observability::metrics::increment_artifact_extraction_errors_total();
         extract_span.record("operation.success", false);
-        extract_span.record("error.message", stderr.to_string());
+        extract_span.record("error.message", e.to_string());
         // Clean up on extraction failure
         let _ = tokio::fs::remove_file(temp_file).await;
         // Also clean up partial extraction directory
         let _ = tokio::fs::remove_dir_all(destination).await;
-        return Err(anyhow::anyhow!(
-            "Failed to extract artifact (corrupt or invalid tar.gz): {}",
-            stderr
-        ));
+        if e.downcast_ref::<ArtifactIntegrityError>().is_some() {
+            return Err(e);
+        }
+        return Err(e.context("Failed to extract artifact (corrupt or invalid tar.gz)"));
     }
 
     // Verify extraction succeeded by checking if directory contains files
@@ -350,6 +412,82 @@ pub async fn extract_artifact(temp_file: &Path, destination: &Path) -> Result<()
     Ok(())
 }
 
+/// Unpack a tar.gz archive entry by entry, validating each path and the running totals
+fn unpack_tar_gz(archive_path: &Path, destination: &Path, limits: ExtractionLimits) -> Result<()> {
+    let file = std::fs::File::open(archive_path).context(format!(
+        "Failed to open artifact: {}",
+        archive_path.display()
+    ))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    archive.set_preserve_permissions(false);
+
+    let mut file_count: u64 = 0;
+    let mut extracted_bytes: u64 = 0;
+
+    for entry in archive.entries().context("Failed to read tar entries")? {
+        let mut entry = entry.context("Failed to read tar entry")?;
+        let path = entry
+            .path()
+            .context("Invalid path in tar entry")?
+            .into_owned();
+        ensure_safe_entry_path(&path)?;
+
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            warn!(
+                "Skipping link entry in artifact: {} (links are not extracted)",
+                path.display()
+            );
+            continue;
+        }
+
+        file_count += 1;
+        if limits.max_files > 0 && file_count > limits.max_files {
+            return Err(ArtifactIntegrityError::LimitExceeded(format!(
+                "more than {} entries",
+                limits.max_files
+            ))
+            .into());
+        }
+
+        // Header sizes are checked before writing so a bomb never reaches the disk
+        extracted_bytes = extracted_bytes.saturating_add(entry.size());
+        if limits.max_extracted_bytes > 0 && extracted_bytes > limits.max_extracted_bytes {
+            return Err(ArtifactIntegrityError::LimitExceeded(format!(
+                "more than {} extracted bytes",
+                limits.max_extracted_bytes
+            ))
+            .into());
+        }
+
+        // unpack_in re-checks the path against the destination as a second line of defense
+        if !entry
+            .unpack_in(destination)
+            .context(format!("Failed to extract tar entry: {}", path.display()))?
+        {
+            return Err(ArtifactIntegrityError::UnsafePath(path.display().to_string()).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Reject tar entry paths that are absolute or could escape the extraction directory
+fn ensure_safe_entry_path(path: &Path) -> Result<(), ArtifactIntegrityError> {
+    use std::path::Component;
+
+    let safe = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if safe {
+        Ok(())
+    } else {
+        Err(ArtifactIntegrityError::UnsafePath(
+            path.display().to_string(),
+        ))
+    }
+}
+
 /// Clean up old revisions, keeping only the 3 newest per namespace/name combination
 /// Removes the 4th oldest revision and any older ones to prevent unbounded disk growth
 pub async fn cleanup_old_revisions(parent_dir: &Path) -> Result<()> {
@@ -391,3 +529,142 @@ pub async fn cleanup_old_revisions(parent_dir: &Path) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use sha2::{Digest, Sha256};
+
+    /// Build a tar.gz with the given (path, contents) entries
+    /// Paths are written straight into the header so unsafe names can be tested
+    fn write_tar_gz(path: &Path, entries: &[(&str, &[u8])]) {
+        let file = std::fs::File::create(path).unwrap_or_else(|e| panic!("create: {e}"));
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        for (name, data) in entries {
+            let mut header = tar::Header::new_old();
+            let raw_name = &mut header.as_old_mut().name;
+            raw_name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_cksum();
+            builder
+                .append(&header, *data)
+                .unwrap_or_else(|e| panic!("append: {e}"));
+        }
+        builder
+            .into_inner()
+            .and_then(GzEncoder::finish)
+            .unwrap_or_else(|e| panic!("finish: {e}"));
+    }
+
+    fn integrity_error(result: Result<()>) -> ArtifactIntegrityError {
+        let err = result
+            .err()
+            .unwrap_or_else(|| panic!("expected an integrity error"));
+        err.downcast::<ArtifactIntegrityError>()
+            .unwrap_or_else(|e| panic!("unexpected error: {e}"))
+    }
+
+    #[test]
+    fn test_verify_checksum_match_and_mismatch() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+        let tarball = dir.path().join("artifact.tar.gz");
+        std::fs::write(&tarball, b"artifact").unwrap_or_else(|e| panic!("{e}"));
+
+        let digest = format!("sha256:{:x}", Sha256::digest(b"artifact"));
+        assert!(verify_checksum(&tarball, &digest).is_ok());
+        let upper = format!("sha256:{:X}", Sha256::digest(b"artifact"));
+        assert!(verify_checksum(&tarball, &upper).is_ok());
+
+        let err = integrity_error(verify_checksum(&tarball, "sha256:00"));
+        assert!(matches!(
+            err,
+            ArtifactIntegrityError::ChecksumMismatch { .. }
+        ));
+        // The corrupt tarball is removed so it can't be extracted by mistake
+        assert!(!tarball.exists());
+    }
+
+    #[test]
+    fn test_verify_checksum_skips_unknown_algorithm() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+        let tarball = dir.path().join("artifact.tar.gz");
+        std::fs::write(&tarball, b"artifact").unwrap_or_else(|e| panic!("{e}"));
+        assert!(verify_checksum(&tarball, "blake3:abcd").is_ok());
+        assert!(tarball.exists());
+    }
+
+    #[tokio::test]
+    async fn test_extract_artifact_unpacks_files() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+        let tarball = dir.path().join("artifact.tar.gz");
+        write_tar_gz(
+            &tarball,
+            &[("profiles/dev/application.secrets.env", b"KEY=value\n")],
+        );
+
+        let destination = dir.path().join("content");
+        extract_artifact(&tarball, &destination, ExtractionLimits::default())
+            .await
+            .unwrap_or_else(|e| panic!("extract: {e}"));
+        let contents =
+            std::fs::read_to_string(destination.join("profiles/dev/application.secrets.env"))
+                .unwrap_or_else(|e| panic!("{e}"));
+        assert_eq!(contents, "KEY=value\n");
+    }
+
+    #[tokio::test]
+    async fn test_extract_artifact_rejects_path_traversal() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+        for name in [
+            "../escape.env",
+            "profiles/../../escape.env",
+            "/etc/escape.env",
+        ] {
+            let tarball = dir.path().join("artifact.tar.gz");
+            write_tar_gz(&tarball, &[("ok.env", b"A=1\n"), (name, b"B=2\n")]);
+
+            let destination = dir.path().join("content");
+            let err = integrity_error(
+                extract_artifact(&tarball, &destination, ExtractionLimits::default()).await,
+            );
+            assert!(
+                matches!(err, ArtifactIntegrityError::UnsafePath(_)),
+                "{name}"
+            );
+            assert!(
+                !destination.exists(),
+                "partial extraction left behind for {name}"
+            );
+            assert!(!dir.path().join("escape.env").exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_artifact_enforces_limits() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+        let tarball = dir.path().join("artifact.tar.gz");
+        let destination = dir.path().join("content");
+
+        let big = [b'x'; 2048];
+        write_tar_gz(&tarball, &[("big.env", big.as_slice())]);
+        let limits = ExtractionLimits {
+            max_extracted_bytes: 1024,
+            max_files: 0,
+        };
+        let err = integrity_error(extract_artifact(&tarball, &destination, limits).await);
+        assert!(matches!(err, ArtifactIntegrityError::LimitExceeded(_)));
+
+        write_tar_gz(&tarball, &[("a.env", b"A=1\n"), ("b.env", b"B=2\n")]);
+        let limits = ExtractionLimits {
+            max_extracted_bytes: 0,
+            max_files: 1,
+        };
+        let err = integrity_error(extract_artifact(&tarball, &destination, limits).await);
+        assert!(matches!(err, ArtifactIntegrityError::LimitExceeded(_)));
+        assert!(!destination.exists());
+    }
+}
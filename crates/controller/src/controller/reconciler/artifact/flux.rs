@@ -4,17 +4,14 @@
 //! Downloads and extracts tar.gz artifacts from FluxCD source-controller.
 
 use crate::controller::reconciler::types::Reconciler;
-use crate::controller::reconciler::utils::{SMC_BASE_PATH, sanitize_path_component};
 use crate::crd::SourceRef;
 use anyhow::{Context, Result};
 use std::path::PathBuf;
 use std::time::Instant;
-use tracing::{Instrument, info, info_span, warn};
+use tracing::{Instrument, debug, info, info_span, warn};
 
-use super::download::{
-    cleanup_old_revisions, download_artifact, extract_artifact, verify_checksum,
-    verify_tar_gz_format,
-};
+use super::cache::ArtifactCache;
+use super::download::{download_artifact, extract_artifact, verify_checksum, verify_tar_gz_format};
 
 /// Get FluxCD GitRepository resource
 #[allow(
@@ -62,7 +59,8 @@ pub async fn get_flux_git_repository(
 }
 
 /// Get artifact path from FluxCD GitRepository status
-/// Downloads and extracts the tar.gz artifact from FluxCD source-controller HTTP service
+/// Downloads and extracts the tar.gz artifact from FluxCD source-controller HTTP service,
+/// unless the same revision is already in the shared artifact cache
 /// Returns the path to the extracted directory
 #[allow(
     clippy::doc_markdown,
@@ -70,7 +68,7 @@ pub async fn get_flux_git_repository(
     reason = "Markdown formatting is intentional, error docs in comments"
 )]
 pub async fn get_flux_artifact_path(
-    _reconciler: &Reconciler,
+    reconciler: &Reconciler,
     git_repo: &serde_json::Value,
 ) -> Result<PathBuf> {
     // Extract artifact information from GitRepository status
@@ -95,139 +93,88 @@ pub async fn get_flux_artifact_path(
         .trim_end_matches('.')
         .to_string();
 
-    // Get revision for caching - use revision to determine if we need to re-download
+    // Revision and digest address the shared artifact cache
     let revision = status
         .get("revision")
         .and_then(|r| r.as_str())
         .unwrap_or("unknown");
+    // v1 reports `digest` ("sha256:<hex>"); v1beta2 only reports a bare sha256 `checksum`
+    let digest = status
+        .get("digest")
+        .and_then(|d| d.as_str())
+        .map(str::to_string)
+        .or_else(|| {
+            status
+                .get("checksum")
+                .and_then(|c| c.as_str())
+                .filter(|c| !c.is_empty())
+                .map(|c| format!("sha256:{c}"))
+        });
+    let digest = digest.as_deref();
 
-    // Extract branch name and short SHA from revision
-    // FluxCD revision format: "main@sha1:7680da431ea59ae7d3f4fdbb903a0f4509da9078"
-    // We need both branch and SHA to avoid conflicts when same SHA exists on different branches
-    let (branch_name, short_sha) = if let Some(at_pos) = revision.find('@') {
-        // Extract branch name (before @)
-        let branch = &revision[..at_pos];
-        let sanitized_branch = sanitize_path_component(branch);
-
-        // Extract SHA (after @sha1: or @sha256:)
-        let sha = if let Some(sha_start) = revision.find("sha1:") {
-            &revision[sha_start + 5..]
-        } else if let Some(sha_start) = revision.find("sha256:") {
-            &revision[sha_start + 7..]
-        } else {
-            // No SHA found, use full revision after @
-            &revision[at_pos + 1..]
-        };
-
-        let short_sha = if sha.len() >= 7 { &sha[..7] } else { sha };
-
-        (sanitized_branch, short_sha.to_string())
-    } else {
-        // No @ separator found, treat entire revision as branch
-        (sanitize_path_component(revision), "unknown".to_string())
-    };
-
-    // Create revision directory name: {branch}-sha-{short_sha}
-    // Example: "main-sha-7680da4" or "old-branch-sha-7680da4"
-    let revision_dir = format!("{}-sha-{}", branch_name, short_sha);
-
-    // Get metadata for constructing cache path
-    let metadata = git_repo
-        .get("metadata")
-        .context("FluxCD GitRepository has no metadata")?;
-
-    let name = metadata
-        .get("name")
-        .and_then(|n| n.as_str())
-        .context("FluxCD GitRepository has no name")?;
-
-    let namespace = metadata
-        .get("namespace")
-        .and_then(|n| n.as_str())
-        .context("FluxCD GitRepository has no namespace")?;
-
-    // Create hierarchical cache directory path: /tmp/smc/flux-artifact/{namespace}/{name}/{branch}-sha-{short_sha}/
-    // This structure:
-    // 1. Avoids performance issues with many files in a single directory
-    // 2. Allows cluster owners to mount a PVC at /tmp/smc for persistent storage
-    // 3. Provides clear organization by namespace, name, branch, and SHA
-    // 4. Uses branch name + short SHA (7 chars) to avoid conflicts when same SHA exists on different branches
-    // 5. Cleanup uses mtime (filesystem modification time) to determine oldest revisions per branch
-    let sanitized_namespace = sanitize_path_component(namespace);
-    let sanitized_name = sanitize_path_component(name);
-
-    let cache_path = PathBuf::from(SMC_BASE_PATH)
-        .join("flux-artifact")
-        .join(&sanitized_namespace)
-        .join(&sanitized_name)
-        .join(&revision_dir);
-
-    // Check if artifact is already cached (directory exists and is not empty)
-    if cache_path.exists() && cache_path.is_dir() {
-        // Verify cache is valid by checking if it contains files
-        if let Ok(mut entries) = std::fs::read_dir(&cache_path) {
-            if entries.next().is_some() {
-                info!(
-                    "Using cached FluxCD artifact at {} (revision: {}, dir: {})",
-                    cache_path.display(),
-                    revision,
-                    revision_dir
-                );
-                return Ok(cache_path);
-            }
-        }
+    let cache = &reconciler.artifact_cache;
+    let cache_key = ArtifactCache::key(digest, revision, &artifact_url);
+
+    if let Some(cache_path) = cache.get(&cache_key) {
+        crate::observability::metrics::increment_artifact_cache_hits_total();
+        debug!(
+            "Using cached FluxCD artifact at {} (revision: {})",
+            cache_path.display(),
+            revision
+        );
+        return Ok(cache_path);
+    }
+
+    // Only one reconcile downloads a given revision; the others wait and then hit the cache
+    let _fetch_guard = cache.lock(&cache_key).await;
+    if let Some(cache_path) = cache.get(&cache_key) {
+        crate::observability::metrics::increment_artifact_cache_hits_total();
+        return Ok(cache_path);
     }
+    crate::observability::metrics::increment_artifact_cache_misses_total();
 
     info!(
-        "Downloading FluxCD artifact from {} (revision: {}, dir: {})",
-        artifact_url, revision, revision_dir
+        "Downloading FluxCD artifact from {} (revision: {})",
+        artifact_url, revision
     );
 
-    // Download tar.gz file to temporary location
-    let temp_tar = cache_path.join("artifact.tar.gz");
-    let (_temp_file, _downloaded_size) = download_artifact(&artifact_url, &temp_tar).await?;
-
-    // Verify checksum if provided by FluxCD
-    // FluxCD provides digest in artifact status (e.g., "sha256:...")
-    if let Some(digest_str) = status.get("digest").and_then(|d| d.as_str()) {
-        verify_checksum(&temp_tar, digest_str)?;
-    }
-
-    // Verify file is a valid tar.gz by checking magic bytes
-    verify_tar_gz_format(&temp_tar)?;
+    // Download and extract into a private staging directory, then move into the cache
+    let staging_dir = cache.staging_dir(&cache_key);
+    let temp_tar = staging_dir.join("artifact.tar.gz");
+    let staged_content = staging_dir.join("content");
+    let result = async {
+        download_artifact(&artifact_url, &temp_tar).await?;
+
+        // Verify checksum before anything reads the tarball
+        // A mismatch fails with ArtifactIntegrityError (reported as ArtifactCorrupted)
+        if let Some(digest_str) = digest {
+            verify_checksum(&temp_tar, digest_str)?;
+        }
 
-    // Extract artifact
-    extract_artifact(&temp_tar, &cache_path).await?;
+        // Verify file is a valid tar.gz by checking magic bytes
+        verify_tar_gz_format(&temp_tar)?;
 
-    // Clean up temporary tar file after successful extraction
-    if let Err(e) = tokio::fs::remove_file(&temp_tar).await {
-        warn!(
-            "Failed to remove temporary tar file {}: {}",
-            temp_tar.display(),
-            e
-        );
-        // Don't fail reconciliation if cleanup fails
+        extract_artifact(&temp_tar, &staged_content, reconciler.artifact_limits).await?;
+        cache.insert(&cache_key, &staged_content).await
     }
-
-    // Clean up old revisions - keep only the 3 newest revisions per namespace/name
-    // This prevents disk space from growing unbounded
-    if let Some(parent_dir) = cache_path.parent() {
-        if let Err(e) = cleanup_old_revisions(parent_dir).await {
-            warn!("Failed to cleanup old revisions: {}", e);
-            // Don't fail reconciliation if cleanup fails
+    .await;
+
+    // The staging directory only holds the tarball (and content, on failure)
+    if let Err(e) = tokio::fs::remove_dir_all(&staging_dir).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!(
+                "Failed to remove artifact staging dir {}: {}",
+                staging_dir.display(),
+                e
+            );
         }
-    } else {
-        warn!(
-            "Cannot cleanup old revisions: cache path {} has no parent directory",
-            cache_path.display()
-        );
     }
 
+    let cache_path = result?;
     info!(
-        "Successfully downloaded and extracted FluxCD artifact to {} (revision: {}, dir: {})",
+        "Successfully downloaded and extracted FluxCD artifact to {} (revision: {})",
         cache_path.display(),
-        revision,
-        revision_dir
+        revision
     );
 
     Ok(cache_path)
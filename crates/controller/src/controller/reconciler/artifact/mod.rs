@@ -1,11 +1,17 @@
 //! # Artifact Management
 //!
-//! Handles downloading and extracting FluxCD and ArgoCD artifacts.
+//! Handles downloading and extracting FluxCD artifacts, and cloning repositories for
+//! ArgoCD Application and GitDirect sources.
 
 mod argocd;
-mod download;
+pub mod cache;
+pub mod download;
 mod flux;
+mod git;
+mod git_credentials;
 
 // Re-export public API
 pub use argocd::get_argocd_artifact_path;
 pub use flux::{get_flux_artifact_path, get_flux_git_repository};
+pub use git::get_git_direct_artifact_path;
+pub use git_credentials::GitCredentialsError;
@@ -0,0 +1,343 @@
+//! # Reconcile Concurrency
+//!
+//! Per-provider concurrency limits for reconciliation.
+//!
+//! The controller runtime bounds how many resources reconcile at once; this module adds a
+//! semaphore per cloud provider so that, for example, 500 resources reconciling after a
+//! controller restart don't all hit the GCP API at the same time. Reconciles waiting for a
+//! slot are reported by the `secret_manager_reconcile_queue_depth` gauge.
+//!
+//! Resources writing to the same cloud project (GCP project, AWS account and region, Azure
+//! vault) compete for the same quota and can conflict on shared secrets. [`ProjectQueues`]
+//! gives each project its own work queue (`MAX_CONCURRENT_PROJECT_RECONCILIATIONS`, 1 to
+//! serialize), so resources of one project take turns while resources of other projects
+//! proceed in parallel. A reconcile whose project is busy doesn't wait for its turn: it is
+//! requeued with a backoff that grows while the project stays busy, so it neither parks a
+//! controller worker nor holds a provider slot other projects could use. These requeues are
+//! counted by `secret_manager_requeues_total{reason="project-busy"}`.
+
+use crate::crd::ProviderConfig;
+use crate::observability;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Requeue delay after a project is first found busy, doubled on each further busy attempt
+const PROJECT_BUSY_BACKOFF_MIN: Duration = Duration::from_secs(2);
+/// Longest requeue delay for a busy project
+const PROJECT_BUSY_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Concurrency limiter with one semaphore per provider
+#[derive(Debug)]
+pub struct ProviderLimiter {
+    /// Maximum concurrent reconciliations per provider (0 = unbounded)
+    limit: usize,
+    semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+/// Decrements the queue-depth gauge when a waiting reconcile leaves the queue
+/// (either because it acquired a permit or because its future was dropped)
+struct QueuedGuard<'a> {
+    provider: &'a str,
+}
+
+impl<'a> QueuedGuard<'a> {
+    fn new(provider: &'a str) -> Self {
+        observability::metrics::increment_reconcile_queue_depth(provider);
+        Self { provider }
+    }
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        observability::metrics::decrement_reconcile_queue_depth(self.provider);
+    }
+}
+
+impl ProviderLimiter {
+    /// Create a limiter allowing `limit` concurrent reconciliations per provider
+    /// A limit of 0 disables the limiter
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            semaphores: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Maximum concurrent reconciliations per provider (0 = unbounded)
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Get or create the semaphore for a provider
+    fn semaphore(&self, provider: &str) -> Arc<Semaphore> {
+        let mut semaphores = self
+            .semaphores
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        semaphores
+            .entry(provider.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.limit)))
+            .clone()
+    }
+
+    /// Wait for a concurrency slot for `provider`
+    /// Returns `None` when the limiter is disabled; otherwise the slot is held until the
+    /// returned permit is dropped
+    pub async fn acquire(&self, provider: &str) -> Option<OwnedSemaphorePermit> {
+        if self.limit == 0 {
+            return None;
+        }
+        let semaphore = self.semaphore(provider);
+        let _queued = QueuedGuard::new(provider);
+        // The semaphore is never closed, so acquisition only fails if that changes
+        semaphore.acquire_owned().await.ok()
+    }
+
+    /// Number of free slots for `provider`
+    pub fn available(&self, provider: &str) -> usize {
+        if self.limit == 0 {
+            return usize::MAX;
+        }
+        self.semaphore(provider).available_permits()
+    }
+}
+
+impl Default for ProviderLimiter {
+    fn default() -> Self {
+        Self::new(crate::constants::DEFAULT_MAX_CONCURRENT_PROVIDER_RECONCILIATIONS)
+    }
+}
+
+/// Work queue of the cloud project a provider configuration writes to
+/// e.g. `gcp/my-project`, `aws/123456789012/eu-west-1`, `azure/my-vault`
+#[must_use]
+pub fn project_key(provider: &ProviderConfig) -> String {
+    match provider {
+        ProviderConfig::Gcp(gcp) => format!("gcp/{}", gcp.project_id),
+        ProviderConfig::Aws(aws) => {
+            // Without a role the account is the controller's own
+            let account = aws
+                .assume_role_arn
+                .as_deref()
+                .and_then(|arn| arn.split(':').nth(4))
+                .filter(|account| !account.is_empty())
+                .unwrap_or("default");
+            format!("aws/{account}/{}", aws.region)
+        }
+        ProviderConfig::Azure(azure) => format!("azure/{}", azure.vault_name),
+    }
+}
+
+/// Work queues with one semaphore per cloud project
+#[derive(Debug, Default)]
+pub struct ProjectQueues {
+    /// Maximum concurrent reconciliations per project (0 = unbounded)
+    limit: usize,
+    queues: Mutex<HashMap<String, ProjectQueue>>,
+}
+
+#[derive(Debug)]
+struct ProjectQueue {
+    semaphore: Arc<Semaphore>,
+    /// Turns refused since the project was last free
+    busy_count: u32,
+}
+
+/// The project's queue is full; reconcile again after `retry_after`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProjectBusy {
+    pub retry_after: Duration,
+}
+
+impl ProjectQueues {
+    /// Create queues allowing `limit` concurrent reconciliations per project
+    /// A limit of 0 disables the queues
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a turn of `provider`'s project without waiting
+    /// Returns `Ok(None)` when the queues are disabled; otherwise the turn is held until the
+    /// returned permit is dropped. When the project has no free turn, returns how long to
+    /// back off before trying again.
+    pub fn try_acquire(
+        &self,
+        provider: &ProviderConfig,
+    ) -> Result<Option<OwnedSemaphorePermit>, ProjectBusy> {
+        if self.limit == 0 {
+            return Ok(None);
+        }
+        let mut queues = self
+            .queues
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let queue = queues
+            .entry(project_key(provider))
+            .or_insert_with(|| ProjectQueue {
+                semaphore: Arc::new(Semaphore::new(self.limit)),
+                busy_count: 0,
+            });
+        // The semaphore is never closed, so only a full queue refuses a turn
+        if let Ok(permit) = queue.semaphore.clone().try_acquire_owned() {
+            queue.busy_count = 0;
+            return Ok(Some(permit));
+        }
+        let retry_after = PROJECT_BUSY_BACKOFF_MIN
+            .saturating_mul(1 << queue.busy_count.min(16))
+            .min(PROJECT_BUSY_BACKOFF_MAX);
+        queue.busy_count = queue.busy_count.saturating_add(1);
+        Err(ProjectBusy { retry_after })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_limiter_bounds_each_provider() {
+        let limiter = ProviderLimiter::new(2);
+        let first = limiter.acquire("gcp").await;
+        let second = limiter.acquire("gcp").await;
+        assert!(first.is_some() && second.is_some());
+        assert_eq!(limiter.available("gcp"), 0);
+
+        // Other providers have their own slots
+        assert_eq!(limiter.available("aws"), 2);
+
+        drop(first);
+        assert_eq!(limiter.available("gcp"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_limiter_waits_for_free_slot() {
+        let limiter = Arc::new(ProviderLimiter::new(1));
+        let held = limiter.acquire("azure").await;
+
+        let waiter = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire("azure").await.is_some() })
+        };
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        drop(held);
+        assert!(waiter.await.unwrap_or(false));
+    }
+
+    #[tokio::test]
+    async fn test_zero_limit_is_unbounded() {
+        let limiter = ProviderLimiter::new(0);
+        assert!(limiter.acquire("gcp").await.is_none());
+        assert_eq!(limiter.available("gcp"), usize::MAX);
+    }
+
+    fn provider(value: serde_json::Value) -> ProviderConfig {
+        serde_json::from_value(value)
+            .unwrap_or_else(|e| panic!("Failed to build ProviderConfig: {e}"))
+    }
+
+    #[test]
+    fn test_project_key() {
+        assert_eq!(project_key(&gcp("a")), "gcp/a");
+
+        let aws = provider(serde_json::json!({
+            "type": "aws",
+            "aws": {
+                "region": "eu-west-1",
+                "assumeRoleArn": "arn:aws:iam::123456789012:role/writer"
+            }
+        }));
+        assert_eq!(project_key(&aws), "aws/123456789012/eu-west-1");
+    }
+
+    fn gcp(project: &str) -> ProviderConfig {
+        provider(serde_json::json!({
+            "type": "gcp",
+            "gcp": { "projectId": project, "location": "us-central1" }
+        }))
+    }
+
+    #[test]
+    fn test_project_queues_back_off_while_busy() {
+        let queues = ProjectQueues::new(1);
+        let held = queues.try_acquire(&gcp("shared"));
+        assert!(matches!(held, Ok(Some(_))));
+
+        // Another project proceeds right away
+        assert!(matches!(queues.try_acquire(&gcp("other")), Ok(Some(_))));
+
+        let busy = |secs| {
+            Err::<bool, _>(ProjectBusy {
+                retry_after: Duration::from_secs(secs),
+            })
+        };
+        let retry = || {
+            queues
+                .try_acquire(&gcp("shared"))
+                .map(|permit| permit.is_some())
+        };
+        assert_eq!(retry(), busy(2));
+        assert_eq!(retry(), busy(4));
+        assert_eq!(retry(), busy(8));
+
+        // A free turn resets the backoff
+        drop(held);
+        let held = queues.try_acquire(&gcp("shared"));
+        assert!(matches!(held, Ok(Some(_))));
+        assert_eq!(retry(), busy(2));
+
+        assert!(matches!(
+            ProjectQueues::new(0).try_acquire(&gcp("shared")),
+            Ok(None)
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_project_queues_never_overlap_turns() {
+        const WORKERS: usize = 8;
+        let queues = Arc::new(ProjectQueues::new(1));
+        let start = Arc::new(tokio::sync::Barrier::new(WORKERS));
+        let active = Arc::new(AtomicUsize::new(0));
+        let overlaps = Arc::new(AtomicUsize::new(0));
+
+        let workers: Vec<_> = (0..WORKERS)
+            .map(|_| {
+                let (queues, start, active, overlaps) = (
+                    queues.clone(),
+                    start.clone(),
+                    active.clone(),
+                    overlaps.clone(),
+                );
+                tokio::spawn(async move {
+                    start.wait().await;
+                    // Requeue until the project is free, as reconcile does
+                    let permit = loop {
+                        match queues.try_acquire(&gcp("shared")) {
+                            Ok(permit) => break permit,
+                            Err(_) => tokio::time::sleep(Duration::from_millis(1)).await,
+                        }
+                    };
+                    if active.fetch_add(1, Ordering::SeqCst) > 0 {
+                        overlaps.fetch_add(1, Ordering::SeqCst);
+                    }
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    drop(permit);
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker
+                .await
+                .unwrap_or_else(|e| panic!("worker panicked: {e}"));
+        }
+        assert_eq!(overlaps.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,349 @@
+//! # Delta Reconcile
+//!
+//! Limits a reconcile to the profiles whose files changed since the last-synced revision.
+//!
+//! Raw file mode walks, parses and syncs every profile in the artifact on every reconcile,
+//! which makes monorepos with hundreds of services O(repo) each time. When the resource was
+//! last synced successfully from an artifact that is still on disk (see `ArtifactCache`), the
+//! old and new trees are compared and only profile directories containing changed files are
+//! processed. Everything else falls back to a full sync:
+//!
+//! - the `DeltaReconcile` feature gate is off, or kustomize mode is used (overlays can
+//!   reference any file in the tree)
+//! - the spec changed, or the last reconcile did not finish `Ready`
+//! - the revision is unchanged (timer reconciles keep correcting drift)
+//! - the previous artifact was evicted or the controller restarted
+
+use crate::crd::SecretManagerConfig;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+/// Feature gate enabling delta reconciles (`FEATURE_GATES=DeltaReconcile=true`)
+pub const DELTA_RECONCILE_GATE: &str = "DeltaReconcile";
+
+/// Extracted artifact a resource was last synced from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncedArtifact {
+    pub revision: String,
+    pub path: PathBuf,
+}
+
+/// Last successfully synced artifact per resource (identified by namespace/name)
+///
+/// Kept in memory only: after a restart the first reconcile of each resource is a full sync.
+#[derive(Debug, Default)]
+pub struct SyncedArtifacts {
+    artifacts: Mutex<HashMap<String, SyncedArtifact>>,
+}
+
+impl SyncedArtifacts {
+    /// Get the artifact a resource was last synced from
+    pub fn get(&self, resource_key: &str) -> Option<SyncedArtifact> {
+        self.artifacts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(resource_key)
+            .cloned()
+    }
+
+    /// Record the artifact a resource was just synced from
+    pub fn record(&self, resource_key: &str, artifact: SyncedArtifact) {
+        self.artifacts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(resource_key.to_string(), artifact);
+    }
+
+    /// Forget a resource (deleted, or synced without a revision)
+    pub fn remove(&self, resource_key: &str) {
+        self.artifacts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(resource_key);
+    }
+}
+
+/// Files added, modified or removed between two extracted artifacts
+/// Paths are relative to the artifact root
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedPaths {
+    paths: BTreeSet<PathBuf>,
+}
+
+impl ChangedPaths {
+    /// Compare two extracted artifact trees
+    pub fn between(old_root: &Path, new_root: &Path) -> Result<Self> {
+        let old_files = list_files(old_root)?;
+        let new_files = list_files(new_root)?;
+
+        let mut paths = BTreeSet::new();
+        for (relative, new_size) in &new_files {
+            let unchanged = match old_files.get(relative) {
+                Some(old_size) if old_size == new_size => {
+                    same_contents(&old_root.join(relative), &new_root.join(relative))?
+                }
+                _ => false,
+            };
+            if !unchanged {
+                paths.insert(relative.clone());
+            }
+        }
+        paths.extend(
+            old_files
+                .into_keys()
+                .filter(|relative| !new_files.contains_key(relative)),
+        );
+
+        Ok(Self { paths })
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Whether any file in `dir` (relative to the artifact root) or below it changed
+    #[must_use]
+    pub fn affects(&self, dir: &Path) -> bool {
+        self.paths.iter().any(|path| path.starts_with(dir))
+    }
+
+    /// Whether any file directly in `dir` (relative to the artifact root) changed
+    #[must_use]
+    pub fn affects_files_in(&self, dir: &Path) -> bool {
+        self.paths.iter().any(|path| path.parent() == Some(dir))
+    }
+}
+
+/// Regular files under `root` with their sizes, keyed by path relative to `root`
+fn list_files(root: &Path) -> Result<BTreeMap<PathBuf, u64>> {
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(root) {
+        let entry = entry.context(format!("Failed to walk artifact {}", root.display()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(root)
+            .context("Artifact entry outside artifact root")?
+            .to_path_buf();
+        let size = entry
+            .metadata()
+            .context(format!("Failed to stat {}", entry.path().display()))?
+            .len();
+        files.insert(relative, size);
+    }
+    Ok(files)
+}
+
+/// Compare two files of equal size chunk by chunk
+fn same_contents(a: &Path, b: &Path) -> Result<bool> {
+    let mut file_a = std::fs::File::open(a).context(format!("Failed to open {}", a.display()))?;
+    let mut file_b = std::fs::File::open(b).context(format!("Failed to open {}", b.display()))?;
+    let mut buffer_a = vec![0u8; 64 * 1024];
+    let mut buffer_b = vec![0u8; 64 * 1024];
+    loop {
+        let read = file_a.read(&mut buffer_a)?;
+        if read == 0 {
+            return Ok(true);
+        }
+        file_b.read_exact(&mut buffer_b[..read])?;
+        if buffer_a[..read] != buffer_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+/// Changed paths for this reconcile, or `None` when a full sync is required
+///
+/// `previous` is the artifact the resource was last synced from (see `SyncedArtifacts`).
+pub fn plan_delta(
+    config: &SecretManagerConfig,
+    previous: Option<&SyncedArtifact>,
+    artifact_path: &Path,
+    revision: Option<&str>,
+) -> Option<ChangedPaths> {
+    if config.spec.secrets.kustomize_path.is_some() {
+        return None;
+    }
+    let revision = revision?;
+    let previous = previous?;
+
+    let status = config.status.as_ref()?;
+    if status.phase.as_deref() != Some("Ready")
+        || status.observed_generation != config.metadata.generation
+        || status.last_synced_revision.as_deref() != Some(previous.revision.as_str())
+    {
+        debug!("Delta reconcile not possible: spec or status changed since the last sync");
+        return None;
+    }
+    if previous.revision == revision {
+        // Same revision: full sync so drift in the provider is still corrected
+        return None;
+    }
+    if !previous.path.is_dir() {
+        debug!(
+            "Delta reconcile not possible: previous artifact {} is no longer cached",
+            previous.path.display()
+        );
+        return None;
+    }
+
+    match ChangedPaths::between(&previous.path, artifact_path) {
+        Ok(changes) => Some(changes),
+        Err(e) => {
+            warn!(
+                "Failed to compare artifacts for delta reconcile, falling back to full sync: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, relative: &str, contents: &str) {
+        let path = root.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap_or_else(|e| panic!("{e}"));
+        }
+        std::fs::write(path, contents).unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    #[test]
+    fn test_changed_paths_between() {
+        let old = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+        let new = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+
+        let api = "api/deployment-configuration/profiles/dev/application.secrets.env";
+        let web = "web/deployment-configuration/profiles/dev/application.secrets.env";
+        let worker = "worker/deployment-configuration/profiles/dev/application.properties";
+        let removed = "old/deployment-configuration/profiles/dev/application.secrets.env";
+        write(old.path(), api, "A=1\n");
+        write(new.path(), api, "A=1\n");
+        write(old.path(), web, "B=1\n");
+        write(new.path(), web, "B=2\n");
+        write(new.path(), worker, "c=1\n");
+        write(old.path(), removed, "D=1\n");
+
+        let changes = ChangedPaths::between(old.path(), new.path())
+            .unwrap_or_else(|e| panic!("between: {e}"));
+        assert_eq!(changes.len(), 3);
+        assert!(!changes.affects(Path::new("api/deployment-configuration/profiles/dev")));
+        assert!(changes.affects(Path::new("web/deployment-configuration/profiles/dev")));
+        assert!(changes.affects(Path::new("worker/deployment-configuration/profiles/dev")));
+        assert!(changes.affects(Path::new("old")));
+        // Prefix matching is per path component
+        assert!(!changes.affects(Path::new("we")));
+        // Base layer directories only count their own files
+        assert!(!changes.affects_files_in(Path::new("web/deployment-configuration")));
+        assert!(changes.affects_files_in(Path::new("web/deployment-configuration/profiles/dev")));
+    }
+
+    #[test]
+    fn test_changed_paths_identical_trees() {
+        let old = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+        let new = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+        write(old.path(), "profiles/dev/application.secrets.env", "A=1\n");
+        write(new.path(), "profiles/dev/application.secrets.env", "A=1\n");
+
+        let changes = ChangedPaths::between(old.path(), new.path())
+            .unwrap_or_else(|e| panic!("between: {e}"));
+        assert!(changes.is_empty());
+    }
+
+    fn synced_config(last_synced_revision: Option<&str>) -> SecretManagerConfig {
+        let yaml = r#"
+apiVersion: secret-management.octopilot.io/v1beta1
+kind: SecretManagerConfig
+metadata:
+  name: app
+  namespace: default
+  generation: 2
+spec:
+  sourceRef:
+    name: repo
+    namespace: flux-system
+  provider:
+    gcp:
+      projectId: p
+      location: us-central1
+  secrets:
+    environment: dev
+"#;
+        let mut config: SecretManagerConfig =
+            serde_yaml::from_str(yaml).unwrap_or_else(|e| panic!("invalid fixture: {e}"));
+        config.status = Some(crate::crd::SecretManagerConfigStatus {
+            phase: Some("Ready".to_string()),
+            observed_generation: Some(2),
+            last_synced_revision: last_synced_revision.map(str::to_string),
+            ..Default::default()
+        });
+        config
+    }
+
+    #[test]
+    fn test_plan_delta() {
+        let old = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+        let new = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
+        write(old.path(), "profiles/dev/application.secrets.env", "A=1\n");
+        write(new.path(), "profiles/dev/application.secrets.env", "A=2\n");
+        let previous = SyncedArtifact {
+            revision: "main@sha1:old".to_string(),
+            path: old.path().to_path_buf(),
+        };
+
+        let config = synced_config(Some("main@sha1:old"));
+        let changes = plan_delta(&config, Some(&previous), new.path(), Some("main@sha1:new"))
+            .unwrap_or_else(|| panic!("delta expected"));
+        assert_eq!(changes.len(), 1);
+
+        // Same revision: full sync to keep correcting drift
+        assert!(plan_delta(&config, Some(&previous), new.path(), Some("main@sha1:old")).is_none());
+        // Nothing synced yet in this process
+        assert!(plan_delta(&config, None, new.path(), Some("main@sha1:new")).is_none());
+        // Status was synced from a different revision than the one on disk
+        let other = synced_config(Some("main@sha1:other"));
+        assert!(plan_delta(&other, Some(&previous), new.path(), Some("main@sha1:new")).is_none());
+
+        // Spec changed since the last sync
+        let mut changed_spec = synced_config(Some("main@sha1:old"));
+        changed_spec.metadata.generation = Some(3);
+        assert!(
+            plan_delta(
+                &changed_spec,
+                Some(&previous),
+                new.path(),
+                Some("main@sha1:new")
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_synced_artifacts_record_and_remove() {
+        let artifacts = SyncedArtifacts::default();
+        let artifact = SyncedArtifact {
+            revision: "main@sha1:abc".to_string(),
+            path: PathBuf::from("/tmp/smc/artifact-cache/sha256-abc/content"),
+        };
+        artifacts.record("default/app", artifact.clone());
+        assert_eq!(artifacts.get("default/app"), Some(artifact));
+        artifacts.remove("default/app");
+        assert!(artifacts.get("default/app").is_none());
+    }
+}
@@ -0,0 +1,47 @@
+//! # Kubernetes Events
+//!
+//! Publishes Events on `SecretManagerConfig` resources, so problems that need a change in Git
+//! show up in `kubectl describe` and event-based alerting, next to the status conditions.
+
+use crate::controller::reconciler::types::Reconciler;
+use crate::crd::SecretManagerConfig;
+use kube::Resource;
+use kube::runtime::events::{Event, EventType, Recorder, Reporter};
+use tracing::warn;
+
+/// Reporting controller on published events
+const REPORTING_CONTROLLER: &str = "secret-manager-controller";
+
+/// Publish a Warning event on `config`
+/// Best effort: a failure to publish is logged and never fails the reconciliation
+pub async fn publish_warning(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    reason: &str,
+    action: &str,
+    note: String,
+) {
+    let recorder = Recorder::new(
+        reconciler.client.clone(),
+        Reporter {
+            controller: REPORTING_CONTROLLER.to_string(),
+            instance: std::env::var("POD_NAME").ok(),
+        },
+    );
+    let event = Event {
+        type_: EventType::Warning,
+        reason: reason.to_string(),
+        note: Some(note),
+        action: action.to_string(),
+        secondary: None,
+    };
+    if let Err(e) = recorder.publish(&event, &config.object_ref(&())).await {
+        warn!(
+            "Failed to publish {} event for {}/{}: {}",
+            reason,
+            config.metadata.namespace.as_deref().unwrap_or("default"),
+            config.metadata.name.as_deref().unwrap_or("unknown"),
+            e
+        );
+    }
+}
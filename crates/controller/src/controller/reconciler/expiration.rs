@@ -0,0 +1,284 @@
+//! # Secret Expiration
+//!
+//! Expiry of the secrets a resource writes (`spec.secrets.expiration`).
+//!
+//! A write sets the secret's expiry to its ttl from now (Azure Key Vault `exp`, AWS tag, GCP
+//! `expireTime`) and records it in `status.sync.secrets`. Unchanged values keep the recorded
+//! expiry, so it only moves when the value is rotated in Git; a ttl shortened in the spec pulls
+//! later expiries in on the next sync. The recorded expiries drive the `ExpiringSoon` condition
+//! and the `secret_manager_secrets_expiring_soon` gauge, so rotation can be scheduled before the
+//! provider expires a secret.
+
+use crate::controller::parser::file_finder::glob_to_regex;
+use crate::crd::{KubeDuration, ResourceSyncState, SecretExpiration, SecretManagerConfig};
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Default `warnBefore`: 14 days
+pub const DEFAULT_WARN_BEFORE: Duration = Duration::from_secs(14 * 24 * 3600);
+
+/// Compiled `spec.secrets.expiration`
+#[derive(Debug)]
+pub struct ExpirationPolicy {
+    ttl: Option<Duration>,
+    keys: Vec<(Regex, Duration)>,
+    warn_before: Duration,
+}
+
+impl ExpirationPolicy {
+    /// Parse the durations and compile the key globs
+    ///
+    /// # Errors
+    /// Returns an error if a duration is malformed or a glob doesn't compile
+    pub fn new(expiration: &SecretExpiration) -> Result<Self> {
+        let ttl = expiration
+            .ttl
+            .as_ref()
+            .map(KubeDuration::to_duration)
+            .transpose()
+            .context("Invalid expiration.ttl")?;
+        let keys = expiration
+            .keys
+            .iter()
+            .flatten()
+            .map(|entry| {
+                let ttl = entry
+                    .ttl
+                    .to_duration()
+                    .with_context(|| format!("Invalid ttl for '{}'", entry.key_pattern))?;
+                Ok((glob_to_regex(&entry.key_pattern)?, ttl))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let warn_before = expiration
+            .warn_before
+            .as_ref()
+            .map(KubeDuration::to_duration)
+            .transpose()
+            .context("Invalid expiration.warnBefore")?
+            .unwrap_or(DEFAULT_WARN_BEFORE);
+        Ok(Self {
+            ttl,
+            keys,
+            warn_before,
+        })
+    }
+
+    /// ttl of `key`: the first matching `keys` entry, otherwise `ttl`
+    #[must_use]
+    pub fn ttl_for(&self, key: &str) -> Option<Duration> {
+        self.keys
+            .iter()
+            .find(|(re, _)| re.is_match(key))
+            .map(|(_, ttl)| *ttl)
+            .or(self.ttl)
+    }
+
+    /// How long before expiry `ExpiringSoon` is raised
+    #[must_use]
+    pub fn warn_before(&self) -> Duration {
+        self.warn_before
+    }
+
+    /// Expiry to set on `key`'s secret after a successful write, or None to keep `current`
+    /// A new value always gets a fresh expiry; an unchanged value only gets one when it has
+    /// none yet, or when its current expiry is later than the ttl allows.
+    #[must_use]
+    pub fn renewal(
+        &self,
+        key: &str,
+        was_updated: bool,
+        current: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let ttl = chrono::Duration::from_std(self.ttl_for(key)?).ok()?;
+        let desired = now + ttl;
+        match current.and_then(parse_expire_time) {
+            Some(current) if !was_updated && current <= desired => None,
+            _ => Some(desired),
+        }
+    }
+}
+
+/// A managed secret that expires within `warnBefore`, or has expired
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpiringSecret {
+    pub name: String,
+    pub expire_time: DateTime<Utc>,
+}
+
+impl fmt::Display for ExpiringSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} expires at {}",
+            self.name,
+            format_expire_time(self.expire_time)
+        )
+    }
+}
+
+/// Format an expiry the way it is recorded in status
+#[must_use]
+pub fn format_expire_time(expire_time: DateTime<Utc>) -> String {
+    expire_time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn parse_expire_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Synced secrets whose recorded expiry is within `warn_before` of `now`, soonest first
+#[must_use]
+pub fn expiring_soon(
+    synced_secrets: &HashMap<String, ResourceSyncState>,
+    warn_before: Duration,
+    now: DateTime<Utc>,
+) -> Vec<ExpiringSecret> {
+    let Ok(warn_before) = chrono::Duration::from_std(warn_before) else {
+        return Vec::new();
+    };
+    let mut expiring: Vec<ExpiringSecret> = synced_secrets
+        .iter()
+        .filter(|(_, state)| state.exists)
+        .filter_map(|(name, state)| {
+            let expire_time = parse_expire_time(state.expire_time.as_deref()?)?;
+            (expire_time - now <= warn_before).then(|| ExpiringSecret {
+                name: name.clone(),
+                expire_time,
+            })
+        })
+        .collect();
+    expiring.sort_by(|a, b| {
+        a.expire_time
+            .cmp(&b.expire_time)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    expiring
+}
+
+/// Expiring secrets of a resource after a sync
+/// Empty for resources without `secrets.expiration`, so a removed policy clears `ExpiringSoon`.
+#[must_use]
+pub fn expiring_secrets(
+    config: &SecretManagerConfig,
+    synced_secrets: &HashMap<String, ResourceSyncState>,
+) -> Vec<ExpiringSecret> {
+    let Some(expiration) = &config.spec.secrets.expiration else {
+        return Vec::new();
+    };
+    let warn_before = ExpirationPolicy::new(expiration)
+        .map(|policy| policy.warn_before())
+        .unwrap_or(DEFAULT_WARN_BEFORE);
+    expiring_soon(synced_secrets, warn_before, Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::KeyExpiration;
+
+    fn policy() -> ExpirationPolicy {
+        ExpirationPolicy::new(&SecretExpiration {
+            ttl: Some(KubeDuration::new("90d")),
+            warn_before: None,
+            keys: Some(vec![KeyExpiration {
+                key_pattern: "DB_*".to_string(),
+                ttl: KubeDuration::new("30d"),
+            }]),
+        })
+        .unwrap_or_else(|e| panic!("Failed to compile expiration: {e}"))
+    }
+
+    fn state(expire_time: Option<&str>) -> ResourceSyncState {
+        ResourceSyncState {
+            exists: true,
+            update_count: 0,
+            source_key: None,
+            tier: None,
+            expire_time: expire_time.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn test_ttl_for_prefers_key_entries() {
+        let policy = policy();
+        assert_eq!(
+            policy.ttl_for("DB_PASSWORD"),
+            Some(Duration::from_secs(30 * 24 * 3600))
+        );
+        assert_eq!(
+            policy.ttl_for("API_KEY"),
+            Some(Duration::from_secs(90 * 24 * 3600))
+        );
+        assert_eq!(policy.warn_before(), DEFAULT_WARN_BEFORE);
+
+        let keys_only = ExpirationPolicy::new(&SecretExpiration {
+            keys: Some(vec![KeyExpiration {
+                key_pattern: "DB_*".to_string(),
+                ttl: KubeDuration::new("30d"),
+            }]),
+            ..SecretExpiration::default()
+        })
+        .unwrap_or_else(|e| panic!("Failed to compile expiration: {e}"));
+        assert_eq!(keys_only.ttl_for("API_KEY"), None);
+    }
+
+    #[test]
+    fn test_renewal() {
+        let policy = policy();
+        let now = Utc::now();
+        let in_10_days = format_expire_time(now + chrono::Duration::days(10));
+        let in_60_days = format_expire_time(now + chrono::Duration::days(60));
+
+        // New values and secrets without an expiry get a fresh one
+        assert_eq!(
+            policy.renewal("API_KEY", true, Some(&in_10_days), now),
+            Some(now + chrono::Duration::days(90))
+        );
+        assert_eq!(
+            policy.renewal("API_KEY", false, None, now),
+            Some(now + chrono::Duration::days(90))
+        );
+        // Unchanged values keep their expiry...
+        assert_eq!(
+            policy.renewal("API_KEY", false, Some(&in_10_days), now),
+            None
+        );
+        // ...unless it is later than a shortened ttl allows
+        assert_eq!(
+            policy.renewal("DB_PASSWORD", false, Some(&in_60_days), now),
+            Some(now + chrono::Duration::days(30))
+        );
+    }
+
+    #[test]
+    fn test_expiring_soon() {
+        let now = Utc::now();
+        let synced = HashMap::from([
+            (
+                "soon".to_string(),
+                state(Some(&format_expire_time(now + chrono::Duration::days(3)))),
+            ),
+            (
+                "expired".to_string(),
+                state(Some(&format_expire_time(now - chrono::Duration::days(1)))),
+            ),
+            (
+                "later".to_string(),
+                state(Some(&format_expire_time(now + chrono::Duration::days(60)))),
+            ),
+            ("never".to_string(), state(None)),
+        ]);
+        let names: Vec<String> = expiring_soon(&synced, DEFAULT_WARN_BEFORE, now)
+            .into_iter()
+            .map(|secret| secret.name)
+            .collect();
+        assert_eq!(names, vec!["expired", "soon"]);
+    }
+}
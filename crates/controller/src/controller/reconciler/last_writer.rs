@@ -0,0 +1,439 @@
+//! # Last-Writer Conflict Detection
+//!
+//! Tells "changed by another actor" apart from "changed by an older write of this resource"
+//! when diff discovery finds a secret whose provider value differs from Git.
+//!
+//! With the `ConflictDetection` feature gate, every write that changes a secret is stamped with
+//! provider labels (GCP labels, AWS tags, Azure tags of the new version) naming the controller,
+//! the owning resource's UID, the source revision and the version that was written. When a
+//! secret has drifted, its labels are compared with the current version:
+//!
+//! - stamped by this resource at the current version: Git moved on since the last sync, which
+//!   is the normal update path
+//! - stamped by another `SecretManagerConfig`: two resources write the same secret
+//! - stamped at an older version, or not stamped at all: someone wrote it outside the controller
+//!
+//! Conflicts are reported on the `ConflictingWriter` condition, a Warning event and the
+//! `secret_manager_conflicting_writers_total` metric. Values are never hashed into labels, as
+//! labels are readable by anyone who can list secrets.
+
+use crate::crd::SecretManagerConfig;
+use crate::provider::{SecretManagerProvider, SecretMetadata, SecretVersionIds};
+use crate::secret::SecretString;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+use tracing::{debug, warn};
+
+/// Feature gate enabling last-writer stamps and conflict detection
+/// (`FEATURE_GATES=ConflictDetection=true`)
+pub const CONFLICT_DETECTION_GATE: &str = "ConflictDetection";
+
+/// Label naming the controller that last wrote a secret
+pub const WRITER_LABEL: &str = "secret-manager-writer";
+/// Label holding the UID of the resource that last wrote a secret
+pub const OWNER_UID_LABEL: &str = "secret-manager-owner-uid";
+/// Label holding the source revision a secret was last written from
+pub const REVISION_LABEL: &str = "secret-manager-revision";
+/// Label holding the version the controller wrote, when the provider reports version IDs
+pub const VERSION_LABEL: &str = "secret-manager-version";
+
+/// Value of `WRITER_LABEL` on secrets written by the controller
+const WRITER: &str = "secret-manager-controller";
+
+/// Longest label value GCP accepts
+const MAX_LABEL_VALUE_LEN: usize = 63;
+
+/// Who last wrote a secret, according to its labels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LastWriter {
+    /// This resource, at the secret's current version
+    ThisResource { revision: Option<String> },
+    /// Another `SecretManagerConfig`, identified by its UID
+    OtherResource { owner_uid: String },
+    /// Someone outside the controller (a newer version than the stamped one, or no stamp)
+    Foreign,
+}
+
+/// A drifted secret last written by someone other than its resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriterConflict {
+    pub secret: String,
+    pub writer: LastWriter,
+}
+
+impl WriterConflict {
+    /// Kind of conflicting writer (metric label): `foreign` or `resource`
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self.writer {
+            LastWriter::OtherResource { .. } => "resource",
+            LastWriter::ThisResource { .. } | LastWriter::Foreign => "foreign",
+        }
+    }
+}
+
+impl fmt::Display for WriterConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.writer {
+            LastWriter::OtherResource { owner_uid } => write!(
+                f,
+                "'{}' was last written by another SecretManagerConfig (uid {owner_uid})",
+                self.secret
+            ),
+            LastWriter::ThisResource { .. } | LastWriter::Foreign => {
+                write!(f, "'{}' was changed outside the controller", self.secret)
+            }
+        }
+    }
+}
+
+/// Make `value` a valid label value on every provider: lowercase `[a-z0-9_-]`, at most
+/// 63 characters
+#[must_use]
+pub fn sanitize_label_value(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| c.to_ascii_lowercase())
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(MAX_LABEL_VALUE_LEN)
+        .collect()
+}
+
+/// Classify the last writer of a secret from its labels and current version
+/// Unstamped secrets are foreign: the caller only asks about secrets this resource has
+/// synced before.
+#[must_use]
+pub fn classify(
+    labels: &HashMap<String, String>,
+    current_version: Option<&str>,
+    owner_uid: &str,
+) -> LastWriter {
+    if labels.get(WRITER_LABEL).map(String::as_str) != Some(WRITER) {
+        return LastWriter::Foreign;
+    }
+    match labels.get(OWNER_UID_LABEL) {
+        Some(owner) if owner != owner_uid => {
+            return LastWriter::OtherResource {
+                owner_uid: owner.clone(),
+            };
+        }
+        None => return LastWriter::Foreign,
+        Some(_) => {}
+    }
+    // A version added after the stamped one keeps the secret-level labels (GCP, AWS)
+    let stamped_version = labels.get(VERSION_LABEL);
+    if let (Some(stamped), Some(current)) = (stamped_version, current_version) {
+        if *stamped != sanitize_label_value(current) {
+            return LastWriter::Foreign;
+        }
+    }
+    LastWriter::ThisResource {
+        revision: labels.get(REVISION_LABEL).cloned(),
+    }
+}
+
+/// Stamps writes of one reconciliation and collects the conflicts diff discovery finds
+#[derive(Debug)]
+pub struct LastWriterTracker {
+    owner_uid: String,
+    revision: Option<String>,
+    conflicts: Mutex<BTreeMap<String, WriterConflict>>,
+}
+
+impl LastWriterTracker {
+    #[must_use]
+    pub fn new(config: &SecretManagerConfig, revision: Option<&str>) -> Self {
+        Self {
+            owner_uid: sanitize_label_value(config.metadata.uid.as_deref().unwrap_or_default()),
+            revision: revision.map(sanitize_label_value),
+            conflicts: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Labels stamped on a secret after writing `version`
+    #[must_use]
+    pub fn stamp(&self, version: Option<&str>) -> HashMap<String, String> {
+        let mut labels = HashMap::from([
+            (WRITER_LABEL.to_string(), WRITER.to_string()),
+            (OWNER_UID_LABEL.to_string(), self.owner_uid.clone()),
+        ]);
+        if let Some(ref revision) = self.revision {
+            labels.insert(REVISION_LABEL.to_string(), revision.clone());
+        }
+        if let Some(version) = version {
+            labels.insert(VERSION_LABEL.to_string(), sanitize_label_value(version));
+        }
+        labels
+    }
+
+    /// Find out who last wrote `secret_name`, which diff discovery found differs from Git,
+    /// and record a conflict unless it was this resource
+    /// Best effort: a failure to read the labels is logged and records nothing.
+    pub async fn check_drifted(&self, provider: &dyn SecretManagerProvider, secret_name: &str) {
+        let labels = match provider.get_secret_metadata(secret_name).await {
+            Ok(Some(metadata)) => metadata.labels,
+            Ok(None) => return,
+            Err(e) => {
+                warn!(
+                    "Failed to read labels of '{}' for conflict detection: {}",
+                    secret_name, e
+                );
+                return;
+            }
+        };
+        let current_version = match provider.version_ids(secret_name).await {
+            Ok(versions) => versions.current,
+            Err(e) => {
+                debug!(
+                    "Failed to read the current version of '{}' for conflict detection: {}",
+                    secret_name, e
+                );
+                None
+            }
+        };
+
+        let writer = classify(&labels, current_version.as_deref(), &self.owner_uid);
+        if let LastWriter::ThisResource { ref revision } = writer {
+            debug!(
+                "Secret '{}' was last written by this resource (revision {}); Git changed since",
+                secret_name,
+                revision.as_deref().unwrap_or("unknown")
+            );
+            return;
+        }
+        let conflict = WriterConflict {
+            secret: secret_name.to_string(),
+            writer,
+        };
+        warn!("⚠️  Conflicting writer: {}", conflict);
+        self.conflicts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(secret_name.to_string(), conflict);
+    }
+
+    /// Conflicts recorded so far, by secret name
+    #[must_use]
+    pub fn conflicts(&self) -> Vec<WriterConflict> {
+        self.conflicts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Provider wrapper stamping every write that changed a secret with last-writer labels
+/// Stamping is best effort: a failure is logged and never fails the write.
+pub struct LastWriterProvider {
+    inner: Box<dyn SecretManagerProvider>,
+    tracker: LastWriterTracker,
+}
+
+impl LastWriterProvider {
+    #[must_use]
+    pub fn new(inner: Box<dyn SecretManagerProvider>, tracker: LastWriterTracker) -> Self {
+        Self { inner, tracker }
+    }
+
+    async fn stamp(&self, secret_name: &str) {
+        let version = match self.inner.version_ids(secret_name).await {
+            Ok(versions) => versions.current,
+            Err(e) => {
+                debug!(
+                    "Failed to read the version written to '{}': {}",
+                    secret_name, e
+                );
+                None
+            }
+        };
+        let labels = self.tracker.stamp(version.as_deref());
+        if let Err(e) = self.inner.set_secret_labels(secret_name, &labels).await {
+            warn!(
+                "Failed to stamp last-writer labels on '{}': {}",
+                secret_name, e
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl SecretManagerProvider for LastWriterProvider {
+    async fn create_or_update_secret(
+        &self,
+        secret_name: &str,
+        secret_value: &SecretString,
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        let was_updated = self
+            .inner
+            .create_or_update_secret(secret_name, secret_value, environment, location)
+            .await?;
+        if was_updated {
+            self.stamp(secret_name).await;
+        }
+        Ok(was_updated)
+    }
+
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
+        self.inner.get_secret_value(secret_name).await
+    }
+
+    async fn get_secret_values(
+        &self,
+        secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        self.inner.get_secret_values(secret_names).await
+    }
+
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        let was_updated = self
+            .inner
+            .create_or_update_secret_binary(secret_name, secret_value, environment, location)
+            .await?;
+        if was_updated {
+            self.stamp(secret_name).await;
+        }
+        Ok(was_updated)
+    }
+
+    async fn get_secret_binary(&self, secret_name: &str) -> Result<Option<Vec<u8>>> {
+        self.inner.get_secret_binary(secret_name).await
+    }
+
+    async fn delete_secret(&self, secret_name: &str) -> Result<()> {
+        self.inner.delete_secret(secret_name).await
+    }
+
+    async fn disable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.inner.disable_secret(secret_name).await
+    }
+
+    async fn enable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.inner.enable_secret(secret_name).await
+    }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        self.inner.version_ids(secret_name).await
+    }
+
+    async fn set_secret_labels(
+        &self,
+        secret_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        self.inner.set_secret_labels(secret_name, labels).await
+    }
+
+    async fn set_secret_expiration(
+        &self,
+        secret_name: &str,
+        expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        self.inner
+            .set_secret_expiration(secret_name, expire_time)
+            .await
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        self.inner.list_secrets(prefix).await
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        self.inner.get_secret_metadata(secret_name).await
+    }
+
+    fn auditor(&self) -> Option<&crate::observability::audit::Auditor> {
+        self.inner.auditor()
+    }
+
+    fn rate_limiter(&self) -> Option<&crate::controller::reconciler::rate_limit::TokenBucket> {
+        self.inner.rate_limiter()
+    }
+
+    fn router(&self) -> Option<&crate::controller::reconciler::routing::KeyRouter> {
+        self.inner.router()
+    }
+
+    fn last_writer(&self) -> Option<&LastWriterTracker> {
+        Some(&self.tracker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamped(owner: &str, version: Option<&str>) -> HashMap<String, String> {
+        let mut labels = HashMap::from([
+            (WRITER_LABEL.to_string(), WRITER.to_string()),
+            (OWNER_UID_LABEL.to_string(), owner.to_string()),
+            (REVISION_LABEL.to_string(), "abc123".to_string()),
+        ]);
+        if let Some(version) = version {
+            labels.insert(VERSION_LABEL.to_string(), version.to_string());
+        }
+        labels
+    }
+
+    #[test]
+    fn test_classify_this_resource() {
+        assert_eq!(
+            classify(&stamped("uid-a", Some("3")), Some("3"), "uid-a"),
+            LastWriter::ThisResource {
+                revision: Some("abc123".to_string())
+            }
+        );
+        // Providers without version IDs rely on the owner alone
+        assert!(matches!(
+            classify(&stamped("uid-a", None), None, "uid-a"),
+            LastWriter::ThisResource { .. }
+        ));
+    }
+
+    #[test]
+    fn test_classify_foreign_writes() {
+        // Written after the controller's stamped version
+        assert_eq!(
+            classify(&stamped("uid-a", Some("3")), Some("4"), "uid-a"),
+            LastWriter::Foreign
+        );
+        // Never stamped (or a new Azure version without the controller's tags)
+        let labels = HashMap::from([("environment".to_string(), "dev".to_string())]);
+        assert_eq!(classify(&labels, Some("4"), "uid-a"), LastWriter::Foreign);
+    }
+
+    #[test]
+    fn test_classify_other_resource() {
+        assert_eq!(
+            classify(&stamped("uid-b", Some("3")), Some("3"), "uid-a"),
+            LastWriter::OtherResource {
+                owner_uid: "uid-b".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_sanitize_label_value() {
+        assert_eq!(sanitize_label_value("main@sha1:ABC123"), "main-sha1-abc123");
+        assert_eq!(sanitize_label_value(&"a".repeat(100)).len(), 63);
+    }
+}
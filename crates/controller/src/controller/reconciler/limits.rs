@@ -0,0 +1,190 @@
+//! # Value Limits
+//!
+//! Size and count guardrails checked before anything is written to the provider.
+//!
+//! A bad merge that commits a 10MB file, or a generator that explodes a profile into
+//! thousands of keys, would otherwise run straight into provider payload limits and quota.
+//! Each `SecretManagerConfig` gets limits (`spec.secrets.limits`, falling back to the
+//! controller-wide defaults) and a budget that is charged per service before its values are
+//! written. A service that would exceed the budget is not written and the reconcile fails
+//! with the `LimitExceeded` reason.
+
+use crate::crd::SecretManagerConfig;
+use thiserror::Error;
+
+/// Effective value limits for one resource (0 = unlimited)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValueLimits {
+    /// Maximum size of a single value in bytes
+    pub max_value_bytes: u64,
+    /// Maximum number of keys
+    pub max_keys: u64,
+    /// Maximum total size of all values in bytes
+    pub max_total_bytes: u64,
+}
+
+impl ValueLimits {
+    /// Resolve the limits for a resource from its spec and the controller-wide defaults
+    #[must_use]
+    pub fn resolve(
+        config: &SecretManagerConfig,
+        default_max_value_bytes: u64,
+        default_max_keys: u64,
+        default_max_total_bytes: u64,
+    ) -> Self {
+        let spec = config.spec.secrets.limits.as_ref();
+        Self {
+            max_value_bytes: spec
+                .and_then(|l| l.max_value_bytes)
+                .unwrap_or(default_max_value_bytes),
+            max_keys: spec.and_then(|l| l.max_keys).unwrap_or(default_max_keys),
+            max_total_bytes: spec
+                .and_then(|l| l.max_total_bytes)
+                .unwrap_or(default_max_total_bytes),
+        }
+    }
+}
+
+/// A resource's values are over one of its limits
+///
+/// Not transient: the same commit fails the same way, so the reconciler reports it with the
+/// `LimitExceeded` reason and waits for the next interval instead of backing off.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum LimitExceeded {
+    #[error("'{key}' is {bytes} bytes, over the limit of {limit} bytes (maxValueBytes)")]
+    ValueTooLarge { key: String, bytes: u64, limit: u64 },
+    #[error("{keys} keys, over the limit of {limit} keys (maxKeys)")]
+    TooManyKeys { keys: u64, limit: u64 },
+    #[error("values total {bytes} bytes, over the limit of {limit} bytes (maxTotalBytes)")]
+    TotalTooLarge { bytes: u64, limit: u64 },
+}
+
+impl LimitExceeded {
+    /// Name of the exceeded limit (metric label)
+    #[must_use]
+    pub fn limit(&self) -> &'static str {
+        match self {
+            Self::ValueTooLarge { .. } => "maxValueBytes",
+            Self::TooManyKeys { .. } => "maxKeys",
+            Self::TotalTooLarge { .. } => "maxTotalBytes",
+        }
+    }
+}
+
+/// Keys and bytes a resource has written during one reconcile
+#[derive(Debug)]
+pub struct LimitBudget {
+    limits: ValueLimits,
+    keys: u64,
+    bytes: u64,
+}
+
+impl LimitBudget {
+    /// Empty budget for `limits`
+    #[must_use]
+    pub fn new(limits: ValueLimits) -> Self {
+        Self {
+            limits,
+            keys: 0,
+            bytes: 0,
+        }
+    }
+
+    /// Charge a batch of values (key, size in bytes) that is about to be written
+    ///
+    /// The batch is charged all or nothing: if any limit would be exceeded, nothing is charged
+    /// and none of the batch should be written.
+    ///
+    /// # Errors
+    /// Returns the first limit the batch would exceed
+    pub fn charge<'a>(
+        &mut self,
+        values: impl IntoIterator<Item = (&'a str, u64)>,
+    ) -> Result<(), LimitExceeded> {
+        let mut keys = self.keys;
+        let mut bytes = self.bytes;
+        for (key, size) in values {
+            if self.limits.max_value_bytes > 0 && size > self.limits.max_value_bytes {
+                return Err(LimitExceeded::ValueTooLarge {
+                    key: key.to_string(),
+                    bytes: size,
+                    limit: self.limits.max_value_bytes,
+                });
+            }
+            keys += 1;
+            bytes = bytes.saturating_add(size);
+        }
+        if self.limits.max_keys > 0 && keys > self.limits.max_keys {
+            return Err(LimitExceeded::TooManyKeys {
+                keys,
+                limit: self.limits.max_keys,
+            });
+        }
+        if self.limits.max_total_bytes > 0 && bytes > self.limits.max_total_bytes {
+            return Err(LimitExceeded::TotalTooLarge {
+                bytes,
+                limit: self.limits.max_total_bytes,
+            });
+        }
+        self.keys = keys;
+        self.bytes = bytes;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(max_value_bytes: u64, max_keys: u64, max_total_bytes: u64) -> ValueLimits {
+        ValueLimits {
+            max_value_bytes,
+            max_keys,
+            max_total_bytes,
+        }
+    }
+
+    #[test]
+    fn test_charge_value_too_large() {
+        let mut budget = LimitBudget::new(limits(10, 0, 0));
+        assert!(budget.charge([("SMALL", 10)]).is_ok());
+        assert_eq!(
+            budget.charge([("OK", 1), ("HUGE", 11)]),
+            Err(LimitExceeded::ValueTooLarge {
+                key: "HUGE".to_string(),
+                bytes: 11,
+                limit: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn test_charge_accumulates_across_batches() {
+        let mut budget = LimitBudget::new(limits(0, 3, 100));
+        assert!(budget.charge([("A", 40), ("B", 40)]).is_ok());
+        assert_eq!(
+            budget.charge([("C", 40)]),
+            Err(LimitExceeded::TotalTooLarge {
+                bytes: 120,
+                limit: 100,
+            })
+        );
+        // The rejected batch wasn't charged
+        assert!(budget.charge([("C", 20)]).is_ok());
+        let exceeded = budget.charge([("D", 0)]);
+        assert_eq!(
+            exceeded,
+            Err(LimitExceeded::TooManyKeys { keys: 4, limit: 3 })
+        );
+        assert_eq!(
+            exceeded.as_ref().err().map(LimitExceeded::limit),
+            Some("maxKeys")
+        );
+    }
+
+    #[test]
+    fn test_zero_is_unlimited() {
+        let mut budget = LimitBudget::new(ValueLimits::default());
+        assert!(budget.charge([("A", u64::MAX), ("B", u64::MAX)]).is_ok());
+    }
+}
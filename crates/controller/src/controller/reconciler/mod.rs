@@ -20,10 +20,23 @@
 //! 5. Sync secrets to cloud provider
 //! 6. Update status
 
+pub mod approval;
 pub mod artifact;
+pub mod concurrency;
+pub mod delta;
+pub mod events;
+pub mod expiration;
+pub mod last_writer;
+pub mod limits;
+pub mod not_found_cache;
 pub mod notifications;
+pub mod policy;
+pub mod prefetch;
 pub mod processing;
+pub mod rate_limit;
 pub mod reconcile;
+pub mod routing;
+pub mod secret_watch;
 pub mod sops;
 pub mod source;
 pub mod status;
@@ -32,13 +45,12 @@ pub mod utils;
 pub mod validation;
 
 // Re-export public API
-pub use reconcile::reconcile;
-pub use sops::start_sops_key_watch;
-pub use source::start_source_watch;
-pub use status::{
-    check_sops_key_availability, update_all_resources_in_namespace, update_sops_key_status,
-};
+pub use reconcile::{reconcile, reconcile_or_busy};
+pub use status::{check_sops_key_availability, update_sops_key_status};
 pub use types::{BackoffState, Reconciler, ReconcilerError, TriggerSource};
 
 // Re-export utility functions for external use (including tests)
-pub use utils::{construct_secret_name, sanitize_secret_name};
+pub use utils::{
+    construct_secret_name, render_name_template, resolve_secret_name, sanitize_for_provider,
+    sanitize_secret_name,
+};
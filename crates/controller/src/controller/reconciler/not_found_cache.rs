@@ -0,0 +1,492 @@
+//! # Provider Not-Found Cache
+//!
+//! Remembers which secrets a resource's provider reported as not found, until the source
+//! revision changes.
+//!
+//! Every reconcile reads each key from the provider (existence checks, diff discovery,
+//! approval plans). Keys that don't exist there yet are read again on every interval while
+//! they stay missing: change sets awaiting approval, keys whose creation keeps failing, keys
+//! routed away. With the `ProviderNotFoundCache` feature gate, a read that found nothing is
+//! answered from memory for the rest of the revision, so steady-state reconciles of large
+//! resources only read the keys that exist.
+//!
+//! Entries are dropped when the controller writes the secret, and all entries of a resource
+//! when its source revision or provider config changes or the resource is deleted. A secret
+//! created outside the controller at the same revision is seen at the next revision (or after
+//! a restart). At `NOT_FOUND_CACHE_MAX_ENTRIES` the oldest entries make room for new ones.
+
+use crate::crd::ProviderConfig;
+use crate::provider::{SecretManagerProvider, SecretMetadata, SecretVersionIds};
+use crate::secret::SecretString;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Feature gate enabling the not-found cache (`FEATURE_GATES=ProviderNotFoundCache=true`)
+pub const PROVIDER_NOT_FOUND_CACHE_GATE: &str = "ProviderNotFoundCache";
+
+/// Secret names remembered across all resources; the oldest are evicted beyond this
+pub const NOT_FOUND_CACHE_MAX_ENTRIES: usize = 100_000;
+
+/// Not-found secrets of one resource, valid for one revision and provider config
+#[derive(Debug, Default)]
+struct ResourceEntries {
+    scope: String,
+    names: HashSet<String>,
+}
+
+/// Cached entries of every resource
+#[derive(Debug, Default)]
+struct Entries {
+    resources: HashMap<String, ResourceEntries>,
+    /// (resource, secret name) in insertion order, oldest first; pairs dropped since are
+    /// skipped when evicting
+    order: VecDeque<(String, String)>,
+    /// Secret names cached across all resources
+    len: usize,
+}
+
+impl Entries {
+    /// Drop every entry of `resource_key`
+    fn clear(&mut self, resource_key: &str) {
+        if let Some(entries) = self.resources.get_mut(resource_key) {
+            self.len -= entries.names.len();
+            entries.names.clear();
+        }
+    }
+
+    /// Drop the oldest cached secret name; false when nothing is cached
+    fn evict_oldest(&mut self) -> bool {
+        while let Some((resource_key, name)) = self.order.pop_front() {
+            if self
+                .resources
+                .get_mut(&resource_key)
+                .is_some_and(|entries| entries.names.remove(&name))
+            {
+                self.len -= 1;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Not-found secrets per resource (identified by namespace/name)
+#[derive(Debug)]
+pub struct NotFoundCache {
+    entries: Mutex<Entries>,
+    max_entries: usize,
+}
+
+impl Default for NotFoundCache {
+    fn default() -> Self {
+        Self::new(NOT_FOUND_CACHE_MAX_ENTRIES)
+    }
+}
+
+impl NotFoundCache {
+    #[must_use]
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: Mutex::new(Entries::default()),
+            max_entries,
+        }
+    }
+
+    /// Cache of one resource for this reconcile
+    /// Entries from another revision or provider config are dropped. Returns None without a
+    /// revision, since there is nothing to tell when the cache goes stale.
+    pub fn scope(
+        self: &Arc<Self>,
+        resource_key: &str,
+        provider: &ProviderConfig,
+        revision: Option<&str>,
+    ) -> Option<NotFoundScope> {
+        let revision = revision?;
+        let scope = format!(
+            "{revision}|{}",
+            serde_json::to_string(provider).unwrap_or_default()
+        );
+        let mut entries = self.lock();
+        if entries
+            .resources
+            .get(resource_key)
+            .is_none_or(|resource| resource.scope != scope)
+        {
+            entries.clear(resource_key);
+            entries
+                .resources
+                .entry(resource_key.to_string())
+                .or_default()
+                .scope = scope;
+        }
+        Some(NotFoundScope {
+            cache: Arc::clone(self),
+            resource_key: resource_key.to_string(),
+        })
+    }
+
+    /// Forget a deleted resource, so its entries don't outlive it
+    pub fn forget(&self, resource_key: &str) {
+        let mut entries = self.lock();
+        entries.clear(resource_key);
+        entries.resources.remove(resource_key);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Not-found cache of one resource
+#[derive(Debug, Clone)]
+pub struct NotFoundScope {
+    cache: Arc<NotFoundCache>,
+    resource_key: String,
+}
+
+impl NotFoundScope {
+    /// Whether `secret_name` was not found at this revision
+    #[must_use]
+    pub fn contains(&self, secret_name: &str) -> bool {
+        self.cache
+            .lock()
+            .resources
+            .get(&self.resource_key)
+            .is_some_and(|entries| entries.names.contains(secret_name))
+    }
+
+    /// Remember that `secret_name` was not found, evicting the oldest entries when full
+    pub fn insert(&self, secret_name: &str) {
+        let mut guard = self.cache.lock();
+        let entries = &mut *guard;
+        // Forgotten while this reconcile ran
+        if !entries.resources.contains_key(&self.resource_key) {
+            return;
+        }
+        while entries.len >= self.cache.max_entries && entries.evict_oldest() {}
+        if entries.len >= self.cache.max_entries {
+            return;
+        }
+        if let Some(resource) = entries.resources.get_mut(&self.resource_key)
+            && resource.names.insert(secret_name.to_string())
+        {
+            entries.len += 1;
+            entries
+                .order
+                .push_back((self.resource_key.clone(), secret_name.to_string()));
+        }
+        // Pairs dropped by writes and revision changes stay queued until evicted
+        if entries.order.len() > 2 * self.cache.max_entries {
+            let resources = &entries.resources;
+            entries.order.retain(|(resource_key, name)| {
+                resources
+                    .get(resource_key)
+                    .is_some_and(|resource| resource.names.contains(name))
+            });
+        }
+    }
+
+    /// Forget `secret_name`, e.g. because the controller just wrote it
+    pub fn remove(&self, secret_name: &str) {
+        let mut guard = self.cache.lock();
+        let entries = &mut *guard;
+        if let Some(resource) = entries.resources.get_mut(&self.resource_key)
+            && resource.names.remove(secret_name)
+        {
+            entries.len -= 1;
+        }
+    }
+}
+
+/// Provider wrapper answering reads of not-found secrets from the resource's cache
+pub struct NotFoundCachedProvider {
+    inner: Box<dyn SecretManagerProvider>,
+    scope: NotFoundScope,
+    provider_name: &'static str,
+}
+
+impl std::fmt::Debug for NotFoundCachedProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NotFoundCachedProvider")
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NotFoundCachedProvider {
+    #[must_use]
+    pub fn new(
+        inner: Box<dyn SecretManagerProvider>,
+        scope: NotFoundScope,
+        provider_name: &'static str,
+    ) -> Self {
+        Self {
+            inner,
+            scope,
+            provider_name,
+        }
+    }
+}
+
+#[async_trait]
+impl SecretManagerProvider for NotFoundCachedProvider {
+    async fn create_or_update_secret(
+        &self,
+        secret_name: &str,
+        secret_value: &SecretString,
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        self.scope.remove(secret_name);
+        self.inner
+            .create_or_update_secret(secret_name, secret_value, environment, location)
+            .await
+    }
+
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
+        if self.scope.contains(secret_name) {
+            crate::observability::metrics::increment_provider_not_found_cache_hits(
+                self.provider_name,
+            );
+            return Ok(None);
+        }
+        let value = self.inner.get_secret_value(secret_name).await?;
+        if value.is_none() {
+            self.scope.insert(secret_name);
+        }
+        Ok(value)
+    }
+
+    async fn get_secret_values(
+        &self,
+        secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        let (cached, uncached): (Vec<String>, Vec<String>) = secret_names
+            .iter()
+            .cloned()
+            .partition(|name| self.scope.contains(name));
+        let mut values = if uncached.is_empty() {
+            HashMap::new()
+        } else {
+            self.inner.get_secret_values(&uncached).await?
+        };
+        for (name, value) in &values {
+            if value.is_none() {
+                self.scope.insert(name);
+            }
+        }
+        for name in cached {
+            crate::observability::metrics::increment_provider_not_found_cache_hits(
+                self.provider_name,
+            );
+            values.insert(name, None);
+        }
+        Ok(values)
+    }
+
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        self.scope.remove(secret_name);
+        self.inner
+            .create_or_update_secret_binary(secret_name, secret_value, environment, location)
+            .await
+    }
+
+    async fn get_secret_binary(&self, secret_name: &str) -> Result<Option<Vec<u8>>> {
+        self.inner.get_secret_binary(secret_name).await
+    }
+
+    async fn delete_secret(&self, secret_name: &str) -> Result<()> {
+        self.inner.delete_secret(secret_name).await
+    }
+
+    async fn disable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.inner.disable_secret(secret_name).await
+    }
+
+    async fn enable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.inner.enable_secret(secret_name).await
+    }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        self.inner.version_ids(secret_name).await
+    }
+
+    async fn set_secret_labels(
+        &self,
+        secret_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        self.inner.set_secret_labels(secret_name, labels).await
+    }
+
+    async fn set_secret_expiration(
+        &self,
+        secret_name: &str,
+        expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        self.inner
+            .set_secret_expiration(secret_name, expire_time)
+            .await
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        self.inner.list_secrets(prefix).await
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        self.inner.get_secret_metadata(secret_name).await
+    }
+
+    fn auditor(&self) -> Option<&crate::observability::audit::Auditor> {
+        self.inner.auditor()
+    }
+
+    fn rate_limiter(&self) -> Option<&crate::controller::reconciler::rate_limit::TokenBucket> {
+        self.inner.rate_limiter()
+    }
+
+    fn router(&self) -> Option<&crate::controller::reconciler::routing::KeyRouter> {
+        self.inner.router()
+    }
+
+    fn last_writer(
+        &self,
+    ) -> Option<&crate::controller::reconciler::last_writer::LastWriterTracker> {
+        self.inner.last_writer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::providers::{FakeSecretManagerProvider, ProviderCall};
+
+    fn gcp(project_id: &str) -> ProviderConfig {
+        serde_json::from_value(serde_json::json!({
+            "type": "gcp",
+            "gcp": { "projectId": project_id, "location": "us-central1" }
+        }))
+        .unwrap_or_else(|e| panic!("Failed to build ProviderConfig: {e}"))
+    }
+
+    fn cached(
+        fake: &FakeSecretManagerProvider,
+        cache: &Arc<NotFoundCache>,
+        revision: &str,
+    ) -> NotFoundCachedProvider {
+        let scope = cache
+            .scope("team-a/app", &gcp("project"), Some(revision))
+            .unwrap_or_else(|| panic!("scope expected with a revision"));
+        NotFoundCachedProvider::new(Box::new(fake.clone()), scope, "gcp")
+    }
+
+    fn reads(fake: &FakeSecretManagerProvider) -> usize {
+        fake.calls()
+            .iter()
+            .filter(|call| matches!(call, ProviderCall::Get { .. }))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_not_found_reads_cached_until_revision_changes() {
+        let fake = FakeSecretManagerProvider::new().with_secret("existing", "value");
+        let cache = Arc::new(NotFoundCache::default());
+
+        let provider = cached(&fake, &cache, "sha1:a");
+        for _ in 0..3 {
+            assert_eq!(provider.get_secret_value("missing").await.ok(), Some(None));
+            assert_eq!(
+                provider.get_secret_value("existing").await.ok(),
+                Some(Some(SecretString::from("value")))
+            );
+        }
+        // One read of the missing key, every read of the existing one
+        assert_eq!(reads(&fake), 4);
+
+        // The next reconcile at the same revision still skips it
+        let provider = cached(&fake, &cache, "sha1:a");
+        assert_eq!(provider.get_secret_value("missing").await.ok(), Some(None));
+        assert_eq!(reads(&fake), 4);
+
+        // A new revision reads it again
+        let provider = cached(&fake, &cache, "sha1:b");
+        assert_eq!(provider.get_secret_value("missing").await.ok(), Some(None));
+        assert_eq!(reads(&fake), 5);
+    }
+
+    #[tokio::test]
+    async fn test_write_invalidates_not_found_entry() {
+        let fake = FakeSecretManagerProvider::new();
+        let cache = Arc::new(NotFoundCache::default());
+        let provider = cached(&fake, &cache, "sha1:a");
+
+        assert_eq!(provider.get_secret_value("key").await.ok(), Some(None));
+        assert!(
+            provider
+                .create_or_update_secret("key", &SecretString::from("value"), "dev", "us-central1")
+                .await
+                .is_ok()
+        );
+        assert_eq!(
+            provider.get_secret_value("key").await.ok(),
+            Some(Some(SecretString::from("value")))
+        );
+    }
+
+    #[test]
+    fn test_scope_resets_on_provider_change_and_is_bounded() {
+        let cache = Arc::new(NotFoundCache::new(2));
+        assert!(cache.scope("team-a/app", &gcp("project"), None).is_none());
+
+        let scope = cache
+            .scope("team-a/app", &gcp("project"), Some("sha1:a"))
+            .unwrap_or_else(|| panic!("scope expected"));
+        for name in ["a", "b", "c"] {
+            scope.insert(name);
+        }
+        assert!(!scope.contains("a"), "oldest entry evicted at the cap");
+        assert!(scope.contains("b") && scope.contains("c"));
+
+        let scope = cache
+            .scope("team-a/app", &gcp("other-project"), Some("sha1:a"))
+            .unwrap_or_else(|| panic!("scope expected"));
+        assert!(!scope.contains("b"));
+    }
+
+    #[test]
+    fn test_forget_drops_deleted_resource_and_frees_room() {
+        let cache = Arc::new(NotFoundCache::new(2));
+        let scope = |resource_key: &str| {
+            cache
+                .scope(resource_key, &gcp("project"), Some("sha1:a"))
+                .unwrap_or_else(|| panic!("scope expected"))
+        };
+        let deleted = scope("team-a/deleted");
+        deleted.insert("a");
+        deleted.insert("b");
+
+        cache.forget("team-a/deleted");
+        assert!(!deleted.contains("a"));
+        // A reconcile still running for the deleted resource doesn't bring it back
+        deleted.insert("c");
+        assert!(!deleted.contains("c"));
+
+        let live = scope("team-a/live");
+        live.insert("x");
+        live.insert("y");
+        assert!(live.contains("x") && live.contains("y"));
+        assert_eq!(cache.lock().len, 2);
+        assert!(!cache.lock().resources.contains_key("team-a/deleted"));
+    }
+}
@@ -0,0 +1,263 @@
+//! # Provider Prefetch
+//!
+//! Reads the current state of all of a resource's secrets in bulk before the sync loop.
+//!
+//! The sync loop reads each key from the provider several times (diff discovery, the
+//! existence check, the no-change check of the write). Providers with batch reads (AWS
+//! `BatchGetSecretValue`) or filtered listing (GCP) return the state of every key in a few
+//! calls up front, and [`PrefetchedProvider`] answers the loop's reads from that snapshot.
+//! Unchanged keys then need no per-key call at all; keys the bulk read left out are read one
+//! at a time as before.
+//!
+//! When the bulk read fails (e.g. an IAM policy without `secretsmanager:BatchGetSecretValue`)
+//! the snapshot is empty and every key is read one at a time.
+//!
+//! The snapshot holds every value of the resource for the whole sync, so values are kept as
+//! [`SecretString`]s and zeroized when the provider is dropped.
+
+use crate::provider::{SecretManagerProvider, SecretMetadata, SecretVersionIds};
+use crate::secret::SecretString;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::debug;
+
+/// Provider answering reads from a bulk snapshot taken at the start of the sync
+/// Writes, disables and enables drop the secret from the snapshot, so later reads see the
+/// provider again.
+pub struct PrefetchedProvider<'a> {
+    inner: &'a dyn SecretManagerProvider,
+    values: Mutex<HashMap<String, Option<SecretString>>>,
+}
+
+impl std::fmt::Debug for PrefetchedProvider<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrefetchedProvider")
+            .field("prefetched", &self.lock().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> PrefetchedProvider<'a> {
+    /// Read `secret_names` from `inner` in bulk
+    pub async fn load(inner: &'a dyn SecretManagerProvider, secret_names: &[String]) -> Self {
+        let values = if secret_names.is_empty() {
+            HashMap::new()
+        } else {
+            inner
+                .get_secret_values(secret_names)
+                .await
+                .unwrap_or_else(|e| {
+                    debug!(
+                        "Bulk read of {} secrets failed, reading one at a time: {e}",
+                        secret_names.len()
+                    );
+                    HashMap::new()
+                })
+        };
+        Self {
+            inner,
+            values: Mutex::new(values),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Option<SecretString>>> {
+        self.values
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn prefetched(&self, secret_name: &str) -> Option<Option<SecretString>> {
+        self.lock().get(secret_name).cloned()
+    }
+
+    fn prefetched_matches(&self, secret_name: &str, secret_value: &SecretString) -> bool {
+        self.lock()
+            .get(secret_name)
+            .and_then(Option::as_ref)
+            .is_some_and(|current| current == secret_value)
+    }
+
+    fn forget(&self, secret_name: &str) {
+        self.lock().remove(secret_name);
+    }
+}
+
+#[async_trait]
+impl SecretManagerProvider for PrefetchedProvider<'_> {
+    async fn create_or_update_secret(
+        &self,
+        secret_name: &str,
+        secret_value: &SecretString,
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        // The provider would read the same value and skip the write
+        if self.prefetched_matches(secret_name, secret_value) {
+            return Ok(false);
+        }
+        self.forget(secret_name);
+        self.inner
+            .create_or_update_secret(secret_name, secret_value, environment, location)
+            .await
+    }
+
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
+        match self.prefetched(secret_name) {
+            Some(value) => Ok(value),
+            None => self.inner.get_secret_value(secret_name).await,
+        }
+    }
+
+    async fn get_secret_values(
+        &self,
+        secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        self.inner.get_secret_values(secret_names).await
+    }
+
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        self.forget(secret_name);
+        self.inner
+            .create_or_update_secret_binary(secret_name, secret_value, environment, location)
+            .await
+    }
+
+    async fn get_secret_binary(&self, secret_name: &str) -> Result<Option<Vec<u8>>> {
+        self.inner.get_secret_binary(secret_name).await
+    }
+
+    async fn delete_secret(&self, secret_name: &str) -> Result<()> {
+        self.forget(secret_name);
+        self.inner.delete_secret(secret_name).await
+    }
+
+    async fn disable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.forget(secret_name);
+        self.inner.disable_secret(secret_name).await
+    }
+
+    async fn enable_secret(&self, secret_name: &str) -> Result<bool> {
+        // Disabled secrets can't be read on any provider, so a readable secret is enabled
+        if self.lock().get(secret_name).is_some_and(Option::is_some) {
+            return Ok(false);
+        }
+        self.forget(secret_name);
+        self.inner.enable_secret(secret_name).await
+    }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        self.inner.version_ids(secret_name).await
+    }
+
+    async fn set_secret_labels(
+        &self,
+        secret_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        self.inner.set_secret_labels(secret_name, labels).await
+    }
+
+    async fn set_secret_expiration(
+        &self,
+        secret_name: &str,
+        expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        self.inner
+            .set_secret_expiration(secret_name, expire_time)
+            .await
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        self.inner.list_secrets(prefix).await
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        self.inner.get_secret_metadata(secret_name).await
+    }
+
+    fn auditor(&self) -> Option<&crate::observability::audit::Auditor> {
+        self.inner.auditor()
+    }
+
+    fn rate_limiter(&self) -> Option<&crate::controller::reconciler::rate_limit::TokenBucket> {
+        self.inner.rate_limiter()
+    }
+
+    fn planner(&self) -> Option<&crate::controller::reconciler::approval::ChangePlanner> {
+        self.inner.planner()
+    }
+
+    fn router(&self) -> Option<&crate::controller::reconciler::routing::KeyRouter> {
+        self.inner.router()
+    }
+
+    fn last_writer(
+        &self,
+    ) -> Option<&crate::controller::reconciler::last_writer::LastWriterTracker> {
+        self.inner.last_writer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::providers::{FakeSecretManagerProvider, ProviderCall};
+
+    #[tokio::test]
+    async fn test_reads_answered_from_snapshot() {
+        let fake = FakeSecretManagerProvider::new().with_secret("unchanged", "value");
+        let names = vec!["unchanged".to_string(), "missing".to_string()];
+        let provider = PrefetchedProvider::load(&fake, &names).await;
+
+        assert_eq!(
+            provider.get_secret_value("unchanged").await.ok(),
+            Some(Some(SecretString::from("value")))
+        );
+        assert_eq!(provider.get_secret_value("missing").await.ok(), Some(None));
+        assert_eq!(
+            provider
+                .create_or_update_secret(
+                    "unchanged",
+                    &SecretString::from("value"),
+                    "dev",
+                    "us-central1"
+                )
+                .await
+                .ok(),
+            Some(false)
+        );
+        assert_eq!(provider.enable_secret("unchanged").await.ok(), Some(false));
+        assert_eq!(fake.calls(), vec![ProviderCall::GetMany { names }]);
+
+        // A write drops the snapshot entry, so the next read goes to the provider
+        assert_eq!(
+            provider
+                .create_or_update_secret(
+                    "missing",
+                    &SecretString::from("new"),
+                    "dev",
+                    "us-central1"
+                )
+                .await
+                .ok(),
+            Some(true)
+        );
+        assert_eq!(
+            provider.get_secret_value("missing").await.ok(),
+            Some(Some(SecretString::from("new")))
+        );
+        assert!(matches!(
+            fake.calls().last(),
+            Some(ProviderCall::Get { name }) if name == "missing"
+        ));
+    }
+}
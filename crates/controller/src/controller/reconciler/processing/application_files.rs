@@ -3,23 +3,39 @@
 //! Main orchestration for processing application files (secrets and properties).
 
 use crate::controller::parser;
-use crate::controller::parser::sops::is_sops_encrypted_impl;
+use crate::controller::parser::sops::{
+    KmsCredentials, check_recipients, detect_key_sources, is_sops_encrypted_impl,
+    requires_private_key,
+};
+use crate::controller::reconciler::limits::LimitBudget;
+use crate::controller::reconciler::policy::{KeyPolicy, PolicyValue, ValueSource};
+use crate::controller::reconciler::sops::SopsKeyRing;
 use crate::controller::reconciler::status::update_decryption_status;
 use crate::controller::reconciler::types::Reconciler;
-use crate::crd::{ProviderConfig, SecretManagerConfig};
+use crate::crd::{ProviderConfig, SecretGrouping, SecretManagerConfig};
 use crate::observability;
 use crate::provider::SecretManagerProvider;
 use anyhow::Result;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{Instrument, debug, error, info_span, warn};
 
+use super::binary_files::store_binary_secrets;
+use super::certificates::store_certificates;
+use super::grouping::{
+    GROUPED_SECRET_KEY, split_groups, store_grouped_secrets, synced_secrets_from_status,
+};
 use super::properties::store_properties;
 use super::secrets::store_secrets;
 
 /// Process application files (secrets and properties)
 /// Parses application.secrets.env, application.secrets.yaml, and application.properties files
 /// Stores secrets in cloud provider secret store and properties in config store (if enabled)
+/// Returns (count, synced_secrets, synced_properties, failed_secrets); failed writes are
+/// collected per key instead of aborting the service
+/// Nothing is written if the service's values break the resource's `policy` or exceed its
+/// `budget`
 #[allow(
     clippy::too_many_lines,
     reason = "Complex file processing logic with SOPS handling and multiple provider paths"
@@ -29,10 +45,13 @@ pub async fn process_application_files(
     provider: &dyn SecretManagerProvider,
     config: &SecretManagerConfig,
     app_files: &parser::ApplicationFiles,
+    policy: Option<&KeyPolicy>,
+    budget: &mut LimitBudget,
 ) -> Result<(
     i32,
     std::collections::HashMap<String, crate::crd::ResourceSyncState>,
     std::collections::HashMap<String, crate::crd::ResourceSyncState>,
+    Vec<crate::crd::FailedSecret>,
 )> {
     let service_name = config
         .spec
@@ -48,26 +67,74 @@ pub async fn process_application_files(
         let secret_prefix = service_name;
 
         // Check if any files are SOPS-encrypted to determine if we need to track decryption status
-        let has_sops_files = {
+        // Files encrypted only with cloud KMS keys (GCP KMS, AWS KMS, Azure Key Vault) don't
+        // need a private key - the controller's workload identity unwraps the data key
+        // Recipients are compared with the repository's `.sops.yaml` creation rules; mismatches
+        // are reported once the files decrypted
+        let (has_sops_files, needs_private_key, recipient_mismatches) = {
             let mut has_sops = false;
-            if let Some(ref path) = app_files.secrets_env {
+            let mut needs_key = false;
+            let mut mismatches = Vec::new();
+            for path in app_files.secrets_files() {
                 if let Ok(content) = tokio::fs::read_to_string(path).await {
-                    has_sops = is_sops_encrypted_impl(&content);
+                    if is_sops_encrypted_impl(&content) {
+                        has_sops = true;
+                        needs_key |= requires_private_key(&detect_key_sources(&content));
+                        match check_recipients(path, &content) {
+                            Ok(Some(mismatch)) => mismatches.push(mismatch),
+                            Ok(None) => {}
+                            Err(e) => warn!(
+                                "Skipping .sops.yaml recipient check of {}: {e:#}",
+                                path.display()
+                            ),
+                        }
+                    }
                 }
             }
-            if !has_sops {
-                if let Some(ref path) = app_files.secrets_yaml {
-                    if let Ok(content) = tokio::fs::read_to_string(path).await {
-                        has_sops = is_sops_encrypted_impl(&content);
+            (has_sops, needs_key, mismatches)
+        };
+
+        // Load the resource's own SOPS key, if it references one
+        // Read on every reconciliation so changes to the referenced secret are picked up
+        let resource_sops_key = match config.spec.sops_key_secret_ref {
+            Some(ref key_ref) if has_sops_files => {
+                let resource_namespace = config.metadata.namespace.as_deref().unwrap_or("default");
+                match crate::controller::reconciler::sops::load_sops_key_ref(
+                    &reconciler.client,
+                    resource_namespace,
+                    key_ref,
+                )
+                .await
+                {
+                    Ok(key) => Some(key),
+                    Err(e) => {
+                        let error_msg = format!("SOPS decryption not available: {e}");
+                        error!("{}", error_msg);
+
+                        // Update decryption status
+                        if let Err(e) = update_decryption_status(
+                            reconciler,
+                            config,
+                            "PermanentFailure",
+                            Some(&error_msg),
+                        )
+                        .await
+                        {
+                            warn!("Failed to update decryption status: {}", e);
+                        }
+
+                        return Err(anyhow::anyhow!("{}", error_msg));
                     }
                 }
             }
-            has_sops
+            _ => None,
         };
 
         // Check SOPS capability and key availability before attempting decryption
         // This separates "system readiness" from "data decryption" concerns
-        if has_sops_files {
+        // Skipped when the resource brings its own key via sopsKeySecretRef, or when
+        // all SOPS files are encrypted with cloud KMS keys only
+        if has_sops_files && needs_private_key && resource_sops_key.is_none() {
             // Check global SOPS capability (bootstrap flag)
             if !reconciler.sops_capability_ready.load(std::sync::atomic::Ordering::Relaxed) {
                 // SOPS is not configured globally - this is a permanent failure
@@ -173,18 +240,39 @@ pub async fn process_application_files(
             }
         }
 
-        // Get SOPS private key from Arc<AsyncMutex> for this reconciliation
-        // Key is available (either from controller namespace or resource namespace)
-        let sops_private_key = {
-            let key_guard = reconciler.sops_private_key.lock().await;
-            key_guard.clone() // Clone the Option<String> to avoid lifetime issues
+        // Snapshot the SOPS key ring from Arc<AsyncMutex> for this reconciliation
+        // The resource's own key (if any) is tried first, then the ring newest first
+        let sops_key_ring = {
+            let ring_guard = reconciler.sops_key_ring.lock().await;
+            let mut ring = ring_guard.clone();
+            if let Some(key) = resource_sops_key {
+                ring.prepend(key);
+            }
+            ring
         };
 
         // Parse secrets with enabled/disabled state - handle SOPS decryption errors with proper classification
-        let parsed_secrets = match parser::parse_secrets_with_state(app_files, sops_private_key.as_deref()).await {
+        // Per-resource workload identity passed to sops for cloud KMS decryption
+        let kms_credentials = KmsCredentials::from_provider(&config.spec.provider);
+        // Provider name for metrics and for selecting x-target documents
+        let provider_name = match &config.spec.provider {
+            ProviderConfig::Gcp(_) => "gcp",
+            ProviderConfig::Aws(_) => "aws",
+            ProviderConfig::Azure(_) => "azure",
+        };
+
+        let mut parsed_secrets = match parse_secrets_with_key_ring(app_files, &sops_key_ring, &kms_credentials, provider_name).await {
             Ok(secrets) => {
                 // Update decryption status on success (if SOPS files were processed)
                 if has_sops_files {
+                    reconciler.recipient_checks.record(
+                        &format!(
+                            "{}/{}",
+                            config.metadata.namespace.as_deref().unwrap_or("default"),
+                            config.metadata.name.as_deref().unwrap_or("unknown")
+                        ),
+                        recipient_mismatches,
+                    );
                     if let Err(e) = update_decryption_status(
                         reconciler,
                         config,
@@ -254,6 +342,10 @@ pub async fn process_application_files(
                 }
             }
         };
+        if config.spec.secrets.resolve_placeholders == Some(true) {
+            parser::resolve_placeholders(&mut parsed_secrets.secrets)
+                .map_err(|e| anyhow::anyhow!("Failed to resolve placeholders: {e}"))?;
+        }
         let properties = parser::parse_properties(app_files).await?;
 
         // Debug: Log keys (not values) for debugging
@@ -297,14 +389,71 @@ pub async fn process_application_files(
             debug!("📋 No properties found in application.properties");
         }
 
-        // Store secrets in cloud provider (GitOps: Git is source of truth)
-        // Get provider name for metrics
-        let provider_name = match &config.spec.provider {
-            ProviderConfig::Gcp(_) => "gcp",
-            ProviderConfig::Aws(_) => "aws",
-            ProviderConfig::Azure(_) => "azure",
+        // Binary files are checked against the policy and limits by file name and size
+        let binary_sizes: Vec<(String, u64)> = match config.spec.secrets.binary_files.as_ref() {
+            Some(patterns) => parser::find_binary_files(&app_files.base_path, patterns)?
+                .into_iter()
+                .filter_map(|path| {
+                    let size = std::fs::metadata(&path).ok()?.len();
+                    let name = path.file_name()?.to_str()?.to_string();
+                    Some((name, size))
+                })
+                .collect(),
+            None => Vec::new(),
         };
 
+        // Check the resource's policy before anything of this service is written
+        // Binary files are always committed in plain text
+        if let Some(policy) = policy {
+            policy.check(
+                parsed_secrets
+                    .secrets
+                    .iter()
+                    .filter(|(_, entry)| entry.enabled)
+                    .map(|(key, entry)| PolicyValue {
+                        key,
+                        value: entry.value.expose_secret(),
+                        source: if entry.encrypted {
+                            ValueSource::Encrypted
+                        } else {
+                            ValueSource::Plaintext
+                        },
+                    })
+                    .chain(properties.iter().map(|(key, value)| PolicyValue {
+                        key,
+                        value,
+                        source: ValueSource::Properties,
+                    }))
+                    .chain(binary_sizes.iter().map(|(name, _)| PolicyValue {
+                        key: name,
+                        value: "",
+                        source: ValueSource::Plaintext,
+                    })),
+            )?;
+        }
+
+        // Charge everything this service writes against the resource's limits up front,
+        // so an oversized commit fails before any provider call
+        budget.charge(
+            parsed_secrets
+                .secrets
+                .iter()
+                .filter(|(_, entry)| entry.enabled)
+                .map(|(key, entry)| (key.as_str(), entry.value.len() as u64))
+                .chain(
+                    properties
+                        .iter()
+                        .map(|(key, value)| (key.as_str(), value.len() as u64)),
+                )
+                .chain(
+                    binary_sizes
+                        .iter()
+                        .map(|(name, size)| (name.as_str(), *size)),
+                ),
+        )?;
+
+        // Store secrets in cloud provider (GitOps: Git is source of truth)
+
         let publish_span = info_span!(
             "secrets.publish",
             provider = provider_name,
@@ -314,14 +463,60 @@ pub async fn process_application_files(
         let publish_start = Instant::now();
 
         // Store secrets using extracted module
-        let (secret_count, _drift_detected, synced_secrets) = store_secrets(
+        // SingleJson grouping stores all keys as one JSON secret instead of one secret per key;
+        // keys of x-target.group documents are stored as one JSON secret per group either way
+        // Failed writes are collected per key so one bad secret doesn't block the rest
+        let (ungrouped, groups) = split_groups(&parsed_secrets);
+        let (mut secret_count, _drift_detected, mut synced_secrets, mut failed_secrets) =
+            if config.spec.secrets.grouping == Some(SecretGrouping::SingleJson) {
+                let mut synced = synced_secrets_from_status(config);
+                let (count, failed) = store_grouped_secrets(
+                    provider,
+                    config,
+                    &ungrouped,
+                    GROUPED_SECRET_KEY,
+                    secret_prefix,
+                    provider_name,
+                    &mut synced,
+                )
+                .await?;
+                (count, false, synced, failed)
+            } else {
+                store_secrets(provider, config, &ungrouped, secret_prefix, provider_name).await?
+            };
+        for (group, secrets) in &groups {
+            let (count, failed) = store_grouped_secrets(
+                provider,
+                config,
+                secrets,
+                group,
+                secret_prefix,
+                provider_name,
+                &mut synced_secrets,
+            )
+            .await?;
+            secret_count += count;
+            failed_secrets.extend(failed);
+        }
+
+        // Store binary files (certs, keystores) matched by secrets.binaryFiles
+        let (binary_count, synced_binary_secrets, failed_binary_secrets) = store_binary_secrets(
             provider,
             config,
-            &parsed_secrets,
+            app_files,
             secret_prefix,
             provider_name,
         )
         .await?;
+        synced_secrets.extend(synced_binary_secrets);
+        failed_secrets.extend(failed_binary_secrets);
+
+        // Import certificate files into Key Vault's certificate store (Azure only)
+        let (certificate_count, synced_certificates, failed_certificates) =
+            store_certificates(config, app_files, secret_prefix, &reconciler.client).await?;
+        synced_secrets.extend(synced_certificates);
+        failed_secrets.extend(failed_certificates);
+        let secret_count = secret_count + binary_count + certificate_count;
         // Note: drift_detected is returned for future notification support
         // synced_secrets tracks which secrets have been pushed (exists=true) and how many times updated (update_count)
 
@@ -330,9 +525,22 @@ pub async fn process_application_files(
             "operation.duration_ms",
             publish_start.elapsed().as_millis() as u64,
         );
-        publish_span.record("operation.success", true);
+        publish_span.record("operation.success", failed_secrets.is_empty());
         publish_span.record("secrets.published", secret_count as u64);
 
+        // Keys stored as their own secret, which properties may reference with {{secretRef: KEY}}
+        let managed_secrets: HashSet<&str> =
+            if config.spec.secrets.grouping == Some(SecretGrouping::SingleJson) {
+                HashSet::new()
+            } else {
+                ungrouped
+                    .secrets
+                    .iter()
+                    .filter(|(_, entry)| entry.enabled)
+                    .map(|(key, _)| key.as_str())
+                    .collect()
+            };
+
         // Store properties using extracted module
         let (property_count, synced_properties) = store_properties(
             reconciler,
@@ -340,18 +548,19 @@ pub async fn process_application_files(
             config,
             properties,
             secret_prefix,
+            &managed_secrets,
         )
         .await?;
 
         let total_count = secret_count + property_count;
         observability::metrics::increment_secrets_synced(i64::from(total_count));
-        Ok((total_count, synced_secrets, synced_properties))
+        Ok((total_count, synced_secrets, synced_properties, failed_secrets))
     }
     .instrument(span)
     .await;
 
     match &result {
-        Ok((count, _, _)) => {
+        Ok((count, _, _, _)) => {
             span_clone_for_match.record("files.count", *count as u64);
             span_clone_for_match
                 .record("operation.duration_ms", start.elapsed().as_millis() as u64);
@@ -367,3 +576,53 @@ pub async fn process_application_files(
 
     result
 }
+
+/// Parse secrets, trying each SOPS key in the ring in order
+/// Falls back to the next key only when decryption failed because of the key itself
+/// (wrong key, key not found, invalid key), so rotated-out keys keep working until removed
+async fn parse_secrets_with_key_ring(
+    app_files: &parser::ApplicationFiles,
+    key_ring: &SopsKeyRing,
+    kms_credentials: &KmsCredentials,
+    provider: &str,
+) -> Result<parser::ParsedSecrets, parser::ParseSecretsError> {
+    let Some((first, rest)) = key_ring.keys().split_first() else {
+        return parser::parse_secrets_with_credentials(
+            app_files,
+            None,
+            kms_credentials,
+            Some(provider),
+        )
+        .await;
+    };
+
+    let mut result = parser::parse_secrets_with_credentials(
+        app_files,
+        Some(first.private_key.expose_secret()),
+        kms_credentials,
+        Some(provider),
+    )
+    .await;
+    for key in rest {
+        match result {
+            Err(ref e)
+                if e.as_sops_error()
+                    .is_some_and(|sops_err| sops_err.reason.is_key_error()) =>
+            {
+                debug!(
+                    "SOPS decryption failed with current key, falling back to key from secret '{}/{}'",
+                    key.namespace, key.secret_name
+                );
+                result = parser::parse_secrets_with_credentials(
+                    app_files,
+                    Some(key.private_key.expose_secret()),
+                    kms_credentials,
+                    Some(provider),
+                )
+                .await;
+            }
+            _ => break,
+        }
+    }
+    result
+}
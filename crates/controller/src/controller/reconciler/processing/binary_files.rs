@@ -0,0 +1,133 @@
+//! # Binary Secret Storage
+//!
+//! Handles syncing binary files (certificates, keystores, p12 bundles) matched by
+//! `secrets.binaryFiles` globs as binary secret payloads.
+
+use crate::controller::parser;
+use crate::controller::reconciler::utils::{
+    check_name_collisions, resolve_secret_name, sanitized_source_key,
+};
+use crate::crd::{FailedSecret, ProviderConfig, ResourceSyncState, SecretManagerConfig};
+use crate::observability;
+use crate::provider::SecretManagerProvider;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use tracing::{debug, error, info};
+
+/// Store binary files from the profile directory as binary secrets
+/// The file name (sanitized) is used as the secret key
+/// Returns (count, synced_secrets_map, failed_secrets) where synced_secrets tracks push state
+pub async fn store_binary_secrets(
+    provider: &dyn SecretManagerProvider,
+    config: &SecretManagerConfig,
+    app_files: &parser::ApplicationFiles,
+    secret_prefix: &str,
+    provider_name: &str,
+) -> Result<(i32, HashMap<String, ResourceSyncState>, Vec<FailedSecret>)> {
+    let mut synced_secrets = HashMap::new();
+
+    let Some(patterns) = config.spec.secrets.binary_files.as_ref() else {
+        return Ok((0, synced_secrets, Vec::new()));
+    };
+
+    let files = parser::find_binary_files(&app_files.base_path, patterns)
+        .context("Failed to find binary files")?;
+    if files.is_empty() {
+        debug!(
+            "📋 No binary files matching {:?} in {}",
+            patterns,
+            app_files.base_path.display()
+        );
+        return Ok((0, synced_secrets, Vec::new()));
+    }
+
+    // File names that differ only in characters the provider disallows would overwrite each other
+    check_name_collisions(
+        config,
+        secret_prefix,
+        files
+            .iter()
+            .filter_map(|path| path.file_name().and_then(|n| n.to_str())),
+    )?;
+
+    let environment = &config.spec.secrets.environment;
+    // Same location semantics as string secrets: empty/"automatic" means automatic replication on GCP
+    let location = match &config.spec.provider {
+        ProviderConfig::Gcp(gcp_config) => {
+            if gcp_config.location.is_empty() || gcp_config.location == "automatic" {
+                String::new()
+            } else {
+                gcp_config.location.clone()
+            }
+        }
+        ProviderConfig::Aws(aws_config) => aws_config.region.clone(),
+        ProviderConfig::Azure(azure_config) => azure_config.location.clone(),
+    };
+
+    let existing_state = config
+        .status
+        .as_ref()
+        .and_then(|s| s.sync.as_ref())
+        .and_then(|sync| sync.secrets.as_ref());
+
+    let mut count = 0;
+    let mut failed_secrets = Vec::new();
+
+    for path in files {
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let secret_name = resolve_secret_name(config, secret_prefix, file_name);
+        if let Some(router) = provider.router() {
+            router.bind(&secret_name, file_name);
+        }
+
+        let payload = tokio::fs::read(&path)
+            .await
+            .context(format!("Failed to read binary file: {}", path.display()))?;
+
+        match provider
+            .create_or_update_secret_binary(&secret_name, &payload, environment, &location)
+            .await
+        {
+            Ok(was_updated) => {
+                count += 1;
+                observability::metrics::increment_secrets_published_total(provider_name, 1);
+
+                let mut sync_state = existing_state
+                    .and_then(|s| s.get(&secret_name).cloned())
+                    .unwrap_or(ResourceSyncState {
+                        exists: false,
+                        update_count: 0,
+                        source_key: None,
+                        tier: None,
+                        expire_time: None,
+                    });
+                sync_state.exists = true;
+                sync_state.source_key = sanitized_source_key(config, file_name);
+                if was_updated {
+                    sync_state.update_count += 1;
+                }
+                info!(
+                    provider = provider_name,
+                    secret_name = secret_name,
+                    size_bytes = payload.len(),
+                    updated = was_updated,
+                    "✅ Synced binary secret '{}' from {} ({} bytes, updated={})",
+                    secret_name,
+                    file_name,
+                    payload.len(),
+                    was_updated
+                );
+                synced_secrets.insert(secret_name, sync_state);
+            }
+            Err(e) => {
+                observability::metrics::increment_secrets_skipped_total(provider_name, "error");
+                error!("Failed to store binary secret {}: {}", secret_name, e);
+                failed_secrets.push(FailedSecret::new(&secret_name, e.to_string()));
+            }
+        }
+    }
+
+    Ok((count, synced_secrets, failed_secrets))
+}
@@ -0,0 +1,107 @@
+//! # Certificate Storage
+//!
+//! Handles importing certificate files into Azure Key Vault's certificate store
+//! when `provider.azure.certificates.enabled` is set.
+
+use crate::controller::parser;
+use crate::controller::reconciler::utils::{resolve_secret_name, sanitized_source_key};
+use crate::crd::{FailedSecret, ProviderConfig, ResourceSyncState, SecretManagerConfig};
+use crate::observability;
+use crate::provider::azure::AzureCertificateProvider;
+use crate::provider::azure::key_vault::CertificateFormat;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use tracing::{error, info};
+
+/// Import certificate files from the profile directory into Azure Key Vault
+/// No-op for non-Azure providers or when certificate sync is disabled
+/// Returns (count, synced_certificates_map, failed_certificates) keyed by certificate name
+pub async fn store_certificates(
+    config: &SecretManagerConfig,
+    app_files: &parser::ApplicationFiles,
+    secret_prefix: &str,
+    k8s_client: &kube::Client,
+) -> Result<(i32, HashMap<String, ResourceSyncState>, Vec<FailedSecret>)> {
+    let mut synced = HashMap::new();
+
+    let ProviderConfig::Azure(azure_config) = &config.spec.provider else {
+        return Ok((0, synced, Vec::new()));
+    };
+    let Some(cert_config) = azure_config.certificates.as_ref().filter(|c| c.enabled) else {
+        return Ok((0, synced, Vec::new()));
+    };
+
+    let files = parser::find_binary_files(&app_files.base_path, &cert_config.files)
+        .context("Failed to find certificate files")?;
+    if files.is_empty() {
+        return Ok((0, synced, Vec::new()));
+    }
+
+    let namespace = config.metadata.namespace.as_deref().unwrap_or("default");
+    let certificate_provider =
+        AzureCertificateProvider::new(&azure_config.with_default_namespace(namespace), k8s_client)?;
+    let existing_state = config
+        .status
+        .as_ref()
+        .and_then(|s| s.sync.as_ref())
+        .and_then(|sync| sync.secrets.as_ref());
+
+    let mut count = 0;
+    let mut failed = Vec::new();
+
+    for path in files {
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        // Key Vault certificate names follow the same rules as secret names
+        let certificate_name = resolve_secret_name(config, secret_prefix, file_name);
+
+        let content = tokio::fs::read(&path).await.context(format!(
+            "Failed to read certificate file: {}",
+            path.display()
+        ))?;
+
+        match certificate_provider
+            .import_certificate(
+                &certificate_name,
+                &content,
+                CertificateFormat::from_file_name(file_name),
+                &config.spec.secrets.environment,
+            )
+            .await
+        {
+            Ok(was_imported) => {
+                count += 1;
+                observability::metrics::increment_secrets_published_total("azure", 1);
+                let mut sync_state = existing_state
+                    .and_then(|s| s.get(&certificate_name).cloned())
+                    .unwrap_or(ResourceSyncState {
+                        exists: false,
+                        update_count: 0,
+                        source_key: None,
+                        tier: None,
+                        expire_time: None,
+                    });
+                sync_state.exists = true;
+                sync_state.source_key = sanitized_source_key(config, file_name);
+                if was_imported {
+                    sync_state.update_count += 1;
+                    info!(
+                        "✅ Imported certificate '{}' from {} - update_count={}",
+                        certificate_name, file_name, sync_state.update_count
+                    );
+                } else {
+                    info!("✅ Certificate '{}' unchanged", certificate_name);
+                }
+                synced.insert(certificate_name, sync_state);
+            }
+            Err(e) => {
+                observability::metrics::increment_secrets_skipped_total("azure", "error");
+                error!("Failed to import certificate {}: {}", certificate_name, e);
+                failed.push(FailedSecret::new(&certificate_name, e.to_string()));
+            }
+        }
+    }
+
+    Ok((count, synced, failed))
+}
@@ -1,10 +1,12 @@
 //! # Diff Discovery
 //!
 //! Detects if secrets have been tampered with in cloud providers by comparing
-//! Git values (source of truth) with cloud provider values.
+//! Git values (source of truth) with cloud provider values. With the `ConflictDetection`
+//! feature gate, drifted secrets are also checked for who last wrote them (see `last_writer`).
 
 use crate::crd::SecretManagerConfig;
 use crate::provider::SecretManagerProvider;
+use crate::secret::SecretString;
 use anyhow::Result;
 use tracing::{debug, warn};
 
@@ -18,7 +20,7 @@ pub async fn detect_secret_diff(
     provider: &dyn SecretManagerProvider,
     config: &SecretManagerConfig,
     secret_name: &str,
-    git_value: &str,
+    git_value: &SecretString,
 ) -> Result<bool> {
     // Check if this secret has been pushed before by looking at status
     let has_been_pushed = config
@@ -42,19 +44,23 @@ pub async fn detect_secret_diff(
     // Secret has been pushed before - check for drift
     match provider.get_secret_value(secret_name).await {
         Ok(Some(cloud_value)) => {
-            if cloud_value != git_value {
+            if cloud_value != *git_value {
                 warn!(
                     "⚠️  Secret '{}' differs between Git (source of truth) and cloud provider",
                     secret_name
                 );
                 debug!(
                     "Git value (source of truth): '{}'",
-                    mask_secret_value(git_value)
+                    mask_secret_value(git_value.expose_secret())
                 );
                 debug!(
                     "Cloud provider value (may have been tampered with): '{}'",
-                    mask_secret_value(&cloud_value)
+                    mask_secret_value(cloud_value.expose_secret())
                 );
+                // With last-writer stamps, tell foreign writes from Git changes
+                if let Some(tracker) = provider.last_writer() {
+                    tracker.check_drifted(provider, secret_name).await;
+                }
                 return Ok(true);
             }
             debug!(
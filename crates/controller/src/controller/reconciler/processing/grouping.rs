@@ -0,0 +1,181 @@
+//! # Grouped Secret Storage
+//!
+//! Handles `secrets.grouping: SingleJson`, where all enabled keys from a service's
+//! secrets files are stored as one provider secret holding a JSON object, and
+//! application.secrets.yaml documents with an `x-target.group` header, whose keys are stored
+//! as one JSON secret named after the group.
+
+use crate::controller::parser;
+use crate::controller::reconciler::utils::resolve_secret_name;
+use crate::crd::{FailedSecret, ProviderConfig, ResourceSyncState, SecretManagerConfig};
+use crate::observability;
+use crate::provider::SecretManagerProvider;
+use crate::secret::SecretString;
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{error, info};
+
+/// Key used to name the grouped secret (`{prefix}-secrets-{suffix}`)
+pub const GROUPED_SECRET_KEY: &str = "secrets";
+
+/// Serialize enabled secrets as a JSON object
+/// Keys are sorted so the document is stable across reconciles (no spurious versions)
+pub fn group_as_json(secrets: &HashMap<String, parser::SecretEntry>) -> Result<SecretString> {
+    let grouped: BTreeMap<&str, &str> = secrets
+        .iter()
+        .filter(|(_, entry)| entry.enabled)
+        .map(|(key, entry)| (key.as_str(), entry.value.expose_secret()))
+        .collect();
+    Ok(SecretString::new(serde_json::to_string(&grouped)?))
+}
+
+/// Split secrets by `x-target.group`: (ungrouped secrets, secrets per group)
+#[must_use]
+pub fn split_groups(
+    parsed_secrets: &parser::ParsedSecrets,
+) -> (
+    parser::ParsedSecrets,
+    BTreeMap<String, parser::ParsedSecrets>,
+) {
+    let mut ungrouped = parser::ParsedSecrets::default();
+    let mut groups: BTreeMap<String, parser::ParsedSecrets> = BTreeMap::new();
+    for (key, entry) in &parsed_secrets.secrets {
+        let secrets = match &entry.group {
+            Some(group) => &mut groups.entry(group.clone()).or_default().secrets,
+            None => &mut ungrouped.secrets,
+        };
+        secrets.insert(key.clone(), entry.clone());
+    }
+    (ungrouped, groups)
+}
+
+/// Sync state of the resource's secrets from its status
+#[must_use]
+pub fn synced_secrets_from_status(
+    config: &SecretManagerConfig,
+) -> HashMap<String, ResourceSyncState> {
+    config
+        .status
+        .as_ref()
+        .and_then(|s| s.sync.as_ref())
+        .and_then(|sync| sync.secrets.clone())
+        .unwrap_or_default()
+}
+
+/// Store all enabled secrets as a single JSON provider secret named `{prefix}-{group}-{suffix}`
+/// The sync state of the grouped secret is recorded in `synced_secrets`
+/// Returns (count, failed_secrets) where count is 1 when the grouped secret was written
+pub async fn store_grouped_secrets(
+    provider: &dyn SecretManagerProvider,
+    config: &SecretManagerConfig,
+    parsed_secrets: &parser::ParsedSecrets,
+    group: &str,
+    secret_prefix: &str,
+    provider_name: &str,
+    synced_secrets: &mut HashMap<String, ResourceSyncState>,
+) -> Result<(i32, Vec<FailedSecret>)> {
+    if !parsed_secrets.secrets.values().any(|entry| entry.enabled) {
+        return Ok((0, Vec::new()));
+    }
+
+    let environment = &config.spec.secrets.environment;
+    let location = match &config.spec.provider {
+        ProviderConfig::Gcp(gcp_config) => {
+            if gcp_config.location.is_empty() || gcp_config.location == "automatic" {
+                String::new()
+            } else {
+                gcp_config.location.clone()
+            }
+        }
+        ProviderConfig::Aws(aws_config) => aws_config.region.clone(),
+        ProviderConfig::Azure(azure_config) => azure_config.location.clone(),
+    };
+
+    let secret_json = group_as_json(&parsed_secrets.secrets)?;
+    let secret_name = resolve_secret_name(config, secret_prefix, group);
+
+    match provider
+        .create_or_update_secret(&secret_name, &secret_json, environment, &location)
+        .await
+    {
+        Ok(was_updated) => {
+            observability::metrics::increment_secrets_published_total(provider_name, 1);
+            let sync_state = synced_secrets
+                .entry(secret_name.clone())
+                .or_insert_with(|| ResourceSyncState {
+                    exists: false,
+                    update_count: 0,
+                    source_key: None,
+                    tier: None,
+                    expire_time: None,
+                });
+            sync_state.exists = true;
+
+            if was_updated {
+                sync_state.update_count += 1;
+                observability::metrics::increment_secrets_updated(1);
+                info!(
+                    "✅ Updated grouped secret '{}' ({} keys) from git - update_count={}",
+                    secret_name,
+                    parsed_secrets.secrets.len(),
+                    sync_state.update_count
+                );
+            } else {
+                info!(
+                    "✅ Grouped secret '{}' unchanged (no update needed) - exists={}, update_count={}",
+                    secret_name, sync_state.exists, sync_state.update_count
+                );
+            }
+            Ok((1, Vec::new()))
+        }
+        Err(e) => {
+            observability::metrics::increment_secrets_skipped_total(provider_name, "error");
+            error!("Failed to store grouped secret {}: {}", secret_name, e);
+            Ok((0, vec![FailedSecret::new(&secret_name, e.to_string())]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(value: &str, enabled: bool) -> parser::SecretEntry {
+        parser::SecretEntry {
+            value: value.into(),
+            enabled,
+            encrypted: false,
+            group: None,
+        }
+    }
+
+    #[test]
+    fn test_group_as_json_is_sorted_and_skips_disabled() {
+        let mut secrets = HashMap::new();
+        secrets.insert("ZETA".to_string(), entry("z", true));
+        secrets.insert("ALPHA".to_string(), entry("a", true));
+        secrets.insert("DISABLED".to_string(), entry("d", false));
+
+        let json = group_as_json(&secrets).unwrap_or_default();
+        assert_eq!(json.expose_secret(), r#"{"ALPHA":"a","ZETA":"z"}"#);
+    }
+
+    #[test]
+    fn test_split_groups() {
+        let mut parsed = parser::ParsedSecrets::default();
+        parsed
+            .secrets
+            .insert("API_KEY".to_string(), entry("k", true));
+        for key in ["DB_USER", "DB_PASSWORD"] {
+            let mut grouped = entry("v", true);
+            grouped.group = Some("database".to_string());
+            parsed.secrets.insert(key.to_string(), grouped);
+        }
+
+        let (ungrouped, groups) = split_groups(&parsed);
+        assert_eq!(ungrouped.secrets.len(), 1);
+        assert!(ungrouped.secrets.contains_key("API_KEY"));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups["database"].secrets.len(), 2);
+    }
+}
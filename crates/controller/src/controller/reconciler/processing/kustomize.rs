@@ -2,10 +2,17 @@
 //!
 //! Handles processing secrets extracted from kustomize builds.
 
-use crate::controller::reconciler::utils::construct_secret_name;
-use crate::crd::{ProviderConfig, ResourceSyncState, SecretManagerConfig};
+use super::secrets::renew_expiration;
+use crate::controller::reconciler::expiration::ExpirationPolicy;
+use crate::controller::reconciler::limits::LimitBudget;
+use crate::controller::reconciler::policy::{KeyPolicy, PolicyValue, ValueSource};
+use crate::controller::reconciler::utils::{
+    check_name_collisions, resolve_secret_name, sanitized_source_key,
+};
+use crate::crd::{FailedSecret, ProviderConfig, ResourceSyncState, SecretManagerConfig};
 use crate::observability;
 use crate::provider::SecretManagerProvider;
+use crate::secret::SecretString;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::time::Instant;
@@ -13,13 +20,24 @@ use tracing::{error, info, info_span, warn};
 
 /// Process Kustomize secrets
 /// Extracts secrets from kustomize-generated Secret resources and stores them in cloud provider
-/// Returns (count, synced_secrets_map) where synced_secrets tracks push state
+/// Also used for the keys of a Kubernetes Secret source, with `source` telling the policy where
+/// the values came from
+/// Returns (count, synced_secrets_map, failed_secrets) where synced_secrets tracks push state
+/// Every secret is attempted; failed writes are collected rather than aborting the loop
+/// Nothing is written if the secrets break the resource's `policy` or exceed its `budget`
 pub async fn process_kustomize_secrets(
     provider: &dyn SecretManagerProvider,
     config: &SecretManagerConfig,
-    secrets: &HashMap<String, String>,
+    secrets: &HashMap<String, SecretString>,
     secret_prefix: &str,
-) -> Result<(i32, std::collections::HashMap<String, ResourceSyncState>)> {
+    source: ValueSource,
+    policy: Option<&KeyPolicy>,
+    budget: &mut LimitBudget,
+) -> Result<(
+    i32,
+    std::collections::HashMap<String, ResourceSyncState>,
+    Vec<FailedSecret>,
+)> {
     // Initialize synced_secrets map from existing status
     let mut synced_secrets = config
         .status
@@ -27,6 +45,7 @@ pub async fn process_kustomize_secrets(
         .and_then(|s| s.sync.as_ref())
         .and_then(|sync| sync.secrets.clone())
         .unwrap_or_default();
+    let mut failed_secrets = Vec::new();
     // Store secrets in cloud provider (GitOps: Git is source of truth)
     // Get provider name for metrics
     let provider_name = match &config.spec.provider {
@@ -65,15 +84,38 @@ pub async fn process_kustomize_secrets(
     );
     let publish_start = Instant::now();
 
+    // Keys that differ only in characters the provider disallows would overwrite each other
+    check_name_collisions(config, secret_prefix, secrets.keys().map(String::as_str))?;
+
+    if let Some(policy) = policy {
+        policy.check(secrets.iter().map(|(key, value)| PolicyValue {
+            key,
+            value: value.expose_secret(),
+            source,
+        }))?;
+    }
+
+    budget.charge(
+        secrets
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.len() as u64)),
+    )?;
+
+    let expiration = config
+        .spec
+        .secrets
+        .expiration
+        .as_ref()
+        .map(ExpirationPolicy::new)
+        .transpose()?;
     let mut count = 0;
     let mut updated_count = 0;
 
     for (key, value) in secrets {
-        let secret_name = construct_secret_name(
-            Some(secret_prefix),
-            key.as_str(),
-            config.spec.secrets.suffix.as_deref(),
-        );
+        let secret_name = resolve_secret_name(config, secret_prefix, key.as_str());
+        if let Some(router) = provider.router() {
+            router.bind(&secret_name, key);
+        }
         match provider
             .create_or_update_secret(&secret_name, value, environment, &location)
             .await
@@ -88,10 +130,14 @@ pub async fn process_kustomize_secrets(
                     .or_insert_with(|| ResourceSyncState {
                         exists: false,
                         update_count: 0,
+                        source_key: None,
+                        tier: None,
+                        expire_time: None,
                     });
 
                 // Mark as existing (successfully pushed)
                 sync_state.exists = true;
+                sync_state.source_key = sanitized_source_key(config, key);
 
                 // Only increment update_count if value actually changed
                 if was_updated {
@@ -107,13 +153,22 @@ pub async fn process_kustomize_secrets(
                         secret_name, sync_state.exists, sync_state.update_count
                     );
                 }
+
+                renew_expiration(
+                    provider,
+                    expiration.as_ref(),
+                    key,
+                    &secret_name,
+                    was_updated,
+                    sync_state,
+                )
+                .await;
             }
             Err(e) => {
                 observability::metrics::increment_secrets_skipped_total(provider_name, "error");
-                publish_span.record("operation.success", false);
                 publish_span.record("error.message", e.to_string());
                 error!("Failed to store secret {}: {}", secret_name, e);
-                return Err(e.context(format!("Failed to store secret: {secret_name}")));
+                failed_secrets.push(FailedSecret::new(&secret_name, e.to_string()));
             }
         }
     }
@@ -131,9 +186,9 @@ pub async fn process_kustomize_secrets(
         "operation.duration_ms",
         publish_start.elapsed().as_millis() as u64,
     );
-    publish_span.record("operation.success", true);
+    publish_span.record("operation.success", failed_secrets.is_empty());
     publish_span.record("secrets.published", count as u64);
 
     observability::metrics::increment_secrets_synced(i64::from(count));
-    Ok((count, synced_secrets))
+    Ok((count, synced_secrets, failed_secrets))
 }
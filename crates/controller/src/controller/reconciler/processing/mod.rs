@@ -3,9 +3,13 @@
 //! Handles parsing application files and processing Kustomize builds to extract secrets and properties.
 
 mod application_files;
+mod binary_files;
+mod certificates;
 mod diff_discovery;
+mod grouping;
 mod kustomize;
 mod properties;
+mod secret_references;
 mod secrets;
 
 pub use application_files::process_application_files;
@@ -3,19 +3,30 @@
 //! Handles storing properties in config stores (Parameter Store, App Configuration) or as secrets.
 
 use crate::controller::reconciler::types::Reconciler;
-use crate::controller::reconciler::utils::construct_secret_name;
-use crate::crd::{ConfigStoreType, ProviderConfig, ResourceSyncState, SecretManagerConfig};
+use crate::controller::reconciler::utils::{
+    check_name_collisions, resolve_secret_name, sanitized_source_key,
+};
+use crate::crd::{
+    ConfigStoreType, ProviderConfig, ResourceSyncState, SecretGrouping, SecretManagerConfig,
+};
 use crate::observability;
 use crate::provider::aws::AwsParameterStore;
 use crate::provider::azure::AzureAppConfiguration;
-use crate::provider::gcp::create_gcp_parameter_manager_provider;
+use crate::provider::gcp::{
+    Impersonation, create_gcp_parameter_manager_provider, resource_endpoint_override,
+};
 use crate::provider::{ConfigStoreProvider, SecretManagerProvider};
+use crate::secret::SecretString;
+
+use super::secret_references::to_parameter_references;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
 /// Store properties in appropriate store (config store if enabled, otherwise secret store)
+/// `managed_secrets` are the keys synced as individual secrets, which `{{secretRef: KEY}}` may
+/// name when `configs.secretReferences` is enabled
 /// Returns (count, synced_properties_map) where synced_properties tracks push state
 pub async fn store_properties(
     reconciler: &Arc<Reconciler>,
@@ -23,6 +34,7 @@ pub async fn store_properties(
     config: &SecretManagerConfig,
     properties: HashMap<String, String>,
     secret_prefix: &str,
+    managed_secrets: &HashSet<&str>,
 ) -> Result<(i32, std::collections::HashMap<String, ResourceSyncState>)> {
     if properties.is_empty() {
         return Ok((0, std::collections::HashMap::new()));
@@ -77,6 +89,13 @@ pub async fn store_properties(
         // Route to appropriate config store based on provider
         match &config.spec.provider {
             ProviderConfig::Gcp(gcp_config) => {
+                // Keys that differ only in characters the provider disallows would overwrite each other
+                check_name_collisions(
+                    config,
+                    secret_prefix,
+                    properties.keys().map(String::as_str),
+                )?;
+
                 // Check if Parameter Manager is configured
                 let use_parameter_manager = config
                     .spec
@@ -90,59 +109,99 @@ pub async fn store_properties(
                     // Use Parameter Manager for configs
                     info!("Using GCP Parameter Manager for configs");
 
-                    // Extract auth config similar to how it's done in provider.rs
-                    let (auth_type, service_account_email_owned) =
-                        if let Some(ref auth_config) = gcp_config.auth {
-                            match serde_json::to_value(auth_config)
-                                .context("Failed to serialize gcpAuth config")
-                            {
-                                Ok(auth_json) => {
-                                    let auth_type_str =
-                                        auth_json.get("authType").and_then(|t| t.as_str());
-                                    if let Some("WorkloadIdentity") = auth_type_str {
-                                        match auth_json
-                                            .get("serviceAccountEmail")
-                                            .and_then(|e| e.as_str())
-                                        {
-                                            Some(email) => {
-                                                (Some("WorkloadIdentity"), Some(email.to_string()))
+                    let param_provider = if let Some(factory) = &reconciler.provider_factory {
+                        factory.config_store(config)
+                    } else {
+                        // Extract auth config similar to how it's done in provider.rs
+                        let (auth_type, service_account_email_owned) =
+                            if let Some(ref auth_config) = gcp_config.auth {
+                                match serde_json::to_value(auth_config)
+                                    .context("Failed to serialize gcpAuth config")
+                                {
+                                    Ok(auth_json) => {
+                                        let auth_type_str =
+                                            auth_json.get("authType").and_then(|t| t.as_str());
+                                        if let Some("WorkloadIdentity") = auth_type_str {
+                                            match auth_json
+                                                .get("serviceAccountEmail")
+                                                .and_then(|e| e.as_str())
+                                            {
+                                                Some(email) => (
+                                                    Some("WorkloadIdentity"),
+                                                    Some(email.to_string()),
+                                                ),
+                                                None => (Some("WorkloadIdentity"), None),
                                             }
-                                            None => (Some("WorkloadIdentity"), None),
+                                        } else {
+                                            (Some("WorkloadIdentity"), None)
                                         }
-                                    } else {
-                                        (Some("WorkloadIdentity"), None)
+                                    }
+                                    Err(e) => {
+                                        error!("Failed to serialize GCP auth config: {}", e);
+                                        return Err(anyhow::anyhow!(
+                                            "Failed to serialize GCP auth config: {}",
+                                            e
+                                        ));
                                     }
                                 }
-                                Err(e) => {
-                                    error!("Failed to serialize GCP auth config: {}", e);
-                                    return Err(anyhow::anyhow!(
-                                        "Failed to serialize GCP auth config: {}",
-                                        e
-                                    ));
-                                }
-                            }
-                        } else {
-                            (Some("WorkloadIdentity"), None)
-                        };
+                            } else {
+                                (Some("WorkloadIdentity"), None)
+                            };
 
-                    let param_provider = create_gcp_parameter_manager_provider(
-                        gcp_config.project_id.clone(),
-                        auth_type,
-                        service_account_email_owned.as_deref(),
-                    )
-                    .await
-                    .context("Failed to create GCP Parameter Manager provider")?;
+                        create_gcp_parameter_manager_provider(
+                            gcp_config.project_id.clone(),
+                            auth_type,
+                            service_account_email_owned.as_deref(),
+                            Impersonation::from_auth(gcp_config.auth.as_ref()).as_ref(),
+                            resource_endpoint_override(
+                                gcp_config
+                                    .endpoint_override
+                                    .as_ref()
+                                    .and_then(|o| o.parameter_manager.as_deref()),
+                            ),
+                        )
+                        .await
+                        .context("Failed to create GCP Parameter Manager provider")?
+                    };
+
+                    let secret_references = config
+                        .spec
+                        .configs
+                        .as_ref()
+                        .is_some_and(|c| c.secret_references);
 
                     for (key, value) in properties {
-                        let config_name = construct_secret_name(
-                            Some(secret_prefix),
-                            key.as_str(),
-                            config.spec.secrets.suffix.as_deref(),
-                        );
-                        match param_provider
-                            .create_or_update_config(&config_name, &value)
-                            .await
-                        {
+                        let config_name = resolve_secret_name(config, secret_prefix, key.as_str());
+                        // Sensitive values are stored as references to their Secret Manager secret
+                        let value = if secret_references {
+                            to_parameter_references(
+                                &key,
+                                &value,
+                                &gcp_config.project_id,
+                                managed_secrets,
+                                |reference| resolve_secret_name(config, secret_prefix, reference),
+                            )
+                            .with_context(|| format!("Failed to store config: {config_name}"))?
+                        } else {
+                            value
+                        };
+                        if let Some(bucket) = provider.rate_limiter() {
+                            bucket.acquire().await;
+                        }
+                        // Approval mode only plans the write
+                        let result = match provider.planner() {
+                            Some(planner) => {
+                                planner
+                                    .plan_config_write(&*param_provider, &config_name, &value)
+                                    .await
+                            }
+                            None => {
+                                param_provider
+                                    .create_or_update_config(&config_name, &value)
+                                    .await
+                            }
+                        };
+                        match result {
                             Ok(was_updated) => {
                                 config_count += 1;
 
@@ -152,10 +211,20 @@ pub async fn store_properties(
                                     .or_insert_with(|| ResourceSyncState {
                                         exists: false,
                                         update_count: 0,
+                                        source_key: None,
+                                        tier: None,
+                                        expire_time: None,
                                     });
+                                let existed = sync_state.exists;
                                 sync_state.exists = true;
+                                sync_state.source_key = sanitized_source_key(config, &key);
 
                                 if was_updated {
+                                    if let Some(auditor) = provider.auditor() {
+                                        auditor
+                                            .record_config_write(&config_name, &value, existed)
+                                            .await;
+                                    }
                                     sync_state.update_count += 1;
                                     config_updated_count += 1;
                                     info!(
@@ -181,11 +250,8 @@ pub async fn store_properties(
                     // Default: reuse Secret Manager provider (store configs as individual secrets)
                     // This maintains backward compatibility
                     for (key, value) in properties {
-                        let config_name = construct_secret_name(
-                            Some(secret_prefix),
-                            key.as_str(),
-                            config.spec.secrets.suffix.as_deref(),
-                        );
+                        let config_name = resolve_secret_name(config, secret_prefix, key.as_str());
+                        let value = SecretString::new(value);
                         match provider
                             .create_or_update_secret(&config_name, &value, environment, &location)
                             .await
@@ -199,8 +265,12 @@ pub async fn store_properties(
                                     .or_insert_with(|| ResourceSyncState {
                                         exists: false,
                                         update_count: 0,
+                                        source_key: None,
+                                        tier: None,
+                                        expire_time: None,
                                     });
                                 sync_state.exists = true;
+                                sync_state.source_key = sanitized_source_key(config, &key);
 
                                 if was_updated {
                                     sync_state.update_count += 1;
@@ -233,49 +303,89 @@ pub async fn store_properties(
                     .configs
                     .as_ref()
                     .and_then(|c| c.parameter_path.as_deref());
-                let aws_param_store = AwsParameterStore::new(
-                    aws_config,
-                    parameter_path,
-                    secret_prefix,
-                    &config.spec.secrets.environment,
-                    &reconciler.client,
-                )
-                .await
-                .context("Failed to create AWS Parameter Store client")?;
+                let aws_param_store: Box<dyn ConfigStoreProvider> = if let Some(factory) =
+                    &reconciler.provider_factory
+                {
+                    factory.config_store(config)
+                } else {
+                    Box::new(
+                        AwsParameterStore::new(
+                            aws_config,
+                            parameter_path,
+                            secret_prefix,
+                            &config.spec.secrets.environment,
+                            &reconciler.client,
+                        )
+                        .await
+                        .context("Failed to create AWS Parameter Store client")?
+                        .with_settings(config.spec.configs.as_ref().and_then(|c| c.aws.as_ref())),
+                    )
+                };
 
-                for (key, value) in properties {
-                    match aws_param_store.create_or_update_config(&key, &value).await {
-                        Ok(was_updated) => {
-                            config_count += 1;
+                let entries: Vec<(String, String)> = properties.into_iter().collect();
+                // Tier each value is written with, surfaced in status
+                let tiers: HashMap<String, String> = entries
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        aws_param_store
+                            .storage_tier(key, value)
+                            .map(|tier| (key.clone(), tier))
+                    })
+                    .collect();
+                // Config store writes bypass the secret provider, so audit them here
+                let audited_values: HashMap<String, String> = if provider.auditor().is_some() {
+                    entries.iter().cloned().collect()
+                } else {
+                    HashMap::new()
+                };
+                // Approval mode only plans the writes
+                let upserted = match provider.planner() {
+                    Some(planner) => planner.plan_config_writes(&*aws_param_store, entries).await,
+                    None => aws_param_store.bulk_upsert(entries).await,
+                };
+                let results = match upserted {
+                    Ok(results) => results,
+                    Err(e) => {
+                        error!("Failed to store configs: {}", e);
+                        return Err(e.context("Failed to store configs"));
+                    }
+                };
 
-                            // Update push state: track existence and update count
-                            let sync_state =
-                                synced_properties.entry(key.clone()).or_insert_with(|| {
-                                    ResourceSyncState {
-                                        exists: false,
-                                        update_count: 0,
-                                    }
-                                });
-                            sync_state.exists = true;
+                for (key, was_updated) in results {
+                    config_count += 1;
 
-                            if was_updated {
-                                sync_state.update_count += 1;
-                                config_updated_count += 1;
-                                info!(
-                                    "Updated config {} from git (GitOps source of truth) - update_count={}",
-                                    key, sync_state.update_count
-                                );
-                            } else {
-                                debug!(
-                                    "Config {} unchanged (no update needed) - exists={}, update_count={}",
-                                    key, sync_state.exists, sync_state.update_count
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            error!("Failed to store config {}: {}", key, e);
-                            return Err(e.context(format!("Failed to store config: {key}")));
+                    // Update push state: track existence and update count
+                    let sync_state =
+                        synced_properties
+                            .entry(key.clone())
+                            .or_insert_with(|| ResourceSyncState {
+                                exists: false,
+                                update_count: 0,
+                                source_key: None,
+                                tier: None,
+                                expire_time: None,
+                            });
+                    let existed = sync_state.exists;
+                    sync_state.exists = true;
+                    sync_state.tier = tiers.get(&key).cloned();
+
+                    if was_updated {
+                        if let (Some(auditor), Some(value)) =
+                            (provider.auditor(), audited_values.get(&key))
+                        {
+                            auditor.record_config_write(&key, value, existed).await;
                         }
+                        sync_state.update_count += 1;
+                        config_updated_count += 1;
+                        info!(
+                            "Updated config {} from git (GitOps source of truth) - update_count={}",
+                            key, sync_state.update_count
+                        );
+                    } else {
+                        debug!(
+                            "Config {} unchanged (no update needed) - exists={}, update_count={}",
+                            key, sync_state.exists, sync_state.update_count
+                        );
                     }
                 }
             }
@@ -286,49 +396,90 @@ pub async fn store_properties(
                     .configs
                     .as_ref()
                     .and_then(|c| c.app_config_endpoint.as_deref());
-                let azure_app_config = AzureAppConfiguration::new(
-                    azure_config,
-                    app_config_endpoint,
-                    secret_prefix,
-                    &config.spec.secrets.environment,
-                    &reconciler.client,
-                )
-                .await
-                .context("Failed to create Azure App Configuration client")?;
+                let feature_flag_prefix = config
+                    .spec
+                    .configs
+                    .as_ref()
+                    .and_then(|c| c.azure.as_ref())
+                    .and_then(|azure| azure.feature_flag_prefix.as_deref());
+                let azure_app_config: Box<dyn ConfigStoreProvider> =
+                    if let Some(factory) = &reconciler.provider_factory {
+                        factory.config_store(config)
+                    } else {
+                        Box::new(
+                            AzureAppConfiguration::new(
+                                &azure_config.with_default_namespace(
+                                    config.metadata.namespace.as_deref().unwrap_or("default"),
+                                ),
+                                app_config_endpoint,
+                                secret_prefix,
+                                &config.spec.secrets.environment,
+                                &reconciler.client,
+                            )
+                            .await
+                            .context("Failed to create Azure App Configuration client")?
+                            .with_feature_flags(feature_flag_prefix),
+                        )
+                    };
 
-                for (key, value) in properties {
-                    match azure_app_config.create_or_update_config(&key, &value).await {
-                        Ok(was_updated) => {
-                            config_count += 1;
+                let entries: Vec<(String, String)> = properties.into_iter().collect();
+                // Config store writes bypass the secret provider, so audit them here
+                let audited_values: HashMap<String, String> = if provider.auditor().is_some() {
+                    entries.iter().cloned().collect()
+                } else {
+                    HashMap::new()
+                };
+                // Approval mode only plans the writes
+                let upserted = match provider.planner() {
+                    Some(planner) => {
+                        planner
+                            .plan_config_writes(&*azure_app_config, entries)
+                            .await
+                    }
+                    None => azure_app_config.bulk_upsert(entries).await,
+                };
+                let results = match upserted {
+                    Ok(results) => results,
+                    Err(e) => {
+                        error!("Failed to store configs: {}", e);
+                        return Err(e.context("Failed to store configs"));
+                    }
+                };
 
-                            // Update push state: track existence and update count
-                            let sync_state =
-                                synced_properties.entry(key.clone()).or_insert_with(|| {
-                                    ResourceSyncState {
-                                        exists: false,
-                                        update_count: 0,
-                                    }
-                                });
-                            sync_state.exists = true;
+                for (key, was_updated) in results {
+                    config_count += 1;
 
-                            if was_updated {
-                                sync_state.update_count += 1;
-                                config_updated_count += 1;
-                                info!(
-                                    "✅ Updated config '{}' from git (GitOps source of truth) - update_count={}",
-                                    key, sync_state.update_count
-                                );
-                            } else {
-                                info!(
-                                    "✅ Config '{}' unchanged (no update needed) - exists={}, update_count={}",
-                                    key, sync_state.exists, sync_state.update_count
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            error!("Failed to store config {}: {}", key, e);
-                            return Err(e.context(format!("Failed to store config: {key}")));
+                    // Update push state: track existence and update count
+                    let sync_state =
+                        synced_properties
+                            .entry(key.clone())
+                            .or_insert_with(|| ResourceSyncState {
+                                exists: false,
+                                update_count: 0,
+                                source_key: None,
+                                tier: None,
+                                expire_time: None,
+                            });
+                    let existed = sync_state.exists;
+                    sync_state.exists = true;
+
+                    if was_updated {
+                        if let (Some(auditor), Some(value)) =
+                            (provider.auditor(), audited_values.get(&key))
+                        {
+                            auditor.record_config_write(&key, value, existed).await;
                         }
+                        sync_state.update_count += 1;
+                        config_updated_count += 1;
+                        info!(
+                            "✅ Updated config '{}' from git (GitOps source of truth) - update_count={}",
+                            key, sync_state.update_count
+                        );
+                    } else {
+                        info!(
+                            "✅ Config '{}' unchanged (no update needed) - exists={}, update_count={}",
+                            key, sync_state.exists, sync_state.update_count
+                        );
                     }
                 }
             }
@@ -343,14 +494,59 @@ pub async fn store_properties(
         }
 
         Ok((config_count, synced_properties))
+    } else if config.spec.secrets.grouping == Some(SecretGrouping::Individual) {
+        // Individual grouping: one secret per property in the secret store
+        check_name_collisions(config, secret_prefix, properties.keys().map(String::as_str))?;
+        let mut count = 0;
+        for (key, value) in &properties {
+            let secret_name = resolve_secret_name(config, secret_prefix, key);
+            match provider
+                .create_or_update_secret(
+                    &secret_name,
+                    &SecretString::from(value.as_str()),
+                    environment,
+                    &location,
+                )
+                .await
+            {
+                Ok(was_updated) => {
+                    count += 1;
+                    let sync_state =
+                        synced_properties
+                            .entry(secret_name.clone())
+                            .or_insert_with(|| ResourceSyncState {
+                                exists: false,
+                                update_count: 0,
+                                source_key: None,
+                                tier: None,
+                                expire_time: None,
+                            });
+                    sync_state.exists = true;
+                    sync_state.source_key = sanitized_source_key(config, key);
+                    if was_updated {
+                        sync_state.update_count += 1;
+                        observability::metrics::increment_secrets_updated(1);
+                        info!(
+                            "✅ Updated property secret '{}' from git - update_count={}",
+                            secret_name, sync_state.update_count
+                        );
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to store property secret {}: {}", secret_name, e);
+                    return Err(
+                        e.context(format!("Failed to store property secret: {secret_name}"))
+                    );
+                }
+            }
+        }
+        Ok((count, synced_properties))
     } else {
         // Backward compatibility: store properties as a single secret (JSON encoded)
-        let properties_json = serde_json::to_string(&properties)?;
-        let secret_name = construct_secret_name(
-            Some(secret_prefix),
-            "properties",
-            config.spec.secrets.suffix.as_deref(),
-        );
+        // Sorted keys keep the JSON stable so unchanged properties don't create new versions
+        let sorted_properties: std::collections::BTreeMap<_, _> = properties.iter().collect();
+        let properties_json = SecretString::new(serde_json::to_string(&sorted_properties)?);
+        let secret_name = resolve_secret_name(config, secret_prefix, "properties");
         match provider
             .create_or_update_secret(&secret_name, &properties_json, environment, &location)
             .await
@@ -363,6 +559,9 @@ pub async fn store_properties(
                         .or_insert_with(|| ResourceSyncState {
                             exists: false,
                             update_count: 0,
+                            source_key: None,
+                            tier: None,
+                            expire_time: None,
                         });
                 sync_state.exists = true;
 
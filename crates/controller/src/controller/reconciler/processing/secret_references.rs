@@ -0,0 +1,123 @@
+//! # Secret References
+//!
+//! Rewrites `{{secretRef: KEY}}` in property values as GCP Parameter Manager secret references
+//! (`configs.secretReferences`), so sensitive values stay in Secret Manager and never reach the
+//! config store:
+//!
+//! ```text
+//! db.url=jdbc:postgresql://db:5432/app
+//! db.password={{secretRef: DB_PASSWORD}}
+//! ```
+//!
+//! is stored in Parameter Manager as
+//! `__REF__("//secretmanager.googleapis.com/projects/{project}/secrets/{name}/versions/latest")`,
+//! where `{name}` is the Secret Manager secret this resource syncs for `DB_PASSWORD`. Parameter
+//! Manager resolves the reference when the version is rendered.
+//!
+//! `KEY` must be an enabled secret stored as its own secret (not `secrets.grouping: SingleJson`
+//! or an `x-target.group`); any other reference fails the properties sync.
+
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// Start of a secret reference in a property value
+const REFERENCE_START: &str = "{{";
+/// End of a secret reference in a property value
+const REFERENCE_END: &str = "}}";
+/// Name of the reference inside the braces
+const REFERENCE_NAME: &str = "secretRef:";
+
+/// Reason a property's secret references could not be rewritten
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SecretReferenceError {
+    #[error("'{key}' references '{reference}', which is not a secret synced as its own secret")]
+    Unknown { key: String, reference: String },
+    #[error("'{key}' has a '{{{{secretRef:' without a closing '}}}}'")]
+    Unterminated { key: String },
+}
+
+/// Property value with `{{secretRef: KEY}}` replaced by Parameter Manager secret references
+///
+/// `managed_secrets` holds the keys synced as individual secrets and `secret_name` resolves a
+/// key to its Secret Manager secret name. Other `{{...}}` text is kept as is.
+pub fn to_parameter_references(
+    key: &str,
+    value: &str,
+    project_id: &str,
+    managed_secrets: &HashSet<&str>,
+    secret_name: impl Fn(&str) -> String,
+) -> Result<String, SecretReferenceError> {
+    let mut rewritten = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find(REFERENCE_START) {
+        let after = &rest[start + REFERENCE_START.len()..];
+        let Some(name) = after.trim_start().strip_prefix(REFERENCE_NAME) else {
+            rewritten.push_str(&rest[..start + REFERENCE_START.len()]);
+            rest = after;
+            continue;
+        };
+        let end = name
+            .find(REFERENCE_END)
+            .ok_or_else(|| SecretReferenceError::Unterminated {
+                key: key.to_string(),
+            })?;
+        let reference = name[..end].trim();
+        if !managed_secrets.contains(reference) {
+            return Err(SecretReferenceError::Unknown {
+                key: key.to_string(),
+                reference: reference.to_string(),
+            });
+        }
+        rewritten.push_str(&rest[..start]);
+        rewritten.push_str(&format!(
+            "__REF__(\"//secretmanager.googleapis.com/projects/{}/secrets/{}/versions/latest\")",
+            project_id,
+            secret_name(reference)
+        ));
+        rest = &name[end + REFERENCE_END.len()..];
+    }
+    rewritten.push_str(rest);
+    Ok(rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite(value: &str) -> Result<String, SecretReferenceError> {
+        let managed: HashSet<&str> = ["DB_PASSWORD", "API_KEY"].into_iter().collect();
+        to_parameter_references("db.password", value, "my-project", &managed, |key| {
+            format!("my-service-{key}")
+        })
+    }
+
+    #[test]
+    fn test_rewrites_secret_references() {
+        assert_eq!(
+            rewrite("{{secretRef: DB_PASSWORD}}").unwrap_or_default(),
+            r#"__REF__("//secretmanager.googleapis.com/projects/my-project/secrets/my-service-DB_PASSWORD/versions/latest")"#
+        );
+        assert_eq!(
+            rewrite("https://api?key={{ secretRef:API_KEY }}&v={{version}}").unwrap_or_default(),
+            r#"https://api?key=__REF__("//secretmanager.googleapis.com/projects/my-project/secrets/my-service-API_KEY/versions/latest")&v={{version}}"#
+        );
+        assert_eq!(rewrite("plain").unwrap_or_default(), "plain");
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_unterminated_references() {
+        assert_eq!(
+            rewrite("{{secretRef: OTHER}}"),
+            Err(SecretReferenceError::Unknown {
+                key: "db.password".to_string(),
+                reference: "OTHER".to_string(),
+            })
+        );
+        assert_eq!(
+            rewrite("{{secretRef: DB_PASSWORD"),
+            Err(SecretReferenceError::Unterminated {
+                key: "db.password".to_string(),
+            })
+        );
+    }
+}
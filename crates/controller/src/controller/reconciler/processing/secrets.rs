@@ -3,17 +3,24 @@
 //! Handles storing secrets in cloud provider secret stores, including enabled/disabled state management.
 
 use crate::controller::parser;
+use crate::controller::reconciler::expiration::{ExpirationPolicy, format_expire_time};
+use crate::controller::reconciler::prefetch::PrefetchedProvider;
 use crate::controller::reconciler::processing::diff_discovery::detect_secret_diff;
-use crate::controller::reconciler::utils::construct_secret_name;
-use crate::crd::{ProviderConfig, ResourceSyncState, SecretManagerConfig};
+use crate::controller::reconciler::utils::{
+    check_name_collisions, resolve_secret_name, sanitized_source_key,
+};
+use crate::crd::{FailedSecret, ProviderConfig, ResourceSyncState, SecretManagerConfig};
 use crate::observability;
-use crate::provider::SecretManagerProvider;
+use crate::provider::{ProviderError, SecretManagerProvider};
 use anyhow::Result;
+use chrono::Utc;
 use tracing::{error, info, warn};
 
 /// Process and store secrets (enabled and disabled)
-/// Returns (secrets_count, drift_detected, synced_secrets_map)
+/// Returns (secrets_count, drift_detected, synced_secrets_map, failed_secrets)
 /// synced_secrets_map tracks which secrets have been successfully pushed and how many times updated
+/// A failed write does not stop the loop; every key is attempted and failures are collected,
+/// except when the provider throttles us, which aborts the loop so the sync is retried later
 pub async fn store_secrets(
     provider: &dyn SecretManagerProvider,
     config: &SecretManagerConfig,
@@ -24,13 +31,14 @@ pub async fn store_secrets(
     i32,
     bool,
     std::collections::HashMap<String, ResourceSyncState>,
+    Vec<FailedSecret>,
 )> {
     let mut count = 0;
     let mut updated_count = 0;
     let mut disabled_count = 0;
     let mut enabled_count = 0;
     let mut drift_detected = false;
-    let mut errors = Vec::new();
+    let mut failed_secrets = Vec::new();
 
     // Initialize synced_secrets map from existing status (preserve state across reconciliations)
     let mut synced_secrets = config
@@ -63,13 +71,40 @@ pub async fn store_secrets(
         }
     };
 
+    let expiration = config
+        .spec
+        .secrets
+        .expiration
+        .as_ref()
+        .map(ExpirationPolicy::new)
+        .transpose()?;
+
+    // Keys that differ only in characters the provider disallows would overwrite each other
+    check_name_collisions(
+        config,
+        secret_prefix,
+        parsed_secrets.secrets.keys().map(String::as_str),
+    )?;
+
+    // Bind every name before the bulk read, so routed names are read from their store
+    let secret_names: Vec<String> = parsed_secrets
+        .secrets
+        .keys()
+        .map(|key| {
+            let secret_name = resolve_secret_name(config, secret_prefix, key.as_str());
+            if let Some(router) = provider.router() {
+                router.bind(&secret_name, key);
+            }
+            secret_name
+        })
+        .collect();
+    // Read the current state of all keys up front; the loop below diffs against it
+    let prefetched = PrefetchedProvider::load(provider, &secret_names).await;
+    let provider: &dyn SecretManagerProvider = &prefetched;
+
     // Process all secrets (both enabled and disabled)
     for (key, entry) in &parsed_secrets.secrets {
-        let secret_name = construct_secret_name(
-            Some(secret_prefix),
-            key.as_str(),
-            config.spec.secrets.suffix.as_deref(),
-        );
+        let secret_name = resolve_secret_name(config, secret_prefix, key.as_str());
 
         if entry.enabled {
             // Diff discovery: Compare Git value with cloud provider value
@@ -122,10 +157,14 @@ pub async fn store_secrets(
                                 .or_insert_with(|| ResourceSyncState {
                                     exists: false,
                                     update_count: 0,
+                                    source_key: None,
+                                    tier: None,
+                                    expire_time: None,
                                 });
 
                         // Mark as existing (successfully pushed)
                         sync_state.exists = true;
+                        sync_state.source_key = sanitized_source_key(config, key);
 
                         // Only increment update_count if value actually changed
                         if was_updated {
@@ -174,14 +213,31 @@ pub async fn store_secrets(
                                 sync_state.update_count
                             );
                         }
+
+                        renew_expiration(
+                            provider,
+                            expiration.as_ref(),
+                            key,
+                            &secret_name,
+                            was_updated,
+                            sync_state,
+                        )
+                        .await;
                     }
                     Err(e) => {
                         observability::metrics::increment_secrets_skipped_total(
                             provider_name,
                             "error",
                         );
+                        if matches!(
+                            ProviderError::find(&e),
+                            Some(ProviderError::Throttled { .. })
+                        ) {
+                            // Writing the remaining keys would only be throttled as well
+                            return Err(e);
+                        }
                         error!("Failed to store secret {}: {}", secret_name, e);
-                        errors.push(format!("Failed to store secret {}: {}", secret_name, e));
+                        failed_secrets.push(FailedSecret::new(&secret_name, e.to_string()));
                         // Continue processing other secrets instead of returning early
                     }
                 }
@@ -207,10 +263,18 @@ pub async fn store_secrets(
         } else {
             // Disabled secret: update value if changed, then disable
             // First, check if secret exists and update value if needed
-            let current_value = provider.get_secret_value(&secret_name).await?;
+            let current_value = match provider.get_secret_value(&secret_name).await {
+                Ok(value) => value,
+                Err(e) => {
+                    observability::metrics::increment_secrets_skipped_total(provider_name, "error");
+                    error!("Failed to read disabled secret {}: {}", secret_name, e);
+                    failed_secrets.push(FailedSecret::new(&secret_name, e.to_string()));
+                    continue;
+                }
+            };
             let value_changed = current_value
                 .as_ref()
-                .map(|v| v != &entry.value)
+                .map(|v| *v != entry.value)
                 .unwrap_or(true);
 
             if value_changed {
@@ -228,8 +292,12 @@ pub async fn store_secrets(
                                 .or_insert_with(|| ResourceSyncState {
                                     exists: false,
                                     update_count: 0,
+                                    source_key: None,
+                                    tier: None,
+                                    expire_time: None,
                                 });
                         sync_state.exists = true;
+                        sync_state.source_key = sanitized_source_key(config, key);
                         if was_updated {
                             sync_state.update_count += 1;
                         }
@@ -240,11 +308,12 @@ pub async fn store_secrets(
                     }
                     Err(e) => {
                         // If secret doesn't exist, that's okay - we'll just disable it when it's created later
-                        if !e.to_string().contains("not found") && !e.to_string().contains("404") {
+                        if !ProviderError::is_not_found(&e) {
                             warn!(
                                 "Failed to update disabled secret {} value: {}",
                                 secret_name, e
                             );
+                            failed_secrets.push(FailedSecret::new(&secret_name, e.to_string()));
                         }
                     }
                 }
@@ -265,9 +334,9 @@ pub async fn store_secrets(
                 }
                 Err(e) => {
                     // If secret doesn't exist, that's okay - it's already effectively disabled
-                    if !e.to_string().contains("not found") && !e.to_string().contains("404") {
+                    if !ProviderError::is_not_found(&e) {
                         warn!("Failed to disable secret {}: {}", secret_name, e);
-                        // Don't fail the entire operation, just log a warning
+                        failed_secrets.push(FailedSecret::new(&secret_name, e.to_string()));
                     }
                 }
             }
@@ -296,5 +365,48 @@ pub async fn store_secrets(
         );
     }
 
-    Ok((count, drift_detected, synced_secrets))
+    if !failed_secrets.is_empty() {
+        warn!(
+            "{} secret(s) failed to sync; remaining secrets were still processed",
+            failed_secrets.len()
+        );
+    }
+
+    Ok((count, drift_detected, synced_secrets, failed_secrets))
+}
+
+/// Set the expiry of a secret that was just written, per `secrets.expiration`
+/// Keys without a ttl stop being tracked (expiries already set in the provider are kept).
+/// A failed update is logged and retried on the next sync; the write itself succeeded.
+pub(super) async fn renew_expiration(
+    provider: &dyn SecretManagerProvider,
+    policy: Option<&ExpirationPolicy>,
+    key: &str,
+    secret_name: &str,
+    was_updated: bool,
+    sync_state: &mut ResourceSyncState,
+) {
+    let Some(policy) = policy.filter(|policy| policy.ttl_for(key).is_some()) else {
+        sync_state.expire_time = None;
+        return;
+    };
+    let Some(expire_time) = policy.renewal(
+        key,
+        was_updated,
+        sync_state.expire_time.as_deref(),
+        Utc::now(),
+    ) else {
+        return;
+    };
+    match provider
+        .set_secret_expiration(secret_name, expire_time)
+        .await
+    {
+        Ok(()) => {
+            let expire_time = format_expire_time(expire_time);
+            info!("⏳ Secret '{}' expires at {}", secret_name, expire_time);
+            sync_state.expire_time = Some(expire_time);
+        }
+        Err(e) => warn!("Failed to set expiration of secret {}: {}", secret_name, e),
+    }
 }
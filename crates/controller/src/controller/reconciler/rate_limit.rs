@@ -0,0 +1,357 @@
+//! # Provider Rate Limiting
+//!
+//! Per-resource token buckets around provider operations.
+//!
+//! Concurrency limits (see `concurrency`) bound how many resources talk to a provider at
+//! once, but a single resource with 1000 keys can still burn through a project-wide API
+//! quota on its own. Each `SecretManagerConfig` gets a token bucket (`spec.providerRateLimit`,
+//! falling back to the controller-wide defaults) that every provider call waits on.
+//! Buckets persist across reconciliations so a resource can't reset its budget by requeueing.
+
+use crate::crd::SecretManagerConfig;
+use crate::provider::{SecretManagerProvider, SecretMetadata, SecretVersionIds};
+use crate::secret::SecretString;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Effective rate limit for one resource (or one Azure Key Vault)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Sustained operations per second
+    pub requests_per_second: f64,
+    /// Operations allowed back-to-back before throttling starts
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Resolve the limit for a resource from its spec and the controller-wide defaults
+    /// Returns `None` when the resulting rate is 0 (unlimited)
+    #[must_use]
+    pub fn resolve(
+        config: &SecretManagerConfig,
+        default_requests_per_second: f64,
+        default_burst: u32,
+    ) -> Option<Self> {
+        let spec = config.spec.provider_rate_limit.as_ref();
+        Self::new(
+            spec.and_then(|l| l.requests_per_second)
+                .unwrap_or(default_requests_per_second),
+            spec.and_then(|l| l.burst).unwrap_or(default_burst),
+        )
+    }
+
+    /// Limit of `requests_per_second` with `burst` (at least 1)
+    /// Returns `None` when the rate is 0 (unlimited)
+    #[must_use]
+    pub fn new(requests_per_second: f64, burst: u32) -> Option<Self> {
+        if requests_per_second <= 0.0 || !requests_per_second.is_finite() {
+            return None;
+        }
+        Some(Self {
+            requests_per_second,
+            burst: burst.max(1),
+        })
+    }
+}
+
+/// Token bucket: holds up to `burst` tokens, refilled at `requests_per_second`
+#[derive(Debug)]
+pub struct TokenBucket {
+    limit: RateLimit,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket
+    #[must_use]
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(BucketState {
+                tokens: f64::from(limit.burst),
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token if one is available, otherwise return how long until one is
+    fn try_take(&self) -> Result<(), Duration> {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.limit.requests_per_second)
+            .min(f64::from(self.limit.burst));
+        state.last_refill = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - state.tokens) / self.limit.requests_per_second,
+            ))
+        }
+    }
+
+    /// Wait until a token is available and take it
+    pub async fn acquire(&self) {
+        while let Err(wait) = self.try_take() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Token buckets per resource (identified by namespace/name)
+#[derive(Debug, Default)]
+pub struct ResourceRateLimiters {
+    buckets: Mutex<HashMap<String, Arc<TokenBucket>>>,
+}
+
+impl ResourceRateLimiters {
+    /// Get the bucket for a resource, or `None` when it is unlimited
+    /// A bucket is replaced (refilled) when the resource's limit changes
+    pub fn bucket(&self, resource_key: &str, limit: Option<RateLimit>) -> Option<Arc<TokenBucket>> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let Some(limit) = limit else {
+            buckets.remove(resource_key);
+            return None;
+        };
+        let bucket = buckets
+            .entry(resource_key.to_string())
+            .and_modify(|bucket| {
+                if bucket.limit != limit {
+                    *bucket = Arc::new(TokenBucket::new(limit));
+                }
+            })
+            .or_insert_with(|| Arc::new(TokenBucket::new(limit)));
+        Some(Arc::clone(bucket))
+    }
+}
+
+/// Provider wrapper that takes a token from the resource's bucket before every call
+pub struct RateLimitedProvider {
+    inner: Box<dyn SecretManagerProvider>,
+    bucket: Arc<TokenBucket>,
+}
+
+impl std::fmt::Debug for RateLimitedProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimitedProvider")
+            .field("bucket", &self.bucket)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RateLimitedProvider {
+    #[must_use]
+    pub fn new(inner: Box<dyn SecretManagerProvider>, bucket: Arc<TokenBucket>) -> Self {
+        Self { inner, bucket }
+    }
+
+    async fn throttle(&self, operation: &str, secret_name: &str) {
+        let start = Instant::now();
+        self.bucket.acquire().await;
+        let waited = start.elapsed();
+        if waited >= Duration::from_millis(100) {
+            debug!(
+                "Provider rate limit delayed {} of {} by {}ms",
+                operation,
+                secret_name,
+                waited.as_millis()
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl SecretManagerProvider for RateLimitedProvider {
+    async fn create_or_update_secret(
+        &self,
+        secret_name: &str,
+        secret_value: &SecretString,
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        self.throttle("create_or_update_secret", secret_name).await;
+        self.inner
+            .create_or_update_secret(secret_name, secret_value, environment, location)
+            .await
+    }
+
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
+        self.throttle("get_secret_value", secret_name).await;
+        self.inner.get_secret_value(secret_name).await
+    }
+
+    async fn get_secret_values(
+        &self,
+        secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        // A bulk read is a handful of calls however many names it covers
+        self.throttle(
+            "get_secret_values",
+            &format!("{} secrets", secret_names.len()),
+        )
+        .await;
+        self.inner.get_secret_values(secret_names).await
+    }
+
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        self.throttle("create_or_update_secret_binary", secret_name)
+            .await;
+        self.inner
+            .create_or_update_secret_binary(secret_name, secret_value, environment, location)
+            .await
+    }
+
+    async fn get_secret_binary(&self, secret_name: &str) -> Result<Option<Vec<u8>>> {
+        self.throttle("get_secret_binary", secret_name).await;
+        self.inner.get_secret_binary(secret_name).await
+    }
+
+    async fn delete_secret(&self, secret_name: &str) -> Result<()> {
+        self.throttle("delete_secret", secret_name).await;
+        self.inner.delete_secret(secret_name).await
+    }
+
+    async fn disable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.throttle("disable_secret", secret_name).await;
+        self.inner.disable_secret(secret_name).await
+    }
+
+    async fn enable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.throttle("enable_secret", secret_name).await;
+        self.inner.enable_secret(secret_name).await
+    }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        self.throttle("version_ids", secret_name).await;
+        self.inner.version_ids(secret_name).await
+    }
+
+    async fn set_secret_labels(
+        &self,
+        secret_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        self.throttle("set_secret_labels", secret_name).await;
+        self.inner.set_secret_labels(secret_name, labels).await
+    }
+
+    async fn set_secret_expiration(
+        &self,
+        secret_name: &str,
+        expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        self.throttle("set_secret_expiration", secret_name).await;
+        self.inner
+            .set_secret_expiration(secret_name, expire_time)
+            .await
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        self.throttle("list_secrets", prefix).await;
+        self.inner.list_secrets(prefix).await
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        self.throttle("get_secret_metadata", secret_name).await;
+        self.inner.get_secret_metadata(secret_name).await
+    }
+
+    fn auditor(&self) -> Option<&crate::observability::audit::Auditor> {
+        self.inner.auditor()
+    }
+
+    fn rate_limiter(&self) -> Option<&TokenBucket> {
+        Some(&self.bucket)
+    }
+
+    fn router(&self) -> Option<&crate::controller::reconciler::routing::KeyRouter> {
+        self.inner.router()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(requests_per_second: f64, burst: u32) -> RateLimit {
+        RateLimit {
+            requests_per_second,
+            burst,
+        }
+    }
+
+    #[test]
+    fn test_bucket_allows_burst_then_throttles() {
+        let bucket = TokenBucket::new(limit(1.0, 3));
+        for _ in 0..3 {
+            assert!(bucket.try_take().is_ok());
+        }
+        let wait = bucket
+            .try_take()
+            .err()
+            .unwrap_or_else(|| panic!("fourth token should be throttled"));
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_refill() {
+        let bucket = TokenBucket::new(limit(50.0, 1));
+        bucket.acquire().await;
+        let start = Instant::now();
+        bucket.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(15));
+    }
+
+    #[test]
+    fn test_registry_reuses_bucket_until_limit_changes() {
+        let limiters = ResourceRateLimiters::default();
+        let first = limiters
+            .bucket("default/app", Some(limit(5.0, 10)))
+            .unwrap_or_else(|| panic!("bucket expected"));
+        let same = limiters
+            .bucket("default/app", Some(limit(5.0, 10)))
+            .unwrap_or_else(|| panic!("bucket expected"));
+        assert!(Arc::ptr_eq(&first, &same));
+
+        let changed = limiters
+            .bucket("default/app", Some(limit(1.0, 10)))
+            .unwrap_or_else(|| panic!("bucket expected"));
+        assert!(!Arc::ptr_eq(&first, &changed));
+
+        // Removing the limit drops the bucket
+        assert!(limiters.bucket("default/app", None).is_none());
+        assert!(
+            limiters
+                .buckets
+                .lock()
+                .unwrap_or_else(|e| panic!("{e}"))
+                .is_empty()
+        );
+    }
+}
@@ -1,12 +1,19 @@
 //! # Artifact Path Resolution
 //!
-//! Handles resolving artifact paths from FluxCD GitRepository or ArgoCD Application sources.
+//! Handles resolving artifact paths from FluxCD GitRepository, ArgoCD Application or
+//! GitDirect sources, and reading Kubernetes Secret sources.
 
+use crate::controller::reconciler::artifact::download::ArtifactIntegrityError;
 use crate::controller::reconciler::artifact::{
-    get_argocd_artifact_path, get_flux_artifact_path, get_flux_git_repository,
+    GitCredentialsError, get_argocd_artifact_path, get_flux_artifact_path, get_flux_git_repository,
+    get_git_direct_artifact_path,
+};
+use crate::controller::reconciler::source::{
+    SourceSecret, get_source_secret, suspend_git_repository,
+};
+use crate::controller::reconciler::status::{
+    ConditionReason, update_status_phase, update_status_phase_with_reason,
 };
-use crate::controller::reconciler::source::suspend_git_repository;
-use crate::controller::reconciler::status::update_status_phase;
 use crate::controller::reconciler::types::{Reconciler, ReconcilerError};
 use crate::crd::SecretManagerConfig;
 use crate::observability;
@@ -18,15 +25,56 @@ use tracing::{error, info, warn};
 /// Can return a path, indicate waiting for resource, or an error
 #[derive(Debug)]
 pub enum ArtifactPathResult {
-    /// Successfully resolved artifact path
-    Path(PathBuf),
+    /// Successfully resolved artifact path and the source revision it was built from (if known)
+    Path(PathBuf, Option<String>),
+    /// Successfully read a Kubernetes Secret source
+    Secret(SourceSecret),
     /// Need to wait for resource (GitRepository not found or still reconciling)
     AwaitChange,
     /// Error occurred
     Error(ReconcilerError),
 }
 
-/// Resolve artifact path from source (FluxCD GitRepository or ArgoCD Application)
+/// Report a failed checkout of a source the controller clones itself (Application, GitDirect)
+///
+/// Credential problems won't fix themselves on retry, so they fail the resource with the
+/// `CredentialsInvalid` reason; other errors are returned for the normal retry handling.
+async fn checkout_failed(
+    ctx: &Arc<Reconciler>,
+    config: &Arc<SecretManagerConfig>,
+    e: anyhow::Error,
+) -> ArtifactPathResult {
+    observability::metrics::increment_reconciliation_errors();
+    if e.downcast_ref::<GitCredentialsError>().is_some() {
+        error!(
+            "❌ Git credentials for {} {}/{} are invalid: {}",
+            config.spec.source_ref.kind,
+            config.spec.source_ref.namespace,
+            config.spec.source_ref.name,
+            e
+        );
+        let _ = update_status_phase_with_reason(
+            ctx,
+            config,
+            "Failed",
+            Some(&format!("Git credentials invalid: {e}")),
+            Some(ConditionReason::CredentialsInvalid),
+        )
+        .await;
+    } else {
+        error!(
+            "Failed to check out {} {}/{}: {}",
+            config.spec.source_ref.kind,
+            config.spec.source_ref.namespace,
+            config.spec.source_ref.name,
+            e
+        );
+    }
+    ArtifactPathResult::Error(ReconcilerError::ReconciliationFailed(e))
+}
+
+/// Resolve artifact path from source (FluxCD GitRepository, ArgoCD Application or GitDirect),
+/// or read the Kubernetes Secret of a Secret source
 pub async fn resolve_artifact_path(
     config: &Arc<SecretManagerConfig>,
     ctx: &Arc<Reconciler>,
@@ -36,7 +84,7 @@ pub async fn resolve_artifact_path(
         config.spec.source_ref.kind, config.spec.source_ref.name, config.spec.source_ref.namespace
     );
 
-    // Determine artifact path based on source type (GitRepository vs Application)
+    // Determine artifact path based on source type (GitRepository, Application or GitDirect)
     // This path points to the cloned/checked-out repository directory containing secrets
     match config.spec.source_ref.kind.as_str() {
         "GitRepository" => {
@@ -109,11 +157,12 @@ pub async fn resolve_artifact_path(
                             "👀 Waiting for GitRepository creation (trigger source: watch-event)",
                         );
                         // Update status to Pending (waiting for GitRepository)
-                        let _ = update_status_phase(
+                        let _ = update_status_phase_with_reason(
                             ctx,
                             config,
                             "Pending",
                             Some("GitRepository not found, waiting for creation"),
+                            Some(ConditionReason::SourceNotFound),
                         )
                         .await;
                         // Return await_change() to wait for watch event instead of blocking timer loop
@@ -145,11 +194,12 @@ pub async fn resolve_artifact_path(
                     );
                     observability::metrics::increment_reconciliation_errors();
                     // Update status to Failed
-                    let _ = update_status_phase(
+                    let _ = update_status_phase_with_reason(
                         ctx,
                         config,
                         "Failed",
                         Some(&format!("Clone failed, repo unavailable: {e}")),
+                        Some(ConditionReason::SourceUnavailable),
                     )
                     .await;
                     return Ok(ArtifactPathResult::Error(
@@ -168,9 +218,36 @@ pub async fn resolve_artifact_path(
                         path.display(),
                         config.spec.source_ref.name
                     );
-                    return Ok(ArtifactPathResult::Path(path));
+                    let revision = git_repo
+                        .get("status")
+                        .and_then(|s| s.get("artifact"))
+                        .and_then(|a| a.get("revision"))
+                        .and_then(|r| r.as_str())
+                        .map(str::to_string);
+                    return Ok(ArtifactPathResult::Path(path, revision));
                 }
                 Err(e) => {
+                    // Checksum mismatch, unsafe paths and size bombs won't fix themselves
+                    // on retry, so they get their own reason instead of the readiness checks
+                    if e.downcast_ref::<ArtifactIntegrityError>().is_some() {
+                        error!(
+                            "❌ FluxCD artifact for GitRepository {}/{} failed integrity checks: {}",
+                            config.spec.source_ref.namespace, config.spec.source_ref.name, e
+                        );
+                        observability::metrics::increment_reconciliation_errors();
+                        let _ = update_status_phase_with_reason(
+                            ctx,
+                            config,
+                            "Failed",
+                            Some(&format!("Artifact corrupted: {e}")),
+                            Some(ConditionReason::ArtifactCorrupted),
+                        )
+                        .await;
+                        return Ok(ArtifactPathResult::Error(
+                            ReconcilerError::ReconciliationFailed(e),
+                        ));
+                    }
+
                     // Check if GitRepository is ready - if not, wait for it to become ready
                     let status = git_repo.get("status");
                     let is_ready = status
@@ -218,11 +295,12 @@ pub async fn resolve_artifact_path(
                                 "👀 Waiting for GitRepository to become ready (trigger source: watch-event)",
                             );
                             // Update status to Pending (waiting for GitRepository to be ready)
-                            let _ = update_status_phase(
+                            let _ = update_status_phase_with_reason(
                                 ctx,
                                 config,
                                 "Pending",
                                 Some("GitRepository is reconciling, waiting for artifact"),
+                                Some(ConditionReason::SourceNotReady),
                             )
                             .await;
                             // Wait for watch event - GitRepository status updates will trigger reconciliation
@@ -252,11 +330,12 @@ pub async fn resolve_artifact_path(
                             );
                             observability::metrics::increment_reconciliation_errors();
                             // Update status to Failed
-                            let _ = update_status_phase(
+                            let _ = update_status_phase_with_reason(
                                 ctx,
                                 config,
                                 "Failed",
                                 Some(&format!("GitRepository not ready: {}", reason)),
+                                Some(ConditionReason::SourceNotReady),
                             )
                             .await;
                             return Ok(ArtifactPathResult::Error(
@@ -273,11 +352,12 @@ pub async fn resolve_artifact_path(
                     error!("Failed to get FluxCD artifact path: {}", e);
                     observability::metrics::increment_reconciliation_errors();
                     // Update status to Failed
-                    let _ = update_status_phase(
+                    let _ = update_status_phase_with_reason(
                         ctx,
                         config,
                         "Failed",
                         Some(&format!("Failed to get artifact path: {e}")),
+                        Some(ConditionReason::SourceUnavailable),
                     )
                     .await;
                     return Ok(ArtifactPathResult::Error(
@@ -297,11 +377,76 @@ pub async fn resolve_artifact_path(
                         path.display(),
                         config.spec.source_ref.name
                     );
-                    return Ok(ArtifactPathResult::Path(path));
+                    // ArgoCD clones are not tied to a resolved commit here
+                    return Ok(ArtifactPathResult::Path(path, None));
+                }
+                Err(e) => return Ok(checkout_failed(ctx, config, e).await),
+            }
+        }
+        "GitDirect" => {
+            // GitDirect: clone sourceRef.url directly, without a GitOps tool
+            match get_git_direct_artifact_path(ctx, &config.spec.source_ref).await {
+                Ok(path) => {
+                    info!(
+                        "Found GitDirect artifact path: {} for {}",
+                        path.display(),
+                        config.spec.source_ref.url.as_deref().unwrap_or_default()
+                    );
+                    return Ok(ArtifactPathResult::Path(path, None));
+                }
+                Err(e) => return Ok(checkout_failed(ctx, config, e).await),
+            }
+        }
+        "Secret" => {
+            // Secret: mirror the keys of a Kubernetes Secret, nothing to check out
+            match get_source_secret(ctx, &config.spec.source_ref).await {
+                Ok(secret) => {
+                    info!(
+                        "Found {} key(s) in Secret {}/{} (resourceVersion {})",
+                        secret.values.len(),
+                        config.spec.source_ref.namespace,
+                        config.spec.source_ref.name,
+                        secret.resource_version
+                    );
+                    return Ok(ArtifactPathResult::Secret(secret));
                 }
                 Err(e) => {
-                    error!("Failed to get ArgoCD artifact path: {}", e);
+                    let is_404 = e.chain().any(|err| {
+                        matches!(
+                            err.downcast_ref::<kube::Error>(),
+                            Some(kube::Error::Api(api_err)) if api_err.code == 404
+                        )
+                    });
+                    if is_404 {
+                        // Same as a missing GitRepository: wait for the Secret to be created
+                        warn!(
+                            "⏳ Secret {}/{} not found yet, waiting for creation",
+                            config.spec.source_ref.namespace, config.spec.source_ref.name
+                        );
+                        let _ = update_status_phase_with_reason(
+                            ctx,
+                            config,
+                            "Pending",
+                            Some("Secret not found, waiting for creation"),
+                            Some(ConditionReason::SourceNotFound),
+                        )
+                        .await;
+                        return Ok(ArtifactPathResult::AwaitChange);
+                    }
+
+                    error!(
+                        "❌ Failed to read Secret {}/{}: {}",
+                        config.spec.source_ref.namespace, config.spec.source_ref.name, e
+                    );
                     observability::metrics::increment_reconciliation_errors();
+                    let _ = update_status_phase_with_reason(
+                        ctx,
+                        config,
+                        "Failed",
+                        Some(&format!("Failed to read source Secret: {e}")),
+                        Some(ConditionReason::SourceUnavailable),
+                    )
+                    .await;
                     return Ok(ArtifactPathResult::Error(
                         ReconcilerError::ReconciliationFailed(e),
                     ));
@@ -2,20 +2,78 @@
 //!
 //! Handles final status updates, metrics, and requeue logic after secret syncing.
 
+use crate::controller::reconciler::delta::SyncedArtifact;
+use crate::controller::reconciler::expiration::expiring_secrets;
 use crate::controller::reconciler::status::{
     calculate_progressive_backoff, clear_manual_trigger_annotation, clear_parsing_error_count,
     get_parsing_error_count, increment_parsing_error_count, update_status,
 };
 use crate::controller::reconciler::types::{Reconciler, ReconcilerError};
-use crate::controller::reconciler::validation::parse_kubernetes_duration;
-use crate::crd::{ResourceSyncState, SecretManagerConfig};
+use crate::crd::{ProviderConfig, ResourceSyncState, SecretManagerConfig};
 use crate::observability;
 use kube_runtime::controller::Action;
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, error, info, warn};
 
+/// Update the per-resource inventory gauges from the secrets and properties pushed this reconcile
+/// The last-successful-reconcile timestamp is only set when `success` is true
+pub fn record_inventory_metrics(
+    config: &SecretManagerConfig,
+    synced_secrets: &std::collections::HashMap<String, ResourceSyncState>,
+    synced_properties: &std::collections::HashMap<String, ResourceSyncState>,
+    success: bool,
+) {
+    let namespace = config.metadata.namespace.as_deref().unwrap_or("default");
+    let name = config.metadata.name.as_deref().unwrap_or("unknown");
+    let provider = match &config.spec.provider {
+        ProviderConfig::Gcp(_) => "gcp",
+        ProviderConfig::Aws(_) => "aws",
+        ProviderConfig::Azure(_) => "azure",
+    };
+    let environment = match &config.spec.secrets.environments {
+        Some(environments) if config.spec.secrets.environment.is_empty() => environments.join(","),
+        _ => config.spec.secrets.environment.clone(),
+    };
+
+    observability::metrics::set_resource_inventory(
+        namespace,
+        name,
+        provider,
+        &environment,
+        synced_secrets.len(),
+        synced_properties.len(),
+    );
+    observability::metrics::set_secrets_expiring_soon(
+        namespace,
+        name,
+        provider,
+        &environment,
+        expiring_secrets(config, synced_secrets).len(),
+    );
+    if success {
+        #[allow(
+            clippy::cast_precision_loss,
+            reason = "Unix seconds fit in f64 exactly"
+        )]
+        let now = chrono::Utc::now().timestamp() as f64;
+        observability::metrics::set_last_successful_reconcile_timestamp(
+            namespace,
+            name,
+            provider,
+            &environment,
+            now,
+        );
+    }
+}
+
 /// Finalize reconciliation: update status, metrics, and determine next action
+/// `synced_artifact` is the source revision and extracted artifact that were just synced
+/// (None for sources without a revision, which disables delta reconciles)
+#[allow(
+    clippy::too_many_arguments,
+    reason = "Reconcile results are passed through as-is"
+)]
 pub async fn finalize_reconciliation(
     config: &Arc<SecretManagerConfig>,
     ctx: &Arc<Reconciler>,
@@ -24,8 +82,14 @@ pub async fn finalize_reconciliation(
     is_manual_trigger: bool,
     synced_secrets: &std::collections::HashMap<String, ResourceSyncState>,
     synced_properties: &std::collections::HashMap<String, ResourceSyncState>,
+    synced_artifact: Option<SyncedArtifact>,
 ) -> Result<Action, ReconcilerError> {
     let name = config.metadata.name.as_deref().unwrap_or("unknown");
+    let resource_key = format!(
+        "{}/{}",
+        config.metadata.namespace.as_deref().unwrap_or("default"),
+        name
+    );
 
     // Update status (includes synced_secrets and synced_properties push state tracking)
     if let Err(e) = update_status(
@@ -34,6 +98,7 @@ pub async fn finalize_reconciliation(
         secrets_synced as i32,
         synced_secrets,
         synced_properties,
+        synced_artifact.as_ref().map(|a| a.revision.as_str()),
     )
     .await
     {
@@ -42,6 +107,12 @@ pub async fn finalize_reconciliation(
         return Err(ReconcilerError::ReconciliationFailed(e));
     }
 
+    // Remember the synced artifact so the next revision can be reconciled as a delta
+    match synced_artifact {
+        Some(artifact) => ctx.synced_artifacts.record(&resource_key, artifact),
+        None => ctx.synced_artifacts.remove(&resource_key),
+    }
+
     // Clear manual trigger annotation if present (msmctl reconcile)
     // This prevents the annotation from triggering repeated reconciliations
     if is_manual_trigger {
@@ -56,6 +127,7 @@ pub async fn finalize_reconciliation(
     // Update metrics
     observability::metrics::observe_reconciliation_duration(start.elapsed().as_secs_f64());
     observability::metrics::set_secrets_managed(secrets_synced as i64);
+    record_inventory_metrics(config, synced_secrets, synced_properties, true);
 
     // Success - reset backoff state for this resource
     // On successful reconciliation, reset the backoff timer to use the resource's reconcile_interval
@@ -63,11 +135,6 @@ pub async fn finalize_reconciliation(
     // the normal schedule defined in the resource spec
     // Note: Backoff state is managed in error_policy() layer, but we reset it here on success
     // to ensure clean state for next reconciliation
-    let resource_key = format!(
-        "{}/{}",
-        config.metadata.namespace.as_deref().unwrap_or("default"),
-        name
-    );
     let was_in_backoff = if let Ok(mut states) = ctx.backoff_states.lock() {
         if let Some(state) = states.get_mut(&resource_key) {
             let had_errors = state.error_count > 0;
@@ -91,7 +158,7 @@ pub async fn finalize_reconciliation(
     // Each resource has its own reconcileInterval and maintains its own error count
     // Parse the reconcile interval and requeue after that duration
     // This ensures we don't reconcile more frequently than specified per resource
-    match parse_kubernetes_duration(&config.spec.reconcile_interval) {
+    match config.spec.reconcile_interval.to_duration() {
         Ok(duration) => {
             // Successfully parsed - use the specified interval for THIS resource
             // Reset any parsing error count by clearing the annotation if it exists
@@ -5,35 +5,147 @@
 mod artifact_path;
 mod finalize;
 mod provider;
+mod role_assignments;
 mod sync;
 
 pub use artifact_path::{ArtifactPathResult, resolve_artifact_path};
 pub use finalize::finalize_reconciliation;
-pub use provider::create_provider;
-pub use sync::{SyncResult, sync_secrets};
+pub use provider::{ProviderFactory, create_provider};
+use role_assignments::check_role_assignments;
+pub use sync::{SyncResult, SyncSource, sync_secrets};
 
 use crate::config::SharedControllerConfig;
-use crate::controller::reconciler::status::{update_status, update_status_phase};
+use crate::controller::reconciler::approval::{PlanningProvider, is_approved};
+use crate::controller::reconciler::concurrency::ProjectBusy;
+use crate::controller::reconciler::delta::{DELTA_RECONCILE_GATE, SyncedArtifact, plan_delta};
+use crate::controller::reconciler::events::publish_warning;
+use crate::controller::reconciler::expiration::expiring_secrets;
+use crate::controller::reconciler::last_writer::{
+    CONFLICT_DETECTION_GATE, LastWriterProvider, LastWriterTracker,
+};
+use crate::controller::reconciler::limits::{LimitExceeded, ValueLimits};
+use crate::controller::reconciler::not_found_cache::{
+    NotFoundCachedProvider, PROVIDER_NOT_FOUND_CACHE_GATE,
+};
+use crate::controller::reconciler::policy::PolicyViolations;
+use crate::controller::reconciler::rate_limit::{RateLimit, RateLimitedProvider};
+use crate::controller::reconciler::status::{
+    ConditionReason, count_changed, record_reconcile, truncate_message,
+    update_conflicting_writer_condition, update_expiring_soon_condition,
+    update_missing_role_assignments_condition, update_status, update_status_awaiting_approval,
+    update_status_partial_failure, update_status_phase, update_status_phase_with_reason,
+    update_wrong_encryption_key_condition,
+};
 use crate::controller::reconciler::types::{Reconciler, ReconcilerError, TriggerSource};
-use crate::controller::reconciler::validation::{
-    validate_duration_interval, validate_secret_manager_config,
+use crate::controller::reconciler::validation::validate_secret_manager_config;
+use crate::crd::{
+    ApprovalPolicy, FailedSecret, FailedService, ProviderConfig, ReconcileRecord,
+    SecretManagerConfig,
 };
-use crate::crd::{ProviderConfig, SecretManagerConfig};
 use crate::observability;
+use crate::observability::audit::{AuditContext, AuditingProvider, Auditor};
+use crate::provider::SecretManagerProvider;
+use crate::provider::gcp::check_endpoint_override;
 use kube_runtime::controller::Action;
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, error, info, warn};
 
+/// Outcome details of a reconciliation, reported to the reconcile history
+#[derive(Debug, Default)]
+struct ReconcileRun {
+    /// Outcome when it isn't plain Succeeded (Ok) or Failed (Err)
+    outcome: Option<&'static str>,
+    /// Message when the reconciliation didn't fail with an error (e.g. invalid spec)
+    message: Option<String>,
+    secrets_changed: u32,
+    /// Set when the resource's cloud project was busy and nothing was reconciled
+    project_busy: Option<ProjectBusy>,
+}
+
 /// Main reconciliation function
 /// Reconcile internal logic - errors are handled by error_policy() in main.rs
 /// This separation prevents blocking watch/timer paths when many resources fail
 /// Backoff logic is now in error_policy() layer as recommended by kube-rs best practices
+/// Every run is recorded in status.recentReconciles, except requeues of a busy project
 pub async fn reconcile(
     config: Arc<SecretManagerConfig>,
     ctx: Arc<Reconciler>,
     trigger_source: TriggerSource,
     controller_config: SharedControllerConfig,
+) -> Result<Action, ReconcilerError> {
+    reconcile_recorded(config, ctx, trigger_source, controller_config)
+        .await
+        .0
+}
+
+/// Reconcile like `reconcile`, but hand a busy cloud project back to the caller
+/// For callers outside the controller (the startup queue), which can't act on the
+/// `Action::requeue` a busy project returns
+pub async fn reconcile_or_busy(
+    config: Arc<SecretManagerConfig>,
+    ctx: Arc<Reconciler>,
+    trigger_source: TriggerSource,
+    controller_config: SharedControllerConfig,
+) -> Result<Result<Action, ReconcilerError>, ProjectBusy> {
+    match reconcile_recorded(config, ctx, trigger_source, controller_config).await {
+        (_, Some(busy)) => Err(busy),
+        (result, None) => Ok(result),
+    }
+}
+
+/// Run a reconciliation and record it in status.recentReconciles
+async fn reconcile_recorded(
+    config: Arc<SecretManagerConfig>,
+    ctx: Arc<Reconciler>,
+    trigger_source: TriggerSource,
+    controller_config: SharedControllerConfig,
+) -> (Result<Action, ReconcilerError>, Option<ProjectBusy>) {
+    let started_at = chrono::Utc::now();
+    let start = Instant::now();
+    let mut run = ReconcileRun::default();
+    let result = reconcile_run(
+        Arc::clone(&config),
+        Arc::clone(&ctx),
+        trigger_source,
+        controller_config,
+        &mut run,
+    )
+    .await;
+
+    let project_busy = run.project_busy;
+    let (outcome, message) = match &result {
+        Ok(_) => (run.outcome.unwrap_or("Succeeded"), run.message),
+        Err(e) => (
+            run.outcome.unwrap_or("Failed"),
+            Some(run.message.unwrap_or_else(|| e.to_string())),
+        ),
+    };
+    let record = ReconcileRecord {
+        trigger: trigger_source.as_str().to_string(),
+        started_at: started_at.to_rfc3339(),
+        duration_ms: u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX),
+        outcome: outcome.to_string(),
+        secrets_changed: run.secrets_changed,
+        message: message.as_deref().map(truncate_message),
+    };
+    // A busy project retries within seconds; recording each retry would push the real
+    // sync history out of recentReconciles
+    if project_busy.is_none()
+        && let Err(e) = record_reconcile(&ctx, &config, record).await
+    {
+        warn!("Failed to record reconcile history: {}", e);
+    }
+
+    (result, project_busy)
+}
+
+async fn reconcile_run(
+    config: Arc<SecretManagerConfig>,
+    ctx: Arc<Reconciler>,
+    trigger_source: TriggerSource,
+    controller_config: SharedControllerConfig,
+    run: &mut ReconcileRun,
 ) -> Result<Action, ReconcilerError> {
     let start = Instant::now();
     let name = config.metadata.name.as_deref().unwrap_or("unknown");
@@ -53,28 +165,36 @@ pub async fn reconcile(
         ProviderConfig::Aws(_) => "aws",
         ProviderConfig::Azure(_) => "azure",
     };
-    let span = tracing::span!(
-        tracing::Level::INFO,
-        "reconcile",
-        resource.name = name,
-        resource.namespace = config.metadata.namespace.as_deref().unwrap_or("default"),
-        resource.kind = "SecretManagerConfig",
-        resource.provider = provider_type
+    // spec.otel.samplingRatio decides whether this reconciliation is traced at all
+    let otel_config = config.spec.otel.as_ref();
+    let trace_seed = (
+        config.metadata.uid.as_deref(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default(),
     );
+    let span = if observability::otel::should_trace_reconcile(otel_config, trace_seed) {
+        tracing::span!(
+            tracing::Level::INFO,
+            "reconcile",
+            resource.name = name,
+            resource.namespace = config.metadata.namespace.as_deref().unwrap_or("default"),
+            resource.kind = "SecretManagerConfig",
+            resource.provider = provider_type
+        )
+    } else {
+        tracing::Span::none()
+    };
+    observability::otel::record_resource_attributes(&span, otel_config);
     let _guard = span.enter();
 
     // Comprehensive validation of all CRD fields
+    // An invalid spec won't fix itself on retry, so it's reported as a terminal InvalidSpec
+    // condition and the resource waits for the next spec change instead of backing off
     if let Err(e) = validate_secret_manager_config(&config) {
         error!("Validation error for {}: {}", name, e);
-        // Update status to Failed with validation error
-        let _ = update_status_phase(
-            &ctx,
-            &config,
-            "Failed",
-            Some(&format!("Validation failed: {e}")),
-        )
-        .await;
-        return Err(ReconcilerError::ReconciliationFailed(e));
+        return Ok(invalid_spec(&ctx, &config, run, format!("Validation failed: {e}")).await);
     }
 
     // Validate GitRepository pull interval - must be at least configured minimum to avoid rate limits
@@ -82,26 +202,22 @@ pub async fn reconcile(
         let cfg = controller_config.read().await;
         cfg.min_gitrepository_pull_interval_secs
     };
-    if let Err(e) = validate_duration_interval(
-        &config.spec.git_repository_pull_interval,
-        "gitRepositoryPullInterval",
-        min_git_pull_interval,
-    ) {
-        let err = anyhow::anyhow!(
-            "Invalid gitRepositoryPullInterval '{}': {}",
-            config.spec.git_repository_pull_interval,
-            e
+    if let Err(e) = config
+        .spec
+        .git_repository_pull_interval
+        .to_duration_at_least("gitRepositoryPullInterval", min_git_pull_interval)
+    {
+        error!(
+            "Validation error for {}: Invalid gitRepositoryPullInterval '{}': {}",
+            name, config.spec.git_repository_pull_interval, e
         );
-        error!("Validation error for {}: {}", name, err);
-        // Update status to Failed with validation error
-        let _ = update_status_phase(
+        return Ok(invalid_spec(
             &ctx,
             &config,
-            "Failed",
-            Some(&format!("Invalid gitRepositoryPullInterval: {e}")),
+            run,
+            format!("Invalid gitRepositoryPullInterval: {e}"),
         )
-        .await;
-        return Err(ReconcilerError::ReconciliationFailed(err));
+        .await);
     }
 
     // Validate reconcile interval - must be at least configured minimum to avoid rate limits
@@ -109,26 +225,54 @@ pub async fn reconcile(
         let cfg = controller_config.read().await;
         cfg.min_reconcile_interval_secs
     };
-    if let Err(e) = validate_duration_interval(
-        &config.spec.reconcile_interval,
-        "reconcileInterval",
-        min_reconcile_interval,
-    ) {
-        let err = anyhow::anyhow!(
-            "Invalid reconcileInterval '{}': {}",
-            config.spec.reconcile_interval,
-            e
+    if let Err(e) = config
+        .spec
+        .reconcile_interval
+        .to_duration_at_least("reconcileInterval", min_reconcile_interval)
+    {
+        error!(
+            "Validation error for {}: Invalid reconcileInterval '{}': {}",
+            name, config.spec.reconcile_interval, e
         );
-        error!("Validation error for {}: {}", name, err);
-        // Update status to Failed with validation error
-        let _ = update_status_phase(
+        return Ok(invalid_spec(
             &ctx,
             &config,
-            "Failed",
-            Some(&format!("Invalid reconcileInterval: {e}")),
+            run,
+            format!("Invalid reconcileInterval: {e}"),
         )
-        .await;
-        return Err(ReconcilerError::ReconciliationFailed(err));
+        .await);
+    }
+
+    // GCP endpoint overrides must stay within the domains the cluster operator allows,
+    // since the controller's access token is sent to them
+    if let ProviderConfig::Gcp(gcp) = &config.spec.provider {
+        if let Some(endpoint_override) = &gcp.endpoint_override {
+            let allowed_domains = {
+                let cfg = controller_config.read().await;
+                cfg.provider_endpoints.gcp_allowed_domains.clone()
+            };
+            for url in [
+                &endpoint_override.secret_manager,
+                &endpoint_override.parameter_manager,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                if let Err(e) = check_endpoint_override(url, &allowed_domains) {
+                    error!(
+                        "Validation error for {}: Invalid provider.gcp.endpointOverride: {}",
+                        name, e
+                    );
+                    return Ok(invalid_spec(
+                        &ctx,
+                        &config,
+                        run,
+                        format!("Invalid provider.gcp.endpointOverride: {e}"),
+                    )
+                    .await);
+                }
+            }
+        }
     }
 
     // Check if reconciliation is suspended
@@ -152,9 +296,28 @@ pub async fn reconcile(
         }
         // Return Action::await_change() to wait for suspend to be cleared
         // This ensures we don't reconcile until suspend is set to false
+        run.outcome = Some("Suspended");
         return Ok(Action::await_change());
     }
 
+    // Take a turn of the resource's cloud project before any status write or source work,
+    // so a busy project costs nothing but the requeue. The permit is held until
+    // reconciliation finishes. A busy project requeues the resource instead of parking
+    // this worker until the project is free.
+    let _project_permit = match ctx.project_queues.try_acquire(&config.spec.provider) {
+        Ok(permit) => permit,
+        Err(busy) => {
+            debug!(
+                "Cloud project of {} is busy, requeueing in {:?}",
+                name, busy.retry_after
+            );
+            observability::metrics::increment_requeues_total("project-busy");
+            run.outcome = Some("ProjectBusy");
+            run.project_busy = Some(busy);
+            return Ok(Action::requeue(busy.retry_after));
+        }
+    };
+
     // Check if this is a manual reconciliation trigger (via annotation)
     let is_manual_trigger = config
         .metadata
@@ -266,19 +429,210 @@ pub async fn reconcile(
         config.metadata.namespace.as_deref().unwrap_or("default")
     );
 
-    // Resolve artifact path
-    let artifact_path = match resolve_artifact_path(&config, &ctx).await {
-        Ok(ArtifactPathResult::Path(path)) => path,
+    // Resolve artifact path (or read the Secret of a Secret source)
+    let (source, source_revision) = match resolve_artifact_path(&config, &ctx).await {
+        Ok(ArtifactPathResult::Path(path, revision)) => (SyncSource::Artifact(path), revision),
+        Ok(ArtifactPathResult::Secret(secret)) => {
+            // A Secret's resourceVersion changes on every write, so it is the Secret
+            // source's revision: recorded as lastSyncedRevision and in the audit log
+            if let Some(last_synced) = config
+                .status
+                .as_ref()
+                .and_then(|s| s.last_synced_revision.as_deref())
+                .filter(|revision| *revision != secret.resource_version)
+            {
+                info!(
+                    "🔀 Source Secret changed since the last sync (resourceVersion {} -> {})",
+                    last_synced, secret.resource_version
+                );
+            }
+            let revision = secret.resource_version.clone();
+            (SyncSource::Secret(secret), Some(revision))
+        }
         Ok(ArtifactPathResult::AwaitChange) => {
             // Need to wait for resource - return await_change
+            run.outcome = Some("WaitingForSource");
             return Ok(Action::await_change());
         }
         Ok(ArtifactPathResult::Error(e)) => return Err(e),
         Err(e) => return Err(e),
     };
 
-    // Create provider client
-    let provider = create_provider(&config, &ctx).await?;
+    // Wait for a provider concurrency slot; held until reconciliation finishes
+    let _provider_permit = ctx.provider_limiter.acquire(provider_type).await;
+
+    // Create provider client (endpoint overrides are hot-reloadable controller config)
+    let (
+        provider_endpoints,
+        rate_limit,
+        vault_rate_limit,
+        value_limits,
+        delta_enabled,
+        conflict_detection,
+        not_found_cache,
+    ) = {
+        let controller_config = controller_config.read().await;
+        (
+            controller_config.provider_endpoints.clone(),
+            RateLimit::resolve(
+                &config,
+                controller_config.provider_rate_limit_rps,
+                controller_config.provider_rate_limit_burst,
+            ),
+            RateLimit::new(
+                controller_config.azure_vault_rate_limit_rps,
+                controller_config.azure_vault_rate_limit_burst,
+            ),
+            ValueLimits::resolve(
+                &config,
+                controller_config.max_secret_size_bytes,
+                controller_config.max_secrets_per_resource,
+                controller_config.max_total_secret_bytes,
+            ),
+            controller_config.feature_enabled(DELTA_RECONCILE_GATE),
+            controller_config.feature_enabled(CONFLICT_DETECTION_GATE),
+            controller_config.feature_enabled(PROVIDER_NOT_FOUND_CACHE_GATE),
+        )
+    };
+    let provider = create_provider(&config, &ctx, &provider_endpoints, vault_rate_limit).await?;
+
+    let resource_key = format!(
+        "{}/{}",
+        config.metadata.namespace.as_deref().unwrap_or("default"),
+        name
+    );
+
+    // Delta reconcile: when the previous artifact is still cached, only process the
+    // profiles that changed since the last-synced revision (None = full sync)
+    // Secret sources have no artifact to diff and are always synced in full
+    let changes = match source.artifact_path() {
+        Some(artifact_path) if delta_enabled => plan_delta(
+            &config,
+            ctx.synced_artifacts.get(&resource_key).as_ref(),
+            artifact_path,
+            source_revision.as_deref(),
+        ),
+        _ => None,
+    };
+    let synced_artifact = source_revision.clone().map(|revision| SyncedArtifact {
+        revision,
+        path: source.artifact_path().cloned().unwrap_or_default(),
+    });
+
+    // Throttle this resource's provider calls to its token bucket (if rate limited)
+    let provider: Box<dyn SecretManagerProvider> =
+        match ctx.rate_limiters.bucket(&resource_key, rate_limit) {
+            Some(bucket) => Box::new(RateLimitedProvider::new(provider, bucket)),
+            None => provider,
+        };
+
+    // Answer reads of secrets already found missing at this revision from memory
+    let provider: Box<dyn SecretManagerProvider> = match not_found_cache
+        .then(|| {
+            ctx.not_found_cache.scope(
+                &resource_key,
+                &config.spec.provider,
+                source_revision.as_deref(),
+            )
+        })
+        .flatten()
+    {
+        Some(scope) => Box::new(NotFoundCachedProvider::new(provider, scope, provider_type)),
+        None => provider,
+    };
+
+    // Record every mutation made through the provider when the audit log is enabled
+    let provider: Box<dyn SecretManagerProvider> = if ctx.audit_log.is_enabled() {
+        let context = AuditContext::new(&config, trigger_source_str, source_revision.clone());
+        Box::new(AuditingProvider::new(
+            provider,
+            Auditor::new(Arc::clone(&ctx.audit_log), context),
+        ))
+    } else {
+        provider
+    };
+
+    // Stamp writes with last-writer labels so diff discovery can tell foreign writes apart
+    let provider: Box<dyn SecretManagerProvider> = if conflict_detection {
+        let tracker = LastWriterTracker::new(&config, source_revision.as_deref());
+        Box::new(LastWriterProvider::new(provider, tracker))
+    } else {
+        provider
+    };
+
+    // Approval gate: plan the sync without writing and stage the changes in status
+    // until the approve annotation matches their hash
+    if config.spec.approval_policy == Some(ApprovalPolicy::Manual) {
+        let planning = PlanningProvider::new(&*provider);
+        match sync_secrets(
+            &config,
+            &ctx,
+            &planning,
+            &source,
+            changes.as_ref(),
+            value_limits,
+        )
+        .await?
+        {
+            (SyncResult::Success(_), _, _) => {}
+            (SyncResult::LimitExceeded(exceeded), _, _) => {
+                return Ok(limit_exceeded(&ctx, &config, run, &exceeded).await);
+            }
+            (SyncResult::PolicyViolation(violations), _, _) => {
+                return Ok(policy_violation(&ctx, &config, run, &violations).await);
+            }
+            (SyncResult::TransientError { retry_after }, _, _) => {
+                run.outcome = Some("TransientError");
+                return Ok(Action::requeue(
+                    retry_after.unwrap_or(std::time::Duration::from_secs(30)),
+                ));
+            }
+            (
+                SyncResult::PartialFailure {
+                    failed,
+                    failed_services,
+                    ..
+                },
+                _,
+                _,
+            ) => {
+                return Err(ReconcilerError::ReconciliationFailed(anyhow::anyhow!(
+                    "Failed to plan changes for {} secret(s) and {} service(s): {}",
+                    failed.len(),
+                    failed_services.len(),
+                    failure_summary(&failed, &failed_services)
+                )));
+            }
+            (SyncResult::Error(e), _, _) => return Err(e),
+        }
+
+        if let Some(pending) = planning.plan(source_revision.as_deref()) {
+            if !is_approved(&config, &pending.hash) {
+                info!(
+                    "⏸️  {} change(s) for {} awaiting approval: {} (approve with hash {})",
+                    pending.operations.len(),
+                    resource_key,
+                    pending.summary,
+                    pending.hash
+                );
+                if let Err(e) = update_status_awaiting_approval(&ctx, &config, &pending).await {
+                    warn!("Failed to stage changes awaiting approval: {}", e);
+                }
+                run.outcome = Some("AwaitingApproval");
+                // Re-plan on the normal interval so drift and new commits update the change set
+                let requeue = config
+                    .spec
+                    .reconcile_interval
+                    .to_duration()
+                    .unwrap_or(std::time::Duration::from_secs(60));
+                return Ok(Action::requeue(requeue));
+            }
+            info!(
+                "Applying approved change set {} for {}: {}",
+                pending.hash, resource_key, pending.summary
+            );
+        }
+    }
 
     // Determine sync mode: secrets vs configs (properties)
     // Configs are stored in config stores (Parameter Store, App Configuration)
@@ -314,15 +668,62 @@ pub async fn reconcile(
     }
 
     // Sync secrets
+    // Drop recipient checks left by the approval plan or an aborted sync
+    ctx.recipient_checks.take(&resource_key);
+    let previous_sync = config.status.as_ref().and_then(|s| s.sync.as_ref());
     let (sync_result, synced_secrets, synced_properties): (
         Result<u32, ReconcilerError>,
         std::collections::HashMap<String, crate::crd::ResourceSyncState>,
         std::collections::HashMap<String, crate::crd::ResourceSyncState>,
-    ) = match sync_secrets(&config, &ctx, &*provider, &artifact_path).await {
+    ) = match sync_secrets(
+        &config,
+        &ctx,
+        &*provider,
+        &source,
+        changes.as_ref(),
+        value_limits,
+    )
+    .await
+    {
         Ok((SyncResult::Success(count), synced_secrets, synced_properties)) => {
+            run.secrets_changed = count_changed(previous_sync, &synced_secrets, &synced_properties);
             (Ok(count), synced_secrets, synced_properties)
         }
-        Ok((SyncResult::TransientError, synced_secrets, synced_properties)) => {
+        Ok((
+            SyncResult::PartialFailure {
+                synced,
+                failed,
+                failed_services,
+            },
+            synced_secrets,
+            synced_properties,
+        )) => {
+            run.outcome = Some("PartialFailure");
+            run.secrets_changed = count_changed(previous_sync, &synced_secrets, &synced_properties);
+            // Successful secrets are already written; record them alongside the failures,
+            // then return an aggregate error so error_policy() applies backoff
+            finalize::record_inventory_metrics(&config, &synced_secrets, &synced_properties, false);
+            let _ = update_status_partial_failure(
+                &ctx,
+                &config,
+                synced as i32,
+                &synced_secrets,
+                &synced_properties,
+                &failed,
+                &failed_services,
+            )
+            .await;
+            return Err(ReconcilerError::ReconciliationFailed(anyhow::anyhow!(
+                "{} secret(s) and {} service(s) failed to sync ({} synced): {}",
+                failed.len(),
+                failed_services.len(),
+                synced,
+                failure_summary(&failed, &failed_services)
+            )));
+        }
+        Ok((SyncResult::TransientError { retry_after }, synced_secrets, synced_properties)) => {
+            run.outcome = Some("TransientError");
+            run.secrets_changed = count_changed(previous_sync, &synced_secrets, &synced_properties);
             // Transient error - retry after delay, but preserve synced_secrets and synced_properties state
             // Update status with current state before retrying
             let current_count = config
@@ -336,11 +737,23 @@ pub async fn reconcile(
                 current_count,
                 &synced_secrets,
                 &synced_properties,
+                None,
             )
             .await;
-            return Ok(Action::requeue(std::time::Duration::from_secs(30)));
+            return Ok(Action::requeue(
+                retry_after.unwrap_or(std::time::Duration::from_secs(30)),
+            ));
+        }
+        Ok((SyncResult::LimitExceeded(exceeded), synced_secrets, synced_properties)) => {
+            run.secrets_changed = count_changed(previous_sync, &synced_secrets, &synced_properties);
+            return Ok(limit_exceeded(&ctx, &config, run, &exceeded).await);
+        }
+        Ok((SyncResult::PolicyViolation(violations), synced_secrets, synced_properties)) => {
+            run.secrets_changed = count_changed(previous_sync, &synced_secrets, &synced_properties);
+            return Ok(policy_violation(&ctx, &config, run, &violations).await);
         }
         Ok((SyncResult::Error(e), synced_secrets, synced_properties)) => {
+            run.secrets_changed = count_changed(previous_sync, &synced_secrets, &synced_properties);
             // Update status with synced_secrets and synced_properties even on error (preserve push state)
             let current_count = config
                 .status
@@ -353,6 +766,7 @@ pub async fn reconcile(
                 current_count,
                 &synced_secrets,
                 &synced_properties,
+                None,
             )
             .await;
             return Err(e);
@@ -362,8 +776,86 @@ pub async fn reconcile(
 
     let secrets_synced = sync_result?;
 
+    let conflicts = provider
+        .last_writer()
+        .map(LastWriterTracker::conflicts)
+        .unwrap_or_default();
+    if !conflicts.is_empty() {
+        for conflict in &conflicts {
+            observability::metrics::increment_conflicting_writers_total(conflict.kind());
+        }
+        let note = conflicts
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        warn!(
+            "⚠️  {} drifted secret(s) of {} were last written by someone else: {}",
+            conflicts.len(),
+            resource_key,
+            note
+        );
+        publish_warning(&ctx, &config, "ConflictingWriter", "Sync", note).await;
+    }
+
+    // None when no SOPS file was decrypted (e.g. a delta reconcile of plaintext profiles)
+    let recipient_mismatches = ctx.recipient_checks.take(&resource_key);
+    if let Some(mismatches) = recipient_mismatches.as_ref().filter(|m| !m.is_empty()) {
+        let note = mismatches
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        warn!(
+            "⚠️  {} SOPS file(s) of {} don't match their .sops.yaml creation rule: {}",
+            mismatches.len(),
+            resource_key,
+            note
+        );
+        publish_warning(&ctx, &config, "WrongEncryptionKey", "Sync", note).await;
+    }
+
+    let role_assignments = check_role_assignments(&ctx, &config, &synced_secrets).await;
+    match &role_assignments {
+        Some(Ok(missing)) if !missing.is_empty() => {
+            let note = missing
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ");
+            warn!(
+                "⚠️  {} Key Vault role assignment(s) of {} are missing: {}",
+                missing.len(),
+                resource_key,
+                note
+            );
+            publish_warning(&ctx, &config, "MissingRoleAssignments", "Sync", note).await;
+        }
+        Some(Err(e)) => warn!(
+            "Failed to check Key Vault role assignments of {}: {:#}",
+            resource_key, e
+        ),
+        _ => {}
+    }
+
+    let expiring = expiring_secrets(&config, &synced_secrets);
+    if !expiring.is_empty() {
+        let note = expiring
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        warn!(
+            "⚠️  {} secret(s) of {} expire soon and should be rotated: {}",
+            expiring.len(),
+            resource_key,
+            note
+        );
+        publish_warning(&ctx, &config, "ExpiringSoon", "Sync", note).await;
+    }
+
     // Finalize reconciliation (includes status update with synced_secrets and synced_properties)
-    finalize_reconciliation(
+    let action = finalize_reconciliation(
         &config,
         &ctx,
         start,
@@ -371,6 +863,140 @@ pub async fn reconcile(
         is_manual_trigger,
         &synced_secrets,
         &synced_properties,
+        synced_artifact,
     )
-    .await
+    .await?;
+
+    if conflict_detection {
+        if let Err(e) = update_conflicting_writer_condition(&ctx, &config, &conflicts).await {
+            warn!("Failed to update ConflictingWriter condition: {}", e);
+        }
+    }
+    if let Some(mismatches) = recipient_mismatches {
+        if let Err(e) = update_wrong_encryption_key_condition(&ctx, &config, &mismatches).await {
+            warn!("Failed to update WrongEncryptionKey condition: {}", e);
+        }
+    }
+    if let Some(result) = &role_assignments {
+        if let Err(e) = update_missing_role_assignments_condition(&ctx, &config, result).await {
+            warn!("Failed to update MissingRoleAssignments condition: {}", e);
+        }
+    }
+    if let Err(e) = update_expiring_soon_condition(&ctx, &config, &expiring).await {
+        warn!("Failed to update ExpiringSoon condition: {}", e);
+    }
+    Ok(action)
+}
+
+/// Report an invalid spec as a terminal `InvalidSpec` condition
+/// Returns `await_change()`: retrying can't succeed until the spec is edited
+async fn invalid_spec(
+    ctx: &Reconciler,
+    config: &SecretManagerConfig,
+    run: &mut ReconcileRun,
+    message: String,
+) -> Action {
+    observability::metrics::increment_reconciliation_errors();
+    let _ = update_status_phase_with_reason(
+        ctx,
+        config,
+        "Failed",
+        Some(&message),
+        Some(ConditionReason::InvalidSpec),
+    )
+    .await;
+    run.outcome = Some("InvalidSpec");
+    run.message = Some(message);
+    Action::await_change()
+}
+
+/// Report values over the resource's limits with the `LimitExceeded` reason
+/// Requeues on the normal interval: the fix is a new commit, which a retry with backoff
+/// can't speed up
+async fn limit_exceeded(
+    ctx: &Reconciler,
+    config: &SecretManagerConfig,
+    run: &mut ReconcileRun,
+    exceeded: &LimitExceeded,
+) -> Action {
+    let message = format!("Secret limits exceeded: {exceeded}");
+    error!(
+        "❌ {} for {}/{}; nothing was written for the offending service",
+        message,
+        config.metadata.namespace.as_deref().unwrap_or("default"),
+        config.metadata.name.as_deref().unwrap_or("unknown")
+    );
+    observability::metrics::increment_reconciliation_errors();
+    observability::metrics::increment_limit_exceeded_total(exceeded.limit());
+    let _ = update_status_phase_with_reason(
+        ctx,
+        config,
+        "Failed",
+        Some(&message),
+        Some(ConditionReason::LimitExceeded),
+    )
+    .await;
+    run.outcome = Some("LimitExceeded");
+    run.message = Some(message);
+    Action::requeue(
+        config
+            .spec
+            .reconcile_interval
+            .to_duration()
+            .unwrap_or(std::time::Duration::from_secs(60)),
+    )
+}
+
+/// Report keys against the resource's policy with the `PolicyViolation` reason and a
+/// Warning event listing every violation
+/// Requeues on the normal interval, like `limit_exceeded`
+async fn policy_violation(
+    ctx: &Reconciler,
+    config: &SecretManagerConfig,
+    run: &mut ReconcileRun,
+    violations: &PolicyViolations,
+) -> Action {
+    let message = format!("Secret policy violated: {violations}");
+    error!(
+        "❌ {} for {}/{}; nothing was written for the offending service",
+        message,
+        config.metadata.namespace.as_deref().unwrap_or("default"),
+        config.metadata.name.as_deref().unwrap_or("unknown")
+    );
+    observability::metrics::increment_reconciliation_errors();
+    for violation in &violations.0 {
+        observability::metrics::increment_policy_violations_total(violation.rule());
+    }
+    publish_warning(ctx, config, "PolicyViolation", "Sync", message.clone()).await;
+    let _ = update_status_phase_with_reason(
+        ctx,
+        config,
+        "Failed",
+        Some(&message),
+        Some(ConditionReason::PolicyViolation),
+    )
+    .await;
+    run.outcome = Some("PolicyViolation");
+    run.message = Some(message);
+    Action::requeue(
+        config
+            .spec
+            .reconcile_interval
+            .to_duration()
+            .unwrap_or(std::time::Duration::from_secs(60)),
+    )
+}
+
+/// `name: reason` for every failed secret and service, for the reconcile error
+fn failure_summary(failed: &[FailedSecret], failed_services: &[FailedService]) -> String {
+    failed
+        .iter()
+        .map(|f| format!("{}: {}", f.name, f.reason))
+        .chain(
+            failed_services
+                .iter()
+                .map(|f| format!("service {}: {}", f.name, f.reason)),
+        )
+        .collect::<Vec<_>>()
+        .join("; ")
 }
@@ -2,16 +2,35 @@
 //!
 //! Handles creation of cloud provider clients (GCP, AWS, Azure).
 
+use crate::config::ProviderEndpoints;
+use crate::controller::reconciler::rate_limit::RateLimit;
+use crate::controller::reconciler::routing::{
+    KeyRouter, RouteTarget, RoutingProvider, provider_location, route_target_config,
+};
 use crate::controller::reconciler::types::{Reconciler, ReconcilerError};
 use crate::crd::{ProviderConfig, SecretManagerConfig};
-use crate::provider::SecretManagerProvider;
 use crate::provider::aws::AwsSecretManager;
+use crate::provider::aws::partition::resource_endpoint_url;
 use crate::provider::azure::AzureKeyVault;
-use crate::provider::gcp::create_gcp_provider;
+use crate::provider::gcp::{Impersonation, create_gcp_provider, resource_endpoint_override};
+use crate::provider::{ConfigStoreProvider, SecretManagerProvider};
 use anyhow::Context;
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
+/// Supplies provider clients in place of the cloud clients
+///
+/// Set with `Reconciler::with_provider_factory` to run reconciliations against in-memory
+/// providers (see `crate::testing`). Without a factory, clients are built from the resource's
+/// provider configuration.
+pub trait ProviderFactory: Send + Sync {
+    /// Secret store for a resource
+    fn secret_manager(&self, config: &SecretManagerConfig) -> Box<dyn SecretManagerProvider>;
+
+    /// Config store for a resource with `configs.enabled`
+    fn config_store(&self, config: &SecretManagerConfig) -> Box<dyn ConfigStoreProvider>;
+}
+
 /// Create provider client based on provider configuration
 ///
 /// Each provider has different authentication methods:
@@ -19,10 +38,46 @@ use tracing::{error, info, warn};
 /// - AWS: IRSA - IAM Roles for Service Accounts (default)
 /// - Azure: Workload Identity or Managed Identity (default)
 /// Provider is created per-reconciliation to support per-resource auth config
+/// Endpoint overrides come from the controller configuration; Azure Key Vault clients pace
+/// their requests with the vault's shared pacer, limited to `vault_rate_limit`
+///
+/// With `secrets.routes`, every route gets its own client for the merged target configuration
+/// and the clients are combined in a `RoutingProvider`
 pub async fn create_provider(
     config: &Arc<SecretManagerConfig>,
     ctx: &Arc<Reconciler>,
+    endpoints: &ProviderEndpoints,
+    vault_rate_limit: Option<RateLimit>,
 ) -> Result<Box<dyn SecretManagerProvider>, ReconcilerError> {
+    let provider = create_provider_client(config, ctx, endpoints, vault_rate_limit).await?;
+    let routes = config.spec.secrets.routes.as_deref().unwrap_or_default();
+    if routes.is_empty() {
+        return Ok(provider);
+    }
+
+    let router = KeyRouter::new(routes).map_err(ReconcilerError::ReconciliationFailed)?;
+    let mut targets = Vec::with_capacity(routes.len());
+    for route in routes {
+        let routed =
+            route_target_config(config, route).map_err(ReconcilerError::ReconciliationFailed)?;
+        let location = provider_location(&routed.spec.provider);
+        let client = create_provider_client(&routed, ctx, endpoints, vault_rate_limit).await?;
+        targets.push(RouteTarget::new(client, location));
+    }
+    Ok(Box::new(RoutingProvider::new(provider, targets, router)))
+}
+
+/// Create the provider client for a single provider configuration
+async fn create_provider_client(
+    config: &SecretManagerConfig,
+    ctx: &Arc<Reconciler>,
+    endpoints: &ProviderEndpoints,
+    vault_rate_limit: Option<RateLimit>,
+) -> Result<Box<dyn SecretManagerProvider>, ReconcilerError> {
+    if let Some(factory) = &ctx.provider_factory {
+        return Ok(factory.secret_manager(config));
+    }
+
     let name = config.metadata.name.as_deref().unwrap_or("unknown");
 
     let provider: Box<dyn SecretManagerProvider> = match &config.spec.provider {
@@ -77,10 +132,22 @@ pub async fn create_provider(
             };
 
             let service_account_email = service_account_email_owned.as_deref();
+            // The resource's endpoint override (checked against the allowed domains during
+            // reconcile) takes precedence over the controller-wide endpoint
+            let endpoint = resource_endpoint_override(
+                gcp_config
+                    .endpoint_override
+                    .as_ref()
+                    .and_then(|o| o.secret_manager.as_deref()),
+            )
+            .or(endpoints.gcp_secret_manager.as_deref());
             match create_gcp_provider(
                 gcp_config.project_id.clone(),
                 auth_type,
                 service_account_email,
+                Impersonation::from_auth(gcp_config.auth.as_ref()).as_ref(),
+                gcp_config.version_retention.as_ref(),
+                endpoint,
             )
             .await
             {
@@ -92,8 +159,11 @@ pub async fn create_provider(
             }
         }
         ProviderConfig::Aws(aws_config) => {
+            // The resource's endpoint URL takes precedence over the controller-wide endpoint
+            let endpoint = resource_endpoint_url(aws_config.endpoint_url.as_deref())
+                .or(endpoints.aws_secrets_manager.as_deref());
             match AwsSecretManager::new(aws_config, &ctx.client).await {
-                Ok(aws_provider) => Box::new(aws_provider),
+                Ok(aws_provider) => Box::new(aws_provider.with_endpoint(endpoint)),
                 Err(e) => {
                     error!("Failed to create AWS Secrets Manager client: {}", e);
                     return Err(ReconcilerError::ReconciliationFailed(
@@ -103,8 +173,12 @@ pub async fn create_provider(
             }
         }
         ProviderConfig::Azure(azure_config) => {
-            match AzureKeyVault::new(azure_config, &ctx.client).await {
-                Ok(azure_provider) => Box::new(azure_provider),
+            let namespace = config.metadata.namespace.as_deref().unwrap_or("default");
+            let azure_config = azure_config.with_default_namespace(namespace);
+            match AzureKeyVault::new(&azure_config, &ctx.client).await {
+                Ok(azure_provider) => {
+                    Box::new(azure_provider.with_pacer(&ctx.vault_pacers, vault_rate_limit))
+                }
                 Err(e) => {
                     error!("Failed to create Azure Key Vault client: {}", e);
                     return Err(ReconcilerError::ReconciliationFailed(
@@ -0,0 +1,47 @@
+//! # Role Assignment Checks
+//!
+//! Checks the Key Vault role assignments of `provider.azure.roleAssignments` after a sync,
+//! so the `MissingRoleAssignments` condition reflects the secrets that were just written.
+
+use crate::controller::reconciler::types::Reconciler;
+use crate::crd::{ProviderConfig, ResourceSyncState, SecretManagerConfig};
+use crate::provider::azure::key_vault::{KeyVaultRoleAssignments, MissingRoleAssignment};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Missing role assignments of the resource's principals (creating them when configured)
+/// Returns None for resources without `provider.azure.roleAssignments`, and with a provider
+/// factory (in-memory providers have no role assignments).
+pub async fn check_role_assignments(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    synced_secrets: &HashMap<String, ResourceSyncState>,
+) -> Option<Result<Vec<MissingRoleAssignment>>> {
+    let ProviderConfig::Azure(azure_config) = &config.spec.provider else {
+        return None;
+    };
+    let assignments = azure_config.role_assignments.as_ref()?;
+    if reconciler.provider_factory.is_some() {
+        return None;
+    }
+
+    let mut secret_names: Vec<String> = synced_secrets
+        .iter()
+        .filter(|(_, state)| state.exists)
+        .map(|(name, _)| name.clone())
+        .collect();
+    secret_names.sort();
+
+    let namespace = config.metadata.namespace.as_deref().unwrap_or("default");
+    let result = async {
+        KeyVaultRoleAssignments::new(
+            &azure_config.with_default_namespace(namespace),
+            assignments,
+            &reconciler.client,
+        )?
+        .reconcile(assignments, &secret_names)
+        .await
+    }
+    .await;
+    Some(result)
+}
@@ -1,38 +1,79 @@
 //! # Secret Syncing
 //!
-//! Handles syncing secrets from artifact path to cloud provider.
+//! Handles syncing secrets from artifact path or Kubernetes Secret source to cloud provider.
 
 use crate::controller::parser;
+use crate::controller::reconciler::delta::ChangedPaths;
+use crate::controller::reconciler::limits::{LimitBudget, LimitExceeded, ValueLimits};
+use crate::controller::reconciler::policy::{KeyPolicy, PolicyViolations, ValueSource};
 use crate::controller::reconciler::processing::{
     process_application_files, process_kustomize_secrets,
 };
-use crate::controller::reconciler::status::update_status_phase;
+use crate::controller::reconciler::source::SourceSecret;
+use crate::controller::reconciler::status::{update_status_phase, update_status_phase_with_reason};
 use crate::controller::reconciler::types::{Reconciler, ReconcilerError};
-use crate::crd::{ResourceSyncState, SecretManagerConfig};
+use crate::crd::{FailedSecret, FailedService, ResourceSyncState, SecretManagerConfig};
 use crate::observability;
-use crate::provider::SecretManagerProvider;
+use crate::provider::{ProviderError, SecretManagerProvider};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::{error, info, warn};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Where the values to sync come from
+#[derive(Debug)]
+pub enum SyncSource {
+    /// Checked-out repository or extracted artifact (GitRepository, Application, GitDirect)
+    Artifact(PathBuf),
+    /// Keys of a Kubernetes Secret (`sourceRef.kind: Secret`)
+    Secret(SourceSecret),
+}
+
+impl SyncSource {
+    /// Artifact directory, if the source has one
+    #[must_use]
+    pub fn artifact_path(&self) -> Option<&PathBuf> {
+        match self {
+            Self::Artifact(path) => Some(path),
+            Self::Secret(_) => None,
+        }
+    }
+}
 
 /// Result type for secret syncing
 #[derive(Debug)]
 pub enum SyncResult {
     /// Successfully synced secrets (count)
     Success(u32),
-    /// Transient error - should retry
-    TransientError,
+    /// Some secrets synced, others failed; failures are recorded in status and retried with backoff
+    /// `failed_services` lists services whose files could not be processed at all
+    PartialFailure {
+        synced: u32,
+        failed: Vec<FailedSecret>,
+        failed_services: Vec<FailedService>,
+    },
+    /// Transient error - should retry, after the delay requested by the provider if any
+    TransientError { retry_after: Option<Duration> },
+    /// Values over the resource's limits; the offending service was not written
+    LimitExceeded(LimitExceeded),
+    /// Keys breaking the resource's policy; the offending service was not written
+    PolicyViolation(PolicyViolations),
     /// Permanent error
     Error(ReconcilerError),
 }
 
-/// Sync secrets from artifact path to provider
+/// Sync secrets from the source to provider
 /// Returns synced_secrets and synced_properties maps tracking which resources have been pushed and their update counts
+/// With `changes` (delta reconcile), only profiles containing changed files are processed and
+/// the recorded sync state of the other profiles is carried over
+/// Every service is checked against `spec.secrets.policy` and `limits` before it is written
 pub async fn sync_secrets(
     config: &Arc<SecretManagerConfig>,
     ctx: &Arc<Reconciler>,
     provider: &dyn SecretManagerProvider,
-    artifact_path: &PathBuf,
+    source: &SyncSource,
+    changes: Option<&ChangedPaths>,
+    limits: ValueLimits,
 ) -> Result<
     (
         SyncResult,
@@ -44,6 +85,24 @@ pub async fn sync_secrets(
     let mut secrets_synced = 0;
     let mut all_synced_secrets = std::collections::HashMap::new();
     let mut all_synced_properties = std::collections::HashMap::new();
+    let mut all_failed_secrets = Vec::new();
+    let mut all_failed_services = Vec::new();
+    let mut budget = LimitBudget::new(limits);
+    let policy = config
+        .spec
+        .secrets
+        .policy
+        .as_ref()
+        .map(KeyPolicy::new)
+        .transpose()?;
+
+    let artifact_path = match source {
+        SyncSource::Artifact(path) => path,
+        SyncSource::Secret(secret) => {
+            return sync_source_secret(config, ctx, provider, secret, policy.as_ref(), budget)
+                .await;
+        }
+    };
 
     // Determine processing mode: kustomize build vs raw file parsing
     // Kustomize mode: Extract secrets from kustomize-generated Secret resources
@@ -61,8 +120,20 @@ pub async fn sync_secrets(
         ) {
             Ok(secrets) => {
                 let secret_prefix = config.spec.secrets.prefix.as_deref().unwrap_or("default");
-                match process_kustomize_secrets(provider, config, &secrets, secret_prefix).await {
-                    Ok((count, kustomize_synced_secrets)) => {
+                match process_kustomize_secrets(
+                    provider,
+                    config,
+                    &secrets,
+                    secret_prefix,
+                    // kustomize output is never SOPS-decrypted by the controller
+                    ValueSource::Plaintext,
+                    policy.as_ref(),
+                    &mut budget,
+                )
+                .await
+                {
+                    Ok((count, kustomize_synced_secrets, failed_secrets)) => {
+                        all_failed_secrets.extend(failed_secrets);
                         secrets_synced += count as u32;
                         // Merge kustomize synced_secrets into overall map
                         for (secret_name, sync_state) in kustomize_synced_secrets {
@@ -71,14 +142,37 @@ pub async fn sync_secrets(
                                 .or_insert_with(|| ResourceSyncState {
                                     exists: false,
                                     update_count: 0,
+                                    source_key: None,
+                                    tier: None,
+                                    expire_time: None,
                                 });
                             existing.exists = existing.exists || sync_state.exists;
                             existing.update_count =
                                 existing.update_count.max(sync_state.update_count);
+                            if existing.source_key.is_none() {
+                                existing.source_key = sync_state.source_key;
+                            }
+                            if sync_state.expire_time.is_some() {
+                                existing.expire_time = sync_state.expire_time;
+                            }
                         }
                         info!("✅ Synced {} secrets from kustomize build", count);
                     }
                     Err(e) => {
+                        if let Some(exceeded) = e.downcast_ref::<LimitExceeded>() {
+                            return Ok((
+                                SyncResult::LimitExceeded(exceeded.clone()),
+                                all_synced_secrets,
+                                all_synced_properties,
+                            ));
+                        }
+                        if let Some(violations) = e.downcast_ref::<PolicyViolations>() {
+                            return Ok((
+                                SyncResult::PolicyViolation(violations.clone()),
+                                all_synced_secrets,
+                                all_synced_properties,
+                            ));
+                        }
                         error!("Failed to process kustomize secrets: {}", e);
                         observability::metrics::increment_reconciliation_errors();
                         // Update status to Failed
@@ -122,33 +216,31 @@ pub async fn sync_secrets(
         // Suitable for simple deployments or when kustomize isn't needed
         info!("Using raw file mode");
 
-        // Find application files for the specified environment
-        // Searches for files matching patterns like:
-        // - {basePath}/profiles/{environment}/application.secrets.env
-        // - {basePath}/{service}/profiles/{environment}/application.secrets.env
-        // Pass secret_prefix as default_service_name for single service deployments
-        let default_service_name = config.spec.secrets.prefix.as_deref();
-        let application_files = match parser::find_application_files(
-            artifact_path,
-            config.spec.secrets.base_path.as_deref(),
-            &config.spec.secrets.environment,
-            default_service_name,
-        )
-        .await
-        {
-            Ok(files) => files,
+        // Delta reconcile: unchanged profiles keep the sync state recorded by the last full sync
+        if changes.is_some() {
+            if let Some(sync) = config.status.as_ref().and_then(|s| s.sync.as_ref()) {
+                all_synced_secrets.extend(sync.secrets.clone().unwrap_or_default());
+                all_synced_properties.extend(sync.properties.clone().unwrap_or_default());
+            }
+        }
+        let mut processed_profiles: u64 = 0;
+        let mut skipped_profiles: u64 = 0;
+
+        // Resolve environments to sync: a single `environment`, or `environments`
+        // (exact names or globs) expanded against the profiles present in the artifact
+        let patterns = config.spec.secrets.environment_patterns();
+        let available =
+            parser::discover_environments(artifact_path, config.spec.secrets.base_path.as_deref());
+        let environments = match parser::expand_environment_patterns(&patterns, &available) {
+            Ok(environments) => environments,
             Err(e) => {
-                error!(
-                    "Failed to find application files for environment '{}': {}",
-                    config.spec.secrets.environment, e
-                );
+                error!("Failed to resolve environments {:?}: {}", patterns, e);
                 observability::metrics::increment_reconciliation_errors();
-                // Update status to Failed
                 let _ = update_status_phase(
                     ctx,
                     config,
                     "Failed",
-                    Some(&format!("Failed to find application files: {e}")),
+                    Some(&format!("Failed to resolve environments: {e}")),
                 )
                 .await;
                 return Ok((
@@ -159,93 +251,245 @@ pub async fn sync_secrets(
             }
         };
 
-        info!(
-            "📋 Found {} application file set(s) to process",
-            application_files.len()
-        );
+        for environment in &environments {
+            // Each environment is processed with its own view of the config:
+            // `environment` set and `{env}` rendered in prefix/suffix
+            let env_config = SecretManagerConfig {
+                spec: crate::crd::SecretManagerConfigSpec {
+                    secrets: config.spec.secrets.for_environment(environment),
+                    ..config.spec.clone()
+                },
+                ..(**config).clone()
+            };
+            if environments.len() > 1 {
+                info!("🌐 Syncing environment '{}'", environment);
+            }
 
-        // Process each application file set
-        for app_files in application_files {
-            match process_application_files(ctx, provider, config, &app_files).await {
-                Ok((count, synced_secrets, synced_properties)) => {
-                    secrets_synced += count as u32;
-                    // Merge synced_secrets from this service into the overall map
-                    for (secret_name, sync_state) in synced_secrets {
-                        // If secret already exists, merge the states (take max update_count, true if either exists)
-                        let existing = all_synced_secrets
-                            .entry(secret_name.clone())
-                            .or_insert_with(|| ResourceSyncState {
-                                exists: false,
-                                update_count: 0,
-                            });
-                        existing.exists = existing.exists || sync_state.exists;
-                        existing.update_count = existing.update_count.max(sync_state.update_count);
-                    }
-                    // Merge synced_properties from this service into the overall map
-                    for (property_name, sync_state) in synced_properties {
-                        let existing = all_synced_properties
-                            .entry(property_name.clone())
-                            .or_insert_with(|| ResourceSyncState {
-                                exists: false,
-                                update_count: 0,
-                            });
-                        existing.exists = existing.exists || sync_state.exists;
-                        existing.update_count = existing.update_count.max(sync_state.update_count);
-                    }
-                    info!(
-                        "✅ Synced {} secrets for service: {}",
-                        count, app_files.service_name
+            // Find application files for the specified environment
+            // Searches for files matching patterns like:
+            // - {basePath}/profiles/{environment}/application.secrets.env
+            // - {basePath}/{service}/profiles/{environment}/application.secrets.env
+            // Pass secret_prefix as default_service_name for single service deployments
+            let default_service_name = env_config.spec.secrets.prefix.as_deref();
+            let application_files = match parser::find_application_files(
+                artifact_path,
+                env_config.spec.secrets.base_path.as_deref(),
+                &env_config.spec.secrets.environment,
+                default_service_name,
+                env_config.spec.secrets.merge_base_profile == Some(true),
+            )
+            .await
+            {
+                Ok(files) => files,
+                Err(e) => {
+                    error!(
+                        "Failed to find application files for environment '{}': {}",
+                        env_config.spec.secrets.environment, e
                     );
+                    observability::metrics::increment_reconciliation_errors();
+                    // Update status to Failed
+                    let _ = update_status_phase(
+                        ctx,
+                        config,
+                        "Failed",
+                        Some(&format!("Failed to find application files: {e}")),
+                    )
+                    .await;
+                    return Ok((
+                        SyncResult::Error(ReconcilerError::ReconciliationFailed(e)),
+                        std::collections::HashMap::new(),
+                        std::collections::HashMap::new(),
+                    ));
                 }
-                Err(e) => {
-                    let error_msg = e.to_string();
-                    // Check if this is a transient SOPS decryption error
-                    let is_transient = error_msg.contains("transient");
+            };
 
-                    if is_transient {
-                        // Transient error - log warning and return action to retry
-                        warn!(
-                            "⏳ Transient error processing service {}: {}. Will retry.",
-                            app_files.service_name, error_msg
-                        );
-                        observability::metrics::increment_reconciliation_errors();
-                        // Update status to indicate retry
-                        let _ = update_status_phase(
-                            ctx,
-                            config,
-                            "Retrying",
-                            Some(&format!("Transient error: {}. Retrying...", error_msg)),
+            info!(
+                "📋 Found {} application file set(s) to process",
+                application_files.len()
+            );
+
+            // Process each application file set
+            for app_files in application_files {
+                if let Some(changes) = changes {
+                    let profile_dir = app_files
+                        .base_path
+                        .strip_prefix(artifact_path)
+                        .unwrap_or(&app_files.base_path);
+                    // Base layers live next to other profiles' files, so only files
+                    // directly in their directory count
+                    let base_changed = app_files.base_layers.iter().any(|layer| {
+                        changes.affects_files_in(
+                            layer
+                                .base_path
+                                .strip_prefix(artifact_path)
+                                .unwrap_or(&layer.base_path),
                         )
-                        .await;
-                        // Return action to retry after a delay
-                        return Ok((
-                            SyncResult::TransientError,
-                            all_synced_secrets,
-                            all_synced_properties,
-                        ));
-                    } else {
-                        // Permanent error - log error and continue with other services
-                        // This allows partial success when multiple services are configured
-                        error!(
-                            "❌ Permanent error processing service {}: {}",
-                            app_files.service_name, error_msg
+                    });
+                    if !changes.affects(profile_dir) && !base_changed {
+                        debug!(
+                            "Skipping unchanged profile {} (delta reconcile)",
+                            profile_dir.display()
                         );
-                        observability::metrics::increment_reconciliation_errors();
-                        // Update status to indicate failure for this service
-                        let _ = update_status_phase(
-                            ctx,
-                            config,
-                            "PartialFailure",
-                            Some(&format!(
-                                "Failed to process service {}: {}",
+                        skipped_profiles += 1;
+                        continue;
+                    }
+                }
+                processed_profiles += 1;
+
+                match process_application_files(
+                    ctx,
+                    provider,
+                    &env_config,
+                    &app_files,
+                    policy.as_ref(),
+                    &mut budget,
+                )
+                .await
+                {
+                    Ok((count, synced_secrets, synced_properties, failed_secrets)) => {
+                        all_failed_secrets.extend(failed_secrets);
+                        secrets_synced += count as u32;
+                        // Merge synced_secrets from this service into the overall map
+                        for (secret_name, sync_state) in synced_secrets {
+                            // If secret already exists, merge the states (take max update_count, true if either exists)
+                            let existing = all_synced_secrets
+                                .entry(secret_name.clone())
+                                .or_insert_with(|| ResourceSyncState {
+                                    exists: false,
+                                    update_count: 0,
+                                    source_key: None,
+                                    tier: None,
+                                    expire_time: None,
+                                });
+                            existing.exists = existing.exists || sync_state.exists;
+                            existing.update_count =
+                                existing.update_count.max(sync_state.update_count);
+                            if existing.source_key.is_none() {
+                                existing.source_key = sync_state.source_key;
+                            }
+                            if sync_state.expire_time.is_some() {
+                                existing.expire_time = sync_state.expire_time;
+                            }
+                        }
+                        // Merge synced_properties from this service into the overall map
+                        for (property_name, sync_state) in synced_properties {
+                            let existing = all_synced_properties
+                                .entry(property_name.clone())
+                                .or_insert_with(|| ResourceSyncState {
+                                    exists: false,
+                                    update_count: 0,
+                                    source_key: None,
+                                    tier: None,
+                                    expire_time: None,
+                                });
+                            existing.exists = existing.exists || sync_state.exists;
+                            existing.update_count =
+                                existing.update_count.max(sync_state.update_count);
+                            if existing.source_key.is_none() {
+                                existing.source_key = sync_state.source_key;
+                            }
+                        }
+                        info!(
+                            "✅ Synced {} secrets for service: {}",
+                            count, app_files.service_name
+                        );
+                    }
+                    Err(e) => {
+                        // Over the limits or against the policy: stop before any other
+                        // service is written
+                        if let Some(exceeded) = e.downcast_ref::<LimitExceeded>() {
+                            return Ok((
+                                SyncResult::LimitExceeded(exceeded.clone()),
+                                all_synced_secrets,
+                                all_synced_properties,
+                            ));
+                        }
+                        if let Some(violations) = e.downcast_ref::<PolicyViolations>() {
+                            return Ok((
+                                SyncResult::PolicyViolation(violations.clone()),
+                                all_synced_secrets,
+                                all_synced_properties,
+                            ));
+                        }
+                        let error_msg = e.to_string();
+                        // Transient SOPS decryption errors, or provider errors worth retrying
+                        let provider_error = ProviderError::find(&e);
+                        let is_transient = error_msg.contains("transient")
+                            || provider_error.is_some_and(ProviderError::is_retryable);
+
+                        if is_transient {
+                            // Transient error - log warning and return action to retry
+                            warn!(
+                                "⏳ Transient error processing service {}: {}. Will retry.",
                                 app_files.service_name, error_msg
-                            )),
-                        )
-                        .await;
+                            );
+                            observability::metrics::increment_reconciliation_errors();
+                            // Update status to indicate retry
+                            let _ = update_status_phase_with_reason(
+                                ctx,
+                                config,
+                                "Retrying",
+                                Some(&format!("Transient error: {}. Retrying...", error_msg)),
+                                provider_error.map(ProviderError::reason),
+                            )
+                            .await;
+                            // Return action to retry after a delay
+                            return Ok((
+                                SyncResult::TransientError {
+                                    retry_after: provider_error
+                                        .and_then(ProviderError::retry_after),
+                                },
+                                all_synced_secrets,
+                                all_synced_properties,
+                            ));
+                        } else {
+                            // Permanent error - log error and continue with other services
+                            // This allows partial success when multiple services are configured
+                            // The service is recorded as failed and reported once all services ran
+                            error!(
+                                "❌ Permanent error processing service {}: {}",
+                                app_files.service_name, error_msg
+                            );
+                            observability::metrics::increment_reconciliation_errors();
+                            all_failed_services
+                                .push(FailedService::new(&app_files.service_name, error_msg));
+                        }
                     }
                 }
             }
         }
+
+        if let Some(changes) = changes {
+            info!(
+                "🔀 Delta reconcile: {} changed file(s), processed {} profile(s), skipped {} unchanged",
+                changes.len(),
+                processed_profiles,
+                skipped_profiles
+            );
+            observability::metrics::increment_delta_reconciles_total();
+            observability::metrics::increment_delta_skipped_profiles_total(skipped_profiles);
+            // Report everything the resource manages, not just what this delta touched
+            secrets_synced = u32::try_from(all_synced_secrets.len() + all_synced_properties.len())
+                .unwrap_or(u32::MAX);
+        }
+    }
+
+    if !all_failed_secrets.is_empty() || !all_failed_services.is_empty() {
+        warn!(
+            "⚠️  {} secret(s) and {} service(s) failed to sync, {} synced successfully",
+            all_failed_secrets.len(),
+            all_failed_services.len(),
+            secrets_synced
+        );
+        return Ok((
+            SyncResult::PartialFailure {
+                synced: secrets_synced,
+                failed: all_failed_secrets,
+                failed_services: all_failed_services,
+            },
+            all_synced_secrets,
+            all_synced_properties,
+        ));
     }
 
     Ok((
@@ -254,3 +498,76 @@ pub async fn sync_secrets(
         all_synced_properties,
     ))
 }
+
+/// Sync the keys of a Kubernetes Secret source to provider
+/// Each key becomes one provider secret, named like kustomize output (prefix, key, suffix)
+async fn sync_source_secret(
+    config: &Arc<SecretManagerConfig>,
+    ctx: &Arc<Reconciler>,
+    provider: &dyn SecretManagerProvider,
+    secret: &SourceSecret,
+    policy: Option<&KeyPolicy>,
+    mut budget: LimitBudget,
+) -> Result<
+    (
+        SyncResult,
+        std::collections::HashMap<String, ResourceSyncState>,
+        std::collections::HashMap<String, ResourceSyncState>,
+    ),
+    ReconcilerError,
+> {
+    info!(
+        "Using Secret source mode: {}/{}",
+        config.spec.source_ref.namespace, config.spec.source_ref.name
+    );
+    let secret_prefix = config.spec.secrets.prefix.as_deref().unwrap_or("default");
+    match process_kustomize_secrets(
+        provider,
+        config,
+        &secret.values,
+        secret_prefix,
+        ValueSource::Cluster,
+        policy,
+        &mut budget,
+    )
+    .await
+    {
+        Ok((count, synced_secrets, failed_secrets)) => {
+            info!("✅ Synced {} secrets from source Secret", count);
+            let synced = count as u32;
+            let result = if failed_secrets.is_empty() {
+                SyncResult::Success(synced)
+            } else {
+                SyncResult::PartialFailure {
+                    synced,
+                    failed: failed_secrets,
+                    failed_services: Vec::new(),
+                }
+            };
+            Ok((result, synced_secrets, std::collections::HashMap::new()))
+        }
+        Err(e) => {
+            let result = if let Some(exceeded) = e.downcast_ref::<LimitExceeded>() {
+                SyncResult::LimitExceeded(exceeded.clone())
+            } else if let Some(violations) = e.downcast_ref::<PolicyViolations>() {
+                SyncResult::PolicyViolation(violations.clone())
+            } else {
+                error!("Failed to process source Secret: {}", e);
+                observability::metrics::increment_reconciliation_errors();
+                let _ = update_status_phase(
+                    ctx,
+                    config,
+                    "Failed",
+                    Some(&format!("Failed to process source Secret: {e}")),
+                )
+                .await;
+                SyncResult::Error(ReconcilerError::ReconciliationFailed(e))
+            };
+            Ok((
+                result,
+                std::collections::HashMap::new(),
+                std::collections::HashMap::new(),
+            ))
+        }
+    }
+}
@@ -0,0 +1,501 @@
+//! # Secret Routes
+//!
+//! Sends individual keys to another project, account or vault (`spec.secrets.routes`).
+//!
+//! Each route gets its own provider client, built from the resource's provider configuration
+//! with the route's target merged on top (`route_target_config`), so a route can use its own
+//! auth. `RoutingProvider` sits directly around these clients, below the rate limiter, audit
+//! log and approval gate, so routed writes are throttled, audited and planned like any other.
+//!
+//! Providers only see secret names, while routes match source keys. The sync loops therefore
+//! `bind` each resolved secret name to its key through `SecretManagerProvider::router` before
+//! touching it; names that were never bound (listing, pruning of keys removed from Git) go to
+//! the resource's own provider.
+
+use crate::controller::parser::file_finder::glob_to_regex;
+use crate::crd::{ProviderConfig, SecretManagerConfig, SecretRoute};
+use crate::provider::{SecretManagerProvider, SecretMetadata, SecretVersionIds};
+use crate::secret::SecretString;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use tracing::debug;
+
+/// Matches keys against the resource's routes and remembers which route each name uses
+#[derive(Debug)]
+pub struct KeyRouter {
+    patterns: Vec<(String, Regex)>,
+    bindings: Mutex<HashMap<String, usize>>,
+}
+
+impl KeyRouter {
+    /// Compile the `match` globs of `routes`, in order
+    pub fn new(routes: &[SecretRoute]) -> Result<Self> {
+        let patterns = routes
+            .iter()
+            .map(|route| {
+                Ok((
+                    route.key_pattern.clone(),
+                    glob_to_regex(&route.key_pattern)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            patterns,
+            bindings: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Index of the first route matching `key`
+    #[must_use]
+    pub fn route_for(&self, key: &str) -> Option<usize> {
+        self.patterns.iter().position(|(_, re)| re.is_match(key))
+    }
+
+    /// Record that `secret_name` holds `key`, so calls for the name use the key's route
+    pub fn bind(&self, secret_name: &str, key: &str) {
+        let mut bindings = self.bindings.lock().unwrap_or_else(PoisonError::into_inner);
+        match self.route_for(key) {
+            Some(index) => {
+                debug!(
+                    "Routing {} (key {}) via route '{}'",
+                    secret_name, key, self.patterns[index].0
+                );
+                bindings.insert(secret_name.to_string(), index);
+            }
+            None => {
+                bindings.remove(secret_name);
+            }
+        }
+    }
+
+    /// Route bound to `secret_name`, if any
+    fn route_of(&self, secret_name: &str) -> Option<usize> {
+        self.bindings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(secret_name)
+            .copied()
+    }
+}
+
+/// Provider client of one route
+pub struct RouteTarget {
+    provider: Box<dyn SecretManagerProvider>,
+    location: String,
+}
+
+impl RouteTarget {
+    /// Client for a route and the location its writes use
+    #[must_use]
+    pub fn new(provider: Box<dyn SecretManagerProvider>, location: String) -> Self {
+        Self { provider, location }
+    }
+}
+
+/// Provider dispatching each call to the route bound to the secret name
+pub struct RoutingProvider {
+    default: Box<dyn SecretManagerProvider>,
+    targets: Vec<RouteTarget>,
+    router: KeyRouter,
+}
+
+impl std::fmt::Debug for RoutingProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RoutingProvider")
+            .field("router", &self.router)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RoutingProvider {
+    /// `targets` must be in the same order as the routes `router` was built from
+    #[must_use]
+    pub fn new(
+        default: Box<dyn SecretManagerProvider>,
+        targets: Vec<RouteTarget>,
+        router: KeyRouter,
+    ) -> Self {
+        Self {
+            default,
+            targets,
+            router,
+        }
+    }
+
+    fn target(&self, secret_name: &str) -> Option<&RouteTarget> {
+        self.router
+            .route_of(secret_name)
+            .and_then(|index| self.targets.get(index))
+    }
+
+    fn provider(&self, secret_name: &str) -> &dyn SecretManagerProvider {
+        self.target(secret_name)
+            .map_or(&*self.default, |target| &*target.provider)
+    }
+}
+
+#[async_trait]
+impl SecretManagerProvider for RoutingProvider {
+    async fn create_or_update_secret(
+        &self,
+        secret_name: &str,
+        secret_value: &SecretString,
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        match self.target(secret_name) {
+            Some(target) => {
+                target
+                    .provider
+                    .create_or_update_secret(
+                        secret_name,
+                        secret_value,
+                        environment,
+                        &target.location,
+                    )
+                    .await
+            }
+            None => {
+                self.default
+                    .create_or_update_secret(secret_name, secret_value, environment, location)
+                    .await
+            }
+        }
+    }
+
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
+        self.provider(secret_name)
+            .get_secret_value(secret_name)
+            .await
+    }
+
+    /// Reads each store's names in bulk; names of a store whose bulk read fails are left out
+    async fn get_secret_values(
+        &self,
+        secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        let mut by_route: HashMap<Option<usize>, Vec<String>> = HashMap::new();
+        for name in secret_names {
+            by_route
+                .entry(self.router.route_of(name))
+                .or_default()
+                .push(name.clone());
+        }
+        let mut values = HashMap::new();
+        for (route, names) in by_route {
+            let provider = route
+                .and_then(|index| self.targets.get(index))
+                .map_or(&*self.default, |target| &*target.provider);
+            match provider.get_secret_values(&names).await {
+                Ok(read) => values.extend(read),
+                Err(e) => debug!("Bulk read of routed secrets failed: {e}"),
+            }
+        }
+        Ok(values)
+    }
+
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        match self.target(secret_name) {
+            Some(target) => {
+                target
+                    .provider
+                    .create_or_update_secret_binary(
+                        secret_name,
+                        secret_value,
+                        environment,
+                        &target.location,
+                    )
+                    .await
+            }
+            None => {
+                self.default
+                    .create_or_update_secret_binary(
+                        secret_name,
+                        secret_value,
+                        environment,
+                        location,
+                    )
+                    .await
+            }
+        }
+    }
+
+    async fn get_secret_binary(&self, secret_name: &str) -> Result<Option<Vec<u8>>> {
+        self.provider(secret_name)
+            .get_secret_binary(secret_name)
+            .await
+    }
+
+    async fn delete_secret(&self, secret_name: &str) -> Result<()> {
+        self.provider(secret_name).delete_secret(secret_name).await
+    }
+
+    async fn disable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.provider(secret_name).disable_secret(secret_name).await
+    }
+
+    async fn enable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.provider(secret_name).enable_secret(secret_name).await
+    }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        self.provider(secret_name).version_ids(secret_name).await
+    }
+
+    async fn set_secret_labels(
+        &self,
+        secret_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        self.provider(secret_name)
+            .set_secret_labels(secret_name, labels)
+            .await
+    }
+
+    async fn set_secret_expiration(
+        &self,
+        secret_name: &str,
+        expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        self.provider(secret_name)
+            .set_secret_expiration(secret_name, expire_time)
+            .await
+    }
+
+    /// Lists the resource's own store only; routed secrets are not listed
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        self.default.list_secrets(prefix).await
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        self.provider(secret_name)
+            .get_secret_metadata(secret_name)
+            .await
+    }
+
+    fn router(&self) -> Option<&KeyRouter> {
+        Some(&self.router)
+    }
+}
+
+/// The resource's configuration with a route's target merged into `spec.provider`
+/// Fields the target leaves unset are inherited from the resource's provider
+/// # Errors
+/// Returns an error when the route has no target for the resource's provider
+pub fn route_target_config(
+    config: &SecretManagerConfig,
+    route: &SecretRoute,
+) -> Result<SecretManagerConfig> {
+    let provider = match (&config.spec.provider, route) {
+        (
+            ProviderConfig::Gcp(gcp),
+            SecretRoute {
+                gcp: Some(target), ..
+            },
+        ) => {
+            let mut gcp = gcp.clone();
+            gcp.project_id.clone_from(&target.project_id);
+            if let Some(location) = &target.location {
+                gcp.location.clone_from(location);
+            }
+            if target.auth.is_some() {
+                gcp.auth.clone_from(&target.auth);
+            }
+            ProviderConfig::Gcp(gcp)
+        }
+        (
+            ProviderConfig::Aws(aws),
+            SecretRoute {
+                aws: Some(target), ..
+            },
+        ) => {
+            let mut aws = aws.clone();
+            if let Some(region) = &target.region {
+                aws.region.clone_from(region);
+            }
+            if target.assume_role_arn.is_some() {
+                aws.assume_role_arn.clone_from(&target.assume_role_arn);
+            }
+            if target.auth.is_some() {
+                aws.auth.clone_from(&target.auth);
+            }
+            ProviderConfig::Aws(aws)
+        }
+        (
+            ProviderConfig::Azure(azure),
+            SecretRoute {
+                azure: Some(target),
+                ..
+            },
+        ) => {
+            let mut azure = azure.clone();
+            azure.vault_name.clone_from(&target.vault_name);
+            if let Some(location) = &target.location {
+                azure.location.clone_from(location);
+            }
+            if target.auth.is_some() {
+                azure.auth.clone_from(&target.auth);
+            }
+            ProviderConfig::Azure(azure)
+        }
+        (provider, route) => {
+            return Err(anyhow::anyhow!(
+                "route '{}' has no {} target",
+                route.key_pattern,
+                provider_kind(provider)
+            ));
+        }
+    };
+
+    let mut routed = config.clone();
+    routed.spec.provider = provider;
+    Ok(routed)
+}
+
+/// Location passed to writes for a provider configuration
+/// Same rules as the sync loops: an empty or "automatic" GCP location means automatic replication
+#[must_use]
+pub fn provider_location(provider: &ProviderConfig) -> String {
+    match provider {
+        ProviderConfig::Gcp(gcp) if gcp.location == "automatic" => String::new(),
+        ProviderConfig::Gcp(gcp) => gcp.location.clone(),
+        ProviderConfig::Aws(aws) => aws.region.clone(),
+        ProviderConfig::Azure(azure) => azure.location.clone(),
+    }
+}
+
+/// Field name of a provider configuration in `spec.provider` and in routes
+#[must_use]
+pub fn provider_kind(provider: &ProviderConfig) -> &'static str {
+    match provider {
+        ProviderConfig::Gcp(_) => "gcp",
+        ProviderConfig::Aws(_) => "aws",
+        ProviderConfig::Azure(_) => "azure",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeSecretManagerProvider;
+
+    fn route(value: serde_json::Value) -> SecretRoute {
+        serde_json::from_value(value).unwrap_or_else(|e| panic!("invalid route: {e}"))
+    }
+
+    fn gcp_config() -> SecretManagerConfig {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "secret-management.octopilot.io/v1beta1",
+            "kind": "SecretManagerConfig",
+            "metadata": {"name": "app", "namespace": "default"},
+            "spec": {
+                "sourceRef": {"kind": "GitRepository", "name": "repo", "namespace": "default"},
+                "provider": {"type": "gcp", "gcp": {
+                    "projectId": "app-project",
+                    "location": "us-central1",
+                    "auth": {"authType": "workloadIdentity", "serviceAccountEmail": "app@app-project.iam.gserviceaccount.com"}
+                }},
+                "secrets": {"environment": "dev"}
+            }
+        }))
+        .unwrap_or_else(|e| panic!("invalid config: {e}"))
+    }
+
+    #[test]
+    fn test_router_uses_first_matching_route() {
+        let router = KeyRouter::new(&[
+            route(serde_json::json!({"match": "PAYMENTS_*", "gcp": {"projectId": "payments"}})),
+            route(serde_json::json!({"match": "*_KEY", "gcp": {"projectId": "keys"}})),
+        ])
+        .unwrap_or_else(|e| panic!("{e}"));
+
+        assert_eq!(router.route_for("PAYMENTS_API_KEY"), Some(0));
+        assert_eq!(router.route_for("SIGNING_KEY"), Some(1));
+        assert_eq!(router.route_for("DATABASE_URL"), None);
+    }
+
+    #[tokio::test]
+    async fn test_routing_provider_dispatches_bound_names() {
+        let default = FakeSecretManagerProvider::new();
+        let payments = FakeSecretManagerProvider::new();
+        let router = KeyRouter::new(&[route(
+            serde_json::json!({"match": "PAYMENTS_*", "gcp": {"projectId": "payments"}}),
+        )])
+        .unwrap_or_else(|e| panic!("{e}"));
+        let provider = RoutingProvider::new(
+            Box::new(default.clone()),
+            vec![RouteTarget::new(
+                Box::new(payments.clone()),
+                "europe-west1".to_string(),
+            )],
+            router,
+        );
+
+        let router = provider
+            .router()
+            .unwrap_or_else(|| panic!("router expected"));
+        router.bind("app-PAYMENTS_TOKEN-dev", "PAYMENTS_TOKEN");
+        router.bind("app-DATABASE_URL-dev", "DATABASE_URL");
+        for name in ["app-PAYMENTS_TOKEN-dev", "app-DATABASE_URL-dev"] {
+            provider
+                .create_or_update_secret(name, &SecretString::from("value"), "dev", "us-central1")
+                .await
+                .unwrap_or_else(|e| panic!("write failed: {e}"));
+        }
+
+        assert_eq!(
+            payments.value("app-PAYMENTS_TOKEN-dev").as_deref(),
+            Some("value")
+        );
+        assert_eq!(default.value("app-PAYMENTS_TOKEN-dev"), None);
+        assert_eq!(
+            default.value("app-DATABASE_URL-dev").as_deref(),
+            Some("value")
+        );
+        assert_eq!(
+            provider
+                .get_secret_value("app-PAYMENTS_TOKEN-dev")
+                .await
+                .unwrap_or_else(|e| panic!("read failed: {e}"))
+                .as_ref()
+                .map(SecretString::expose_secret),
+            Some("value")
+        );
+    }
+
+    #[test]
+    fn test_route_target_config_merges_target() {
+        let config = gcp_config();
+        let routed = route_target_config(
+            &config,
+            &route(serde_json::json!({
+                "match": "PAYMENTS_*",
+                "gcp": {"projectId": "payments-project"}
+            })),
+        )
+        .unwrap_or_else(|e| panic!("{e}"));
+
+        match &routed.spec.provider {
+            ProviderConfig::Gcp(gcp) => {
+                assert_eq!(gcp.project_id, "payments-project");
+                assert_eq!(gcp.location, "us-central1");
+                assert!(gcp.auth.is_some(), "auth is inherited");
+            }
+            other => panic!("unexpected provider {other:?}"),
+        }
+        assert_eq!(provider_location(&routed.spec.provider), "us-central1");
+
+        let mismatched = route(serde_json::json!({"match": "X_*", "aws": {"region": "us-east-1"}}));
+        assert!(route_target_config(&config, &mismatched).is_err());
+    }
+}
@@ -0,0 +1,364 @@
+//! # Referenced Secret Watch
+//!
+//! Watches Kubernetes Secrets across all namespaces and maps each change to the
+//! SecretManagerConfigs that use the Secret, so only those resources are reconciled.
+//!
+//! A resource uses a Secret when it is referenced by:
+//! - `secrets.sopsKeySecretRef` (SOPS key pinned by the resource)
+//! - `sourceRef.gitCredentials` (Git credentials)
+//! - `provider.azure.auth.certificateSecretRef` (provider credentials)
+//! - `sourceRef` with `kind: Secret` (mirrored Secret)
+//!
+//! SOPS key ring secrets (well-known names or the SOPS key label) are used by the resources in
+//! their namespace. Keys in the controller namespace are shared by every resource, but only
+//! resources whose decryption is failing are reconciled for them: the others pick up the new
+//! key set on their next periodic reconcile. The key ring is reloaded before the resources are
+//! reconciled.
+
+use crate::controller::reconciler::sops::{
+    controller_namespace, is_sops_key_secret, reload_sops_keys_for_secret,
+};
+use crate::controller::reconciler::status::DECRYPTION_READY_CONDITION;
+use crate::controller::reconciler::types::Reconciler;
+use crate::crd::{AzureAuthConfig, ProviderConfig, SecretManagerConfig};
+use futures::{Stream, StreamExt};
+use k8s_openapi::api::core::v1::Secret;
+use kube::Api;
+use kube_runtime::reflector::ObjectRef;
+use kube_runtime::{WatchStreamExt, watcher};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+/// Kind of a Secret watch event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretEvent {
+    /// Listed by the initial (or restarted) watch
+    Listed,
+    /// Created or updated
+    Applied,
+    /// Deleted
+    Deleted,
+}
+
+/// Digest of the content of each Secret seen by the watch
+/// Only data and labels count, so metadata-only updates don't reconcile anything.
+#[derive(Debug, Default)]
+pub struct SecretVersions {
+    digests: Mutex<HashMap<String, u64>>,
+}
+
+fn secret_key(secret: &Secret) -> String {
+    format!(
+        "{}/{}",
+        secret.metadata.namespace.as_deref().unwrap_or("default"),
+        secret.metadata.name.as_deref().unwrap_or_default()
+    )
+}
+
+fn secret_digest(secret: &Secret) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for (key, value) in secret.data.iter().flatten() {
+        key.hash(&mut hasher);
+        value.0.hash(&mut hasher);
+    }
+    secret.string_data.hash(&mut hasher);
+    secret.metadata.labels.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl SecretVersions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the event changes the content of `secret`
+    /// Secrets listed for the first time are only recorded, so starting the watch doesn't
+    /// reconcile every resource; a relisted Secret that changed while the watch was down does.
+    pub fn changed(&self, secret: &Secret, event: SecretEvent) -> bool {
+        let mut digests = self
+            .digests
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if event == SecretEvent::Deleted {
+            digests.remove(&secret_key(secret));
+            return true;
+        }
+        let digest = secret_digest(secret);
+        match digests.insert(secret_key(secret), digest) {
+            Some(previous) => previous != digest,
+            None => event == SecretEvent::Applied,
+        }
+    }
+}
+
+/// Whether the Secret `namespace/name` is referenced by the spec of `config`
+fn references_secret(config: &SecretManagerConfig, namespace: &str, name: &str) -> bool {
+    let config_namespace = config.metadata.namespace.as_deref().unwrap_or("default");
+    let source_ref = &config.spec.source_ref;
+    let matches = |ref_name: &str, ref_namespace: Option<&str>, default_namespace: &str| {
+        ref_name == name && ref_namespace.unwrap_or(default_namespace) == namespace
+    };
+
+    let sops_key = config
+        .spec
+        .secrets
+        .sops_key_secret_ref
+        .as_ref()
+        .is_some_and(|key| matches(&key.name, key.namespace.as_deref(), config_namespace));
+    let git_credentials = source_ref
+        .git_credentials
+        .as_ref()
+        .is_some_and(|credentials| {
+            matches(
+                &credentials.name,
+                credentials.namespace.as_deref(),
+                &source_ref.namespace,
+            )
+        });
+    let provider_credentials = match &config.spec.provider {
+        ProviderConfig::Azure(azure) => match &azure.auth {
+            Some(AzureAuthConfig::ClientCertificate {
+                certificate_secret_ref,
+                ..
+            }) => matches(
+                &certificate_secret_ref.name,
+                certificate_secret_ref.namespace.as_deref(),
+                config_namespace,
+            ),
+            _ => false,
+        },
+        ProviderConfig::Gcp(_) | ProviderConfig::Aws(_) => false,
+    };
+    let source =
+        source_ref.kind == "Secret" && matches(&source_ref.name, None, &source_ref.namespace);
+
+    sops_key || git_credentials || provider_credentials || source
+}
+
+/// Whether the last reconcile of `config` failed to decrypt (e.g. no SOPS key)
+fn decryption_failing(config: &SecretManagerConfig) -> bool {
+    config.status.as_ref().is_some_and(|status| {
+        status
+            .conditions
+            .iter()
+            .any(|c| c.r#type == DECRYPTION_READY_CONDITION && c.status == "False")
+    })
+}
+
+/// SecretManagerConfigs that use `secret`
+#[must_use]
+pub fn referencing_configs(
+    configs: &[Arc<SecretManagerConfig>],
+    secret: &Secret,
+    controller_namespace: &str,
+) -> Vec<ObjectRef<SecretManagerConfig>> {
+    let namespace = secret.metadata.namespace.as_deref().unwrap_or("default");
+    let name = secret.metadata.name.as_deref().unwrap_or_default();
+    let sops_key = is_sops_key_secret(secret);
+    configs
+        .iter()
+        .filter(|config| {
+            if references_secret(config, namespace, name) {
+                return true;
+            }
+            if !sops_key {
+                return false;
+            }
+            config.metadata.namespace.as_deref() == Some(namespace)
+                || (namespace == controller_namespace && decryption_failing(config))
+        })
+        .map(|config| ObjectRef::from_obj(config.as_ref()))
+        .collect()
+}
+
+/// Secret changes to map to the resources that use them
+/// Unchanged Secrets are filtered out, and SOPS key changes reload the key ring before they
+/// are yielded.
+pub fn referenced_secret_events(
+    reconciler: Arc<Reconciler>,
+    versions: Arc<SecretVersions>,
+) -> impl Stream<Item = Result<Secret, watcher::Error>> + Send {
+    let secrets: Api<Secret> = Api::all(reconciler.client.clone());
+    watcher(secrets, watcher::Config::default())
+        .default_backoff()
+        .filter_map(move |event| {
+            let reconciler = reconciler.clone();
+            let versions = versions.clone();
+            async move {
+                let (secret, event) = match event {
+                    Ok(watcher::Event::InitApply(secret)) => (secret, SecretEvent::Listed),
+                    Ok(watcher::Event::Apply(secret)) => (secret, SecretEvent::Applied),
+                    Ok(watcher::Event::Delete(secret)) => (secret, SecretEvent::Deleted),
+                    Ok(watcher::Event::Init | watcher::Event::InitDone) => return None,
+                    Err(e) => return Some(Err(e)),
+                };
+                if !versions.changed(&secret, event) {
+                    return None;
+                }
+                reload_sops_keys_for_secret(&reconciler, &secret, event == SecretEvent::Deleted)
+                    .await;
+                Some(Ok(secret))
+            }
+        })
+}
+
+/// Map a Secret change to the resources that use it (for `Controller::watches_stream_with`)
+#[must_use]
+pub fn secret_mapper(
+    store: kube_runtime::reflector::Store<SecretManagerConfig>,
+) -> impl Fn(Secret) -> Vec<ObjectRef<SecretManagerConfig>> + Send + Sync + 'static {
+    let controller_namespace = controller_namespace();
+    move |secret| {
+        let configs = referencing_configs(&store.state(), &secret, &controller_namespace);
+        tracing::debug!(
+            secret.name = secret.metadata.name.as_deref().unwrap_or("unknown"),
+            secret.namespace = secret.metadata.namespace.as_deref().unwrap_or("unknown"),
+            resources = configs.len(),
+            "Referenced secret changed, reconciling resources that use it"
+        );
+        configs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::reconciler::status::{ConditionReason, new_condition};
+    use crate::crd::SecretManagerConfigStatus;
+    use crate::testing::ConfigFixture;
+    use k8s_openapi::ByteString;
+    use kube::api::ObjectMeta;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn secret(namespace: &str, name: &str, value: &str) -> Secret {
+        Secret {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some(namespace.to_string()),
+                ..ObjectMeta::default()
+            },
+            data: Some(BTreeMap::from([(
+                "token".to_string(),
+                ByteString(value.as_bytes().to_vec()),
+            )])),
+            ..Secret::default()
+        }
+    }
+
+    fn names(refs: Vec<ObjectRef<SecretManagerConfig>>) -> Vec<String> {
+        refs.into_iter().map(|object| object.name).collect()
+    }
+
+    #[test]
+    fn test_secret_versions_ignore_unchanged_content() {
+        let versions = SecretVersions::new();
+        let token = secret("team-a", "git-credentials", "a");
+        assert!(!versions.changed(&token, SecretEvent::Listed));
+        assert!(!versions.changed(&token, SecretEvent::Applied));
+
+        let mut annotated = token.clone();
+        annotated.metadata.annotations = Some(BTreeMap::from([("a".to_string(), "b".to_string())]));
+        assert!(!versions.changed(&annotated, SecretEvent::Applied));
+
+        let rotated = secret("team-a", "git-credentials", "b");
+        assert!(versions.changed(&rotated, SecretEvent::Applied));
+        // A relist after a watch restart reports changes made while the watch was down
+        let rotated_again = secret("team-a", "git-credentials", "c");
+        assert!(versions.changed(&rotated_again, SecretEvent::Listed));
+
+        assert!(versions.changed(&rotated_again, SecretEvent::Deleted));
+        assert!(versions.changed(&rotated_again, SecretEvent::Applied));
+    }
+
+    #[test]
+    fn test_referencing_configs_by_reference() {
+        let configs = [
+            ConfigFixture::new("git").with_spec(json!({ "sourceRef": {
+                "kind": "Application", "name": "app", "namespace": "argocd",
+                "gitCredentials": { "name": "git-credentials", "namespace": "team-a" }
+            } })),
+            ConfigFixture::new("sops").with_spec(json!({ "secrets": {
+                "environment": "dev",
+                "sopsKeySecretRef": { "name": "git-credentials" }
+            } })),
+            ConfigFixture::new("mirror").with_spec(json!({ "sourceRef": {
+                "kind": "Secret", "name": "git-credentials", "namespace": "team-a"
+            } })),
+            ConfigFixture::new("azure").with_spec(json!({ "provider": {
+                "type": "azure",
+                "azure": {
+                    "vaultName": "vault",
+                    "location": "eastus",
+                    "auth": {
+                        "authType": "clientCertificate",
+                        "clientId": "client",
+                        "tenantId": "tenant",
+                        "certificateSecretRef": { "name": "git-credentials" }
+                    }
+                }
+            } })),
+            ConfigFixture::new("unrelated"),
+            ConfigFixture::new("other-namespace")
+                .with_namespace("team-b")
+                .with_spec(json!({ "secrets": {
+                    "environment": "dev",
+                    "sopsKeySecretRef": { "name": "git-credentials" }
+                } })),
+        ]
+        .map(|fixture| Arc::new(fixture.build()));
+        assert_eq!(
+            names(referencing_configs(
+                &configs,
+                &secret("team-a", "git-credentials", "a"),
+                "octopilot-system"
+            )),
+            ["git", "sops", "mirror", "azure"]
+        );
+    }
+
+    #[test]
+    fn test_referencing_configs_for_sops_key_ring() {
+        let failing = ConfigFixture::new("failing")
+            .with_namespace("team-b")
+            .with_status(SecretManagerConfigStatus {
+                conditions: vec![new_condition(
+                    DECRYPTION_READY_CONDITION,
+                    "False",
+                    ConditionReason::SopsKeyNotFound,
+                    "no key",
+                    None,
+                )],
+                ..SecretManagerConfigStatus::default()
+            })
+            .build();
+        let configs = [
+            Arc::new(ConfigFixture::new("same-namespace").build()),
+            Arc::new(
+                ConfigFixture::new("healthy")
+                    .with_namespace("team-b")
+                    .build(),
+            ),
+            Arc::new(failing),
+        ];
+
+        assert_eq!(
+            names(referencing_configs(
+                &configs,
+                &secret("team-a", "sops-private-key", "key"),
+                "octopilot-system"
+            )),
+            ["same-namespace"]
+        );
+        assert_eq!(
+            names(referencing_configs(
+                &configs,
+                &secret("octopilot-system", "sops-private-key", "key"),
+                "octopilot-system"
+            )),
+            ["failing"]
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! # SOPS Key Ring
+//!
+//! Ordered set of SOPS private keys used for decryption.
+//!
+//! Keys are collected from the well-known secret names (`sops-private-key`, `sops-gpg-key`,
+//! `gpg-key`) and from any secret labelled `secret-management.microscaler.io/sops-key=true`.
+//! The ring is ordered newest first (by secret creation time) so that during key rotation the
+//! new key is tried first and the previous key remains available as a fallback.
+
+use crate::secret::SecretString;
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+/// Well-known SOPS key secret names
+pub const SOPS_KEY_SECRET_NAMES: [&str; 3] = ["sops-private-key", "sops-gpg-key", "gpg-key"];
+
+/// Label selecting additional SOPS key secrets
+pub const SOPS_KEY_LABEL: &str = "secret-management.microscaler.io/sops-key";
+
+/// Label selector matching labelled SOPS key secrets
+pub const SOPS_KEY_LABEL_SELECTOR: &str = "secret-management.microscaler.io/sops-key=true";
+
+/// Data keys checked for the private key material, in order
+pub const SOPS_KEY_DATA_KEYS: [&str; 3] = ["private-key", "key", "gpg-key"];
+
+/// A single SOPS private key and the secret it was loaded from
+#[derive(Clone)]
+pub struct SopsKey {
+    pub namespace: String,
+    pub secret_name: String,
+    pub created: Option<Time>,
+    pub private_key: SecretString,
+}
+
+impl std::fmt::Debug for SopsKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SopsKey")
+            .field("namespace", &self.namespace)
+            .field("secret_name", &self.secret_name)
+            .field("created", &self.created)
+            .field("private_key", &self.private_key)
+            .finish()
+    }
+}
+
+/// SOPS private keys ordered newest first
+#[derive(Clone, Debug, Default)]
+pub struct SopsKeyRing {
+    keys: Vec<SopsKey>,
+}
+
+impl SopsKeyRing {
+    /// Build a key ring from secrets, skipping secrets without key material
+    /// Secrets are ordered newest first; duplicate key material is kept only once
+    pub fn from_secrets<'a>(secrets: impl IntoIterator<Item = &'a Secret>) -> Self {
+        let mut ring = Self::default();
+        for secret in secrets {
+            if let Some(key) = sops_key_from_secret(secret) {
+                ring.insert(key);
+            }
+        }
+        ring
+    }
+
+    /// Insert a key, keeping the ring ordered newest first
+    /// Keys without a creation time sort after those with one
+    pub fn insert(&mut self, key: SopsKey) {
+        if self.keys.iter().any(|k| k.private_key == key.private_key) {
+            return;
+        }
+        self.keys.push(key);
+        self.keys.sort_by(|a, b| {
+            b.created
+                .as_ref()
+                .map(|t| t.0)
+                .cmp(&a.created.as_ref().map(|t| t.0))
+                .then_with(|| a.secret_name.cmp(&b.secret_name))
+        });
+    }
+
+    /// Put a key at the front of the ring regardless of age
+    /// Used for keys referenced explicitly by a SecretManagerConfig
+    pub fn prepend(&mut self, key: SopsKey) {
+        self.keys.retain(|k| k.private_key != key.private_key);
+        self.keys.insert(0, key);
+    }
+
+    /// Remove every key loaded from the given namespace
+    pub fn remove_namespace(&mut self, namespace: &str) {
+        self.keys.retain(|k| k.namespace != namespace);
+    }
+
+    /// Whether any key was loaded from the given namespace
+    pub fn has_namespace(&self, namespace: &str) -> bool {
+        self.keys.iter().any(|k| k.namespace == namespace)
+    }
+
+    /// Whether a key was loaded from the given secret
+    pub fn contains_secret(&self, namespace: &str, secret_name: &str) -> bool {
+        self.keys
+            .iter()
+            .any(|k| k.namespace == namespace && k.secret_name == secret_name)
+    }
+
+    /// Keys in the order they should be tried
+    pub fn keys(&self) -> &[SopsKey] {
+        &self.keys
+    }
+
+    /// The newest key, if any
+    pub fn newest(&self) -> Option<&SopsKey> {
+        self.keys.first()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Secret names in the ring, formatted as `namespace/name`
+    pub fn secret_refs(&self) -> Vec<String> {
+        self.keys
+            .iter()
+            .map(|k| format!("{}/{}", k.namespace, k.secret_name))
+            .collect()
+    }
+}
+
+/// Whether a secret holds a SOPS key, either by well-known name or by label
+pub fn is_sops_key_secret(secret: &Secret) -> bool {
+    let name_matches = secret
+        .metadata
+        .name
+        .as_deref()
+        .is_some_and(|name| SOPS_KEY_SECRET_NAMES.contains(&name));
+    let label_matches = secret
+        .metadata
+        .labels
+        .as_ref()
+        .and_then(|labels| labels.get(SOPS_KEY_LABEL))
+        .is_some_and(|value| value == "true");
+    name_matches || label_matches
+}
+
+/// Extract the private key material from a secret
+/// Checks `private-key`, `key`, then `gpg-key`, or `data_key` when given
+pub fn extract_private_key(secret: &Secret, data_key: Option<&str>) -> Option<SecretString> {
+    let data = secret.data.as_ref()?;
+    let bytes = match data_key {
+        Some(data_key) => data.get(data_key),
+        None => SOPS_KEY_DATA_KEYS.iter().find_map(|k| data.get(*k)),
+    }?;
+    String::from_utf8(bytes.0.clone())
+        .ok()
+        .map(SecretString::new)
+        .filter(|key| !key.expose_secret().trim().is_empty())
+}
+
+/// Build a `SopsKey` from a secret, if it contains key material
+pub fn sops_key_from_secret(secret: &Secret) -> Option<SopsKey> {
+    Some(SopsKey {
+        namespace: secret.metadata.namespace.clone().unwrap_or_default(),
+        secret_name: secret.metadata.name.clone().unwrap_or_default(),
+        created: secret.metadata.creation_timestamp.clone(),
+        private_key: extract_private_key(secret, None)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::ByteString;
+    use std::collections::BTreeMap;
+
+    fn secret(name: &str, key: &str, created_secs: Option<i64>, labelled: bool) -> Secret {
+        let mut secret = Secret::default();
+        secret.metadata.name = Some(name.to_string());
+        secret.metadata.namespace = Some("octopilot-system".to_string());
+        secret.metadata.creation_timestamp = created_secs
+            .map(|secs| Time(chrono::DateTime::from_timestamp(secs, 0).unwrap_or_default()));
+        if labelled {
+            secret.metadata.labels = Some(BTreeMap::from([(
+                SOPS_KEY_LABEL.to_string(),
+                "true".to_string(),
+            )]));
+        }
+        secret.data = Some(BTreeMap::from([(
+            "private-key".to_string(),
+            ByteString(key.as_bytes().to_vec()),
+        )]));
+        secret
+    }
+
+    #[test]
+    fn test_key_ring_orders_newest_first() {
+        let old = secret("sops-key-2023", "old", Some(1_000), true);
+        let new = secret("sops-key-2024", "new", Some(2_000), true);
+        let unknown = secret("sops-private-key", "unknown", None, false);
+        let ring = SopsKeyRing::from_secrets([&old, &unknown, &new]);
+        let order: Vec<&str> = ring
+            .keys()
+            .iter()
+            .map(|k| k.private_key.expose_secret())
+            .collect();
+        assert_eq!(order, vec!["new", "old", "unknown"]);
+        assert_eq!(
+            ring.newest().map(|k| k.secret_name.as_str()),
+            Some("sops-key-2024")
+        );
+    }
+
+    #[test]
+    fn test_key_ring_deduplicates_and_prepends() {
+        let a = secret("a", "same", Some(1_000), true);
+        let b = secret("b", "same", Some(2_000), true);
+        let mut ring = SopsKeyRing::from_secrets([&a, &b]);
+        assert_eq!(ring.len(), 1);
+
+        let pinned = secret("pinned", "pinned", Some(0), false);
+        if let Some(key) = sops_key_from_secret(&pinned) {
+            ring.prepend(key);
+        }
+        assert_eq!(
+            ring.newest().map(|k| k.secret_name.as_str()),
+            Some("pinned")
+        );
+
+        assert_eq!(ring.len(), 2);
+        assert!(ring.contains_secret("octopilot-system", "pinned"));
+        assert!(ring.has_namespace("octopilot-system"));
+        ring.remove_namespace("octopilot-system");
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn test_is_sops_key_secret() {
+        assert!(is_sops_key_secret(&secret("gpg-key", "k", None, false)));
+        assert!(is_sops_key_secret(&secret("team-key", "k", None, true)));
+        assert!(!is_sops_key_secret(&secret("team-key", "k", None, false)));
+    }
+
+    #[test]
+    fn test_extract_private_key_skips_empty() {
+        let empty = secret("sops-private-key", "  ", None, false);
+        assert!(extract_private_key(&empty, None).is_none());
+        let key = secret("sops-private-key", "material", None, false);
+        assert_eq!(
+            extract_private_key(&key, Some("private-key"))
+                .as_ref()
+                .map(SecretString::expose_secret),
+            Some("material")
+        );
+        assert!(extract_private_key(&key, Some("other")).is_none());
+    }
+}
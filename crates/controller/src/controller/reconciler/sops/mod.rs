@@ -4,16 +4,27 @@
 //!
 //! ## Module Structure
 //!
+//! - `keyring.rs` - Ordered key ring used for key rotation
 //! - `load.rs` - Key loading and reloading functions
-//! - `watch.rs` - Watch loop for secret changes
+//! - `watch.rs` - Key ring reload on secret changes
 //! - `rbac.rs` - RBAC verification
+//! - `recipients.rs` - `.sops.yaml` recipient mismatches per resource
 
+mod keyring;
 mod load;
 mod rbac;
+mod recipients;
 mod watch;
 
 // Re-export public API
+pub use keyring::{
+    SOPS_KEY_LABEL, SOPS_KEY_LABEL_SELECTOR, SOPS_KEY_SECRET_NAMES, SopsKey, SopsKeyRing,
+    extract_private_key, is_sops_key_secret,
+};
 pub use load::{
-    load_sops_private_key, reload_sops_private_key, reload_sops_private_key_from_namespace,
+    controller_namespace, load_sops_key_ref, load_sops_key_ring, reload_sops_private_key,
+    reload_sops_private_key_from_namespace,
 };
-pub use watch::start_sops_key_watch;
+pub use rbac::verify_rbac_for_sops_watch;
+pub use recipients::RecipientChecks;
+pub use watch::reload_sops_keys_for_secret;
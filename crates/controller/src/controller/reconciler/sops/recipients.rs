@@ -0,0 +1,62 @@
+//! # Recipient Checks
+//!
+//! Collects the `.sops.yaml` recipient mismatches found while a resource's files are
+//! decrypted, for its `WrongEncryptionKey` condition.
+//!
+//! Files are checked per service while they are processed; the reconcile takes the collected
+//! mismatches after the sync and updates the condition once.
+
+use crate::controller::parser::sops::RecipientMismatch;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+/// Recipient mismatches per resource (identified by namespace/name) for the running sync
+#[derive(Debug, Default)]
+pub struct RecipientChecks {
+    checks: Mutex<HashMap<String, Vec<RecipientMismatch>>>,
+}
+
+impl RecipientChecks {
+    /// Record that SOPS files of a resource were checked, with the mismatches found
+    pub fn record(&self, resource_key: &str, mismatches: Vec<RecipientMismatch>) {
+        self.checks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(resource_key.to_string())
+            .or_default()
+            .extend(mismatches);
+    }
+
+    /// Take the mismatches recorded for a resource
+    /// `None` when no SOPS files were checked since the last call
+    pub fn take(&self, resource_key: &str) -> Option<Vec<RecipientMismatch>> {
+        self.checks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(resource_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_take_returns_checks_since_last_take() {
+        let checks = RecipientChecks::default();
+        assert_eq!(checks.take("ns/app"), None);
+
+        checks.record("ns/app", vec![]);
+        checks.record(
+            "ns/app",
+            vec![RecipientMismatch {
+                file: PathBuf::from("application.secrets.env"),
+                unexpected: vec!["pgp:FFFF".to_string()],
+                missing: vec![],
+            }],
+        );
+        assert_eq!(checks.take("ns/app").map(|m| m.len()), Some(1));
+        assert_eq!(checks.take("ns/app"), None);
+    }
+}
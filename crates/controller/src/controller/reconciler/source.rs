@@ -1,29 +1,182 @@
 //! # Source Management
 //!
-//! Handles GitRepository and ArgoCD Application source management.
+//! Handles GitRepository and ArgoCD Application source management, mapping source revisions
+//! to the SecretManagerConfigs that reference them, and reading Kubernetes Secret sources.
 
 use crate::controller::reconciler::types::Reconciler;
-use crate::crd::SourceRef;
+use crate::crd::{SecretManagerConfig, SourceRef};
+use crate::secret::SecretString;
 use anyhow::{Context, Result};
+use k8s_openapi::api::core::v1::Secret;
+use kube::api::{ApiResource, DynamicObject, ListParams};
+use kube::core::GroupVersionKind;
+use kube_runtime::reflector::ObjectRef;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tracing::{debug, info};
 
-/// Start watching for GitRepository and ArgoCD Application changes
-/// Note: The main controller watch already handles SecretManagerConfig changes.
-/// During reconciliation, the controller fetches the latest GitRepository/Application,
-/// so source changes are automatically picked up on the next reconciliation cycle.
-/// This function is a placeholder for future enhancement to directly watch source resources.
-pub fn start_source_watch(
-    _reconciler: std::sync::Arc<Reconciler>,
-    _configs_api: kube::Api<crate::crd::SecretManagerConfig>,
-) {
-    // Currently, the main controller watch handles SecretManagerConfig changes,
-    // and during reconciliation, it fetches the latest GitRepository/Application.
-    // This ensures source changes are picked up without restarting the controller.
-    // Future enhancement: Directly watch GitRepository and Application resources
-    // and trigger reconciliation of referencing SecretManagerConfig resources.
-    info!(
-        "Source watch: SecretManagerConfig resources are watched by main controller, source changes are picked up during reconciliation"
-    );
+/// Keys of a Kubernetes Secret source (`sourceRef.kind: Secret`)
+#[derive(Debug, Clone)]
+pub struct SourceSecret {
+    /// Decoded values by key
+    pub values: HashMap<String, SecretString>,
+    /// Secret `resourceVersion`, used as the source revision
+    pub resource_version: String,
+}
+
+/// Read the Kubernetes Secret a `sourceRef.kind: Secret` points to
+/// Values must be UTF-8: binary keys can't be mirrored as provider secret strings
+///
+/// # Errors
+/// Returns an error if the Secret can't be read (including 404) or a value isn't UTF-8
+pub async fn get_source_secret(
+    reconciler: &Reconciler,
+    source_ref: &SourceRef,
+) -> Result<SourceSecret> {
+    let api: kube::Api<Secret> =
+        kube::Api::namespaced(reconciler.client.clone(), &source_ref.namespace);
+    let secret = api.get(&source_ref.name).await.context(format!(
+        "Failed to get Secret: {}/{}",
+        source_ref.namespace, source_ref.name
+    ))?;
+
+    let mut values = HashMap::new();
+    for (key, value) in secret.data.unwrap_or_default() {
+        let value = String::from_utf8(value.0).map_err(|_| {
+            anyhow::anyhow!(
+                "Secret {}/{} key '{}' is not valid UTF-8",
+                source_ref.namespace,
+                source_ref.name,
+                key
+            )
+        })?;
+        values.insert(key, SecretString::new(value));
+    }
+
+    Ok(SourceSecret {
+        values,
+        resource_version: secret.metadata.resource_version.unwrap_or_default(),
+    })
+}
+
+/// Source kinds the watch loop watches for new revisions
+/// A new revision triggers a reconcile of every SecretManagerConfig whose `sourceRef` points at
+/// the source, instead of waiting for its next periodic reconcile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchedSource {
+    /// FluxCD GitRepository (`status.artifact.revision`)
+    GitRepository,
+    /// ArgoCD Application (`spec.source` and `status.sync.revision`)
+    Application,
+}
+
+impl WatchedSource {
+    pub const ALL: [Self; 2] = [Self::GitRepository, Self::Application];
+
+    /// `sourceRef.kind` of the source
+    #[must_use]
+    pub fn kind(self) -> &'static str {
+        match self {
+            Self::GitRepository => "GitRepository",
+            Self::Application => "Application",
+        }
+    }
+
+    /// API resource of the source, at the version the reconciler reads
+    #[must_use]
+    pub fn api_resource(self) -> ApiResource {
+        let (group, version) = match self {
+            Self::GitRepository => ("source.toolkit.fluxcd.io", "v1beta2"),
+            Self::Application => ("argoproj.io", "v1alpha1"),
+        };
+        ApiResource::from_gvk(&GroupVersionKind {
+            group: group.to_string(),
+            version: version.to_string(),
+            kind: self.kind().to_string(),
+        })
+    }
+
+    /// Fields of a source object whose change means new content to sync
+    /// None while the source has no revision yet (GitRepository without an artifact).
+    #[must_use]
+    pub fn revision(self, source: &DynamicObject) -> Option<String> {
+        let field = |path: &[&str]| {
+            path.iter()
+                .try_fold(&source.data, |value, key| value.get(key))
+                .and_then(|value| value.as_str())
+        };
+        match self {
+            Self::GitRepository => field(&["status", "artifact", "revision"]).map(str::to_string),
+            Self::Application => {
+                let repo_url = field(&["spec", "source", "repoURL"])?;
+                let target_revision =
+                    field(&["spec", "source", "targetRevision"]).unwrap_or("HEAD");
+                let synced = field(&["status", "sync", "revision"]).unwrap_or_default();
+                Some(format!("{repo_url}@{target_revision}:{synced}"))
+            }
+        }
+    }
+}
+
+/// Last revision seen for each watched source object
+#[derive(Debug, Default)]
+pub struct SourceRevisions {
+    revisions: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl SourceRevisions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a watch event for `source` carries a new revision
+    /// The first event of a source only records its revision, so the initial list after a
+    /// (re)start doesn't reconcile every resource at once.
+    pub fn changed(&self, kind: WatchedSource, source: &DynamicObject) -> bool {
+        let key = format!(
+            "{}/{}/{}",
+            kind.kind(),
+            source.metadata.namespace.as_deref().unwrap_or("default"),
+            source.metadata.name.as_deref().unwrap_or_default()
+        );
+        let revision = kind.revision(source);
+        let mut revisions = self
+            .revisions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match revisions.insert(key, revision.clone()) {
+            Some(previous) => revision.is_some() && previous != revision,
+            None => false,
+        }
+    }
+}
+
+/// SecretManagerConfigs whose `sourceRef` points at `source`
+#[must_use]
+pub fn referencing_configs(
+    configs: &[Arc<SecretManagerConfig>],
+    kind: WatchedSource,
+    source: &DynamicObject,
+) -> Vec<ObjectRef<SecretManagerConfig>> {
+    let namespace = source.metadata.namespace.as_deref().unwrap_or("default");
+    let name = source.metadata.name.as_deref().unwrap_or_default();
+    configs
+        .iter()
+        .filter(|config| {
+            let source_ref = &config.spec.source_ref;
+            source_ref.kind == kind.kind()
+                && source_ref.namespace == namespace
+                && source_ref.name == name
+        })
+        .map(|config| ObjectRef::from_obj(config.as_ref()))
+        .collect()
+}
+
+/// Whether the source's CRD is served by the cluster (FluxCD or ArgoCD installed)
+pub async fn source_served(client: &kube::Client, kind: WatchedSource) -> bool {
+    let api: kube::Api<DynamicObject> = kube::Api::all_with(client.clone(), &kind.api_resource());
+    api.list(&ListParams::default().limit(1)).await.is_ok()
 }
 
 /// Suspend or resume GitRepository pulls
@@ -34,14 +187,9 @@ pub async fn suspend_git_repository(
     source_ref: &SourceRef,
     suspend: bool,
 ) -> Result<()> {
-    use kube::api::{ApiResource, Patch, PatchParams};
-    use kube::core::DynamicObject;
+    use kube::api::{Patch, PatchParams};
 
-    let ar = ApiResource::from_gvk(&kube::core::GroupVersionKind {
-        group: "source.toolkit.fluxcd.io".to_string(),
-        version: "v1beta2".to_string(),
-        kind: "GitRepository".to_string(),
-    });
+    let ar = WatchedSource::GitRepository.api_resource();
 
     let api: kube::Api<DynamicObject> =
         kube::Api::namespaced_with(reconciler.client.clone(), &source_ref.namespace, &ar);
@@ -101,3 +249,72 @@ pub async fn suspend_git_repository(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ConfigFixture;
+    use serde_json::json;
+
+    fn git_repository(name: &str, revision: Option<&str>) -> DynamicObject {
+        let mut source = DynamicObject::new(name, &WatchedSource::GitRepository.api_resource())
+            .within("flux-system");
+        if let Some(revision) = revision {
+            source.data = json!({ "status": { "artifact": { "revision": revision } } });
+        }
+        source
+    }
+
+    #[test]
+    fn test_source_revisions_trigger_on_new_revision_only() {
+        let revisions = SourceRevisions::new();
+        let kind = WatchedSource::GitRepository;
+        // First sight only records the revision
+        assert!(!revisions.changed(kind, &git_repository("repo", Some("main@sha1:a"))));
+        assert!(!revisions.changed(kind, &git_repository("repo", Some("main@sha1:a"))));
+        assert!(revisions.changed(kind, &git_repository("repo", Some("main@sha1:b"))));
+        // Losing the artifact isn't a new revision, getting one back is
+        assert!(!revisions.changed(kind, &git_repository("repo", None)));
+        assert!(revisions.changed(kind, &git_repository("repo", Some("main@sha1:b"))));
+
+        assert!(!revisions.changed(kind, &git_repository("other", None)));
+        assert!(revisions.changed(kind, &git_repository("other", Some("main@sha1:c"))));
+    }
+
+    #[test]
+    fn test_application_revision() {
+        let mut application =
+            DynamicObject::new("app", &WatchedSource::Application.api_resource()).within("argocd");
+        assert_eq!(WatchedSource::Application.revision(&application), None);
+        application.data = json!({
+            "spec": { "source": { "repoURL": "https://github.com/org/repo", "targetRevision": "main" } },
+            "status": { "sync": { "revision": "abc123" } }
+        });
+        assert_eq!(
+            WatchedSource::Application.revision(&application).as_deref(),
+            Some("https://github.com/org/repo@main:abc123")
+        );
+    }
+
+    #[test]
+    fn test_referencing_configs() {
+        let configs = [
+            ("a", "GitRepository", "repo"),
+            ("b", "GitRepository", "other"),
+            ("c", "Application", "repo"),
+            ("d", "GitRepository", "repo"),
+        ]
+        .map(|(name, kind, source)| {
+            Arc::new(ConfigFixture::new(name).with_source(kind, source).build())
+        });
+        let names: Vec<String> = referencing_configs(
+            &configs,
+            WatchedSource::GitRepository,
+            &git_repository("repo", None),
+        )
+        .into_iter()
+        .map(|object| object.name)
+        .collect();
+        assert_eq!(names, ["a", "d"]);
+    }
+}
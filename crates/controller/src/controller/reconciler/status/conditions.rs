@@ -0,0 +1,947 @@
+//! # Status Conditions
+//!
+//! Standard conditions following `metav1.Condition` semantics, so kstatus (Flux health checks)
+//! and Argo CD health assessments understand `SecretManagerConfig` without custom checks.
+//!
+//! | Type | Polarity | Set by |
+//! |------|----------|--------|
+//! | `Ready` | normal-true | every phase update (`Unknown` while reconciling) |
+//! | `Reconciling` | abnormal-true | phase updates; present only while a reconcile is in progress |
+//! | `Stalled` | abnormal-true | phase updates; present only while the last reconcile failed or waits for approval |
+//! | `SourceAvailable` | normal-true | phase updates that resolved (or failed to resolve) the source |
+//! | `DecryptionReady` | normal-true | SOPS decryption and SOPS key status updates |
+//! | `ConflictingWriter` | abnormal-true | syncs with the `ConflictDetection` feature gate |
+//! | `WrongEncryptionKey` | abnormal-true | syncs that decrypted files covered by a `.sops.yaml` creation rule |
+//! | `MissingRoleAssignments` | abnormal-true | syncs with `provider.azure.roleAssignments` |
+//! | `ExpiringSoon` | abnormal-true | syncs with `secrets.expiration` |
+//!
+//! Every condition records the generation it was computed for, and keeps its
+//! `lastTransitionTime` until its status changes. Reasons are CamelCase `ConditionReason`s.
+
+use crate::controller::parser::sops::RecipientMismatch;
+use crate::controller::reconciler::expiration::ExpiringSecret;
+use crate::controller::reconciler::last_writer::{LastWriter, WriterConflict};
+use crate::controller::reconciler::types::Reconciler;
+use crate::crd::{Condition, SecretManagerConfig};
+use crate::provider::azure::key_vault::MissingRoleAssignment;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use kube::api::PatchParams;
+use std::fmt;
+use tracing::debug;
+
+/// Whether the last reconcile synced everything
+pub const READY_CONDITION: &str = "Ready";
+/// Present (True) while a reconcile is in progress
+pub const RECONCILING_CONDITION: &str = "Reconciling";
+/// Present (True) while the resource can't progress without a change (new commit, spec edit,
+/// approval or provider permissions)
+pub const STALLED_CONDITION: &str = "Stalled";
+/// Whether the source (GitRepository, Application, Git clone or Secret) could be read
+pub const SOURCE_AVAILABLE_CONDITION: &str = "SourceAvailable";
+/// Whether SOPS-encrypted files can be decrypted
+pub const DECRYPTION_READY_CONDITION: &str = "DecryptionReady";
+/// Set while drifted secrets were last written by someone other than their resource
+pub const CONFLICTING_WRITER_CONDITION: &str = "ConflictingWriter";
+/// Set while SOPS files are encrypted for other recipients than their `.sops.yaml` creation rule
+pub const WRONG_ENCRYPTION_KEY_CONDITION: &str = "WrongEncryptionKey";
+/// Set while principals in `provider.azure.roleAssignments` can't read the synced secrets
+pub const MISSING_ROLE_ASSIGNMENTS_CONDITION: &str = "MissingRoleAssignments";
+/// Set while secrets with a `secrets.expiration` ttl expire within `warnBefore`
+pub const EXPIRING_SOON_CONDITION: &str = "ExpiringSoon";
+
+const TRUE: &str = "True";
+const FALSE: &str = "False";
+const UNKNOWN: &str = "Unknown";
+
+/// Reason of a condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionReason {
+    ReconciliationSucceeded,
+    ReconciliationFailed,
+    ReconciliationInProgress,
+    Suspended,
+    PartialFailure,
+    AwaitingApproval,
+    InvalidSpec,
+    LimitExceeded,
+    PolicyViolation,
+    SourceAvailable,
+    SourceNotFound,
+    SourceNotReady,
+    SourceUnavailable,
+    CredentialsInvalid,
+    ArtifactCorrupted,
+    DecryptionSucceeded,
+    DecryptionFailed,
+    NotEncrypted,
+    SopsKeyNotFound,
+    SopsKeyAvailable,
+    ProviderNotFound,
+    ProviderPermissionDenied,
+    ProviderThrottled,
+    ProviderConflict,
+    ProviderInvalidPayload,
+    ProviderTransientError,
+    ProviderError,
+    ForeignWrite,
+    OtherResource,
+    NoConflict,
+    UnexpectedRecipients,
+    MissingRecipients,
+    ExpectedRecipients,
+    RoleAssignmentMissing,
+    RoleAssignmentsPresent,
+    RoleAssignmentCheckFailed,
+    SecretsExpiringSoon,
+    SecretsExpired,
+    NoSecretsExpiringSoon,
+}
+
+impl ConditionReason {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReconciliationSucceeded => "ReconciliationSucceeded",
+            Self::ReconciliationFailed => "ReconciliationFailed",
+            Self::ReconciliationInProgress => "ReconciliationInProgress",
+            Self::Suspended => "Suspended",
+            Self::PartialFailure => "PartialFailure",
+            Self::AwaitingApproval => "AwaitingApproval",
+            Self::InvalidSpec => "InvalidSpec",
+            Self::LimitExceeded => "LimitExceeded",
+            Self::PolicyViolation => "PolicyViolation",
+            Self::SourceAvailable => "SourceAvailable",
+            Self::SourceNotFound => "SourceNotFound",
+            Self::SourceNotReady => "SourceNotReady",
+            Self::SourceUnavailable => "SourceUnavailable",
+            Self::CredentialsInvalid => "CredentialsInvalid",
+            Self::ArtifactCorrupted => "ArtifactCorrupted",
+            Self::DecryptionSucceeded => "DecryptionSucceeded",
+            Self::DecryptionFailed => "DecryptionFailed",
+            Self::NotEncrypted => "NotEncrypted",
+            Self::SopsKeyNotFound => "SopsKeyNotFound",
+            Self::SopsKeyAvailable => "SopsKeyAvailable",
+            Self::ProviderNotFound => "ProviderNotFound",
+            Self::ProviderPermissionDenied => "ProviderPermissionDenied",
+            Self::ProviderThrottled => "ProviderThrottled",
+            Self::ProviderConflict => "ProviderConflict",
+            Self::ProviderInvalidPayload => "ProviderInvalidPayload",
+            Self::ProviderTransientError => "ProviderTransientError",
+            Self::ProviderError => "ProviderError",
+            Self::ForeignWrite => "ForeignWrite",
+            Self::OtherResource => "OtherResource",
+            Self::NoConflict => "NoConflict",
+            Self::UnexpectedRecipients => "UnexpectedRecipients",
+            Self::MissingRecipients => "MissingRecipients",
+            Self::ExpectedRecipients => "ExpectedRecipients",
+            Self::RoleAssignmentMissing => "RoleAssignmentMissing",
+            Self::RoleAssignmentsPresent => "RoleAssignmentsPresent",
+            Self::RoleAssignmentCheckFailed => "RoleAssignmentCheckFailed",
+            Self::SecretsExpiringSoon => "SecretsExpiringSoon",
+            Self::SecretsExpired => "SecretsExpired",
+            Self::NoSecretsExpiringSoon => "NoSecretsExpiringSoon",
+        }
+    }
+
+    /// Default Ready reason of a phase
+    #[must_use]
+    pub fn for_phase(phase: &str) -> Self {
+        match phase {
+            "Ready" => Self::ReconciliationSucceeded,
+            "Failed" => Self::ReconciliationFailed,
+            "Suspended" => Self::Suspended,
+            _ => Self::ReconciliationInProgress,
+        }
+    }
+
+    /// Whether the reason means the source couldn't be read
+    #[must_use]
+    pub fn is_source_failure(self) -> bool {
+        matches!(
+            self,
+            Self::SourceNotFound
+                | Self::SourceNotReady
+                | Self::SourceUnavailable
+                | Self::CredentialsInvalid
+                | Self::ArtifactCorrupted
+        )
+    }
+}
+
+impl fmt::Display for ConditionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A condition computed now for `generation`
+#[must_use]
+pub fn new_condition(
+    r#type: &str,
+    status: &str,
+    reason: ConditionReason,
+    message: impl Into<String>,
+    generation: Option<i64>,
+) -> Condition {
+    Condition {
+        r#type: r#type.to_string(),
+        status: status.to_string(),
+        observed_generation: generation,
+        last_transition_time: Some(chrono::Utc::now().to_rfc3339()),
+        reason: Some(reason.as_str().to_string()),
+        message: Some(message.into()),
+    }
+}
+
+/// Insert `condition`, replacing the condition of the same type
+/// The existing `lastTransitionTime` is kept while the status doesn't change.
+pub fn set_condition(conditions: &mut Vec<Condition>, mut condition: Condition) {
+    match conditions.iter_mut().find(|c| c.r#type == condition.r#type) {
+        Some(existing) => {
+            if existing.status == condition.status && existing.last_transition_time.is_some() {
+                condition
+                    .last_transition_time
+                    .clone_from(&existing.last_transition_time);
+            }
+            *existing = condition;
+        }
+        None => conditions.push(condition),
+    }
+}
+
+/// Remove the condition of type `r#type`, if present
+pub fn remove_condition(conditions: &mut Vec<Condition>, r#type: &str) {
+    conditions.retain(|c| c.r#type != r#type);
+}
+
+/// The resource's conditions after moving to `phase`
+///
+/// Sets Ready (True when `Ready`, Unknown while in progress, False otherwise), adds Reconciling
+/// while in progress and Stalled after a failure or while awaiting approval, and sets
+/// SourceAvailable when the phase or reason says whether the source could be read. Other
+/// conditions are kept.
+#[must_use]
+pub fn phase_conditions(
+    config: &SecretManagerConfig,
+    phase: &str,
+    reason: ConditionReason,
+    message: Option<&str>,
+) -> Vec<Condition> {
+    let generation = config.metadata.generation;
+    let message = message.unwrap_or(phase);
+    let mut conditions = config
+        .status
+        .as_ref()
+        .map(|s| s.conditions.clone())
+        .unwrap_or_default();
+
+    let in_progress = matches!(
+        phase,
+        "Started" | "Cloning" | "Pending" | "Updating" | "Retrying"
+    );
+    let stalled = matches!(phase, "Failed" | "PartialFailure" | "AwaitingApproval");
+    let ready = match phase {
+        "Ready" => TRUE,
+        _ if in_progress => UNKNOWN,
+        _ => FALSE,
+    };
+    set_condition(
+        &mut conditions,
+        new_condition(READY_CONDITION, ready, reason, message, generation),
+    );
+    if in_progress {
+        set_condition(
+            &mut conditions,
+            new_condition(RECONCILING_CONDITION, TRUE, reason, message, generation),
+        );
+    } else {
+        remove_condition(&mut conditions, RECONCILING_CONDITION);
+    }
+    if stalled {
+        set_condition(
+            &mut conditions,
+            new_condition(STALLED_CONDITION, TRUE, reason, message, generation),
+        );
+    } else {
+        remove_condition(&mut conditions, STALLED_CONDITION);
+    }
+
+    // Phases past source resolution (and guardrails checked on its contents) prove the source
+    // was read; earlier phases and unrelated failures leave the condition as it was
+    let source_read = matches!(
+        phase,
+        "Updating" | "Ready" | "PartialFailure" | "AwaitingApproval"
+    ) || matches!(
+        reason,
+        ConditionReason::LimitExceeded | ConditionReason::PolicyViolation
+    );
+    if reason.is_source_failure() {
+        set_condition(
+            &mut conditions,
+            new_condition(
+                SOURCE_AVAILABLE_CONDITION,
+                FALSE,
+                reason,
+                message,
+                generation,
+            ),
+        );
+    } else if source_read {
+        let source_ref = &config.spec.source_ref;
+        set_condition(
+            &mut conditions,
+            new_condition(
+                SOURCE_AVAILABLE_CONDITION,
+                TRUE,
+                ConditionReason::SourceAvailable,
+                format!(
+                    "{} {}/{} is available",
+                    source_ref.kind, source_ref.namespace, source_ref.name
+                ),
+                generation,
+            ),
+        );
+    }
+    conditions
+}
+
+/// DecryptionReady condition for a SOPS decryption status
+/// (`Success`, `TransientFailure`, `PermanentFailure`, `NotApplicable`)
+#[must_use]
+pub fn decryption_condition(
+    config: &SecretManagerConfig,
+    decryption_status: &str,
+    error_message: Option<&str>,
+) -> Condition {
+    let (status, reason, message) = match decryption_status {
+        "Success" => (
+            TRUE,
+            ConditionReason::DecryptionSucceeded,
+            "SOPS-encrypted files were decrypted",
+        ),
+        "NotApplicable" => (
+            TRUE,
+            ConditionReason::NotEncrypted,
+            "No SOPS-encrypted files to decrypt",
+        ),
+        _ => (
+            FALSE,
+            ConditionReason::DecryptionFailed,
+            error_message.unwrap_or("SOPS decryption failed"),
+        ),
+    };
+    new_condition(
+        DECRYPTION_READY_CONDITION,
+        status,
+        reason,
+        message,
+        config.metadata.generation,
+    )
+}
+
+/// DecryptionReady condition after a SOPS key check, for resources that decrypt files
+/// A missing key sets it False; a key showing up again after that sets it Unknown until the
+/// next decryption. Returns None when the check doesn't change it.
+#[must_use]
+pub fn sops_key_condition(config: &SecretManagerConfig, key_available: bool) -> Option<Condition> {
+    let status = config.status.as_ref()?;
+    let uses_sops = status
+        .decryption_status
+        .as_deref()
+        .is_some_and(|s| s != "NotApplicable");
+    if !uses_sops {
+        return None;
+    }
+    let current = status
+        .conditions
+        .iter()
+        .find(|c| c.r#type == DECRYPTION_READY_CONDITION);
+    let namespace = config.metadata.namespace.as_deref().unwrap_or("default");
+    if !key_available {
+        return Some(new_condition(
+            DECRYPTION_READY_CONDITION,
+            FALSE,
+            ConditionReason::SopsKeyNotFound,
+            format!("No SOPS private key found in namespace {namespace}"),
+            config.metadata.generation,
+        ));
+    }
+    current
+        .filter(|c| c.reason.as_deref() == Some(ConditionReason::SopsKeyNotFound.as_str()))
+        .map(|_| {
+            new_condition(
+                DECRYPTION_READY_CONDITION,
+                UNKNOWN,
+                ConditionReason::SopsKeyAvailable,
+                format!(
+                    "SOPS private key found in namespace {namespace}; decryption is retried on the next reconcile"
+                ),
+                config.metadata.generation,
+            )
+        })
+}
+
+/// ConflictingWriter condition for the conflicts found by a sync
+/// Returns None when nothing changed: no conflicts and no True condition to clear, or the same
+/// conflicts as before.
+fn conflicting_writer_condition(
+    existing: Option<&Condition>,
+    conflicts: &[WriterConflict],
+    generation: Option<i64>,
+) -> Option<Condition> {
+    let (status, reason, message) = if conflicts.is_empty() {
+        (
+            FALSE,
+            ConditionReason::NoConflict,
+            "No drifted secret was last written by someone else".to_string(),
+        )
+    } else {
+        let reason = if conflicts
+            .iter()
+            .any(|c| matches!(c.writer, LastWriter::OtherResource { .. }))
+        {
+            ConditionReason::OtherResource
+        } else {
+            ConditionReason::ForeignWrite
+        };
+        let message = conflicts
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        (TRUE, reason, message)
+    };
+
+    let unchanged = match existing {
+        None => conflicts.is_empty(),
+        Some(condition) => {
+            condition.status == status
+                && condition.reason.as_deref() == Some(reason.as_str())
+                && condition.message.as_deref() == Some(message.as_str())
+                && condition.observed_generation == generation
+        }
+    };
+    if unchanged {
+        return None;
+    }
+    Some(new_condition(
+        CONFLICTING_WRITER_CONDITION,
+        status,
+        reason,
+        message,
+        generation,
+    ))
+}
+
+/// Set the ConflictingWriter condition from the conflicts found by a sync (`ConflictDetection`
+/// gate), or set it False once a sync finds none
+/// Skipped when the condition is unchanged.
+pub async fn update_conflicting_writer_condition(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    conflicts: &[WriterConflict],
+) -> Result<()> {
+    let existing = config.status.as_ref().and_then(|s| {
+        s.conditions
+            .iter()
+            .find(|c| c.r#type == CONFLICTING_WRITER_CONDITION)
+    });
+    let Some(condition) =
+        conflicting_writer_condition(existing, conflicts, config.metadata.generation)
+    else {
+        return Ok(());
+    };
+
+    patch_condition(reconciler, config, condition).await
+}
+
+/// WrongEncryptionKey condition for the recipient mismatches found by a sync
+/// Returns None when nothing changed, as for [`conflicting_writer_condition`].
+fn wrong_encryption_key_condition(
+    existing: Option<&Condition>,
+    mismatches: &[RecipientMismatch],
+    generation: Option<i64>,
+) -> Option<Condition> {
+    let (status, reason, message) = if mismatches.is_empty() {
+        (
+            FALSE,
+            ConditionReason::ExpectedRecipients,
+            "SOPS files are encrypted for the recipients of their .sops.yaml creation rules"
+                .to_string(),
+        )
+    } else {
+        let reason = if mismatches.iter().any(|m| !m.unexpected.is_empty()) {
+            ConditionReason::UnexpectedRecipients
+        } else {
+            ConditionReason::MissingRecipients
+        };
+        let message = mismatches
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        (TRUE, reason, message)
+    };
+
+    let unchanged = match existing {
+        None => mismatches.is_empty(),
+        Some(condition) => {
+            condition.status == status
+                && condition.reason.as_deref() == Some(reason.as_str())
+                && condition.message.as_deref() == Some(message.as_str())
+                && condition.observed_generation == generation
+        }
+    };
+    if unchanged {
+        return None;
+    }
+    Some(new_condition(
+        WRONG_ENCRYPTION_KEY_CONDITION,
+        status,
+        reason,
+        message,
+        generation,
+    ))
+}
+
+/// Set the WrongEncryptionKey condition from the `.sops.yaml` recipient mismatches found by a
+/// sync, or set it False once the checked files match their creation rules
+/// Skipped when the condition is unchanged.
+pub async fn update_wrong_encryption_key_condition(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    mismatches: &[RecipientMismatch],
+) -> Result<()> {
+    let existing = config.status.as_ref().and_then(|s| {
+        s.conditions
+            .iter()
+            .find(|c| c.r#type == WRONG_ENCRYPTION_KEY_CONDITION)
+    });
+    let Some(condition) =
+        wrong_encryption_key_condition(existing, mismatches, config.metadata.generation)
+    else {
+        return Ok(());
+    };
+    patch_condition(reconciler, config, condition).await
+}
+
+/// MissingRoleAssignments condition for the result of a role assignment check
+/// Unknown when the assignments couldn't be read. Returns None when nothing changed, as for
+/// [`conflicting_writer_condition`].
+fn missing_role_assignments_condition(
+    existing: Option<&Condition>,
+    result: &Result<Vec<MissingRoleAssignment>>,
+    generation: Option<i64>,
+) -> Option<Condition> {
+    let (status, reason, message) = match result {
+        Err(e) => (
+            UNKNOWN,
+            ConditionReason::RoleAssignmentCheckFailed,
+            format!("{e:#}"),
+        ),
+        Ok(missing) if missing.is_empty() => (
+            FALSE,
+            ConditionReason::RoleAssignmentsPresent,
+            "Every principal has the Key Vault Secrets User role on its scopes".to_string(),
+        ),
+        Ok(missing) => (
+            TRUE,
+            ConditionReason::RoleAssignmentMissing,
+            missing
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        ),
+    };
+
+    let unchanged = existing.is_some_and(|condition| {
+        condition.status == status
+            && condition.reason.as_deref() == Some(reason.as_str())
+            && condition.message.as_deref() == Some(message.as_str())
+            && condition.observed_generation == generation
+    });
+    if unchanged {
+        return None;
+    }
+    Some(new_condition(
+        MISSING_ROLE_ASSIGNMENTS_CONDITION,
+        status,
+        reason,
+        message,
+        generation,
+    ))
+}
+
+/// Set the MissingRoleAssignments condition from a `provider.azure.roleAssignments` check
+/// Skipped when the condition is unchanged.
+pub async fn update_missing_role_assignments_condition(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    result: &Result<Vec<MissingRoleAssignment>>,
+) -> Result<()> {
+    let existing = config.status.as_ref().and_then(|s| {
+        s.conditions
+            .iter()
+            .find(|c| c.r#type == MISSING_ROLE_ASSIGNMENTS_CONDITION)
+    });
+    let Some(condition) =
+        missing_role_assignments_condition(existing, result, config.metadata.generation)
+    else {
+        return Ok(());
+    };
+    patch_condition(reconciler, config, condition).await
+}
+
+/// ExpiringSoon condition for the secrets that expire within `warnBefore`
+/// SecretsExpired once any of them is past its expiry. Returns None when nothing changed, as
+/// for [`conflicting_writer_condition`].
+fn expiring_soon_condition(
+    existing: Option<&Condition>,
+    expiring: &[ExpiringSecret],
+    now: DateTime<Utc>,
+    generation: Option<i64>,
+) -> Option<Condition> {
+    let (status, reason, message) = if expiring.is_empty() {
+        (
+            FALSE,
+            ConditionReason::NoSecretsExpiringSoon,
+            "No managed secret expires within warnBefore".to_string(),
+        )
+    } else {
+        let reason = if expiring.iter().any(|secret| secret.expire_time <= now) {
+            ConditionReason::SecretsExpired
+        } else {
+            ConditionReason::SecretsExpiringSoon
+        };
+        let message = expiring
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        (TRUE, reason, message)
+    };
+
+    let unchanged = match existing {
+        None => expiring.is_empty(),
+        Some(condition) => {
+            condition.status == status
+                && condition.reason.as_deref() == Some(reason.as_str())
+                && condition.message.as_deref() == Some(message.as_str())
+                && condition.observed_generation == generation
+        }
+    };
+    if unchanged {
+        return None;
+    }
+    Some(new_condition(
+        EXPIRING_SOON_CONDITION,
+        status,
+        reason,
+        message,
+        generation,
+    ))
+}
+
+/// Set the ExpiringSoon condition from the secrets that expire within `secrets.expiration`'s
+/// `warnBefore`, or set it False once none do
+/// Skipped when the condition is unchanged.
+pub async fn update_expiring_soon_condition(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    expiring: &[ExpiringSecret],
+) -> Result<()> {
+    let existing = config.status.as_ref().and_then(|s| {
+        s.conditions
+            .iter()
+            .find(|c| c.r#type == EXPIRING_SOON_CONDITION)
+    });
+    let Some(condition) =
+        expiring_soon_condition(existing, expiring, Utc::now(), config.metadata.generation)
+    else {
+        return Ok(());
+    };
+    patch_condition(reconciler, config, condition).await
+}
+
+/// Set `condition` on the resource's current status
+/// The conditions array is replaced as a whole by a merge patch, so the current status is read
+/// first to keep the conditions written by this reconciliation.
+async fn patch_condition(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    condition: Condition,
+) -> Result<()> {
+    let condition_type = condition.r#type.clone();
+    let resource_name = config.metadata.name.as_deref().unwrap_or("unknown");
+    let resource_namespace = config.metadata.namespace.as_deref().unwrap_or("default");
+    let api: kube::Api<SecretManagerConfig> =
+        kube::Api::namespaced(reconciler.client.clone(), resource_namespace);
+
+    let current = match api.get_status(resource_name).await {
+        Ok(current) => current,
+        Err(kube::Error::Api(api_err)) if api_err.code == 404 => {
+            debug!(
+                "SecretManagerConfig {}/{} was deleted during reconciliation, skipping {} condition",
+                resource_namespace, resource_name, condition_type
+            );
+            return Ok(());
+        }
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Failed to read status of SecretManagerConfig {}/{}: {}",
+                resource_namespace,
+                resource_name,
+                e
+            ));
+        }
+    };
+    let mut conditions = current.status.map(|s| s.conditions).unwrap_or_default();
+    set_condition(&mut conditions, condition);
+
+    let patch = serde_json::json!({
+        "status": {
+            "conditions": conditions
+        }
+    });
+
+    match api
+        .patch_status(
+            resource_name,
+            &PatchParams::apply("secret-manager-controller"),
+            &kube::api::Patch::Merge(patch),
+        )
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(kube::Error::Api(api_err)) if api_err.code == 404 => {
+            debug!(
+                "SecretManagerConfig {}/{} was deleted during reconciliation, skipping {} condition",
+                resource_namespace, resource_name, condition_type
+            );
+            Ok(())
+        }
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to update {} condition for SecretManagerConfig {}/{}: {}",
+            condition_type,
+            resource_namespace,
+            resource_name,
+            e
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::SecretManagerConfigStatus;
+    use crate::testing::ConfigFixture;
+
+    fn config(conditions: Vec<Condition>) -> SecretManagerConfig {
+        ConfigFixture::new("test")
+            .with_generation(3)
+            .with_status(SecretManagerConfigStatus {
+                conditions,
+                ..SecretManagerConfigStatus::default()
+            })
+            .build()
+    }
+
+    fn find<'a>(conditions: &'a [Condition], r#type: &str) -> Option<&'a Condition> {
+        conditions.iter().find(|c| c.r#type == r#type)
+    }
+
+    #[test]
+    fn test_phase_conditions_in_progress() {
+        let conditions = phase_conditions(
+            &config(vec![]),
+            "Updating",
+            ConditionReason::ReconciliationInProgress,
+            Some("Reconciling secrets to Secret Manager"),
+        );
+        let ready = find(&conditions, READY_CONDITION)
+            .unwrap_or_else(|| panic!("No Ready condition: {conditions:?}"));
+        assert_eq!(ready.status, UNKNOWN);
+        assert_eq!(ready.observed_generation, Some(3));
+        assert_eq!(
+            find(&conditions, RECONCILING_CONDITION).map(|c| c.status.as_str()),
+            Some(TRUE)
+        );
+        assert!(find(&conditions, STALLED_CONDITION).is_none());
+        assert_eq!(
+            find(&conditions, SOURCE_AVAILABLE_CONDITION).map(|c| c.status.as_str()),
+            Some(TRUE)
+        );
+    }
+
+    #[test]
+    fn test_phase_conditions_stalled_source() {
+        let previous = phase_conditions(
+            &config(vec![]),
+            "Started",
+            ConditionReason::ReconciliationInProgress,
+            None,
+        );
+        let conditions = phase_conditions(
+            &config(previous),
+            "Failed",
+            ConditionReason::CredentialsInvalid,
+            Some("Git credentials invalid"),
+        );
+        assert!(find(&conditions, RECONCILING_CONDITION).is_none());
+        let stalled = find(&conditions, STALLED_CONDITION)
+            .unwrap_or_else(|| panic!("No Stalled condition: {conditions:?}"));
+        assert_eq!(stalled.reason.as_deref(), Some("CredentialsInvalid"));
+        let source = find(&conditions, SOURCE_AVAILABLE_CONDITION)
+            .unwrap_or_else(|| panic!("No SourceAvailable condition: {conditions:?}"));
+        assert_eq!(source.status, FALSE);
+        assert_eq!(
+            find(&conditions, READY_CONDITION).map(|c| c.status.as_str()),
+            Some(FALSE)
+        );
+    }
+
+    #[test]
+    fn test_set_condition_keeps_transition_time() {
+        let mut first = new_condition(
+            READY_CONDITION,
+            TRUE,
+            ConditionReason::ReconciliationSucceeded,
+            "synced",
+            Some(1),
+        );
+        first.last_transition_time = Some("2026-01-01T00:00:00Z".to_string());
+        let mut conditions = vec![first];
+        set_condition(
+            &mut conditions,
+            new_condition(
+                READY_CONDITION,
+                TRUE,
+                ConditionReason::ReconciliationSucceeded,
+                "synced again",
+                Some(2),
+            ),
+        );
+        assert_eq!(conditions.len(), 1);
+        assert_eq!(
+            conditions[0].last_transition_time.as_deref(),
+            Some("2026-01-01T00:00:00Z")
+        );
+        assert_eq!(conditions[0].observed_generation, Some(2));
+    }
+
+    #[test]
+    fn test_sops_key_condition() {
+        let mut missing_key = config(vec![]);
+        assert!(sops_key_condition(&missing_key, false).is_none());
+
+        if let Some(status) = missing_key.status.as_mut() {
+            status.decryption_status = Some("Success".to_string());
+        }
+        let lost = sops_key_condition(&missing_key, false)
+            .unwrap_or_else(|| panic!("Expected DecryptionReady to be set False"));
+        assert_eq!(lost.status, FALSE);
+        assert!(sops_key_condition(&missing_key, true).is_none());
+
+        if let Some(status) = missing_key.status.as_mut() {
+            status.conditions = vec![lost];
+        }
+        let found = sops_key_condition(&missing_key, true)
+            .unwrap_or_else(|| panic!("Expected DecryptionReady to be reset"));
+        assert_eq!(found.status, UNKNOWN);
+    }
+
+    #[test]
+    fn test_conflicting_writer_condition() {
+        let conflicts = vec![WriterConflict {
+            secret: "app-db-password".to_string(),
+            writer: LastWriter::Foreign,
+        }];
+        // Nothing to report or clear
+        assert!(conflicting_writer_condition(None, &[], Some(1)).is_none());
+
+        let raised = conflicting_writer_condition(None, &conflicts, Some(1))
+            .unwrap_or_else(|| panic!("Expected a ConflictingWriter condition"));
+        assert_eq!(raised.status, "True");
+        assert_eq!(raised.reason.as_deref(), Some("ForeignWrite"));
+        assert!(conflicting_writer_condition(Some(&raised), &conflicts, Some(1)).is_none());
+
+        let cleared = conflicting_writer_condition(Some(&raised), &[], Some(1))
+            .unwrap_or_else(|| panic!("Expected the condition to be cleared"));
+        assert_eq!(cleared.status, "False");
+        assert!(conflicting_writer_condition(Some(&cleared), &[], Some(1)).is_none());
+    }
+
+    #[test]
+    fn test_wrong_encryption_key_condition() {
+        let mismatches = vec![RecipientMismatch {
+            file: std::path::PathBuf::from("prod/application.secrets.env"),
+            unexpected: vec!["pgp:FFFF".to_string()],
+            missing: vec![],
+        }];
+        assert!(wrong_encryption_key_condition(None, &[], Some(1)).is_none());
+
+        let raised = wrong_encryption_key_condition(None, &mismatches, Some(1))
+            .unwrap_or_else(|| panic!("Expected a WrongEncryptionKey condition"));
+        assert_eq!(raised.status, "True");
+        assert_eq!(raised.reason.as_deref(), Some("UnexpectedRecipients"));
+        assert!(wrong_encryption_key_condition(Some(&raised), &mismatches, Some(1)).is_none());
+
+        let cleared = wrong_encryption_key_condition(Some(&raised), &[], Some(1))
+            .unwrap_or_else(|| panic!("Expected the condition to be cleared"));
+        assert_eq!(cleared.status, "False");
+    }
+
+    #[test]
+    fn test_missing_role_assignments_condition() {
+        let missing = vec![MissingRoleAssignment {
+            principal_id: "app-identity".to_string(),
+            scope: "vault".to_string(),
+            error: None,
+        }];
+        let raised = missing_role_assignments_condition(None, &Ok(missing.clone()), Some(1))
+            .unwrap_or_else(|| panic!("Expected a MissingRoleAssignments condition"));
+        assert_eq!(raised.status, "True");
+        assert_eq!(
+            raised.message.as_deref(),
+            Some("app-identity has no Key Vault Secrets User role on vault")
+        );
+        assert!(missing_role_assignments_condition(Some(&raised), &Ok(missing), Some(1)).is_none());
+
+        let error = Err(anyhow::anyhow!("403 - AuthorizationFailed"));
+        let failed = missing_role_assignments_condition(Some(&raised), &error, Some(1))
+            .unwrap_or_else(|| panic!("Expected an Unknown condition"));
+        assert_eq!(failed.status, "Unknown");
+        assert_eq!(failed.reason.as_deref(), Some("RoleAssignmentCheckFailed"));
+
+        let cleared = missing_role_assignments_condition(Some(&failed), &Ok(vec![]), Some(1))
+            .unwrap_or_else(|| panic!("Expected the condition to be cleared"));
+        assert_eq!(cleared.status, "False");
+    }
+
+    #[test]
+    fn test_expiring_soon_condition() {
+        let now = Utc::now();
+        let expiring = vec![ExpiringSecret {
+            name: "app-db-password".to_string(),
+            expire_time: now + chrono::Duration::days(3),
+        }];
+        assert!(expiring_soon_condition(None, &[], now, Some(1)).is_none());
+
+        let raised = expiring_soon_condition(None, &expiring, now, Some(1))
+            .unwrap_or_else(|| panic!("Expected an ExpiringSoon condition"));
+        assert_eq!(raised.status, "True");
+        assert_eq!(raised.reason.as_deref(), Some("SecretsExpiringSoon"));
+        assert!(expiring_soon_condition(Some(&raised), &expiring, now, Some(1)).is_none());
+
+        let later = now + chrono::Duration::days(4);
+        let expired = expiring_soon_condition(Some(&raised), &expiring, later, Some(1))
+            .unwrap_or_else(|| panic!("Expected the reason to change"));
+        assert_eq!(expired.reason.as_deref(), Some("SecretsExpired"));
+
+        let cleared = expiring_soon_condition(Some(&expired), &[], later, Some(1))
+            .unwrap_or_else(|| panic!("Expected the condition to be cleared"));
+        assert_eq!(cleared.status, "False");
+    }
+}
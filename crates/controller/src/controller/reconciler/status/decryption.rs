@@ -1,7 +1,8 @@
 //! # Decryption Status Updates
 //!
-//! Handles updating SOPS decryption status.
+//! Handles updating SOPS decryption status and the DecryptionReady condition.
 
+use super::conditions::{decryption_condition, set_condition};
 use crate::controller::reconciler::types::Reconciler;
 use crate::crd::SecretManagerConfig;
 use anyhow::Result;
@@ -42,6 +43,10 @@ pub async fn update_decryption_status(
             .map(|s| s.conditions.clone())
             .unwrap_or_default();
     }
+    set_condition(
+        &mut new_status.conditions,
+        decryption_condition(config, status, error_message),
+    );
     if new_status.observed_generation.is_none() {
         new_status.observed_generation = config.metadata.generation;
     }
@@ -0,0 +1,185 @@
+//! # Reconcile History
+//!
+//! Records each completed reconciliation in `status.recentReconciles`.
+//!
+//! The list is bounded to `MAX_RECENT_RECONCILES` entries (oldest first) so operators can see
+//! the timing and outcome of the last few runs from the resource alone. It is written with a
+//! merge patch of only `recentReconciles`, after the reconciliation's own status updates.
+
+use crate::constants::{MAX_RECENT_RECONCILE_MESSAGE_LEN, MAX_RECENT_RECONCILES};
+use crate::controller::reconciler::types::Reconciler;
+use crate::crd::{ReconcileRecord, ResourceSyncState, SecretManagerConfig, SyncStatus};
+use anyhow::Result;
+use kube::api::PatchParams;
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Append `record` to `history`, dropping the oldest entries beyond `MAX_RECENT_RECONCILES`
+pub fn append_reconcile(
+    history: Option<&[ReconcileRecord]>,
+    record: ReconcileRecord,
+) -> Vec<ReconcileRecord> {
+    let history = history.unwrap_or_default();
+    let keep = history.len().min(MAX_RECENT_RECONCILES.saturating_sub(1));
+    let mut recent = history[history.len() - keep..].to_vec();
+    recent.push(record);
+    recent
+}
+
+/// Truncate an error message for the history (on a char boundary)
+pub fn truncate_message(message: &str) -> String {
+    match message.char_indices().nth(MAX_RECENT_RECONCILE_MESSAGE_LEN) {
+        Some((end, _)) => format!("{}…", &message[..end]),
+        None => message.to_string(),
+    }
+}
+
+/// Number of secrets and properties created or updated compared to the previous sync state
+pub fn count_changed(
+    previous: Option<&SyncStatus>,
+    synced_secrets: &HashMap<String, ResourceSyncState>,
+    synced_properties: &HashMap<String, ResourceSyncState>,
+) -> u32 {
+    let changed = |previous: Option<&HashMap<String, ResourceSyncState>>,
+                   current: &HashMap<String, ResourceSyncState>| {
+        current
+            .iter()
+            .filter(|(name, state)| {
+                state.exists
+                    && previous.and_then(|p| p.get(*name)).is_none_or(|before| {
+                        !before.exists || state.update_count > before.update_count
+                    })
+            })
+            .count()
+    };
+    let secrets = changed(previous.and_then(|s| s.secrets.as_ref()), synced_secrets);
+    let properties = changed(
+        previous.and_then(|s| s.properties.as_ref()),
+        synced_properties,
+    );
+    u32::try_from(secrets + properties).unwrap_or(u32::MAX)
+}
+
+/// Append `record` to the resource's `recentReconciles`
+pub async fn record_reconcile(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    record: ReconcileRecord,
+) -> Result<()> {
+    let recent = append_reconcile(
+        config
+            .status
+            .as_ref()
+            .and_then(|s| s.recent_reconciles.as_deref()),
+        record,
+    );
+
+    let resource_name = config.metadata.name.as_deref().unwrap_or("unknown");
+    let resource_namespace = config.metadata.namespace.as_deref().unwrap_or("default");
+    let api: kube::Api<SecretManagerConfig> =
+        kube::Api::namespaced(reconciler.client.clone(), resource_namespace);
+
+    let patch = serde_json::json!({
+        "status": {
+            "recentReconciles": recent
+        }
+    });
+
+    match api
+        .patch_status(
+            resource_name,
+            &PatchParams::apply("secret-manager-controller"),
+            &kube::api::Patch::Merge(patch),
+        )
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(kube::Error::Api(api_err)) if api_err.code == 404 => {
+            // Resource was deleted during reconciliation - nothing to record
+            debug!(
+                "SecretManagerConfig {}/{} was deleted during reconciliation, skipping reconcile history",
+                resource_namespace, resource_name
+            );
+            Ok(())
+        }
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to record reconcile history for SecretManagerConfig {}/{}: {}",
+            resource_namespace,
+            resource_name,
+            e
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(outcome: &str) -> ReconcileRecord {
+        ReconcileRecord {
+            trigger: "timer-based".to_string(),
+            started_at: "2025-01-15T10:30:00Z".to_string(),
+            duration_ms: 1200,
+            outcome: outcome.to_string(),
+            secrets_changed: 0,
+            message: None,
+        }
+    }
+
+    fn state(exists: bool, update_count: i32) -> ResourceSyncState {
+        ResourceSyncState {
+            exists,
+            update_count,
+            source_key: None,
+            tier: None,
+            expire_time: None,
+        }
+    }
+
+    #[test]
+    fn test_append_reconcile_is_bounded() {
+        let mut history = Vec::new();
+        for i in 0..MAX_RECENT_RECONCILES + 3 {
+            history = append_reconcile(Some(&history), record(&i.to_string()));
+        }
+        assert_eq!(history.len(), MAX_RECENT_RECONCILES);
+        assert_eq!(history[0].outcome, "3");
+        assert_eq!(
+            history.last().map(|r| r.outcome.as_str()),
+            Some((MAX_RECENT_RECONCILES + 2).to_string().as_str())
+        );
+        assert_eq!(append_reconcile(None, record("Succeeded")).len(), 1);
+    }
+
+    #[test]
+    fn test_truncate_message() {
+        assert_eq!(truncate_message("short"), "short");
+        let long = "é".repeat(MAX_RECENT_RECONCILE_MESSAGE_LEN + 10);
+        assert_eq!(
+            truncate_message(&long).chars().count(),
+            MAX_RECENT_RECONCILE_MESSAGE_LEN + 1
+        );
+    }
+
+    #[test]
+    fn test_count_changed() {
+        let previous = SyncStatus {
+            secrets: Some(HashMap::from([
+                ("unchanged".to_string(), state(true, 2)),
+                ("updated".to_string(), state(true, 1)),
+                ("recreated".to_string(), state(false, 0)),
+            ])),
+            properties: None,
+        };
+        let secrets = HashMap::from([
+            ("unchanged".to_string(), state(true, 2)),
+            ("updated".to_string(), state(true, 2)),
+            ("recreated".to_string(), state(true, 0)),
+            ("created".to_string(), state(true, 0)),
+        ]);
+        let properties = HashMap::from([("app.port".to_string(), state(true, 0))]);
+
+        assert_eq!(count_changed(Some(&previous), &secrets, &properties), 4);
+        assert_eq!(count_changed(None, &secrets, &HashMap::new()), 4);
+    }
+}
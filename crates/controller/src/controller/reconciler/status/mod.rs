@@ -4,7 +4,9 @@
 
 mod annotations;
 mod backoff;
+mod conditions;
 mod decryption;
+mod history;
 mod phase;
 mod sops;
 mod status;
@@ -14,9 +16,16 @@ pub use annotations::{
     increment_parsing_error_count,
 };
 pub use backoff::calculate_progressive_backoff;
-pub use decryption::update_decryption_status;
-pub use phase::update_status_phase;
-pub use sops::{
-    check_sops_key_availability, update_all_resources_in_namespace, update_sops_key_status,
+pub use conditions::{
+    CONFLICTING_WRITER_CONDITION, ConditionReason, DECRYPTION_READY_CONDITION,
+    EXPIRING_SOON_CONDITION, MISSING_ROLE_ASSIGNMENTS_CONDITION, READY_CONDITION,
+    RECONCILING_CONDITION, SOURCE_AVAILABLE_CONDITION, STALLED_CONDITION,
+    WRONG_ENCRYPTION_KEY_CONDITION, new_condition, phase_conditions, remove_condition,
+    set_condition, update_conflicting_writer_condition, update_expiring_soon_condition,
+    update_missing_role_assignments_condition, update_wrong_encryption_key_condition,
 };
-pub use status::update_status;
+pub use decryption::update_decryption_status;
+pub use history::{append_reconcile, count_changed, record_reconcile, truncate_message};
+pub use phase::{update_status_phase, update_status_phase_with_reason};
+pub use sops::{check_sops_key_availability, update_sops_key_status};
+pub use status::{update_status, update_status_awaiting_approval, update_status_partial_failure};
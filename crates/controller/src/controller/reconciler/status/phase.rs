@@ -2,9 +2,9 @@
 //!
 //! Handles updating status phase and description.
 
+use super::conditions::{ConditionReason, READY_CONDITION, phase_conditions};
 use crate::controller::reconciler::types::Reconciler;
-use crate::controller::reconciler::validation::parse_kubernetes_duration;
-use crate::crd::{Condition, SecretManagerConfig, SecretManagerConfigStatus};
+use crate::crd::{SecretManagerConfig, SecretManagerConfigStatus};
 use anyhow::Result;
 use kube::api::PatchParams;
 use tracing::debug;
@@ -16,6 +16,18 @@ pub async fn update_status_phase(
     config: &SecretManagerConfig,
     phase: &str,
     message: Option<&str>,
+) -> Result<()> {
+    update_status_phase_with_reason(reconciler, config, phase, message, None).await
+}
+
+/// Update status phase and description with an explicit condition reason
+/// `None` derives the reason from the phase (ReconciliationSucceeded/Failed/InProgress/Suspended)
+pub async fn update_status_phase_with_reason(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    phase: &str,
+    message: Option<&str>,
+    reason: Option<ConditionReason>,
 ) -> Result<()> {
     // CRITICAL: Check if status actually changed before updating
     // This prevents unnecessary status updates that trigger watch events
@@ -24,9 +36,26 @@ pub async fn update_status_phase(
         .status
         .as_ref()
         .and_then(|s| s.description.as_deref());
+    let reason_unchanged = reason.is_none_or(|reason| {
+        config
+            .status
+            .as_ref()
+            .and_then(|s| s.conditions.iter().find(|c| c.r#type == READY_CONDITION))
+            .and_then(|c| c.reason.as_deref())
+            == Some(reason.as_str())
+    });
+
+    // A new generation must be recorded even when nothing else changed, or kstatus keeps
+    // reporting the resource as InProgress
+    let generation_observed =
+        config.status.as_ref().and_then(|s| s.observed_generation) == config.metadata.generation;
 
-    // Only update if phase or description actually changed
-    if current_phase == Some(phase) && current_description == message.as_deref() {
+    // Only update if phase, description, an explicit reason or the generation actually changed
+    if current_phase == Some(phase)
+        && current_description == message.as_deref()
+        && reason_unchanged
+        && generation_observed
+    {
         debug!(
             "Skipping status update - phase and description unchanged: phase={:?}, description={:?}",
             phase, message
@@ -39,26 +68,14 @@ pub async fn update_status_phase(
         config.metadata.namespace.as_deref().unwrap_or("default"),
     );
 
-    let mut conditions = vec![];
-    let ready_status = if phase == "Ready" { "True" } else { "False" };
-    let ready_reason = if phase == "Ready" {
-        "ReconciliationSucceeded"
-    } else if phase == "Failed" {
-        "ReconciliationFailed"
-    } else {
-        "ReconciliationInProgress"
-    };
-
-    conditions.push(Condition {
-        r#type: "Ready".to_string(),
-        status: ready_status.to_string(),
-        last_transition_time: Some(chrono::Utc::now().to_rfc3339()),
-        reason: Some(ready_reason.to_string()),
-        message: message.map(|s| s.to_string()),
-    });
+    let reason = reason.unwrap_or_else(|| ConditionReason::for_phase(phase));
+    let conditions = phase_conditions(config, phase, reason, message);
 
     // Calculate next reconcile time based on reconcile interval
-    let next_reconcile_time = parse_kubernetes_duration(&config.spec.reconcile_interval)
+    let next_reconcile_time = config
+        .spec
+        .reconcile_interval
+        .to_duration()
         .ok()
         .map(|duration| {
             chrono::Utc::now()
@@ -77,9 +94,13 @@ pub async fn update_status_phase(
         conditions,
         observed_generation: config.metadata.generation,
         last_reconcile_time: Some(chrono::Utc::now().to_rfc3339()),
+        last_synced_revision: existing_status.and_then(|s| s.last_synced_revision.clone()),
         next_reconcile_time,
         secrets_synced: None,
         sync: existing_status.and_then(|s| s.sync.clone()),
+        failed_secrets: existing_status.and_then(|s| s.failed_secrets.clone()),
+        failed_services: existing_status.and_then(|s| s.failed_services.clone()),
+        pending_changes: existing_status.and_then(|s| s.pending_changes.clone()),
         decryption_status: existing_status.and_then(|s| s.decryption_status.clone()),
         last_decryption_attempt: existing_status.and_then(|s| s.last_decryption_attempt.clone()),
         last_decryption_error: existing_status.and_then(|s| s.last_decryption_error.clone()),
@@ -87,6 +108,7 @@ pub async fn update_status_phase(
         sops_key_secret_name: existing_status.and_then(|s| s.sops_key_secret_name.clone()),
         sops_key_namespace: existing_status.and_then(|s| s.sops_key_namespace.clone()),
         sops_key_last_checked: existing_status.and_then(|s| s.sops_key_last_checked.clone()),
+        recent_reconciles: existing_status.and_then(|s| s.recent_reconciles.clone()),
     };
 
     let patch = serde_json::json!({
@@ -2,11 +2,15 @@
 //!
 //! Handles checking and updating SOPS key availability status.
 
+use super::conditions::{set_condition, sops_key_condition};
+use crate::controller::reconciler::sops::{
+    SOPS_KEY_LABEL_SELECTOR, SOPS_KEY_SECRET_NAMES, extract_private_key,
+};
 use crate::controller::reconciler::types::Reconciler;
 use crate::crd::SecretManagerConfig;
 use anyhow::Result;
 use kube::Api;
-use kube::api::PatchParams;
+use kube::api::{ListParams, PatchParams};
 use tracing::debug;
 
 /// Check SOPS key availability in a namespace
@@ -18,20 +22,11 @@ pub async fn check_sops_key_availability(
     use k8s_openapi::api::core::v1::Secret;
 
     let secrets: Api<Secret> = Api::namespaced(reconciler.client.clone(), namespace);
-    let secret_names = vec!["sops-private-key", "sops-gpg-key", "gpg-key"];
-
-    for secret_name in secret_names {
+    for secret_name in SOPS_KEY_SECRET_NAMES {
         match secrets.get(secret_name).await {
             Ok(secret) => {
-                if let Some(ref data_map) = secret.data {
-                    if data_map
-                        .get("private-key")
-                        .or_else(|| data_map.get("key"))
-                        .or_else(|| data_map.get("gpg-key"))
-                        .is_some()
-                    {
-                        return Ok((true, Some(secret_name.to_string())));
-                    }
+                if extract_private_key(&secret, None).is_some() {
+                    return Ok((true, Some(secret_name.to_string())));
                 }
             }
             Err(kube::Error::Api(api_err)) if api_err.code == 404 => {
@@ -46,6 +41,29 @@ pub async fn check_sops_key_availability(
         }
     }
 
+    // Fall back to secrets labelled as SOPS keys
+    match secrets
+        .list(&ListParams::default().labels(SOPS_KEY_LABEL_SELECTOR))
+        .await
+    {
+        Ok(list) => {
+            if let Some(name) = list
+                .items
+                .iter()
+                .find(|secret| extract_private_key(secret, None).is_some())
+                .and_then(|secret| secret.metadata.name.clone())
+            {
+                return Ok((true, Some(name)));
+            }
+        }
+        Err(e) => {
+            debug!(
+                "Failed to list labelled SOPS key secrets in '{}': {}",
+                namespace, e
+            );
+        }
+    }
+
     Ok((false, None))
 }
 
@@ -83,6 +101,9 @@ pub async fn update_sops_key_status(
             .map(|s| s.conditions.clone())
             .unwrap_or_default();
     }
+    if let Some(condition) = sops_key_condition(config, key_available) {
+        set_condition(&mut new_status.conditions, condition);
+    }
     if new_status.observed_generation.is_none() {
         new_status.observed_generation = config.metadata.generation;
     }
@@ -152,49 +173,3 @@ pub async fn update_sops_key_status(
 
     Ok(())
 }
-
-/// Update SOPS key status for all SecretManagerConfig resources in a namespace
-/// Called by watch when SOPS key secret is created/deleted
-pub async fn update_all_resources_in_namespace(
-    reconciler: &Reconciler,
-    namespace: &str,
-    key_available: bool,
-    secret_name: Option<String>,
-) -> Result<()> {
-    let api: Api<SecretManagerConfig> = Api::namespaced(reconciler.client.clone(), namespace);
-
-    // List all SecretManagerConfig resources in this namespace
-    let resources = api.list(&kube::api::ListParams::default()).await?;
-
-    let mut updated_count = 0;
-    let mut failed_count = 0;
-
-    for resource in resources {
-        match update_sops_key_status(reconciler, &resource, key_available, secret_name.clone())
-            .await
-        {
-            Ok(_) => {
-                updated_count += 1;
-            }
-            Err(e) => {
-                failed_count += 1;
-                debug!(
-                    "Failed to update SOPS key status for {}/{}: {}",
-                    namespace,
-                    resource.metadata.name.as_deref().unwrap_or("unknown"),
-                    e
-                );
-            }
-        }
-    }
-
-    debug!(
-        "Updated SOPS key status for {} resources in namespace '{}' ({} succeeded, {} failed)",
-        updated_count + failed_count,
-        namespace,
-        updated_count,
-        failed_count
-    );
-
-    Ok(())
-}
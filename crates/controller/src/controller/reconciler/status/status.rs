@@ -1,11 +1,13 @@
 //! # Status Updates
 //!
-//! Handles updating status with secrets synced count.
+//! Handles updating status with secrets synced count, per-secret sync failures and
+//! change sets awaiting approval.
 
+use super::conditions::{ConditionReason, phase_conditions};
 use crate::controller::reconciler::types::Reconciler;
-use crate::controller::reconciler::validation::parse_kubernetes_duration;
 use crate::crd::{
-    Condition, ResourceSyncState, SecretManagerConfig, SecretManagerConfigStatus, SyncStatus,
+    FailedSecret, FailedService, PendingChanges, ResourceSyncState, SecretManagerConfig,
+    SecretManagerConfigStatus, SyncStatus,
 };
 use anyhow::Result;
 use kube::api::PatchParams;
@@ -15,12 +17,14 @@ use tracing::debug;
 /// CRITICAL: Checks if status actually changed before updating to prevent unnecessary watch events
 /// synced_secrets tracks which secrets have been successfully pushed and their update counts
 /// synced_properties tracks which properties have been successfully pushed and their update counts
+/// synced_revision is the source revision that was fully synced (None keeps the recorded one)
 pub async fn update_status(
     reconciler: &Reconciler,
     config: &SecretManagerConfig,
     secrets_synced: i32,
     synced_secrets: &std::collections::HashMap<String, ResourceSyncState>,
     synced_properties: &std::collections::HashMap<String, ResourceSyncState>,
+    synced_revision: Option<&str>,
 ) -> Result<()> {
     // CRITICAL: Check if status actually changed before updating
     let current_secrets_synced = config
@@ -28,9 +32,15 @@ pub async fn update_status(
         .as_ref()
         .and_then(|s| s.secrets_synced)
         .unwrap_or(0);
+    let current_revision = config
+        .status
+        .as_ref()
+        .and_then(|s| s.last_synced_revision.as_deref());
 
     if current_secrets_synced == secrets_synced
         && config.status.as_ref().and_then(|s| s.phase.as_deref()) == Some("Ready")
+        && synced_revision.is_none_or(|revision| current_revision == Some(revision))
+        && config.status.as_ref().and_then(|s| s.observed_generation) == config.metadata.generation
     {
         debug!(
             "Skipping status update - secrets_synced and phase unchanged: secrets_synced={}",
@@ -39,11 +49,6 @@ pub async fn update_status(
         return Ok(());
     }
 
-    let api: kube::Api<SecretManagerConfig> = kube::Api::namespaced(
-        reconciler.client.clone(),
-        config.metadata.namespace.as_deref().unwrap_or("default"),
-    );
-
     let description = format!("Successfully synced {} secrets", secrets_synced);
 
     // Preserve existing decryption status fields if they exist
@@ -51,16 +56,21 @@ pub async fn update_status(
     let status = SecretManagerConfigStatus {
         phase: Some("Ready".to_string()),
         description: Some(description.clone()),
-        conditions: vec![Condition {
-            r#type: "Ready".to_string(),
-            status: "True".to_string(),
-            last_transition_time: Some(chrono::Utc::now().to_rfc3339()),
-            reason: Some("ReconciliationSucceeded".to_string()),
-            message: Some(description),
-        }],
+        conditions: phase_conditions(
+            config,
+            "Ready",
+            ConditionReason::ReconciliationSucceeded,
+            Some(&description),
+        ),
         observed_generation: config.metadata.generation,
         last_reconcile_time: Some(chrono::Utc::now().to_rfc3339()),
-        next_reconcile_time: parse_kubernetes_duration(&config.spec.reconcile_interval)
+        last_synced_revision: synced_revision
+            .map(str::to_string)
+            .or_else(|| current_revision.map(str::to_string)),
+        next_reconcile_time: config
+            .spec
+            .reconcile_interval
+            .to_duration()
             .ok()
             .map(|duration| {
                 chrono::Utc::now()
@@ -75,6 +85,10 @@ pub async fn update_status(
             secrets: Some(synced_secrets.clone()),
             properties: Some(synced_properties.clone()),
         }),
+        failed_secrets: None,
+        failed_services: None,
+        // Everything planned has now been applied
+        pending_changes: None,
         decryption_status: existing_status.and_then(|s| s.decryption_status.clone()),
         last_decryption_attempt: existing_status.and_then(|s| s.last_decryption_attempt.clone()),
         last_decryption_error: existing_status.and_then(|s| s.last_decryption_error.clone()),
@@ -82,8 +96,133 @@ pub async fn update_status(
         sops_key_secret_name: existing_status.and_then(|s| s.sops_key_secret_name.clone()),
         sops_key_namespace: existing_status.and_then(|s| s.sops_key_namespace.clone()),
         sops_key_last_checked: existing_status.and_then(|s| s.sops_key_last_checked.clone()),
+        recent_reconciles: existing_status.and_then(|s| s.recent_reconciles.clone()),
     };
 
+    patch_status(reconciler, config, &status).await
+}
+
+/// Update status after a reconcile where some secrets failed to sync
+/// Successful secrets are recorded in the sync maps as usual; failures are listed in
+/// `failedSecrets`, services that failed as a whole in `failedServices`, and the Ready
+/// condition is set to False with reason PartialFailure
+pub async fn update_status_partial_failure(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    secrets_synced: i32,
+    synced_secrets: &std::collections::HashMap<String, ResourceSyncState>,
+    synced_properties: &std::collections::HashMap<String, ResourceSyncState>,
+    failed_secrets: &[FailedSecret],
+    failed_services: &[FailedService],
+) -> Result<()> {
+    let description = partial_failure_description(secrets_synced, failed_secrets, failed_services);
+
+    let existing_status = config.status.as_ref();
+    let mut status = existing_status.cloned().unwrap_or_default();
+    status.phase = Some("PartialFailure".to_string());
+    status.description = Some(description.clone());
+    status.conditions = phase_conditions(
+        config,
+        "PartialFailure",
+        ConditionReason::PartialFailure,
+        Some(&description),
+    );
+    status.observed_generation = config.metadata.generation;
+    status.last_reconcile_time = Some(chrono::Utc::now().to_rfc3339());
+    status.secrets_synced = Some(secrets_synced);
+    status.sync = Some(SyncStatus {
+        secrets: Some(synced_secrets.clone()),
+        properties: Some(synced_properties.clone()),
+    });
+    status.failed_secrets = Some(failed_secrets.to_vec());
+    status.failed_services = Some(failed_services.to_vec());
+
+    patch_status(reconciler, config, &status).await
+}
+
+/// "Synced N secrets, X failed: a, b; services failed: c"
+fn partial_failure_description(
+    secrets_synced: i32,
+    failed_secrets: &[FailedSecret],
+    failed_services: &[FailedService],
+) -> String {
+    let mut description = format!(
+        "Synced {} secrets, {} failed: {}",
+        secrets_synced,
+        failed_secrets.len(),
+        failed_secrets
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if !failed_services.is_empty() {
+        description.push_str(&format!(
+            "; services failed: {}",
+            failed_services
+                .iter()
+                .map(|f| f.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    description
+}
+
+/// Stage a change set for approval (`approvalPolicy: Manual`)
+/// Sets phase AwaitingApproval with the Ready condition False; sync state is left untouched
+/// because nothing was written. Skipped when the same change set is already staged.
+pub async fn update_status_awaiting_approval(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    pending_changes: &PendingChanges,
+) -> Result<()> {
+    let existing_status = config.status.as_ref();
+    if existing_status.and_then(|s| s.phase.as_deref()) == Some("AwaitingApproval")
+        && existing_status
+            .and_then(|s| s.pending_changes.as_ref())
+            .map(|p| p.hash.as_str())
+            == Some(pending_changes.hash.as_str())
+    {
+        debug!(
+            "Skipping status update - change set {} already awaiting approval",
+            pending_changes.hash
+        );
+        return Ok(());
+    }
+
+    let description = format!(
+        "{} awaiting approval (hash {})",
+        pending_changes.summary, pending_changes.hash
+    );
+
+    let mut status = existing_status.cloned().unwrap_or_default();
+    status.phase = Some("AwaitingApproval".to_string());
+    status.description = Some(description.clone());
+    status.conditions = phase_conditions(
+        config,
+        "AwaitingApproval",
+        ConditionReason::AwaitingApproval,
+        Some(&description),
+    );
+    status.observed_generation = config.metadata.generation;
+    status.last_reconcile_time = Some(chrono::Utc::now().to_rfc3339());
+    status.pending_changes = Some(pending_changes.clone());
+
+    patch_status(reconciler, config, &status).await
+}
+
+/// Merge-patch the status subresource, treating a deleted resource as success
+async fn patch_status(
+    reconciler: &Reconciler,
+    config: &SecretManagerConfig,
+    status: &SecretManagerConfigStatus,
+) -> Result<()> {
+    let api: kube::Api<SecretManagerConfig> = kube::Api::namespaced(
+        reconciler.client.clone(),
+        config.metadata.namespace.as_deref().unwrap_or("default"),
+    );
+
     let patch = serde_json::json!({
         "status": status
     });
@@ -116,3 +255,25 @@ pub async fn update_status(
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_failure_description_lists_services_separately() {
+        let failed_secrets = [FailedSecret::new("token", "access denied")];
+        assert_eq!(
+            partial_failure_description(2, &failed_secrets, &[]),
+            "Synced 2 secrets, 1 failed: token"
+        );
+        assert_eq!(
+            partial_failure_description(
+                2,
+                &failed_secrets,
+                &[FailedService::new("billing", "invalid env file")]
+            ),
+            "Synced 2 secrets, 1 failed: token; services failed: billing"
+        );
+    }
+}
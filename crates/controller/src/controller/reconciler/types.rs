@@ -3,6 +3,16 @@
 //! Core types for the reconciler.
 
 use crate::controller::backoff::FibonacciBackoff;
+use crate::controller::reconciler::artifact::cache::ArtifactCache;
+use crate::controller::reconciler::artifact::download::ExtractionLimits;
+use crate::controller::reconciler::concurrency::{ProjectQueues, ProviderLimiter};
+use crate::controller::reconciler::delta::SyncedArtifacts;
+use crate::controller::reconciler::not_found_cache::NotFoundCache;
+use crate::controller::reconciler::rate_limit::ResourceRateLimiters;
+use crate::controller::reconciler::reconcile::ProviderFactory;
+use crate::controller::reconciler::sops::RecipientChecks;
+use crate::observability::audit::AuditLog;
+use crate::provider::azure::VaultPacers;
 use anyhow::Result;
 use kube::Client;
 use std::collections::HashMap;
@@ -52,6 +62,8 @@ impl TriggerSource {
 pub struct BackoffState {
     pub backoff: FibonacciBackoff,
     pub error_count: u32,
+    /// When the next retry is scheduled (set by error_policy)
+    pub next_retry_time: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl BackoffState {
@@ -59,6 +71,7 @@ impl BackoffState {
         Self {
             backoff: FibonacciBackoff::new(1, 10), // 1 minute min, 10 minutes max (converted to seconds internally)
             error_count: 0,
+            next_retry_time: None,
         }
     }
 
@@ -68,6 +81,7 @@ impl BackoffState {
 
     pub fn reset(&mut self) {
         self.error_count = 0;
+        self.next_retry_time = None;
         self.backoff.reset();
     }
 }
@@ -77,8 +91,9 @@ pub struct Reconciler {
     pub client: Client,
     // Note: secret_manager is created per-reconciliation to support per-resource auth config
     // In the future, we might want to cache clients per auth config
-    // SOPS private key is wrapped in Arc<AsyncMutex> to allow hot-reloading when secret changes
-    pub sops_private_key: Arc<AsyncMutex<Option<String>>>,
+    // SOPS private keys (newest first) are wrapped in Arc<AsyncMutex> to allow hot-reloading
+    // when key secrets change; older keys stay in the ring as a fallback during rotation
+    pub sops_key_ring: Arc<AsyncMutex<crate::controller::reconciler::sops::SopsKeyRing>>,
     // SOPS capability bootstrap flag - tracks if SOPS is configured globally (controller namespace)
     // Set to true once key is successfully loaded, updated by watch when key changes
     // This separates "system readiness" from "per-file decryption" concerns
@@ -90,13 +105,39 @@ pub struct Reconciler {
     // Ensures only one git operation (clone/fetch) per resource at a time
     // Uses AsyncMutex to serialize git operations without blocking the entire controller
     pub git_operation_locks: Arc<Mutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
+    // Per-provider concurrency limit shared by all reconciliations
+    // Bounds concurrent calls into each cloud provider's API
+    pub provider_limiter: Arc<ProviderLimiter>,
+    // Per-cloud-project work queues shared by all reconciliations (unbounded by default)
+    // Resources writing to the same project take turns; other projects proceed in parallel
+    pub project_queues: Arc<ProjectQueues>,
+    // Audit log of provider mutations shared by all reconciliations (disabled by default)
+    pub audit_log: Arc<AuditLog>,
+    // Provider API token buckets per resource (identified by namespace/name)
+    // Persist across reconciliations so requeues don't reset a resource's budget
+    pub rate_limiters: Arc<ResourceRateLimiters>,
+    // Azure Key Vault request pacing per vault URL, shared by every resource using the vault
+    pub vault_pacers: Arc<VaultPacers>,
+    // Extracted FluxCD artifacts shared by all resources, addressed by artifact digest
+    pub artifact_cache: Arc<ArtifactCache>,
+    // Size and entry-count limits applied when extracting artifact tarballs
+    pub artifact_limits: ExtractionLimits,
+    // Artifact each resource was last synced from, for delta reconciles (identified by namespace/name)
+    pub synced_artifacts: Arc<SyncedArtifacts>,
+    // Secrets each resource's provider reported as not found at its current revision
+    // (ProviderNotFoundCache gate, identified by namespace/name)
+    pub not_found_cache: Arc<NotFoundCache>,
+    // `.sops.yaml` recipient mismatches found by the running sync (identified by namespace/name)
+    pub recipient_checks: Arc<RecipientChecks>,
+    // Supplies provider clients instead of the cloud clients (in-memory fakes for tests)
+    pub provider_factory: Option<Arc<dyn ProviderFactory>>,
 }
 
 impl std::fmt::Debug for Reconciler {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // Note: We can't lock the mutex in Debug, so we just indicate if it's set
         f.debug_struct("Reconciler")
-            .field("sops_private_key", &"***")
+            .field("sops_key_ring", &"***")
             .finish_non_exhaustive()
     }
 }
@@ -106,30 +147,95 @@ impl Reconciler {
         // Provider is created per-reconciliation based on provider config
         // Per-resource auth config is handled in reconcile()
 
-        // Load SOPS private key from Kubernetes secret
-        let sops_private_key =
-            crate::controller::reconciler::sops::load_sops_private_key(&client).await?;
+        // Load SOPS private keys from Kubernetes secrets in the controller namespace
+        let controller_namespace = crate::controller::reconciler::sops::controller_namespace();
+        let sops_key_ring =
+            crate::controller::reconciler::sops::load_sops_key_ring(&client, &controller_namespace)
+                .await?;
 
-        // Set capability flag based on whether key was loaded
+        // Set capability flag based on whether any key was loaded
         // This proves "SOPS is configured and ready" at bootstrap time
-        let sops_capability_ready = Arc::new(AtomicBool::new(sops_private_key.is_some()));
+        let sops_capability_ready = Arc::new(AtomicBool::new(!sops_key_ring.is_empty()));
 
-        if sops_private_key.is_some() {
-            tracing::info!("✅ SOPS capability ready - GPG key loaded from controller namespace");
+        if !sops_key_ring.is_empty() {
+            tracing::info!(
+                "✅ SOPS capability ready - {} GPG key(s) loaded from controller namespace",
+                sops_key_ring.len()
+            );
         } else {
-            tracing::warn!("⚠️  SOPS capability not ready - no key in controller namespace");
+            tracing::warn!(
+                "⚠️  SOPS capability not ready - no key in controller namespace '{}'",
+                controller_namespace
+            );
             tracing::warn!("   SOPS decryption will be disabled until key is added");
         }
 
         Ok(Self {
             client,
-            sops_private_key: Arc::new(AsyncMutex::new(sops_private_key)),
+            sops_key_ring: Arc::new(AsyncMutex::new(sops_key_ring)),
             sops_capability_ready,
             backoff_states: Arc::new(Mutex::new(HashMap::new())),
             git_operation_locks: Arc::new(Mutex::new(HashMap::new())),
+            provider_limiter: Arc::new(ProviderLimiter::default()),
+            project_queues: Arc::new(ProjectQueues::default()),
+            audit_log: Arc::new(AuditLog::disabled()),
+            rate_limiters: Arc::new(ResourceRateLimiters::default()),
+            vault_pacers: Arc::new(VaultPacers::default()),
+            artifact_cache: Arc::new(ArtifactCache::default()),
+            artifact_limits: ExtractionLimits::default(),
+            synced_artifacts: Arc::new(SyncedArtifacts::default()),
+            not_found_cache: Arc::new(NotFoundCache::default()),
+            recipient_checks: Arc::new(RecipientChecks::default()),
+            provider_factory: None,
         })
     }
 
+    /// Set the maximum number of concurrent reconciliations per provider (0 = unbounded)
+    #[must_use]
+    pub fn with_provider_concurrency(mut self, limit: usize) -> Self {
+        self.provider_limiter = Arc::new(ProviderLimiter::new(limit));
+        self
+    }
+
+    /// Set the maximum number of concurrent reconciliations per cloud project (0 = unbounded)
+    #[must_use]
+    pub fn with_project_concurrency(mut self, limit: usize) -> Self {
+        self.project_queues = Arc::new(ProjectQueues::new(limit));
+        self
+    }
+
+    /// Set how many extracted artifacts the artifact cache keeps before evicting (LRU)
+    #[must_use]
+    pub fn with_artifact_cache_max_entries(mut self, max_entries: usize) -> Self {
+        self.artifact_cache = Arc::new(ArtifactCache::new(
+            std::path::PathBuf::from(crate::controller::reconciler::utils::SMC_BASE_PATH)
+                .join("artifact-cache"),
+            max_entries,
+        ));
+        self
+    }
+
+    /// Set the size and entry-count limits for artifact extraction
+    #[must_use]
+    pub fn with_artifact_limits(mut self, limits: ExtractionLimits) -> Self {
+        self.artifact_limits = limits;
+        self
+    }
+
+    /// Set the audit log that records provider mutations
+    #[must_use]
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Arc::new(audit_log);
+        self
+    }
+
+    /// Use `factory` for provider clients instead of building cloud clients from the spec
+    #[must_use]
+    pub fn with_provider_factory(mut self, factory: Arc<dyn ProviderFactory>) -> Self {
+        self.provider_factory = Some(factory);
+        self
+    }
+
     /// Get or create a git operation lock for a resource
     /// This ensures only one git operation (clone/fetch) per resource at a time
     /// Returns a guard that will be released when dropped
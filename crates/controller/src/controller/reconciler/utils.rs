@@ -105,3 +105,224 @@ fn sanitize_secret_name_impl(name: &str) -> String {
     // Remove leading and trailing dashes
     result.trim_matches('-').to_string()
 }
+
+/// Variables supported in `secrets.nameTemplate`
+/// - `service`: secret prefix, or the service directory name when no prefix is set
+/// - `env`: environment/profile name
+/// - `key`: secret key from the source file
+/// - `suffix`: secret suffix (empty if not set)
+/// - `name` / `namespace`: SecretManagerConfig metadata
+/// - `repo`: Git source name (`sourceRef.name`)
+pub const NAME_TEMPLATE_VARIABLES: &[&str] = &[
+    "service",
+    "env",
+    "key",
+    "suffix",
+    "name",
+    "namespace",
+    "repo",
+];
+
+/// Split a name template into literal and `{variable}` segments
+/// Returns (is_variable, text) pairs in order
+/// # Errors
+/// Returns an error for unbalanced or nested braces and empty variable names
+pub fn parse_name_template(template: &str) -> anyhow::Result<Vec<(bool, &str)>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(open) = rest.find(['{', '}']) {
+        if rest[open..].starts_with('}') {
+            return Err(anyhow::anyhow!("unmatched '}}' in name template"));
+        }
+        if open > 0 {
+            segments.push((false, &rest[..open]));
+        }
+        let after = &rest[open + 1..];
+        let close = after
+            .find(['{', '}'])
+            .filter(|&i| after[i..].starts_with('}'))
+            .ok_or_else(|| anyhow::anyhow!("unclosed '{{' in name template"))?;
+        let variable = &after[..close];
+        if variable.is_empty() {
+            return Err(anyhow::anyhow!("empty variable '{{}}' in name template"));
+        }
+        segments.push((true, variable));
+        rest = &after[close + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push((false, rest));
+    }
+
+    Ok(segments)
+}
+
+/// Render a name template, substituting `{variable}` with values from `variables`
+/// Unknown variables render as empty strings (templates are validated at admission)
+/// The result is sanitized with the same rules as `construct_secret_name`
+#[must_use]
+pub fn render_name_template(template: &str, variables: &[(&str, &str)]) -> String {
+    let Ok(segments) = parse_name_template(template) else {
+        return sanitize_secret_name(template);
+    };
+
+    let rendered: String = segments
+        .into_iter()
+        .map(|(is_variable, text)| {
+            if is_variable {
+                variables
+                    .iter()
+                    .find(|(name, _)| *name == text)
+                    .map_or("", |(_, value)| *value)
+            } else {
+                text
+            }
+        })
+        .collect();
+
+    sanitize_secret_name(&rendered)
+}
+
+/// Resolve the cloud secret name for a key
+/// Uses `secrets.nameTemplate` when set, otherwise the `{prefix}-{key}-{suffix}` convention,
+/// then applies the provider's naming rules (see `sanitize_for_provider`)
+#[must_use]
+pub fn resolve_secret_name(
+    config: &crate::crd::SecretManagerConfig,
+    secret_prefix: &str,
+    key: &str,
+) -> String {
+    let secrets = &config.spec.secrets;
+    let name = match secrets.name_template.as_deref().filter(|t| !t.is_empty()) {
+        Some(template) => {
+            let variables = [
+                ("service", secret_prefix),
+                ("env", secrets.environment.as_str()),
+                ("key", key),
+                ("suffix", secrets.suffix.as_deref().unwrap_or_default()),
+                ("name", config.metadata.name.as_deref().unwrap_or_default()),
+                (
+                    "namespace",
+                    config.metadata.namespace.as_deref().unwrap_or_default(),
+                ),
+                ("repo", config.spec.source_ref.name.as_str()),
+            ];
+            render_name_template(template, &variables)
+        }
+        None => construct_secret_name(Some(secret_prefix), key, secrets.suffix.as_deref()),
+    };
+    sanitize_for_provider(
+        &name,
+        &config.spec.provider,
+        sanitization_replacement(config),
+    )
+}
+
+/// Replacement character configured in `secrets.sanitization.replacement`
+fn sanitization_replacement(config: &crate::crd::SecretManagerConfig) -> Option<char> {
+    config
+        .spec
+        .secrets
+        .sanitization
+        .as_ref()
+        .and_then(|s| s.replacement.as_deref())
+        .and_then(|r| r.chars().next())
+}
+
+/// Apply provider naming rules to an already-constructed secret name
+/// Disallowed characters are replaced, repeated dashes collapsed, and the name truncated
+///
+/// - GCP Secret Manager: `[A-Za-z0-9_-]`, max 255 characters (default replacement `_`)
+/// - AWS Secrets Manager: `[A-Za-z0-9/_+=.@-]`, max 512 characters (default replacement `_`)
+/// - Azure Key Vault: `[A-Za-z0-9-]`, max 127 characters (default replacement `-`)
+///
+/// `replacement` overrides the default when it is itself allowed by the provider
+#[must_use]
+pub fn sanitize_for_provider(
+    name: &str,
+    provider: &crate::crd::ProviderConfig,
+    replacement: Option<char>,
+) -> String {
+    use crate::crd::ProviderConfig;
+
+    let (allowed, max_len, default_replacement): (fn(char) -> bool, usize, char) = match provider {
+        ProviderConfig::Gcp(_) => (
+            |c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'),
+            255,
+            '_',
+        ),
+        ProviderConfig::Aws(_) => (
+            |c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '+' | '=' | '.' | '@' | '-'),
+            512,
+            '_',
+        ),
+        ProviderConfig::Azure(_) => (|c| c.is_ascii_alphanumeric() || c == '-', 127, '-'),
+    };
+    let replacement = replacement
+        .filter(|r| allowed(*r))
+        .unwrap_or(default_replacement);
+
+    let mut result = String::with_capacity(name.len());
+    for c in name.chars() {
+        let c = if allowed(c) { c } else { replacement };
+        if c == '-' && result.ends_with('-') {
+            continue;
+        }
+        result.push(c);
+    }
+
+    let trimmed = result.trim_matches('-');
+    trimmed
+        .chars()
+        .take(max_len)
+        .collect::<String>()
+        .trim_end_matches('-')
+        .to_string()
+}
+
+/// Original key to record in status when provider sanitization rewrote it
+/// Returns None when the key is already valid for the provider
+#[must_use]
+pub fn sanitized_source_key(config: &crate::crd::SecretManagerConfig, key: &str) -> Option<String> {
+    let sanitized = sanitize_for_provider(
+        &sanitize_secret_name(key),
+        &config.spec.provider,
+        sanitization_replacement(config),
+    );
+    (sanitized != key).then(|| key.to_string())
+}
+
+/// Detect keys that resolve to the same secret name after sanitization
+/// (e.g. `db.password` and `db_password` both become `db_password`)
+/// # Errors
+/// Returns an error listing every colliding key pair
+pub fn check_name_collisions<'a>(
+    config: &crate::crd::SecretManagerConfig,
+    secret_prefix: &str,
+    keys: impl IntoIterator<Item = &'a str>,
+) -> anyhow::Result<()> {
+    let mut seen: std::collections::HashMap<String, &str> = std::collections::HashMap::new();
+    let mut collisions = Vec::new();
+
+    for key in keys {
+        let name = resolve_secret_name(config, secret_prefix, key);
+        match seen.get(name.as_str()) {
+            Some(existing) if *existing != key => {
+                collisions.push(format!("'{existing}' and '{key}' both map to '{name}'"));
+            }
+            Some(_) => {}
+            None => {
+                seen.insert(name, key);
+            }
+        }
+    }
+
+    if collisions.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Secret name collision after sanitization: {}",
+            collisions.join("; ")
+        ))
+    }
+}
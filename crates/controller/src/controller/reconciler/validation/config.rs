@@ -2,98 +2,284 @@
 //!
 //! Main orchestration for validating SecretManagerConfig resources.
 
-use crate::crd::SecretManagerConfig;
+use crate::crd::{SecretManagerConfig, SecretsConfig};
 use anyhow::Result;
 
-use super::configs::validate_configs_config;
+use super::configs::configs_errors;
 use super::kubernetes::{
     validate_kubernetes_label, validate_kubernetes_name, validate_kubernetes_namespace,
     validate_source_ref_kind,
 };
-use super::paths::validate_path;
-use super::provider::validate_provider_config;
-use super::secrets::validate_secret_name_component;
+use super::paths::{validate_git_revision, validate_git_url, validate_path};
+use super::provider::provider_errors;
+use super::secrets::{
+    validate_name_template, validate_secret_expiration, validate_secret_name_component,
+    validate_secret_policy, validate_secret_route,
+};
+use super::spec::ValidationError;
 
 /// Validate SecretManagerConfig resource
-/// Performs comprehensive validation of all CRD fields
+/// Performs comprehensive validation of all CRD fields; the error lists every problem found
 pub fn validate_secret_manager_config(config: &SecretManagerConfig) -> Result<()> {
-    // Validate sourceRef.kind
-    if config.spec.source_ref.kind.is_empty() {
-        return Err(anyhow::anyhow!("sourceRef.kind is required but is empty"));
+    let errors = config_errors(config);
+    if errors.is_empty() {
+        return Ok(());
     }
-    if let Err(e) = validate_source_ref_kind(&config.spec.source_ref.kind) {
-        return Err(anyhow::anyhow!(
-            "Invalid sourceRef.kind '{}': {}",
-            config.spec.source_ref.kind,
-            e
-        ));
+    Err(anyhow::anyhow!(
+        "{}",
+        errors
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ")
+    ))
+}
+
+/// Every problem in the CRD fields of `config`
+/// Each field is checked independently, so one bad field doesn't hide the others
+#[must_use]
+pub fn config_errors(config: &SecretManagerConfig) -> Vec<ValidationError> {
+    let mut errors = vec![];
+    let mut error =
+        |field: &str, message: String| errors.push(ValidationError::new(field, message));
+    let source_ref = &config.spec.source_ref;
+
+    // Validate sourceRef.kind
+    if source_ref.kind.is_empty() {
+        error(
+            "sourceRef.kind",
+            "sourceRef.kind is required but is empty".to_string(),
+        );
+    } else if let Err(e) = validate_source_ref_kind(&source_ref.kind) {
+        error(
+            "sourceRef.kind",
+            format!("Invalid sourceRef.kind '{}': {}", source_ref.kind, e),
+        );
     }
 
     // Validate sourceRef.name
-    if config.spec.source_ref.name.is_empty() {
-        return Err(anyhow::anyhow!("sourceRef.name is required but is empty"));
-    }
-    if let Err(e) = validate_kubernetes_name(&config.spec.source_ref.name, "sourceRef.name") {
-        return Err(anyhow::anyhow!(
-            "Invalid sourceRef.name '{}': {}",
-            config.spec.source_ref.name,
-            e
-        ));
+    if source_ref.name.is_empty() {
+        error(
+            "sourceRef.name",
+            "sourceRef.name is required but is empty".to_string(),
+        );
+    } else if let Err(e) = validate_kubernetes_name(&source_ref.name, "sourceRef.name") {
+        error(
+            "sourceRef.name",
+            format!("Invalid sourceRef.name '{}': {}", source_ref.name, e),
+        );
     }
 
     // Validate sourceRef.namespace
-    if config.spec.source_ref.namespace.is_empty() {
-        return Err(anyhow::anyhow!(
-            "sourceRef.namespace is required but is empty"
-        ));
-    }
-    if let Err(e) = validate_kubernetes_namespace(&config.spec.source_ref.namespace) {
-        return Err(anyhow::anyhow!(
-            "Invalid sourceRef.namespace '{}': {}",
-            config.spec.source_ref.namespace,
-            e
-        ));
-    }
-
-    // Validate secrets.environment
-    if config.spec.secrets.environment.is_empty() {
-        return Err(anyhow::anyhow!(
-            "secrets.environment is required but is empty"
-        ));
-    }
-    if let Err(e) =
+    if source_ref.namespace.is_empty() {
+        error(
+            "sourceRef.namespace",
+            "sourceRef.namespace is required but is empty".to_string(),
+        );
+    } else if let Err(e) = validate_kubernetes_namespace(&source_ref.namespace) {
+        error(
+            "sourceRef.namespace",
+            format!(
+                "Invalid sourceRef.namespace '{}': {}",
+                source_ref.namespace, e
+            ),
+        );
+    }
+
+    // Validate sourceRef.url / sourceRef.revision (GitDirect only)
+    if source_ref.kind == "GitDirect" {
+        match source_ref.url.as_deref() {
+            None => error(
+                "sourceRef.url",
+                "sourceRef.url is required when sourceRef.kind is GitDirect".to_string(),
+            ),
+            Some(url) => {
+                if let Err(e) = validate_git_url(url, "sourceRef.url") {
+                    error("sourceRef.url", e.to_string());
+                }
+            }
+        }
+        if let Some(ref revision) = source_ref.revision {
+            if let Err(e) = validate_git_revision(revision, "sourceRef.revision") {
+                error("sourceRef.revision", e.to_string());
+            }
+        }
+    } else if source_ref.url.is_some() || source_ref.revision.is_some() {
+        error(
+            if source_ref.url.is_some() {
+                "sourceRef.url"
+            } else {
+                "sourceRef.revision"
+            },
+            format!(
+                "sourceRef.url and sourceRef.revision are only used when sourceRef.kind is GitDirect, not {}",
+                source_ref.kind
+            ),
+        );
+    }
+
+    // Validate sourceRef.gitCredentials
+    if let Some(ref git_credentials) = source_ref.git_credentials {
+        if let Err(e) =
+            validate_kubernetes_name(&git_credentials.name, "sourceRef.gitCredentials.name")
+        {
+            error(
+                "sourceRef.gitCredentials.name",
+                format!(
+                    "Invalid sourceRef.gitCredentials.name '{}': {}",
+                    git_credentials.name, e
+                ),
+            );
+        }
+        if let Some(ref namespace) = git_credentials.namespace {
+            if let Err(e) = validate_kubernetes_namespace(namespace) {
+                error(
+                    "sourceRef.gitCredentials.namespace",
+                    format!("Invalid sourceRef.gitCredentials.namespace '{namespace}': {e}"),
+                );
+            }
+        }
+    }
+
+    // Validate Secret sources: only Secrets in the resource's own namespace can be mirrored,
+    // so namespace tenants can't read each other's Secrets through the controller
+    if source_ref.kind == "Secret" {
+        let namespace = config.metadata.namespace.as_deref().unwrap_or("default");
+        if source_ref.namespace != namespace {
+            error(
+                "sourceRef.namespace",
+                format!(
+                    "sourceRef.namespace must be the resource's namespace '{namespace}' when sourceRef.kind is Secret, not '{}'",
+                    source_ref.namespace
+                ),
+            );
+        }
+        if source_ref.git_credentials.is_some() {
+            error(
+                "sourceRef.gitCredentials",
+                "sourceRef.gitCredentials is not used when sourceRef.kind is Secret".to_string(),
+            );
+        }
+        if config.spec.secrets.kustomize_path.is_some() {
+            error(
+                "secrets.kustomizePath",
+                "secrets.kustomizePath is not used when sourceRef.kind is Secret".to_string(),
+            );
+        }
+    }
+
+    // Validate secrets.environment / secrets.environments
+    // At least one must be set; `environments` takes precedence when non-empty
+    let has_environments = config
+        .spec
+        .secrets
+        .environments
+        .as_ref()
+        .is_some_and(|envs| !envs.is_empty());
+    if has_environments {
+        for (index, environment) in config
+            .spec
+            .secrets
+            .environments
+            .iter()
+            .flatten()
+            .enumerate()
+        {
+            let field = format!("secrets.environments[{index}]");
+            if environment.is_empty() {
+                error(
+                    &field,
+                    "secrets.environments entries must not be empty".to_string(),
+                );
+                continue;
+            }
+            // Glob entries are resolved against discovered profiles at reconcile time
+            let is_glob = environment.contains('*') || environment.contains('?');
+            if !is_glob {
+                if let Err(e) = validate_kubernetes_label(environment, "secrets.environments") {
+                    error(
+                        &field,
+                        format!("Invalid secrets.environments entry '{environment}': {e}"),
+                    );
+                }
+            }
+        }
+    } else if config.spec.secrets.environment.is_empty() {
+        error(
+            "secrets.environment",
+            "secrets.environment is required but is empty".to_string(),
+        );
+    } else if let Err(e) =
         validate_kubernetes_label(&config.spec.secrets.environment, "secrets.environment")
     {
-        return Err(anyhow::anyhow!(
-            "Invalid secrets.environment '{}': {}",
-            config.spec.secrets.environment,
-            e
-        ));
+        error(
+            "secrets.environment",
+            format!(
+                "Invalid secrets.environment '{}': {}",
+                config.spec.secrets.environment, e
+            ),
+        );
     }
 
     // Validate optional secrets fields
+    // The `{env}` placeholder is rendered per environment, so validate with a sample value
     if let Some(ref prefix) = config.spec.secrets.prefix {
         if !prefix.is_empty() {
-            if let Err(e) = validate_secret_name_component(prefix, "secrets.prefix") {
-                return Err(anyhow::anyhow!("Invalid secrets.prefix '{prefix}': {e}"));
+            let rendered = prefix.replace(SecretsConfig::ENV_PLACEHOLDER, "env");
+            if let Err(e) = validate_secret_name_component(&rendered, "secrets.prefix") {
+                error(
+                    "secrets.prefix",
+                    format!("Invalid secrets.prefix '{prefix}': {e}"),
+                );
             }
         }
     }
 
     if let Some(ref suffix) = config.spec.secrets.suffix {
         if !suffix.is_empty() {
-            if let Err(e) = validate_secret_name_component(suffix, "secrets.suffix") {
-                return Err(anyhow::anyhow!("Invalid secrets.suffix '{suffix}': {e}"));
+            let rendered = suffix.replace(SecretsConfig::ENV_PLACEHOLDER, "env");
+            if let Err(e) = validate_secret_name_component(&rendered, "secrets.suffix") {
+                error(
+                    "secrets.suffix",
+                    format!("Invalid secrets.suffix '{suffix}': {e}"),
+                );
             }
         }
     }
 
+    if let Some(replacement) = config
+        .spec
+        .secrets
+        .sanitization
+        .as_ref()
+        .and_then(|s| s.replacement.as_deref())
+    {
+        if !matches!(replacement, "-" | "_") {
+            error(
+                "secrets.sanitization.replacement",
+                format!(
+                    "Invalid secrets.sanitization.replacement '{replacement}': must be \"-\" or \"_\""
+                ),
+            );
+        }
+    }
+
+    if let Some(ref name_template) = config.spec.secrets.name_template {
+        if let Err(e) = validate_name_template(name_template) {
+            error(
+                "secrets.nameTemplate",
+                format!("Invalid secrets.nameTemplate '{name_template}': {e}"),
+            );
+        }
+    }
+
     if let Some(ref base_path) = config.spec.secrets.base_path {
         if !base_path.is_empty() {
             if let Err(e) = validate_path(base_path, "secrets.basePath") {
-                return Err(anyhow::anyhow!(
-                    "Invalid secrets.basePath '{base_path}': {e}"
-                ));
+                error(
+                    "secrets.basePath",
+                    format!("Invalid secrets.basePath '{base_path}': {e}"),
+                );
             }
         }
     }
@@ -101,27 +287,73 @@ pub fn validate_secret_manager_config(config: &SecretManagerConfig) -> Result<()
     if let Some(ref kustomize_path) = config.spec.secrets.kustomize_path {
         if !kustomize_path.is_empty() {
             if let Err(e) = validate_path(kustomize_path, "secrets.kustomizePath") {
-                return Err(anyhow::anyhow!(
-                    "Invalid secrets.kustomizePath '{kustomize_path}': {e}"
-                ));
+                error(
+                    "secrets.kustomizePath",
+                    format!("Invalid secrets.kustomizePath '{kustomize_path}': {e}"),
+                );
+            }
+        }
+    }
+
+    if let Some(ref binary_files) = config.spec.secrets.binary_files {
+        for (index, pattern) in binary_files.iter().enumerate() {
+            if pattern.trim().is_empty() || pattern.contains('/') {
+                error(
+                    &format!("secrets.binaryFiles[{index}]"),
+                    format!(
+                        "Invalid secrets.binaryFiles entry '{pattern}': must be a non-empty file name glob (no '/')"
+                    ),
+                );
             }
         }
     }
 
     // Validate provider configuration
-    if let Err(e) = validate_provider_config(&config.spec.provider) {
-        return Err(anyhow::anyhow!("Invalid provider configuration: {e}"));
+    for e in provider_errors(&config.spec.provider) {
+        error(
+            &e.field,
+            format!("Invalid provider configuration: {}", e.message),
+        );
+    }
+
+    if let Some(ref routes) = config.spec.secrets.routes {
+        for (index, route) in routes.iter().enumerate() {
+            if let Err(e) = validate_secret_route(config, route) {
+                error(
+                    &format!("secrets.routes[{index}]"),
+                    format!("Invalid secrets.routes entry '{}': {e}", route.key_pattern),
+                );
+            }
+        }
+    }
+
+    if let Some(ref policy) = config.spec.secrets.policy {
+        if let Err(e) = validate_secret_policy(policy) {
+            error("secrets.policy", format!("Invalid secrets.policy: {e}"));
+        }
+    }
+
+    if let Some(ref expiration) = config.spec.secrets.expiration {
+        if let Err(e) = validate_secret_expiration(expiration) {
+            error(
+                "secrets.expiration",
+                format!("Invalid secrets.expiration: {e}"),
+            );
+        }
     }
 
     // Validate configs configuration if present
     if let Some(ref configs) = config.spec.configs {
-        if let Err(e) = validate_configs_config(configs) {
-            return Err(anyhow::anyhow!("Invalid configs configuration: {e}"));
+        for e in configs_errors(configs) {
+            error(
+                &e.field,
+                format!("Invalid configs configuration: {}", e.message),
+            );
         }
     }
 
     // Boolean fields are validated by serde, but we ensure they're not None
     // diffDiscovery and triggerUpdate have defaults, so they're always present
 
-    Ok(())
+    errors
 }
@@ -2,30 +2,37 @@
 //!
 //! Validates configs configuration (Parameter Store, App Configuration).
 
-use crate::crd::ConfigsConfig;
+use crate::crd::{ConfigStoreType, ConfigsConfig, KubeDuration, ParameterPolicies};
 use anyhow::Result;
+use std::time::Duration;
 
 use super::paths::{validate_aws_parameter_path, validate_url};
+use super::spec::ValidationError;
 
-/// Validate configs configuration
-pub fn validate_configs_config(configs: &ConfigsConfig) -> Result<()> {
-    // Validate store type if present
-    // ConfigStoreType is an enum, so it's already validated by serde
-    // No additional validation needed - enum variants are: SecretManager, ParameterManager
-    if let Some(ref _store) = configs.store {
-        // Enum is already validated by serde deserialization
-        // ConfigStoreType::SecretManager or ConfigStoreType::ParameterManager are the only valid values
+/// Every problem in the configs configuration
+#[must_use]
+pub fn configs_errors(configs: &ConfigsConfig) -> Vec<ValidationError> {
+    let mut errors = vec![];
+    let mut error =
+        |field: &str, message: String| errors.push(ValidationError::new(field, message));
+    // Store type is an enum (SecretManager, ParameterManager), already validated by serde
+
+    // Secret references are resolved by Parameter Manager when a version is rendered
+    if configs.secret_references && configs.store != Some(ConfigStoreType::ParameterManager) {
+        error(
+            "configs.secretReferences",
+            "configs.secretReferences requires configs.store: ParameterManager".to_string(),
+        );
     }
 
     // Validate appConfigEndpoint if present
     if let Some(endpoint) = &configs.app_config_endpoint {
         if !endpoint.is_empty() {
             if let Err(e) = validate_url(endpoint, "configs.appConfigEndpoint") {
-                return Err(anyhow::anyhow!(
-                    "Invalid configs.appConfigEndpoint '{}': {}",
-                    endpoint,
-                    e
-                ));
+                error(
+                    "configs.appConfigEndpoint",
+                    format!("Invalid configs.appConfigEndpoint '{endpoint}': {e}"),
+                );
             }
         }
     }
@@ -34,14 +41,76 @@ pub fn validate_configs_config(configs: &ConfigsConfig) -> Result<()> {
     if let Some(path) = &configs.parameter_path {
         if !path.is_empty() {
             if let Err(e) = validate_aws_parameter_path(path, "configs.parameterPath") {
-                return Err(anyhow::anyhow!(
-                    "Invalid configs.parameterPath '{}': {}",
-                    path,
-                    e
-                ));
+                error(
+                    "configs.parameterPath",
+                    format!("Invalid configs.parameterPath '{path}': {e}"),
+                );
             }
         }
     }
 
-    Ok(())
+    if let Some(policies) = configs.aws.as_ref().and_then(|aws| aws.policies.as_ref()) {
+        if let Err(e) = validate_parameter_policies(policies) {
+            error("configs.aws.policies", e.to_string());
+        }
+    }
+
+    // An empty prefix would turn every property into a feature flag
+    if let Some(prefix) = configs
+        .azure
+        .as_ref()
+        .and_then(|azure| azure.feature_flag_prefix.as_deref())
+    {
+        if prefix.trim().is_empty() {
+            error(
+                "configs.azure.featureFlagPrefix",
+                "configs.azure.featureFlagPrefix must not be empty".to_string(),
+            );
+        }
+    }
+
+    errors
+}
+
+/// Validate Parameter Store policies
+/// Policies are expressed in whole hours or days, and a notification before expiration
+/// needs an expiration to count from
+fn validate_parameter_policies(policies: &ParameterPolicies) -> Result<()> {
+    let expiration = policy_duration(
+        policies.expiration.as_ref(),
+        "configs.aws.policies.expiration",
+    )?;
+    let notification = policy_duration(
+        policies.expiration_notification.as_ref(),
+        "configs.aws.policies.expirationNotification",
+    )?;
+    policy_duration(
+        policies.no_change_notification.as_ref(),
+        "configs.aws.policies.noChangeNotification",
+    )?;
+
+    match (expiration, notification) {
+        (None, Some(_)) => Err(anyhow::anyhow!(
+            "configs.aws.policies.expirationNotification requires configs.aws.policies.expiration"
+        )),
+        (Some(expiration), Some(notification)) if notification >= expiration => {
+            Err(anyhow::anyhow!(
+                "configs.aws.policies.expirationNotification must be shorter than configs.aws.policies.expiration"
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn policy_duration(value: Option<&KubeDuration>, field_name: &str) -> Result<Option<Duration>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let duration = value.to_duration_at_least(field_name, 3_600)?;
+    if duration.as_secs() % 3_600 != 0 {
+        return Err(anyhow::anyhow!(
+            "{field_name} '{value}' must be a whole number of hours or days"
+        ));
+    }
+    Ok(Some(duration))
 }
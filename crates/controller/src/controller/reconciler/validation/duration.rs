@@ -2,8 +2,8 @@
 //!
 //! Handles parsing and validating Kubernetes duration strings.
 
+use crate::crd::patterns::{DURATION_PATTERN, matches};
 use anyhow::Result;
-use regex::Regex;
 use std::time::Duration;
 
 /// Parse Kubernetes duration string into std::time::Duration
@@ -16,45 +16,17 @@ pub fn parse_kubernetes_duration(duration_str: &str) -> Result<Duration> {
         return Err(anyhow::anyhow!("Duration string cannot be empty"));
     }
 
-    // Regex pattern for Kubernetes duration format
-    // Matches: <number><unit> where:
-    //   - number: one or more digits
-    //   - unit: s, m, h, d (case insensitive)
-    let duration_regex = Regex::new(r"^(?P<number>\d+)(?P<unit>[smhd])$")
-        .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
+    // Same format the CRD schema enforces: <number><unit>, unit s, m, h or d (case insensitive)
+    if !matches(DURATION_PATTERN, duration_trimmed) {
+        return Err(anyhow::anyhow!(
+            "Invalid duration format '{}'. Expected format: <number><unit> (e.g., '1m', '5m', '1h')",
+            duration_trimmed
+        ));
+    }
 
-    // Match against trimmed, lowercase version
+    // The pattern guarantees an ASCII unit as the last character
     let interval_lower = duration_trimmed.to_lowercase();
-
-    let captures = duration_regex
-        .captures(&interval_lower)
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "Invalid duration format '{}'. Expected format: <number><unit> (e.g., '1m', '5m', '1h')",
-                duration_trimmed
-            )
-        })?;
-
-    // Extract number and unit from regex captures
-    let number_str = captures
-        .name("number")
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "Failed to extract number from duration '{}'",
-                duration_trimmed
-            )
-        })?
-        .as_str();
-
-    let unit = captures
-        .name("unit")
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "Failed to extract unit from duration '{}'",
-                duration_trimmed
-            )
-        })?
-        .as_str();
+    let (number_str, unit) = interval_lower.split_at(interval_lower.len() - 1);
 
     // Parse number safely
     let number: u64 = number_str.parse().map_err(|e| {
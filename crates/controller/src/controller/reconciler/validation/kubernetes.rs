@@ -6,13 +6,13 @@ use anyhow::Result;
 use regex::Regex;
 
 /// Validate sourceRef.kind
-/// Must be "GitRepository" or "Application" (case-sensitive)
+/// Must be "GitRepository", "Application", "GitDirect" or "Secret" (case-sensitive)
 pub fn validate_source_ref_kind(kind: &str) -> Result<()> {
     let kind_trimmed = kind.trim();
     match kind_trimmed {
-        "GitRepository" | "Application" => Ok(()),
+        "GitRepository" | "Application" | "GitDirect" | "Secret" => Ok(()),
         _ => Err(anyhow::anyhow!(
-            "Must be 'GitRepository' or 'Application' (case-sensitive), got '{kind_trimmed}'"
+            "Must be 'GitRepository', 'Application', 'GitDirect' or 'Secret' (case-sensitive), got '{kind_trimmed}'"
         )),
     }
 }
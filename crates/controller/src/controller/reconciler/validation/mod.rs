@@ -1,6 +1,8 @@
 //! # Validation
 //!
 //! Validates SecretManagerConfig resources and duration strings.
+//!
+//! [`validate_spec`] is the entry point for tools outside the controller.
 
 mod config;
 mod configs;
@@ -9,6 +11,7 @@ mod kubernetes;
 mod paths;
 mod provider;
 mod secrets;
+mod spec;
 
 pub use config::validate_secret_manager_config;
 pub use duration::{parse_kubernetes_duration, validate_duration_interval};
@@ -17,6 +20,10 @@ pub use kubernetes::{
     validate_source_ref_kind,
 };
 // These validation helpers live in the local paths submodule (not the smc-paths crate)
-pub use paths::{validate_aws_parameter_path, validate_path, validate_url};
+pub use paths::{
+    validate_aws_parameter_path, validate_git_revision, validate_git_url, validate_path,
+    validate_url,
+};
 pub use provider::validate_provider_config;
-pub use secrets::validate_secret_name_component;
+pub use secrets::{validate_name_template, validate_secret_name_component};
+pub use spec::{ValidationError, validate_spec, validation_errors};
@@ -96,3 +96,65 @@ pub fn validate_url(url: &str, field_name: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// Validate a Git repository URL (GitDirect `sourceRef.url`)
+/// Accepts `https://`, `http://` and `ssh://` URLs and scp-like SSH (`git@host:org/repo.git`)
+/// Credentials must come from `gitCredentials`, not the URL, so they never appear in status or logs
+pub fn validate_git_url(url: &str, field_name: &str) -> Result<()> {
+    let url_trimmed = url.trim();
+
+    if url_trimmed.is_empty() {
+        return Err(anyhow::anyhow!("{field_name} cannot be empty"));
+    }
+
+    let url_regex = Regex::new(r"^(https?|ssh)://[^\s/]+/[^\s]+$")
+        .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
+    let scp_regex = Regex::new(r"^[A-Za-z0-9._-]+@[A-Za-z0-9.-]+:[^\s/][^\s]*$")
+        .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
+
+    if let Some((scheme, rest)) = url_trimmed.split_once("://") {
+        if !url_regex.is_match(url_trimmed) {
+            return Err(anyhow::anyhow!(
+                "{field_name} '{url_trimmed}' must be an https://, http:// or ssh:// URL with a repository path"
+            ));
+        }
+        let authority = rest.split('/').next().unwrap_or_default();
+        let has_password = authority
+            .rsplit_once('@')
+            .is_some_and(|(userinfo, _)| userinfo.contains(':'));
+        let has_user = authority.contains('@');
+        if has_password || (scheme != "ssh" && has_user) {
+            return Err(anyhow::anyhow!(
+                "{field_name} must not embed credentials; use sourceRef.gitCredentials instead"
+            ));
+        }
+    } else if !scp_regex.is_match(url_trimmed) {
+        return Err(anyhow::anyhow!(
+            "{field_name} '{url_trimmed}' must be an https://, http:// or ssh:// URL, or user@host:path"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate a Git revision (branch, tag or commit SHA)
+/// Revisions are passed to git as arguments, so option-like values are rejected
+pub fn validate_git_revision(revision: &str, field_name: &str) -> Result<()> {
+    if revision.is_empty() {
+        return Err(anyhow::anyhow!("{field_name} cannot be empty"));
+    }
+    if revision.starts_with('-') {
+        return Err(anyhow::anyhow!(
+            "{field_name} '{revision}' cannot start with '-'"
+        ));
+    }
+    if revision.chars().any(|c| {
+        c.is_whitespace() || c.is_control() || matches!(c, '~' | '^' | ':' | '?' | '*' | '[' | '\\')
+    }) || revision.contains("..")
+    {
+        return Err(anyhow::anyhow!(
+            "{field_name} '{revision}' is not a valid branch, tag or commit"
+        ));
+    }
+    Ok(())
+}
@@ -2,64 +2,198 @@
 //!
 //! Validates provider-specific configuration (GCP, AWS, Azure).
 
-use crate::crd::ProviderConfig;
+use super::kubernetes::{validate_kubernetes_name, validate_kubernetes_namespace};
+use super::spec::ValidationError;
+use crate::crd::patterns::{
+    AWS_REGION_PATTERN, AWS_ROLE_ARN_PATTERN, AZURE_GUID_PATTERN, AZURE_LOCATION_PATTERN,
+    AZURE_RESOURCE_GROUP_PATTERN, AZURE_TENANT_ID_PATTERN, AZURE_VAULT_NAME_MAX_LEN,
+    AZURE_VAULT_NAME_MIN_LEN, AZURE_VAULT_NAME_PATTERN, GCP_LOCATION_PATTERN,
+    GCP_PROJECT_ID_PATTERN, GCP_SERVICE_ACCOUNT_EMAIL_PATTERN, matches,
+};
+use crate::crd::{
+    AwsAuthConfig, AzureAuthConfig, AzureRoleAssignmentsConfig, GcpAuthConfig, ProviderConfig,
+};
+use crate::provider::aws::partition::{AwsPartition, check_endpoint_url, check_role_arn_partition};
 use anyhow::Result;
-use regex::Regex;
 
-/// Validate provider configuration
+/// Validate provider configuration; the error lists every problem found
+pub fn validate_provider_config(provider: &ProviderConfig) -> Result<()> {
+    let errors = provider_errors(provider);
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow::anyhow!(
+        "{}",
+        errors
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ")
+    ))
+}
+
+/// Every problem in the provider configuration
 /// Uses official provider API constraints from:
 /// - GCP: https://cloud.google.com/resource-manager/docs/creating-managing-projects
 /// - AWS: https://docs.aws.amazon.com/general/latest/gr/rande.html
 /// - Azure: https://learn.microsoft.com/en-us/azure/key-vault/general/about-keys-secrets-certificates#vault-name
-pub fn validate_provider_config(provider: &ProviderConfig) -> Result<()> {
+#[must_use]
+pub fn provider_errors(provider: &ProviderConfig) -> Vec<ValidationError> {
+    let mut errors = vec![];
+    let mut error =
+        |field: &str, message: String| errors.push(ValidationError::new(field, message));
     match provider {
         ProviderConfig::Gcp(gcp) => {
-            if gcp.project_id.is_empty() {
-                return Err(anyhow::anyhow!(
-                    "provider.gcp.projectId is required but is empty"
-                ));
-            }
             // GCP project ID validation per official GCP API constraints:
             // - Length: 6-30 characters
             // - Must start with a lowercase letter
             // - Cannot end with a hyphen
             // - Allowed: lowercase letters, numbers, hyphens
             // Reference: https://cloud.google.com/resource-manager/docs/creating-managing-projects
-            let project_id_regex = Regex::new(r"^[a-z][a-z0-9-]{4,28}[a-z0-9]$")
-                .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
-
-            if !project_id_regex.is_match(&gcp.project_id) {
-                return Err(anyhow::anyhow!(
-                    "provider.gcp.projectId '{}' must be a valid GCP project ID (6-30 characters, lowercase letters/numbers/hyphens, must start with letter, cannot end with hyphen). See: https://cloud.google.com/resource-manager/docs/creating-managing-projects",
-                    gcp.project_id
-                ));
+            if gcp.project_id.is_empty() {
+                error(
+                    "provider.gcp.projectId",
+                    "provider.gcp.projectId is required but is empty".to_string(),
+                );
+            } else if !matches(GCP_PROJECT_ID_PATTERN, &gcp.project_id) {
+                error(
+                    "provider.gcp.projectId",
+                    format!(
+                        "provider.gcp.projectId '{}' must be a valid GCP project ID (6-30 characters, lowercase letters/numbers/hyphens, must start with letter, cannot end with hyphen). See: https://cloud.google.com/resource-manager/docs/creating-managing-projects",
+                        gcp.project_id
+                    ),
+                );
             }
 
             // GCP location validation per official GCP API constraints:
             // - Format: [continent]-[direction][number] (e.g., us-central1, europe-west1)
             // - Examples: us-central1, us-east1, europe-west1, asia-east1
             // Reference: https://cloud.google.com/about/locations
-            validate_gcp_location(&gcp.location)?;
+            if let Err(e) = validate_gcp_location(&gcp.location) {
+                error("provider.gcp.location", e.to_string());
+            }
+
+            if let Some(GcpAuthConfig::Impersonation {
+                ref target_service_account,
+                ref delegates,
+            }) = gcp.auth
+            {
+                for (field, email) in
+                    std::iter::once(("targetServiceAccount", target_service_account))
+                        .chain(delegates.iter().map(|d| ("delegates", d)))
+                {
+                    if !matches(GCP_SERVICE_ACCOUNT_EMAIL_PATTERN, email) {
+                        error(
+                            &format!("provider.gcp.auth.{field}"),
+                            format!(
+                                "provider.gcp.auth.{field} '{email}' must be a service account email (format: <name>@<project-id>.iam.gserviceaccount.com)"
+                            ),
+                        );
+                    }
+                }
+            }
+
+            if let Some(ref retention) = gcp.version_retention {
+                if let Err(e) = crate::provider::gcp::VersionRetention::from_config(retention) {
+                    error(
+                        "provider.gcp.versionRetention",
+                        format!("Invalid provider.gcp.versionRetention: {e:#}"),
+                    );
+                }
+            }
+
+            // Allowed domains are controller configuration, checked when the provider is created
+            if let Some(ref endpoint_override) = gcp.endpoint_override {
+                for (field, url) in [
+                    ("secretManager", &endpoint_override.secret_manager),
+                    ("parameterManager", &endpoint_override.parameter_manager),
+                ] {
+                    if let Some(url) = url {
+                        if let Err(e) = crate::provider::gcp::endpoint_override_host(url) {
+                            error(
+                                &format!("provider.gcp.endpointOverride.{field}"),
+                                format!("Invalid provider.gcp.endpointOverride.{field}: {e}"),
+                            );
+                        }
+                    }
+                }
+            }
         }
         ProviderConfig::Aws(aws) => {
-            if aws.region.is_empty() {
-                return Err(anyhow::anyhow!(
-                    "provider.aws.region is required but is empty"
-                ));
-            }
             // AWS region validation per official AWS API constraints:
             // - Format: [a-z]{2}-[a-z]+-[0-9]+ (e.g., us-east-1, eu-west-1)
             // - Some regions include -gov or -iso segments (e.g., us-gov-west-1)
             // - Must match valid AWS region codes
             // Reference: https://docs.aws.amazon.com/general/latest/gr/rande.html
-            validate_aws_region(&aws.region)?;
+            if aws.region.is_empty() {
+                error(
+                    "provider.aws.region",
+                    "provider.aws.region is required but is empty".to_string(),
+                );
+            } else if let Err(e) = validate_aws_region(&aws.region) {
+                error("provider.aws.region", e.to_string());
+            }
+
+            if let Some(AwsAuthConfig::Irsa { ref role_arn }) = aws.auth {
+                if !matches(AWS_ROLE_ARN_PATTERN, role_arn) {
+                    error(
+                        "provider.aws.auth.roleArn",
+                        format!(
+                            "provider.aws.auth.roleArn '{role_arn}' must be an IAM role ARN (format: arn:aws:iam::<12-digit-account-id>:role/<role-name>)"
+                        ),
+                    );
+                } else if let Err(e) = check_role_arn_partition(role_arn, &aws.region) {
+                    // STS in one partition can't assume roles from another (e.g. aws vs aws-us-gov)
+                    error(
+                        "provider.aws.auth.roleArn",
+                        format!("Invalid provider.aws.auth.roleArn: {e}"),
+                    );
+                }
+            }
+
+            if let Some(ref role_arn) = aws.assume_role_arn {
+                if !matches(AWS_ROLE_ARN_PATTERN, role_arn) {
+                    error(
+                        "provider.aws.assumeRoleArn",
+                        format!(
+                            "provider.aws.assumeRoleArn '{role_arn}' must be an IAM role ARN (format: arn:aws:iam::<12-digit-account-id>:role/<role-name>)"
+                        ),
+                    );
+                } else if let Err(e) = check_role_arn_partition(role_arn, &aws.region) {
+                    error(
+                        "provider.aws.assumeRoleArn",
+                        format!("Invalid provider.aws.assumeRoleArn: {e}"),
+                    );
+                }
+            }
+
+            if aws.use_fips && !AwsPartition::from_region(&aws.region).supports_fips() {
+                error(
+                    "provider.aws.useFips",
+                    format!(
+                        "provider.aws.useFips is not supported in region '{}' (no FIPS endpoints in partition '{}')",
+                        aws.region,
+                        AwsPartition::from_region(&aws.region).id()
+                    ),
+                );
+            }
+
+            if let Some(ref endpoint_url) = aws.endpoint_url {
+                if let Err(e) = check_endpoint_url(endpoint_url) {
+                    error(
+                        "provider.aws.endpointUrl",
+                        format!("Invalid provider.aws.endpointUrl: {e}"),
+                    );
+                }
+            }
+
+            if let Some(ref version_stages) = aws.version_stages {
+                if let Err(e) = validate_aws_version_stages(&version_stages.labels) {
+                    error("provider.aws.versionStages.labels", e.to_string());
+                }
+            }
         }
         ProviderConfig::Azure(azure) => {
-            if azure.vault_name.is_empty() {
-                return Err(anyhow::anyhow!(
-                    "provider.azure.vaultName is required but is empty"
-                ));
-            }
             // Azure Key Vault name validation per official Azure API constraints:
             // - Length: 3-24 characters
             // - Must start with a letter
@@ -67,29 +201,112 @@ pub fn validate_provider_config(provider: &ProviderConfig) -> Result<()> {
             // - Allowed: alphanumeric characters and hyphens
             // - Hyphens cannot be consecutive
             // Reference: https://learn.microsoft.com/en-us/azure/key-vault/general/about-keys-secrets-certificates#vault-name
-            let vault_name_regex = Regex::new(r"^[a-zA-Z][a-zA-Z0-9-]{1,22}[a-zA-Z0-9]$")
-                .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
-
-            if !vault_name_regex.is_match(&azure.vault_name) {
-                return Err(anyhow::anyhow!(
-                    "provider.azure.vaultName '{}' must be a valid Azure Key Vault name (3-24 characters, alphanumeric/hyphens, must start with letter, cannot end with hyphen). See: https://learn.microsoft.com/en-us/azure/key-vault/general/about-keys-secrets-certificates#vault-name",
-                    azure.vault_name
-                ));
-            }
-
-            // Check for consecutive hyphens
-            if azure.vault_name.contains("--") {
-                return Err(anyhow::anyhow!(
-                    "provider.azure.vaultName '{}' cannot contain consecutive hyphens",
-                    azure.vault_name
-                ));
+            let length_ok = (AZURE_VAULT_NAME_MIN_LEN..=AZURE_VAULT_NAME_MAX_LEN)
+                .contains(&azure.vault_name.len());
+            if azure.vault_name.is_empty() {
+                error(
+                    "provider.azure.vaultName",
+                    "provider.azure.vaultName is required but is empty".to_string(),
+                );
+            } else if azure.vault_name.contains("--") {
+                error(
+                    "provider.azure.vaultName",
+                    format!(
+                        "provider.azure.vaultName '{}' cannot contain consecutive hyphens",
+                        azure.vault_name
+                    ),
+                );
+            } else if !length_ok || !matches(AZURE_VAULT_NAME_PATTERN, &azure.vault_name) {
+                error(
+                    "provider.azure.vaultName",
+                    format!(
+                        "provider.azure.vaultName '{}' must be a valid Azure Key Vault name (3-24 characters, alphanumeric/hyphens, must start with letter, cannot end with hyphen). See: https://learn.microsoft.com/en-us/azure/key-vault/general/about-keys-secrets-certificates#vault-name",
+                        azure.vault_name
+                    ),
+                );
             }
 
             // Azure location validation per official Azure API constraints:
             // - Format: [direction][region][number] (e.g., eastus, westus2, southeastasia)
             // - Examples: eastus, westus2, centralus, southeastasia
             // Reference: https://azure.microsoft.com/en-us/explore/global-infrastructure/geographies/
-            validate_azure_location(&azure.location)?;
+            if let Err(e) = validate_azure_location(&azure.location) {
+                error("provider.azure.location", e.to_string());
+            }
+
+            if let Some(ref auth) = azure.auth {
+                if let Err(e) = validate_azure_auth(auth) {
+                    error("provider.azure.auth", e.to_string());
+                }
+            }
+
+            if let Some(ref role_assignments) = azure.role_assignments {
+                if let Err(e) = validate_azure_role_assignments(role_assignments) {
+                    error("provider.azure.roleAssignments", e.to_string());
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Validate Key Vault role assignments: the vault's resource ID parts and principal object IDs
+fn validate_azure_role_assignments(role_assignments: &AzureRoleAssignmentsConfig) -> Result<()> {
+    if !matches(AZURE_GUID_PATTERN, &role_assignments.subscription_id) {
+        return Err(anyhow::anyhow!(
+            "provider.azure.roleAssignments.subscriptionId '{}' must be a subscription ID (GUID)",
+            role_assignments.subscription_id
+        ));
+    }
+    if !matches(
+        AZURE_RESOURCE_GROUP_PATTERN,
+        &role_assignments.resource_group,
+    ) {
+        return Err(anyhow::anyhow!(
+            "provider.azure.roleAssignments.resourceGroup '{}' must be a valid resource group name (1-90 characters: alphanumerics, underscores, hyphens, periods and parentheses, not ending with a period)",
+            role_assignments.resource_group
+        ));
+    }
+    if role_assignments.principals.is_empty() {
+        return Err(anyhow::anyhow!(
+            "provider.azure.roleAssignments.principals must list at least one principal"
+        ));
+    }
+    for principal in &role_assignments.principals {
+        if !matches(AZURE_GUID_PATTERN, &principal.principal_id) {
+            return Err(anyhow::anyhow!(
+                "provider.azure.roleAssignments.principals principalId '{}' must be an object ID (GUID)",
+                principal.principal_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validate Azure auth: tenant IDs and the client certificate secret reference
+fn validate_azure_auth(auth: &AzureAuthConfig) -> Result<()> {
+    let tenant_id = match auth {
+        AzureAuthConfig::WorkloadIdentity { tenant_id, .. } => tenant_id.as_deref(),
+        AzureAuthConfig::ClientCertificate {
+            tenant_id,
+            certificate_secret_ref,
+            ..
+        } => {
+            validate_kubernetes_name(
+                &certificate_secret_ref.name,
+                "provider.azure.auth.certificateSecretRef.name",
+            )?;
+            if let Some(ref namespace) = certificate_secret_ref.namespace {
+                validate_kubernetes_namespace(namespace)?;
+            }
+            Some(tenant_id.as_str())
+        }
+    };
+    if let Some(tenant_id) = tenant_id {
+        if !matches(AZURE_TENANT_ID_PATTERN, tenant_id) {
+            return Err(anyhow::anyhow!(
+                "provider.azure.auth.tenantId '{tenant_id}' must be a tenant ID (GUID) or a verified domain (e.g. contoso.onmicrosoft.com)"
+            ));
         }
     }
     Ok(())
@@ -105,40 +322,12 @@ pub fn validate_aws_region(region: &str) -> Result<()> {
         return Err(anyhow::anyhow!("provider.aws.region cannot be empty"));
     }
 
-    // AWS region format patterns:
-    // Standard: [a-z]{2}-[a-z]+-[0-9]+ (e.g., us-east-1, eu-west-1)
+    // AWS region format patterns (AWS_REGION_PATTERN, case insensitive):
+    // Standard: [a-z]{2}-[a-z]+-[0-9]+ (e.g., us-east-1, eu-west-1, cn-north-1)
     // Gov: [a-z]{2}-gov-[a-z]+-[0-9]+ (e.g., us-gov-west-1)
     // ISO: [a-z]{2}-iso-[a-z]+-[0-9]+ (e.g., us-iso-east-1)
-    // China: cn-[a-z]+-[0-9]+ (e.g., cn-north-1)
     // Local: local (for localstack)
-
-    // Standard region pattern: [a-z]{2}-[a-z]+-[0-9]+
-    let standard_pattern = Regex::new(r"^[a-z]{2}-[a-z]+-\d+$")
-        .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
-
-    // Gov region pattern: [a-z]{2}-gov-[a-z]+-[0-9]+
-    let gov_pattern = Regex::new(r"^[a-z]{2}-gov-[a-z]+-\d+$")
-        .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
-
-    // ISO region pattern: [a-z]{2}-iso-[a-z]+-[0-9]+
-    let iso_pattern = Regex::new(r"^[a-z]{2}-iso-[a-z]+-\d+$")
-        .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
-
-    // China region pattern: cn-[a-z]+-[0-9]+
-    let china_pattern = Regex::new(r"^cn-[a-z]+-\d+$")
-        .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
-
-    // Local pattern (for local development/testing with localstack)
-    // Note: This allows "local" as a region for local development environments
-    let local_pattern =
-        Regex::new(r"^local$").map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
-
-    if standard_pattern.is_match(&region_trimmed)
-        || gov_pattern.is_match(&region_trimmed)
-        || iso_pattern.is_match(&region_trimmed)
-        || china_pattern.is_match(&region_trimmed)
-        || local_pattern.is_match(&region_trimmed)
-    {
+    if matches(AWS_REGION_PATTERN, &region_trimmed) {
         Ok(())
     } else {
         Err(anyhow::anyhow!(
@@ -160,10 +349,7 @@ pub fn validate_gcp_location(location: &str) -> Result<()> {
     // GCP location format: [continent]-[direction][number]
     // Examples: us-central1, us-east1, europe-west1, asia-east1
     // Pattern: [a-z]+-[a-z]+[0-9]+
-    let location_pattern = Regex::new(r"^[a-z]+-[a-z]+[0-9]+$")
-        .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
-
-    if location_pattern.is_match(&location_trimmed) {
+    if matches(GCP_LOCATION_PATTERN, &location_trimmed) {
         Ok(())
     } else {
         Err(anyhow::anyhow!(
@@ -186,10 +372,7 @@ pub fn validate_azure_location(location: &str) -> Result<()> {
     // Azure location format: [direction][region][number]
     // Examples: eastus, westus2, centralus, southeastasia
     // Pattern: [a-z]+[0-9]*
-    let location_pattern = Regex::new(r"^[a-z]+[0-9]*$")
-        .map_err(|e| anyhow::anyhow!("Failed to compile regex: {e}"))?;
-
-    if location_pattern.is_match(&location_trimmed) {
+    if matches(AZURE_LOCATION_PATTERN, &location_trimmed) {
         Ok(())
     } else {
         Err(anyhow::anyhow!(
@@ -199,10 +382,37 @@ pub fn validate_azure_location(location: &str) -> Result<()> {
     }
 }
 
+/// Validate custom AWS staging labels
+/// AWS limits: 1-256 characters, at most 20 labels per version, and the AWS-managed
+/// labels (AWSCURRENT, AWSPENDING, AWSPREVIOUS) cannot be managed explicitly
+/// Reference: https://docs.aws.amazon.com/secretsmanager/latest/apireference/API_UpdateSecretVersionStage.html
+fn validate_aws_version_stages(labels: &[String]) -> Result<()> {
+    // AWSCURRENT (and possibly AWSPENDING) share the 20-label limit
+    if labels.len() > 18 {
+        return Err(anyhow::anyhow!(
+            "provider.aws.versionStages.labels has {} labels, at most 18 custom labels are allowed",
+            labels.len()
+        ));
+    }
+    for label in labels {
+        if label.is_empty() || label.len() > 256 {
+            return Err(anyhow::anyhow!(
+                "provider.aws.versionStages.labels entry '{label}' must be 1-256 characters"
+            ));
+        }
+        if matches!(label.as_str(), "AWSCURRENT" | "AWSPENDING" | "AWSPREVIOUS") {
+            return Err(anyhow::anyhow!(
+                "provider.aws.versionStages.labels entry '{label}' is managed by AWS and cannot be set"
+            ));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::crd::{AwsConfig, AzureConfig, GcpConfig};
+    use crate::crd::{AwsConfig, AzureConfig, AzureEnvironment, GcpConfig};
 
     #[test]
     fn test_validate_gcp_location_valid() {
@@ -299,6 +509,51 @@ mod tests {
         assert!(validate_aws_region("Eu-West-1").is_ok());
     }
 
+    #[test]
+    fn test_validate_provider_config_aws_partitions() {
+        let aws = |region: &str, role_arn: &str, use_fips: bool, endpoint_url: Option<&str>| {
+            ProviderConfig::Aws(AwsConfig {
+                region: region.to_string(),
+                auth: Some(AwsAuthConfig::Irsa {
+                    role_arn: role_arn.to_string(),
+                }),
+                version_stages: None,
+                endpoint_url: endpoint_url.map(str::to_string),
+                use_fips,
+                assume_role_arn: None,
+            })
+        };
+        let gov_role = "arn:aws-us-gov:iam::123456789012:role/test-role";
+        let cn_role = "arn:aws-cn:iam::123456789012:role/test-role";
+
+        assert!(validate_provider_config(&aws("us-gov-west-1", gov_role, true, None)).is_ok());
+        assert!(validate_provider_config(&aws("cn-north-1", cn_role, false, None)).is_ok());
+        assert!(
+            validate_provider_config(&aws(
+                "us-gov-west-1",
+                gov_role,
+                false,
+                Some("https://vpce-0abc.secretsmanager.us-gov-west-1.vpce.amazonaws.com")
+            ))
+            .is_ok()
+        );
+
+        // Role from another partition
+        assert!(validate_provider_config(&aws("us-east-1", gov_role, false, None)).is_err());
+        assert!(validate_provider_config(&aws("us-gov-west-1", cn_role, false, None)).is_err());
+        // No FIPS endpoints in China
+        assert!(validate_provider_config(&aws("cn-north-1", cn_role, true, None)).is_err());
+        assert!(
+            validate_provider_config(&aws(
+                "us-gov-west-1",
+                gov_role,
+                false,
+                Some("http://secretsmanager.us-gov-west-1.amazonaws.com")
+            ))
+            .is_err()
+        );
+    }
+
     #[test]
     fn test_validate_azure_location_valid() {
         let valid_locations = vec![
@@ -351,6 +606,8 @@ mod tests {
             project_id: "test-project".to_string(),
             location: "us-central1".to_string(),
             auth: None,
+            version_retention: None,
+            endpoint_override: None,
         });
 
         assert!(validate_provider_config(&config).is_ok());
@@ -362,16 +619,96 @@ mod tests {
             project_id: "test-project".to_string(),
             location: "invalid-location".to_string(),
             auth: None,
+            version_retention: None,
+            endpoint_override: None,
         });
 
         assert!(validate_provider_config(&config).is_err());
     }
 
+    #[test]
+    fn test_provider_errors_reports_every_field() {
+        let config = ProviderConfig::Gcp(GcpConfig {
+            project_id: "X".to_string(),
+            location: "invalid-location".to_string(),
+            auth: None,
+            version_retention: None,
+            endpoint_override: None,
+        });
+
+        let fields: Vec<_> = provider_errors(&config)
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(fields, ["provider.gcp.projectId", "provider.gcp.location"]);
+        let message = validate_provider_config(&config)
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        assert!(message.contains("projectId") && message.contains("location"));
+    }
+
+    #[test]
+    fn test_validate_provider_config_gcp_impersonation() {
+        let gcp = |target: &str, delegates: &[&str]| {
+            ProviderConfig::Gcp(GcpConfig {
+                project_id: "my-project".to_string(),
+                location: "us-central1".to_string(),
+                auth: Some(GcpAuthConfig::Impersonation {
+                    target_service_account: target.to_string(),
+                    delegates: delegates.iter().map(ToString::to_string).collect(),
+                }),
+                version_retention: None,
+                endpoint_override: None,
+            })
+        };
+        let target = "team-a@team-a-project.iam.gserviceaccount.com";
+
+        assert!(validate_provider_config(&gcp(target, &[])).is_ok());
+        assert!(
+            validate_provider_config(&gcp(
+                target,
+                &["broker@platform-project.iam.gserviceaccount.com"]
+            ))
+            .is_ok()
+        );
+        assert!(validate_provider_config(&gcp("team-a", &[])).is_err());
+        assert!(validate_provider_config(&gcp(target, &["broker@example.com"])).is_err());
+    }
+
+    #[test]
+    fn test_validate_provider_config_gcp_endpoint_override() {
+        let gcp = |url: &str| {
+            ProviderConfig::Gcp(GcpConfig {
+                project_id: "test-project".to_string(),
+                location: "us-central1".to_string(),
+                auth: None,
+                version_retention: None,
+                endpoint_override: Some(crate::crd::GcpEndpointOverride {
+                    secret_manager: Some(url.to_string()),
+                    parameter_manager: None,
+                }),
+            })
+        };
+
+        assert!(
+            validate_provider_config(&gcp("https://secretmanager-psc.p.googleapis.com")).is_ok()
+        );
+        // Domains are checked against the controller configuration during reconcile
+        assert!(validate_provider_config(&gcp("https://secrets.corp.example.com")).is_ok());
+        assert!(validate_provider_config(&gcp("http://secretmanager.googleapis.com")).is_err());
+        assert!(validate_provider_config(&gcp("https://secretmanager.googleapis.com/v1")).is_err());
+    }
+
     #[test]
     fn test_validate_provider_config_aws_with_valid_region() {
         let config = ProviderConfig::Aws(AwsConfig {
             region: "us-east-1".to_string(),
             auth: None,
+            version_stages: None,
+            endpoint_url: None,
+            use_fips: false,
+            assume_role_arn: None,
         });
 
         assert!(validate_provider_config(&config).is_ok());
@@ -382,6 +719,10 @@ mod tests {
         let config = ProviderConfig::Aws(AwsConfig {
             region: "invalid-region".to_string(),
             auth: None,
+            version_stages: None,
+            endpoint_url: None,
+            use_fips: false,
+            assume_role_arn: None,
         });
 
         assert!(validate_provider_config(&config).is_err());
@@ -393,17 +734,141 @@ mod tests {
             vault_name: "test-vault".to_string(),
             location: "eastus".to_string(),
             auth: None,
+            certificates: None,
+            environment: AzureEnvironment::Public,
+            role_assignments: None,
         });
 
         assert!(validate_provider_config(&config).is_ok());
     }
 
+    #[test]
+    fn test_validate_provider_config_azure_auth() {
+        let config = |auth: serde_json::Value| {
+            ProviderConfig::Azure(AzureConfig {
+                vault_name: "test-vault".to_string(),
+                location: "eastus".to_string(),
+                auth: Some(
+                    serde_json::from_value(auth).unwrap_or_else(|e| panic!("invalid auth: {e}")),
+                ),
+                certificates: None,
+                environment: AzureEnvironment::Public,
+                role_assignments: None,
+            })
+        };
+
+        assert!(
+            validate_provider_config(&config(serde_json::json!({
+                "authType": "workloadIdentity",
+                "clientId": "12345678-1234-1234-1234-123456789012",
+                "tenantId": "contoso.onmicrosoft.com"
+            })))
+            .is_ok()
+        );
+        assert!(
+            validate_provider_config(&config(serde_json::json!({
+                "authType": "clientCertificate",
+                "clientId": "12345678-1234-1234-1234-123456789012",
+                "tenantId": "72f988bf-86f1-41af-91ab-2d7cd011db47",
+                "certificateSecretRef": {"name": "azure-sp-cert"}
+            })))
+            .is_ok()
+        );
+        // Invalid tenant
+        assert!(
+            validate_provider_config(&config(serde_json::json!({
+                "authType": "workloadIdentity",
+                "clientId": "12345678-1234-1234-1234-123456789012",
+                "tenantId": "not a tenant"
+            })))
+            .is_err()
+        );
+        // Invalid secret name
+        assert!(
+            validate_provider_config(&config(serde_json::json!({
+                "authType": "clientCertificate",
+                "clientId": "12345678-1234-1234-1234-123456789012",
+                "tenantId": "72f988bf-86f1-41af-91ab-2d7cd011db47",
+                "certificateSecretRef": {"name": "Azure_SP_Cert"}
+            })))
+            .is_err()
+        );
+        // tenantId is required for client certificates
+        assert!(
+            serde_json::from_value::<AzureAuthConfig>(serde_json::json!({
+                "authType": "clientCertificate",
+                "clientId": "12345678-1234-1234-1234-123456789012",
+                "certificateSecretRef": {"name": "azure-sp-cert"}
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate_provider_config_azure_role_assignments() {
+        let config = |role_assignments: serde_json::Value| {
+            ProviderConfig::Azure(AzureConfig {
+                vault_name: "test-vault".to_string(),
+                location: "eastus".to_string(),
+                auth: None,
+                certificates: None,
+                environment: AzureEnvironment::Public,
+                role_assignments: Some(
+                    serde_json::from_value(role_assignments)
+                        .unwrap_or_else(|e| panic!("invalid roleAssignments: {e}")),
+                ),
+            })
+        };
+
+        assert!(
+            validate_provider_config(&config(serde_json::json!({
+                "subscriptionId": "00000000-0000-0000-0000-000000000001",
+                "resourceGroup": "rg-secrets_prod(1)",
+                "principals": [{
+                    "principalId": "11111111-2222-3333-4444-555555555555",
+                    "scope": "Secrets"
+                }]
+            })))
+            .is_ok()
+        );
+        // Not a GUID
+        assert!(
+            validate_provider_config(&config(serde_json::json!({
+                "subscriptionId": "00000000-0000-0000-0000-000000000001",
+                "resourceGroup": "rg",
+                "principals": [{"principalId": "my-app"}]
+            })))
+            .is_err()
+        );
+        // Resource groups can't end with a period
+        assert!(
+            validate_provider_config(&config(serde_json::json!({
+                "subscriptionId": "00000000-0000-0000-0000-000000000001",
+                "resourceGroup": "rg.",
+                "principals": [{"principalId": "11111111-2222-3333-4444-555555555555"}]
+            })))
+            .is_err()
+        );
+        // No principals
+        assert!(
+            validate_provider_config(&config(serde_json::json!({
+                "subscriptionId": "00000000-0000-0000-0000-000000000001",
+                "resourceGroup": "rg",
+                "principals": []
+            })))
+            .is_err()
+        );
+    }
+
     #[test]
     fn test_validate_provider_config_azure_with_invalid_location() {
         let config = ProviderConfig::Azure(AzureConfig {
             vault_name: "test-vault".to_string(),
             location: "invalid-location".to_string(),
             auth: None,
+            certificates: None,
+            environment: AzureEnvironment::Public,
+            role_assignments: None,
         });
 
         assert!(validate_provider_config(&config).is_err());
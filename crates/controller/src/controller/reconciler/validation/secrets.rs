@@ -1,10 +1,20 @@
 //! # Secret Name Validation
 //!
-//! Validates secret name components (prefix, suffix) for cloud provider compatibility.
+//! Validates secret name components (prefix, suffix), name templates and secret routes for cloud provider compatibility,
+//! the key globs of the secret policy, and the secret expiration.
 
+use super::provider::validate_provider_config;
+use crate::controller::parser::file_finder::glob_to_regex;
+use crate::controller::reconciler::expiration::DEFAULT_WARN_BEFORE;
+use crate::controller::reconciler::routing::{provider_kind, route_target_config};
+use crate::controller::reconciler::utils::{NAME_TEMPLATE_VARIABLES, parse_name_template};
+use crate::crd::{SecretExpiration, SecretManagerConfig, SecretPolicy, SecretRoute};
 use anyhow::Result;
 use regex::Regex;
 
+/// Shortest ttl accepted in `secrets.expiration`
+const MIN_EXPIRATION_TTL_SECS: u64 = 3_600;
+
 /// Validate secret name component (prefix or suffix)
 /// Must be valid for cloud provider secret names
 /// Format: alphanumeric, hyphens, underscores
@@ -37,3 +47,231 @@ pub fn validate_secret_name_component(component: &str, field_name: &str) -> Resu
 
     Ok(())
 }
+
+/// Validate a secret name template (`secrets.nameTemplate`)
+/// Variables must be known, `{key}` must be present, and literal text must be
+/// alphanumeric, hyphens, or underscores
+pub fn validate_name_template(template: &str) -> Result<()> {
+    if template.trim().is_empty() {
+        return Err(anyhow::anyhow!("secrets.nameTemplate cannot be empty"));
+    }
+
+    let segments = parse_name_template(template)?;
+    let mut has_key = false;
+    for (is_variable, text) in segments {
+        if is_variable {
+            if !NAME_TEMPLATE_VARIABLES.contains(&text) {
+                return Err(anyhow::anyhow!(
+                    "unknown variable '{{{text}}}' (supported: {})",
+                    NAME_TEMPLATE_VARIABLES
+                        .iter()
+                        .map(|v| format!("{{{v}}}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+            has_key |= text == "key";
+        } else {
+            validate_secret_name_component(text, "secrets.nameTemplate literal")?;
+        }
+    }
+
+    if !has_key {
+        return Err(anyhow::anyhow!(
+            "secrets.nameTemplate must contain {{key}} so each key maps to a distinct secret"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate a secret route (`secrets.routes[]`)
+/// The `match` glob must compile, exactly one target must be set and match the resource's
+/// provider, and the merged target configuration must be a valid provider configuration
+pub fn validate_secret_route(config: &SecretManagerConfig, route: &SecretRoute) -> Result<()> {
+    if route.key_pattern.trim().is_empty() {
+        return Err(anyhow::anyhow!("match cannot be empty"));
+    }
+    glob_to_regex(&route.key_pattern)?;
+
+    let targets = [
+        route.gcp.is_some(),
+        route.aws.is_some(),
+        route.azure.is_some(),
+    ];
+    if targets.iter().filter(|t| **t).count() != 1 {
+        return Err(anyhow::anyhow!(
+            "exactly one target must be set ({})",
+            provider_kind(&config.spec.provider)
+        ));
+    }
+
+    let routed = route_target_config(config, route)?;
+    validate_provider_config(&routed.spec.provider)
+}
+
+/// Validate the secret policy (`secrets.policy`)
+/// Every key glob must be non-empty and compile
+pub fn validate_secret_policy(policy: &SecretPolicy) -> Result<()> {
+    for (field, patterns) in [
+        ("forbiddenKeyPatterns", &policy.forbidden_key_patterns),
+        ("requireSopsForKeys", &policy.require_sops_for_keys),
+    ] {
+        for pattern in patterns.iter().flatten() {
+            if pattern.trim().is_empty() {
+                return Err(anyhow::anyhow!("{field} entries cannot be empty"));
+            }
+            glob_to_regex(pattern).map_err(|e| anyhow::anyhow!("{field}: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Validate the secret expiration (`secrets.expiration`)
+/// ttls must be at least an hour and longer than `warnBefore`, otherwise secrets would be
+/// reported as expiring as soon as they are written; key globs must be non-empty and compile
+pub fn validate_secret_expiration(expiration: &SecretExpiration) -> Result<()> {
+    let warn_before = match &expiration.warn_before {
+        Some(warn_before) => warn_before.to_duration_at_least("warnBefore", 1)?,
+        None => DEFAULT_WARN_BEFORE,
+    };
+    let mut ttls: Vec<_> = expiration
+        .ttl
+        .iter()
+        .map(|ttl| ("ttl".to_string(), ttl))
+        .collect();
+    for entry in expiration.keys.iter().flatten() {
+        if entry.key_pattern.trim().is_empty() {
+            return Err(anyhow::anyhow!("keys entries need a match glob"));
+        }
+        glob_to_regex(&entry.key_pattern)
+            .map_err(|e| anyhow::anyhow!("keys '{}': {e}", entry.key_pattern))?;
+        ttls.push((format!("keys '{}' ttl", entry.key_pattern), &entry.ttl));
+    }
+    for (field, ttl) in ttls {
+        if ttl.to_duration_at_least(&field, MIN_EXPIRATION_TTL_SECS)? <= warn_before {
+            return Err(anyhow::anyhow!(
+                "{field} '{ttl}' must be longer than warnBefore ({}s)",
+                warn_before.as_secs()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name_template_valid() {
+        assert!(validate_name_template("{service}-{env}-{key}").is_ok());
+        assert!(validate_name_template("{repo}_{key}").is_ok());
+        assert!(validate_name_template("{key}").is_ok());
+    }
+
+    #[test]
+    fn test_validate_name_template_requires_key() {
+        assert!(validate_name_template("{service}-{env}").is_err());
+    }
+
+    #[test]
+    fn test_validate_name_template_unknown_variable() {
+        let err = validate_name_template("{team}-{key}").unwrap_err();
+        assert!(err.to_string().contains("unknown variable"));
+    }
+
+    #[test]
+    fn test_validate_name_template_invalid_syntax() {
+        assert!(validate_name_template("{service-{key}").is_err());
+        assert!(validate_name_template("service}-{key}").is_err());
+        assert!(validate_name_template("{}-{key}").is_err());
+        assert!(validate_name_template("{service}.{key}").is_err());
+        assert!(validate_name_template("").is_err());
+    }
+
+    #[test]
+    fn test_validate_secret_route() {
+        let config: SecretManagerConfig = serde_json::from_value(serde_json::json!({
+            "apiVersion": "secret-management.octopilot.io/v1beta1",
+            "kind": "SecretManagerConfig",
+            "metadata": {"name": "app", "namespace": "default"},
+            "spec": {
+                "sourceRef": {"kind": "GitRepository", "name": "repo", "namespace": "default"},
+                "provider": {"type": "gcp", "gcp": {"projectId": "app-project", "location": "us-central1"}},
+                "secrets": {"environment": "dev"}
+            }
+        }))
+        .unwrap_or_else(|e| panic!("invalid config: {e}"));
+        let route = |value: serde_json::Value| -> SecretRoute {
+            serde_json::from_value(value).unwrap_or_else(|e| panic!("invalid route: {e}"))
+        };
+
+        let valid = route(
+            serde_json::json!({"match": "PAYMENTS_*", "gcp": {"projectId": "payments-project"}}),
+        );
+        assert!(validate_secret_route(&config, &valid).is_ok());
+
+        let empty =
+            route(serde_json::json!({"match": " ", "gcp": {"projectId": "payments-project"}}));
+        assert!(validate_secret_route(&config, &empty).is_err());
+
+        let no_target = route(serde_json::json!({"match": "PAYMENTS_*"}));
+        assert!(validate_secret_route(&config, &no_target).is_err());
+
+        let wrong_provider =
+            route(serde_json::json!({"match": "PAYMENTS_*", "aws": {"region": "us-east-1"}}));
+        assert!(validate_secret_route(&config, &wrong_provider).is_err());
+
+        let bad_location = route(serde_json::json!({
+            "match": "PAYMENTS_*",
+            "gcp": {"projectId": "payments-project", "location": "mars-central1"}
+        }));
+        assert!(validate_secret_route(&config, &bad_location).is_err());
+    }
+
+    #[test]
+    fn test_validate_secret_policy() {
+        let policy = |value: serde_json::Value| -> SecretPolicy {
+            serde_json::from_value(value).unwrap_or_else(|e| panic!("invalid policy: {e}"))
+        };
+
+        let valid = policy(serde_json::json!({
+            "forbiddenKeyPatterns": ["*_PROD_*"],
+            "requireSopsForKeys": ["*_PASSWORD", "TLS_KEY"]
+        }));
+        assert!(validate_secret_policy(&valid).is_ok());
+        assert!(validate_secret_policy(&SecretPolicy::default()).is_ok());
+
+        let empty = policy(serde_json::json!({"requireSopsForKeys": [""]}));
+        assert!(validate_secret_policy(&empty).is_err());
+    }
+
+    #[test]
+    fn test_validate_secret_expiration() {
+        let expiration = |value: serde_json::Value| -> SecretExpiration {
+            serde_json::from_value(value).unwrap_or_else(|e| panic!("invalid expiration: {e}"))
+        };
+
+        let valid = expiration(serde_json::json!({
+            "ttl": "90d",
+            "warnBefore": "7d",
+            "keys": [{"match": "DB_*", "ttl": "30d"}]
+        }));
+        assert!(validate_secret_expiration(&valid).is_ok());
+        assert!(validate_secret_expiration(&SecretExpiration::default()).is_ok());
+
+        // Shorter than the default warnBefore of 14d
+        let short = expiration(serde_json::json!({"ttl": "7d"}));
+        assert!(validate_secret_expiration(&short).is_err());
+
+        let too_short = expiration(serde_json::json!({"ttl": "30m", "warnBefore": "1m"}));
+        assert!(validate_secret_expiration(&too_short).is_err());
+
+        let empty_match = expiration(serde_json::json!({"keys": [{"match": " ", "ttl": "30d"}]}));
+        assert!(validate_secret_expiration(&empty_match).is_err());
+
+        let malformed = expiration(serde_json::json!({"ttl": "90 days"}));
+        assert!(validate_secret_expiration(&malformed).is_err());
+    }
+}
@@ -0,0 +1,432 @@
+//! # Resources API
+//!
+//! Read-only JSON endpoints exposing reconcile state for an ops dashboard:
+//!
+//! - `/api/v1/resources` - All SecretManagerConfig resources
+//! - `/api/v1/resources/{namespace}/{name}` - A single resource
+//! - `/api/v1/resources/{namespace}/{name}/logs` - Recent log events of a resource
+//!
+//! Responses are built from the watch cache, the reconciler's backoff state and the
+//! per-resource log buffer. Only names, phases, timestamps, error and log messages are
+//! exposed - never secret values or source content.
+//!
+//! Requests must carry `Authorization: Bearer <token>`, where the token is read from
+//! `RESOURCES_API_TOKEN` or the file named by `RESOURCES_API_TOKEN_FILE`. Without a token the
+//! API is disabled and every request is rejected.
+
+use crate::controller::health::{is_failing, is_ready};
+use crate::controller::reconciler::BackoffState;
+use crate::controller::server::ServerState;
+use crate::crd::{FailedSecret, FailedService, ProviderConfig, SecretManagerConfig};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Environment variable holding the bearer token
+pub const RESOURCES_API_TOKEN_ENV: &str = "RESOURCES_API_TOKEN";
+
+/// Environment variable naming a file that holds the bearer token (e.g. a mounted Secret)
+pub const RESOURCES_API_TOKEN_FILE_ENV: &str = "RESOURCES_API_TOKEN_FILE";
+
+/// Load the bearer token from `RESOURCES_API_TOKEN_FILE` or `RESOURCES_API_TOKEN`
+/// Returns `None` (API disabled) when neither is set or the token is empty
+pub fn load_api_token() -> Option<String> {
+    let token = match std::env::var(RESOURCES_API_TOKEN_FILE_ENV) {
+        Ok(path) if !path.is_empty() => match std::fs::read_to_string(&path) {
+            Ok(token) => Some(token),
+            Err(e) => {
+                warn!(
+                    "Failed to read {} '{}': {}, resources API disabled",
+                    RESOURCES_API_TOKEN_FILE_ENV, path, e
+                );
+                None
+            }
+        },
+        _ => std::env::var(RESOURCES_API_TOKEN_ENV).ok(),
+    };
+    token
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// Backoff state of a resource that is retrying after errors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackoffView {
+    /// Consecutive reconcile errors
+    pub error_count: u32,
+    /// When the next retry is scheduled (RFC3339)
+    pub next_retry_time: Option<String>,
+}
+
+/// Redacted reconcile state of a single resource
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceView {
+    pub namespace: String,
+    pub name: String,
+    pub provider: &'static str,
+    /// Source reference as `Kind/namespace/name`
+    pub source: String,
+    pub suspended: bool,
+    pub phase: Option<String>,
+    pub ready: bool,
+    pub failing: bool,
+    pub description: Option<String>,
+    pub generation: Option<i64>,
+    pub observed_generation: Option<i64>,
+    pub last_reconcile_time: Option<String>,
+    pub next_reconcile_time: Option<String>,
+    pub secrets_synced: Option<i32>,
+    /// Names of secrets pushed to the provider (values are never exposed)
+    pub secrets: Vec<String>,
+    pub failed_secrets: Vec<FailedSecret>,
+    /// Services whose files could not be processed
+    pub failed_services: Vec<FailedService>,
+    /// Most relevant error: the status description while failing, else the last decryption error
+    pub last_error: Option<String>,
+    pub decryption_status: Option<String>,
+    pub backoff: Option<BackoffView>,
+}
+
+impl ResourceView {
+    /// Build the view of a resource and its backoff state (if it is retrying)
+    pub fn new(config: &SecretManagerConfig, backoff: Option<&BackoffState>) -> Self {
+        let status = config.status.as_ref();
+        let failing = is_failing(config);
+        let mut secrets: Vec<String> = status
+            .and_then(|s| s.sync.as_ref())
+            .and_then(|sync| sync.secrets.as_ref())
+            .map(|secrets| secrets.keys().cloned().collect())
+            .unwrap_or_default();
+        secrets.sort();
+
+        let last_error = if failing {
+            status.and_then(|s| s.description.clone())
+        } else {
+            None
+        }
+        .or_else(|| status.and_then(|s| s.last_decryption_error.clone()));
+
+        Self {
+            namespace: config
+                .metadata
+                .namespace
+                .clone()
+                .unwrap_or_else(|| "default".to_string()),
+            name: config.metadata.name.clone().unwrap_or_default(),
+            provider: match &config.spec.provider {
+                ProviderConfig::Gcp(_) => "gcp",
+                ProviderConfig::Aws(_) => "aws",
+                ProviderConfig::Azure(_) => "azure",
+            },
+            source: format!(
+                "{}/{}/{}",
+                config.spec.source_ref.kind,
+                config.spec.source_ref.namespace,
+                config.spec.source_ref.name
+            ),
+            suspended: config.spec.suspend,
+            phase: status.and_then(|s| s.phase.clone()),
+            ready: is_ready(config),
+            failing,
+            description: status.and_then(|s| s.description.clone()),
+            generation: config.metadata.generation,
+            observed_generation: status.and_then(|s| s.observed_generation),
+            last_reconcile_time: status.and_then(|s| s.last_reconcile_time.clone()),
+            next_reconcile_time: status.and_then(|s| s.next_reconcile_time.clone()),
+            secrets_synced: status.and_then(|s| s.secrets_synced),
+            secrets,
+            failed_secrets: status
+                .and_then(|s| s.failed_secrets.clone())
+                .unwrap_or_default(),
+            failed_services: status
+                .and_then(|s| s.failed_services.clone())
+                .unwrap_or_default(),
+            last_error,
+            decryption_status: status.and_then(|s| s.decryption_status.clone()),
+            backoff: backoff.filter(|b| b.error_count > 0).map(|b| BackoffView {
+                error_count: b.error_count,
+                next_retry_time: b.next_retry_time.map(|t| t.to_rfc3339()),
+            }),
+        }
+    }
+}
+
+/// Compare two tokens without short-circuiting on the first differing byte
+fn tokens_match(expected: &str, provided: &str) -> bool {
+    let (expected, provided) = (expected.as_bytes(), provided.as_bytes());
+    expected.len() == provided.len()
+        && expected
+            .iter()
+            .zip(provided)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Check the `Authorization: Bearer` header against the configured token
+pub(crate) fn authorize(expected: Option<&str>, headers: &HeaderMap) -> Result<(), Response> {
+    let Some(expected) = expected else {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": format!("resources API disabled: set {RESOURCES_API_TOKEN_ENV} or {RESOURCES_API_TOKEN_FILE_ENV}")
+            })),
+        )
+            .into_response());
+    };
+    let provided = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    match provided {
+        Some(provided) if tokens_match(expected, provided) => Ok(()),
+        _ => Err((
+            StatusCode::UNAUTHORIZED,
+            [("www-authenticate", "Bearer")],
+            Json(serde_json::json!({ "error": "invalid or missing bearer token" })),
+        )
+            .into_response()),
+    }
+}
+
+/// Snapshot of the reconciler's backoff states, keyed by `namespace/name`
+fn backoff_snapshot(state: &ServerState) -> HashMap<String, BackoffState> {
+    state
+        .backoff_states
+        .get()
+        .and_then(|states| states.lock().ok().map(|s| s.clone()))
+        .unwrap_or_default()
+}
+
+/// Current resources from the watch cache, or `None` if the watch hasn't started yet
+async fn cached_resources(state: &ServerState) -> Option<Vec<Arc<SecretManagerConfig>>> {
+    state
+        .resource_store
+        .read()
+        .await
+        .as_ref()
+        .map(kube_runtime::reflector::Store::state)
+}
+
+fn watch_not_started() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(serde_json::json!({ "error": "controller watch has not started yet" })),
+    )
+        .into_response()
+}
+
+async fn list_resources_handler(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(response) = authorize(state.api_token.as_deref(), &headers) {
+        return response;
+    }
+    debug!("Resources API: list requested");
+    let Some(resources) = cached_resources(&state).await else {
+        return watch_not_started();
+    };
+    let backoff = backoff_snapshot(&state);
+
+    let mut views: Vec<ResourceView> = resources
+        .iter()
+        .map(|config| {
+            let key = format!(
+                "{}/{}",
+                config.metadata.namespace.as_deref().unwrap_or("default"),
+                config.metadata.name.as_deref().unwrap_or_default()
+            );
+            ResourceView::new(config, backoff.get(&key))
+        })
+        .collect();
+    views.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+
+    Json(serde_json::json!({ "items": views })).into_response()
+}
+
+async fn get_resource_handler(
+    State(state): State<Arc<ServerState>>,
+    Path((namespace, name)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(response) = authorize(state.api_token.as_deref(), &headers) {
+        return response;
+    }
+    debug!("Resources API: {}/{} requested", namespace, name);
+    let Some(resources) = cached_resources(&state).await else {
+        return watch_not_started();
+    };
+
+    let found = resources.iter().find(|config| {
+        config.metadata.namespace.as_deref() == Some(namespace.as_str())
+            && config.metadata.name.as_deref() == Some(name.as_str())
+    });
+    match found {
+        Some(config) => {
+            let backoff = backoff_snapshot(&state);
+            Json(ResourceView::new(
+                config,
+                backoff.get(&format!("{namespace}/{name}")),
+            ))
+            .into_response()
+        }
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": format!("SecretManagerConfig {namespace}/{name} not found")
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// Recent log events of a resource, oldest first
+/// Served even if the resource is gone, so the logs of a deleted resource can still be read
+async fn resource_logs_handler(
+    State(state): State<Arc<ServerState>>,
+    Path((namespace, name)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(response) = authorize(state.api_token.as_deref(), &headers) {
+        return response;
+    }
+    debug!("Resources API: logs of {}/{} requested", namespace, name);
+    if !state.resource_logs.is_enabled() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "resource log buffer disabled: RESOURCE_LOG_BUFFER_SIZE is 0"
+            })),
+        )
+            .into_response();
+    }
+
+    let events = state.resource_logs.events(&format!("{namespace}/{name}"));
+    Json(serde_json::json!({
+        "namespace": namespace,
+        "name": name,
+        "events": events,
+    }))
+    .into_response()
+}
+
+/// Routes for the resources API
+pub fn routes() -> Router<Arc<ServerState>> {
+    Router::new()
+        .route("/api/v1/resources", get(list_resources_handler))
+        .route(
+            "/api/v1/resources/{namespace}/{name}",
+            get(get_resource_handler),
+        )
+        .route(
+            "/api/v1/resources/{namespace}/{name}/logs",
+            get(resource_logs_handler),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::{ResourceSyncState, SecretManagerConfigStatus, SyncStatus};
+    use crate::testing::ConfigFixture;
+    use serde_json::json;
+
+    fn config() -> ConfigFixture {
+        ConfigFixture::new("my-secrets")
+            .with_generation(3)
+            .with_spec(json!({ "provider": { "type": "aws", "aws": { "region": "us-east-1" } } }))
+    }
+
+    #[test]
+    fn test_resource_view_failing_with_backoff() {
+        let mut synced = std::collections::HashMap::new();
+        for name in ["db-password", "api-key"] {
+            synced.insert(
+                name.to_string(),
+                ResourceSyncState {
+                    exists: true,
+                    update_count: 1,
+                    source_key: None,
+                    tier: None,
+                    expire_time: None,
+                },
+            );
+        }
+        let status = SecretManagerConfigStatus {
+            phase: Some("PartialFailure".to_string()),
+            description: Some(
+                "Synced 2 secrets, 1 failed: token; services failed: billing".to_string(),
+            ),
+            sync: Some(SyncStatus {
+                secrets: Some(synced),
+                properties: None,
+            }),
+            failed_secrets: Some(vec![FailedSecret::new("token", "access denied")]),
+            failed_services: Some(vec![FailedService::new("billing", "invalid env file")]),
+            ..Default::default()
+        };
+        let mut backoff = BackoffState::new();
+        backoff.increment_error();
+
+        let view = ResourceView::new(&config().with_status(status).build(), Some(&backoff));
+        assert_eq!(view.provider, "aws");
+        assert_eq!(view.source, "GitRepository/flux-system/repo");
+        assert!(view.failing);
+        assert!(!view.ready);
+        assert_eq!(view.secrets, vec!["api-key", "db-password"]);
+        assert_eq!(view.failed_secrets.len(), 1);
+        assert_eq!(view.failed_services[0].name, "billing");
+        assert_eq!(
+            view.last_error.as_deref(),
+            Some("Synced 2 secrets, 1 failed: token; services failed: billing")
+        );
+        assert_eq!(view.backoff.map(|b| b.error_count), Some(1));
+    }
+
+    #[test]
+    fn test_resource_view_without_status() {
+        let view = ResourceView::new(&config().build(), Some(&BackoffState::new()));
+        assert!(!view.failing);
+        assert!(view.phase.is_none());
+        assert!(view.secrets.is_empty());
+        // A backoff state with no errors is not reported
+        assert!(view.backoff.is_none());
+    }
+
+    #[test]
+    fn test_authorize() {
+        let mut headers = HeaderMap::new();
+        assert!(authorize(None, &headers).is_err());
+        assert!(authorize(Some("secret"), &headers).is_err());
+
+        headers.insert(
+            AUTHORIZATION,
+            "Bearer wrong".parse().unwrap_or_else(|_| panic!()),
+        );
+        assert!(authorize(Some("secret"), &headers).is_err());
+
+        headers.insert(
+            AUTHORIZATION,
+            "Bearer secret".parse().unwrap_or_else(|_| panic!()),
+        );
+        assert!(authorize(Some("secret"), &headers).is_ok());
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc", "abc"));
+        assert!(!tokens_match("abc", "abd"));
+        assert!(!tokens_match("abc", "abcd"));
+    }
+}
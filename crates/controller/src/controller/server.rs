@@ -5,19 +5,58 @@
 //! Provides endpoints:
 //! - `/metrics` - Prometheus metrics in text format
 //! - `/healthz` - Liveness probe (always returns 200)
-//! - `/readyz` - Readiness probe (returns 200 when controller is ready)
+//! - `/readyz` - Readiness probe (returns 200 when controller is ready) with a JSON summary of
+//!   resource sync health; `?strict=true` also returns 503 while any resource is failing
+//! - `/config` - Effective controller configuration as JSON (reflects hot-reloads)
+//! - `/api/v1/resources` and `/api/v1/resources/{namespace}/{name}` - Redacted per-resource
+//!   reconcile state for debugging (bearer token required, see `resources_api`)
+//! - `/debug/pprof/profile` and `/debug/pprof/heap` - CPU and heap profiles, only with the
+//!   `profiling` feature (bearer token required, see `profiling`)
 //!
 //! The server runs on port 5000 by default (configurable via `METRICS_PORT` environment variable).
 
-use axum::{Router, extract::State, http::StatusCode, response::IntoResponse, routing::get};
+use crate::config::{CONFIG_FILE_ENV, SharedControllerConfig, SharedServerConfig};
+use crate::controller::health::{SharedResourceStore, summarize_store};
+use crate::controller::reconciler::BackoffState;
+use crate::controller::resources_api;
+use crate::observability::resource_logs::ResourceLogBuffer;
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
 use prometheus::{Encoder, TextEncoder};
-use std::sync::Arc;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
 use tokio::net::TcpListener;
 use tracing::{debug, error, info};
 
 #[derive(Debug)]
 pub struct ServerState {
     pub is_ready: Arc<std::sync::atomic::AtomicBool>,
+    /// Shared controller configuration, served by `/config`
+    pub controller_config: SharedControllerConfig,
+    /// Shared server configuration, served by `/config`
+    pub server_config: SharedServerConfig,
+    /// Watch cache of SecretManagerConfig resources, summarized by `/readyz`
+    pub resource_store: SharedResourceStore,
+    /// Reconciler backoff states, set once the reconciler is created; served by `/api/v1/resources`
+    pub backoff_states: OnceLock<Arc<Mutex<HashMap<String, BackoffState>>>>,
+    /// Bearer token for `/api/v1/resources` (`None` disables the API)
+    pub api_token: Option<String>,
+    /// Recent log events per resource, served by `/api/v1/resources/{namespace}/{name}/logs`
+    pub resource_logs: Arc<ResourceLogBuffer>,
+}
+
+/// Query parameters for `/readyz`
+#[derive(Debug, Default, Deserialize)]
+struct ReadyzQuery {
+    /// Also report not-ready while any resource is failing to sync
+    #[serde(default)]
+    strict: bool,
 }
 
 pub async fn start_server(port: u16, state: Arc<ServerState>) -> Result<(), anyhow::Error> {
@@ -25,7 +64,11 @@ pub async fn start_server(port: u16, state: Arc<ServerState>) -> Result<(), anyh
         .route("/metrics", get(metrics_handler))
         .route("/healthz", get(healthz_handler))
         .route("/readyz", get(readyz_handler))
-        .with_state(state.clone());
+        .route("/config", get(config_handler))
+        .merge(resources_api::routes());
+    #[cfg(feature = "profiling")]
+    let app = app.merge(crate::controller::profiling::routes());
+    let app = app.with_state(state.clone());
 
     let addr = format!("0.0.0.0:{port}");
     let listener = TcpListener::bind(&addr).await?;
@@ -48,7 +91,26 @@ fn gather() -> Vec<prometheus::proto::MetricFamily> {
     REGISTRY.gather()
 }
 
-async fn metrics_handler() -> impl IntoResponse {
+async fn metrics_handler(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    // Drop per-resource series for deleted resources before exporting
+    if let Some(store) = state.resource_store.read().await.as_ref() {
+        let live: HashSet<(String, String)> = store
+            .state()
+            .iter()
+            .map(|config| {
+                (
+                    config
+                        .metadata
+                        .namespace
+                        .clone()
+                        .unwrap_or_else(|| "default".to_string()),
+                    config.metadata.name.clone().unwrap_or_default(),
+                )
+            })
+            .collect();
+        crate::observability::metrics::retain_resource_metrics(&live);
+    }
+
     let encoder = TextEncoder::new();
     let metric_families = gather();
 
@@ -74,12 +136,42 @@ async fn healthz_handler() -> impl IntoResponse {
     StatusCode::OK
 }
 
-async fn readyz_handler(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+async fn readyz_handler(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<ReadyzQuery>,
+) -> impl IntoResponse {
     let is_ready = state.is_ready.load(std::sync::atomic::Ordering::Relaxed);
-    debug!("Readiness check requested: ready={}", is_ready);
-    if is_ready {
+    let resources = summarize_store(&state.resource_store).await;
+    // Without strict, readiness only reflects the process so failing resources don't pull
+    // the pod out of service; strict callers (deploy gates, monitors) also require sync health
+    let resources_healthy = resources.as_ref().is_some_and(|r| r.is_healthy());
+    let ready = is_ready && (!query.strict || resources_healthy);
+    debug!(
+        "Readiness check requested: ready={}, strict={}, resources={:?}",
+        ready, query.strict, resources
+    );
+
+    let status = if ready {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
-    }
+    };
+    (
+        status,
+        Json(serde_json::json!({
+            "ready": ready,
+            "resources": resources,
+        })),
+    )
+}
+
+async fn config_handler(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    debug!("Effective configuration requested");
+    let controller = state.controller_config.read().await.clone();
+    let server = state.server_config.read().await.clone();
+    Json(serde_json::json!({
+        "configFile": std::env::var(CONFIG_FILE_ENV).ok(),
+        "controller": controller,
+        "server": server,
+    }))
 }
@@ -0,0 +1,259 @@
+//! # Admission Policy
+//!
+//! Generates a Kubernetes ValidatingAdmissionPolicy with CEL rules for SecretManagerConfig.
+//!
+//! The rules mirror the checks the controller runs before reconciling (interval formats and
+//! minimums, exactly one provider, required environment, `{key}` in name templates), so
+//! clusters reject bad resources at admission instead of reporting them in status later.
+//! Requires Kubernetes 1.30+ (ValidatingAdmissionPolicy v1).
+
+use crate::crd::patterns::DURATION_PATTERN;
+use crate::crd::{
+    SecretManagerConfig, default_git_repository_pull_interval, default_reconcile_interval,
+};
+use k8s_openapi::api::admissionregistration::v1::{
+    MatchResources, NamedRuleWithOperations, ValidatingAdmissionPolicy,
+    ValidatingAdmissionPolicyBinding, ValidatingAdmissionPolicyBindingSpec,
+    ValidatingAdmissionPolicySpec, Validation, Variable,
+};
+use kube::Resource;
+use kube::api::ObjectMeta;
+
+/// Name of the generated policy and its binding
+pub const ADMISSION_POLICY_NAME: &str = "secretmanagerconfig-validation";
+
+/// Settings the generated rules depend on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdmissionPolicyOptions {
+    /// Minimum `reconcileInterval` in seconds (`MIN_RECONCILE_INTERVAL_SECS`)
+    pub min_reconcile_interval_secs: u64,
+    /// Minimum `gitRepositoryPullInterval` in seconds (`MIN_GITREPOSITORY_PULL_INTERVAL_SECS`)
+    pub min_git_repository_pull_interval_secs: u64,
+}
+
+impl Default for AdmissionPolicyOptions {
+    fn default() -> Self {
+        Self {
+            min_reconcile_interval_secs: crate::constants::MIN_RECONCILE_INTERVAL_SECS,
+            min_git_repository_pull_interval_secs:
+                crate::constants::MIN_GITREPOSITORY_PULL_INTERVAL_SECS,
+        }
+    }
+}
+
+/// CEL variable (named after the field) holding a normalized duration, falling back to the CRD default
+fn duration_variable(field: &str, default: &str) -> Variable {
+    Variable {
+        name: field.to_string(),
+        expression: format!(
+            "has(object.spec.{field}) ? object.spec.{field}.trim().lowerAscii() : '{default}'"
+        ),
+    }
+}
+
+/// Format and minimum rules for a duration variable
+fn duration_validations(field: &str, min_secs: u64) -> [Validation; 2] {
+    let value = format!("variables.{field}");
+    let seconds = format!(
+        "int({value}.substring(0, size({value}) - 1)) * \
+         ({value}.endsWith('d') ? 86400 : {value}.endsWith('h') ? 3600 : {value}.endsWith('m') ? 60 : 1)"
+    );
+    [
+        Validation {
+            expression: format!("{value}.matches('{DURATION_PATTERN}')"),
+            message: Some(format!(
+                "spec.{field} must be <number><unit> with unit s, m, h or d (e.g. '1m', '5m', '1h')"
+            )),
+            reason: Some("Invalid".to_string()),
+            ..Validation::default()
+        },
+        Validation {
+            // Malformed values are reported by the format rule above
+            expression: format!(
+                "!{value}.matches('{DURATION_PATTERN}') || {seconds} >= {min_secs}"
+            ),
+            message: Some(format!("spec.{field} must be at least {min_secs}s")),
+            reason: Some("Invalid".to_string()),
+            ..Validation::default()
+        },
+    ]
+}
+
+/// CEL validations for SecretManagerConfig
+fn validations(options: &AdmissionPolicyOptions) -> Vec<Validation> {
+    let rule = |expression: String, message: &str| Validation {
+        expression,
+        message: Some(message.to_string()),
+        reason: Some("Invalid".to_string()),
+        ..Validation::default()
+    };
+
+    let mut validations = vec![];
+    validations.extend(duration_validations(
+        "reconcileInterval",
+        options.min_reconcile_interval_secs,
+    ));
+    validations.extend(duration_validations(
+        "gitRepositoryPullInterval",
+        options.min_git_repository_pull_interval_secs,
+    ));
+    validations.extend([
+        rule(
+            "[has(object.spec.provider.gcp), has(object.spec.provider.aws), \
+             has(object.spec.provider.azure)].filter(set, set).size() == 1"
+                .to_string(),
+            "spec.provider must set exactly one of gcp, aws or azure",
+        ),
+        rule(
+            "!has(object.spec.provider.type) || \
+             (object.spec.provider.type == 'gcp' ? has(object.spec.provider.gcp) : \
+             object.spec.provider.type == 'aws' ? has(object.spec.provider.aws) : \
+             has(object.spec.provider.azure))"
+                .to_string(),
+            "spec.provider.type must match the configured provider",
+        ),
+        rule(
+            "(has(object.spec.secrets.environments) && size(object.spec.secrets.environments) > 0) || \
+             (has(object.spec.secrets.environment) && object.spec.secrets.environment != '')"
+                .to_string(),
+            "spec.secrets.environment or spec.secrets.environments is required",
+        ),
+        rule(
+            "!has(object.spec.secrets.nameTemplate) || \
+             object.spec.secrets.nameTemplate.contains('{key}')"
+                .to_string(),
+            "spec.secrets.nameTemplate must contain {key} so each key maps to a distinct secret",
+        ),
+        rule(
+            "!has(object.spec.secrets.sanitization) || \
+             !has(object.spec.secrets.sanitization.replacement) || \
+             object.spec.secrets.sanitization.replacement in ['-', '_']"
+                .to_string(),
+            "spec.secrets.sanitization.replacement must be \"-\" or \"_\"",
+        ),
+    ]);
+    validations
+}
+
+/// ValidatingAdmissionPolicy rejecting invalid SecretManagerConfig resources on create/update
+#[must_use]
+pub fn validating_admission_policy(options: &AdmissionPolicyOptions) -> ValidatingAdmissionPolicy {
+    ValidatingAdmissionPolicy {
+        metadata: ObjectMeta {
+            name: Some(ADMISSION_POLICY_NAME.to_string()),
+            ..ObjectMeta::default()
+        },
+        spec: Some(ValidatingAdmissionPolicySpec {
+            failure_policy: Some("Fail".to_string()),
+            match_constraints: Some(MatchResources {
+                resource_rules: Some(vec![NamedRuleWithOperations {
+                    api_groups: Some(vec![SecretManagerConfig::group(&()).to_string()]),
+                    api_versions: Some(vec![SecretManagerConfig::version(&()).to_string()]),
+                    operations: Some(vec!["CREATE".to_string(), "UPDATE".to_string()]),
+                    resources: Some(vec![SecretManagerConfig::plural(&()).to_string()]),
+                    ..NamedRuleWithOperations::default()
+                }]),
+                ..MatchResources::default()
+            }),
+            variables: Some(vec![
+                duration_variable("reconcileInterval", default_reconcile_interval().as_str()),
+                duration_variable(
+                    "gitRepositoryPullInterval",
+                    default_git_repository_pull_interval().as_str(),
+                ),
+            ]),
+            validations: Some(validations(options)),
+            ..ValidatingAdmissionPolicySpec::default()
+        }),
+        ..ValidatingAdmissionPolicy::default()
+    }
+}
+
+/// Binding enforcing the policy in all namespaces
+/// `validation_actions` is usually `["Deny"]`; use `["Warn", "Audit"]` to roll out gradually
+#[must_use]
+pub fn validating_admission_policy_binding(
+    validation_actions: Vec<String>,
+) -> ValidatingAdmissionPolicyBinding {
+    ValidatingAdmissionPolicyBinding {
+        metadata: ObjectMeta {
+            name: Some(ADMISSION_POLICY_NAME.to_string()),
+            ..ObjectMeta::default()
+        },
+        spec: Some(ValidatingAdmissionPolicyBindingSpec {
+            policy_name: Some(ADMISSION_POLICY_NAME.to_string()),
+            validation_actions: Some(validation_actions),
+            ..ValidatingAdmissionPolicyBindingSpec::default()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::reconciler::validation::parse_kubernetes_duration;
+
+    #[test]
+    fn test_duration_pattern_matches_parser() {
+        let pattern =
+            regex::Regex::new(DURATION_PATTERN).unwrap_or_else(|e| panic!("invalid pattern: {e}"));
+        for value in [
+            "30s", "1m", "5M", " 2h ", "1d", "1", "m", "1.5h", "1h30m", "-1m", "",
+        ] {
+            let normalized = value.trim().to_lowercase();
+            assert_eq!(
+                pattern.is_match(&normalized),
+                parse_kubernetes_duration(value).is_ok(),
+                "pattern and parser disagree on '{value}'"
+            );
+        }
+    }
+
+    #[test]
+    fn test_policy_matches_secretmanagerconfigs() {
+        let policy = validating_admission_policy(&AdmissionPolicyOptions::default());
+        let spec = policy.spec.unwrap_or_else(|| panic!("policy has no spec"));
+        let rules = spec
+            .match_constraints
+            .and_then(|m| m.resource_rules)
+            .unwrap_or_default();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(
+            rules[0].api_groups.as_deref(),
+            Some(&["secret-management.octopilot.io".to_string()][..])
+        );
+        assert_eq!(
+            rules[0].resources.as_deref(),
+            Some(&["secretmanagerconfigs".to_string()][..])
+        );
+        assert_eq!(spec.failure_policy.as_deref(), Some("Fail"));
+    }
+
+    #[test]
+    fn test_policy_uses_interval_minimums() {
+        let options = AdmissionPolicyOptions {
+            min_reconcile_interval_secs: 120,
+            min_git_repository_pull_interval_secs: 300,
+        };
+        let validations = validations(&options);
+        assert!(validations.iter().any(|v| {
+            v.expression.contains("variables.reconcileInterval") && v.expression.ends_with(">= 120")
+        }));
+        assert!(validations.iter().any(|v| {
+            v.expression.contains("variables.gitRepositoryPullInterval")
+                && v.expression.ends_with(">= 300")
+        }));
+        // Every rule explains itself
+        assert!(validations.iter().all(|v| v.message.is_some()));
+    }
+
+    #[test]
+    fn test_binding_references_policy() {
+        let binding = validating_admission_policy_binding(vec!["Deny".to_string()]);
+        let spec = binding
+            .spec
+            .unwrap_or_else(|| panic!("binding has no spec"));
+        assert_eq!(spec.policy_name.as_deref(), Some(ADMISSION_POLICY_NAME));
+        assert_eq!(spec.validation_actions, Some(vec!["Deny".to_string()]));
+    }
+}
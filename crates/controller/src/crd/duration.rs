@@ -0,0 +1,138 @@
+//! # Duration Fields
+//!
+//! `KubeDuration` is the type of every duration field in the spec (`reconcileInterval`,
+//! `gitRepositoryPullInterval`, `versionRetention.destroyOlderThan`).
+//!
+//! The schema carries `DURATION_PATTERN`, so the API server rejects malformed values at
+//! admission. Deserialization still accepts any string: resources stored before the pattern
+//! existed must not break the watch. The reconciler parses the value up front and reports
+//! a malformed or too-short duration as a terminal `InvalidSpec` condition.
+
+use anyhow::Result;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::time::Duration;
+
+use crate::controller::reconciler::validation::{
+    parse_kubernetes_duration, validate_duration_interval,
+};
+
+/// Kubernetes duration string with a single unit, e.g. "30s", "5m", "1h", "7d"
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct KubeDuration(String);
+
+impl KubeDuration {
+    #[must_use]
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// The duration as written in the spec
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Parse the duration
+    /// # Errors
+    /// Returns an error if the value doesn't match the duration format
+    pub fn to_duration(&self) -> Result<Duration> {
+        parse_kubernetes_duration(&self.0)
+    }
+
+    /// Parse the duration and enforce a minimum
+    /// # Errors
+    /// Returns an error naming `field_name` if the value is malformed or below `min_seconds`
+    pub fn to_duration_at_least(&self, field_name: &str, min_seconds: u64) -> Result<Duration> {
+        validate_duration_interval(&self.0, field_name, min_seconds)?;
+        self.to_duration()
+    }
+}
+
+impl fmt::Display for KubeDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for KubeDuration {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for KubeDuration {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl PartialEq<str> for KubeDuration {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for KubeDuration {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl JsonSchema for KubeDuration {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("KubeDuration")
+    }
+
+    // Inlined so each field's schema carries the pattern (CRD schemas can't use $ref)
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        crate::crd::patterns::duration_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kube_duration_parses() {
+        let duration = KubeDuration::from("5m");
+        assert_eq!(duration, "5m");
+        assert_eq!(duration.to_string(), "5m");
+        assert_eq!(duration.to_duration().ok(), Some(Duration::from_secs(300)));
+        assert!(KubeDuration::from("5 minutes").to_duration().is_err());
+    }
+
+    #[test]
+    fn test_kube_duration_minimum() {
+        let duration = KubeDuration::from("30s");
+        assert!(
+            duration
+                .to_duration_at_least("reconcileInterval", 60)
+                .is_err()
+        );
+        assert_eq!(
+            duration.to_duration_at_least("reconcileInterval", 30).ok(),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn test_kube_duration_deserializes_any_string() {
+        let duration: KubeDuration = serde_json::from_value(serde_json::json!("soon"))
+            .unwrap_or_else(|e| panic!("deserialize: {e}"));
+        assert_eq!(duration, "soon");
+        assert!(duration.to_duration().is_err());
+        assert_eq!(
+            serde_json::to_value(&duration).ok(),
+            Some(serde_json::json!("soon"))
+        );
+    }
+}
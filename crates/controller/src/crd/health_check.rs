@@ -0,0 +1,259 @@
+//! # Health Checks
+//!
+//! How GitOps tools read the health of a SecretManagerConfig from its status.
+//!
+//! Flux health checks use kstatus, which only looks at `observedGeneration` and the
+//! `Reconciling`/`Stalled` conditions; [`kstatus`] computes the same result so `msmctl status`
+//! shows what Flux will see. Argo CD has no generic health check for custom resources, so
+//! [`argo_health_lua`] generates the `argocd-cm` health customization from the same conditions.
+
+use crate::crd::{Condition, SecretManagerConfig};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::Resource;
+use kube::api::ObjectMeta;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Name of Argo CD's settings ConfigMap
+pub const ARGOCD_CONFIG_MAP: &str = "argocd-cm";
+
+/// kstatus status of a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KStatus {
+    /// The last reconcile of the current generation finished
+    Current,
+    /// The controller hasn't finished reconciling the current generation
+    InProgress,
+    /// The resource can't progress without a change (`Stalled`)
+    Failed,
+}
+
+impl KStatus {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Current => "Current",
+            Self::InProgress => "InProgress",
+            Self::Failed => "Failed",
+        }
+    }
+}
+
+impl fmt::Display for KStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn condition_true(conditions: &[Condition], r#type: &str) -> bool {
+    conditions
+        .iter()
+        .any(|c| c.r#type == r#type && c.status == "True")
+}
+
+/// kstatus status of a SecretManagerConfig, as computed by Flux health checks
+/// Suspended resources are `Current`: nothing is in progress and nothing failed.
+#[must_use]
+pub fn kstatus(config: &SecretManagerConfig) -> KStatus {
+    let Some(status) = config.status.as_ref() else {
+        return KStatus::InProgress;
+    };
+    if let Some(generation) = config.metadata.generation
+        && status
+            .observed_generation
+            .is_none_or(|observed| observed < generation)
+    {
+        return KStatus::InProgress;
+    }
+    if condition_true(&status.conditions, "Reconciling") {
+        KStatus::InProgress
+    } else if condition_true(&status.conditions, "Stalled") {
+        KStatus::Failed
+    } else {
+        KStatus::Current
+    }
+}
+
+/// `argocd-cm` key of the SecretManagerConfig health customization
+#[must_use]
+pub fn argo_health_key() -> String {
+    format!(
+        "resource.customizations.health.{}_{}",
+        SecretManagerConfig::group(&()),
+        SecretManagerConfig::kind(&())
+    )
+}
+
+/// Argo CD health check (Lua) for SecretManagerConfig
+///
+/// Maps the standard conditions in the same order as [`kstatus`]: an unobserved generation or
+/// `Reconciling` is Progressing (even when `Stalled` is also set), `Stalled` is Degraded, `Ready` is Healthy, and the `Suspended` reason is
+/// Suspended.
+#[must_use]
+pub fn argo_health_lua() -> String {
+    r#"hs = {status = "Progressing", message = "Waiting for the controller to reconcile"}
+if obj.status == nil then
+  return hs
+end
+if obj.metadata.generation ~= nil and (obj.status.observedGeneration == nil or obj.status.observedGeneration < obj.metadata.generation) then
+  return hs
+end
+local reconciling = nil
+local stalled = nil
+local ready = nil
+if obj.status.conditions ~= nil then
+  for _, c in ipairs(obj.status.conditions) do
+    if c.type == "Reconciling" and c.status == "True" then
+      reconciling = c
+    elseif c.type == "Stalled" and c.status == "True" then
+      stalled = c
+    elseif c.type == "Ready" then
+      ready = c
+    end
+  end
+end
+if reconciling ~= nil then
+  return {status = "Progressing", message = reconciling.message}
+end
+if stalled ~= nil then
+  return {status = "Degraded", message = stalled.message}
+end
+if ready == nil then
+  return hs
+end
+if ready.status == "True" then
+  return {status = "Healthy", message = ready.message}
+end
+if ready.reason == "Suspended" then
+  return {status = "Suspended", message = ready.message}
+end
+if ready.status == "False" then
+  return {status = "Degraded", message = ready.message}
+end
+return {status = "Progressing", message = ready.message}
+"#
+    .to_string()
+}
+
+/// `argocd-cm` ConfigMap holding only the health customization, to merge into the existing
+/// ConfigMap (`kubectl patch --type merge`)
+#[must_use]
+pub fn argo_health_config_map(argocd_namespace: &str) -> ConfigMap {
+    ConfigMap {
+        metadata: ObjectMeta {
+            name: Some(ARGOCD_CONFIG_MAP.to_string()),
+            namespace: Some(argocd_namespace.to_string()),
+            ..ObjectMeta::default()
+        },
+        data: Some(BTreeMap::from([(argo_health_key(), argo_health_lua())])),
+        ..ConfigMap::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::reconciler::status::{ConditionReason, phase_conditions};
+    use crate::crd::SecretManagerConfigStatus;
+    use crate::testing::ConfigFixture;
+
+    /// Status written by a phase update of the current generation
+    fn in_phase(phase: &str, reason: Option<ConditionReason>) -> SecretManagerConfig {
+        let mut config = ConfigFixture::new("test").with_generation(2).build();
+        let reason = reason.unwrap_or_else(|| ConditionReason::for_phase(phase));
+        config.status = Some(SecretManagerConfigStatus {
+            phase: Some(phase.to_string()),
+            conditions: phase_conditions(&config, phase, reason, None),
+            observed_generation: config.metadata.generation,
+            ..SecretManagerConfigStatus::default()
+        });
+        config
+    }
+
+    #[test]
+    fn test_kstatus_of_phases() {
+        for (phase, expected) in [
+            ("Started", KStatus::InProgress),
+            ("Cloning", KStatus::InProgress),
+            ("Pending", KStatus::InProgress),
+            ("Updating", KStatus::InProgress),
+            ("Retrying", KStatus::InProgress),
+            ("Ready", KStatus::Current),
+            ("Suspended", KStatus::Current),
+            ("Failed", KStatus::Failed),
+            ("PartialFailure", KStatus::Failed),
+            ("AwaitingApproval", KStatus::Failed),
+        ] {
+            assert_eq!(kstatus(&in_phase(phase, None)), expected, "phase {phase}");
+        }
+    }
+
+    #[test]
+    fn test_kstatus_waits_for_current_generation() {
+        assert_eq!(
+            kstatus(&ConfigFixture::new("test").with_generation(2).build()),
+            KStatus::InProgress
+        );
+
+        let mut stale = in_phase("Ready", None);
+        stale.metadata.generation = Some(3);
+        assert_eq!(kstatus(&stale), KStatus::InProgress);
+
+        // Retrying after a failure clears Stalled and sets Reconciling
+        let mut retrying = in_phase("Failed", Some(ConditionReason::SourceNotReady));
+        let conditions = phase_conditions(
+            &retrying,
+            "Retrying",
+            ConditionReason::ProviderThrottled,
+            None,
+        );
+        if let Some(status) = retrying.status.as_mut() {
+            status.conditions = conditions;
+        }
+        assert_eq!(kstatus(&retrying), KStatus::InProgress);
+    }
+
+    #[test]
+    fn test_reconciling_takes_precedence_over_stalled() {
+        let mut config = in_phase("Failed", None);
+        if let Some(status) = config.status.as_mut() {
+            status.conditions.push(Condition {
+                r#type: "Reconciling".to_string(),
+                status: "True".to_string(),
+                observed_generation: config.metadata.generation,
+                last_transition_time: None,
+                reason: Some("Progressing".to_string()),
+                message: Some("Retrying".to_string()),
+            });
+        }
+        assert_eq!(kstatus(&config), KStatus::InProgress);
+
+        // The Lua checks Reconciling before Stalled, whatever order the conditions are in
+        let lua = argo_health_lua();
+        let position = |needle: &str| {
+            lua.find(needle)
+                .unwrap_or_else(|| panic!("{needle} missing from the health check"))
+        };
+        assert!(
+            position("if reconciling ~= nil") < position("if stalled ~= nil"),
+            "Stalled checked before Reconciling"
+        );
+    }
+
+    #[test]
+    fn test_argo_health_config_map() {
+        assert_eq!(
+            argo_health_key(),
+            "resource.customizations.health.secret-management.octopilot.io_SecretManagerConfig"
+        );
+        let config_map = argo_health_config_map("argocd");
+        assert_eq!(config_map.metadata.name.as_deref(), Some(ARGOCD_CONFIG_MAP));
+        let lua = config_map
+            .data
+            .and_then(|data| data.get(&argo_health_key()).cloned())
+            .unwrap_or_else(|| panic!("health customization missing"));
+        for status in ["Healthy", "Progressing", "Degraded", "Suspended"] {
+            assert!(lua.contains(status), "{status} not mapped");
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! # Install Manifests
+//!
+//! The CRD, RBAC, and deployment manifests installing the controller, as printed by
+//! `secret-manager-controller crd generate` and `msmctl install`.
+//!
+//! The CRD is generated from [`SecretManagerConfig`], so it always matches the compiled schema;
+//! `config/crd/secretmanagerconfig.yaml` is a copy of it, checked by the tests below. RBAC,
+//! deployment, and service manifests are embedded from `config/` when the binary is built.
+
+use super::SecretManagerConfig;
+use anyhow::{Context, Result};
+use kube::core::CustomResourceExt;
+
+/// Namespace the embedded manifests are written for
+pub const DEFAULT_INSTALL_NAMESPACE: &str = "octopilot-system";
+
+/// Manifests embedded from `config/`, in apply order (after the CRD)
+const STATIC_MANIFESTS: &[&str] = &[
+    include_str!("../../../../config/namespace.yaml"),
+    include_str!("../../../../config/rbac/serviceaccount.yaml"),
+    include_str!("../../../../config/rbac/role.yaml"),
+    include_str!("../../../../config/rbac/rolebinding.yaml"),
+    include_str!("../../../../config/rbac/clusterrole.yaml"),
+    include_str!("../../../../config/rbac/clusterrolebinding.yaml"),
+    include_str!("../../../../config/deployment/deployment.yaml"),
+    include_str!("../../../../config/service/metrics-service.yaml"),
+];
+
+/// CRD YAML generated from the Rust types, with the header of `config/crd/`
+pub fn crd_yaml() -> Result<String> {
+    let yaml = serde_yaml::to_string(&SecretManagerConfig::crd())
+        .context("Failed to serialize CRD to YAML")?;
+    Ok(format!(
+        "# This file is auto-generated by crdgen\n\
+         # DO NOT EDIT THIS FILE MANUALLY\n\
+         # If there are malformed YAML issues, fix them in the Rust code (src/crd/mod.rs)\n\
+         # This file will be overwritten on every code update\n\
+         #\n\
+         ---\n\
+         {yaml}"
+    ))
+}
+
+/// All install manifests for `namespace`, CRD first
+pub fn install_manifests(namespace: &str) -> Result<Vec<String>> {
+    let mut manifests = vec![crd_yaml()?];
+    manifests.extend(
+        STATIC_MANIFESTS
+            .iter()
+            .map(|manifest| manifest.replace(DEFAULT_INSTALL_NAMESPACE, namespace)),
+    );
+    Ok(manifests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> serde_yaml::Value {
+        serde_yaml::from_str(yaml).unwrap_or_else(|e| panic!("Failed to parse YAML: {e}"))
+    }
+
+    #[test]
+    fn test_checked_in_crd_matches_generated() {
+        let generated = crd_yaml().unwrap_or_else(|e| panic!("Failed to generate CRD: {e}"));
+        assert!(
+            parse(&generated)
+                == parse(include_str!(
+                    "../../../../config/crd/secretmanagerconfig.yaml"
+                )),
+            "config/crd/secretmanagerconfig.yaml is stale; regenerate it with \
+             `cargo run -p controller --bin secret-manager-controller -- crd generate`"
+        );
+    }
+
+    #[test]
+    fn test_install_manifests_use_namespace() {
+        let manifests =
+            install_manifests("team-a").unwrap_or_else(|e| panic!("Failed to render: {e}"));
+        assert_eq!(manifests.len(), STATIC_MANIFESTS.len() + 1);
+        assert_eq!(
+            parse(&manifests[0])["kind"].as_str(),
+            Some("CustomResourceDefinition")
+        );
+        assert!(
+            manifests[1..]
+                .iter()
+                .all(|manifest| !manifest.contains(DEFAULT_INSTALL_NAMESPACE))
+        );
+    }
+}
@@ -12,17 +12,28 @@
 //! - `source.rs` - Source references and secrets/configs configuration
 //! - `status.rs` - Status types for tracking reconciliation state
 //! - `otel.rs` - OpenTelemetry configuration
+//! - `admission_policy.rs` - ValidatingAdmissionPolicy (CEL) generation
+//! - `manifests.rs` - Install manifests (generated CRD, embedded RBAC and deployment)
+//! - `health_check.rs` - kstatus and Argo CD health mapping of the status conditions
+//! - `patterns.rs` - Format patterns shared by the CRD schema and validation
+//! - `duration.rs` - Typed duration fields
 
+pub mod admission_policy;
+mod duration;
+pub mod health_check;
 mod hot_reload;
 mod logging;
+pub mod manifests;
 mod notifications;
 mod otel;
+pub mod patterns;
 mod provider;
 mod source;
 mod spec;
 mod status;
 
 // Re-export all public types
+pub use duration::KubeDuration;
 pub use hot_reload::HotReloadConfig;
 pub use logging::{LogLevel, LoggingConfig};
 pub use notifications::{
@@ -31,13 +42,24 @@ pub use notifications::{
 };
 pub use otel::OtelConfig;
 pub use provider::{
-    AwsAuthConfig, AwsConfig, AzureAuthConfig, AzureConfig, GcpAuthConfig, GcpConfig,
-    ProviderConfig,
+    AwsAuthConfig, AwsConfig, AwsRouteTarget, AwsVersionStagesConfig, AzureAuthConfig,
+    AzureCertificateSecretRef, AzureCertificatesConfig, AzureConfig, AzureEnvironment,
+    AzurePrincipalType, AzureRoleAssignmentPrincipal, AzureRoleAssignmentScope,
+    AzureRoleAssignmentsConfig, AzureRouteTarget, GcpAuthConfig, GcpConfig, GcpEndpointOverride,
+    GcpRouteTarget, GcpVersionRetention, ProviderConfig, ProviderRateLimit,
+};
+pub use source::{
+    AwsConfigsConfig, AzureConfigsConfig, ConfigStoreType, ConfigsConfig, GitCredentialsRef,
+    KeyExpiration, NameSanitizationConfig, ParameterPolicies, ParameterTier, SecretExpiration,
+    SecretGrouping, SecretLimits, SecretPolicy, SecretRoute, SecretsConfig, SopsKeySecretRef,
+    SourceRef,
 };
-pub use source::{ConfigStoreType, ConfigsConfig, GitCredentialsRef, SecretsConfig, SourceRef};
 pub use spec::{
-    SecretManagerConfig, SecretManagerConfigSpec, default_false,
+    ApprovalPolicy, SecretManagerConfig, SecretManagerConfigSpec, default_false,
     default_git_repository_pull_interval, default_reconcile_interval, default_source_kind,
     default_true,
 };
-pub use status::{Condition, ResourceSyncState, SecretManagerConfigStatus, SyncStatus};
+pub use status::{
+    Condition, FailedSecret, FailedService, PendingChanges, PlannedOperation, ReconcileRecord,
+    ResourceSyncState, SecretManagerConfigStatus, SyncStatus,
+};
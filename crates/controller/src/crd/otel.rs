@@ -5,6 +5,7 @@
 use schemars::{JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 /// OpenTelemetry configuration
 /// Supports both OTLP exporter and Datadog direct export
@@ -24,6 +25,12 @@ pub enum OtelConfig {
         /// Deployment environment (e.g., "dev", "prod")
         #[serde(default)]
         environment: Option<String>,
+        /// Fraction of this resource's reconciliations to trace, from 0.0 to 1.0 (defaults to 1.0)
+        #[serde(default, rename = "samplingRatio")]
+        sampling_ratio: Option<f64>,
+        /// Extra attributes recorded on this resource's reconcile spans (e.g. team, service tier)
+        #[serde(default, rename = "resourceAttributes")]
+        resource_attributes: BTreeMap<String, String>,
     },
     /// Use Datadog OpenTelemetry exporter (direct to Datadog)
     Datadog {
@@ -44,9 +51,44 @@ pub enum OtelConfig {
         /// If not specified, uses DD_API_KEY environment variable
         #[serde(default, rename = "apiKey")]
         api_key: Option<String>,
+        /// Fraction of this resource's reconciliations to trace, from 0.0 to 1.0 (defaults to 1.0)
+        #[serde(default, rename = "samplingRatio")]
+        sampling_ratio: Option<f64>,
+        /// Extra attributes recorded on this resource's reconcile spans (e.g. team, service tier)
+        #[serde(default, rename = "resourceAttributes")]
+        resource_attributes: BTreeMap<String, String>,
     },
 }
 
+impl OtelConfig {
+    /// Fraction of reconciliations to trace, clamped to 0.0..=1.0
+    #[must_use]
+    pub fn sampling_ratio(&self) -> f64 {
+        let (Self::Otlp { sampling_ratio, .. } | Self::Datadog { sampling_ratio, .. }) = self;
+        sampling_ratio.map_or(1.0, |ratio| {
+            if ratio.is_nan() {
+                1.0
+            } else {
+                ratio.clamp(0.0, 1.0)
+            }
+        })
+    }
+
+    /// Extra attributes for reconcile spans
+    #[must_use]
+    pub fn resource_attributes(&self) -> &BTreeMap<String, String> {
+        let (Self::Otlp {
+            resource_attributes,
+            ..
+        }
+        | Self::Datadog {
+            resource_attributes,
+            ..
+        }) = self;
+        resource_attributes
+    }
+}
+
 impl JsonSchema for OtelConfig {
     fn schema_name() -> Cow<'static, str> {
         Cow::Borrowed("OtelConfig")
@@ -88,6 +130,17 @@ impl JsonSchema for OtelConfig {
                 "apiKey": {
                     "type": "string",
                     "description": "Datadog API key - used when type is 'datadog'"
+                },
+                "samplingRatio": {
+                    "type": "number",
+                    "minimum": 0.0,
+                    "maximum": 1.0,
+                    "description": "Fraction of this resource's reconciliations to trace, from 0.0 to 1.0 (defaults to 1.0)"
+                },
+                "resourceAttributes": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Extra attributes recorded on this resource's reconcile spans (e.g. team, service tier)"
                 }
             },
             "required": ["type"]
@@ -0,0 +1,275 @@
+//! # Validation Patterns
+//!
+//! Format rules shared by the CRD schema and the controller's validation.
+//!
+//! The schema embeds these patterns so the API server rejects malformed values at admission,
+//! and the validators in `controller::reconciler::validation` match against the same
+//! constants, so the two can't drift apart. Patterns are case-insensitive where the
+//! validators are, and don't use lookarounds (Kubernetes evaluates them with Go's RE2).
+
+use schemars::{Schema, SchemaGenerator};
+
+/// Duration with a single unit (s, m, h, d) and a positive amount, e.g. "30s", "5m", "1h"
+pub const DURATION_PATTERN: &str = "^0*[1-9][0-9]*[smhdSMHD]$";
+
+/// Supported `sourceRef.kind` values (case-sensitive)
+pub const SOURCE_REF_KINDS: [&str; 4] = ["GitRepository", "Application", "GitDirect", "Secret"];
+
+/// GCP project ID: 6-30 characters, lowercase letters/digits/hyphens, starts with a letter,
+/// doesn't end with a hyphen
+/// Reference: https://cloud.google.com/resource-manager/docs/creating-managing-projects
+pub const GCP_PROJECT_ID_PATTERN: &str = "^[a-z][a-z0-9-]{4,28}[a-z0-9]$";
+
+/// GCP location: [continent]-[direction][number], e.g. us-central1
+pub const GCP_LOCATION_PATTERN: &str = "^[a-zA-Z]+-[a-zA-Z]+[0-9]+$";
+
+/// AWS region: standard (us-east-1, cn-north-1), gov (us-gov-west-1), iso (us-iso-east-1),
+/// or "local" for localstack
+pub const AWS_REGION_PATTERN: &str =
+    "^[a-zA-Z]{2}(-[gG][oO][vV]|-[iI][sS][oO])?-[a-zA-Z]+-[0-9]+$|^[lL][oO][cC][aA][lL]$";
+
+/// IAM role ARN in any partition, e.g. arn:aws:iam::123456789012:role/my-role
+pub const AWS_ROLE_ARN_PATTERN: &str =
+    "^arn:aws(-[a-z]+)*:iam::[0-9]{12}:role/[A-Za-z0-9+=,.@_/-]+$";
+
+/// GCP service account email, e.g. my-sa@my-project.iam.gserviceaccount.com or
+/// 123456789012-compute@developer.gserviceaccount.com
+pub const GCP_SERVICE_ACCOUNT_EMAIL_PATTERN: &str =
+    "^[a-z0-9][a-z0-9-]{0,62}@[a-z0-9][a-z0-9.-]*\\.gserviceaccount\\.com$";
+
+/// Azure location: [direction][region][number], e.g. eastus, westus2
+pub const AZURE_LOCATION_PATTERN: &str = "^[a-zA-Z]+[0-9]*$";
+
+/// Azure Key Vault name: starts with a letter, alphanumerics and single hyphens, doesn't end
+/// with a hyphen (length is bounded separately)
+/// Reference: https://learn.microsoft.com/en-us/azure/key-vault/general/about-keys-secrets-certificates#vault-name
+pub const AZURE_VAULT_NAME_PATTERN: &str = "^[a-zA-Z](-?[a-zA-Z0-9])*$";
+pub const AZURE_VAULT_NAME_MIN_LEN: usize = 3;
+pub const AZURE_VAULT_NAME_MAX_LEN: usize = 24;
+
+/// Entra tenant: a GUID or a verified domain, e.g. contoso.onmicrosoft.com
+pub const AZURE_TENANT_ID_PATTERN: &str = "^[0-9a-fA-F]{8}(-[0-9a-fA-F]{4}){3}-[0-9a-fA-F]{12}$|^[a-zA-Z0-9]([a-zA-Z0-9-]*[a-zA-Z0-9])?(\\.[a-zA-Z0-9]([a-zA-Z0-9-]*[a-zA-Z0-9])?)+$";
+
+/// Azure subscription or object ID (GUID)
+pub const AZURE_GUID_PATTERN: &str = "^[0-9a-fA-F]{8}(-[0-9a-fA-F]{4}){3}-[0-9a-fA-F]{12}$";
+
+/// Azure resource group: 1-90 alphanumerics, underscores, hyphens, periods and parentheses,
+/// not ending with a period
+/// Reference: https://learn.microsoft.com/en-us/azure/azure-resource-manager/management/resource-name-rules
+pub const AZURE_RESOURCE_GROUP_PATTERN: &str = "^[-\\w.()]{0,89}[-\\w()]$";
+
+/// Whether `value` matches one of the patterns above
+/// Patterns are constants, so a compile failure is a bug caught by the tests
+#[must_use]
+pub fn matches(pattern: &str, value: &str) -> bool {
+    regex::Regex::new(pattern).is_ok_and(|regex| regex.is_match(value))
+}
+
+fn string_schema(value: serde_json::Value, name: &str) -> Schema {
+    Schema::try_from(value).unwrap_or_else(|_| panic!("Failed to create Schema for {name}"))
+}
+
+/// Schema for duration fields (`KubeDuration`)
+pub fn duration_schema(_gen: &mut SchemaGenerator) -> Schema {
+    string_schema(
+        serde_json::json!({ "type": "string", "pattern": DURATION_PATTERN }),
+        "duration",
+    )
+}
+
+/// Schema for `sourceRef.kind`
+pub fn source_ref_kind_schema(_gen: &mut SchemaGenerator) -> Schema {
+    string_schema(
+        serde_json::json!({ "type": "string", "enum": SOURCE_REF_KINDS }),
+        "sourceRef.kind",
+    )
+}
+
+/// Schema for `provider.gcp.projectId`
+pub fn gcp_project_id_schema(_gen: &mut SchemaGenerator) -> Schema {
+    string_schema(
+        serde_json::json!({ "type": "string", "pattern": GCP_PROJECT_ID_PATTERN }),
+        "provider.gcp.projectId",
+    )
+}
+
+/// Schema for `provider.azure.vaultName`
+pub fn azure_vault_name_schema(_gen: &mut SchemaGenerator) -> Schema {
+    string_schema(
+        serde_json::json!({
+            "type": "string",
+            "pattern": AZURE_VAULT_NAME_PATTERN,
+            "minLength": AZURE_VAULT_NAME_MIN_LEN,
+            "maxLength": AZURE_VAULT_NAME_MAX_LEN,
+        }),
+        "provider.azure.vaultName",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_compile() {
+        for pattern in [
+            DURATION_PATTERN,
+            GCP_PROJECT_ID_PATTERN,
+            GCP_LOCATION_PATTERN,
+            AWS_REGION_PATTERN,
+            AWS_ROLE_ARN_PATTERN,
+            AZURE_LOCATION_PATTERN,
+            AZURE_VAULT_NAME_PATTERN,
+            AZURE_TENANT_ID_PATTERN,
+            AZURE_GUID_PATTERN,
+            AZURE_RESOURCE_GROUP_PATTERN,
+        ] {
+            assert!(
+                regex::Regex::new(pattern).is_ok(),
+                "invalid pattern {pattern}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_duration_pattern() {
+        for valid in ["30s", "1m", "5M", "1h", "7d", "010m"] {
+            assert!(matches(DURATION_PATTERN, valid), "'{valid}' should match");
+        }
+        for invalid in ["0s", "00m", "1", "m", "1.5h", "1h30m", "-1m", "1w", ""] {
+            assert!(
+                !matches(DURATION_PATTERN, invalid),
+                "'{invalid}' should not match"
+            );
+        }
+    }
+
+    #[test]
+    fn test_aws_role_arn_pattern() {
+        for valid in [
+            "arn:aws:iam::123456789012:role/my-role",
+            "arn:aws-us-gov:iam::123456789012:role/path/to/role",
+            "arn:aws-cn:iam::123456789012:role/role+name=a,b.c@d_e",
+        ] {
+            assert!(
+                matches(AWS_ROLE_ARN_PATTERN, valid),
+                "'{valid}' should match"
+            );
+        }
+        for invalid in [
+            "arn:aws:iam::12345:role/my-role",
+            "arn:aws:iam::123456789012:user/my-user",
+            "arn:aws:s3:::bucket",
+            "my-role",
+        ] {
+            assert!(
+                !matches(AWS_ROLE_ARN_PATTERN, invalid),
+                "'{invalid}' should not match"
+            );
+        }
+    }
+
+    #[test]
+    fn test_gcp_service_account_email_pattern() {
+        for valid in [
+            "my-sa@my-project.iam.gserviceaccount.com",
+            "123456789012-compute@developer.gserviceaccount.com",
+        ] {
+            assert!(
+                matches(GCP_SERVICE_ACCOUNT_EMAIL_PATTERN, valid),
+                "'{valid}' should match"
+            );
+        }
+        for invalid in [
+            "my-sa",
+            "my-sa@example.com",
+            "My-SA@my-project.iam.gserviceaccount.com",
+            "my-sa@my-project.iam.gserviceaccount.com.evil",
+        ] {
+            assert!(
+                !matches(GCP_SERVICE_ACCOUNT_EMAIL_PATTERN, invalid),
+                "'{invalid}' should not match"
+            );
+        }
+    }
+
+    #[test]
+    fn test_azure_vault_name_pattern() {
+        for valid in ["my-vault", "Vault1", "a-b-c"] {
+            assert!(
+                matches(AZURE_VAULT_NAME_PATTERN, valid),
+                "'{valid}' should match"
+            );
+        }
+        for invalid in ["1vault", "my--vault", "vault-", "-vault", "my_vault"] {
+            assert!(
+                !matches(AZURE_VAULT_NAME_PATTERN, invalid),
+                "'{invalid}' should not match"
+            );
+        }
+    }
+
+    #[test]
+    fn test_azure_tenant_id_pattern() {
+        for valid in [
+            "72f988bf-86f1-41af-91ab-2d7cd011db47",
+            "72F988BF-86F1-41AF-91AB-2D7CD011DB47",
+            "contoso.onmicrosoft.com",
+            "contoso.com",
+        ] {
+            assert!(
+                matches(AZURE_TENANT_ID_PATTERN, valid),
+                "'{valid}' should match"
+            );
+        }
+        for invalid in [
+            "",
+            "72f988bf-86f1-41af-91ab",
+            "contoso",
+            "contoso.com/",
+            "-contoso.com",
+        ] {
+            assert!(
+                !matches(AZURE_TENANT_ID_PATTERN, invalid),
+                "'{invalid}' should not match"
+            );
+        }
+    }
+
+    #[test]
+    fn test_crd_schema_uses_shared_patterns() {
+        use kube::core::CustomResourceExt;
+
+        let crd = serde_json::to_value(crate::crd::SecretManagerConfig::crd())
+            .unwrap_or_else(|e| panic!("failed to serialize CRD: {e}"));
+        let spec = &crd["spec"]["versions"][0]["schema"]["openAPIV3Schema"]["properties"]["spec"]["properties"];
+        let provider = &spec["provider"]["properties"];
+
+        assert_eq!(spec["reconcileInterval"]["pattern"], DURATION_PATTERN);
+        assert_eq!(
+            spec["gitRepositoryPullInterval"]["pattern"],
+            DURATION_PATTERN
+        );
+        assert_eq!(spec["reconcileInterval"]["default"], "1m");
+        assert_eq!(
+            spec["sourceRef"]["properties"]["kind"]["enum"],
+            serde_json::json!(SOURCE_REF_KINDS)
+        );
+        assert_eq!(
+            provider["gcp"]["properties"]["projectId"]["pattern"],
+            GCP_PROJECT_ID_PATTERN
+        );
+        assert_eq!(
+            provider["aws"]["properties"]["region"]["pattern"],
+            AWS_REGION_PATTERN
+        );
+        assert_eq!(
+            provider["aws"]["properties"]["auth"]["properties"]["roleArn"]["pattern"],
+            AWS_ROLE_ARN_PATTERN
+        );
+        assert_eq!(
+            provider["azure"]["properties"]["vaultName"]["pattern"],
+            AZURE_VAULT_NAME_PATTERN
+        );
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Cloud provider configuration types for GCP, AWS, and Azure.
 
+use crate::crd::{KubeDuration, patterns};
 use schemars::{JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -45,6 +46,7 @@ impl JsonSchema for ProviderConfig {
             serde_json::to_value(&azure_schema).unwrap_or_else(|_| serde_json::json!({}));
 
         // Manually add pattern validation to location/region fields
+        // Patterns are shared with the controller's validation (see crd::patterns)
         // This is done by modifying the schema JSON after generation
         // GCP location pattern: [continent]-[direction][number] (e.g., us-central1)
         if let Some(props) = gcp_json
@@ -54,7 +56,7 @@ impl JsonSchema for ProviderConfig {
             if let Some(location) = props.get_mut("location").and_then(|l| l.as_object_mut()) {
                 location.insert(
                     "pattern".to_string(),
-                    serde_json::json!("^[a-z]+-[a-z]+[0-9]+$"),
+                    serde_json::json!(patterns::GCP_LOCATION_PATTERN),
                 );
             }
         }
@@ -65,7 +67,10 @@ impl JsonSchema for ProviderConfig {
             .and_then(|p| p.as_object_mut())
         {
             if let Some(region) = props.get_mut("region").and_then(|r| r.as_object_mut()) {
-                region.insert("pattern".to_string(), serde_json::json!("^[a-z]{2}-[a-z]+-[0-9]+$|^[a-z]{2}-gov-[a-z]+-[0-9]+$|^[a-z]{2}-iso-[a-z]+-[0-9]+$|^cn-[a-z]+-[0-9]+$|^local$"));
+                region.insert(
+                    "pattern".to_string(),
+                    serde_json::json!(patterns::AWS_REGION_PATTERN),
+                );
             }
         }
 
@@ -75,7 +80,10 @@ impl JsonSchema for ProviderConfig {
             .and_then(|p| p.as_object_mut())
         {
             if let Some(location) = props.get_mut("location").and_then(|l| l.as_object_mut()) {
-                location.insert("pattern".to_string(), serde_json::json!("^[a-z]+[0-9]*$"));
+                location.insert(
+                    "pattern".to_string(),
+                    serde_json::json!(patterns::AZURE_LOCATION_PATTERN),
+                );
             }
         }
 
@@ -183,6 +191,7 @@ impl<'de> serde::Deserialize<'de> for ProviderConfig {
 #[serde(rename_all = "camelCase")]
 pub struct GcpConfig {
     /// GCP project ID for Secret Manager
+    #[schemars(schema_with = "patterns::gcp_project_id_schema")]
     pub project_id: String,
     /// GCP location/region for Secret Manager (e.g., "us-central1", "europe-west1")
     /// Required: Must be specified for all GCP configurations
@@ -192,6 +201,61 @@ pub struct GcpConfig {
     /// GCP authentication configuration. If not specified, defaults to Workload Identity (recommended).
     #[serde(default)]
     pub auth: Option<GcpAuthConfig>,
+    /// Version retention policy (optional)
+    /// When set, superseded secret versions are destroyed (not just disabled) after each write.
+    /// GCP bills per active version and limits the number of versions per secret.
+    #[serde(default)]
+    pub version_retention: Option<GcpVersionRetention>,
+    /// API endpoint overrides for this resource (optional)
+    /// Routes Secret Manager / Parameter Manager traffic through a Private Service Connect
+    /// endpoint (e.g. `https://secretmanager-myendpoint.p.googleapis.com`) or a regional or
+    /// restricted endpoint. Takes precedence over the controller-wide `GCP_SECRET_MANAGER_ENDPOINT`.
+    /// Hosts must be within `googleapis.com` or a domain listed in the controller's
+    /// `GCP_ENDPOINT_ALLOWED_DOMAINS`. Ignored in Pact mode.
+    #[serde(default)]
+    pub endpoint_override: Option<GcpEndpointOverride>,
+}
+
+/// GCP API base URL overrides
+/// Each URL is `https://<host>[:port]` without a path; unset services use their public endpoint
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GcpEndpointOverride {
+    /// Secret Manager base URL (e.g. "https://secretmanager-myendpoint.p.googleapis.com")
+    #[serde(default)]
+    pub secret_manager: Option<String>,
+    /// Parameter Manager base URL (e.g. "https://parametermanager-myendpoint.p.googleapis.com")
+    #[serde(default)]
+    pub parameter_manager: Option<String>,
+}
+
+/// GCP Secret Manager version retention policy
+/// The latest version is never destroyed. When both fields are set, a version is destroyed
+/// only if it is outside `keepLast` AND older than `destroyOlderThan`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GcpVersionRetention {
+    /// Number of most recent versions to keep (minimum 1)
+    #[serde(default)]
+    pub keep_last: Option<u32>,
+    /// Destroy superseded versions older than this duration (e.g. "30d", "12h")
+    /// Uses the same duration format as reconcileInterval
+    #[serde(default)]
+    pub destroy_older_than: Option<KubeDuration>,
+}
+
+/// Rate limit for provider API operations made on behalf of one resource
+/// Unset fields fall back to the controller-wide defaults
+/// (`PROVIDER_RATE_LIMIT_RPS`, `PROVIDER_RATE_LIMIT_BURST`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRateLimit {
+    /// Sustained provider operations per second (0 = unlimited)
+    #[serde(default)]
+    pub requests_per_second: Option<f64>,
+    /// Operations allowed back-to-back before throttling starts (minimum 1)
+    #[serde(default)]
+    pub burst: Option<u32>,
 }
 
 /// AWS configuration for Secrets Manager
@@ -205,6 +269,37 @@ pub struct AwsConfig {
     /// AWS authentication configuration. If not specified, defaults to IRSA (IAM Roles for Service Accounts) - recommended.
     #[serde(default)]
     pub auth: Option<AwsAuthConfig>,
+    /// Version staging behavior (optional)
+    /// If not specified, PutSecretValue uses the AWS defaults (new version becomes AWSCURRENT)
+    #[serde(default)]
+    pub version_stages: Option<AwsVersionStagesConfig>,
+    /// Custom Secrets Manager endpoint URL (optional), e.g. an interface VPC endpoint or proxy
+    /// Takes precedence over the controller-wide AWS_SECRETS_MANAGER_ENDPOINT and over useFips
+    #[serde(default)]
+    pub endpoint_url: Option<String>,
+    /// Use FIPS 140-2 endpoints for Secrets Manager, Parameter Store and STS (default: false)
+    /// Available in US commercial and GovCloud regions; not available in the China regions
+    #[serde(default)]
+    pub use_fips: bool,
+    /// IAM role assumed with the controller's credentials before calling Secrets Manager (optional)
+    /// For cross-account access: the role lives in the target account and must trust the
+    /// controller's IRSA role. Unlike `auth.roleArn`, this role is actually assumed.
+    #[serde(default)]
+    pub assume_role_arn: Option<String>,
+}
+
+/// AWS Secrets Manager version staging configuration
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AwsVersionStagesConfig {
+    /// Write updated values as AWSPENDING, then promote them to AWSCURRENT (default: false)
+    /// Matches how Lambda rotation functions stage versions, for consumers that expect it
+    #[serde(default)]
+    pub promote_pending: bool,
+    /// Custom staging labels to move to each new current version (e.g. ["smc-synced"])
+    /// AWSCURRENT, AWSPENDING and AWSPREVIOUS are managed by AWS and not allowed here
+    #[serde(default)]
+    pub labels: Vec<String>,
 }
 
 /// Azure configuration for Key Vault
@@ -212,6 +307,7 @@ pub struct AwsConfig {
 #[serde(rename_all = "camelCase")]
 pub struct AzureConfig {
     /// Azure Key Vault name
+    #[schemars(schema_with = "patterns::azure_vault_name_schema")]
     pub vault_name: String,
     /// Azure location/region for Key Vault (e.g., "eastus", "westus2", "southeastasia")
     /// Required: Must be specified for all Azure configurations
@@ -221,10 +317,200 @@ pub struct AzureConfig {
     /// Azure authentication configuration. If not specified, defaults to Workload Identity (recommended).
     #[serde(default)]
     pub auth: Option<AzureAuthConfig>,
+    /// Certificate sync configuration (optional)
+    /// When enabled, certificate files in the profile directory are imported into
+    /// Key Vault's certificate store instead of being synced as secrets
+    #[serde(default)]
+    pub certificates: Option<AzureCertificatesConfig>,
+    /// Azure cloud the vault lives in (default: Public)
+    /// Selects the Key Vault and App Configuration DNS suffixes and the AAD authority used for tokens
+    #[serde(default)]
+    pub environment: AzureEnvironment,
+    /// Key Vault Secrets User role assignments for the consumers of the synced secrets (optional)
+    /// Missing assignments are reported on the `MissingRoleAssignments` condition, and created
+    /// when `create` is true
+    #[serde(default)]
+    pub role_assignments: Option<AzureRoleAssignmentsConfig>,
+}
+
+impl AzureConfig {
+    /// Copy with secret references that have no namespace resolved to `namespace`
+    /// (the SecretManagerConfig namespace)
+    #[must_use]
+    pub fn with_default_namespace(&self, namespace: &str) -> Self {
+        let mut config = self.clone();
+        if let Some(AzureAuthConfig::ClientCertificate {
+            certificate_secret_ref,
+            ..
+        }) = &mut config.auth
+        {
+            certificate_secret_ref
+                .namespace
+                .get_or_insert_with(|| namespace.to_string());
+        }
+        config
+    }
+}
+
+/// GCP target of a secret route
+/// Unset fields are inherited from `provider.gcp`
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GcpRouteTarget {
+    /// GCP project the routed secrets are written to
+    #[schemars(schema_with = "patterns::gcp_project_id_schema")]
+    pub project_id: String,
+    /// GCP location (optional)
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Authentication for the target project (optional)
+    #[serde(default)]
+    pub auth: Option<GcpAuthConfig>,
+}
+
+/// AWS target of a secret route
+/// Unset fields are inherited from `provider.aws`
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AwsRouteTarget {
+    /// AWS region (optional)
+    #[serde(default)]
+    pub region: Option<String>,
+    /// IAM role in the target account, assumed with the controller's credentials (optional)
+    #[serde(default)]
+    pub assume_role_arn: Option<String>,
+    /// Authentication configuration (optional)
+    #[serde(default)]
+    pub auth: Option<AwsAuthConfig>,
+}
+
+/// Azure target of a secret route
+/// Unset fields are inherited from `provider.azure`
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureRouteTarget {
+    /// Key Vault the routed secrets are written to
+    #[schemars(schema_with = "patterns::azure_vault_name_schema")]
+    pub vault_name: String,
+    /// Azure location (optional)
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Authentication for the target vault (optional)
+    #[serde(default)]
+    pub auth: Option<AzureAuthConfig>,
+}
+
+/// Azure cloud (public or sovereign)
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema,
+)]
+pub enum AzureEnvironment {
+    /// Azure public cloud (vault.azure.net, login.microsoftonline.com)
+    #[default]
+    Public,
+    /// Azure US Government (vault.usgovcloudapi.net, login.microsoftonline.us)
+    UsGovernment,
+    /// Azure China, operated by 21Vianet (vault.azure.cn, login.chinacloudapi.cn)
+    China,
+}
+
+/// Key Vault role assignments checked (or created) after each sync
+/// The controller's identity needs `Microsoft.Authorization/roleAssignments/read` on the vault,
+/// and `Microsoft.Authorization/roleAssignments/write` (e.g. Role Based Access Control
+/// Administrator) when `create` is true
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureRoleAssignmentsConfig {
+    /// Subscription ID of the vault
+    pub subscription_id: String,
+    /// Resource group of the vault
+    pub resource_group: String,
+    /// Create missing role assignments instead of only reporting them (default: false)
+    #[serde(default)]
+    pub create: bool,
+    /// Principals that read the synced secrets
+    pub principals: Vec<AzureRoleAssignmentPrincipal>,
+}
+
+/// Principal that needs the Key Vault Secrets User role
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureRoleAssignmentPrincipal {
+    /// Object ID of the principal (e.g. the managed identity of the consuming workload)
+    pub principal_id: String,
+    /// Type of the principal (default: ServicePrincipal)
+    #[serde(default)]
+    pub principal_type: AzurePrincipalType,
+    /// Scope of the role assignment (default: Vault)
+    #[serde(default)]
+    pub scope: AzureRoleAssignmentScope,
+}
+
+/// Azure AD principal type of a role assignment
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema,
+)]
+pub enum AzurePrincipalType {
+    /// Service principal or managed identity
+    #[default]
+    ServicePrincipal,
+    /// User
+    User,
+    /// Group (members aren't resolved: only assignments to the group itself are checked)
+    Group,
+}
+
+/// Scope of a Key Vault role assignment
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema,
+)]
+pub enum AzureRoleAssignmentScope {
+    /// The whole vault
+    #[default]
+    Vault,
+    /// Each secret synced by this resource
+    Secrets,
+}
+
+/// Azure Key Vault certificate sync configuration
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureCertificatesConfig {
+    /// Enable certificate import (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// File name globs selecting certificate files in the profile directory
+    /// `.pfx`/`.p12` files are imported as PKCS#12, everything else as PEM
+    /// Default: ["*.pem", "*.pfx"]
+    #[serde(default = "default_certificate_files")]
+    pub files: Vec<String>,
+    /// Whether the imported private key is exportable (default: true)
+    /// Must be true for consumers that read the certificate through the secrets API
+    #[serde(default = "crate::crd::default_true")]
+    pub exportable: bool,
+    /// Whether the private key is reused when the certificate is renewed (default: false)
+    #[serde(default)]
+    pub reuse_key: bool,
+}
+
+impl Default for AzureCertificatesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            files: default_certificate_files(),
+            exportable: true,
+            reuse_key: false,
+        }
+    }
+}
+
+/// Default certificate file globs
+fn default_certificate_files() -> Vec<String> {
+    vec!["*.pem".to_string(), "*.pfx".to_string()]
 }
 
 /// GCP authentication configuration
-/// Only supports Workload Identity (recommended and default)
+/// Workload Identity (recommended and default), optionally followed by service account impersonation
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase", tag = "authType")]
 pub enum GcpAuthConfig {
@@ -237,6 +523,19 @@ pub enum GcpAuthConfig {
         #[serde(rename = "serviceAccountEmail")]
         service_account_email: String,
     },
+    /// Impersonate a service account with the controller's Workload Identity
+    /// Tokens come from the IAM Credentials `generateAccessToken` API, so each resource can use
+    /// its own identity; the controller's service account needs
+    /// `roles/iam.serviceAccountTokenCreator` on the first service account in the chain
+    Impersonation {
+        /// Service account the access token is issued for
+        #[serde(rename = "targetServiceAccount")]
+        target_service_account: String,
+        /// Delegation chain between the controller's and the target service account (optional)
+        /// Each service account needs `roles/iam.serviceAccountTokenCreator` on the next one
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        delegates: Vec<String>,
+    },
 }
 
 impl JsonSchema for GcpAuthConfig {
@@ -248,19 +547,32 @@ impl JsonSchema for GcpAuthConfig {
         // Use a single schema instead of oneOf to avoid nullable issues
         let schema_value = serde_json::json!({
             "type": "object",
-            "description": "GCP authentication configuration - only supports Workload Identity",
+            "description": "GCP authentication configuration - Workload Identity, or impersonation of another service account",
             "properties": {
                 "authType": {
                     "type": "string",
-                    "enum": ["workloadIdentity"],
-                    "description": "Authentication type: 'workloadIdentity' for Workload Identity"
+                    "enum": ["workloadIdentity", "impersonation"],
+                    "description": "Authentication type: 'workloadIdentity' for Workload Identity, 'impersonation' to impersonate targetServiceAccount"
                 },
                 "serviceAccountEmail": {
                     "type": "string",
                     "description": "GCP service account email to impersonate. Format: <service-account-name>@<project-id>.iam.gserviceaccount.com"
+                },
+                "targetServiceAccount": {
+                    "type": "string",
+                    "pattern": patterns::GCP_SERVICE_ACCOUNT_EMAIL_PATTERN,
+                    "description": "Service account the access token is issued for (impersonation)"
+                },
+                "delegates": {
+                    "type": "array",
+                    "items": {
+                        "type": "string",
+                        "pattern": patterns::GCP_SERVICE_ACCOUNT_EMAIL_PATTERN
+                    },
+                    "description": "Delegation chain between the controller's and the target service account (impersonation, optional)"
                 }
             },
-            "required": ["authType", "serviceAccountEmail"]
+            "required": ["authType"]
         });
         Schema::try_from(schema_value).expect("Failed to create Schema for GcpAuthConfig")
     }
@@ -300,6 +612,7 @@ impl JsonSchema for AwsAuthConfig {
                 },
                 "roleArn": {
                     "type": "string",
+                    "pattern": patterns::AWS_ROLE_ARN_PATTERN,
                     "description": "AWS IAM role ARN to assume. Format: arn:aws:iam::<account-id>:role/<role-name>"
                 }
             },
@@ -310,7 +623,7 @@ impl JsonSchema for AwsAuthConfig {
 }
 
 /// Azure authentication configuration
-/// Only supports Workload Identity (recommended and default)
+/// Workload Identity (recommended and default) or a service principal with a client certificate
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase", tag = "authType")]
 pub enum AzureAuthConfig {
@@ -321,9 +634,37 @@ pub enum AzureAuthConfig {
         /// Azure service principal client ID
         #[serde(rename = "clientId")]
         client_id: String,
+        /// Entra tenant the identity lives in (optional)
+        /// Overrides the pod's `AZURE_TENANT_ID` for vaults in another tenant
+        #[serde(rename = "tenantId", default, skip_serializing_if = "Option::is_none")]
+        tenant_id: Option<String>,
+    },
+    /// Service principal authenticated with a client certificate
+    /// For clusters without Workload Identity federation
+    ClientCertificate {
+        /// Azure service principal client ID
+        #[serde(rename = "clientId")]
+        client_id: String,
+        /// Entra tenant of the service principal
+        #[serde(rename = "tenantId")]
+        tenant_id: String,
+        /// Kubernetes TLS secret (`tls.crt`, `tls.key`) holding the certificate and its key
+        #[serde(rename = "certificateSecretRef")]
+        certificate_secret_ref: AzureCertificateSecretRef,
     },
 }
 
+/// Kubernetes secret holding an Azure client certificate
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureCertificateSecretRef {
+    /// Secret name (type `kubernetes.io/tls`: PEM certificate in `tls.crt`, RSA key in `tls.key`)
+    pub name: String,
+    /// Secret namespace (defaults to the SecretManagerConfig namespace if not specified)
+    #[serde(default)]
+    pub namespace: Option<String>,
+}
+
 impl JsonSchema for AzureAuthConfig {
     fn schema_name() -> Cow<'static, str> {
         Cow::Borrowed("AzureAuthConfig")
@@ -333,16 +674,36 @@ impl JsonSchema for AzureAuthConfig {
         // Use a single schema instead of oneOf to avoid nullable issues
         let schema_value = serde_json::json!({
             "type": "object",
-            "description": "Azure authentication configuration - only supports Workload Identity",
+            "description": "Azure authentication configuration - Workload Identity or a client certificate",
             "properties": {
                 "authType": {
                     "type": "string",
-                    "enum": ["workloadIdentity"],
-                    "description": "Authentication type: 'workloadIdentity' for Workload Identity"
+                    "enum": ["workloadIdentity", "clientCertificate"],
+                    "description": "Authentication type: 'workloadIdentity' for Workload Identity, 'clientCertificate' for a service principal certificate"
                 },
                 "clientId": {
                     "type": "string",
                     "description": "Azure service principal client ID"
+                },
+                "tenantId": {
+                    "type": "string",
+                    "description": "Entra tenant ID (required for clientCertificate; overrides AZURE_TENANT_ID for workloadIdentity)"
+                },
+                "certificateSecretRef": {
+                    "type": "object",
+                    "description": "Kubernetes TLS secret with the client certificate (tls.crt) and key (tls.key) (clientCertificate)",
+                    "properties": {
+                        "name": {
+                            "type": "string",
+                            "description": "Secret name"
+                        },
+                        "namespace": {
+                            "type": "string",
+                            "nullable": true,
+                            "description": "Secret namespace (defaults to the SecretManagerConfig namespace)"
+                        }
+                    },
+                    "required": ["name"]
                 }
             },
             "required": ["authType", "clientId"]
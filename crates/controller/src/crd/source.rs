@@ -2,6 +2,8 @@
 //!
 //! Types for GitOps source references and secrets/configs sync configuration.
 
+use crate::crd::KubeDuration;
+use crate::crd::provider::{AwsRouteTarget, AzureRouteTarget, GcpRouteTarget};
 use schemars::{JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -10,20 +12,38 @@ use std::borrow::Cow;
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SourceRef {
-    /// Source kind: "GitRepository" (FluxCD) or "Application" (ArgoCD)
+    /// Source kind: "GitRepository" (FluxCD), "Application" (ArgoCD), "GitDirect"
+    /// (the controller clones `url` itself, without a GitOps tool) or "Secret"
+    /// (mirrors the keys of a Kubernetes Secret in the resource's namespace)
     #[serde(default = "crate::crd::spec::default_source_kind")]
+    #[schemars(schema_with = "crate::crd::patterns::source_ref_kind_schema")]
     pub kind: String,
     /// Source name
+    /// For GitDirect this only names the local checkout (cache directory and git lock)
     pub name: String,
     /// Source namespace
+    /// For Secret this must be the resource's own namespace
     pub namespace: String,
-    /// Git credentials reference for ArgoCD Application cloning (optional)
-    /// When using ArgoCD Application as source, this allows specifying a Kubernetes secret
-    /// containing git credentials for private repositories. If not specified, the controller
-    /// will attempt to clone without credentials (works for public repos).
+    /// Repository URL (GitDirect only, required)
+    /// HTTPS (`https://host/org/repo.git`) or SSH (`ssh://git@host/org/repo.git` or
+    /// `git@host:org/repo.git`). Credentials must not be embedded in the URL; use
+    /// `gitCredentials` instead
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Branch, tag or commit SHA to check out (GitDirect only, default: the remote HEAD)
+    #[serde(default)]
+    pub revision: Option<String>,
+    /// Git credentials reference for private repositories (GitDirect and Application)
+    /// The controller clones GitDirect and ArgoCD Application sources itself, and uses this
+    /// secret to authenticate the clone and subsequent fetches. If not specified, the
+    /// controller clones without credentials (works for public repos).
+    /// If the secret is missing, holds no usable credentials, or the Git server rejects
+    /// them, the resource fails with the `CredentialsInvalid` reason instead of retrying
+    /// anonymously.
     ///
     /// The secret should contain either:
     /// - For HTTPS: `username` and `password` keys (or `token` as password)
+    /// - For tokens: `token` (or `githubToken`) alone, sent as both username and password
     /// - For SSH: `identity` key containing the SSH private key
     ///
     /// Example:
@@ -36,7 +56,7 @@ pub struct SourceRef {
     pub git_credentials: Option<GitCredentialsRef>,
 }
 
-/// Git credentials reference for ArgoCD repository cloning
+/// Git credentials reference for repository cloning
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GitCredentialsRef {
@@ -47,13 +67,40 @@ pub struct GitCredentialsRef {
     pub namespace: Option<String>,
 }
 
+/// SOPS private key secret reference for a single SecretManagerConfig
+///
+/// The referenced key is tried before the controller's key ring, so a resource can
+/// pin its own key while shared keys remain available as a fallback
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SopsKeySecretRef {
+    /// Secret name containing the SOPS private key
+    pub name: String,
+    /// Secret namespace (defaults to the SecretManagerConfig namespace if not specified)
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Data key holding the private key
+    /// If not specified, `private-key`, `key`, and `gpg-key` are checked in order
+    #[serde(default)]
+    pub key: Option<String>,
+}
+
 /// Secrets sync configuration
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SecretsConfig {
     /// Environment/profile name to sync (e.g., "dev", "dev-cf", "prod-cf", "pp-cf")
     /// This must match the directory name under profiles/
+    /// May be omitted when `environments` is set
+    #[serde(default)]
     pub environment: String,
+    /// Multiple environments/profiles to sync from a single resource (optional, raw file mode only)
+    /// Entries are exact profile names or globs (`*`, `?`) matched against discovered profile directories
+    /// When set, takes precedence over `environment`. Use the `{env}` placeholder in prefix/suffix
+    /// to give each environment distinct secret names
+    /// Example: ["dev", "dev-cf"] or ["dev*"]
+    #[serde(default)]
+    pub environments: Option<Vec<String>>,
     /// Kustomize path - path to kustomization.yaml file (relative to GitRepository root)
     /// If specified, controller will run `kustomize build` on this path and extract secrets
     /// from the generated Kubernetes Secret resources. This supports kustomize overlays,
@@ -67,6 +114,13 @@ pub struct SecretsConfig {
     /// Examples: "microservices", "services", "apps", or "." for root
     #[serde(default)]
     pub base_path: Option<String>,
+    /// Merge base files under each profile's files, Spring profile style (default: false)
+    /// application.secrets.env/yaml and application.properties in `deployment-configuration/`
+    /// form the base, `application.secrets.{env}.env/yaml` and `application-{env}.properties`
+    /// next to them override it, and the files in the profile directory override both
+    /// Raw file mode only
+    #[serde(default)]
+    pub merge_base_profile: Option<bool>,
     /// Secret name prefix (default: repository name)
     /// Matches kustomize-google-secret-manager prefix behavior
     #[serde(default)]
@@ -76,6 +130,227 @@ pub struct SecretsConfig {
     /// Common use cases: environment identifiers, tags, etc.
     #[serde(default)]
     pub suffix: Option<String>,
+    /// Secret name template (optional)
+    /// Replaces the fixed `{prefix}-{key}-{suffix}` naming when set
+    /// Variables: {service}, {env}, {key}, {suffix}, {name}, {namespace}, {repo}
+    /// `{key}` is required so each key maps to a distinct secret
+    /// The rendered name is sanitized the same way as prefix/key/suffix names
+    /// Example: "{service}-{env}-{key}"
+    #[serde(default)]
+    pub name_template: Option<String>,
+    /// Provider name sanitization (optional)
+    /// Keys are always mapped to provider-safe names (GCP: `[A-Za-z0-9_-]`,
+    /// Azure: alphanumerics and dashes, AWS: `[A-Za-z0-9/_+=.@-]`); rewritten keys are
+    /// recorded in status.sync as `sourceKey`, and keys that collide after sanitization fail the sync
+    #[serde(default)]
+    pub sanitization: Option<NameSanitizationConfig>,
+    /// How keys from each source file map to provider secrets (optional)
+    /// - Individual: one provider secret per key; application.properties is also split
+    ///   into individual secrets instead of a single "properties" JSON secret
+    /// - SingleJson: all enabled keys from application.secrets.env/yaml are combined into one
+    ///   provider secret named `{prefix}-secrets-{suffix}` holding a JSON object
+    /// If not specified: secrets are individual and properties use a single JSON secret
+    /// (unless configs.enabled routes them to a config store)
+    #[serde(default)]
+    pub grouping: Option<SecretGrouping>,
+    /// Binary file globs (optional, raw file mode only)
+    /// Files in the profile directory matching any of these globs are synced as binary secrets
+    /// (certificates, keystores, p12 bundles). The file name becomes the secret key.
+    /// GCP stores the raw bytes as the base64 payload, AWS uses SecretBinary,
+    /// Azure stores base64 with contentType "application/octet-stream"
+    /// Supports `*` and `?` wildcards, matched against the file name
+    /// Examples: ["*.p12", "*.jks", "tls.crt"]
+    #[serde(default)]
+    pub binary_files: Option<Vec<String>>,
+    /// Route keys to another project, account or vault (optional)
+    /// The first route whose `match` glob matches a key wins; unmatched keys use `provider`.
+    /// Applies to individual secrets and binary files, not to SingleJson groups or properties
+    /// Example: [{match: "PAYMENTS_*", gcp: {projectId: payments-project}}]
+    #[serde(default)]
+    pub routes: Option<Vec<SecretRoute>>,
+    /// Size and count guardrails for the values this resource writes (optional)
+    /// Checked before any provider write, so a bad merge (e.g. a 10MB file committed as a
+    /// secret) fails with the `LimitExceeded` reason instead of hitting provider limits
+    /// Unset fields use the controller defaults (`MAX_SECRET_SIZE_BYTES`,
+    /// `MAX_SECRETS_PER_RESOURCE`, `MAX_TOTAL_SECRET_BYTES`)
+    #[serde(default)]
+    pub limits: Option<SecretLimits>,
+    /// Key and value policy (optional)
+    /// Checked before any provider write; keys that break the policy fail the sync with the
+    /// `PolicyViolation` reason and a Warning event, and nothing is written for their service
+    /// Example: {forbiddenKeyPatterns: ["*_PROD_*"], requireSopsForKeys: ["*_PASSWORD"]}
+    #[serde(default)]
+    pub policy: Option<SecretPolicy>,
+    /// Resolve `${OTHER_KEY}` placeholders in secret values (default: false)
+    /// Values of application.secrets.env/yaml can reference other enabled secret keys, so
+    /// composite values (connection strings) are assembled at sync time. `${KEY:default}`
+    /// supplies a default, `\${KEY}` keeps the text literally; cycles and unknown keys without
+    /// default fail the sync
+    #[serde(default)]
+    pub resolve_placeholders: Option<bool>,
+    /// Expiry of the secrets this resource writes (optional)
+    /// A write sets the expiry to `ttl` from now: the Azure Key Vault `exp` attribute, the AWS
+    /// tag `secret-manager-expires-at` or the GCP `expireTime` (GCP deletes expired secrets; the
+    /// next sync recreates them). Unchanged values keep their expiry, so rotating the value in
+    /// Git renews it. Secrets expiring within `warnBefore` raise the `ExpiringSoon` condition
+    /// Applies to individual secrets, not to SingleJson groups, binary files or properties
+    /// Example: {ttl: "90d", keys: [{match: "DB_*", ttl: "30d"}]}
+    #[serde(default)]
+    pub expiration: Option<SecretExpiration>,
+}
+
+/// Expiry of the secrets of one resource
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretExpiration {
+    /// Time from a write until the secret expires (e.g. "90d")
+    /// Keys without a ttl here or in `keys` get no expiry
+    #[serde(default)]
+    pub ttl: Option<KubeDuration>,
+    /// Raise `ExpiringSoon` this long before a secret expires (default: "14d")
+    #[serde(default)]
+    pub warn_before: Option<KubeDuration>,
+    /// Per-key ttls; the first entry whose `match` glob matches a key overrides `ttl`
+    #[serde(default)]
+    pub keys: Option<Vec<KeyExpiration>>,
+}
+
+/// Expiry of the keys matching a glob
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyExpiration {
+    /// Key glob (`*`, `?`), matched against the source key (e.g. "DB_*")
+    #[serde(rename = "match")]
+    pub key_pattern: String,
+    /// Time from a write until the matching secrets expire
+    pub ttl: KubeDuration,
+}
+
+/// Size and count limits for the values of one resource (0 = unlimited)
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretLimits {
+    /// Maximum size of a single secret, property or binary file in bytes
+    #[serde(default)]
+    pub max_value_bytes: Option<u64>,
+    /// Maximum number of keys across all environments and services of the resource
+    #[serde(default)]
+    pub max_keys: Option<u64>,
+    /// Maximum total size of all values of the resource in bytes
+    #[serde(default)]
+    pub max_total_bytes: Option<u64>,
+}
+
+/// Policy for the keys and values of one resource
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretPolicy {
+    /// Key globs (`*`, `?`) that must not be synced, e.g. "*_PROD_*" in a dev resource
+    #[serde(default)]
+    pub forbidden_key_patterns: Option<Vec<String>>,
+    /// Key globs (`*`, `?`) whose values must come from a SOPS-encrypted file
+    /// Properties and kustomize-built secrets are never SOPS-encrypted, so matching keys
+    /// from those sources are rejected
+    #[serde(default)]
+    pub require_sops_for_keys: Option<Vec<String>>,
+    /// Reject values that look like a private key (PEM `PRIVATE KEY` block) unless they
+    /// come from a SOPS-encrypted file (default: true)
+    #[serde(default)]
+    pub reject_plaintext_private_keys: Option<bool>,
+    /// Only sync secrets read from SOPS-encrypted files (default: false)
+    /// Secrets committed in plain text (unencrypted secrets files, kustomize output, binary
+    /// files) are rejected, so an accidental plaintext commit never reaches the provider;
+    /// application.properties is plain text configuration and stays allowed
+    #[serde(default)]
+    pub require_encrypted_source: Option<bool>,
+}
+
+/// Route for keys stored outside the resource's provider target
+/// Exactly one target is set, matching the resource's provider
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretRoute {
+    /// Key glob (`*`, `?`), matched against the source key (e.g. "PAYMENTS_*")
+    #[serde(rename = "match")]
+    pub key_pattern: String,
+    /// GCP target (GCP resources)
+    #[serde(default)]
+    pub gcp: Option<GcpRouteTarget>,
+    /// AWS target (AWS resources)
+    #[serde(default)]
+    pub aws: Option<AwsRouteTarget>,
+    /// Azure target (Azure resources)
+    #[serde(default)]
+    pub azure: Option<AzureRouteTarget>,
+}
+
+impl SecretsConfig {
+    /// Placeholder replaced with the environment name in prefix/suffix
+    pub const ENV_PLACEHOLDER: &'static str = "{env}";
+
+    /// Environment patterns to sync: `environments` if set, otherwise the single `environment`
+    #[must_use]
+    pub fn environment_patterns(&self) -> Vec<String> {
+        match &self.environments {
+            Some(environments) if !environments.is_empty() => environments.clone(),
+            _ => vec![self.environment.clone()],
+        }
+    }
+
+    /// Resolve this configuration for a single environment
+    /// Sets `environment` and renders the `{env}` placeholder in prefix and suffix
+    #[must_use]
+    pub fn for_environment(&self, environment: &str) -> Self {
+        let render = |value: &Option<String>| {
+            value
+                .as_ref()
+                .map(|v| v.replace(Self::ENV_PLACEHOLDER, environment))
+        };
+        Self {
+            environment: environment.to_string(),
+            environments: None,
+            prefix: render(&self.prefix),
+            suffix: render(&self.suffix),
+            ..self.clone()
+        }
+    }
+}
+
+/// Secret grouping mode for a source file
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum SecretGrouping {
+    /// One provider secret per key
+    Individual,
+    /// One provider secret per file, holding all keys as a JSON object
+    SingleJson,
+}
+
+impl JsonSchema for SecretGrouping {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("SecretGrouping")
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        // Plain string enum (not oneOf) so the schema stays structural for Kubernetes
+        let schema_value = serde_json::json!({
+            "type": "string",
+            "enum": ["Individual", "SingleJson"],
+            "description": "Secret grouping mode. Individual: one provider secret per key. SingleJson: one provider secret per file holding all keys as a JSON object."
+        });
+        Schema::try_from(schema_value)
+            .unwrap_or_else(|_| panic!("Failed to create Schema for SecretGrouping"))
+    }
+}
+
+/// Secret name sanitization configuration
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NameSanitizationConfig {
+    /// Character used to replace disallowed characters: "-" or "_"
+    /// Defaults to "_" for GCP and AWS, "-" for Azure
+    /// Ignored when the provider does not allow the character (e.g. "_" on Azure)
+    #[serde(default)]
+    pub replacement: Option<String>,
 }
 
 /// Config store configuration for routing application.properties to config stores
@@ -100,12 +375,26 @@ pub struct ConfigsConfig {
     /// - ParameterManager: Store configs in Parameter Manager (future, after ESO contribution)
     #[serde(default)]
     pub store: Option<ConfigStoreType>,
+    /// GCP-specific: Store `{{secretRef: KEY}}` in property values as secret references
+    /// Only applies when store == ParameterManager
+    /// Each reference is written as a Parameter Manager `__REF__(...)` pointing at the
+    /// Secret Manager secret synced for KEY, so the value stays out of the config store
+    #[serde(default)]
+    pub secret_references: bool,
+    /// AWS-specific: Parameter Store tier and policies
+    /// Only applies when provider.type == aws
+    #[serde(default)]
+    pub aws: Option<AwsConfigsConfig>,
     /// Azure-specific: App Configuration endpoint
     /// Only applies when provider.type == azure
     /// Optional: defaults to auto-detection from vault region if not specified
     /// Example: https://my-app-config.azconfig.io
     #[serde(default)]
     pub app_config_endpoint: Option<String>,
+    /// Azure-specific: App Configuration feature flags
+    /// Only applies when provider.type == azure
+    #[serde(default)]
+    pub azure: Option<AzureConfigsConfig>,
 }
 
 /// GCP config store type
@@ -138,3 +427,96 @@ impl JsonSchema for ConfigStoreType {
             .unwrap_or_else(|_| panic!("Failed to create Schema for ConfigStoreType"))
     }
 }
+
+/// Azure App Configuration settings for configs
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureConfigsConfig {
+    /// Properties whose key starts with this prefix are written as feature flags
+    /// (`.appconfig.featureflag/{key without prefix}`) instead of key-values
+    /// Values are `true`, `false` or a JSON flag definition (e.g. with `conditions`)
+    /// Example: feature.
+    #[serde(default)]
+    pub feature_flag_prefix: Option<String>,
+}
+
+/// AWS Parameter Store settings for configs
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AwsConfigsConfig {
+    /// Parameter tier (default: Standard)
+    /// Standard parameters holding values over 4 KB, or with policies, are written as Advanced
+    #[serde(default)]
+    pub tier: Option<ParameterTier>,
+    /// Parameter policies, attached to every parameter written (requires the Advanced tier)
+    #[serde(default)]
+    pub policies: Option<ParameterPolicies>,
+}
+
+/// AWS Parameter Store tier
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ParameterTier {
+    /// Up to 4 KB, no policies, no additional charge
+    #[default]
+    Standard,
+    /// Up to 8 KB, supports policies
+    Advanced,
+    /// Parameter Store picks Standard or Advanced per write
+    IntelligentTiering,
+}
+
+impl ParameterTier {
+    /// Tier name used by the Parameter Store API
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::Advanced => "Advanced",
+            Self::IntelligentTiering => "Intelligent-Tiering",
+        }
+    }
+}
+
+impl JsonSchema for ParameterTier {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("ParameterTier")
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        // Plain string enum (not oneOf) so the schema stays structural for Kubernetes
+        let schema_value = serde_json::json!({
+            "type": "string",
+            "enum": ["Standard", "Advanced", "IntelligentTiering"],
+            "description": "Parameter Store tier. Standard: up to 4 KB (values over 4 KB are written as Advanced). Advanced: up to 8 KB, supports policies. IntelligentTiering: Parameter Store picks the tier per write."
+        });
+        Schema::try_from(schema_value)
+            .unwrap_or_else(|_| panic!("Failed to create Schema for ParameterTier"))
+    }
+}
+
+/// AWS Parameter Store parameter policies
+/// Durations use the duration format with a day or hour granularity (e.g. "90d", "12h")
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ParameterPolicies {
+    /// Delete parameters this long after they were last written (Expiration policy)
+    #[serde(default)]
+    pub expiration: Option<KubeDuration>,
+    /// Emit an EventBridge notification this long before expiration (ExpirationNotification policy)
+    /// Requires expiration
+    #[serde(default)]
+    pub expiration_notification: Option<KubeDuration>,
+    /// Emit an EventBridge notification when a parameter hasn't changed for this long
+    /// (NoChangeNotification policy)
+    #[serde(default)]
+    pub no_change_notification: Option<KubeDuration>,
+}
+
+impl ParameterPolicies {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.expiration.is_none()
+            && self.expiration_notification.is_none()
+            && self.no_change_notification.is_none()
+    }
+}
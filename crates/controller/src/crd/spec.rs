@@ -2,7 +2,10 @@
 //!
 //! Main CRD specification types and default values.
 
+use crate::crd::KubeDuration;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 /// SecretManagerConfig Custom Resource Definition
 ///
@@ -41,7 +44,7 @@ use serde::{Deserialize, Serialize};
 )]
 #[serde(rename_all = "camelCase")]
 pub struct SecretManagerConfigSpec {
-    /// Source reference - supports FluxCD GitRepository and ArgoCD Application
+    /// Source reference - supports FluxCD GitRepository, ArgoCD Application and direct Git clones
     /// This makes the controller GitOps-agnostic
     pub source_ref: crate::crd::SourceRef,
     /// Cloud provider configuration - supports GCP, AWS, and Azure
@@ -64,13 +67,13 @@ pub struct SecretManagerConfigSpec {
     /// Default: "5m" (5 minutes)
     /// Recommended: 5m or greater to avoid rate limiting
     #[serde(default = "default_git_repository_pull_interval")]
-    pub git_repository_pull_interval: String,
+    pub git_repository_pull_interval: KubeDuration,
     /// Reconcile interval
     /// How often to reconcile secrets between Git and cloud providers (Secret Manager or Parameter Manager)
     /// Format: Kubernetes duration string (e.g., "1m", "30s", "5m")
     /// Default: "1m" (1 minute)
     #[serde(default = "default_reconcile_interval")]
-    pub reconcile_interval: String,
+    pub reconcile_interval: KubeDuration,
     /// Enable diff discovery
     /// When enabled, detects if secrets have been tampered with in Secret Manager or Parameter Manager
     /// and logs warnings when differences are found between Git (source of truth) and cloud provider
@@ -114,6 +117,54 @@ pub struct SecretManagerConfigSpec {
     /// Default: disabled (false) - most users rely on pod restarts via Reloader or manual updates
     #[serde(default)]
     pub hot_reload: Option<crate::crd::HotReloadConfig>,
+    /// SOPS private key secret for this resource (optional)
+    /// When set, this key is tried first when decrypting SOPS files, falling back to the
+    /// controller's key ring (well-known key secrets and secrets labelled
+    /// `secret-management.microscaler.io/sops-key=true`)
+    /// Changes to the referenced secret reconcile the resource right away
+    #[serde(default)]
+    pub sops_key_secret_ref: Option<crate::crd::SopsKeySecretRef>,
+    /// Rate limit for provider API operations made for this resource (optional)
+    /// Enforced with a token bucket around every provider call, so one resource with many keys
+    /// can't exhaust an API quota shared with other teams (e.g. a GCP project)
+    /// If not specified, the controller-wide defaults apply (unlimited unless configured)
+    #[serde(default)]
+    pub provider_rate_limit: Option<crate::crd::ProviderRateLimit>,
+    /// How detected changes are promoted to the provider (optional)
+    /// - Automatic: changes are applied as soon as they are detected
+    /// - Manual: changes are staged in `status.pendingChanges` and only applied once the
+    ///   `secret-management.octopilot.io/approve` annotation is set to the staged hash
+    ///   (e.g. with `msmctl apply`)
+    /// Default: Automatic
+    #[serde(default)]
+    pub approval_policy: Option<ApprovalPolicy>,
+}
+
+/// Approval policy for changes detected during reconciliation
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ApprovalPolicy {
+    /// Apply changes as soon as they are detected
+    #[default]
+    Automatic,
+    /// Stage changes in status until they are approved
+    Manual,
+}
+
+impl JsonSchema for ApprovalPolicy {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("ApprovalPolicy")
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        // Plain string enum (not oneOf) so the schema stays structural for Kubernetes
+        let schema_value = serde_json::json!({
+            "type": "string",
+            "enum": ["Automatic", "Manual"],
+            "description": "Approval policy. Automatic: apply changes as soon as they are detected. Manual: stage changes in status until approved."
+        });
+        Schema::try_from(schema_value)
+            .unwrap_or_else(|_| panic!("Failed to create Schema for ApprovalPolicy"))
+    }
 }
 
 /// Default value for source kind
@@ -122,13 +173,13 @@ pub fn default_source_kind() -> String {
 }
 
 /// Default value for GitRepository pull interval
-pub fn default_git_repository_pull_interval() -> String {
-    "5m".to_string()
+pub fn default_git_repository_pull_interval() -> KubeDuration {
+    "5m".into()
 }
 
 /// Default value for reconcile interval
-pub fn default_reconcile_interval() -> String {
-    "1m".to_string()
+pub fn default_reconcile_interval() -> KubeDuration {
+    "1m".into()
 }
 
 /// Default value for boolean true
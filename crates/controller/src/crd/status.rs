@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "camelCase")]
 pub struct SecretManagerConfigStatus {
     /// Current phase of reconciliation
-    /// Values: Pending, Started, Cloning, Updating, Failed, Ready
+    /// Values: Pending, Started, Cloning, Updating, Failed, PartialFailure, AwaitingApproval, Ready
     #[serde(default)]
     pub phase: Option<String>,
     /// Human-readable description of current state
@@ -27,6 +27,11 @@ pub struct SecretManagerConfigStatus {
     /// Last reconciliation time
     #[serde(default)]
     pub last_reconcile_time: Option<String>,
+    /// Source revision (e.g. FluxCD `main@sha1:...`, or the `resourceVersion` of a Secret
+    /// source) of the last successful sync
+    /// Delta reconciles only process profiles changed since this revision
+    #[serde(default)]
+    pub last_synced_revision: Option<String>,
     /// Next scheduled reconciliation time (RFC3339)
     /// Used to persist periodic reconciliation schedule across watch restarts
     #[serde(default)]
@@ -38,6 +43,18 @@ pub struct SecretManagerConfigStatus {
     /// Tracks which resources have been successfully pushed and how many times updated
     #[serde(default)]
     pub sync: Option<SyncStatus>,
+    /// Secrets that failed to sync during the last reconciliation
+    /// Other secrets are still written; failures trigger a retry with backoff
+    #[serde(default)]
+    pub failed_secrets: Option<Vec<FailedSecret>>,
+    /// Services whose files could not be processed during the last reconciliation
+    /// None of their secrets were written; failures trigger a retry with backoff
+    #[serde(default)]
+    pub failed_services: Option<Vec<FailedService>>,
+    /// Changes detected but not yet applied (`approvalPolicy: Manual`)
+    /// Applied once the `secret-management.octopilot.io/approve` annotation matches `hash`
+    #[serde(default)]
+    pub pending_changes: Option<PendingChanges>,
     /// SOPS decryption status
     /// Values: Success, TransientFailure, PermanentFailure, NotApplicable
     /// NotApplicable means no SOPS-encrypted files were processed
@@ -67,6 +84,10 @@ pub struct SecretManagerConfigStatus {
     /// Last time the SOPS key availability was checked (RFC3339)
     #[serde(default)]
     pub sops_key_last_checked: Option<String>,
+    /// Most recent reconciliations, oldest first (bounded to the last few)
+    /// Shows trigger, timing and outcome of each run to diagnose flapping resources
+    #[serde(default)]
+    pub recent_reconciles: Option<Vec<ReconcileRecord>>,
 }
 
 /// Sync state tracking for secrets and properties
@@ -83,6 +104,105 @@ pub struct SyncStatus {
     pub properties: Option<std::collections::HashMap<String, ResourceSyncState>>,
 }
 
+/// A secret that failed to sync during reconciliation
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedSecret {
+    /// Secret name
+    pub name: String,
+    /// Reason the write failed
+    pub reason: String,
+    /// Time of the failed attempt (RFC3339)
+    #[serde(default)]
+    pub last_attempt_time: Option<String>,
+}
+
+impl FailedSecret {
+    /// Record a failure for `name` at the current time
+    pub fn new(name: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            reason: reason.into(),
+            last_attempt_time: Some(chrono::Utc::now().to_rfc3339()),
+        }
+    }
+}
+
+/// A service whose files could not be processed during reconciliation
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedService {
+    /// Service name
+    pub name: String,
+    /// Reason the service failed
+    pub reason: String,
+    /// Time of the failed attempt (RFC3339)
+    #[serde(default)]
+    pub last_attempt_time: Option<String>,
+}
+
+impl FailedService {
+    /// Record a failure for `name` at the current time
+    pub fn new(name: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            reason: reason.into(),
+            last_attempt_time: Some(chrono::Utc::now().to_rfc3339()),
+        }
+    }
+}
+
+/// A completed reconciliation, recorded in `recentReconciles`
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileRecord {
+    /// What triggered the reconciliation (e.g. "timer-based", "manual-cli", "error-backoff")
+    pub trigger: String,
+    /// Start time (RFC3339)
+    pub started_at: String,
+    /// Time taken in milliseconds
+    pub duration_ms: u64,
+    /// Values: Succeeded, Failed, PartialFailure, TransientError, AwaitingApproval,
+    /// WaitingForSource, InvalidSpec, Suspended
+    pub outcome: String,
+    /// Secrets and properties created or updated in the provider
+    #[serde(default)]
+    pub secrets_changed: u32,
+    /// Error message (truncated) when the reconciliation failed
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Change set staged for approval
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingChanges {
+    /// Hash identifying this change set; approve by setting the approve annotation to it
+    /// Covers operations and values, so any further change in Git requires a new approval
+    pub hash: String,
+    /// Source revision the changes were planned from
+    #[serde(default)]
+    pub revision: Option<String>,
+    /// Human-readable summary (e.g. "2 create, 1 update")
+    pub summary: String,
+    /// Planned operations in key order (values are never recorded)
+    #[serde(default)]
+    pub operations: Vec<PlannedOperation>,
+    /// Time the changes were planned (RFC3339)
+    #[serde(default)]
+    pub planned_at: Option<String>,
+}
+
+/// A single provider operation waiting for approval
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedOperation {
+    /// Operation (Create, Update, Delete, Disable, Enable)
+    pub action: String,
+    /// Secret or config name in the provider
+    pub key: String,
+}
+
 /// State tracking for a synced resource (secret or property)
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -97,20 +217,38 @@ pub struct ResourceSyncState {
     /// >0 = resource has been updated this many times due to value changes in Git
     #[serde(default)]
     pub update_count: i32,
+    /// Original source key when provider name sanitization rewrote it
+    /// (e.g. "db.password" stored as "db-password" on Azure)
+    #[serde(default)]
+    pub source_key: Option<String>,
+    /// Storage tier the value was last written with, for config stores with tiers
+    /// (AWS Parameter Store: "Standard" or "Advanced")
+    #[serde(default)]
+    pub tier: Option<String>,
+    /// Expiry the controller set on the secret from `secrets.expiration` (RFC3339)
+    /// Renewed when the value changes; drives the `ExpiringSoon` condition
+    #[serde(default)]
+    pub expire_time: Option<String>,
 }
 
-/// Condition represents a condition of a resource
+/// Condition represents a condition of a resource (`metav1.Condition` semantics)
+/// Optional fields keep statuses written by older controllers readable; the controller
+/// always sets them.
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Condition {
-    /// Type of condition
+    /// Type of condition (Ready, Reconciling, Stalled, SourceAvailable, DecryptionReady,
+    /// ConflictingWriter)
     pub r#type: String,
     /// Status of the condition (True, False, Unknown)
     pub status: String,
-    /// Last transition time
+    /// Generation the condition was computed for
+    #[serde(default)]
+    pub observed_generation: Option<i64>,
+    /// Last time the status changed
     #[serde(default)]
     pub last_transition_time: Option<String>,
-    /// Reason for the condition
+    /// Reason for the condition's last transition (CamelCase)
     #[serde(default)]
     pub reason: Option<String>,
     /// Message describing the condition
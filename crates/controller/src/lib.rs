@@ -21,3 +21,6 @@ pub mod observability;
 pub mod prelude;
 pub mod provider;
 pub mod runtime;
+pub mod secret;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
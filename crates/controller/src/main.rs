@@ -27,6 +27,7 @@
 //! See the [README.md](../README.md) for detailed usage instructions and examples.
 
 use anyhow::Result;
+use clap::Parser;
 
 mod config;
 mod constants;
@@ -35,21 +36,48 @@ pub mod crd;
 pub mod observability;
 pub mod provider;
 pub mod runtime;
+pub mod secret;
+#[cfg(test)]
+pub mod testing;
 
+use runtime::args::{ControllerArgs, ControllerCommand, CrdCommand};
 use runtime::initialization::initialize;
 use runtime::watch_loop::run_watch_loop;
 
+// Heap profiles (`/debug/pprof/heap`) read jemalloc's sampled allocation profile
+#[cfg(feature = "profiling")]
+#[global_allocator]
+static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+/// jemalloc options: sample one allocation per 512 KiB allocated (2^19 bytes)
+#[cfg(feature = "profiling")]
+#[allow(non_upper_case_globals, reason = "jemalloc reads this exact symbol")]
+#[unsafe(export_name = "malloc_conf")]
+pub static malloc_conf: &[u8] = b"prof:true,prof_active:true,lg_prof_sample:19\0";
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = ControllerArgs::parse();
+
+    if let Some(ControllerCommand::Crd {
+        command: CrdCommand::Generate,
+    }) = &args.command
+    {
+        print!("{}", crd::manifests::crd_yaml()?);
+        return Ok(());
+    }
+
     // Initialize the controller runtime
-    let init_result = initialize().await?;
+    let init_result = initialize(&args).await?;
 
     // Run the watch loop
     run_watch_loop(
         init_result.configs,
         init_result.reconciler,
         init_result.server_state,
+        init_result.schedule,
         init_result.controller_config,
+        &args,
     )
     .await?;
 
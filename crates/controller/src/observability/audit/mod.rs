@@ -0,0 +1,471 @@
+//! # Audit Log
+//!
+//! Append-only record of every provider mutation (create, update, delete, disable, enable).
+//!
+//! Each event carries the hash of the previous event, forming a SHA-256 chain: editing,
+//! removing, or reordering a record breaks every hash after it. `verify_chain` checks a
+//! sequence of events read back from the sink.
+//!
+//! Sinks:
+//! - `stdout`: one JSON line per event, interleaved with controller logs
+//! - `file`: JSON lines appended to a file; the chain resumes from the last line on restart
+//! - `http`: each event POSTed as JSON to an endpoint
+//!
+//! Secret values are never recorded, only their SHA-256 hash.
+
+pub mod provider;
+
+pub use provider::{AuditContext, AuditingProvider, Auditor};
+
+use crate::config::AuditConfig;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{error, info};
+
+/// `prevHash` of the first event in a chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Actor recorded on every event
+const ACTOR: &str = "controller";
+
+/// Timeout for a single HTTP sink delivery
+const HTTP_SINK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Kind of provider mutation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Disable,
+    Enable,
+}
+
+/// A mutation to record, as reported by the caller
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// `namespace/name` of the SecretManagerConfig
+    pub resource: String,
+    /// Secret or config key as stored in the provider
+    pub key: String,
+    /// Provider type (gcp, aws, azure)
+    pub provider: String,
+    pub old_version: Option<String>,
+    pub new_version: Option<String>,
+    /// Value written (hashed before recording; `None` for delete/disable/enable)
+    pub value: Option<Vec<u8>>,
+    pub trigger_source: String,
+    pub git_revision: Option<String>,
+}
+
+/// A recorded audit event (one line in the sink)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEvent {
+    /// Position in the chain, starting at 1
+    pub sequence: u64,
+    /// RFC3339 timestamp
+    pub timestamp: String,
+    pub actor: String,
+    pub action: AuditAction,
+    pub resource: String,
+    pub key: String,
+    pub provider: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_version: Option<String>,
+    /// `sha256:<hex>` of the value written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_hash: Option<String>,
+    pub trigger_source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_revision: Option<String>,
+    /// Hash of the previous event (`GENESIS_HASH` for the first)
+    pub prev_hash: String,
+    /// SHA-256 of `prevHash` followed by this event's JSON without `hash`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub hash: String,
+}
+
+impl AuditEvent {
+    /// Compute the chain hash of this event (ignores the current `hash` field)
+    #[must_use]
+    pub fn compute_hash(&self) -> String {
+        let mut unsigned = self.clone();
+        unsigned.hash = String::new();
+        // Serializing a plain struct of strings cannot fail
+        let body = serde_json::to_vec(&unsigned).unwrap_or_default();
+        let mut hasher = Sha256::new();
+        hasher.update(self.prev_hash.as_bytes());
+        hasher.update(&body);
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// Hash a written value for the audit record
+#[must_use]
+pub fn value_hash(value: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(value))
+}
+
+/// Verify a sequence of events forms an unbroken chain
+///
+/// `prev_hash` is the hash the first event should link to (`GENESIS_HASH` for a full log).
+/// Returns the sequence number of the first event that fails verification.
+pub fn verify_chain(events: &[AuditEvent], prev_hash: &str) -> Result<(), u64> {
+    let mut expected_prev = prev_hash.to_string();
+    let mut expected_sequence = events.first().map_or(1, |e| e.sequence);
+    for event in events {
+        if event.sequence != expected_sequence
+            || event.prev_hash != expected_prev
+            || event.hash != event.compute_hash()
+        {
+            return Err(event.sequence);
+        }
+        expected_prev.clone_from(&event.hash);
+        expected_sequence += 1;
+    }
+    Ok(())
+}
+
+/// Where events are written
+enum AuditSink {
+    Disabled,
+    Stdout,
+    File(PathBuf),
+    Http {
+        client: reqwest::Client,
+        endpoint: String,
+    },
+}
+
+/// Position of the last written event
+struct ChainState {
+    sequence: u64,
+    prev_hash: String,
+}
+
+impl ChainState {
+    fn genesis() -> Self {
+        Self {
+            sequence: 0,
+            prev_hash: GENESIS_HASH.to_string(),
+        }
+    }
+}
+
+/// Audit log shared by all reconciliations
+pub struct AuditLog {
+    sink: AuditSink,
+    // Held while an event is built and written so sink order matches chain order
+    chain: Mutex<ChainState>,
+}
+
+impl std::fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sink = match &self.sink {
+            AuditSink::Disabled => "none",
+            AuditSink::Stdout => "stdout",
+            AuditSink::File(_) => "file",
+            AuditSink::Http { .. } => "http",
+        };
+        f.debug_struct("AuditLog").field("sink", &sink).finish()
+    }
+}
+
+impl AuditLog {
+    /// Audit log that records nothing
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self {
+            sink: AuditSink::Disabled,
+            chain: Mutex::new(ChainState::genesis()),
+        }
+    }
+
+    /// Build the audit log from controller configuration
+    pub fn from_config(config: &AuditConfig) -> Result<Self> {
+        let (sink, chain) = match config.sink.to_lowercase().as_str() {
+            "" | "none" => (AuditSink::Disabled, ChainState::genesis()),
+            "stdout" => (AuditSink::Stdout, ChainState::genesis()),
+            "file" => {
+                let path = PathBuf::from(&config.file_path);
+                let chain = resume_chain(&path)?;
+                (AuditSink::File(path), chain)
+            }
+            "http" => {
+                let endpoint = config
+                    .http_endpoint
+                    .clone()
+                    .context("AUDIT_HTTP_ENDPOINT is required when AUDIT_SINK=http")?;
+                let client = reqwest::Client::builder()
+                    .timeout(HTTP_SINK_TIMEOUT)
+                    .build()
+                    .context("Failed to build audit HTTP client")?;
+                (AuditSink::Http { client, endpoint }, ChainState::genesis())
+            }
+            other => {
+                anyhow::bail!("Invalid AUDIT_SINK '{other}' (expected none, stdout, file, or http)")
+            }
+        };
+
+        if !matches!(sink, AuditSink::Disabled) {
+            info!(
+                "Audit log enabled: sink={}, resuming at sequence {}",
+                config.sink, chain.sequence
+            );
+        }
+
+        Ok(Self {
+            sink,
+            chain: Mutex::new(chain),
+        })
+    }
+
+    /// Whether events are recorded
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self.sink, AuditSink::Disabled)
+    }
+
+    /// Append an event for a provider mutation
+    ///
+    /// Delivery failures are logged, not returned: the mutation has already happened, and
+    /// the missing sequence number shows up as a gap when the chain is verified.
+    pub async fn record(&self, action: AuditAction, entry: AuditEntry) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut chain = self.chain.lock().await;
+        let mut event = AuditEvent {
+            sequence: chain.sequence + 1,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            actor: ACTOR.to_string(),
+            action,
+            resource: entry.resource,
+            key: entry.key,
+            provider: entry.provider,
+            old_version: entry.old_version,
+            new_version: entry.new_version,
+            value_hash: entry.value.as_deref().map(value_hash),
+            trigger_source: entry.trigger_source,
+            git_revision: entry.git_revision,
+            prev_hash: chain.prev_hash.clone(),
+            hash: String::new(),
+        };
+        event.hash = event.compute_hash();
+
+        if let Err(e) = self.write(&event).await {
+            error!(
+                "Failed to write audit event {} ({:?} {}/{}): {:#}",
+                event.sequence, event.action, event.resource, event.key, e
+            );
+        }
+
+        chain.sequence = event.sequence;
+        chain.prev_hash = event.hash;
+    }
+
+    async fn write(&self, event: &AuditEvent) -> Result<()> {
+        match &self.sink {
+            AuditSink::Disabled => Ok(()),
+            AuditSink::Stdout => {
+                let line = serde_json::to_string(event)?;
+                println!("{line}");
+                Ok(())
+            }
+            AuditSink::File(path) => {
+                let mut line = serde_json::to_vec(event)?;
+                line.push(b'\n');
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("Failed to open audit file {}", path.display()))?;
+                file.write_all(&line)?;
+                file.sync_data()?;
+                Ok(())
+            }
+            AuditSink::Http { client, endpoint } => {
+                client
+                    .post(endpoint)
+                    .json(event)
+                    .send()
+                    .await
+                    .context("Failed to send audit event")?
+                    .error_for_status()
+                    .context("Audit endpoint rejected event")?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Read the last event from an existing audit file so the chain continues across restarts
+fn resume_chain(path: &Path) -> Result<ChainState> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(ChainState::genesis()),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to open audit file {}", path.display()));
+        }
+    };
+
+    let mut last_line = None;
+    for line in BufReader::new(file).lines() {
+        let line = line.with_context(|| format!("Failed to read audit file {}", path.display()))?;
+        if !line.trim().is_empty() {
+            last_line = Some(line);
+        }
+    }
+
+    match last_line {
+        Some(line) => {
+            let event: AuditEvent = serde_json::from_str(&line).with_context(|| {
+                format!("Last line of {} is not an audit event", path.display())
+            })?;
+            Ok(ChainState {
+                sequence: event.sequence,
+                prev_hash: event.hash,
+            })
+        }
+        None => Ok(ChainState::genesis()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str) -> AuditEntry {
+        AuditEntry {
+            resource: "default/app".to_string(),
+            key: key.to_string(),
+            provider: "gcp".to_string(),
+            old_version: Some("1".to_string()),
+            new_version: Some("2".to_string()),
+            value: Some(b"s3cret".to_vec()),
+            trigger_source: "timer-or-event".to_string(),
+            git_revision: Some("main@sha1:abc123".to_string()),
+        }
+    }
+
+    fn file_config(path: &Path) -> AuditConfig {
+        AuditConfig {
+            sink: "file".to_string(),
+            file_path: path.display().to_string(),
+            http_endpoint: None,
+        }
+    }
+
+    fn read_events(path: &Path) -> Vec<AuditEvent> {
+        std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("read audit file: {e}"))
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap_or_else(|e| panic!("parse event: {e}")))
+            .collect()
+    }
+
+    #[test]
+    fn test_value_hash_never_contains_value() {
+        let hash = value_hash(b"s3cret");
+        assert!(hash.starts_with("sha256:"));
+        assert_eq!(hash.len(), "sha256:".len() + 64);
+        assert!(!hash.contains("s3cret"));
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_builds_verifiable_chain() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir: {e}"));
+        let path = dir.path().join("audit.jsonl");
+        let log = AuditLog::from_config(&file_config(&path))
+            .unwrap_or_else(|e| panic!("from_config: {e}"));
+
+        log.record(AuditAction::Create, entry("db-password")).await;
+        log.record(AuditAction::Update, entry("api-key")).await;
+
+        let events = read_events(&path);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].sequence, 1);
+        assert_eq!(events[0].prev_hash, GENESIS_HASH);
+        assert_eq!(events[1].prev_hash, events[0].hash);
+        assert_eq!(events[0].actor, "controller");
+        assert_eq!(events[1].action, AuditAction::Update);
+        assert_eq!(verify_chain(&events, GENESIS_HASH), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_resumes_chain_after_restart() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir: {e}"));
+        let path = dir.path().join("audit.jsonl");
+
+        let first = AuditLog::from_config(&file_config(&path))
+            .unwrap_or_else(|e| panic!("from_config: {e}"));
+        first
+            .record(AuditAction::Create, entry("db-password"))
+            .await;
+
+        let second = AuditLog::from_config(&file_config(&path))
+            .unwrap_or_else(|e| panic!("from_config: {e}"));
+        second
+            .record(AuditAction::Delete, entry("db-password"))
+            .await;
+
+        let events = read_events(&path);
+        assert_eq!(events[1].sequence, 2);
+        assert_eq!(verify_chain(&events, GENESIS_HASH), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_verify_chain_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir: {e}"));
+        let path = dir.path().join("audit.jsonl");
+        let log = AuditLog::from_config(&file_config(&path))
+            .unwrap_or_else(|e| panic!("from_config: {e}"));
+        for key in ["a", "b", "c"] {
+            log.record(AuditAction::Update, entry(key)).await;
+        }
+        let events = read_events(&path);
+
+        let mut edited = events.clone();
+        edited[1].key = "other".to_string();
+        assert_eq!(verify_chain(&edited, GENESIS_HASH), Err(2));
+
+        let mut removed = events.clone();
+        removed.remove(1);
+        assert_eq!(verify_chain(&removed, GENESIS_HASH), Err(3));
+
+        let mut rehashed = events;
+        rehashed[0].key = "other".to_string();
+        rehashed[0].hash = rehashed[0].compute_hash();
+        assert_eq!(verify_chain(&rehashed, GENESIS_HASH), Err(2));
+    }
+
+    #[test]
+    fn test_from_config_rejects_invalid_sink() {
+        let config = AuditConfig {
+            sink: "syslog".to_string(),
+            ..AuditConfig::default()
+        };
+        assert!(AuditLog::from_config(&config).is_err());
+
+        let config = AuditConfig {
+            sink: "http".to_string(),
+            ..AuditConfig::default()
+        };
+        assert!(AuditLog::from_config(&config).is_err());
+        assert!(
+            !AuditLog::from_config(&AuditConfig::default())
+                .unwrap_or_else(|e| panic!("from_config: {e}"))
+                .is_enabled()
+        );
+    }
+}
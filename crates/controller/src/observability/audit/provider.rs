@@ -0,0 +1,302 @@
+//! # Auditing Provider
+//!
+//! `SecretManagerProvider` wrapper that records every mutation to the audit log.
+//!
+//! Config store writes (Parameter Manager, Parameter Store, App Configuration) bypass the
+//! secret provider, so `store_properties` records them through `SecretManagerProvider::auditor`.
+
+use super::{AuditAction, AuditEntry, AuditLog};
+use crate::crd::{ProviderConfig, SecretManagerConfig};
+use crate::provider::{SecretManagerProvider, SecretMetadata, SecretVersionIds};
+use crate::secret::SecretString;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::debug;
+
+/// Per-reconciliation details attached to every audit event
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    /// `namespace/name` of the SecretManagerConfig
+    pub resource: String,
+    pub provider: String,
+    pub trigger_source: String,
+    pub git_revision: Option<String>,
+    /// Secrets already pushed in earlier reconciliations (writes to these are updates)
+    pub existing_secrets: HashSet<String>,
+}
+
+impl AuditContext {
+    #[must_use]
+    pub fn new(
+        config: &SecretManagerConfig,
+        trigger_source: &str,
+        git_revision: Option<String>,
+    ) -> Self {
+        let provider = match &config.spec.provider {
+            ProviderConfig::Gcp(_) => "gcp",
+            ProviderConfig::Aws(_) => "aws",
+            ProviderConfig::Azure(_) => "azure",
+        };
+        let existing_secrets = config
+            .status
+            .as_ref()
+            .and_then(|s| s.sync.as_ref())
+            .and_then(|sync| sync.secrets.as_ref())
+            .map(|secrets| {
+                secrets
+                    .iter()
+                    .filter(|(_, state)| state.exists)
+                    .map(|(name, _)| name.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            resource: format!(
+                "{}/{}",
+                config.metadata.namespace.as_deref().unwrap_or("default"),
+                config.metadata.name.as_deref().unwrap_or("unknown")
+            ),
+            provider: provider.to_string(),
+            trigger_source: trigger_source.to_string(),
+            git_revision,
+            existing_secrets,
+        }
+    }
+}
+
+/// Records mutations for one reconciliation
+#[derive(Debug, Clone)]
+pub struct Auditor {
+    log: Arc<AuditLog>,
+    context: AuditContext,
+}
+
+impl Auditor {
+    #[must_use]
+    pub fn new(log: Arc<AuditLog>, context: AuditContext) -> Self {
+        Self { log, context }
+    }
+
+    fn entry(&self, key: &str) -> AuditEntry {
+        AuditEntry {
+            resource: self.context.resource.clone(),
+            key: key.to_string(),
+            provider: self.context.provider.clone(),
+            trigger_source: self.context.trigger_source.clone(),
+            git_revision: self.context.git_revision.clone(),
+            ..AuditEntry::default()
+        }
+    }
+
+    /// Record a secret write that changed the stored value
+    pub async fn record_secret_write(&self, key: &str, value: &[u8], versions: SecretVersionIds) {
+        let action = if self.context.existing_secrets.contains(key) {
+            AuditAction::Update
+        } else {
+            AuditAction::Create
+        };
+        self.record_write(action, key, value, versions).await;
+    }
+
+    /// Record a config store write that changed the stored value
+    /// `existed` comes from the config's sync status before the write
+    pub async fn record_config_write(&self, key: &str, value: &str, existed: bool) {
+        let action = if existed {
+            AuditAction::Update
+        } else {
+            AuditAction::Create
+        };
+        self.record_write(action, key, value.as_bytes(), SecretVersionIds::default())
+            .await;
+    }
+
+    async fn record_write(
+        &self,
+        action: AuditAction,
+        key: &str,
+        value: &[u8],
+        versions: SecretVersionIds,
+    ) {
+        let entry = AuditEntry {
+            old_version: if action == AuditAction::Update {
+                versions.previous
+            } else {
+                None
+            },
+            new_version: versions.current,
+            value: Some(value.to_vec()),
+            ..self.entry(key)
+        };
+        self.log.record(action, entry).await;
+    }
+
+    /// Record a delete, disable, or enable
+    pub async fn record(&self, action: AuditAction, key: &str) {
+        self.log.record(action, self.entry(key)).await;
+    }
+}
+
+/// Provider wrapper that audits every mutation of the inner provider
+pub struct AuditingProvider {
+    inner: Box<dyn SecretManagerProvider>,
+    auditor: Auditor,
+}
+
+impl std::fmt::Debug for AuditingProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditingProvider")
+            .field("auditor", &self.auditor)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuditingProvider {
+    #[must_use]
+    pub fn new(inner: Box<dyn SecretManagerProvider>, auditor: Auditor) -> Self {
+        Self { inner, auditor }
+    }
+
+    async fn versions_after_write(&self, secret_name: &str) -> SecretVersionIds {
+        // Version IDs are best-effort; a failed lookup must not hide the write itself
+        self.inner
+            .version_ids(secret_name)
+            .await
+            .unwrap_or_else(|e| {
+                debug!(
+                    "Failed to read version IDs of {} for audit: {}",
+                    secret_name, e
+                );
+                SecretVersionIds::default()
+            })
+    }
+}
+
+#[async_trait]
+impl SecretManagerProvider for AuditingProvider {
+    async fn create_or_update_secret(
+        &self,
+        secret_name: &str,
+        secret_value: &SecretString,
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        let changed = self
+            .inner
+            .create_or_update_secret(secret_name, secret_value, environment, location)
+            .await?;
+        if changed {
+            let versions = self.versions_after_write(secret_name).await;
+            self.auditor
+                .record_secret_write(
+                    secret_name,
+                    secret_value.expose_secret().as_bytes(),
+                    versions,
+                )
+                .await;
+        }
+        Ok(changed)
+    }
+
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
+        self.inner.get_secret_value(secret_name).await
+    }
+
+    async fn get_secret_values(
+        &self,
+        secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        self.inner.get_secret_values(secret_names).await
+    }
+
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        let changed = self
+            .inner
+            .create_or_update_secret_binary(secret_name, secret_value, environment, location)
+            .await?;
+        if changed {
+            let versions = self.versions_after_write(secret_name).await;
+            self.auditor
+                .record_secret_write(secret_name, secret_value, versions)
+                .await;
+        }
+        Ok(changed)
+    }
+
+    async fn get_secret_binary(&self, secret_name: &str) -> Result<Option<Vec<u8>>> {
+        self.inner.get_secret_binary(secret_name).await
+    }
+
+    async fn delete_secret(&self, secret_name: &str) -> Result<()> {
+        self.inner.delete_secret(secret_name).await?;
+        self.auditor.record(AuditAction::Delete, secret_name).await;
+        Ok(())
+    }
+
+    async fn disable_secret(&self, secret_name: &str) -> Result<bool> {
+        let changed = self.inner.disable_secret(secret_name).await?;
+        if changed {
+            self.auditor.record(AuditAction::Disable, secret_name).await;
+        }
+        Ok(changed)
+    }
+
+    async fn enable_secret(&self, secret_name: &str) -> Result<bool> {
+        let changed = self.inner.enable_secret(secret_name).await?;
+        if changed {
+            self.auditor.record(AuditAction::Enable, secret_name).await;
+        }
+        Ok(changed)
+    }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        self.inner.version_ids(secret_name).await
+    }
+
+    async fn set_secret_labels(
+        &self,
+        secret_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        self.inner.set_secret_labels(secret_name, labels).await
+    }
+
+    async fn set_secret_expiration(
+        &self,
+        secret_name: &str,
+        expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        self.inner
+            .set_secret_expiration(secret_name, expire_time)
+            .await
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        self.inner.list_secrets(prefix).await
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        self.inner.get_secret_metadata(secret_name).await
+    }
+
+    fn auditor(&self) -> Option<&Auditor> {
+        Some(&self.auditor)
+    }
+
+    fn rate_limiter(&self) -> Option<&crate::controller::reconciler::rate_limit::TokenBucket> {
+        self.inner.rate_limiter()
+    }
+
+    fn router(&self) -> Option<&crate::controller::reconciler::routing::KeyRouter> {
+        self.inner.router()
+    }
+}
@@ -0,0 +1,98 @@
+//! # Datadog Log Correlation
+//!
+//! Links log lines to the Datadog APM trace they were emitted in.
+//!
+//! When the Datadog exporter is active, `DatadogLogFormat` wraps the standard log line
+//! format and appends `dd.trace_id` and `dd.span_id` to every event logged inside a span
+//! backed by a valid OpenTelemetry span context (the reconcile spans and everything logged
+//! under them). Datadog expects both IDs in decimal, with the trace ID truncated to its lower
+//! 64 bits. Events logged outside such spans are formatted unchanged.
+
+use opentelemetry::trace::TraceContextExt;
+use std::fmt::{self, Write as _};
+use tracing::{Event, Subscriber};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::fmt::format::{Format, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Datadog correlation fields for a span (`dd.trace_id=... dd.span_id=...`)
+pub fn correlation_fields(trace_id: u128, span_id: u64) -> String {
+    // Datadog trace IDs are the lower 64 bits of the OpenTelemetry trace ID
+    #[allow(
+        clippy::cast_possible_truncation,
+        reason = "Datadog uses the lower 64 bits"
+    )]
+    let dd_trace_id = trace_id as u64;
+    format!("dd.trace_id={dd_trace_id} dd.span_id={span_id}")
+}
+
+/// Correlation fields for the current span, or none if it has no valid OpenTelemetry context
+pub fn current_correlation_fields() -> Option<String> {
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    if !span_context.is_valid() {
+        return None;
+    }
+    Some(correlation_fields(
+        u128::from_be_bytes(span_context.trace_id().to_bytes()),
+        u64::from_be_bytes(span_context.span_id().to_bytes()),
+    ))
+}
+
+/// Log line format that appends Datadog correlation IDs to events logged inside traced spans
+#[derive(Debug, Default)]
+pub struct DatadogLogFormat<F = Format> {
+    inner: F,
+}
+
+impl<F> DatadogLogFormat<F> {
+    #[must_use]
+    pub fn new(inner: F) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S, N, F> FormatEvent<S, N> for DatadogLogFormat<F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    F: FormatEvent<S, N>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let Some(fields) = current_correlation_fields() else {
+            return self.inner.format_event(ctx, writer, event);
+        };
+        // The inner format ends the line, so format it first and append before the newline
+        let mut line = String::new();
+        self.inner
+            .format_event(ctx, Writer::new(&mut line), event)?;
+        let line = line.strip_suffix('\n').unwrap_or(&line);
+        writeln!(writer, "{line} {fields}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correlation_fields_use_lower_64_bits() {
+        let trace_id = (7_u128 << 64) | 42;
+        assert_eq!(
+            correlation_fields(trace_id, 1_234),
+            "dd.trace_id=42 dd.span_id=1234"
+        );
+    }
+
+    #[test]
+    fn test_current_correlation_fields_without_otel() {
+        assert!(current_correlation_fields().is_none());
+    }
+}
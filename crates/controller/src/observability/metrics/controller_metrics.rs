@@ -1,11 +1,13 @@
 //! # Controller Metrics
 //!
-//! Metrics for controller operations: reconciliations, secrets management, and requeues.
+//! Metrics for controller operations: reconciliations, secrets management, requeues, and
+//! reconcile concurrency.
 
 use crate::observability::metrics::registry::REGISTRY;
 use anyhow::Result;
-use prometheus::{Histogram, IntCounter, IntCounterVec, IntGauge};
-use std::sync::LazyLock;
+use prometheus::{GaugeVec, Histogram, IntCounter, IntCounterVec, IntGauge, IntGaugeVec};
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
 
 // Controller reconciliation metrics
 static RECONCILIATIONS_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
@@ -72,6 +74,106 @@ static REQUEUES_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
     .expect("Failed to create REQUEUES_TOTAL metric - this should never happen")
 });
 
+static LIMIT_EXCEEDED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        prometheus::Opts::new(
+            "secret_manager_limit_exceeded_total",
+            "Total number of reconciliations stopped because values exceeded a resource's limits",
+        ),
+        &["limit"],
+    )
+    .expect("Failed to create LIMIT_EXCEEDED_TOTAL metric - this should never happen")
+});
+
+static POLICY_VIOLATIONS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        prometheus::Opts::new(
+            "secret_manager_policy_violations_total",
+            "Total number of keys rejected by a resource's secrets.policy",
+        ),
+        &["rule"],
+    )
+    .expect("Failed to create POLICY_VIOLATIONS_TOTAL metric - this should never happen")
+});
+
+static CONFLICTING_WRITERS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        prometheus::Opts::new(
+            "secret_manager_conflicting_writers_total",
+            "Total number of drifted secrets last written by someone other than their resource",
+        ),
+        &["kind"],
+    )
+    .expect("Failed to create CONFLICTING_WRITERS_TOTAL metric - this should never happen")
+});
+
+// Concurrency metrics
+static RECONCILE_QUEUE_DEPTH: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    IntGaugeVec::new(
+        prometheus::Opts::new(
+            "secret_manager_reconcile_queue_depth",
+            "Number of reconciliations waiting for a provider concurrency slot",
+        ),
+        &["provider"],
+    )
+    .expect("Failed to create RECONCILE_QUEUE_DEPTH metric - this should never happen")
+});
+
+// Per-resource inventory metrics
+const RESOURCE_LABELS: &[&str] = &["namespace", "name", "provider", "environment"];
+
+static MANAGED_SECRETS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    IntGaugeVec::new(
+        prometheus::Opts::new(
+            "secret_manager_managed_secrets_total",
+            "Number of secrets managed by each SecretManagerConfig",
+        ),
+        RESOURCE_LABELS,
+    )
+    .expect("Failed to create MANAGED_SECRETS metric - this should never happen")
+});
+
+static MANAGED_CONFIGS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    IntGaugeVec::new(
+        prometheus::Opts::new(
+            "secret_manager_managed_configs_total",
+            "Number of config values (properties) managed by each SecretManagerConfig",
+        ),
+        RESOURCE_LABELS,
+    )
+    .expect("Failed to create MANAGED_CONFIGS metric - this should never happen")
+});
+
+static SECRETS_EXPIRING_SOON: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    IntGaugeVec::new(
+        prometheus::Opts::new(
+            "secret_manager_secrets_expiring_soon",
+            "Number of secrets of each SecretManagerConfig expiring within warnBefore",
+        ),
+        RESOURCE_LABELS,
+    )
+    .expect("Failed to create SECRETS_EXPIRING_SOON metric - this should never happen")
+});
+
+static LAST_SUCCESSFUL_RECONCILE_TIMESTAMP: LazyLock<GaugeVec> = LazyLock::new(|| {
+    GaugeVec::new(
+        prometheus::Opts::new(
+            "secret_manager_last_successful_reconcile_timestamp_seconds",
+            "Unix timestamp of the last successful reconcile of each SecretManagerConfig",
+        ),
+        RESOURCE_LABELS,
+    )
+    .expect(
+        "Failed to create LAST_SUCCESSFUL_RECONCILE_TIMESTAMP metric - this should never happen",
+    )
+});
+
+/// Label values last used for each resource, keyed by (namespace, name)
+/// Lets a resource's series be replaced when its provider or environment changes, and removed
+/// when the resource is deleted
+static RESOURCE_LABEL_VALUES: LazyLock<Mutex<HashMap<(String, String), [String; 4]>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 /// Register controller metrics with the registry
 pub(crate) fn register_controller_metrics() -> Result<()> {
     REGISTRY.register(Box::new(RECONCILIATIONS_TOTAL.clone()))?;
@@ -81,6 +183,14 @@ pub(crate) fn register_controller_metrics() -> Result<()> {
     REGISTRY.register(Box::new(SECRETS_UPDATED_TOTAL.clone()))?;
     REGISTRY.register(Box::new(SECRETS_MANAGED.clone()))?;
     REGISTRY.register(Box::new(REQUEUES_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(LIMIT_EXCEEDED_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(POLICY_VIOLATIONS_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(CONFLICTING_WRITERS_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(RECONCILE_QUEUE_DEPTH.clone()))?;
+    REGISTRY.register(Box::new(MANAGED_SECRETS.clone()))?;
+    REGISTRY.register(Box::new(MANAGED_CONFIGS.clone()))?;
+    REGISTRY.register(Box::new(SECRETS_EXPIRING_SOON.clone()))?;
+    REGISTRY.register(Box::new(LAST_SUCCESSFUL_RECONCILE_TIMESTAMP.clone()))?;
     Ok(())
 }
 
@@ -118,6 +228,123 @@ pub fn increment_requeues_total(reason: &str) {
     REQUEUES_TOTAL.with_label_values(&[reason]).inc();
 }
 
+/// Count a reconciliation stopped by a value limit (`maxValueBytes`, `maxKeys`, `maxTotalBytes`)
+pub fn increment_limit_exceeded_total(limit: &str) {
+    LIMIT_EXCEEDED_TOTAL.with_label_values(&[limit]).inc();
+}
+
+/// Count a key rejected by a policy rule (`forbiddenKeyPatterns`, `requireSopsForKeys`,
+/// `rejectPlaintextPrivateKeys`, `requireEncryptedSource`)
+pub fn increment_policy_violations_total(rule: &str) {
+    POLICY_VIOLATIONS_TOTAL.with_label_values(&[rule]).inc();
+}
+
+/// Count a drifted secret last written by someone else (`foreign` for writes outside the
+/// controller, `resource` for another SecretManagerConfig)
+pub fn increment_conflicting_writers_total(kind: &str) {
+    CONFLICTING_WRITERS_TOTAL.with_label_values(&[kind]).inc();
+}
+
+pub fn increment_reconcile_queue_depth(provider: &str) {
+    RECONCILE_QUEUE_DEPTH.with_label_values(&[provider]).inc();
+}
+
+pub fn decrement_reconcile_queue_depth(provider: &str) {
+    RECONCILE_QUEUE_DEPTH.with_label_values(&[provider]).dec();
+}
+
+/// Remove all per-resource series for a label set
+fn remove_resource_series(labels: &[String; 4]) {
+    let values: Vec<&str> = labels.iter().map(String::as_str).collect();
+    let _ = MANAGED_SECRETS.remove_label_values(&values);
+    let _ = MANAGED_CONFIGS.remove_label_values(&values);
+    let _ = SECRETS_EXPIRING_SOON.remove_label_values(&values);
+    let _ = LAST_SUCCESSFUL_RECONCILE_TIMESTAMP.remove_label_values(&values);
+}
+
+/// Record the label values for a resource, dropping its old series if they changed
+fn resource_labels(namespace: &str, name: &str, provider: &str, environment: &str) -> [String; 4] {
+    let labels = [
+        namespace.to_string(),
+        name.to_string(),
+        provider.to_string(),
+        environment.to_string(),
+    ];
+    let mut known = RESOURCE_LABEL_VALUES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(previous) = known.insert((namespace.to_string(), name.to_string()), labels.clone())
+        && previous != labels
+    {
+        remove_resource_series(&previous);
+    }
+    labels
+}
+
+/// Set the secret and config inventory of a resource
+pub fn set_resource_inventory(
+    namespace: &str,
+    name: &str,
+    provider: &str,
+    environment: &str,
+    secrets: usize,
+    configs: usize,
+) {
+    let labels = resource_labels(namespace, name, provider, environment);
+    let values: Vec<&str> = labels.iter().map(String::as_str).collect();
+    MANAGED_SECRETS
+        .with_label_values(&values)
+        .set(i64::try_from(secrets).unwrap_or(i64::MAX));
+    MANAGED_CONFIGS
+        .with_label_values(&values)
+        .set(i64::try_from(configs).unwrap_or(i64::MAX));
+}
+
+/// Set the number of secrets of a resource that expire within its `warnBefore`
+pub fn set_secrets_expiring_soon(
+    namespace: &str,
+    name: &str,
+    provider: &str,
+    environment: &str,
+    expiring: usize,
+) {
+    let labels = resource_labels(namespace, name, provider, environment);
+    let values: Vec<&str> = labels.iter().map(String::as_str).collect();
+    SECRETS_EXPIRING_SOON
+        .with_label_values(&values)
+        .set(i64::try_from(expiring).unwrap_or(i64::MAX));
+}
+
+/// Set the last successful reconcile time of a resource (Unix seconds)
+pub fn set_last_successful_reconcile_timestamp(
+    namespace: &str,
+    name: &str,
+    provider: &str,
+    environment: &str,
+    timestamp: f64,
+) {
+    let labels = resource_labels(namespace, name, provider, environment);
+    let values: Vec<&str> = labels.iter().map(String::as_str).collect();
+    LAST_SUCCESSFUL_RECONCILE_TIMESTAMP
+        .with_label_values(&values)
+        .set(timestamp);
+}
+
+/// Drop per-resource series for resources not in `live` (namespace, name) pairs
+/// Called with the watch cache contents so deleted resources stop being reported
+pub fn retain_resource_metrics(live: &HashSet<(String, String)>) {
+    let mut known = RESOURCE_LABEL_VALUES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    known.retain(|key, labels| {
+        let keep = live.contains(key);
+        if !keep {
+            remove_resource_series(labels);
+        }
+        keep
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,4 +402,50 @@ mod tests {
         set_secrets_managed(20);
         assert_eq!(SECRETS_MANAGED.get(), 20);
     }
+
+    #[test]
+    fn test_reconcile_queue_depth() {
+        increment_reconcile_queue_depth("test-provider");
+        increment_reconcile_queue_depth("test-provider");
+        decrement_reconcile_queue_depth("test-provider");
+        assert_eq!(
+            RECONCILE_QUEUE_DEPTH
+                .with_label_values(&["test-provider"])
+                .get(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_resource_inventory_lifecycle() {
+        set_resource_inventory("inv-ns", "inv", "gcp", "dev", 3, 2);
+        set_last_successful_reconcile_timestamp("inv-ns", "inv", "gcp", "dev", 1_700_000_000.0);
+        assert_eq!(
+            MANAGED_SECRETS
+                .with_label_values(&["inv-ns", "inv", "gcp", "dev"])
+                .get(),
+            3
+        );
+
+        // Changing the environment replaces the old series
+        set_resource_inventory("inv-ns", "inv", "gcp", "prod", 4, 2);
+        assert!(
+            MANAGED_SECRETS
+                .get_metric_with_label_values(&["inv-ns", "inv", "gcp", "prod"])
+                .is_ok()
+        );
+        assert!(
+            MANAGED_SECRETS
+                .remove_label_values(&["inv-ns", "inv", "gcp", "dev"])
+                .is_err()
+        );
+
+        // Deleted resources are dropped
+        retain_resource_metrics(&HashSet::new());
+        assert!(
+            MANAGED_CONFIGS
+                .remove_label_values(&["inv-ns", "inv", "gcp", "prod"])
+                .is_err()
+        );
+    }
 }
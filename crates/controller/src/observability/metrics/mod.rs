@@ -8,14 +8,17 @@
 //! - `controller_metrics` - Controller-specific metrics (reconciliations, secrets, requeues)
 //! - `provider_metrics` - Provider-specific metrics (GCP, generic provider operations)
 //! - `processing_metrics` - Processing operation metrics (SOPS, Kustomize, Git, Artifacts)
+//! - `runtime_metrics` - Controller process health (tokio runtime, reconcile queue lag, memory)
 
 pub mod controller_metrics;
 pub mod processing_metrics;
 pub mod provider_metrics;
 pub mod registry;
+pub mod runtime_metrics;
 
 // Re-export all public functions for backward compatibility
 pub use controller_metrics::*;
 pub use processing_metrics::*;
 pub use provider_metrics::*;
 pub use registry::*;
+pub use runtime_metrics::*;
@@ -177,6 +177,46 @@ static ARTIFACT_EXTRACTION_ERRORS_TOTAL: LazyLock<IntCounter> = LazyLock::new(||
     .expect("Failed to create ARTIFACT_EXTRACTION_ERRORS_TOTAL metric - this should never happen")
 });
 
+static ARTIFACT_CACHE_HITS_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "secret_manager_artifact_cache_hits_total",
+        "Total number of FluxCD artifacts served from the shared artifact cache",
+    )
+    .expect("Failed to create ARTIFACT_CACHE_HITS_TOTAL metric - this should never happen")
+});
+
+static ARTIFACT_CACHE_MISSES_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "secret_manager_artifact_cache_misses_total",
+        "Total number of FluxCD artifacts downloaded because they were not in the artifact cache",
+    )
+    .expect("Failed to create ARTIFACT_CACHE_MISSES_TOTAL metric - this should never happen")
+});
+
+static ARTIFACT_CACHE_EVICTIONS_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "secret_manager_artifact_cache_evictions_total",
+        "Total number of artifacts evicted from the artifact cache (least recently used first)",
+    )
+    .expect("Failed to create ARTIFACT_CACHE_EVICTIONS_TOTAL metric - this should never happen")
+});
+
+static DELTA_RECONCILES_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "secret_manager_delta_reconciles_total",
+        "Total number of reconciliations limited to the profiles changed since the last-synced revision",
+    )
+    .expect("Failed to create DELTA_RECONCILES_TOTAL metric - this should never happen")
+});
+
+static DELTA_SKIPPED_PROFILES_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "secret_manager_delta_skipped_profiles_total",
+        "Total number of unchanged profiles skipped by delta reconciliation",
+    )
+    .expect("Failed to create DELTA_SKIPPED_PROFILES_TOTAL metric - this should never happen")
+});
+
 /// Register processing metrics with the registry
 pub(crate) fn register_processing_metrics() -> Result<()> {
     REGISTRY.register(Box::new(DURATION_PARSING_ERRORS_TOTAL.clone()))?;
@@ -197,6 +237,11 @@ pub(crate) fn register_processing_metrics() -> Result<()> {
     REGISTRY.register(Box::new(ARTIFACT_EXTRACTIONS_TOTAL.clone()))?;
     REGISTRY.register(Box::new(ARTIFACT_EXTRACTION_DURATION.clone()))?;
     REGISTRY.register(Box::new(ARTIFACT_EXTRACTION_ERRORS_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(ARTIFACT_CACHE_HITS_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(ARTIFACT_CACHE_MISSES_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(ARTIFACT_CACHE_EVICTIONS_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(DELTA_RECONCILES_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(DELTA_SKIPPED_PROFILES_TOTAL.clone()))?;
     Ok(())
 }
 
@@ -278,6 +323,26 @@ pub fn increment_artifact_extraction_errors_total() {
     ARTIFACT_EXTRACTION_ERRORS_TOTAL.inc();
 }
 
+pub fn increment_artifact_cache_hits_total() {
+    ARTIFACT_CACHE_HITS_TOTAL.inc();
+}
+
+pub fn increment_artifact_cache_misses_total() {
+    ARTIFACT_CACHE_MISSES_TOTAL.inc();
+}
+
+pub fn increment_artifact_cache_evictions_total(count: u64) {
+    ARTIFACT_CACHE_EVICTIONS_TOTAL.inc_by(count);
+}
+
+pub fn increment_delta_reconciles_total() {
+    DELTA_RECONCILES_TOTAL.inc();
+}
+
+pub fn increment_delta_skipped_profiles_total(count: u64) {
+    DELTA_SKIPPED_PROFILES_TOTAL.inc_by(count);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,6 +66,19 @@ static PROVIDER_OPERATION_ERRORS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(
     .expect("Failed to create PROVIDER_OPERATION_ERRORS_TOTAL metric - this should never happen")
 });
 
+// Provider API latency, recorded per HTTP request inside each provider client
+static PROVIDER_API_REQUEST_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    HistogramVec::new(
+        prometheus::HistogramOpts::new(
+            "secret_manager_provider_api_request_duration_seconds",
+            "Duration of individual provider API requests in seconds by provider, operation and status code",
+        )
+        .buckets(vec![0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+        &["provider", "operation", "status_code"],
+    )
+    .expect("Failed to create PROVIDER_API_REQUEST_DURATION metric - this should never happen")
+});
+
 // Secret publishing metrics
 static SECRETS_PUBLISHED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
     IntCounterVec::new(
@@ -100,6 +113,41 @@ static SECRETS_DIFF_DETECTED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
     .expect("Failed to create SECRETS_DIFF_DETECTED_TOTAL metric - this should never happen")
 });
 
+static SECRET_VERSIONS_DESTROYED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        prometheus::Opts::new(
+            "secret_manager_secret_versions_destroyed_total",
+            "Total number of superseded secret versions destroyed by version retention",
+        ),
+        &["provider"],
+    )
+    .expect("Failed to create SECRET_VERSIONS_DESTROYED_TOTAL metric - this should never happen")
+});
+
+static PROVIDER_NOT_FOUND_CACHE_HITS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        prometheus::Opts::new(
+            "secret_manager_provider_not_found_cache_hits_total",
+            "Total number of provider reads skipped because the secret was not found at the same revision",
+        ),
+        &["provider"],
+    )
+    .expect(
+        "Failed to create PROVIDER_NOT_FOUND_CACHE_HITS_TOTAL metric - this should never happen",
+    )
+});
+
+static VAULT_THROTTLED_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        prometheus::Opts::new(
+            "secret_manager_vault_throttled_total",
+            "Total number of Azure Key Vault requests rejected with 429 (Too Many Requests)",
+        ),
+        &["vault"],
+    )
+    .expect("Failed to create VAULT_THROTTLED_TOTAL metric - this should never happen")
+});
+
 /// Register provider metrics with the registry
 pub(crate) fn register_provider_metrics() -> Result<()> {
     REGISTRY.register(Box::new(GCP_SECRET_MANAGER_OPERATIONS_TOTAL.clone()))?;
@@ -107,9 +155,13 @@ pub(crate) fn register_provider_metrics() -> Result<()> {
     REGISTRY.register(Box::new(PROVIDER_OPERATIONS_TOTAL.clone()))?;
     REGISTRY.register(Box::new(PROVIDER_OPERATION_DURATION.clone()))?;
     REGISTRY.register(Box::new(PROVIDER_OPERATION_ERRORS_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(PROVIDER_API_REQUEST_DURATION.clone()))?;
     REGISTRY.register(Box::new(SECRETS_PUBLISHED_TOTAL.clone()))?;
     REGISTRY.register(Box::new(SECRETS_SKIPPED_TOTAL.clone()))?;
     REGISTRY.register(Box::new(SECRETS_DIFF_DETECTED_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(SECRET_VERSIONS_DESTROYED_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(PROVIDER_NOT_FOUND_CACHE_HITS_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(VAULT_THROTTLED_TOTAL.clone()))?;
     Ok(())
 }
 
@@ -149,6 +201,24 @@ pub fn increment_provider_operation_errors(provider: &str) {
         .inc();
 }
 
+/// Increment Azure Key Vault requests throttled by the vault
+pub fn increment_vault_throttled(vault: &str) {
+    VAULT_THROTTLED_TOTAL.with_label_values(&[vault]).inc();
+}
+
+/// Record the duration of a single provider API request
+/// `status_code` is the HTTP status, or "error" when no response was received
+pub fn observe_provider_api_request(
+    provider: &str,
+    operation: &str,
+    status_code: &str,
+    duration: f64,
+) {
+    PROVIDER_API_REQUEST_DURATION
+        .with_label_values(&[provider, operation, status_code])
+        .observe(duration);
+}
+
 pub fn increment_secrets_published_total(provider: &str, count: u64) {
     SECRETS_PUBLISHED_TOTAL
         .with_label_values(&[provider])
@@ -161,6 +231,11 @@ pub fn increment_secrets_diff_detected_total(provider: &str) {
         .inc();
 }
 
+/// Increment Azure Key Vault requests throttled by the vault
+pub fn increment_vault_throttled(vault: &str) {
+    VAULT_THROTTLED_TOTAL.with_label_values(&[vault]).inc();
+}
+
 pub fn increment_secrets_skipped_total(provider: &str, reason: &str) {
     SECRETS_SKIPPED_TOTAL
         .with_label_values(&[provider, reason])
@@ -226,4 +301,32 @@ mod tests {
             .get();
         assert_eq!(after, before + 1u64);
     }
+
+    #[test]
+    fn test_observe_provider_api_request() {
+        let histogram =
+            PROVIDER_API_REQUEST_DURATION.with_label_values(&["azure", "get_secret", "404"]);
+        let before = histogram.get_sample_count();
+        observe_provider_api_request("azure", "get_secret", "404", 0.05);
+        assert_eq!(histogram.get_sample_count(), before + 1);
+    }
+}
+
+/// Increment destroyed secret versions counter (version retention pruning)
+pub fn increment_secret_versions_destroyed(provider: &str, count: u64) {
+    SECRET_VERSIONS_DESTROYED_TOTAL
+        .with_label_values(&[provider])
+        .inc_by(count);
+}
+
+/// Increment reads answered by the provider not-found cache
+pub fn increment_provider_not_found_cache_hits(provider: &str) {
+    PROVIDER_NOT_FOUND_CACHE_HITS_TOTAL
+        .with_label_values(&[provider])
+        .inc();
+}
+
+/// Increment Azure Key Vault requests throttled by the vault
+pub fn increment_vault_throttled(vault: &str) {
+    VAULT_THROTTLED_TOTAL.with_label_values(&[vault]).inc();
 }
@@ -29,5 +29,8 @@ pub fn register_metrics() -> Result<()> {
     // Register processing metrics
     super::processing_metrics::register_processing_metrics()?;
 
+    // Register runtime metrics
+    super::runtime_metrics::register_runtime_metrics()?;
+
     Ok(())
 }
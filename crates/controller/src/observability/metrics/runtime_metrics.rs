@@ -0,0 +1,218 @@
+//! # Runtime Metrics
+//!
+//! Health of the controller process itself: tokio runtime load, reconcile queue lag and
+//! memory. These give early warning before the controller stalls under load.
+//!
+//! A background task (`spawn_runtime_metrics_sampler`) samples the tokio runtime and the
+//! process RSS on a fixed interval. Blocking pool metrics are only available from tokio's
+//! unstable metrics and are reported when the controller is built with
+//! `RUSTFLAGS="--cfg tokio_unstable"`.
+
+use crate::observability::metrics::registry::REGISTRY;
+use anyhow::Result;
+use prometheus::{Gauge, Histogram, IntGauge};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+static TOKIO_WORKERS: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "secret_manager_tokio_workers",
+        "Number of tokio runtime worker threads",
+    )
+    .expect("Failed to create TOKIO_WORKERS metric - this should never happen")
+});
+
+static TOKIO_ALIVE_TASKS: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "secret_manager_tokio_alive_tasks",
+        "Number of tasks alive in the tokio runtime",
+    )
+    .expect("Failed to create TOKIO_ALIVE_TASKS metric - this should never happen")
+});
+
+static TOKIO_GLOBAL_QUEUE_DEPTH: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "secret_manager_tokio_global_queue_depth",
+        "Number of tasks waiting in the tokio runtime's global queue",
+    )
+    .expect("Failed to create TOKIO_GLOBAL_QUEUE_DEPTH metric - this should never happen")
+});
+
+static TOKIO_WORKER_BUSY_RATIO: LazyLock<Gauge> = LazyLock::new(|| {
+    Gauge::new(
+        "secret_manager_tokio_worker_busy_ratio",
+        "Fraction of time tokio workers were busy over the last sample interval (0-1)",
+    )
+    .expect("Failed to create TOKIO_WORKER_BUSY_RATIO metric - this should never happen")
+});
+
+#[cfg(tokio_unstable)]
+static TOKIO_BLOCKING_QUEUE_DEPTH: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "secret_manager_tokio_blocking_queue_depth",
+        "Number of tasks waiting for a thread in the tokio blocking pool",
+    )
+    .expect("Failed to create TOKIO_BLOCKING_QUEUE_DEPTH metric - this should never happen")
+});
+
+#[cfg(tokio_unstable)]
+static TOKIO_BLOCKING_THREADS: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "secret_manager_tokio_blocking_threads",
+        "Number of threads in the tokio blocking pool",
+    )
+    .expect("Failed to create TOKIO_BLOCKING_THREADS metric - this should never happen")
+});
+
+static RECONCILE_QUEUE_LAG: LazyLock<Histogram> = LazyLock::new(|| {
+    Histogram::with_opts(
+        prometheus::HistogramOpts::new(
+            "secret_manager_reconcile_queue_lag_seconds",
+            "Delay between a periodic reconcile's scheduled time and its start",
+        )
+        .buckets(vec![1.0, 5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0]),
+    )
+    .expect("Failed to create RECONCILE_QUEUE_LAG metric - this should never happen")
+});
+
+static PROCESS_RESIDENT_MEMORY: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "secret_manager_process_resident_memory_bytes",
+        "Resident memory (RSS) of the controller process in bytes",
+    )
+    .expect("Failed to create PROCESS_RESIDENT_MEMORY metric - this should never happen")
+});
+
+/// Register runtime metrics with the registry
+pub(crate) fn register_runtime_metrics() -> Result<()> {
+    REGISTRY.register(Box::new(TOKIO_WORKERS.clone()))?;
+    REGISTRY.register(Box::new(TOKIO_ALIVE_TASKS.clone()))?;
+    REGISTRY.register(Box::new(TOKIO_GLOBAL_QUEUE_DEPTH.clone()))?;
+    REGISTRY.register(Box::new(TOKIO_WORKER_BUSY_RATIO.clone()))?;
+    #[cfg(tokio_unstable)]
+    {
+        REGISTRY.register(Box::new(TOKIO_BLOCKING_QUEUE_DEPTH.clone()))?;
+        REGISTRY.register(Box::new(TOKIO_BLOCKING_THREADS.clone()))?;
+    }
+    REGISTRY.register(Box::new(RECONCILE_QUEUE_LAG.clone()))?;
+    REGISTRY.register(Box::new(PROCESS_RESIDENT_MEMORY.clone()))?;
+    Ok(())
+}
+
+/// Record how late a periodic reconcile started compared to its scheduled time
+pub fn observe_reconcile_queue_lag(seconds: f64) {
+    RECONCILE_QUEUE_LAG.observe(seconds.max(0.0));
+}
+
+/// Sample runtime and process metrics every `interval` until the runtime shuts down
+/// Must be called from within the tokio runtime
+pub fn spawn_runtime_metrics_sampler(interval: Duration) -> JoinHandle<()> {
+    let handle = tokio::runtime::Handle::current();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut previous: Option<(Instant, Duration)> = None;
+        loop {
+            ticker.tick().await;
+            let metrics = handle.metrics();
+            let workers = metrics.num_workers();
+            let busy: Duration = (0..workers)
+                .map(|worker| metrics.worker_total_busy_duration(worker))
+                .sum();
+            let now = Instant::now();
+
+            TOKIO_WORKERS.set(i64::try_from(workers).unwrap_or(i64::MAX));
+            TOKIO_ALIVE_TASKS.set(i64::try_from(metrics.num_alive_tasks()).unwrap_or(i64::MAX));
+            TOKIO_GLOBAL_QUEUE_DEPTH
+                .set(i64::try_from(metrics.global_queue_depth()).unwrap_or(i64::MAX));
+            if let Some((previous_time, previous_busy)) = previous {
+                TOKIO_WORKER_BUSY_RATIO.set(busy_ratio(
+                    busy.saturating_sub(previous_busy),
+                    now.duration_since(previous_time),
+                    workers,
+                ));
+            }
+            previous = Some((now, busy));
+
+            #[cfg(tokio_unstable)]
+            {
+                TOKIO_BLOCKING_QUEUE_DEPTH
+                    .set(i64::try_from(metrics.blocking_queue_depth()).unwrap_or(i64::MAX));
+                TOKIO_BLOCKING_THREADS
+                    .set(i64::try_from(metrics.num_blocking_threads()).unwrap_or(i64::MAX));
+            }
+
+            match resident_memory_bytes() {
+                Some(rss) => PROCESS_RESIDENT_MEMORY.set(i64::try_from(rss).unwrap_or(i64::MAX)),
+                None => debug!("Resident memory not available on this platform"),
+            }
+        }
+    })
+}
+
+/// Average fraction of `elapsed` that `workers` workers spent busy
+fn busy_ratio(busy: Duration, elapsed: Duration, workers: usize) -> f64 {
+    let capacity = elapsed.as_secs_f64() * f64::from(u32::try_from(workers).unwrap_or(u32::MAX));
+    if capacity <= 0.0 {
+        return 0.0;
+    }
+    (busy.as_secs_f64() / capacity).clamp(0.0, 1.0)
+}
+
+/// Resident memory of this process from `/proc/self/status` (Linux only)
+fn resident_memory_bytes() -> Option<u64> {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .as_deref()
+        .and_then(parse_vm_rss)
+}
+
+/// Parse `VmRSS` (reported in kB) from `/proc/<pid>/status`
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line
+        .trim_start_matches("VmRSS:")
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+    kib.checked_mul(1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_busy_ratio() {
+        let ratio = busy_ratio(Duration::from_secs(4), Duration::from_secs(10), 2);
+        assert!((ratio - 0.2).abs() < f64::EPSILON);
+        assert!(busy_ratio(Duration::from_secs(30), Duration::from_secs(10), 2) <= 1.0);
+        assert!(busy_ratio(Duration::from_secs(1), Duration::ZERO, 2).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tcontroller\nVmPeak:\t  300000 kB\nVmRSS:\t   51200 kB\nThreads:\t8\n";
+        assert_eq!(parse_vm_rss(status), Some(51200 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tcontroller\n"), None);
+    }
+
+    #[test]
+    fn test_observe_reconcile_queue_lag() {
+        let before = RECONCILE_QUEUE_LAG.get_sample_count();
+        observe_reconcile_queue_lag(-1.0);
+        observe_reconcile_queue_lag(12.5);
+        assert_eq!(RECONCILE_QUEUE_LAG.get_sample_count(), before + 2);
+    }
+
+    #[tokio::test]
+    async fn test_sampler_sets_worker_count() {
+        let sampler = spawn_runtime_metrics_sampler(Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        sampler.abort();
+        assert!(TOKIO_WORKERS.get() >= 1);
+    }
+}
@@ -2,11 +2,21 @@
 //!
 //! Observability modules for metrics and tracing.
 //!
+//! - `audit`: Hash-chained audit log of provider mutations
+//! - `log_correlation`: Datadog trace IDs on log lines
 //! - `metrics`: Prometheus metrics collection
 //! - `otel`: OpenTelemetry tracing integration
+//! - `propagation`: Trace context headers for outgoing provider HTTP calls
+//! - `redact`: Secret values masked in logged provider request and response bodies
+//! - `resource_logs`: Recent log events per SecretManagerConfig, served by the resources API
 
+pub mod audit;
+pub mod log_correlation;
 pub mod metrics;
 pub mod otel;
+pub mod propagation;
+pub mod redact;
+pub mod resource_logs;
 
 // Re-export for convenience
 pub use metrics::*;
@@ -19,10 +19,21 @@
 //!
 //! When OTLP is configured, traces are exported to an OpenTelemetry Collector
 //! which can then forward to various backends (Datadog, Jaeger, etc.)
+//!
+//! ## Per-Resource Configuration
+//!
+//! The exporter is initialized once per process. A resource's `otel` field controls its own
+//! reconcile spans: `samplingRatio` decides what fraction of its reconciliations are traced
+//! (see [`should_trace_reconcile`]) and `resourceAttributes` are recorded on each traced
+//! reconcile span (see [`record_resource_attributes`]).
 
 use anyhow::Result;
+use opentelemetry::trace::TracerProvider as _;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::time::Duration;
 use tracing::{info, warn};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::crd::OtelConfig;
 
@@ -36,6 +47,17 @@ pub enum TracerProviderHandle {
     Otlp(()),
 }
 
+impl TracerProviderHandle {
+    /// Tracer for bridging `tracing` spans to the exporter (none for the pending OTLP exporter)
+    #[must_use]
+    pub fn tracer(&self) -> Option<opentelemetry_sdk::trace::SdkTracer> {
+        match self {
+            Self::Datadog(provider) => Some(provider.tracer("secret-manager-controller")),
+            Self::Otlp(()) => None,
+        }
+    }
+}
+
 /// Initialize OpenTelemetry tracing based on configuration
 ///
 /// Returns `Ok(None)` if OpenTelemetry is not configured (no CRD config and no env vars).
@@ -66,6 +88,7 @@ pub fn init_otel(config: Option<&OtelConfig>) -> Result<Option<TracerProviderHan
             environment,
             site,
             api_key,
+            ..
         }) => init_datadog(
             service_name.as_deref(),
             service_version.as_deref(),
@@ -78,6 +101,7 @@ pub fn init_otel(config: Option<&OtelConfig>) -> Result<Option<TracerProviderHan
             service_name,
             service_version,
             environment,
+            ..
         }) => {
             // OTLP implementation pending - log configuration for now
             info!(
@@ -233,3 +257,83 @@ pub fn shutdown_otel(tracer_provider: Option<TracerProviderHandle>) {
         }
     }
 }
+
+/// Whether to trace a reconciliation of a resource with the given `otel` config
+///
+/// Resources without `otel` config are always traced. `seed` must differ between
+/// reconciliations (e.g. resource UID and start time) so the ratio holds over time.
+pub fn should_trace_reconcile(config: Option<&OtelConfig>, seed: impl Hash) -> bool {
+    let ratio = config.map_or(1.0, OtelConfig::sampling_ratio);
+    if ratio >= 1.0 {
+        return true;
+    }
+    if ratio <= 0.0 {
+        return false;
+    }
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    // Spread the hash over 0.0..1.0
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "only the leading bits matter for sampling"
+    )]
+    let sample = hasher.finish() as f64 / u64::MAX as f64;
+    sample < ratio
+}
+
+/// Record the resource's `resourceAttributes` on its reconcile span
+pub fn record_resource_attributes(span: &tracing::Span, config: Option<&OtelConfig>) {
+    let Some(config) = config else {
+        return;
+    };
+    for (key, value) in config.resource_attributes() {
+        span.set_attribute(key.clone(), value.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn otlp_with_ratio(ratio: f64) -> OtelConfig {
+        serde_json::from_value(serde_json::json!({
+            "type": "otlp",
+            "endpoint": "http://otel-collector:4317",
+            "samplingRatio": ratio,
+            "resourceAttributes": { "team": "payments", "service.tier": "critical" }
+        }))
+        .unwrap_or_else(|e| panic!("deserialize: {e}"))
+    }
+
+    #[test]
+    fn test_should_trace_reconcile_bounds() {
+        assert!(should_trace_reconcile(None, 1_u64));
+        assert!(should_trace_reconcile(Some(&otlp_with_ratio(1.0)), 1_u64));
+        assert!(!should_trace_reconcile(Some(&otlp_with_ratio(0.0)), 1_u64));
+        // Out-of-range ratios are clamped
+        assert!(should_trace_reconcile(Some(&otlp_with_ratio(4.0)), 1_u64));
+        assert!(!should_trace_reconcile(Some(&otlp_with_ratio(-1.0)), 1_u64));
+    }
+
+    #[test]
+    fn test_should_trace_reconcile_ratio() {
+        let config = otlp_with_ratio(0.25);
+        let traced = (0_u64..10_000)
+            .filter(|seed| should_trace_reconcile(Some(&config), seed))
+            .count();
+        assert!((2_000..3_000).contains(&traced), "traced {traced} of 10000");
+    }
+
+    #[test]
+    fn test_resource_attributes() {
+        let config = otlp_with_ratio(0.5);
+        assert!((config.sampling_ratio() - 0.5).abs() < f64::EPSILON);
+        assert_eq!(
+            config
+                .resource_attributes()
+                .get("service.tier")
+                .map(String::as_str),
+            Some("critical")
+        );
+    }
+}
@@ -0,0 +1,312 @@
+//! # Per-Resource Log Buffer
+//!
+//! Keeps the most recent log events of each `SecretManagerConfig` in memory.
+//!
+//! `ResourceLogLayer` is a tracing layer that picks up events logged inside spans carrying
+//! `resource.name` and `resource.namespace` fields (the watch and reconcile spans) and
+//! stores them in a bounded ring buffer per resource. The buffer is served by the resources
+//! API (`/api/v1/resources/{namespace}/{name}/logs`), so troubleshooting a single resource
+//! doesn't need access to the controller pod logs. Events are only buffered when they pass
+//! the controller's log filter, and the buffer is per replica.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Write as _};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber, span};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Resources tracked at once; the least recently logged resource is dropped beyond this
+const MAX_TRACKED_RESOURCES: usize = 2000;
+
+/// A log event recorded for a resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResourceLogEvent {
+    /// Time the event was logged (RFC3339)
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    /// Message followed by the event's other fields (`key=value`)
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+struct ResourceLog {
+    events: VecDeque<ResourceLogEvent>,
+    /// Write sequence number of the latest event, used for eviction
+    last_write: u64,
+}
+
+#[derive(Debug, Default)]
+struct Buffers {
+    resources: HashMap<String, ResourceLog>,
+    sequence: u64,
+}
+
+/// Bounded log events per resource (identified by namespace/name)
+#[derive(Debug)]
+pub struct ResourceLogBuffer {
+    capacity: AtomicUsize,
+    buffers: Mutex<Buffers>,
+}
+
+impl ResourceLogBuffer {
+    /// Keep up to `capacity` events per resource (0 disables the buffer)
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: AtomicUsize::new(capacity),
+            buffers: Mutex::new(Buffers::default()),
+        }
+    }
+
+    /// Change the number of events kept per resource (0 disables and clears the buffer)
+    pub fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+        let mut buffers = self.lock();
+        if capacity == 0 {
+            buffers.resources.clear();
+            return;
+        }
+        for log in buffers.resources.values_mut() {
+            while log.events.len() > capacity {
+                log.events.pop_front();
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.capacity.load(Ordering::Relaxed) > 0
+    }
+
+    /// Record an event for a resource, dropping its oldest event when full
+    pub fn push(&self, resource_key: &str, event: ResourceLogEvent) {
+        let capacity = self.capacity.load(Ordering::Relaxed);
+        if capacity == 0 {
+            return;
+        }
+
+        let mut buffers = self.lock();
+        buffers.sequence += 1;
+        let sequence = buffers.sequence;
+
+        if !buffers.resources.contains_key(resource_key)
+            && buffers.resources.len() >= MAX_TRACKED_RESOURCES
+        {
+            let oldest = buffers
+                .resources
+                .iter()
+                .min_by_key(|(_, log)| log.last_write)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                buffers.resources.remove(&oldest);
+            }
+        }
+
+        let log = buffers
+            .resources
+            .entry(resource_key.to_string())
+            .or_default();
+        while log.events.len() >= capacity {
+            log.events.pop_front();
+        }
+        log.events.push_back(event);
+        log.last_write = sequence;
+    }
+
+    /// Buffered events of a resource, oldest first
+    #[must_use]
+    pub fn events(&self, resource_key: &str) -> Vec<ResourceLogEvent> {
+        self.lock()
+            .resources
+            .get(resource_key)
+            .map(|log| log.events.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Buffers> {
+        self.buffers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Tracing layer feeding a `ResourceLogBuffer`
+#[derive(Debug)]
+pub struct ResourceLogLayer {
+    buffer: std::sync::Arc<ResourceLogBuffer>,
+}
+
+impl ResourceLogLayer {
+    #[must_use]
+    pub fn new(buffer: std::sync::Arc<ResourceLogBuffer>) -> Self {
+        Self { buffer }
+    }
+}
+
+/// Resource a span belongs to, stored in the span's extensions
+struct ResourceKey(String);
+
+/// Picks `resource.name` / `resource.namespace` out of span fields
+#[derive(Default)]
+struct ResourceFieldVisitor {
+    name: Option<String>,
+    namespace: Option<String>,
+}
+
+impl Visit for ResourceFieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "resource.name" => self.name = Some(value.to_string()),
+            "resource.namespace" => self.namespace = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if matches!(field.name(), "resource.name" | "resource.namespace") {
+            let value = format!("{value:?}");
+            self.record_str(field, value.trim_matches('"'));
+        }
+    }
+}
+
+/// Formats an event as `message key=value ...`
+#[derive(Default)]
+struct EventFormatter {
+    message: String,
+    fields: String,
+}
+
+impl Visit for EventFormatter {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+impl<S> Layer<S> for ResourceLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut visitor = ResourceFieldVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(namespace), Some(name), Some(span)) =
+            (visitor.namespace, visitor.name, ctx.span(id))
+        {
+            span.extensions_mut()
+                .insert(ResourceKey(format!("{namespace}/{name}")));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if !self.buffer.is_enabled() {
+            return;
+        }
+        // Innermost span that belongs to a resource
+        let Some(resource_key) = ctx.event_scope(event).and_then(|mut scope| {
+            scope.find_map(|span| {
+                span.extensions()
+                    .get::<ResourceKey>()
+                    .map(|key| key.0.clone())
+            })
+        }) else {
+            return;
+        };
+
+        let mut formatter = EventFormatter::default();
+        event.record(&mut formatter);
+        let metadata = event.metadata();
+        self.buffer.push(
+            &resource_key,
+            ResourceLogEvent {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                level: metadata.level().to_string(),
+                target: metadata.target().to_string(),
+                message: formatter.message + &formatter.fields,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn event(message: &str) -> ResourceLogEvent {
+        ResourceLogEvent {
+            timestamp: String::new(),
+            level: "INFO".to_string(),
+            target: "test".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_buffer_keeps_most_recent_events() {
+        let buffer = ResourceLogBuffer::new(2);
+        for message in ["one", "two", "three"] {
+            buffer.push("default/app", event(message));
+        }
+        let messages: Vec<String> = buffer
+            .events("default/app")
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(messages, vec!["two", "three"]);
+        assert!(buffer.events("default/other").is_empty());
+
+        buffer.set_capacity(1);
+        assert_eq!(buffer.events("default/app").len(), 1);
+
+        buffer.set_capacity(0);
+        buffer.push("default/app", event("four"));
+        assert!(buffer.events("default/app").is_empty());
+    }
+
+    #[test]
+    fn test_layer_records_events_in_resource_spans() {
+        let buffer = Arc::new(ResourceLogBuffer::new(10));
+        let subscriber =
+            tracing_subscriber::registry().with(ResourceLogLayer::new(Arc::clone(&buffer)));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("outside any resource");
+            let span = tracing::info_span!(
+                "reconcile",
+                resource.name = "app",
+                resource.namespace = "team-a"
+            );
+            let _guard = span.enter();
+            let inner = tracing::info_span!("sync");
+            let _inner_guard = inner.enter();
+            tracing::warn!(secret = "db-password", "Failed to sync secret");
+        });
+
+        let events = buffer.events("team-a/app");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].level, "WARN");
+        assert_eq!(
+            events[0].message,
+            "Failed to sync secret secret=\"db-password\""
+        );
+    }
+}
@@ -13,7 +13,7 @@
 //!
 //! This brings into scope:
 //! - All CRD types (SecretManagerConfig, ProviderConfig, etc.)
-//! - Provider traits (SecretManagerProvider, ConfigStoreProvider)
+//! - Provider traits (SecretManagerProvider, ConfigStoreProvider) and ProviderError
 //! - Reconciler types (Reconciler, ReconcilerError, etc.)
 //! - Config types (ControllerConfig, ServerConfig)
 //! - Common error types
@@ -22,7 +22,7 @@
 pub use crate::crd::*;
 
 // Provider traits - needed for implementing providers
-pub use crate::provider::{ConfigStoreProvider, SecretManagerProvider};
+pub use crate::provider::{ConfigStoreProvider, ProviderError, SecretManagerProvider};
 
 // Reconciler types - core controller functionality
 pub use crate::controller::reconciler::{
@@ -4,11 +4,106 @@
 //!
 //! - `secrets_manager`: AWS Secrets Manager for secrets
 //! - `parameter_store`: AWS Systems Manager Parameter Store for config values
+//! - `partition`: Region to partition mapping (GovCloud, China) and endpoint checks
+
+use crate::observability::metrics;
+use crate::provider::ProviderError;
+use crate::provider::error::parse_retry_after;
+use aws_sdk_secretsmanager::config::http::HttpResponse;
+use aws_sdk_secretsmanager::error::{ProvideErrorMetadata, SdkError};
+use std::future::Future;
+use std::time::Instant;
 
 pub mod parameter_store;
 mod parameter_store_pact_api_override;
+pub mod partition;
 pub mod secrets_manager;
 
 // Re-export for convenience
 pub use parameter_store::AwsParameterStore;
 pub use secrets_manager::AwsSecretManager;
+
+/// Start an SDK config loader for `region`, selecting FIPS endpoints when `use_fips` is set
+///
+/// The setting also applies to the STS client the default credential chain uses for IRSA.
+pub(crate) fn sdk_config_loader(region: &str, use_fips: bool) -> aws_config::ConfigLoader {
+    aws_config::defaults(aws_config::BehaviorVersion::latest())
+        .region(aws_config::Region::new(region.to_string()))
+        .use_fips(use_fips)
+}
+
+/// Record AWS SDK calls in the provider API latency histogram
+/// (`secret_manager_provider_api_request_duration_seconds`)
+///
+/// Implemented for the futures returned by `send()` on any AWS SDK fluent builder. Successful
+/// calls are recorded as status 200 (AWS JSON APIs only return 200 on success); failed calls use
+/// the raw response status, or "error" when no response was received.
+pub(crate) trait TimedSdkCall<T, E>:
+    Future<Output = Result<T, SdkError<E, HttpResponse>>>
+{
+    /// Await the call, recording its duration under `operation`
+    fn timed(
+        self,
+        operation: &'static str,
+    ) -> impl Future<Output = Result<T, SdkError<E, HttpResponse>>> + Send;
+}
+
+impl<F, T, E> TimedSdkCall<T, E> for F
+where
+    F: Future<Output = Result<T, SdkError<E, HttpResponse>>> + Send,
+    T: Send,
+    E: Send,
+{
+    async fn timed(self, operation: &'static str) -> Result<T, SdkError<E, HttpResponse>> {
+        let start = Instant::now();
+        let result = self.await;
+        let status_code = match &result {
+            Ok(_) => "200".to_string(),
+            Err(e) => e.raw_response().map_or_else(
+                || "error".to_string(),
+                |response| response.status().as_u16().to_string(),
+            ),
+        };
+        metrics::observe_provider_api_request(
+            "aws",
+            operation,
+            &status_code,
+            start.elapsed().as_secs_f64(),
+        );
+        result
+    }
+}
+
+/// Classify an AWS SDK error, keeping `message` as the error text
+///
+/// Uses the exception code (e.g. `ResourceNotFoundException`) when the service returned one,
+/// otherwise the raw response status. Timeouts and dispatch failures are transient.
+pub(crate) fn classify_sdk_error<E>(
+    error: &SdkError<E, HttpResponse>,
+    message: impl Into<String>,
+) -> ProviderError
+where
+    E: ProvideErrorMetadata,
+{
+    let message = message.into();
+    match error {
+        SdkError::TimeoutError(_) | SdkError::DispatchFailure(_) => {
+            ProviderError::Transient(message)
+        }
+        _ => {
+            let response = error.raw_response();
+            let classified = error
+                .code()
+                .and_then(|code| ProviderError::from_code(code, message.clone()))
+                .or_else(|| {
+                    response.map(|response| {
+                        ProviderError::from_status(response.status().as_u16(), message.clone())
+                    })
+                })
+                .unwrap_or(ProviderError::Permanent(message));
+            classified.with_retry_after(parse_retry_after(
+                response.and_then(|response| response.headers().get("retry-after")),
+            ))
+        }
+    }
+}
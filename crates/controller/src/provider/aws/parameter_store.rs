@@ -5,26 +5,42 @@
 //! This module provides functionality to:
 //! - Create and update parameters in AWS Parameter Store
 //! - Retrieve parameter values
+//! - List and bulk-write parameters under the configured path (`GetParametersByPath`)
 //! - Support IRSA (IAM Roles for Service Accounts) authentication
+//! - Write parameters in the configured tier with parameter policies (`configs.aws`)
+//!
+//! Standard parameters hold up to 4 KB and no policies, so values over 4 KB and parameters
+//! with policies are written as Advanced. Parameter Store can't move a parameter back from
+//! Advanced to Standard; `IntelligentTiering` lets it pick the tier on each write instead.
 //!
 //! Parameter Store is used for storing configuration values (non-secrets)
 //! and provides better integration with EKS via ASCP (AWS Secrets and Configuration Provider).
 
-use crate::crd::{AwsAuthConfig, AwsConfig};
+use crate::crd::{AwsAuthConfig, AwsConfig, AwsConfigsConfig, ParameterPolicies, ParameterTier};
 use crate::observability::metrics;
-use crate::provider::ConfigStoreProvider;
+use crate::provider::aws::partition::warn_on_irsa_partition_mismatch;
+use crate::provider::aws::{TimedSdkCall, classify_sdk_error, sdk_config_loader};
+use crate::provider::{ConfigStoreProvider, ProviderError};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use aws_config::SdkConfig;
 use aws_sdk_ssm::Client as SsmClient;
-use std::time::Instant;
+use aws_sdk_ssm::operation::put_parameter::builders::PutParameterFluentBuilder;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use tracing::{Instrument, debug, info, info_span};
 
+/// Largest value a Standard parameter can hold (4 KB)
+const STANDARD_TIER_MAX_BYTES: usize = 4 * 1024;
+
 /// AWS Parameter Store provider implementation
 pub struct AwsParameterStore {
     client: SsmClient,
     parameter_path_prefix: String,
     _region: String,
+    tier: ParameterTier,
+    policies: Option<ParameterPolicies>,
 }
 
 impl std::fmt::Debug for AwsParameterStore {
@@ -32,6 +48,8 @@ impl std::fmt::Debug for AwsParameterStore {
         f.debug_struct("AwsParameterStore")
             .field("parameter_path_prefix", &self.parameter_path_prefix)
             .field("_region", &self._region)
+            .field("tier", &self.tier)
+            .field("policies", &self.policies)
             .finish_non_exhaustive()
     }
 }
@@ -57,7 +75,7 @@ impl AwsParameterStore {
         let sdk_config = match &config.auth {
             Some(AwsAuthConfig::Irsa { role_arn }) => {
                 info!("Using IRSA authentication with role: {}", role_arn);
-                Self::create_irsa_config(&region, role_arn, k8s_client).await?
+                Self::create_irsa_config(&region, role_arn, config.use_fips, k8s_client).await?
             }
             None => {
                 info!(
@@ -67,7 +85,7 @@ impl AwsParameterStore {
                     "Ensure pod service account has annotation: eks.amazonaws.com/role-arn=<role-arn>"
                 );
                 // Default to IRSA - the AWS SDK will automatically discover the role from the pod's service account
-                Self::create_default_config(&region).await?
+                Self::create_default_config(&region, config.use_fips).await?
             }
         };
 
@@ -94,13 +112,60 @@ impl AwsParameterStore {
             client,
             parameter_path_prefix,
             _region: region,
+            tier: ParameterTier::default(),
+            policies: None,
         })
     }
 
+    /// Write parameters with the tier and policies from `configs.aws`
+    /// `None` keeps Standard parameters without policies
+    #[must_use]
+    pub fn with_settings(mut self, settings: Option<&AwsConfigsConfig>) -> Self {
+        if let Some(settings) = settings {
+            self.tier = settings.tier.unwrap_or_default();
+            self.policies = settings.policies.clone().filter(|p| !p.is_empty());
+        }
+        self
+    }
+
+    /// Tier a value ends up in: Standard or Advanced
+    fn effective_tier(&self, value: &str) -> ParameterTier {
+        effective_tier(self.tier, value, self.policies.is_some())
+    }
+
+    /// PutParameter request for a value, with the configured tier and policies
+    fn put_parameter(
+        &self,
+        parameter_name: &str,
+        value: &str,
+        overwrite: bool,
+    ) -> Result<PutParameterFluentBuilder> {
+        // Intelligent-Tiering is passed through so Parameter Store keeps choosing on later writes
+        let tier = match self.tier {
+            ParameterTier::IntelligentTiering => ParameterTier::IntelligentTiering,
+            _ => self.effective_tier(value),
+        };
+        let policies = self
+            .policies
+            .as_ref()
+            .map(|policies| policies_json(policies, Utc::now()))
+            .transpose()?;
+        Ok(self
+            .client
+            .put_parameter()
+            .name(parameter_name)
+            .value(value)
+            .r#type(aws_sdk_ssm::types::ParameterType::String)
+            .tier(aws_sdk_ssm::types::ParameterTier::from(tier.as_str()))
+            .set_policies(policies)
+            .overwrite(overwrite))
+    }
+
     /// Create AWS SDK config using IRSA (IAM Roles for Service Accounts)
     async fn create_irsa_config(
         region: &str,
         role_arn: &str,
+        use_fips: bool,
         _k8s_client: &kube::Client,
     ) -> Result<SdkConfig> {
         // IRSA works by:
@@ -112,9 +177,9 @@ impl AwsParameterStore {
             "IRSA authentication: Ensure pod service account has annotation: eks.amazonaws.com/role-arn={}",
             role_arn
         );
+        warn_on_irsa_partition_mismatch(region);
 
-        let mut builder = aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .region(aws_config::Region::new(region.to_string()));
+        let mut builder = sdk_config_loader(region, use_fips);
 
         // Support Pact mock server integration via PactModeAPIOverride trait
         // When PACT_MODE=true, route requests to Pact mock server instead of real AWS
@@ -157,9 +222,8 @@ impl AwsParameterStore {
     }
 
     /// Create AWS SDK config using default credential chain
-    async fn create_default_config(region: &str) -> Result<SdkConfig> {
-        let mut builder = aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .region(aws_config::Region::new(region.to_string()));
+    async fn create_default_config(region: &str, use_fips: bool) -> Result<SdkConfig> {
+        let mut builder = sdk_config_loader(region, use_fips);
 
         // Support Pact mock server integration via PactModeAPIOverride trait
         // When PACT_MODE=true, route requests to Pact mock server instead of real AWS
@@ -211,6 +275,55 @@ impl AwsParameterStore {
         // Construct full path
         format!("{}/{}", self.parameter_path_prefix, sanitized_key)
     }
+
+    /// Fetch all parameters directly under the path prefix with their values
+    /// Keys are relative to the prefix (the sanitized config key)
+    async fn list_parameter_values(&self) -> Result<HashMap<String, String>> {
+        let start = Instant::now();
+        let path_prefix = format!("{}/", self.parameter_path_prefix);
+        let mut values = HashMap::new();
+        let mut next_token: Option<String> = None;
+
+        loop {
+            let response = self
+                .client
+                .get_parameters_by_path()
+                .path(&self.parameter_path_prefix)
+                .recursive(false)
+                .with_decryption(true)
+                .set_next_token(next_token.take())
+                .send()
+                .timed("get_parameters_by_path")
+                .await
+                .map_err(|e| {
+                    metrics::increment_provider_operation_errors("aws");
+                    anyhow::Error::new(classify_sdk_error(
+                        &e,
+                        format!(
+                            "Failed to list AWS Parameter Store parameters under {}: {e}",
+                            self.parameter_path_prefix
+                        ),
+                    ))
+                })?;
+
+            for parameter in response.parameters() {
+                let (Some(name), Some(value)) = (parameter.name(), parameter.value()) else {
+                    continue;
+                };
+                if let Some(key) = name.strip_prefix(&path_prefix) {
+                    values.insert(key.to_string(), value.to_string());
+                }
+            }
+
+            match response.next_token() {
+                Some(token) if !token.is_empty() => next_token = Some(token.to_string()),
+                _ => break,
+            }
+        }
+
+        metrics::record_secret_operation("aws", "list", start.elapsed().as_secs_f64());
+        Ok(values)
+    }
 }
 
 #[async_trait]
@@ -232,6 +345,7 @@ impl ConfigStoreProvider for AwsParameterStore {
                 .get_parameter()
                 .name(&parameter_name)
                 .send()
+                .timed("get_parameter")
                 .await
                 .is_ok();
 
@@ -239,13 +353,9 @@ impl ConfigStoreProvider for AwsParameterStore {
                 // Create parameter
                 info!("Creating AWS Parameter Store parameter: {}", parameter_name);
                 match self
-                    .client
-                    .put_parameter()
-                    .name(&parameter_name)
-                    .value(config_value)
-                    .r#type(aws_sdk_ssm::types::ParameterType::String)
-                    .overwrite(false)
+                    .put_parameter(&parameter_name, config_value, false)?
                     .send()
+                    .timed("put_parameter")
                     .await
                 {
                     Ok(_) => {
@@ -268,9 +378,13 @@ impl ConfigStoreProvider for AwsParameterStore {
                         span_clone
                             .record("operation.duration_ms", start.elapsed().as_millis() as u64);
                         metrics::increment_provider_operation_errors("aws");
-                        return Err(anyhow::anyhow!(
-                            "Failed to create AWS Parameter Store parameter {parameter_name}: {e}"
-                        ));
+                        return Err(classify_sdk_error(
+                            &e,
+                            format!(
+                                "Failed to create AWS Parameter Store parameter {parameter_name}: {e}"
+                            ),
+                        )
+                        .into());
                     }
                 }
             } else {
@@ -299,13 +413,9 @@ impl ConfigStoreProvider for AwsParameterStore {
                 // Update parameter (overwrite existing)
                 info!("Updating AWS Parameter Store parameter: {}", parameter_name);
                 match self
-                    .client
-                    .put_parameter()
-                    .name(&parameter_name)
-                    .value(config_value)
-                    .r#type(aws_sdk_ssm::types::ParameterType::String)
-                    .overwrite(true)
+                    .put_parameter(&parameter_name, config_value, true)?
                     .send()
+                    .timed("put_parameter")
                     .await
                 {
                     Ok(_) => {
@@ -324,9 +434,13 @@ impl ConfigStoreProvider for AwsParameterStore {
                         span_clone
                             .record("operation.duration_ms", start.elapsed().as_millis() as u64);
                         metrics::increment_provider_operation_errors("aws");
-                        return Err(anyhow::anyhow!(
-                            "Failed to update AWS Parameter Store parameter {parameter_name}: {e}"
-                        ));
+                        return Err(classify_sdk_error(
+                            &e,
+                            format!(
+                                "Failed to update AWS Parameter Store parameter {parameter_name}: {e}"
+                            ),
+                        )
+                        .into());
                     }
                 }
             };
@@ -357,6 +471,7 @@ impl ConfigStoreProvider for AwsParameterStore {
                 .name(&parameter_name)
                 .with_decryption(true) // Decrypt SecureString parameters if needed
                 .send()
+                .timed("get_parameter")
                 .await
             {
                 Ok(response) => {
@@ -387,7 +502,11 @@ impl ConfigStoreProvider for AwsParameterStore {
                 }
                 Err(e) => {
                     let error_msg = e.to_string();
-                    if error_msg.contains("ParameterNotFound") {
+                    let error = classify_sdk_error(
+                        &e,
+                        format!("Failed to get AWS Parameter Store parameter: {e}"),
+                    );
+                    if matches!(error, ProviderError::NotFound(_)) {
                         span_clone.record("operation.success", true);
                         span_clone.record("operation.found", false);
                         span_clone
@@ -404,9 +523,7 @@ impl ConfigStoreProvider for AwsParameterStore {
                         span_clone
                             .record("operation.duration_ms", start.elapsed().as_millis() as u64);
                         metrics::increment_provider_operation_errors("aws");
-                        Err(anyhow::anyhow!(
-                            "Failed to get AWS Parameter Store parameter: {e}"
-                        ))
+                        Err(error.into())
                     }
                 }
             }
@@ -422,8 +539,195 @@ impl ConfigStoreProvider for AwsParameterStore {
             .delete_parameter()
             .name(&parameter_name)
             .send()
+            .timed("delete_parameter")
             .await
             .context("Failed to delete AWS Parameter Store parameter")?;
         Ok(())
     }
+
+    async fn list_configs(&self, prefix: &str) -> Result<Vec<String>> {
+        let prefix = prefix.replace(['.', '/'], "_");
+        let mut keys: Vec<String> = self
+            .list_parameter_values()
+            .await?
+            .into_keys()
+            .filter(|key| key.starts_with(&prefix))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn bulk_upsert(&self, entries: Vec<(String, String)>) -> Result<HashMap<String, bool>> {
+        // Parameter Store has no batch write; read the whole path once and only put changed values
+        let current_values = self.list_parameter_values().await?;
+        let mut results = HashMap::with_capacity(entries.len());
+
+        for (config_key, config_value) in entries {
+            let parameter_name = self.construct_parameter_name(&config_key);
+            let current = parameter_name
+                .strip_prefix(&self.parameter_path_prefix)
+                .map(|key| key.trim_start_matches('/'))
+                .and_then(|key| current_values.get(key));
+
+            if current.is_some_and(|current| *current == config_value) {
+                debug!(
+                    "AWS Parameter Store parameter {} unchanged, skipping update",
+                    parameter_name
+                );
+                results.insert(config_key, false);
+                continue;
+            }
+
+            let operation = if current.is_some() {
+                "update"
+            } else {
+                "create"
+            };
+            info!(
+                "Bulk {} of AWS Parameter Store parameter: {}",
+                operation, parameter_name
+            );
+            let start = Instant::now();
+            self.put_parameter(&parameter_name, &config_value, current.is_some())?
+                .send()
+                .timed("put_parameter")
+                .await
+                .map_err(|e| {
+                    metrics::increment_provider_operation_errors("aws");
+                    anyhow::Error::new(classify_sdk_error(
+                        &e,
+                        format!(
+                            "Failed to {operation} AWS Parameter Store parameter {parameter_name}: {e}"
+                        ),
+                    ))
+                })?;
+            metrics::record_secret_operation("aws", operation, start.elapsed().as_secs_f64());
+            results.insert(config_key, true);
+        }
+
+        Ok(results)
+    }
+
+    fn storage_tier(&self, _config_key: &str, config_value: &str) -> Option<String> {
+        Some(self.effective_tier(config_value).as_str().to_string())
+    }
+}
+
+/// Tier a value ends up in: Standard or Advanced
+/// Standard (and Intelligent-Tiering, on the AWS side) moves to Advanced for values over 4 KB
+/// and for parameters with policies
+fn effective_tier(configured: ParameterTier, value: &str, has_policies: bool) -> ParameterTier {
+    match configured {
+        ParameterTier::Advanced => ParameterTier::Advanced,
+        ParameterTier::Standard | ParameterTier::IntelligentTiering
+            if has_policies || value.len() > STANDARD_TIER_MAX_BYTES =>
+        {
+            ParameterTier::Advanced
+        }
+        ParameterTier::Standard | ParameterTier::IntelligentTiering => ParameterTier::Standard,
+    }
+}
+
+/// Parameter policies as the JSON array PutParameter expects
+/// Expiration is relative to `now`, so it counts from the last write
+fn policies_json(policies: &ParameterPolicies, now: DateTime<Utc>) -> Result<String> {
+    let mut documents = Vec::new();
+    if let Some(expiration) = &policies.expiration {
+        let expires_at = now
+            + chrono::Duration::from_std(expiration.to_duration()?)
+                .context("configs.aws.policies.expiration is out of range")?;
+        documents.push(serde_json::json!({
+            "Type": "Expiration",
+            "Version": "1.0",
+            "Attributes": {
+                "Timestamp": expires_at.to_rfc3339_opts(SecondsFormat::Millis, true)
+            }
+        }));
+    }
+    if let Some(before) = &policies.expiration_notification {
+        let (amount, unit) = policy_interval(before.to_duration()?);
+        documents.push(serde_json::json!({
+            "Type": "ExpirationNotification",
+            "Version": "1.0",
+            "Attributes": { "Before": amount.to_string(), "Unit": unit }
+        }));
+    }
+    if let Some(after) = &policies.no_change_notification {
+        let (amount, unit) = policy_interval(after.to_duration()?);
+        documents.push(serde_json::json!({
+            "Type": "NoChangeNotification",
+            "Version": "1.0",
+            "Attributes": { "After": amount.to_string(), "Unit": unit }
+        }));
+    }
+    Ok(serde_json::Value::Array(documents).to_string())
+}
+
+/// Policy interval in whole days, or hours (rounded up) when it isn't a whole number of days
+fn policy_interval(duration: Duration) -> (u64, &'static str) {
+    let seconds = duration.as_secs();
+    if seconds % 86_400 == 0 {
+        (seconds / 86_400, "Days")
+    } else {
+        (seconds.div_ceil(3_600), "Hours")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::KubeDuration;
+
+    #[test]
+    fn test_effective_tier() {
+        let small = "x".repeat(STANDARD_TIER_MAX_BYTES);
+        let large = "x".repeat(STANDARD_TIER_MAX_BYTES + 1);
+        assert_eq!(
+            effective_tier(ParameterTier::Standard, &small, false),
+            ParameterTier::Standard
+        );
+        assert_eq!(
+            effective_tier(ParameterTier::Standard, &large, false),
+            ParameterTier::Advanced
+        );
+        assert_eq!(
+            effective_tier(ParameterTier::Standard, "v", true),
+            ParameterTier::Advanced
+        );
+        assert_eq!(
+            effective_tier(ParameterTier::IntelligentTiering, "v", false),
+            ParameterTier::Standard
+        );
+        assert_eq!(
+            effective_tier(ParameterTier::Advanced, "v", false),
+            ParameterTier::Advanced
+        );
+    }
+
+    #[test]
+    fn test_policies_json() {
+        let policies = ParameterPolicies {
+            expiration: Some(KubeDuration::new("90d")),
+            expiration_notification: Some(KubeDuration::new("14d")),
+            no_change_notification: Some(KubeDuration::new("36h")),
+        };
+        let now = DateTime::parse_from_rfc3339("2025-01-15T10:30:00Z")
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_default();
+        let json: serde_json::Value = policies_json(&policies, now)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"Type": "Expiration", "Version": "1.0",
+                 "Attributes": {"Timestamp": "2025-04-15T10:30:00.000Z"}},
+                {"Type": "ExpirationNotification", "Version": "1.0",
+                 "Attributes": {"Before": "14", "Unit": "Days"}},
+                {"Type": "NoChangeNotification", "Version": "1.0",
+                 "Attributes": {"After": "36", "Unit": "Hours"}}
+            ])
+        );
+    }
 }
@@ -3,14 +3,22 @@
 //! Handles AWS SDK configuration and authentication setup.
 
 use crate::crd::{AwsAuthConfig, AwsConfig};
+use crate::provider::aws::partition::{AwsPartition, warn_on_irsa_partition_mismatch};
+use crate::provider::aws::sdk_config_loader;
 use anyhow::{Context, Result};
 use aws_config::SdkConfig;
-use tracing::info;
+use aws_config::sts::AssumeRoleProvider;
+use aws_credential_types::provider::SharedCredentialsProvider;
+use tracing::{info, warn};
+
+/// STS session name for roles assumed through `assumeRoleArn` (shows up in CloudTrail)
+const ASSUME_ROLE_SESSION_NAME: &str = "secret-manager-controller";
 
 /// Create AWS SDK config using IRSA (IAM Roles for Service Accounts)
 pub async fn create_irsa_config(
     region: &str,
     role_arn: &str,
+    use_fips: bool,
     _k8s_client: &kube::Client,
 ) -> Result<SdkConfig> {
     // IRSA works by:
@@ -24,9 +32,13 @@ pub async fn create_irsa_config(
         "IRSA authentication: Ensure pod service account has annotation: eks.amazonaws.com/role-arn={}",
         role_arn
     );
+    info!(
+        "IRSA: assuming role through STS endpoint {}",
+        AwsPartition::from_region(region).sts_endpoint(region, use_fips)
+    );
+    warn_on_irsa_partition_mismatch(region);
 
-    let mut builder = aws_config::defaults(aws_config::BehaviorVersion::latest())
-        .region(aws_config::Region::new(region.to_string()));
+    let mut builder = sdk_config_loader(region, use_fips);
 
     // Support Pact mock server integration via PactModeAPIOverride trait
     // When PACT_MODE=true, route requests to Pact mock server instead of real AWS
@@ -69,9 +81,8 @@ pub async fn create_irsa_config(
 }
 
 /// Create AWS SDK config using default credential chain
-pub async fn create_default_config(region: &str) -> Result<SdkConfig> {
-    let mut builder = aws_config::defaults(aws_config::BehaviorVersion::latest())
-        .region(aws_config::Region::new(region.to_string()));
+pub async fn create_default_config(region: &str, use_fips: bool) -> Result<SdkConfig> {
+    let mut builder = sdk_config_loader(region, use_fips);
 
     // Support Pact mock server integration via PactModeAPIOverride trait
     // When PACT_MODE=true, route requests to Pact mock server instead of real AWS
@@ -119,10 +130,10 @@ pub async fn create_sdk_config(config: &AwsConfig, k8s_client: &kube::Client) ->
 
     // Build AWS SDK config based on authentication method
     // Default to IRSA when auth is not specified
-    match &config.auth {
+    let sdk_config = match &config.auth {
         Some(AwsAuthConfig::Irsa { role_arn }) => {
             info!("Using IRSA authentication with role: {}", role_arn);
-            create_irsa_config(&region, role_arn, k8s_client).await
+            create_irsa_config(&region, role_arn, config.use_fips, k8s_client).await?
         }
         None => {
             info!(
@@ -132,7 +143,34 @@ pub async fn create_sdk_config(config: &AwsConfig, k8s_client: &kube::Client) ->
                 "Ensure pod service account has annotation: eks.amazonaws.com/role-arn=<role-arn>"
             );
             // Default to IRSA - the AWS SDK will automatically discover the role from the pod's service account
-            create_default_config(&region).await
+            create_default_config(&region, config.use_fips).await?
+        }
+    };
+
+    match config.assume_role_arn.as_deref() {
+        Some(role_arn) if !role_arn.trim().is_empty() => {
+            Ok(assume_role_config(sdk_config, role_arn).await)
         }
+        _ => Ok(sdk_config),
     }
 }
+
+/// Wrap an SDK config's credentials in an STS `AssumeRole` of `role_arn`
+/// Used for cross-account access: the controller's identity assumes a role in the target account
+async fn assume_role_config(sdk_config: SdkConfig, role_arn: &str) -> SdkConfig {
+    if std::env::var("PACT_MODE").is_ok() {
+        warn!("PACT_MODE: not assuming role {}", role_arn);
+        return sdk_config;
+    }
+
+    info!("Assuming role {} for cross-account access", role_arn);
+    let provider = AssumeRoleProvider::builder(role_arn)
+        .session_name(ASSUME_ROLE_SESSION_NAME)
+        .configure(&sdk_config)
+        .build()
+        .await;
+    sdk_config
+        .to_builder()
+        .credentials_provider(SharedCredentialsProvider::new(provider))
+        .build()
+}
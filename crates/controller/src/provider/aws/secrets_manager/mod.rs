@@ -10,11 +10,13 @@
 mod auth;
 mod operations;
 mod pact_api_override;
+mod staging;
 
 use aws_sdk_secretsmanager::Client as SecretsManagerClient;
 
-use crate::crd::AwsConfig;
+use crate::crd::{AwsConfig, AwsVersionStagesConfig};
 use anyhow::Result;
+use tracing::info;
 
 use self::auth::create_sdk_config;
 
@@ -22,6 +24,7 @@ use self::auth::create_sdk_config;
 pub struct AwsSecretManager {
     pub(crate) client: SecretsManagerClient,
     pub(crate) _region: String,
+    pub(crate) version_stages: Option<AwsVersionStagesConfig>,
 }
 
 impl std::fmt::Debug for AwsSecretManager {
@@ -47,8 +50,26 @@ impl AwsSecretManager {
         Ok(Self {
             client,
             _region: region,
+            version_stages: config.version_stages.clone(),
         })
     }
+
+    /// Override the Secrets Manager endpoint URL (e.g. a VPC endpoint or proxy)
+    /// `None` keeps the endpoint resolved by the SDK
+    #[must_use]
+    pub fn with_endpoint(mut self, endpoint: Option<&str>) -> Self {
+        if let Some(endpoint) = endpoint {
+            info!("Using AWS Secrets Manager endpoint override: {}", endpoint);
+            let config = self
+                .client
+                .config()
+                .to_builder()
+                .endpoint_url(endpoint)
+                .build();
+            self.client = SecretsManagerClient::from_conf(config);
+        }
+        self
+    }
 }
 
 #[cfg(test)]
@@ -62,6 +83,10 @@ mod tests {
             auth: Some(AwsAuthConfig::Irsa {
                 role_arn: "arn:aws:iam::123456789012:role/test-role".to_string(),
             }),
+            version_stages: None,
+            endpoint_url: None,
+            use_fips: false,
+            assume_role_arn: None,
         };
 
         assert_eq!(config.region, "us-east-1");
@@ -78,6 +103,10 @@ mod tests {
         let config = AwsConfig {
             region: "eu-west-1".to_string(),
             auth: None,
+            version_stages: None,
+            endpoint_url: None,
+            use_fips: false,
+            assume_role_arn: None,
         };
 
         assert_eq!(config.region, "eu-west-1");
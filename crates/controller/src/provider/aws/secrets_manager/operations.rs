@@ -3,20 +3,29 @@
 //! Implements SecretManagerProvider trait for AWS Secrets Manager.
 
 use crate::observability::metrics;
-use crate::provider::SecretManagerProvider;
+use crate::provider::aws::{TimedSdkCall, classify_sdk_error};
+use crate::provider::{ProviderError, SecretManagerProvider, SecretMetadata, SecretVersionIds};
+use crate::secret::SecretString;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::HashMap;
 use std::time::Instant;
 use tracing::{Instrument, debug, info, info_span, warn};
 
 use super::AwsSecretManager;
+use super::staging::{AWS_CURRENT, AWS_PREVIOUS, put_version_stages, version_holding};
+
+/// Tag holding the expiry set by `secrets.expiration` (RFC3339)
+/// Secrets Manager has no expiry of its own, so the tag is informational
+const EXPIRES_AT_TAG: &str = "secret-manager-expires-at";
 
 #[async_trait]
 impl SecretManagerProvider for AwsSecretManager {
     async fn create_or_update_secret(
         &self,
         secret_name: &str,
-        secret_value: &str,
+        secret_value: &SecretString,
         environment: &str,
         location: &str,
     ) -> Result<bool> {
@@ -35,6 +44,7 @@ impl SecretManagerProvider for AwsSecretManager {
                 .describe_secret()
                 .secret_id(secret_name)
                 .send()
+                .timed("describe_secret")
                 .await
                 .is_ok();
 
@@ -54,7 +64,7 @@ impl SecretManagerProvider for AwsSecretManager {
                     .client
                     .create_secret()
                     .name(secret_name)
-                    .secret_string(secret_value)
+                    .secret_string(secret_value.expose_secret())
                     .tags(
                         aws_sdk_secretsmanager::types::Tag::builder()
                             .key("environment")
@@ -74,8 +84,10 @@ impl SecretManagerProvider for AwsSecretManager {
                         create_request.client_request_token("00000000-0000-0000-0000-000000000000");
                 }
 
-                match create_request.send().await {
-                    Ok(_) => {
+                match create_request.send().timed("create_secret").await {
+                    Ok(output) => {
+                        self.finish_version_staging(secret_name, output.version_id(), true)
+                            .await?;
                         metrics::record_secret_operation(
                             "aws",
                             "create",
@@ -107,9 +119,11 @@ impl SecretManagerProvider for AwsSecretManager {
                             "Failed to create AWS secret: {}",
                             e
                         );
-                        return Err(anyhow::anyhow!(
-                            "Failed to create AWS secret {secret_name}: {e}"
-                        ));
+                        return Err(classify_sdk_error(
+                            &e,
+                            format!("Failed to create AWS secret {secret_name}: {e}"),
+                        )
+                        .into());
                     }
                 }
             } else {
@@ -117,7 +131,7 @@ impl SecretManagerProvider for AwsSecretManager {
                 let current_value = self.get_secret_value(secret_name).await?;
 
                 if let Some(current) = current_value {
-                    if current == secret_value {
+                    if current == *secret_value {
                         debug!(
                             provider = "aws",
                             region = self._region,
@@ -154,7 +168,8 @@ impl SecretManagerProvider for AwsSecretManager {
                     .client
                     .put_secret_value()
                     .secret_id(secret_name)
-                    .secret_string(secret_value);
+                    .secret_string(secret_value.expose_secret())
+                    .set_version_stages(put_version_stages(self.version_stages.as_ref()));
 
                 if std::env::var("PACT_MODE").is_ok() {
                     // Use a fixed UUID for Pact testing to ensure request body matches
@@ -162,8 +177,10 @@ impl SecretManagerProvider for AwsSecretManager {
                         put_request.client_request_token("00000000-0000-0000-0000-000000000000");
                 }
 
-                match put_request.send().await {
-                    Ok(_) => {
+                match put_request.send().timed("put_secret_value").await {
+                    Ok(output) => {
+                        self.finish_version_staging(secret_name, output.version_id(), false)
+                            .await?;
                         metrics::record_secret_operation(
                             "aws",
                             "update",
@@ -179,9 +196,11 @@ impl SecretManagerProvider for AwsSecretManager {
                         span_clone
                             .record("operation.duration_ms", start.elapsed().as_millis() as u64);
                         metrics::increment_provider_operation_errors("aws");
-                        return Err(anyhow::anyhow!(
-                            "Failed to update AWS secret {secret_name}: {e}"
-                        ));
+                        return Err(classify_sdk_error(
+                            &e,
+                            format!("Failed to update AWS secret {secret_name}: {e}"),
+                        )
+                        .into());
                     }
                 }
             };
@@ -195,7 +214,7 @@ impl SecretManagerProvider for AwsSecretManager {
         .await
     }
 
-    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<String>> {
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
         let span = tracing::debug_span!(
             "aws.secret.get",
             secret.name = secret_name,
@@ -210,6 +229,7 @@ impl SecretManagerProvider for AwsSecretManager {
                 .get_secret_value()
                 .secret_id(secret_name)
                 .send()
+                .timed("get_secret_value")
                 .await
             {
                 Ok(response) => {
@@ -235,7 +255,7 @@ impl SecretManagerProvider for AwsSecretManager {
                                 "get",
                                 start.elapsed().as_secs_f64(),
                             );
-                            Ok(Some(v))
+                            Ok(Some(SecretString::new(v)))
                         }
                         None => {
                             span_clone.record("operation.success", false);
@@ -252,7 +272,8 @@ impl SecretManagerProvider for AwsSecretManager {
                 }
                 Err(e) => {
                     let error_msg = e.to_string();
-                    if error_msg.contains("ResourceNotFoundException") {
+                    let error = classify_sdk_error(&e, format!("Failed to get AWS secret: {e}"));
+                    if matches!(error, ProviderError::NotFound(_)) {
                         span_clone.record("operation.success", true);
                         span_clone.record("operation.found", false);
                         span_clone
@@ -269,7 +290,7 @@ impl SecretManagerProvider for AwsSecretManager {
                         span_clone
                             .record("operation.duration_ms", start.elapsed().as_millis() as u64);
                         metrics::increment_provider_operation_errors("aws");
-                        Err(anyhow::anyhow!("Failed to get AWS secret: {e}"))
+                        Err(error.into())
                     }
                 }
             }
@@ -278,6 +299,164 @@ impl SecretManagerProvider for AwsSecretManager {
         .await
     }
 
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        let span = info_span!(
+            "aws.secret.create_or_update_binary",
+            secret.name = secret_name,
+            region = self._region
+        );
+        let span_clone = span.clone();
+        let start = Instant::now();
+
+        async move {
+            let current_value = self.get_secret_binary(secret_name).await?;
+
+            let operation_type = match current_value {
+                Some(ref current) if current.as_slice() == secret_value => {
+                    debug!(
+                        provider = "aws",
+                        region = self._region,
+                        secret_name = secret_name,
+                        operation = "no_change",
+                        "AWS binary secret {} unchanged, skipping update",
+                        secret_name
+                    );
+                    metrics::record_secret_operation(
+                        "aws",
+                        "no_change",
+                        start.elapsed().as_secs_f64(),
+                    );
+                    span_clone.record("operation.type", "no_change");
+                    span_clone.record("operation.duration_ms", start.elapsed().as_millis() as u64);
+                    span_clone.record("operation.success", true);
+                    return Ok(false);
+                }
+                Some(_) => "update",
+                None => "create",
+            };
+
+            info!(
+                provider = "aws",
+                region = self._region,
+                secret_name = secret_name,
+                operation = operation_type,
+                "Writing AWS binary secret: region={}, secret={}, operation={}",
+                self._region,
+                secret_name,
+                operation_type
+            );
+
+            let blob = aws_sdk_secretsmanager::primitives::Blob::new(secret_value.to_vec());
+            let result = if operation_type == "create" {
+                let mut create_request = self
+                    .client
+                    .create_secret()
+                    .name(secret_name)
+                    .secret_binary(blob)
+                    .tags(
+                        aws_sdk_secretsmanager::types::Tag::builder()
+                            .key("environment")
+                            .value(environment)
+                            .build(),
+                    )
+                    .tags(
+                        aws_sdk_secretsmanager::types::Tag::builder()
+                            .key("location")
+                            .value(location)
+                            .build(),
+                    );
+                if std::env::var("PACT_MODE").is_ok() {
+                    create_request =
+                        create_request.client_request_token("00000000-0000-0000-0000-000000000000");
+                }
+                create_request
+                    .send()
+                    .timed("create_secret")
+                    .await
+                    .map(|output| output.version_id().map(ToString::to_string))
+                    .map_err(|e| e.to_string())
+            } else {
+                let mut put_request = self
+                    .client
+                    .put_secret_value()
+                    .secret_id(secret_name)
+                    .secret_binary(blob)
+                    .set_version_stages(put_version_stages(self.version_stages.as_ref()));
+                if std::env::var("PACT_MODE").is_ok() {
+                    put_request =
+                        put_request.client_request_token("00000000-0000-0000-0000-000000000000");
+                }
+                put_request
+                    .send()
+                    .timed("put_secret_value")
+                    .await
+                    .map(|output| output.version_id().map(ToString::to_string))
+                    .map_err(|e| e.to_string())
+            };
+
+            span_clone.record("operation.type", operation_type);
+            span_clone.record("operation.duration_ms", start.elapsed().as_millis() as u64);
+            match result {
+                Ok(version_id) => {
+                    self.finish_version_staging(
+                        secret_name,
+                        version_id.as_deref(),
+                        operation_type == "create",
+                    )
+                    .await?;
+                    metrics::record_secret_operation(
+                        "aws",
+                        operation_type,
+                        start.elapsed().as_secs_f64(),
+                    );
+                    span_clone.record("operation.success", true);
+                    Ok(true)
+                }
+                Err(error_msg) => {
+                    span_clone.record("operation.success", false);
+                    span_clone.record("error.message", error_msg.clone());
+                    metrics::increment_provider_operation_errors("aws");
+                    Err(anyhow::anyhow!(
+                        "Failed to {operation_type} AWS binary secret {secret_name}: {error_msg}"
+                    ))
+                }
+            }
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn get_secret_binary(&self, secret_name: &str) -> Result<Option<Vec<u8>>> {
+        match self
+            .client
+            .get_secret_value()
+            .secret_id(secret_name)
+            .send()
+            .timed("get_secret_value")
+            .await
+        {
+            Ok(response) => Ok(response
+                .secret_binary()
+                .map(|blob| blob.as_ref().to_vec())
+                .or_else(|| response.secret_string().map(|s| s.as_bytes().to_vec()))),
+            Err(e) => {
+                let error = classify_sdk_error(&e, format!("Failed to get AWS binary secret: {e}"));
+                if matches!(error, ProviderError::NotFound(_)) {
+                    Ok(None)
+                } else {
+                    metrics::increment_provider_operation_errors("aws");
+                    Err(error.into())
+                }
+            }
+        }
+    }
+
     async fn delete_secret(&self, secret_name: &str) -> Result<()> {
         info!("Deleting AWS secret: {}", secret_name);
         self.client
@@ -285,6 +464,7 @@ impl SecretManagerProvider for AwsSecretManager {
             .secret_id(secret_name)
             .force_delete_without_recovery(true)
             .send()
+            .timed("delete_secret")
             .await
             .context("Failed to delete AWS secret")?;
         Ok(())
@@ -304,6 +484,7 @@ impl SecretManagerProvider for AwsSecretManager {
             .describe_secret()
             .secret_id(secret_name)
             .send()
+            .timed("describe_secret")
             .await
             .is_ok();
 
@@ -321,6 +502,7 @@ impl SecretManagerProvider for AwsSecretManager {
             .secret_id(secret_name)
             .recovery_window_in_days(7) // 7 days recovery window
             .send()
+            .timed("delete_secret")
             .await
         {
             Ok(_) => {
@@ -328,15 +510,18 @@ impl SecretManagerProvider for AwsSecretManager {
                 Ok(true)
             }
             Err(e) => {
-                let error_msg = e.to_string();
+                let error = classify_sdk_error(
+                    &e,
+                    format!("Failed to disable AWS secret {secret_name}: {e}"),
+                );
                 // If already deleted/disabled, return false
-                if error_msg.contains("not found") || error_msg.contains("InvalidRequestException")
-                {
+                if matches!(
+                    error,
+                    ProviderError::NotFound(_) | ProviderError::InvalidPayload(_)
+                ) {
                     Ok(false)
                 } else {
-                    Err(anyhow::anyhow!(
-                        "Failed to disable AWS secret {secret_name}: {e}"
-                    ))
+                    Err(error.into())
                 }
             }
         }
@@ -351,6 +536,7 @@ impl SecretManagerProvider for AwsSecretManager {
             .restore_secret()
             .secret_id(secret_name)
             .send()
+            .timed("restore_secret")
             .await
         {
             Ok(_) => {
@@ -358,7 +544,6 @@ impl SecretManagerProvider for AwsSecretManager {
                 Ok(true)
             }
             Err(e) => {
-                let error_msg = e.to_string();
                 // Log detailed error information for debugging
                 let error_details = format!("{:?}", e);
                 warn!(
@@ -372,19 +557,258 @@ impl SecretManagerProvider for AwsSecretManager {
                     e
                 );
                 // If secret doesn't exist or is already enabled, return false
-                if error_msg.contains("not found") || error_msg.contains("InvalidRequestException")
-                {
+                let error = classify_sdk_error(
+                    &e,
+                    format!("Failed to enable AWS secret {secret_name}: {e}"),
+                );
+                if matches!(
+                    error,
+                    ProviderError::NotFound(_) | ProviderError::InvalidPayload(_)
+                ) {
                     debug!(
                         "Secret {} does not exist or is already enabled",
                         secret_name
                     );
                     Ok(false)
                 } else {
-                    Err(anyhow::anyhow!(
-                        "Failed to enable AWS secret {secret_name}: {e}"
+                    Err(error.into())
+                }
+            }
+        }
+    }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        let description = self
+            .client
+            .describe_secret()
+            .secret_id(secret_name)
+            .send()
+            .timed("describe_secret")
+            .await
+            .context(format!("Failed to describe AWS secret {secret_name}"))?;
+        let versions_to_stages = description
+            .version_ids_to_stages()
+            .cloned()
+            .unwrap_or_default();
+        Ok(SecretVersionIds {
+            previous: version_holding(&versions_to_stages, AWS_PREVIOUS).map(str::to_string),
+            current: version_holding(&versions_to_stages, AWS_CURRENT).map(str::to_string),
+        })
+    }
+
+    async fn set_secret_labels(
+        &self,
+        secret_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        // TagResource adds or overwrites the given tags and keeps the others
+        let tags = labels
+            .iter()
+            .map(|(key, value)| {
+                aws_sdk_secretsmanager::types::Tag::builder()
+                    .key(key)
+                    .value(value)
+                    .build()
+            })
+            .collect();
+        self.client
+            .tag_resource()
+            .secret_id(secret_name)
+            .set_tags(Some(tags))
+            .send()
+            .timed("tag_resource")
+            .await
+            .map_err(|e| {
+                metrics::increment_provider_operation_errors("aws");
+                anyhow::Error::new(classify_sdk_error(
+                    &e,
+                    format!("Failed to tag AWS secret {secret_name}: {e}"),
+                ))
+            })?;
+        Ok(())
+    }
+
+    async fn set_secret_expiration(
+        &self,
+        secret_name: &str,
+        expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        let tag = HashMap::from([(
+            EXPIRES_AT_TAG.to_string(),
+            expire_time.to_rfc3339_opts(SecondsFormat::Secs, true),
+        )]);
+        self.set_secret_labels(secret_name, &tag).await
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        // The Name filter matches prefixes; names are re-checked in case it's ignored
+        let name_filter = (!prefix.is_empty()).then(|| {
+            aws_sdk_secretsmanager::types::Filter::builder()
+                .key(aws_sdk_secretsmanager::types::FilterNameStringType::Name)
+                .values(prefix)
+                .build()
+        });
+        let mut names = Vec::new();
+        let mut next_token = None;
+        loop {
+            let response = self
+                .client
+                .list_secrets()
+                .set_filters(name_filter.clone().map(|filter| vec![filter]))
+                .set_next_token(next_token.take())
+                .send()
+                .timed("list_secrets")
+                .await
+                .map_err(|e| {
+                    metrics::increment_provider_operation_errors("aws");
+                    anyhow::Error::new(classify_sdk_error(
+                        &e,
+                        format!("Failed to list AWS secrets: {e}"),
                     ))
+                })?;
+
+            names.extend(
+                response
+                    .secret_list()
+                    .iter()
+                    .filter_map(|secret| secret.name())
+                    .filter(|name| name.starts_with(prefix))
+                    .map(ToString::to_string),
+            );
+
+            match response.next_token() {
+                Some(token) if !token.is_empty() => next_token = Some(token.to_string()),
+                _ => break,
+            }
+        }
+        Ok(names)
+    }
+
+    async fn get_secret_values(
+        &self,
+        secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        let start = Instant::now();
+        let mut values = HashMap::new();
+        // BatchGetSecretValue accepts at most 20 secret IDs per call
+        for chunk in secret_names.chunks(20) {
+            let mut next_token = None;
+            loop {
+                let response = self
+                    .client
+                    .batch_get_secret_value()
+                    .set_secret_id_list(Some(chunk.to_vec()))
+                    .set_next_token(next_token.take())
+                    .send()
+                    .timed("batch_get_secret_value")
+                    .await
+                    .map_err(|e| {
+                        metrics::increment_provider_operation_errors("aws");
+                        anyhow::Error::new(classify_sdk_error(
+                            &e,
+                            format!("Failed to batch get AWS secrets: {e}"),
+                        ))
+                    })?;
+
+                for entry in response.secret_values() {
+                    let Some(name) = entry.name() else {
+                        continue;
+                    };
+                    let value = entry.secret_string().map(ToString::to_string).or_else(|| {
+                        entry
+                            .secret_binary()
+                            .map(|blob| String::from_utf8_lossy(blob.as_ref()).to_string())
+                    });
+                    if let Some(value) = value {
+                        values.insert(name.to_string(), Some(SecretString::new(value)));
+                    }
+                }
+                // Other errors (scheduled for deletion, access denied) are left to the
+                // per-secret read, which reports them
+                for error in response.errors() {
+                    if error.error_code() == Some("ResourceNotFoundException")
+                        && let Some(name) = error.secret_id()
+                    {
+                        values.insert(name.to_string(), None);
+                    }
+                }
+
+                match response.next_token() {
+                    Some(token) if !token.is_empty() => next_token = Some(token.to_string()),
+                    _ => break,
+                }
+            }
+        }
+        metrics::record_secret_operation("aws", "batch_get", start.elapsed().as_secs_f64());
+        Ok(values)
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        let description = match self
+            .client
+            .describe_secret()
+            .secret_id(secret_name)
+            .send()
+            .timed("describe_secret")
+            .await
+        {
+            Ok(description) => description,
+            Err(e) => {
+                let error = classify_sdk_error(
+                    &e,
+                    format!("Failed to describe AWS secret {secret_name}: {e}"),
+                );
+                if matches!(error, ProviderError::NotFound(_)) {
+                    return Ok(None);
                 }
+                metrics::increment_provider_operation_errors("aws");
+                return Err(error.into());
+            }
+        };
+
+        let mut version_count = 0;
+        let mut next_token = None;
+        loop {
+            let response = self
+                .client
+                .list_secret_version_ids()
+                .secret_id(secret_name)
+                .set_next_token(next_token.take())
+                .send()
+                .timed("list_secret_version_ids")
+                .await
+                .map_err(|e| {
+                    metrics::increment_provider_operation_errors("aws");
+                    anyhow::Error::new(classify_sdk_error(
+                        &e,
+                        format!("Failed to list versions of AWS secret {secret_name}: {e}"),
+                    ))
+                })?;
+            version_count += response.versions().len();
+
+            match response.next_token() {
+                Some(token) if !token.is_empty() => next_token = Some(token.to_string()),
+                _ => break,
             }
         }
+
+        let labels: HashMap<String, String> = description
+            .tags()
+            .iter()
+            .filter_map(|tag| Some((tag.key()?.to_string(), tag.value()?.to_string())))
+            .collect();
+        let expire_time = labels
+            .get(EXPIRES_AT_TAG)
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|time| time.with_timezone(&Utc));
+        Ok(Some(SecretMetadata {
+            name: secret_name.to_string(),
+            labels,
+            create_time: description.created_date().and_then(|created| {
+                chrono::DateTime::from_timestamp(created.secs(), created.subsec_nanos())
+            }),
+            version_count,
+            expire_time,
+        }))
     }
 }
@@ -0,0 +1,200 @@
+//! # AWS Secrets Manager Version Staging
+//!
+//! Manages staging labels on secret versions when `aws.versionStages` is configured.
+//!
+//! By default `PutSecretValue` attaches `AWSCURRENT` to the new version and moves
+//! `AWSPREVIOUS` to the old one. Rotation-aware consumers (e.g. Lambda rotation
+//! functions) instead expect a new value to be staged as `AWSPENDING` first and then
+//! promoted to `AWSCURRENT`. Custom labels are moved to the newly promoted version.
+//!
+//! References:
+//! - [Staging labels](https://docs.aws.amazon.com/secretsmanager/latest/userguide/whats-in-a-secret.html#term_version)
+//! - [UpdateSecretVersionStage](https://docs.aws.amazon.com/secretsmanager/latest/apireference/API_UpdateSecretVersionStage.html)
+
+use crate::crd::AwsVersionStagesConfig;
+use crate::provider::aws::TimedSdkCall;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use tracing::info;
+
+use super::AwsSecretManager;
+
+/// Label attached to the version consumers read by default
+pub const AWS_CURRENT: &str = "AWSCURRENT";
+/// Label attached to a version that is staged but not yet promoted
+pub const AWS_PENDING: &str = "AWSPENDING";
+/// Label AWS moves to the previously current version
+pub const AWS_PREVIOUS: &str = "AWSPREVIOUS";
+
+/// Staging labels to request on `PutSecretValue`
+/// Returns None to keep the SDK default (`AWSCURRENT` only)
+#[must_use]
+pub fn put_version_stages(config: Option<&AwsVersionStagesConfig>) -> Option<Vec<String>> {
+    let config = config?;
+    if config.promote_pending {
+        // Custom labels are moved after promotion so they never point at an unpromoted version
+        Some(vec![AWS_PENDING.to_string()])
+    } else if config.labels.is_empty() {
+        None
+    } else {
+        let mut stages = vec![AWS_CURRENT.to_string()];
+        stages.extend(config.labels.iter().cloned());
+        Some(stages)
+    }
+}
+
+/// Find the version currently holding a staging label
+#[must_use]
+pub fn version_holding<'a>(
+    versions_to_stages: &'a HashMap<String, Vec<String>>,
+    stage: &str,
+) -> Option<&'a str> {
+    versions_to_stages
+        .iter()
+        .find(|(_, stages)| stages.iter().any(|s| s == stage))
+        .map(|(version_id, _)| version_id.as_str())
+}
+
+impl AwsSecretManager {
+    /// Complete staging for a newly written version
+    /// - `created`: the version was created by `CreateSecret` (already `AWSCURRENT`)
+    ///
+    /// With `promotePending`, an updated version is promoted from `AWSPENDING` to `AWSCURRENT`.
+    /// Custom labels are moved to the new version when they were not set by `PutSecretValue`.
+    /// # Errors
+    /// Returns an error if describing the secret or moving a staging label fails
+    pub(crate) async fn finish_version_staging(
+        &self,
+        secret_name: &str,
+        version_id: Option<&str>,
+        created: bool,
+    ) -> Result<()> {
+        let Some(config) = self.version_stages.as_ref() else {
+            return Ok(());
+        };
+        let promote = config.promote_pending && !created;
+        // Without promotion, PutSecretValue already attached the custom labels
+        let move_labels = !config.labels.is_empty() && (created || config.promote_pending);
+        if !promote && !move_labels {
+            return Ok(());
+        }
+        let Some(version_id) = version_id else {
+            return Err(anyhow::anyhow!(
+                "AWS did not return a version ID for secret {secret_name}, cannot stage version"
+            ));
+        };
+
+        let description = self
+            .client
+            .describe_secret()
+            .secret_id(secret_name)
+            .send()
+            .timed("describe_secret")
+            .await
+            .context(format!("Failed to describe AWS secret {secret_name}"))?;
+        let versions_to_stages = description
+            .version_ids_to_stages()
+            .cloned()
+            .unwrap_or_default();
+
+        if promote {
+            let current = version_holding(&versions_to_stages, AWS_CURRENT)
+                .filter(|current| *current != version_id);
+            self.move_stage(secret_name, AWS_CURRENT, Some(version_id), current)
+                .await?;
+            self.move_stage(secret_name, AWS_PENDING, None, Some(version_id))
+                .await?;
+            info!(
+                provider = "aws",
+                secret_name = secret_name,
+                version_id = version_id,
+                "Promoted AWS secret version from {} to {}: secret={}, version={}",
+                AWS_PENDING,
+                AWS_CURRENT,
+                secret_name,
+                version_id
+            );
+        }
+
+        if move_labels {
+            for label in &config.labels {
+                let holder = version_holding(&versions_to_stages, label)
+                    .filter(|holder| *holder != version_id);
+                self.move_stage(secret_name, label, Some(version_id), holder)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move (or remove, when `move_to` is None) a staging label
+    async fn move_stage(
+        &self,
+        secret_name: &str,
+        stage: &str,
+        move_to: Option<&str>,
+        remove_from: Option<&str>,
+    ) -> Result<()> {
+        self.client
+            .update_secret_version_stage()
+            .secret_id(secret_name)
+            .version_stage(stage)
+            .set_move_to_version_id(move_to.map(ToString::to_string))
+            .set_remove_from_version_id(remove_from.map(ToString::to_string))
+            .send()
+            .timed("update_secret_version_stage")
+            .await
+            .context(format!(
+                "Failed to update staging label {stage} on AWS secret {secret_name}"
+            ))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(promote_pending: bool, labels: &[&str]) -> AwsVersionStagesConfig {
+        AwsVersionStagesConfig {
+            promote_pending,
+            labels: labels.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn test_put_version_stages_default() {
+        assert_eq!(put_version_stages(None), None);
+        assert_eq!(put_version_stages(Some(&config(false, &[]))), None);
+    }
+
+    #[test]
+    fn test_put_version_stages_with_labels() {
+        assert_eq!(
+            put_version_stages(Some(&config(false, &["deployed"]))),
+            Some(vec!["AWSCURRENT".to_string(), "deployed".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_put_version_stages_promote_pending() {
+        assert_eq!(
+            put_version_stages(Some(&config(true, &["deployed"]))),
+            Some(vec!["AWSPENDING".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_version_holding() {
+        let mut versions = HashMap::new();
+        versions.insert("v1".to_string(), vec![AWS_PREVIOUS.to_string()]);
+        versions.insert(
+            "v2".to_string(),
+            vec![AWS_CURRENT.to_string(), "deployed".to_string()],
+        );
+        assert_eq!(version_holding(&versions, AWS_CURRENT), Some("v2"));
+        assert_eq!(version_holding(&versions, "deployed"), Some("v2"));
+        assert_eq!(version_holding(&versions, AWS_PENDING), None);
+    }
+}
@@ -3,6 +3,7 @@
 //! Handles authentication for Azure App Configuration, including token management.
 
 use crate::crd::{AzureAuthConfig, AzureConfig};
+use crate::provider::azure::cloud::{cloud_credential, needs_cloud_credential};
 use anyhow::{Context, Result};
 use azure_core::credentials::{AccessToken, TokenCredential, TokenRequestOptions};
 use azure_identity::{ManagedIdentityCredential, WorkloadIdentityCredential};
@@ -10,14 +11,22 @@ use std::sync::Arc;
 use tracing::info;
 
 /// Create Azure App Configuration credential based on configuration
-/// Supports Workload Identity, Managed Identity, and mock credentials for Pact testing
-pub fn create_credential(config: &AzureConfig) -> Result<Arc<dyn TokenCredential>> {
+/// Supports Workload Identity, client certificates, Managed Identity, and mock credentials for
+/// Pact testing
+pub fn create_credential(
+    config: &AzureConfig,
+    k8s_client: &kube::Client,
+) -> Result<Arc<dyn TokenCredential>> {
     let credential: Arc<dyn TokenCredential> = if std::env::var("PACT_MODE").is_ok() {
         // Use mock credential for Pact testing
         Arc::new(MockTokenCredential)
+    } else if needs_cloud_credential(config) {
+        // Sovereign clouds, tenant overrides and client certificates need their own
+        // authority, tenant or assertion
+        cloud_credential(config, k8s_client)?
     } else {
         match &config.auth {
-            Some(AzureAuthConfig::WorkloadIdentity { client_id }) => {
+            Some(AzureAuthConfig::WorkloadIdentity { client_id, .. }) => {
                 info!(
                     "Using Azure Workload Identity authentication with client ID: {}",
                     client_id
@@ -30,6 +39,9 @@ pub fn create_credential(config: &AzureConfig) -> Result<Arc<dyn TokenCredential
                 WorkloadIdentityCredential::new(Some(options))
                     .context("Failed to create WorkloadIdentityCredential")?
             }
+            Some(AzureAuthConfig::ClientCertificate { .. }) => {
+                unreachable!("client certificates use cloud_credential")
+            }
             None => {
                 info!("No auth configuration specified, using Managed Identity");
                 info!("This works automatically in Azure environments (AKS, App Service, etc.)");
@@ -3,6 +3,7 @@
 //! Handles the creation of HTTP client and endpoint configuration.
 
 use crate::crd::AzureConfig;
+use crate::provider::azure::cloud::AzureCloud;
 use anyhow::{Context, Result};
 use azure_core::credentials::TokenCredential;
 use reqwest::Client;
@@ -26,7 +27,7 @@ pub fn create_client_components(
     credential: Arc<dyn TokenCredential>,
 ) -> Result<ClientComponents> {
     // Construct App Configuration endpoint
-    // Format: https://{store-name}.azconfig.io
+    // Format: https://{store-name}.azconfig.io (public cloud)
     let endpoint = if let Some(endpoint) = app_config_endpoint {
         endpoint.to_string()
     } else {
@@ -34,7 +35,8 @@ pub fn create_client_components(
         // Extract store name from vault name pattern
         // This is a simple heuristic - users should provide endpoint explicitly
         let store_name = config.vault_name.replace("-vault", "-appconfig");
-        format!("https://{store_name}.azconfig.io")
+        let suffix = AzureCloud::for_environment(config.environment).app_configuration_dns_suffix;
+        format!("https://{store_name}.{suffix}")
     };
 
     // Ensure endpoint doesn't have trailing slash
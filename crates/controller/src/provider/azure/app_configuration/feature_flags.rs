@@ -0,0 +1,128 @@
+//! # Azure App Configuration Feature Flags
+//!
+//! Properties whose key starts with `configs.azure.featureFlagPrefix` are stored as feature
+//! flags rather than plain key-values: the key `.appconfig.featureflag/{id}` with the feature
+//! flag content type, where `{id}` is the property key without the prefix.
+//!
+//! - `true` / `false` set `enabled` on a flag without conditions
+//! - A JSON object is used as the flag definition (`description`, `conditions`, ...); its
+//!   `id` is always set from the key
+//!
+//! Stored flags are compared in the same form, so a flag is only rewritten when its
+//! definition changes.
+//!
+//! Reference: https://learn.microsoft.com/en-us/azure/azure-app-configuration/concept-feature-management
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value, json};
+
+/// Key prefix App Configuration reserves for feature flags
+pub const FEATURE_FLAG_KEY_PREFIX: &str = ".appconfig.featureflag/";
+
+/// Content type App Configuration (and its Kubernetes provider) expects on feature flags
+pub const FEATURE_FLAG_CONTENT_TYPE: &str =
+    "application/vnd.microsoft.appconfig.ff+json;charset=utf-8";
+
+/// Feature flag document for a property value
+/// # Errors
+/// Returns an error if the id is empty or the value is neither a boolean nor a JSON object
+pub fn feature_flag_document(id: &str, value: &str) -> Result<Value> {
+    if id.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Feature flag key has no name after the prefix"
+        ));
+    }
+    let trimmed = value.trim();
+    let mut document = if trimmed.eq_ignore_ascii_case("true") {
+        json!({"enabled": true})
+    } else if trimmed.eq_ignore_ascii_case("false") {
+        json!({"enabled": false})
+    } else {
+        match serde_json::from_str::<Value>(trimmed)
+            .with_context(|| format!("Feature flag '{id}' must be true, false or a JSON object"))?
+        {
+            Value::Object(map) => Value::Object(map),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Feature flag '{id}' must be true, false or a JSON object"
+                ));
+            }
+        }
+    };
+
+    if let Value::Object(map) = &mut document {
+        map.insert("id".to_string(), Value::String(id.to_string()));
+        map.entry("enabled").or_insert(Value::Bool(false));
+        map.entry("conditions")
+            .or_insert_with(|| json!({"client_filters": []}));
+    }
+    Ok(document)
+}
+
+/// Property form of a feature flag document: "true" or "false" for a flag without
+/// conditions or other settings, otherwise the document as JSON (without `id`)
+#[must_use]
+pub fn feature_flag_property_value(document: &Value) -> String {
+    let Value::Object(map) = document else {
+        return document.to_string();
+    };
+    let mut settings: Map<String, Value> = map.clone();
+    settings.remove("id");
+
+    // Defaults the portal and SDKs write on otherwise plain flags
+    let mut plain = settings.clone();
+    if plain.get("description") == Some(&json!("")) {
+        plain.remove("description");
+    }
+    if plain.get("conditions") == Some(&json!({"client_filters": []})) {
+        plain.remove("conditions");
+    }
+
+    match plain.get("enabled").and_then(Value::as_bool) {
+        Some(enabled) if plain.len() == 1 => enabled.to_string(),
+        _ => Value::Object(settings).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_flag_document() {
+        assert_eq!(
+            feature_flag_document("beta", "True").unwrap_or_default(),
+            json!({"id": "beta", "enabled": true, "conditions": {"client_filters": []}})
+        );
+        let targeted = r#"{"enabled": true, "conditions": {"client_filters": [{"name": "Microsoft.Percentage", "parameters": {"Value": 50}}]}}"#;
+        assert_eq!(
+            feature_flag_document("rollout", targeted).unwrap_or_default()["conditions"]["client_filters"]
+                [0]["name"],
+            "Microsoft.Percentage"
+        );
+        assert!(feature_flag_document("beta", "maybe").is_err());
+        assert!(feature_flag_document("beta", "[true]").is_err());
+        assert!(feature_flag_document("", "true").is_err());
+    }
+
+    #[test]
+    fn test_feature_flag_property_value_round_trips() {
+        for value in ["true", "false"] {
+            let document = feature_flag_document("beta", value).unwrap_or_default();
+            assert_eq!(feature_flag_property_value(&document), value);
+        }
+        // Flags edited in the portal carry an empty description
+        let stored = json!({
+            "id": "beta", "description": "", "enabled": true,
+            "conditions": {"client_filters": []}
+        });
+        assert_eq!(feature_flag_property_value(&stored), "true");
+
+        let targeted = r#"{"enabled": true, "conditions": {"client_filters": [{"name": "Microsoft.Targeting"}]}}"#;
+        let normalized = feature_flag_property_value(
+            &feature_flag_document("rollout", targeted).unwrap_or_default(),
+        );
+        let stored = feature_flag_document("rollout", &normalized).unwrap_or_default();
+        assert_eq!(feature_flag_property_value(&stored), normalized);
+    }
+}
@@ -5,6 +5,8 @@
 //! This module provides functionality to:
 //! - Create and update key-value pairs in Azure App Configuration
 //! - Retrieve configuration values
+//! - List and bulk-write key-values under the configured key prefix
+//! - Write keys under `configs.azure.featureFlagPrefix` as feature flags
 //! - Support Workload Identity authentication
 //!
 //! Azure App Configuration is used for storing configuration values (non-secrets)
@@ -12,6 +14,7 @@
 
 mod auth;
 mod client;
+mod feature_flags;
 mod operations;
 mod types;
 
@@ -52,9 +55,9 @@ impl AzureAppConfiguration {
         app_config_endpoint: Option<&str>,
         secret_prefix: &str,
         environment: &str,
-        _k8s_client: &kube::Client,
+        k8s_client: &kube::Client,
     ) -> Result<Self> {
-        let credential = create_credential(config)?;
+        let credential = create_credential(config, k8s_client)?;
         let components = create_client_components(
             config,
             app_config_endpoint,
@@ -62,9 +65,21 @@ impl AzureAppConfiguration {
             environment,
             credential,
         )?;
-        let operations = AzureAppConfigurationOperations { components };
+        let operations = AzureAppConfigurationOperations {
+            components,
+            feature_flag_prefix: None,
+        };
         Ok(Self { operations })
     }
+
+    /// Store keys starting with `prefix` as feature flags instead of key-values
+    #[must_use]
+    pub fn with_feature_flags(mut self, prefix: Option<&str>) -> Self {
+        self.operations.feature_flag_prefix = prefix
+            .filter(|prefix| !prefix.is_empty())
+            .map(str::to_string);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -82,4 +97,15 @@ impl ConfigStoreProvider for AzureAppConfiguration {
     async fn delete_config(&self, config_key: &str) -> Result<()> {
         self.operations.delete_config(config_key).await
     }
+
+    async fn list_configs(&self, prefix: &str) -> Result<Vec<String>> {
+        self.operations.list_configs(prefix).await
+    }
+
+    async fn bulk_upsert(
+        &self,
+        entries: Vec<(String, String)>,
+    ) -> Result<std::collections::HashMap<String, bool>> {
+        self.operations.bulk_upsert(entries).await
+    }
 }
@@ -4,17 +4,25 @@
 
 use super::auth::get_token;
 use super::client::ClientComponents;
-use super::types::KeyValue;
+use super::feature_flags::{
+    FEATURE_FLAG_CONTENT_TYPE, FEATURE_FLAG_KEY_PREFIX, feature_flag_document,
+    feature_flag_property_value,
+};
+use super::types::{KeyValue, KeyValueList};
 use crate::observability::metrics;
 use crate::provider::ConfigStoreProvider;
+use crate::provider::common::TimedSend;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::time::Instant;
 use tracing::{Instrument, debug, info, info_span};
 
 /// Azure App Configuration provider operations
 pub struct AzureAppConfigurationOperations {
     pub(crate) components: ClientComponents,
+    /// Config keys starting with this prefix are stored as feature flags
+    pub(crate) feature_flag_prefix: Option<String>,
 }
 
 impl AzureAppConfigurationOperations {
@@ -23,17 +31,245 @@ impl AzureAppConfigurationOperations {
     pub fn construct_key_name(&self, key: &str) -> String {
         format!("{}{}", self.components.key_prefix, key)
     }
+
+    /// Feature flag id for a config key under the feature flag prefix
+    fn feature_flag_id<'a>(&self, config_key: &'a str) -> Option<&'a str> {
+        self.feature_flag_prefix
+            .as_deref()
+            .and_then(|prefix| config_key.strip_prefix(prefix))
+    }
+
+    /// Fetch all key-values whose key starts with the store prefix plus `prefix`
+    /// Keys are returned relative to the store prefix
+    async fn list_key_values(&self, prefix: &str) -> Result<HashMap<String, String>> {
+        let items = self.list_items(&self.construct_key_name(prefix)).await?;
+        Ok(items
+            .into_iter()
+            .filter_map(|(key, value)| {
+                key.strip_prefix(&self.components.key_prefix)
+                    .map(|key| (key.to_string(), value))
+            })
+            .collect())
+    }
+
+    /// Fetch all feature flags, keyed by flag id, in the form property values are compared in
+    async fn list_feature_flags(&self) -> Result<HashMap<String, String>> {
+        let items = self.list_items(FEATURE_FLAG_KEY_PREFIX).await?;
+        Ok(items
+            .into_iter()
+            .filter_map(|(key, value)| {
+                key.strip_prefix(FEATURE_FLAG_KEY_PREFIX)
+                    .map(|id| (id.to_string(), stored_feature_flag_value(&value)))
+            })
+            .collect())
+    }
+
+    /// Fetch all key-values whose full key starts with `key_prefix`
+    async fn list_items(&self, key_prefix: &str) -> Result<HashMap<String, String>> {
+        let start = Instant::now();
+        let token = get_token(&self.components.credential).await?;
+        let filter = format!("{}*", escape_key_filter(key_prefix));
+        let mut values = HashMap::new();
+        let mut request = self
+            .components
+            .http_client
+            .get(format!("{}/kv", self.components.endpoint))
+            .query(&[("key", filter.as_str())]);
+
+        loop {
+            let response = request
+                .header("Authorization", format!("Bearer {token}"))
+                .send_timed("azure", "list_key_values")
+                .await
+                .context("Failed to list Azure App Configuration key-values")?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                metrics::increment_provider_operation_errors("azure");
+                return Err(anyhow::anyhow!(
+                    "Failed to list Azure App Configuration key-values: {status} - {error_text}"
+                ));
+            }
+
+            let page: KeyValueList = response
+                .json()
+                .await
+                .context("Failed to deserialize Azure App Configuration list response")?;
+            for item in page.items {
+                values.insert(item.key, item.value.unwrap_or_default());
+            }
+
+            match page.next_link.filter(|link| !link.is_empty()) {
+                Some(link) => {
+                    request = self
+                        .components
+                        .http_client
+                        .get(format!("{}{}", self.components.endpoint, link));
+                }
+                None => break,
+            }
+        }
+
+        metrics::record_secret_operation("azure", "list", start.elapsed().as_secs_f64());
+        Ok(values)
+    }
+
+    /// Write a key-value without checking the current value
+    async fn put_key_value(
+        &self,
+        token: &str,
+        key_name: &str,
+        config_value: &str,
+        content_type: &str,
+    ) -> Result<()> {
+        let kv = KeyValue {
+            key: key_name.to_string(),
+            value: config_value.to_string(),
+            label: None,
+            content_type: Some(content_type.to_string()),
+        };
+
+        let put_url = format!("{}/kv", self.components.endpoint);
+        let response = self
+            .components
+            .http_client
+            .put(&put_url)
+            .header("Authorization", format!("Bearer {token}"))
+            .header("Content-Type", "application/json")
+            .json(&kv)
+            .send_timed("azure", "set_key_value")
+            .await
+            .context("Failed to write Azure App Configuration key-value")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("azure");
+            return Err(anyhow::anyhow!(
+                "Failed to write Azure App Configuration key-value {key_name}: {status} - {error_text}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Current value of a feature flag, in the form property values are compared in
+    async fn get_feature_flag(&self, id: &str) -> Result<Option<String>> {
+        let start = Instant::now();
+        let token = get_token(&self.components.credential).await?;
+        let url = format!(
+            "{}/kv/{}",
+            self.components.endpoint,
+            feature_flag_key_path(id)
+        );
+        let response = self
+            .components
+            .http_client
+            .get(&url)
+            .header("Authorization", format!("Bearer {token}"))
+            .send_timed("azure", "get_key_value")
+            .await
+            .context("Failed to get Azure App Configuration feature flag")?;
+
+        if response.status() == 404 {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("azure");
+            return Err(anyhow::anyhow!(
+                "Failed to get Azure App Configuration feature flag {id}: {status} - {error_text}"
+            ));
+        }
+
+        let kv: KeyValue = response
+            .json()
+            .await
+            .context("Failed to deserialize Azure App Configuration feature flag")?;
+        metrics::record_secret_operation("azure", "get", start.elapsed().as_secs_f64());
+        Ok(Some(stored_feature_flag_value(&kv.value)))
+    }
+
+    /// Write a feature flag if its definition differs from `current`
+    /// Returns whether the flag was written
+    async fn upsert_feature_flag(
+        &self,
+        token: &str,
+        id: &str,
+        config_value: &str,
+        current: Option<&String>,
+    ) -> Result<bool> {
+        let document = feature_flag_document(id, config_value)?;
+        if current.is_some_and(|current| *current == feature_flag_property_value(&document)) {
+            debug!(
+                "Azure App Configuration feature flag {} unchanged, skipping update",
+                id
+            );
+            return Ok(false);
+        }
+
+        let operation = if current.is_some() {
+            "update"
+        } else {
+            "create"
+        };
+        info!("Azure App Configuration feature flag {}: {}", operation, id);
+        let start = Instant::now();
+        self.put_key_value(
+            token,
+            &format!("{FEATURE_FLAG_KEY_PREFIX}{id}"),
+            &document.to_string(),
+            FEATURE_FLAG_CONTENT_TYPE,
+        )
+        .await?;
+        metrics::record_secret_operation("azure", operation, start.elapsed().as_secs_f64());
+        Ok(true)
+    }
+}
+
+/// Stored feature flag value in the form property values are compared in
+/// Values that aren't valid flag JSON are returned as is, so they're rewritten
+fn stored_feature_flag_value(value: &str) -> String {
+    serde_json::from_str(value)
+        .map(|document| feature_flag_property_value(&document))
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// Feature flag key as a `/kv/{key}` path segment (the `/` must be percent-encoded)
+fn feature_flag_key_path(id: &str) -> String {
+    format!("{FEATURE_FLAG_KEY_PREFIX}{id}").replace('/', "%2F")
+}
+
+/// Escape characters that are reserved in App Configuration key filters (`*`, `,`, `\`)
+fn escape_key_filter(key: &str) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    for c in key.chars() {
+        if matches!(c, '*' | ',' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
 }
 
 #[async_trait]
 impl ConfigStoreProvider for AzureAppConfigurationOperations {
     async fn create_or_update_config(&self, config_key: &str, config_value: &str) -> Result<bool> {
+        if let Some(id) = self.feature_flag_id(config_key) {
+            let current = self.get_feature_flag(id).await?;
+            let token = get_token(&self.components.credential).await?;
+            return self
+                .upsert_feature_flag(&token, id, config_value, current.as_ref())
+                .await;
+        }
+
         let key_name = self.construct_key_name(config_key);
         let vault_name = self
             .components
             .endpoint
             .strip_prefix("https://")
-            .and_then(|s| s.strip_suffix(".azconfig.io"))
+            .and_then(|s| s.split('.').next())
             .unwrap_or("unknown");
         let span = info_span!(
             "azure.appconfig.create_or_update",
@@ -55,7 +291,7 @@ impl ConfigStoreProvider for AzureAppConfigurationOperations {
                 .get(&get_url)
                 .header("Authorization", format!("Bearer {token}"))
                 .header("Content-Type", "application/json")
-                .send()
+                .send_timed("azure", "get_key_value")
                 .await
                 .context("Failed to check if Azure App Configuration key exists")?;
 
@@ -79,7 +315,7 @@ impl ConfigStoreProvider for AzureAppConfigurationOperations {
                     .header("Authorization", format!("Bearer {token}"))
                     .header("Content-Type", "application/json")
                     .json(&kv)
-                    .send()
+                    .send_timed("azure", "set_key_value")
                     .await
                     .context("Failed to create Azure App Configuration key-value")?;
 
@@ -136,7 +372,7 @@ impl ConfigStoreProvider for AzureAppConfigurationOperations {
                     .header("Authorization", format!("Bearer {token}"))
                     .header("Content-Type", "application/json")
                     .json(&kv)
-                    .send()
+                    .send_timed("azure", "set_key_value")
                     .await
                     .context("Failed to update Azure App Configuration key-value")?;
 
@@ -167,12 +403,16 @@ impl ConfigStoreProvider for AzureAppConfigurationOperations {
     }
 
     async fn get_config_value(&self, config_key: &str) -> Result<Option<String>> {
+        if let Some(id) = self.feature_flag_id(config_key) {
+            return self.get_feature_flag(id).await;
+        }
+
         let key_name = self.construct_key_name(config_key);
         let vault_name = self
             .components
             .endpoint
             .strip_prefix("https://")
-            .and_then(|s| s.strip_suffix(".azconfig.io"))
+            .and_then(|s| s.split('.').next())
             .unwrap_or("unknown");
         let span = tracing::debug_span!(
             "azure.appconfig.get",
@@ -202,7 +442,7 @@ impl ConfigStoreProvider for AzureAppConfigurationOperations {
                 .get(&url)
                 .header("Authorization", format!("Bearer {token}"))
                 .header("Content-Type", "application/json")
-                .send()
+                .send_timed("azure", "get_key_value")
                 .await
             {
                 Ok(response) => {
@@ -260,14 +500,26 @@ impl ConfigStoreProvider for AzureAppConfigurationOperations {
         let key_name = self.construct_key_name(config_key);
         let token = get_token(&self.components.credential).await?;
 
-        info!("Deleting Azure App Configuration key: {}", key_name);
-        let url = format!("{}/kv/{}", self.components.endpoint, key_name);
+        let url = match self.feature_flag_id(config_key) {
+            Some(id) => {
+                info!("Deleting Azure App Configuration feature flag: {}", id);
+                format!(
+                    "{}/kv/{}",
+                    self.components.endpoint,
+                    feature_flag_key_path(id)
+                )
+            }
+            None => {
+                info!("Deleting Azure App Configuration key: {}", key_name);
+                format!("{}/kv/{}", self.components.endpoint, key_name)
+            }
+        };
         let response = self
             .components
             .http_client
             .delete(&url)
             .header("Authorization", format!("Bearer {token}"))
-            .send()
+            .send_timed("azure", "delete_key_value")
             .await
             .context("Failed to delete Azure App Configuration key-value")?;
 
@@ -281,4 +533,95 @@ impl ConfigStoreProvider for AzureAppConfigurationOperations {
 
         Ok(())
     }
+
+    async fn list_configs(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys: Vec<String> = self.list_key_values(prefix).await?.into_keys().collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn bulk_upsert(&self, entries: Vec<(String, String)>) -> Result<HashMap<String, bool>> {
+        // One list request replaces a GET per key; only changed values are written
+        let current_values = self.list_key_values("").await?;
+        let current_flags = if entries
+            .iter()
+            .any(|(config_key, _)| self.feature_flag_id(config_key).is_some())
+        {
+            self.list_feature_flags().await?
+        } else {
+            HashMap::new()
+        };
+        let token = get_token(&self.components.credential).await?;
+        let mut results = HashMap::with_capacity(entries.len());
+
+        for (config_key, config_value) in entries {
+            if let Some(id) = self.feature_flag_id(&config_key) {
+                let written = self
+                    .upsert_feature_flag(&token, id, &config_value, current_flags.get(id))
+                    .await?;
+                results.insert(config_key, written);
+                continue;
+            }
+
+            let key_name = self.construct_key_name(&config_key);
+            let current = current_values.get(&config_key);
+            if current.is_some_and(|current| *current == config_value) {
+                debug!(
+                    "Azure App Configuration key {} unchanged, skipping update",
+                    key_name
+                );
+                results.insert(config_key, false);
+                continue;
+            }
+
+            let operation = if current.is_some() {
+                "update"
+            } else {
+                "create"
+            };
+            info!(
+                "Bulk {} of Azure App Configuration key: {}",
+                operation, key_name
+            );
+            let start = Instant::now();
+            self.put_key_value(&token, &key_name, &config_value, "text/plain")
+                .await?;
+            metrics::record_secret_operation("azure", operation, start.elapsed().as_secs_f64());
+            results.insert(config_key, true);
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_key_filter() {
+        assert_eq!(escape_key_filter("app:dev:"), "app:dev:");
+        assert_eq!(escape_key_filter("a*b,c\\d"), "a\\*b\\,c\\\\d");
+    }
+
+    #[test]
+    fn test_feature_flag_key_path() {
+        assert_eq!(
+            feature_flag_key_path("beta-checkout"),
+            ".appconfig.featureflag%2Fbeta-checkout"
+        );
+    }
+
+    #[test]
+    fn test_stored_feature_flag_value() {
+        let stored =
+            r#"{"id":"beta","description":"","enabled":true,"conditions":{"client_filters":[]}}"#;
+        assert_eq!(stored_feature_flag_value(stored), "true");
+        assert_eq!(stored_feature_flag_value("not json"), "not json");
+        let desired = feature_flag_document("beta", "TRUE").unwrap_or_default();
+        assert_eq!(
+            feature_flag_property_value(&desired),
+            stored_feature_flag_value(stored)
+        );
+    }
 }
@@ -14,3 +14,21 @@ pub struct KeyValue {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content_type: Option<String>,
 }
+
+/// Key-value item returned by the list endpoint (values may be null)
+#[derive(Debug, Deserialize)]
+pub struct KeyValueItem {
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+/// Page of key-values returned by `GET /kv?key={filter}`
+#[derive(Debug, Deserialize)]
+pub struct KeyValueList {
+    #[serde(default)]
+    pub items: Vec<KeyValueItem>,
+    /// Relative link to the next page, if any
+    #[serde(rename = "@nextLink", default)]
+    pub next_link: Option<String>,
+}
@@ -0,0 +1,282 @@
+//! # Azure Key Vault Certificates
+//!
+//! Imports certificate files into Key Vault's certificate store (not the secrets store).
+//!
+//! Key Vault has no "set certificate" operation - certificates are imported via
+//! `POST /certificates/{name}/import`, which always creates a new version. To avoid
+//! creating a version on every reconcile, the SHA-256 of the source file is stored
+//! as a tag and compared before importing.
+//!
+//! References:
+//! - [Import Certificate](https://learn.microsoft.com/en-us/rest/api/keyvault/certificates/import-certificate/import-certificate)
+//! - [Get Certificate](https://learn.microsoft.com/en-us/rest/api/keyvault/certificates/get-certificate/get-certificate)
+
+use crate::crd::{AzureCertificatesConfig, AzureConfig};
+use crate::observability::metrics;
+use crate::provider::common::TimedSend;
+use anyhow::{Context, Result};
+use azure_core::credentials::{TokenCredential, TokenRequestOptions};
+use base64::{Engine as _, engine::general_purpose};
+use reqwest::Client as ReqwestClient;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info};
+
+use super::auth::create_credential;
+use super::client::construct_vault_url;
+
+/// Key Vault REST API version used for certificate operations
+const CERTIFICATES_API_VERSION: &str = "7.4";
+
+/// Tag holding the SHA-256 of the imported source file
+const CONTENT_HASH_TAG: &str = "smc-content-sha256";
+
+/// Content type for PKCS#12 certificate bundles
+const PKCS12_CONTENT_TYPE: &str = "application/x-pkcs12";
+
+/// Content type for PEM certificate bundles
+const PEM_CONTENT_TYPE: &str = "application/x-pem-file";
+
+/// Certificate encoding, derived from the source file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateFormat {
+    /// PKCS#12 bundle (`.pfx`, `.p12`) - sent base64-encoded
+    Pkcs12,
+    /// PEM bundle (certificate + private key) - sent as text
+    Pem,
+}
+
+impl CertificateFormat {
+    /// Detect the certificate format from a file name
+    #[must_use]
+    pub fn from_file_name(file_name: &str) -> Self {
+        let lower = file_name.to_ascii_lowercase();
+        if lower.ends_with(".pfx") || lower.ends_with(".p12") {
+            Self::Pkcs12
+        } else {
+            Self::Pem
+        }
+    }
+
+    /// Content type Key Vault uses for the certificate's backing secret
+    #[must_use]
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Pkcs12 => PKCS12_CONTENT_TYPE,
+            Self::Pem => PEM_CONTENT_TYPE,
+        }
+    }
+}
+
+/// Azure Key Vault certificate provider
+pub struct AzureCertificateProvider {
+    http_client: ReqwestClient,
+    credential: Arc<dyn TokenCredential>,
+    vault_url: String,
+    policy: AzureCertificatesConfig,
+}
+
+impl std::fmt::Debug for AzureCertificateProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AzureCertificateProvider")
+            .field("vault_url", &self.vault_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AzureCertificateProvider {
+    /// Create a new certificate provider for the vault in `config`
+    /// # Errors
+    /// Returns an error if credential or HTTP client creation fails
+    pub fn new(config: &AzureConfig, k8s_client: &kube::Client) -> Result<Self> {
+        let vault_url = construct_vault_url(config);
+        let credential = create_credential(config, k8s_client)?;
+        let http_client = ReqwestClient::builder()
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            http_client,
+            credential,
+            vault_url,
+            policy: config.certificates.clone().unwrap_or_default(),
+        })
+    }
+
+    /// Import a certificate if its content changed since the last import
+    /// Returns true if a new certificate version was imported, false if unchanged
+    /// # Errors
+    /// Returns an error if the Key Vault API call fails
+    pub async fn import_certificate(
+        &self,
+        certificate_name: &str,
+        content: &[u8],
+        format: CertificateFormat,
+        environment: &str,
+    ) -> Result<bool> {
+        let start = Instant::now();
+        let content_hash = format!("{:x}", Sha256::digest(content));
+
+        if self
+            .current_content_hash(certificate_name)
+            .await?
+            .as_deref()
+            == Some(&content_hash)
+        {
+            debug!(
+                "Azure certificate {} unchanged, skipping import",
+                certificate_name
+            );
+            metrics::record_secret_operation("azure", "no_change", start.elapsed().as_secs_f64());
+            return Ok(false);
+        }
+
+        let value = match format {
+            CertificateFormat::Pkcs12 => general_purpose::STANDARD.encode(content),
+            CertificateFormat::Pem => {
+                String::from_utf8(content.to_vec()).context("PEM certificate is not valid UTF-8")?
+            }
+        };
+
+        let body = json!({
+            "value": value,
+            "policy": {
+                "key_props": {
+                    "exportable": self.policy.exportable,
+                    "reuse_key": self.policy.reuse_key,
+                },
+                "secret_props": {
+                    "contentType": format.content_type(),
+                },
+            },
+            "tags": {
+                "environment": environment,
+                CONTENT_HASH_TAG: content_hash,
+            },
+        });
+
+        info!(
+            provider = "azure",
+            certificate_name = certificate_name,
+            content_type = format.content_type(),
+            "Importing Azure Key Vault certificate: {}",
+            certificate_name
+        );
+
+        let url = format!(
+            "{}certificates/{}/import?api-version={}",
+            self.vault_url, certificate_name, CERTIFICATES_API_VERSION
+        );
+        let response = self
+            .http_client
+            .post(&url)
+            .header("Authorization", self.bearer_token().await?)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send_timed("azure", "import_certificate")
+            .await
+            .context("Failed to import Azure certificate")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("azure");
+            return Err(anyhow::anyhow!(
+                "Failed to import Azure certificate {}: HTTP {} - {}",
+                certificate_name,
+                status,
+                error_text
+            ));
+        }
+
+        metrics::record_secret_operation("azure", "import", start.elapsed().as_secs_f64());
+        Ok(true)
+    }
+
+    /// Read the content hash tag of the latest certificate version
+    /// Returns None if the certificate does not exist or was not imported by the controller
+    async fn current_content_hash(&self, certificate_name: &str) -> Result<Option<String>> {
+        let url = format!(
+            "{}certificates/{}?api-version={}",
+            self.vault_url, certificate_name, CERTIFICATES_API_VERSION
+        );
+        let response = self
+            .http_client
+            .get(&url)
+            .header("Authorization", self.bearer_token().await?)
+            .send_timed("azure", "get_certificate")
+            .await
+            .context("Failed to get Azure certificate")?;
+
+        if response.status() == 404 {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("azure");
+            return Err(anyhow::anyhow!(
+                "Failed to get Azure certificate {}: HTTP {} - {}",
+                certificate_name,
+                status,
+                error_text
+            ));
+        }
+
+        let bundle: serde_json::Value = response
+            .json()
+            .await
+            .context("Failed to parse Azure certificate response")?;
+        Ok(bundle
+            .get("tags")
+            .and_then(|t| t.get(CONTENT_HASH_TAG))
+            .and_then(|h| h.as_str())
+            .map(ToString::to_string))
+    }
+
+    /// Get an Authorization header value for the Key Vault scope
+    async fn bearer_token(&self) -> Result<String> {
+        let scope = &["https://vault.azure.net/.default"];
+        let token_response = self
+            .credential
+            .get_token(scope, Some(TokenRequestOptions::default()))
+            .await
+            .context("Failed to get Azure Key Vault access token")?;
+        Ok(format!("Bearer {}", token_response.token.secret()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CertificateFormat;
+
+    #[test]
+    fn test_certificate_format_from_file_name() {
+        assert_eq!(
+            CertificateFormat::from_file_name("ingress.pfx"),
+            CertificateFormat::Pkcs12
+        );
+        assert_eq!(
+            CertificateFormat::from_file_name("INGRESS.P12"),
+            CertificateFormat::Pkcs12
+        );
+        assert_eq!(
+            CertificateFormat::from_file_name("ingress.pem"),
+            CertificateFormat::Pem
+        );
+    }
+
+    #[test]
+    fn test_certificate_format_content_type() {
+        assert_eq!(
+            CertificateFormat::Pkcs12.content_type(),
+            "application/x-pkcs12"
+        );
+        assert_eq!(
+            CertificateFormat::Pem.content_type(),
+            "application/x-pem-file"
+        );
+    }
+}
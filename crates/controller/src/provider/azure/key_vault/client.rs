@@ -3,6 +3,7 @@
 //! Handles creation and initialization of Azure Key Vault client.
 
 use crate::crd::AzureConfig;
+use crate::provider::azure::cloud::AzureCloud;
 use anyhow::{Context, Result};
 use azure_core::credentials::TokenCredential;
 use azure_security_keyvault_secrets::SecretClient;
@@ -52,17 +53,18 @@ pub fn construct_vault_url(config: &AzureConfig) -> String {
         return endpoint;
     }
 
-    // Normal mode: use real Azure Key Vault
+    // Normal mode: use real Azure Key Vault in the configured cloud
     if config.vault_name.starts_with("https://") {
         config.vault_name.clone()
     } else {
-        format!("https://{}.vault.azure.net/", config.vault_name)
+        AzureCloud::for_environment(config.environment).vault_url(&config.vault_name)
     }
 }
 
 /// Create Azure Key Vault client components
 pub async fn create_client_components(
     config: &AzureConfig,
+    k8s_client: &kube::Client,
 ) -> Result<(
     SecretClient,
     ReqwestClient,
@@ -70,7 +72,7 @@ pub async fn create_client_components(
     String,
 )> {
     let vault_url = construct_vault_url(config);
-    let credential = create_credential(config)?;
+    let credential = create_credential(config, k8s_client)?;
 
     let client = SecretClient::new(&vault_url, credential.clone(), None)
         .context("Failed to create Azure Key Vault SecretClient")?;
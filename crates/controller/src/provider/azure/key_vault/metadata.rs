@@ -0,0 +1,207 @@
+//! # Secret Metadata
+//!
+//! Lists secrets and their versions through the Key Vault REST API. List responses
+//! carry attributes and tags but never values, so they back `list_secrets` and
+//! `get_secret_metadata` without reading secret values.
+//!
+//! References:
+//! - [Get secrets](https://learn.microsoft.com/en-us/rest/api/keyvault/secrets/get-secrets/get-secrets)
+//! - [Get secret versions](https://learn.microsoft.com/en-us/rest/api/keyvault/secrets/get-secret-versions/get-secret-versions)
+
+use crate::observability::metrics;
+use crate::provider::ProviderError;
+use anyhow::{Context, Result};
+use azure_core::credentials::TokenRequestOptions;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::AzureKeyVault;
+
+/// Page of `SecretItem`s returned by the list operations
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SecretListResult {
+    #[serde(default)]
+    pub value: Vec<SecretItem>,
+    /// Absolute URL of the next page, absent or null on the last page
+    #[serde(default)]
+    pub next_link: Option<String>,
+}
+
+/// Secret (or secret version) without its value
+#[derive(Debug, Deserialize)]
+pub(crate) struct SecretItem {
+    /// `https://{vault}.vault.azure.net/secrets/{name}[/{version}]`
+    pub id: String,
+    #[serde(default)]
+    pub attributes: Option<SecretItemAttributes>,
+    #[serde(default)]
+    pub tags: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SecretItemAttributes {
+    /// Creation time in seconds since the Unix epoch
+    #[serde(default)]
+    pub created: Option<i64>,
+    /// Expiry in seconds since the Unix epoch, if set
+    #[serde(default)]
+    pub exp: Option<i64>,
+}
+
+impl SecretItem {
+    /// Secret name from the item ID
+    pub fn secret_name(&self) -> Option<&str> {
+        let (_, path) = self.id.split_once("/secrets/")?;
+        path.split('/').next().filter(|name| !name.is_empty())
+    }
+
+    /// Version ID from a version item's ID (None for a secret item)
+    pub fn version_id(&self) -> Option<&str> {
+        let (_, path) = self.id.split_once("/secrets/")?;
+        path.split('/').nth(1).filter(|version| !version.is_empty())
+    }
+
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.attributes
+            .as_ref()
+            .and_then(|attributes| attributes.created)
+            .and_then(|created| DateTime::from_timestamp(created, 0))
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.attributes
+            .as_ref()
+            .and_then(|attributes| attributes.exp)
+            .and_then(|exp| DateTime::from_timestamp(exp, 0))
+    }
+}
+
+impl AzureKeyVault {
+    /// List all secrets in the vault, following `nextLink`
+    pub(crate) async fn list_secret_items(&self) -> Result<Vec<SecretItem>> {
+        let url = format!("{}secrets?api-version=7.4", self._vault_url);
+        Ok(self
+            .list_items(url, "list_secrets")
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// List all versions of a secret
+    /// Returns None if the secret doesn't exist
+    pub(crate) async fn list_version_items(
+        &self,
+        secret_name: &str,
+    ) -> Result<Option<Vec<SecretItem>>> {
+        let url = format!(
+            "{}secrets/{}/versions?api-version=7.4",
+            self._vault_url, secret_name
+        );
+        self.list_items(url, "list_secret_versions").await
+    }
+
+    async fn list_items(
+        &self,
+        first_page: String,
+        operation: &'static str,
+    ) -> Result<Option<Vec<SecretItem>>> {
+        let scope = &["https://vault.azure.net/.default"];
+        let token_response = self
+            .credential
+            .get_token(scope, Some(TokenRequestOptions::default()))
+            .await
+            .context("Failed to get Azure Key Vault access token")?;
+        let token = token_response.token.secret().to_string();
+
+        let mut items = Vec::new();
+        let mut next = Some(first_page);
+        while let Some(url) = next.take() {
+            let response = self
+                .pacer
+                .send(
+                    operation,
+                    self.http_client
+                        .get(&url)
+                        .header("Authorization", format!("Bearer {}", token)),
+                )
+                .await
+                .context(format!("Failed to {operation} in Azure Key Vault"))?;
+
+            let status = response.status();
+            if status == 404 {
+                return Ok(None);
+            }
+            if !status.is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                metrics::increment_provider_operation_errors("azure");
+                return Err(ProviderError::from_status(
+                    status.as_u16(),
+                    format!(
+                        "Failed to {operation} in Azure Key Vault: HTTP {status} - {error_text}"
+                    ),
+                )
+                .into());
+            }
+
+            let page: SecretListResult = response
+                .json()
+                .await
+                .context("Failed to parse Azure Key Vault list response")?;
+            items.extend(page.value);
+            // Stop if the service hands back the page we just fetched
+            next = page
+                .next_link
+                .filter(|link| !link.is_empty() && *link != url);
+        }
+        Ok(Some(items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secret_list_result() {
+        let page: SecretListResult = serde_json::from_str(
+            r#"{
+                "value": [{
+                    "id": "https://my-vault.vault.azure.net/secrets/db-password",
+                    "attributes": { "enabled": true, "created": 1700000000 },
+                    "tags": { "environment": "dev" }
+                }],
+                "nextLink": null
+            }"#,
+        )
+        .unwrap_or_else(|e| panic!("parse: {e}"));
+
+        let item = &page.value[0];
+        assert_eq!(item.secret_name(), Some("db-password"));
+        assert_eq!(item.version_id(), None);
+        assert_eq!(
+            item.created_at().map(|t| t.timestamp()),
+            Some(1_700_000_000)
+        );
+        assert_eq!(
+            item.tags
+                .as_ref()
+                .and_then(|t| t.get("environment"))
+                .map(String::as_str),
+            Some("dev")
+        );
+        assert!(page.next_link.is_none());
+    }
+
+    #[test]
+    fn test_secret_name_from_version_id() {
+        let item = SecretItem {
+            id: "https://my-vault.vault.azure.net/secrets/api-key/0123abcd".to_string(),
+            attributes: None,
+            tags: None,
+        };
+        assert_eq!(item.secret_name(), Some("api-key"));
+        assert_eq!(item.version_id(), Some("0123abcd"));
+        assert_eq!(item.created_at(), None);
+    }
+}
@@ -6,20 +6,33 @@
 //! - Create and update secrets in Azure Key Vault
 //! - Retrieve secret values
 //! - Manage secret versions
+//! - Import certificates into the Key Vault certificate store
+//! - Pace requests per vault and honor `Retry-After` on 429s
+//! - Check and create Key Vault Secrets User role assignments for consumers
 //! - Support Workload Identity and Service Principal authentication
 
 mod auth;
+mod certificates;
 mod client;
+mod metadata;
 mod operations;
+mod pacing;
 mod pact_api_override;
+mod role_assignments;
 
 pub use auth::MockTokenCredential;
 use azure_core::credentials::TokenCredential;
 use azure_security_keyvault_secrets::SecretClient;
+pub use certificates::{AzureCertificateProvider, CertificateFormat};
+pub use pacing::{VaultPacer, VaultPacers};
 use reqwest::Client as ReqwestClient;
+pub use role_assignments::{KeyVaultRoleAssignments, MissingRoleAssignment};
 use std::sync::Arc;
 
+use crate::controller::reconciler::rate_limit::RateLimit;
 use crate::crd::AzureConfig;
+use crate::observability::metrics;
+use crate::provider::ProviderError;
 use anyhow::Result;
 
 use self::client::create_client_components;
@@ -30,6 +43,7 @@ pub struct AzureKeyVault {
     pub(crate) _vault_url: String,
     pub(crate) http_client: ReqwestClient,
     pub(crate) credential: Arc<dyn TokenCredential>,
+    pub(crate) pacer: Arc<VaultPacer>,
 }
 
 impl std::fmt::Debug for AzureKeyVault {
@@ -42,7 +56,7 @@ impl std::fmt::Debug for AzureKeyVault {
 
 impl AzureKeyVault {
     /// Create a new Azure Key Vault client
-    /// Supports both Service Principal and Workload Identity
+    /// Supports Workload Identity, client certificates and Managed Identity
     /// # Errors
     /// Returns an error if Azure client initialization fails
     #[allow(
@@ -50,21 +64,72 @@ impl AzureKeyVault {
         clippy::unused_async,
         reason = "Error docs in comments, async signature matches trait"
     )]
-    pub async fn new(config: &AzureConfig, _k8s_client: &kube::Client) -> Result<Self> {
-        let (client, http_client, credential, vault_url) = create_client_components(config).await?;
+    pub async fn new(config: &AzureConfig, k8s_client: &kube::Client) -> Result<Self> {
+        let (client, http_client, credential, vault_url) =
+            create_client_components(config, k8s_client).await?;
 
         Ok(Self {
             client,
+            pacer: Arc::new(VaultPacer::new(&vault_url, None)),
             _vault_url: vault_url,
             http_client,
             credential,
         })
     }
+
+    /// Pace requests with the vault's shared pacer (see `VaultPacers`)
+    /// Without one, the client only honors 429s it receives itself.
+    #[must_use]
+    pub fn with_pacer(mut self, pacers: &VaultPacers, limit: Option<RateLimit>) -> Self {
+        self.pacer = pacers.pacer(&self._vault_url, limit);
+        self
+    }
+}
+
+/// Await a Key Vault SDK call, recording its duration in the provider API latency histogram
+/// (`secret_manager_provider_api_request_duration_seconds`)
+pub(crate) async fn timed_sdk_call<T>(
+    operation: &'static str,
+    call: impl std::future::Future<Output = azure_core::Result<azure_core::http::Response<T>>>,
+) -> azure_core::Result<azure_core::http::Response<T>> {
+    let start = std::time::Instant::now();
+    let result = call.await;
+    let status_code = match &result {
+        Ok(response) => u16::from(response.status()).to_string(),
+        Err(e) => e
+            .http_status()
+            .map_or_else(|| "error".to_string(), |s| u16::from(s).to_string()),
+    };
+    metrics::observe_provider_api_request(
+        "azure",
+        operation,
+        &status_code,
+        start.elapsed().as_secs_f64(),
+    );
+    result
+}
+
+/// Classify a Key Vault SDK error, keeping `message` as the error text
+///
+/// Errors without an HTTP response are transient when they came from the transport (I/O),
+/// permanent otherwise (credential and deserialization failures).
+pub(crate) fn classify_sdk_error(
+    error: &azure_core::Error,
+    message: impl Into<String>,
+) -> ProviderError {
+    let message = message.into();
+    match error.http_status() {
+        Some(status) => ProviderError::from_status(u16::from(status), message),
+        None if matches!(error.kind(), azure_core::error::ErrorKind::Io) => {
+            ProviderError::Transient(message)
+        }
+        None => ProviderError::Permanent(message),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::crd::{AzureAuthConfig, AzureConfig};
+    use crate::crd::{AzureAuthConfig, AzureConfig, AzureEnvironment};
 
     #[test]
     fn test_azure_config_workload_identity() {
@@ -73,12 +138,16 @@ mod tests {
             location: "eastus".to_string(),
             auth: Some(AzureAuthConfig::WorkloadIdentity {
                 client_id: "12345678-1234-1234-1234-123456789012".to_string(),
+                tenant_id: None,
             }),
+            certificates: None,
+            environment: AzureEnvironment::Public,
+            role_assignments: None,
         };
 
         assert_eq!(config.vault_name, "my-vault");
         match config.auth {
-            Some(AzureAuthConfig::WorkloadIdentity { client_id }) => {
+            Some(AzureAuthConfig::WorkloadIdentity { client_id, .. }) => {
                 assert_eq!(client_id, "12345678-1234-1234-1234-123456789012");
             }
             _ => panic!("Expected WorkloadIdentity auth config"),
@@ -91,6 +160,9 @@ mod tests {
             vault_name: "prod-vault".to_string(),
             location: "eastus".to_string(),
             auth: None,
+            certificates: None,
+            environment: AzureEnvironment::Public,
+            role_assignments: None,
         };
 
         assert_eq!(config.vault_name, "prod-vault");
@@ -104,6 +176,9 @@ mod tests {
             vault_name: "my-vault".to_string(),
             location: "eastus".to_string(),
             auth: None,
+            certificates: None,
+            environment: AzureEnvironment::Public,
+            role_assignments: None,
         };
         let expected_url = "https://my-vault.vault.azure.net/";
         // This would be tested in the new() method, but we can test the logic
@@ -119,6 +194,9 @@ mod tests {
             vault_name: "https://custom-vault.vault.azure.net/".to_string(),
             location: "eastus".to_string(),
             auth: None,
+            certificates: None,
+            environment: AzureEnvironment::Public,
+            role_assignments: None,
         };
         let vault_url2 = if config2.vault_name.starts_with("https://") {
             config2.vault_name.clone()
@@ -3,30 +3,38 @@
 //! Implements SecretManagerProvider trait for Azure Key Vault.
 
 use crate::observability::metrics;
-use crate::provider::SecretManagerProvider;
+use crate::provider::{ProviderError, SecretManagerProvider, SecretMetadata, SecretVersionIds};
+use crate::secret::SecretString;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use azure_core::credentials::TokenRequestOptions;
 use azure_security_keyvault_secrets::models::SetSecretParameters;
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
 use serde_json::json;
+use std::collections::HashMap;
 use std::time::Instant;
 use tracing::{Instrument, debug, info, info_span};
 
-use super::AzureKeyVault;
+use super::metadata::SecretItem;
+use super::{AzureKeyVault, classify_sdk_error};
+
+/// Content type set on secrets holding base64-encoded binary payloads
+const BINARY_CONTENT_TYPE: &str = "application/octet-stream";
 
 #[async_trait]
 impl SecretManagerProvider for AzureKeyVault {
     async fn create_or_update_secret(
         &self,
         secret_name: &str,
-        secret_value: &str,
+        secret_value: &SecretString,
         environment: &str,
         location: &str,
     ) -> Result<bool> {
         let vault_name = self
             ._vault_url
             .strip_prefix("https://")
-            .and_then(|s| s.strip_suffix(".vault.azure.net/"))
+            .and_then(|s| s.split('.').next())
             .unwrap_or("unknown");
         let span = info_span!(
             "azure.keyvault.secret.create_or_update",
@@ -35,7 +43,6 @@ impl SecretManagerProvider for AzureKeyVault {
         );
         let span_clone = span.clone();
         let start = Instant::now();
-        let secret_value_clone = secret_value.to_string();
 
         async move {
             // Check if secret exists by trying to get it
@@ -44,11 +51,11 @@ impl SecretManagerProvider for AzureKeyVault {
             let vault_name = self
                 ._vault_url
                 .strip_prefix("https://")
-                .and_then(|s| s.strip_suffix(".vault.azure.net/"))
+                .and_then(|s| s.split('.').next())
                 .unwrap_or("unknown");
 
             let operation_type = if let Some(current) = current_value {
-                if current == secret_value_clone {
+                if current == *secret_value {
                     debug!(
                         provider = "azure",
                         vault_name = vault_name,
@@ -90,13 +97,17 @@ impl SecretManagerProvider for AzureKeyVault {
             tags.insert("location".to_string(), location.to_string());
 
             let parameters = SetSecretParameters {
-                value: Some(secret_value_clone),
+                value: Some(secret_value.expose_secret().to_string()),
                 tags: Some(tags),
                 ..Default::default()
             };
             match self
-                .client
-                .set_secret(secret_name, parameters.try_into()?, None)
+                .pacer
+                .sdk_call(
+                    "set_secret",
+                    self.client
+                        .set_secret(secret_name, parameters.try_into()?, None),
+                )
                 .await
             {
                 Ok(_) => {
@@ -117,9 +128,11 @@ impl SecretManagerProvider for AzureKeyVault {
                     span_clone.record("error.message", error_msg.clone());
                     span_clone.record("operation.duration_ms", start.elapsed().as_millis() as u64);
                     metrics::increment_provider_operation_errors("azure");
-                    Err(anyhow::anyhow!(
-                        "Failed to create/update Azure secret {secret_name}: {e}"
-                    ))
+                    Err(classify_sdk_error(
+                        &e,
+                        format!("Failed to create/update Azure secret {secret_name}: {e}"),
+                    )
+                    .into())
                 }
             }
         }
@@ -127,11 +140,102 @@ impl SecretManagerProvider for AzureKeyVault {
         .await
     }
 
-    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<String>> {
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        let span = info_span!(
+            "azure.keyvault.secret.create_or_update_binary",
+            secret.name = secret_name
+        );
+        let span_clone = span.clone();
+        let start = Instant::now();
+        // Key Vault secrets are strings - binary payloads are stored base64-encoded and
+        // flagged via contentType so consumers know to decode them
+        let encoded = general_purpose::STANDARD.encode(secret_value);
+
+        async move {
+            let current_value = self.get_secret_value(secret_name).await?;
+            let operation_type = match current_value {
+                Some(ref current) if current.expose_secret() == encoded => {
+                    metrics::record_secret_operation(
+                        "azure",
+                        "no_change",
+                        start.elapsed().as_secs_f64(),
+                    );
+                    span_clone.record("operation.type", "no_change");
+                    span_clone.record("operation.duration_ms", start.elapsed().as_millis() as u64);
+                    span_clone.record("operation.success", true);
+                    return Ok(false);
+                }
+                Some(_) => "update",
+                None => "create",
+            };
+
+            info!(
+                provider = "azure",
+                secret_name = secret_name,
+                operation = operation_type,
+                "Creating/updating binary Azure secret: secret={}, operation={}",
+                secret_name,
+                operation_type
+            );
+            let mut tags = std::collections::HashMap::new();
+            tags.insert("environment".to_string(), environment.to_string());
+            tags.insert("location".to_string(), location.to_string());
+
+            let parameters = SetSecretParameters {
+                value: Some(encoded),
+                content_type: Some(BINARY_CONTENT_TYPE.to_string()),
+                tags: Some(tags),
+                ..Default::default()
+            };
+            match self
+                .pacer
+                .sdk_call(
+                    "set_secret",
+                    self.client
+                        .set_secret(secret_name, parameters.try_into()?, None),
+                )
+                .await
+            {
+                Ok(_) => {
+                    metrics::record_secret_operation(
+                        "azure",
+                        operation_type,
+                        start.elapsed().as_secs_f64(),
+                    );
+                    span_clone.record("operation.type", operation_type);
+                    span_clone.record("operation.duration_ms", start.elapsed().as_millis() as u64);
+                    span_clone.record("operation.success", true);
+                    Ok(true)
+                }
+                Err(e) => {
+                    span_clone.record("operation.success", false);
+                    span_clone.record("operation.type", operation_type);
+                    span_clone.record("error.message", e.to_string());
+                    span_clone.record("operation.duration_ms", start.elapsed().as_millis() as u64);
+                    metrics::increment_provider_operation_errors("azure");
+                    Err(classify_sdk_error(
+                        &e,
+                        format!("Failed to create/update binary Azure secret {secret_name}: {e}"),
+                    )
+                    .into())
+                }
+            }
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
         let vault_name = self
             ._vault_url
             .strip_prefix("https://")
-            .and_then(|s| s.strip_suffix(".vault.azure.net/"))
+            .and_then(|s| s.split('.').next())
             .unwrap_or("unknown");
         let span = tracing::debug_span!(
             "azure.keyvault.secret.get",
@@ -143,7 +247,11 @@ impl SecretManagerProvider for AzureKeyVault {
 
         async move {
             // Get the latest version of the secret (no version parameter needed - defaults to latest)
-            match self.client.get_secret(secret_name, None).await {
+            match self
+                .pacer
+                .sdk_call("get_secret", self.client.get_secret(secret_name, None))
+                .await
+            {
                 Ok(response) => {
                     // Response body needs to be deserialized into the Secret model
                     use azure_security_keyvault_secrets::models::Secret;
@@ -160,7 +268,7 @@ impl SecretManagerProvider for AzureKeyVault {
                                 "get",
                                 start.elapsed().as_secs_f64(),
                             );
-                            Ok(secret.value)
+                            Ok(secret.value.map(SecretString::new))
                         }
                         Err(e) => {
                             let error_msg = e.to_string();
@@ -185,12 +293,10 @@ impl SecretManagerProvider for AzureKeyVault {
                 }
                 Err(e) => {
                     let error_msg = e.to_string();
+                    let error = classify_sdk_error(&e, format!("Failed to get Azure secret: {e}"));
                     // Treat missing, disabled, or not found secrets as Ok(None) so controller can create them
-                    if error_msg.contains("SecretNotFound")
-                        || error_msg.contains("404")
-                        || error_msg.contains("not found")
-                        || error_msg.contains("disabled")
-                        || error_msg.contains("is disabled")
+                    // (Key Vault reports reads of disabled secrets as 403 with a "disabled" message)
+                    if matches!(error, ProviderError::NotFound(_)) || error_msg.contains("disabled")
                     {
                         span_clone.record("operation.success", true);
                         span_clone.record("operation.found", false);
@@ -208,7 +314,7 @@ impl SecretManagerProvider for AzureKeyVault {
                         span_clone
                             .record("operation.duration_ms", start.elapsed().as_millis() as u64);
                         metrics::increment_provider_operation_errors("azure");
-                        Err(anyhow::anyhow!("Failed to get Azure secret: {e}"))
+                        Err(error.into())
                     }
                 }
             }
@@ -219,8 +325,11 @@ impl SecretManagerProvider for AzureKeyVault {
 
     async fn delete_secret(&self, secret_name: &str) -> Result<()> {
         info!("Deleting Azure secret: {}", secret_name);
-        self.client
-            .delete_secret(secret_name, None)
+        self.pacer
+            .sdk_call(
+                "delete_secret",
+                self.client.delete_secret(secret_name, None),
+            )
             .await
             .context(format!("Failed to delete Azure secret: {secret_name}"))?;
         Ok(())
@@ -253,12 +362,15 @@ impl SecretManagerProvider for AzureKeyVault {
         });
 
         let response = self
-            .http_client
-            .patch(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+            .pacer
+            .send(
+                "disable_secret",
+                self.http_client
+                    .patch(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
             .await
             .context("Failed to disable Azure secret")?;
 
@@ -272,12 +384,13 @@ impl SecretManagerProvider for AzureKeyVault {
                 return Ok(false);
             }
 
-            return Err(anyhow::anyhow!(
-                "Failed to disable Azure secret {}: HTTP {} - {}",
-                secret_name,
-                status,
-                error_text
-            ));
+            return Err(ProviderError::from_status(
+                status.as_u16(),
+                format!(
+                    "Failed to disable Azure secret {secret_name}: HTTP {status} - {error_text}"
+                ),
+            )
+            .into());
         }
 
         Ok(true)
@@ -310,12 +423,15 @@ impl SecretManagerProvider for AzureKeyVault {
         });
 
         let response = self
-            .http_client
-            .patch(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+            .pacer
+            .send(
+                "enable_secret",
+                self.http_client
+                    .patch(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&body),
+            )
             .await
             .context("Failed to enable Azure secret")?;
 
@@ -329,14 +445,301 @@ impl SecretManagerProvider for AzureKeyVault {
                 return Ok(false);
             }
 
+            return Err(ProviderError::from_status(
+                status.as_u16(),
+                format!(
+                    "Failed to enable Azure secret {secret_name}: HTTP {status} - {error_text}"
+                ),
+            )
+            .into());
+        }
+
+        Ok(true)
+    }
+
+    async fn set_secret_labels(
+        &self,
+        secret_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        // Updating a secret without a version updates its current version, whose tags are
+        // replaced as a whole, so merge into the newest version's tags first
+        let Some(metadata) = self.get_secret_metadata(secret_name).await? else {
             return Err(anyhow::anyhow!(
-                "Failed to enable Azure secret {}: HTTP {} - {}",
-                secret_name,
-                status,
-                error_text
+                "Failed to tag Azure secret {secret_name}: secret not found"
             ));
+        };
+        if labels
+            .iter()
+            .all(|(k, v)| metadata.labels.get(k) == Some(v))
+        {
+            return Ok(());
         }
+        let mut tags = metadata.labels;
+        tags.extend(labels.iter().map(|(k, v)| (k.clone(), v.clone())));
 
-        Ok(true)
+        let scope = &["https://vault.azure.net/.default"];
+        let token_response = self
+            .credential
+            .get_token(scope, Some(TokenRequestOptions::default()))
+            .await
+            .context("Failed to get Azure Key Vault access token")?;
+        let token = token_response.token.secret().to_string();
+
+        let url = format!("{}secrets/{}?api-version=7.4", self._vault_url, secret_name);
+        let response = self
+            .pacer
+            .send(
+                "update_secret_tags",
+                self.http_client
+                    .patch(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&json!({ "tags": tags })),
+            )
+            .await
+            .context("Failed to tag Azure secret")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("azure");
+            return Err(ProviderError::from_status(
+                status.as_u16(),
+                format!("Failed to tag Azure secret {secret_name}: HTTP {status} - {error_text}"),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    async fn set_secret_expiration(
+        &self,
+        secret_name: &str,
+        expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        // Updating a secret without a version sets `exp` on its current version; new versions
+        // start without one, so the expiry is set again after each write
+        let scope = &["https://vault.azure.net/.default"];
+        let token_response = self
+            .credential
+            .get_token(scope, Some(TokenRequestOptions::default()))
+            .await
+            .context("Failed to get Azure Key Vault access token")?;
+        let token = token_response.token.secret().to_string();
+
+        let url = format!("{}secrets/{}?api-version=7.4", self._vault_url, secret_name);
+        let response = self
+            .pacer
+            .send(
+                "update_secret_expiration",
+                self.http_client
+                    .patch(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&json!({ "attributes": { "exp": expire_time.timestamp() } })),
+            )
+            .await
+            .context("Failed to set expiration of Azure secret")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("azure");
+            return Err(ProviderError::from_status(
+                status.as_u16(),
+                format!(
+                    "Failed to set expiration of Azure secret {secret_name}: HTTP {status} - {error_text}"
+                ),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .list_secret_items()
+            .await?
+            .iter()
+            .filter_map(|item| item.secret_name())
+            .filter(|name| name.starts_with(prefix))
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        let Some(versions) = self.list_version_items(secret_name).await? else {
+            return Ok(None);
+        };
+        // Tags and expiry live on versions; the secret's are those of its newest version
+        let created = versions.iter().filter_map(|version| version.created_at());
+        let newest = versions.iter().max_by_key(|version| version.created_at());
+        let labels = newest
+            .and_then(|version| version.tags.clone())
+            .unwrap_or_default();
+        Ok(Some(SecretMetadata {
+            name: secret_name.to_string(),
+            labels,
+            create_time: created.min(),
+            version_count: versions.len(),
+            expire_time: newest.and_then(|version| version.expires_at()),
+        }))
+    }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        // Version IDs are opaque; order versions by creation time, newest first
+        let mut versions = self
+            .list_version_items(secret_name)
+            .await?
+            .unwrap_or_default();
+        versions.sort_by_key(|version| std::cmp::Reverse(version.created_at()));
+        let mut ids = versions.iter().filter_map(SecretItem::version_id);
+        Ok(SecretVersionIds {
+            current: ids.next().map(str::to_string),
+            previous: ids.next().map(str::to_string),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AuditConfig;
+    use crate::observability::audit::{
+        AuditAction, AuditContext, AuditEvent, AuditLog, AuditingProvider, Auditor,
+    };
+    use crate::provider::azure::key_vault::{MockTokenCredential, VaultPacer};
+    use azure_security_keyvault_secrets::SecretClient;
+    use pact_consumer::prelude::*;
+    use std::sync::Arc;
+
+    fn key_vault(vault_url: &str) -> AzureKeyVault {
+        let credential: Arc<dyn azure_core::credentials::TokenCredential> =
+            Arc::new(MockTokenCredential);
+        AzureKeyVault {
+            client: SecretClient::new(vault_url, credential.clone(), None)
+                .unwrap_or_else(|e| panic!("SecretClient: {e}")),
+            _vault_url: vault_url.to_string(),
+            http_client: reqwest::Client::new(),
+            credential,
+            pacer: Arc::new(VaultPacer::new(vault_url, None)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_event_records_version_ids() {
+        let mut pact_builder = PactBuilder::new("Secret-Manager-Controller", "Azure-Key-Vault");
+        pact_builder
+            .interaction("get current value before audited update", "", |mut i| {
+                i.given("the secret exists with two versions");
+                i.request
+                    .method("GET")
+                    .path("/secrets/api-key/")
+                    .header("authorization", "Bearer test-token")
+                    .query_param("api-version", "2025-07-01");
+                i.response
+                    .status(200)
+                    .header("content-type", "application/json")
+                    .json_body(json!({
+                        "value": "old-value",
+                        "id": "https://test-vault.vault.azure.net/secrets/api-key/v1",
+                        "attributes": { "enabled": true, "created": 1704067200 }
+                    }));
+                i
+            })
+            .interaction("set value for audited update", "", |mut i| {
+                i.given("the secret exists with two versions");
+                i.request
+                    .method("PUT")
+                    .path("/secrets/api-key")
+                    .header("authorization", "Bearer test-token")
+                    .query_param("api-version", "2025-07-01")
+                    .json_body(json!({
+                        "value": "new-value",
+                        "tags": { "environment": "test", "location": "eastus" }
+                    }));
+                i.response
+                    .status(200)
+                    .header("content-type", "application/json")
+                    .json_body(json!({
+                        "value": "new-value",
+                        "id": "https://test-vault.vault.azure.net/secrets/api-key/v2",
+                        "attributes": { "enabled": true, "created": 1704070800 }
+                    }));
+                i
+            })
+            .interaction("list versions after audited update", "", |mut i| {
+                i.given("the secret exists with two versions");
+                i.request
+                    .method("GET")
+                    .path("/secrets/api-key/versions")
+                    .header("authorization", "Bearer test-token")
+                    .query_param("api-version", "7.4");
+                // Oldest version first: the current version is picked by creation time, not position
+                i.response
+                    .status(200)
+                    .header("content-type", "application/json")
+                    .json_body(json!({
+                        "value": [
+                            {
+                                "id": "https://test-vault.vault.azure.net/secrets/api-key/v1",
+                                "attributes": { "enabled": true, "created": 1704067200 }
+                            },
+                            {
+                                "id": "https://test-vault.vault.azure.net/secrets/api-key/v2",
+                                "attributes": { "enabled": true, "created": 1704070800 }
+                            }
+                        ],
+                        "nextLink": null
+                    }));
+                i
+            });
+        let mock_server = pact_builder.start_mock_server(None, None);
+        let vault_url = format!("{}/", mock_server.url().as_str().trim_end_matches('/'));
+
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir: {e}"));
+        let path = dir.path().join("audit.jsonl");
+        let log = AuditLog::from_config(&AuditConfig {
+            sink: "file".to_string(),
+            file_path: path.display().to_string(),
+            http_endpoint: None,
+        })
+        .unwrap_or_else(|e| panic!("from_config: {e}"));
+        let context = AuditContext {
+            resource: "default/app".to_string(),
+            provider: "azure".to_string(),
+            trigger_source: "timer-or-event".to_string(),
+            existing_secrets: ["api-key".to_string()].into(),
+            ..AuditContext::default()
+        };
+        let provider = AuditingProvider::new(
+            Box::new(key_vault(&vault_url)),
+            Auditor::new(Arc::new(log), context),
+        );
+
+        let changed = provider
+            .create_or_update_secret(
+                "api-key",
+                &SecretString::from("new-value"),
+                "test",
+                "eastus",
+            )
+            .await
+            .unwrap_or_else(|e| panic!("create_or_update_secret: {e}"));
+        assert!(changed);
+
+        let events: Vec<AuditEvent> = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("read audit file: {e}"))
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap_or_else(|e| panic!("parse event: {e}")))
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, AuditAction::Update);
+        assert_eq!(events[0].provider, "azure");
+        assert_eq!(events[0].new_version.as_deref(), Some("v2"));
+        assert_eq!(events[0].old_version.as_deref(), Some("v1"));
     }
 }
@@ -0,0 +1,212 @@
+//! # Vault Pacing
+//!
+//! Keeps requests to one Azure Key Vault under its transaction limit.
+//!
+//! Key Vault throttles per vault, not per resource: several `SecretManagerConfig`s writing to
+//! the same vault share its budget, and a large resource can get every other resource's
+//! requests rejected with 429. All clients of a vault share one [`VaultPacer`], which
+//! - spaces requests with a token bucket (`AZURE_VAULT_RATE_LIMIT_RPS` /
+//!   `AZURE_VAULT_RATE_LIMIT_BURST`), and
+//! - on a 429, pauses every request to the vault for the `Retry-After` the vault sent, then
+//!   retries the rejected REST request.
+//!
+//! SDK calls retry 429s themselves. The `azure_core` error of a 429 the SDK gives up on does not
+//! carry the response headers, so that 429 pauses the vault for the default pause, not for the
+//! vault's `Retry-After`. Each 429 increments `secret_manager_vault_throttled_total{vault}`.
+
+use crate::controller::reconciler::rate_limit::{RateLimit, TokenBucket};
+use crate::observability::metrics;
+use crate::provider::common::TimedSend;
+use crate::provider::error::parse_retry_after;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use super::timed_sdk_call;
+
+/// Retries of a REST request rejected with 429
+const MAX_THROTTLE_RETRIES: u32 = 3;
+
+/// Pause after a 429 without `Retry-After`
+const DEFAULT_THROTTLE_PAUSE: Duration = Duration::from_secs(1);
+
+/// Longest pause honored from `Retry-After`
+const MAX_THROTTLE_PAUSE: Duration = Duration::from_secs(60);
+
+/// Request pacing shared by all clients of one vault
+#[derive(Debug)]
+pub struct VaultPacer {
+    vault: String,
+    limit: Option<RateLimit>,
+    bucket: Option<TokenBucket>,
+    paused_until: Mutex<Option<Instant>>,
+}
+
+impl VaultPacer {
+    /// Pacer for `vault`; `None` only honors 429s
+    #[must_use]
+    pub fn new(vault: &str, limit: Option<RateLimit>) -> Self {
+        Self {
+            vault: vault.to_string(),
+            limit,
+            bucket: limit.map(TokenBucket::new),
+            paused_until: Mutex::new(None),
+        }
+    }
+
+    fn remaining_pause(&self) -> Option<Duration> {
+        let paused_until = *self
+            .paused_until
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        paused_until.and_then(|until| until.checked_duration_since(Instant::now()))
+    }
+
+    /// Wait out a pause after a 429, then for a token
+    pub async fn acquire(&self) {
+        while let Some(pause) = self.remaining_pause() {
+            tokio::time::sleep(pause).await;
+        }
+        if let Some(bucket) = &self.bucket {
+            bucket.acquire().await;
+        }
+    }
+
+    /// Record a 429 and pause the vault for `retry_after` (capped); returns the pause
+    pub fn throttled(&self, retry_after: Option<Duration>) -> Duration {
+        metrics::increment_vault_throttled(&self.vault);
+        let pause = retry_after
+            .unwrap_or(DEFAULT_THROTTLE_PAUSE)
+            .min(MAX_THROTTLE_PAUSE);
+        let until = Instant::now() + pause;
+        let mut paused_until = self
+            .paused_until
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if paused_until.is_none_or(|current| current < until) {
+            *paused_until = Some(until);
+        }
+        debug!(
+            "Azure Key Vault {} throttled the controller, pausing requests for {}ms",
+            self.vault,
+            pause.as_millis()
+        );
+        pause
+    }
+
+    /// Send a REST request once the vault allows it, retrying 429s after `Retry-After`
+    /// The last response is returned as-is, so a request still throttled after the retries
+    /// fails with a 429 status.
+    pub async fn send(
+        &self,
+        operation: &'static str,
+        request: reqwest::RequestBuilder,
+    ) -> reqwest::Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            self.acquire().await;
+            // Requests with a streaming body can't be retried
+            let Some(retry) = request.try_clone() else {
+                return request.send_timed("azure", operation).await;
+            };
+            let response = retry.send_timed("azure", operation).await?;
+            if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+            self.throttled(parse_retry_after(
+                response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok()),
+            ));
+            if attempt == MAX_THROTTLE_RETRIES {
+                return Ok(response);
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Run a Key Vault SDK call once the vault allows it
+    pub async fn sdk_call<T>(
+        &self,
+        operation: &'static str,
+        call: impl std::future::Future<Output = azure_core::Result<azure_core::http::Response<T>>>,
+    ) -> azure_core::Result<azure_core::http::Response<T>> {
+        self.acquire().await;
+        let result = timed_sdk_call(operation, call).await;
+        if let Err(e) = &result
+            && e.http_status().map(u16::from) == Some(429)
+        {
+            // The error carries no headers to read `Retry-After` from
+            self.throttled(None);
+        }
+        result
+    }
+}
+
+/// Pacers per vault URL, shared by every resource's clients
+#[derive(Debug, Default)]
+pub struct VaultPacers {
+    pacers: Mutex<HashMap<String, Arc<VaultPacer>>>,
+}
+
+impl VaultPacers {
+    /// Pacer of `vault_url`, replaced when the configured limit changes
+    pub fn pacer(&self, vault_url: &str, limit: Option<RateLimit>) -> Arc<VaultPacer> {
+        let mut pacers = self.pacers.lock().unwrap_or_else(PoisonError::into_inner);
+        let pacer = pacers
+            .entry(vault_url.to_string())
+            .and_modify(|pacer| {
+                if pacer.limit != limit {
+                    *pacer = Arc::new(VaultPacer::new(vault_url, limit));
+                }
+            })
+            .or_insert_with(|| Arc::new(VaultPacer::new(vault_url, limit)));
+        Arc::clone(pacer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pacers_shared_per_vault() {
+        let pacers = VaultPacers::default();
+        let limit = RateLimit::new(10.0, 5);
+        let a = pacers.pacer("https://a.vault.azure.net/", limit);
+        assert!(Arc::ptr_eq(
+            &a,
+            &pacers.pacer("https://a.vault.azure.net/", limit)
+        ));
+        assert!(!Arc::ptr_eq(
+            &a,
+            &pacers.pacer("https://b.vault.azure.net/", limit)
+        ));
+        // A new limit replaces the pacer
+        assert!(!Arc::ptr_eq(
+            &a,
+            &pacers.pacer("https://a.vault.azure.net/", RateLimit::new(20.0, 5))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_throttle_pauses_vault() {
+        let pacer = VaultPacer::new("https://a.vault.azure.net/", None);
+        assert!(pacer.remaining_pause().is_none());
+
+        assert_eq!(
+            pacer.throttled(Some(Duration::from_secs(3600))),
+            MAX_THROTTLE_PAUSE
+        );
+        assert!(pacer.remaining_pause().is_some());
+        // A shorter Retry-After doesn't cut the pause short
+        pacer.throttled(Some(Duration::from_millis(10)));
+        assert!(
+            pacer
+                .remaining_pause()
+                .is_some_and(|pause| pause > Duration::from_secs(30))
+        );
+    }
+}
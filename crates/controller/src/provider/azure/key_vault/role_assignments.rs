@@ -0,0 +1,426 @@
+//! # Azure Key Vault Role Assignments
+//!
+//! Checks, and with `create: true` creates, Key Vault Secrets User role assignments for the
+//! principals in `provider.azure.roleAssignments`, so consumers missing access show up on the
+//! resource instead of as 403s at runtime.
+//!
+//! Assignments are read and written through Azure Resource Manager. A principal has access to
+//! a scope (the vault, or `{vault}/secrets/{name}`) when it has an assignment of a role that
+//! can read secret values (Key Vault Secrets User, Secrets Officer or Administrator) at that
+//! scope or above it. Group memberships aren't resolved. Created assignments are named after
+//! their scope, principal and role, so a retried create writes the same assignment.
+//!
+//! Reference: https://learn.microsoft.com/en-us/azure/key-vault/general/rbac-guide
+
+use crate::crd::{
+    AzureConfig, AzurePrincipalType, AzureRoleAssignmentScope, AzureRoleAssignmentsConfig,
+};
+use crate::provider::azure::cloud::AzureCloud;
+use crate::provider::common::TimedSend;
+use anyhow::{Context, Result};
+use azure_core::credentials::{TokenCredential, TokenRequestOptions};
+use reqwest::Client as ReqwestClient;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+use super::auth::create_credential;
+
+/// Role assignments API version
+const API_VERSION: &str = "2022-04-01";
+
+/// Key Vault Secrets User (read secret values)
+pub const KEY_VAULT_SECRETS_USER_ROLE: &str = "4633458b-17de-408a-b874-0445c86b69e6";
+
+/// Built-in roles that can read secret values: Secrets User, Secrets Officer, Administrator
+const SECRET_READER_ROLES: [&str; 3] = [
+    KEY_VAULT_SECRETS_USER_ROLE,
+    "b86a8fe4-44ce-4948-aee5-eccb2c155cd7",
+    "00482a5a-887f-4fb3-b363-3b7fe8e74483",
+];
+
+/// A principal without read access to a scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingRoleAssignment {
+    pub principal_id: String,
+    /// `vault` or `secret {name}`
+    pub scope: String,
+    /// Why the assignment couldn't be created (`create: true` only)
+    pub error: Option<String>,
+}
+
+impl fmt::Display for MissingRoleAssignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} has no Key Vault Secrets User role on {}",
+            self.principal_id, self.scope
+        )?;
+        if let Some(error) = &self.error {
+            write!(f, " ({error})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Role assignment as returned by Azure Resource Manager
+#[derive(Debug, Deserialize)]
+struct RoleAssignment {
+    properties: RoleAssignmentProperties,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RoleAssignmentProperties {
+    role_definition_id: String,
+    scope: String,
+}
+
+/// Page of role assignments
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RoleAssignmentList {
+    #[serde(default)]
+    value: Vec<RoleAssignment>,
+    #[serde(default)]
+    next_link: Option<String>,
+}
+
+/// Role assignment client for one vault
+pub struct KeyVaultRoleAssignments {
+    http_client: ReqwestClient,
+    credential: Arc<dyn TokenCredential>,
+    cloud: &'static AzureCloud,
+    subscription_id: String,
+    vault_scope: String,
+}
+
+impl KeyVaultRoleAssignments {
+    /// Create a role assignment client for the vault of `config`
+    /// # Errors
+    /// Returns an error if the credential or HTTP client can't be created
+    pub fn new(
+        config: &AzureConfig,
+        assignments: &AzureRoleAssignmentsConfig,
+        k8s_client: &kube::Client,
+    ) -> Result<Self> {
+        let credential = create_credential(config, k8s_client)?;
+        let http_client = ReqwestClient::builder()
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(Self {
+            http_client,
+            credential,
+            cloud: AzureCloud::for_environment(config.environment),
+            subscription_id: assignments.subscription_id.clone(),
+            vault_scope: vault_scope(
+                &assignments.subscription_id,
+                &assignments.resource_group,
+                &config.vault_name,
+            ),
+        })
+    }
+
+    /// Check every principal's access to its scopes, creating missing assignments when
+    /// `assignments.create` is set
+    /// `secret_names` are the Key Vault secrets synced by the resource (for `scope: Secrets`).
+    /// # Errors
+    /// Returns an error if assignments can't be read (e.g. the controller lacks
+    /// `Microsoft.Authorization/roleAssignments/read`)
+    pub async fn reconcile(
+        &self,
+        assignments: &AzureRoleAssignmentsConfig,
+        secret_names: &[String],
+    ) -> Result<Vec<MissingRoleAssignment>> {
+        let token = self.token().await?;
+        let mut missing = Vec::new();
+
+        for principal in &assignments.principals {
+            let scopes: Vec<(String, String)> = match principal.scope {
+                AzureRoleAssignmentScope::Vault => {
+                    vec![(self.vault_scope.clone(), "vault".to_string())]
+                }
+                AzureRoleAssignmentScope::Secrets => secret_names
+                    .iter()
+                    .map(|name| {
+                        (
+                            format!("{}/secrets/{name}", self.vault_scope),
+                            format!("secret {name}"),
+                        )
+                    })
+                    .collect(),
+            };
+
+            for (scope, label) in scopes {
+                if self
+                    .has_access(&token, &scope, &principal.principal_id)
+                    .await?
+                {
+                    continue;
+                }
+                if !assignments.create {
+                    missing.push(MissingRoleAssignment {
+                        principal_id: principal.principal_id.clone(),
+                        scope: label,
+                        error: None,
+                    });
+                    continue;
+                }
+
+                match self
+                    .create(
+                        &token,
+                        &scope,
+                        &principal.principal_id,
+                        principal.principal_type,
+                    )
+                    .await
+                {
+                    Ok(()) => info!(
+                        "Assigned Key Vault Secrets User to {} on {}",
+                        principal.principal_id, label
+                    ),
+                    Err(e) => missing.push(MissingRoleAssignment {
+                        principal_id: principal.principal_id.clone(),
+                        scope: label,
+                        error: Some(e.to_string()),
+                    }),
+                }
+            }
+        }
+
+        Ok(missing)
+    }
+
+    async fn token(&self) -> Result<String> {
+        let scope = self.cloud.resource_manager_scope();
+        let token = self
+            .credential
+            .get_token(&[scope.as_str()], Some(TokenRequestOptions::default()))
+            .await
+            .context("Failed to get Azure Resource Manager access token")?;
+        Ok(token.token.secret().to_string())
+    }
+
+    /// Whether `principal_id` can read secrets at `scope`, through an assignment at or above it
+    async fn has_access(&self, token: &str, scope: &str, principal_id: &str) -> Result<bool> {
+        let filter = format!("assignedTo('{principal_id}')");
+        let mut request = self
+            .http_client
+            .get(format!(
+                "{}{scope}/providers/Microsoft.Authorization/roleAssignments",
+                self.cloud.resource_manager_endpoint
+            ))
+            .query(&[("api-version", API_VERSION), ("$filter", filter.as_str())]);
+
+        loop {
+            let response = request
+                .bearer_auth(token)
+                .send_timed("azure", "list_role_assignments")
+                .await
+                .context("Failed to list Key Vault role assignments")?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(anyhow::anyhow!(
+                    "Failed to list role assignments on {scope}: {status} - {error_text}"
+                ));
+            }
+
+            let page: RoleAssignmentList = response
+                .json()
+                .await
+                .context("Failed to deserialize role assignments")?;
+            if page
+                .value
+                .iter()
+                .any(|assignment| grants_secret_read(&assignment.properties, scope))
+            {
+                return Ok(true);
+            }
+
+            match page.next_link.filter(|link| !link.is_empty()) {
+                Some(link) => request = self.http_client.get(link),
+                None => break,
+            }
+        }
+
+        debug!("{} has no secret read role on {}", principal_id, scope);
+        Ok(false)
+    }
+
+    /// Assign Key Vault Secrets User to `principal_id` at `scope`
+    async fn create(
+        &self,
+        token: &str,
+        scope: &str,
+        principal_id: &str,
+        principal_type: AzurePrincipalType,
+    ) -> Result<()> {
+        let url = format!(
+            "{}{scope}/providers/Microsoft.Authorization/roleAssignments/{}",
+            self.cloud.resource_manager_endpoint,
+            assignment_name(scope, principal_id)
+        );
+        let body = serde_json::json!({
+            "properties": {
+                "roleDefinitionId": format!(
+                    "/subscriptions/{}/providers/Microsoft.Authorization/roleDefinitions/{KEY_VAULT_SECRETS_USER_ROLE}",
+                    self.subscription_id
+                ),
+                "principalId": principal_id,
+                "principalType": principal_type_name(principal_type),
+            }
+        });
+
+        let response = self
+            .http_client
+            .put(&url)
+            .query(&[("api-version", API_VERSION)])
+            .bearer_auth(token)
+            .json(&body)
+            .send_timed("azure", "create_role_assignment")
+            .await
+            .context("Failed to create Key Vault role assignment")?;
+
+        // 409 RoleAssignmentExists: the same assignment exists under another name
+        if response.status().is_success() || response.status() == 409 {
+            return Ok(());
+        }
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        Err(anyhow::anyhow!(
+            "failed to create role assignment: {status} - {error_text}"
+        ))
+    }
+}
+
+/// Resource ID of a vault, the scope of vault-wide assignments
+/// `vault_name` may also be a vault URL.
+#[must_use]
+pub fn vault_scope(subscription_id: &str, resource_group: &str, vault_name: &str) -> String {
+    let name = vault_name
+        .strip_prefix("https://")
+        .and_then(|host| host.split('.').next())
+        .unwrap_or(vault_name);
+    format!(
+        "/subscriptions/{subscription_id}/resourceGroups/{resource_group}/providers/Microsoft.KeyVault/vaults/{name}"
+    )
+}
+
+/// Whether an assignment grants secret read access at `scope` (at the scope or inherited)
+fn grants_secret_read(assignment: &RoleAssignmentProperties, scope: &str) -> bool {
+    let role = assignment
+        .role_definition_id
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    let assignment_scope = assignment.scope.trim_end_matches('/').to_ascii_lowercase();
+    let scope = scope.to_ascii_lowercase();
+    let covers = assignment_scope.is_empty()
+        || scope == assignment_scope
+        || scope.starts_with(&format!("{assignment_scope}/"));
+    covers
+        && SECRET_READER_ROLES
+            .iter()
+            .any(|reader| reader.eq_ignore_ascii_case(role))
+}
+
+/// Role assignment name (a GUID) derived from scope, principal and role
+fn assignment_name(scope: &str, principal_id: &str) -> String {
+    let digest = Sha256::digest(
+        format!(
+            "{}|{}|{KEY_VAULT_SECRETS_USER_ROLE}",
+            scope.to_ascii_lowercase(),
+            principal_id.to_ascii_lowercase()
+        )
+        .as_bytes(),
+    );
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_random_bytes(bytes)
+        .into_uuid()
+        .to_string()
+}
+
+/// Principal type name used by the role assignments API
+fn principal_type_name(principal_type: AzurePrincipalType) -> &'static str {
+    match principal_type {
+        AzurePrincipalType::ServicePrincipal => "ServicePrincipal",
+        AzurePrincipalType::User => "User",
+        AzurePrincipalType::Group => "Group",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT: &str =
+        "/subscriptions/sub/resourceGroups/rg/providers/Microsoft.KeyVault/vaults/my-vault";
+
+    fn assignment(scope: &str, role: &str) -> RoleAssignmentProperties {
+        RoleAssignmentProperties {
+            role_definition_id: format!(
+                "/subscriptions/sub/providers/Microsoft.Authorization/roleDefinitions/{role}"
+            ),
+            scope: scope.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_vault_scope() {
+        assert_eq!(vault_scope("sub", "rg", "my-vault"), VAULT);
+        assert_eq!(
+            vault_scope("sub", "rg", "https://my-vault.vault.azure.net/"),
+            VAULT
+        );
+    }
+
+    #[test]
+    fn test_grants_secret_read() {
+        let secret = format!("{VAULT}/secrets/db-password");
+        // At the scope, inherited from the vault or resource group, case-insensitively
+        assert!(grants_secret_read(
+            &assignment(&secret, KEY_VAULT_SECRETS_USER_ROLE),
+            &secret
+        ));
+        assert!(grants_secret_read(
+            &assignment(VAULT, "B86A8FE4-44CE-4948-AEE5-ECCB2C155CD7"),
+            &secret
+        ));
+        assert!(grants_secret_read(
+            &assignment(
+                "/subscriptions/sub/resourceGroups/RG",
+                KEY_VAULT_SECRETS_USER_ROLE
+            ),
+            VAULT
+        ));
+        // Other roles, sibling secrets and vaults with a common prefix don't count
+        assert!(!grants_secret_read(
+            &assignment(VAULT, "21090545-7ca7-4776-b22c-e363652d74d2"),
+            VAULT
+        ));
+        assert!(!grants_secret_read(
+            &assignment(
+                &format!("{VAULT}/secrets/other"),
+                KEY_VAULT_SECRETS_USER_ROLE
+            ),
+            &secret
+        ));
+        assert!(!grants_secret_read(
+            &assignment(VAULT, KEY_VAULT_SECRETS_USER_ROLE),
+            &format!("{VAULT}-2")
+        ));
+    }
+
+    #[test]
+    fn test_assignment_name_is_stable() {
+        let name = assignment_name(VAULT, "principal");
+        assert_eq!(name, assignment_name(&VAULT.to_uppercase(), "PRINCIPAL"));
+        assert_ne!(name, assignment_name(VAULT, "other"));
+        assert!(uuid::Uuid::parse_str(&name).is_ok());
+    }
+}
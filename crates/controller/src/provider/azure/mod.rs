@@ -4,10 +4,13 @@
 //!
 //! - `key_vault`: Azure Key Vault for secrets
 //! - `app_configuration`: Azure App Configuration for config values
+//! - `cloud`: Public and sovereign cloud endpoints (`provider.azure.environment`)
 
 pub mod app_configuration;
+pub mod cloud;
 pub mod key_vault;
 
 // Re-export for convenience
 pub use app_configuration::AzureAppConfiguration;
-pub use key_vault::AzureKeyVault;
+pub use cloud::AzureCloud;
+pub use key_vault::{AzureCertificateProvider, AzureKeyVault, VaultPacers};
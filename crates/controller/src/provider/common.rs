@@ -5,7 +5,8 @@
 //! This module reduces code duplication by providing common functionality
 //! for secret management operations that are similar across providers.
 
-use crate::observability::metrics;
+use crate::observability::{metrics, propagation};
+use crate::secret::SecretString;
 use anyhow::Result;
 use std::time::Instant;
 use tracing::debug;
@@ -38,17 +39,17 @@ pub enum SecretComparison {
 /// `SecretComparison` indicating the result of the comparison
 pub async fn compare_secret_value<F, Fut>(
     get_current_value: F,
-    new_value: &str,
+    new_value: &SecretString,
 ) -> Result<SecretComparison>
 where
     F: FnOnce() -> Fut,
-    Fut: std::future::Future<Output = Result<Option<String>>>,
+    Fut: std::future::Future<Output = Result<Option<SecretString>>>,
 {
     let current_value = get_current_value().await?;
 
     match current_value {
         None => Ok(SecretComparison::NotFound),
-        Some(current) if current == new_value => Ok(SecretComparison::Unchanged),
+        Some(current) if current == *new_value => Ok(SecretComparison::Unchanged),
         Some(_) => Ok(SecretComparison::Changed),
     }
 }
@@ -67,13 +68,56 @@ pub fn record_secret_metrics(provider: &str, operation: &str, start_time: Instan
     metrics::record_secret_operation(provider, operation, duration);
 }
 
+/// Send provider API requests with per-request latency metrics and trace context
+///
+/// Records `secret_manager_provider_api_request_duration_seconds{provider, operation, status_code}`
+/// for every request, so provider latency can be alerted on separately from reconcile duration.
+/// Also injects `traceparent` (and `X-Cloud-Trace-Context` for GCP) from the current span so
+/// provider-side logs can be correlated with reconcile traces.
+pub trait TimedSend {
+    /// Send the request, recording its duration under `provider` and `operation`
+    fn send_timed(
+        self,
+        provider: &'static str,
+        operation: &'static str,
+    ) -> impl std::future::Future<Output = reqwest::Result<reqwest::Response>> + Send;
+}
+
+impl TimedSend for reqwest::RequestBuilder {
+    async fn send_timed(
+        self,
+        provider: &'static str,
+        operation: &'static str,
+    ) -> reqwest::Result<reqwest::Response> {
+        let mut request = self;
+        for (name, value) in propagation::current_trace_headers(provider == "gcp") {
+            request = request.header(name, value);
+        }
+        let start = Instant::now();
+        let result = request.send().await;
+        let status_code = match &result {
+            Ok(response) => response.status().as_u16().to_string(),
+            Err(e) => e
+                .status()
+                .map_or_else(|| "error".to_string(), |s| s.as_u16().to_string()),
+        };
+        metrics::observe_provider_api_request(
+            provider,
+            operation,
+            &status_code,
+            start.elapsed().as_secs_f64(),
+        );
+        result
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[tokio::test]
     async fn test_compare_secret_value_not_found() {
-        let result = compare_secret_value(|| async { Ok(None) }, "new-value")
+        let result = compare_secret_value(|| async { Ok(None) }, &SecretString::from("new-value"))
             .await
             .expect("compare_secret_value should succeed for NotFound case");
         assert_eq!(result, SecretComparison::NotFound);
@@ -82,8 +126,8 @@ mod tests {
     #[tokio::test]
     async fn test_compare_secret_value_unchanged() {
         let result = compare_secret_value(
-            || async { Ok(Some("same-value".to_string())) },
-            "same-value",
+            || async { Ok(Some(SecretString::from("same-value"))) },
+            &SecretString::from("same-value"),
         )
         .await
         .expect("compare_secret_value should succeed for Unchanged case");
@@ -92,17 +136,22 @@ mod tests {
 
     #[tokio::test]
     async fn test_compare_secret_value_changed() {
-        let result =
-            compare_secret_value(|| async { Ok(Some("old-value".to_string())) }, "new-value")
-                .await
-                .expect("compare_secret_value should succeed for Changed case");
+        let result = compare_secret_value(
+            || async { Ok(Some(SecretString::from("old-value"))) },
+            &SecretString::from("new-value"),
+        )
+        .await
+        .expect("compare_secret_value should succeed for Changed case");
         assert_eq!(result, SecretComparison::Changed);
     }
 
     #[tokio::test]
     async fn test_compare_secret_value_error_propagation() {
-        let result =
-            compare_secret_value(|| async { Err(anyhow::anyhow!("Test error")) }, "value").await;
+        let result = compare_secret_value(
+            || async { Err(anyhow::anyhow!("Test error")) },
+            &SecretString::from("value"),
+        )
+        .await;
         assert!(result.is_err());
     }
 
@@ -178,7 +227,7 @@ pub fn log_secret_operation(provider: &str, secret_name: &str, comparison: Secre
 //
 // Example usage:
 // ```rust,ignore
-// async fn create_or_update_secret(&self, secret_name: &str, secret_value: &str) -> Result<bool> {
+// async fn create_or_update_secret(&self, secret_name: &str, secret_value: &SecretString) -> Result<bool> {
 //     let start = Instant::now();
 //     let comparison = compare_secret_value(|| self.get_secret_value(secret_name), secret_value).await?;
 //     if comparison == SecretComparison::Unchanged {
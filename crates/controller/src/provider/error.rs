@@ -0,0 +1,257 @@
+//! # Provider Errors
+//!
+//! Structured classification of provider API failures.
+//!
+//! Provider clients return `anyhow::Error`, but attach a `ProviderError` as the source of
+//! failures that came from the provider API. The reconciler finds it in the error chain with
+//! [`ProviderError::find`] to decide whether a failure is retryable, how long to wait, and
+//! which reason to report on the Ready condition, instead of matching on error messages.
+//!
+//! ```rust,ignore
+//! // In a provider
+//! return Err(ProviderError::from_status(404, format!("Secret {name} not found")).into());
+//!
+//! // In the reconciler
+//! if ProviderError::is_not_found(&e) { /* nothing to disable */ }
+//! ```
+
+use crate::controller::reconciler::status::ConditionReason;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Classified provider API failure
+///
+/// The message is the provider's error, as it was reported before classification.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum ProviderError {
+    /// The secret, parameter or version doesn't exist
+    #[error("{0}")]
+    NotFound(String),
+    /// Credentials are missing, expired or lack permission
+    #[error("{0}")]
+    PermissionDenied(String),
+    /// Rate limited; retry after the given delay if the provider sent one
+    #[error("{message}")]
+    Throttled {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    /// Concurrent modification or the resource already exists
+    #[error("{0}")]
+    Conflict(String),
+    /// The provider rejected the request or value (size, format, invalid argument)
+    #[error("{0}")]
+    InvalidPayload(String),
+    /// Network error, timeout or provider-side failure; retrying may succeed
+    #[error("{0}")]
+    Transient(String),
+    /// Any other failure; retrying won't help without a change
+    #[error("{0}")]
+    Permanent(String),
+}
+
+impl ProviderError {
+    /// Classify by HTTP status code
+    pub fn from_status(status: u16, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match status {
+            404 | 410 => Self::NotFound(message),
+            401 | 403 => Self::PermissionDenied(message),
+            429 => Self::Throttled {
+                retry_after: None,
+                message,
+            },
+            409 | 412 => Self::Conflict(message),
+            400 | 413 | 422 => Self::InvalidPayload(message),
+            408 | 500..=599 => Self::Transient(message),
+            _ => Self::Permanent(message),
+        }
+    }
+
+    /// Classify by provider error code (AWS exception name, GCP status, Azure error code)
+    ///
+    /// Returns `None` for codes that don't identify a class; fall back to `from_status`.
+    pub fn from_code(code: &str, message: impl Into<String>) -> Option<Self> {
+        let message = message.into();
+        let error = match code {
+            "ResourceNotFoundException"
+            | "ParameterNotFound"
+            | "ParameterVersionNotFound"
+            | "SecretNotFound"
+            | "KeyValueNotFound"
+            | "NOT_FOUND" => Self::NotFound(message),
+            "AccessDeniedException"
+            | "UnrecognizedClientException"
+            | "ExpiredTokenException"
+            | "Forbidden"
+            | "Unauthorized"
+            | "PERMISSION_DENIED"
+            | "UNAUTHENTICATED" => Self::PermissionDenied(message),
+            "ThrottlingException"
+            | "TooManyUpdates"
+            | "Throttled"
+            | "TooManyRequests"
+            | "RESOURCE_EXHAUSTED" => Self::Throttled {
+                retry_after: None,
+                message,
+            },
+            "ResourceExistsException"
+            | "ParameterAlreadyExists"
+            | "Conflict"
+            | "ALREADY_EXISTS"
+            | "ABORTED" => Self::Conflict(message),
+            "InvalidParameterException"
+            | "InvalidRequestException"
+            | "ValidationException"
+            | "ParameterMaxVersionLimitExceeded"
+            | "BadParameter"
+            | "INVALID_ARGUMENT"
+            | "FAILED_PRECONDITION" => Self::InvalidPayload(message),
+            "InternalServiceError"
+            | "InternalServerError"
+            | "InternalFailure"
+            | "ServiceUnavailable"
+            | "INTERNAL"
+            | "UNAVAILABLE"
+            | "DEADLINE_EXCEEDED" => Self::Transient(message),
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Set the delay requested by a throttling response (no effect on other classes)
+    #[must_use]
+    pub fn with_retry_after(self, delay: Option<Duration>) -> Self {
+        match self {
+            Self::Throttled { message, .. } => Self::Throttled {
+                retry_after: delay,
+                message,
+            },
+            other => other,
+        }
+    }
+
+    /// The provider error in `error`'s chain, if it came from a provider API
+    pub fn find(error: &anyhow::Error) -> Option<&ProviderError> {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<ProviderError>())
+    }
+
+    /// Whether `error` is a provider NotFound
+    pub fn is_not_found(error: &anyhow::Error) -> bool {
+        matches!(Self::find(error), Some(Self::NotFound(_)))
+    }
+
+    /// Whether retrying the same request may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Throttled { .. } | Self::Conflict(_) | Self::Transient(_)
+        )
+    }
+
+    /// Delay requested by the provider before retrying
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Throttled { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Reason for the Ready condition
+    pub fn reason(&self) -> ConditionReason {
+        match self {
+            Self::NotFound(_) => ConditionReason::ProviderNotFound,
+            Self::PermissionDenied(_) => ConditionReason::ProviderPermissionDenied,
+            Self::Throttled { .. } => ConditionReason::ProviderThrottled,
+            Self::Conflict(_) => ConditionReason::ProviderConflict,
+            Self::InvalidPayload(_) => ConditionReason::ProviderInvalidPayload,
+            Self::Transient(_) => ConditionReason::ProviderTransientError,
+            Self::Permanent(_) => ConditionReason::ProviderError,
+        }
+    }
+}
+
+/// Parse a `Retry-After` header value given in seconds
+pub fn parse_retry_after(value: Option<&str>) -> Option<Duration> {
+    value
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_status() {
+        assert!(matches!(
+            ProviderError::from_status(404, "x"),
+            ProviderError::NotFound(_)
+        ));
+        assert!(matches!(
+            ProviderError::from_status(403, "x"),
+            ProviderError::PermissionDenied(_)
+        ));
+        assert!(matches!(
+            ProviderError::from_status(429, "x"),
+            ProviderError::Throttled { .. }
+        ));
+        assert!(matches!(
+            ProviderError::from_status(503, "x"),
+            ProviderError::Transient(_)
+        ));
+        assert!(matches!(
+            ProviderError::from_status(400, "x"),
+            ProviderError::InvalidPayload(_)
+        ));
+    }
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(
+            ProviderError::from_code("ResourceNotFoundException", "gone"),
+            Some(ProviderError::NotFound("gone".to_string()))
+        );
+        assert!(matches!(
+            ProviderError::from_code("RESOURCE_EXHAUSTED", "slow down"),
+            Some(ProviderError::Throttled { .. })
+        ));
+        assert_eq!(ProviderError::from_code("SomethingElse", "x"), None);
+    }
+
+    #[test]
+    fn test_find_through_context() {
+        let error = anyhow::Error::new(
+            ProviderError::from_status(429, "Too many requests")
+                .with_retry_after(Some(Duration::from_secs(7))),
+        )
+        .context("Failed to create secret");
+
+        let provider_error =
+            ProviderError::find(&error).unwrap_or_else(|| panic!("provider error expected"));
+        assert!(provider_error.is_retryable());
+        assert_eq!(provider_error.retry_after(), Some(Duration::from_secs(7)));
+        assert_eq!(provider_error.reason().as_str(), "ProviderThrottled");
+        assert!(!ProviderError::is_not_found(&error));
+        assert!(ProviderError::find(&anyhow::anyhow!("not found")).is_none());
+    }
+
+    #[test]
+    fn test_message_is_preserved() {
+        let error = ProviderError::from_status(404, "HTTP 404 (status: 404 Not Found): missing");
+        assert_eq!(
+            error.to_string(),
+            "HTTP 404 (status: 404 Not Found): missing"
+        );
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after(Some("30")), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after(Some("soon")), None);
+        assert_eq!(parse_retry_after(None), None);
+    }
+}
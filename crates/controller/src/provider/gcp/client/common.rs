@@ -3,7 +3,8 @@
 //! This module contains shared logic for the REST implementation
 //! to reduce code duplication and maintain consistency.
 
-use crate::observability::metrics;
+use crate::observability::{metrics, redact};
+use anyhow::Result;
 use std::time::{Duration, Instant};
 use tracing::Span;
 
@@ -15,7 +16,7 @@ use tracing::Span;
 /// - `Some("update")` if the secret exists but value has changed
 /// - `None` if there's an error (should not happen in normal flow)
 pub fn determine_operation_type(
-    existing_secret: &Option<String>,
+    existing_secret: Option<&str>,
     new_value: &str,
 ) -> Option<&'static str> {
     match existing_secret {
@@ -91,11 +92,12 @@ impl OperationTracker {
     }
 
     /// Record error metrics
+    /// Secret values in a JSON error body are masked before it is recorded on the span.
     pub fn record_error(&self, operation_type: Option<&str>, error_message: &str) {
         record_error_metrics(
             &self.span,
             operation_type,
-            error_message,
+            &redact::redact_body(error_message),
             self.start.elapsed(),
         );
     }
@@ -116,3 +118,102 @@ impl OperationTracker {
         &self.span
     }
 }
+
+/// Maximum number of pages followed by a single list call
+/// Guards against an API that keeps returning page tokens forever
+pub const MAX_LIST_PAGES: usize = 1000;
+
+/// Follows `nextPageToken` across the pages of a GCP list call
+///
+/// GCP list endpoints return at most one page per request and signal more results
+/// with a non-empty `nextPageToken`. Callers send `token()` as the `pageToken`
+/// query parameter and pass each response's token to `advance`.
+#[derive(Debug, Default)]
+pub struct PageCursor {
+    next_token: Option<String>,
+    pages: usize,
+}
+
+impl PageCursor {
+    /// Create a cursor positioned at the first page
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Page token for the next request (None for the first page)
+    #[must_use]
+    pub fn token(&self) -> Option<&str> {
+        self.next_token.as_deref()
+    }
+
+    /// Number of pages fetched so far
+    #[must_use]
+    pub fn pages(&self) -> usize {
+        self.pages
+    }
+
+    /// Record a fetched page
+    /// Returns true if another page should be requested
+    /// # Errors
+    /// Returns an error if the page cap is reached or the API repeats a page token,
+    /// rather than silently truncating the listing
+    pub fn advance(&mut self, next_page_token: Option<String>) -> Result<bool> {
+        self.pages += 1;
+        let Some(token) = next_page_token.filter(|t| !t.is_empty()) else {
+            self.next_token = None;
+            return Ok(false);
+        };
+        if self.next_token.as_deref() == Some(token.as_str()) {
+            return Err(anyhow::anyhow!(
+                "GCP list returned the same page token twice after {} pages",
+                self.pages
+            ));
+        }
+        if self.pages >= MAX_LIST_PAGES {
+            return Err(anyhow::anyhow!(
+                "GCP list exceeded {MAX_LIST_PAGES} pages, refusing to continue"
+            ));
+        }
+        self.next_token = Some(token);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_cursor_follows_tokens() {
+        let mut cursor = PageCursor::new();
+        assert_eq!(cursor.token(), None);
+        assert!(cursor.advance(Some("a".to_string())).unwrap_or(false));
+        assert_eq!(cursor.token(), Some("a"));
+        assert!(cursor.advance(Some("b".to_string())).unwrap_or(false));
+        assert!(!cursor.advance(None).unwrap_or(true));
+        assert_eq!(cursor.pages(), 3);
+    }
+
+    #[test]
+    fn test_page_cursor_empty_token_ends_listing() {
+        let mut cursor = PageCursor::new();
+        assert!(!cursor.advance(Some(String::new())).unwrap_or(true));
+    }
+
+    #[test]
+    fn test_page_cursor_rejects_repeated_token() {
+        let mut cursor = PageCursor::new();
+        assert!(cursor.advance(Some("a".to_string())).unwrap_or(false));
+        assert!(cursor.advance(Some("a".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_page_cursor_cap() {
+        let mut cursor = PageCursor::new();
+        for page in 1..MAX_LIST_PAGES {
+            assert!(cursor.advance(Some(format!("t{page}"))).unwrap_or(false));
+        }
+        assert!(cursor.advance(Some("last".to_string())).is_err());
+    }
+}
@@ -6,4 +6,4 @@
 pub mod common;
 pub mod rest;
 
-pub use rest::SecretManagerREST;
+pub use rest::{SecretManagerREST, VersionRetention};
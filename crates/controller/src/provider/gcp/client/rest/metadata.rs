@@ -0,0 +1,233 @@
+//! # Secret Metadata
+//!
+//! Lists secrets and reads their metadata (labels, create time) without accessing
+//! their values. Used by `list_secrets`, `get_secret_metadata` and the existence check of
+//! `get_secret_values`. `list_secrets_stream` yields the listing page by page for projects
+//! too large to hold in memory.
+//!
+//! References:
+//! - [List secrets](https://cloud.google.com/secret-manager/docs/reference/rest/v1/projects.secrets/list)
+//! - [Get secret](https://cloud.google.com/secret-manager/docs/reference/rest/v1/projects.secrets/get)
+
+use crate::observability::metrics;
+use crate::provider::common::TimedSend;
+use crate::provider::gcp::client::common::PageCursor;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream, TryStreamExt};
+use smc_paths::prelude::{GcpOperation, PathBuilder};
+
+use super::SecretManagerREST;
+use super::responses::{ListSecretsResponse, Secret};
+
+impl Secret {
+    /// Secret ID, the last segment of `projects/*/secrets/{secret}`
+    #[must_use]
+    pub fn secret_id(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or(&self.name)
+    }
+
+    /// Secret creation time, if reported and parseable
+    #[must_use]
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.create_time
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    /// Secret expiry, if set and parseable
+    #[must_use]
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expire_time
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+    }
+}
+
+impl SecretManagerREST {
+    /// List the secrets in the project, following pagination
+    /// `filter` narrows the listing server-side (e.g. `name:app-`, a substring match on the
+    /// secret name); None lists every secret.
+    pub(super) async fn list_secret_resources(&self, filter: Option<&str>) -> Result<Vec<Secret>> {
+        self.list_secrets_stream(filter).try_collect().await
+    }
+
+    /// Stream the secrets in the project page by page
+    /// Use instead of collecting the whole listing for very large projects; pages are
+    /// fetched lazily as the stream is polled. `filter` is as for `list_secret_resources`.
+    pub fn list_secrets_stream<'a>(
+        &'a self,
+        filter: Option<&'a str>,
+    ) -> impl Stream<Item = Result<Secret>> + 'a {
+        stream::try_unfold(Some(PageCursor::new()), move |cursor| async move {
+            let Some(mut cursor) = cursor else {
+                return Ok(None);
+            };
+            let page = self.list_secrets_page(filter, cursor.token()).await?;
+            let next = cursor
+                .advance(page.next_page_token)
+                .context(format!(
+                    "Failed to list GCP secrets in project: {}",
+                    self.project_id()
+                ))?
+                .then_some(cursor);
+            Ok::<_, anyhow::Error>(Some((page.secrets, next)))
+        })
+        .map_ok(|secrets| stream::iter(secrets.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Fetch a single page of secrets
+    async fn list_secrets_page(
+        &self,
+        filter: Option<&str>,
+        page_token: Option<&str>,
+    ) -> Result<ListSecretsResponse> {
+        // Secrets are listed on the same collection path they are created on
+        let secrets_path = PathBuilder::new()
+            .gcp_operation(GcpOperation::CreateSecret)
+            .project(self.project_id())
+            .build_http_path()
+            .context("Failed to build list secrets path")?;
+
+        let mut request = self.make_request("GET", &secrets_path, None);
+        if let Some(filter) = filter {
+            request = request.query(&[("filter", filter)]);
+        }
+        if let Some(token) = page_token {
+            request = request.query(&[("pageToken", token)]);
+        }
+        let response = request
+            .send_timed("gcp", "list_secrets")
+            .await
+            .context("Failed to list secrets")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("gcp");
+            self.handle_error_response(status, error_text)
+                .context(format!(
+                    "Failed to list GCP secrets in project: {}",
+                    self.project_id()
+                ))?;
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse list secrets response")
+    }
+
+    /// Get a secret resource (without its value)
+    /// Returns None if the secret doesn't exist
+    pub(super) async fn get_secret_resource(&self, secret_name: &str) -> Result<Option<Secret>> {
+        let path = PathBuilder::new()
+            .gcp_operation(GcpOperation::GetSecret)
+            .project(self.project_id())
+            .secret(secret_name)
+            .build_http_path()
+            .context("Failed to build get secret path")?;
+
+        let response = self
+            .make_request("GET", &path, None)
+            .send_timed("gcp", "get_secret")
+            .await
+            .context("Failed to get secret")?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("gcp");
+            self.handle_error_response(status, error_text)
+                .context(format!("Failed to get GCP secret: {}", secret_name))?;
+        }
+
+        let secret = response
+            .json()
+            .await
+            .context("Failed to parse get secret response")?;
+        Ok(Some(secret))
+    }
+}
+
+/// Longest prefix shared by all names (empty when there are none)
+/// Used to narrow the listing of `get_secret_values` to the resource's secrets
+pub(super) fn common_prefix(names: &[String]) -> &str {
+    let Some((first, rest)) = names.split_first() else {
+        return "";
+    };
+    let mut len = first.len();
+    for name in rest {
+        len = first
+            .bytes()
+            .zip(name.bytes())
+            .take(len)
+            .take_while(|(a, b)| a == b)
+            .count();
+    }
+    // Don't cut a multi-byte character
+    while !first.is_char_boundary(len) {
+        len -= 1;
+    }
+    &first[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list_secrets_response() {
+        let page: ListSecretsResponse = serde_json::from_str(
+            r#"{
+                "secrets": [{
+                    "name": "projects/p/secrets/db-password",
+                    "replication": { "automatic": {} },
+                    "createTime": "2024-01-02T03:04:05Z",
+                    "labels": { "environment": "dev" }
+                }],
+                "nextPageToken": "next"
+            }"#,
+        )
+        .unwrap_or_else(|e| panic!("parse: {e}"));
+
+        let secret = &page.secrets[0];
+        assert_eq!(secret.secret_id(), "db-password");
+        assert_eq!(
+            secret.created_at().map(|t| t.to_rfc3339()),
+            Some("2024-01-02T03:04:05+00:00".to_string())
+        );
+        assert_eq!(
+            secret.labels.get("environment").map(String::as_str),
+            Some("dev")
+        );
+        assert_eq!(page.next_page_token.as_deref(), Some("next"));
+    }
+
+    #[test]
+    fn test_parse_empty_list_secrets_response() {
+        let page: ListSecretsResponse =
+            serde_json::from_str("{}").unwrap_or_else(|e| panic!("parse: {e}"));
+        assert!(page.secrets.is_empty());
+        assert!(page.next_page_token.is_none());
+    }
+
+    #[test]
+    fn test_common_prefix() {
+        let names = |names: &[&str]| names.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            common_prefix(&names(&["app-DB_PASSWORD-dev", "app-API_KEY-dev"])),
+            "app-"
+        );
+        assert_eq!(common_prefix(&names(&["only"])), "only");
+        assert_eq!(common_prefix(&names(&["a", "b"])), "");
+        assert_eq!(common_prefix(&[]), "");
+        assert_eq!(common_prefix(&names(&["é1", "é2"])), "é");
+    }
+}
@@ -12,11 +12,18 @@
 //! References:
 //! - [GCP Secret Manager REST API v1](https://docs.cloud.google.com/secret-manager/docs/reference/rest)
 
+mod metadata;
 mod operations;
 mod pact_api_override;
 mod requests;
 mod responses;
+mod retention;
 
+pub use retention::VersionRetention;
+
+use crate::observability::redact;
+use crate::provider::ProviderError;
+use crate::provider::gcp::impersonation::Impersonation;
 use anyhow::{Context, Result};
 use reqwest::Client;
 use tracing::{debug, info, warn};
@@ -27,6 +34,7 @@ pub struct SecretManagerREST {
     base_url: String,
     project_id: String,
     access_token: String,
+    version_retention: Option<VersionRetention>,
 }
 
 impl std::fmt::Debug for SecretManagerREST {
@@ -116,9 +124,45 @@ impl SecretManagerREST {
             base_url,
             project_id,
             access_token,
+            version_retention: None,
         })
     }
 
+    /// Exchange the Workload Identity token for a token of an impersonated service account
+    /// `None` keeps the Workload Identity token
+    ///
+    /// # Errors
+    /// Returns an error if the IAM Credentials API refuses the exchange
+    pub async fn with_impersonation(
+        mut self,
+        impersonation: Option<&Impersonation>,
+    ) -> Result<Self> {
+        if let Some(impersonation) = impersonation {
+            self.access_token = impersonation
+                .exchange(&self.http_client, &self.access_token)
+                .await?;
+        }
+        Ok(self)
+    }
+
+    /// Set the version retention policy applied after each write
+    #[must_use]
+    pub fn with_version_retention(mut self, version_retention: Option<VersionRetention>) -> Self {
+        self.version_retention = version_retention;
+        self
+    }
+
+    /// Override the API base URL (e.g. a Private Service Connect endpoint)
+    /// `None` keeps the default (or Pact mock server) endpoint
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: Option<String>) -> Self {
+        if let Some(base_url) = base_url {
+            info!("Using GCP Secret Manager endpoint override: {}", base_url);
+            self.base_url = base_url;
+        }
+        self
+    }
+
     /// Get OAuth2 access token for GCP API authentication
     ///
     /// Supports:
@@ -151,7 +195,10 @@ impl SecretManagerREST {
                     .await
                     .context("Failed to parse token response from metadata server")?;
                 info!("Retrieved access token from metadata server (Workload Identity)");
-                return Ok(format!("Bearer {}", token_response.access_token));
+                return Ok(format!(
+                    "Bearer {}",
+                    token_response.access_token.expose_secret()
+                ));
             }
             Ok(response) => {
                 debug!(
@@ -263,6 +310,11 @@ impl SecretManagerREST {
             .header("Content-Type", "application/json");
 
         if let Some(body) = body {
+            if tracing::enabled!(tracing::Level::DEBUG) {
+                let mut redacted = body.clone();
+                redact::redact_json(&mut redacted);
+                debug!(body = %redacted, "🔵 GCP REST Request body");
+            }
             request = request.json(&body);
         }
 
@@ -275,23 +327,34 @@ impl SecretManagerREST {
         status: reqwest::StatusCode,
         error_text: String,
     ) -> Result<()> {
+        let error_text = redact::redact_body(&error_text);
+        debug!(status = status.as_u16(), body = %error_text, "🔴 GCP REST Error response");
+
         // Try to parse GCP error response
         if let Ok(error_response) = serde_json::from_str::<responses::GcpErrorResponse>(&error_text)
         {
-            Err(anyhow::anyhow!(
+            let message = format!(
                 "GCP API error: {} (code: {}, status: {})",
                 error_response.error.message,
                 error_response.error.code,
                 error_response.error.status
-            ))
+            );
+            let error = ProviderError::from_code(&error_response.error.status, message.clone())
+                .unwrap_or_else(|| ProviderError::from_status(status.as_u16(), message))
+                .with_retry_after(error_response.error.retry_delay());
+            Err(error.into())
         } else {
             // Include status code in error message for easier matching in tests
-            Err(anyhow::anyhow!(
-                "HTTP {} (status: {}): {}",
+            Err(ProviderError::from_status(
                 status.as_u16(),
-                status,
-                error_text
-            ))
+                format!(
+                    "HTTP {} (status: {}): {}",
+                    status.as_u16(),
+                    status,
+                    error_text
+                ),
+            )
+            .into())
         }
     }
 
@@ -314,4 +377,9 @@ impl SecretManagerREST {
     pub(crate) fn access_token(&self) -> &str {
         &self.access_token
     }
+
+    /// Get the version retention policy
+    pub(crate) fn version_retention(&self) -> Option<&VersionRetention> {
+        self.version_retention.as_ref()
+    }
 }
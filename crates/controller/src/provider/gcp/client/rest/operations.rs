@@ -6,14 +6,22 @@
 //! enable, and disable secrets.
 
 use crate::observability::metrics;
-use crate::provider::SecretManagerProvider;
+use crate::provider::common::TimedSend;
+use crate::provider::{SecretManagerProvider, SecretMetadata, SecretVersionIds};
+use crate::secret::SecretString;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
-use tracing::{Instrument, debug, info, info_span};
+use tracing::{Instrument, debug, info, info_span, warn};
 
-use super::requests::{AddVersionRequest, CreateSecretRequest};
+use super::metadata::common_prefix;
+use super::requests::{
+    AddVersionRequest, CreateSecretRequest, UpdateSecretExpirationRequest,
+    UpdateSecretLabelsRequest,
+};
 use super::responses::AccessSecretVersionResponse;
 use crate::provider::gcp::client::common::{OperationTracker, determine_operation_type};
 use crate::provider::gcp::client::rest::SecretManagerREST;
@@ -24,7 +32,7 @@ impl SecretManagerProvider for SecretManagerREST {
     async fn create_or_update_secret(
         &self,
         secret_name: &str,
-        secret_value: &str,
+        secret_value: &SecretString,
         environment: &str,
         location: &str,
     ) -> Result<bool> {
@@ -40,6 +48,7 @@ impl SecretManagerProvider for SecretManagerREST {
         let http_client = self.http_client().clone();
         let base_url = self.base_url().to_string();
         let access_token = self.access_token().to_string();
+        let version_retention = self.version_retention().cloned();
 
         async move {
             let tracker = OperationTracker::new(span_clone.clone());
@@ -48,6 +57,7 @@ impl SecretManagerProvider for SecretManagerREST {
                 base_url,
                 project_id: project_id.clone(),
                 access_token,
+                version_retention,
             };
 
             // Check if secret exists
@@ -91,7 +101,7 @@ impl SecretManagerProvider for SecretManagerREST {
 
                 let response = self_ref
                     .make_request("POST", &path, Some(serde_json::to_value(&create_request)?))
-                    .send()
+                    .send_timed("gcp", "create_secret")
                     .await
                     .context("Failed to create secret")?;
 
@@ -107,7 +117,10 @@ impl SecretManagerProvider for SecretManagerREST {
             }
 
             // Check if value changed
-            let operation_type = match determine_operation_type(&existing_secret, secret_value) {
+            let operation_type = match determine_operation_type(
+                existing_secret.as_ref().map(SecretString::expose_secret),
+                secret_value.expose_secret(),
+            ) {
                 Some("no_change") => {
                     tracker.record_no_change();
                     return Ok(false);
@@ -135,7 +148,7 @@ impl SecretManagerProvider for SecretManagerREST {
 
             // Add new version with the value
             // GCP Secret Manager expects base64-encoded data
-            let secret_bytes = secret_value.as_bytes();
+            let secret_bytes = secret_value.expose_secret().as_bytes();
             let encoded = general_purpose::STANDARD.encode(secret_bytes);
 
             let add_version_request = AddVersionRequest::new(encoded);
@@ -166,7 +179,7 @@ impl SecretManagerProvider for SecretManagerREST {
                     &path,
                     Some(serde_json::to_value(&add_version_request)?),
                 )
-                .send()
+                .send_timed("gcp", "add_version")
                 .await
                 .context("Failed to add secret version")?;
 
@@ -196,13 +209,137 @@ impl SecretManagerProvider for SecretManagerREST {
             }
 
             tracker.record_success(operation_type);
+
+            // Pruning is best-effort: the new version is already written
+            if let Err(e) = self_ref.prune_versions(secret_name).await {
+                warn!(
+                    "Failed to prune versions of GCP secret {}: {}",
+                    secret_name, e
+                );
+            }
+            Ok(true)
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
+        match self.get_secret_binary(secret_name).await? {
+            Some(bytes) => Ok(Some(SecretString::new(
+                String::from_utf8(bytes).context("Secret value is not valid UTF-8")?,
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        let span = info_span!(
+            "gcp.secret.create_or_update_binary",
+            secret.name = secret_name,
+            project.id = self.project_id(),
+            provider = "gcp",
+            operation = "create_or_update_secret_binary"
+        );
+        let tracker = OperationTracker::new(span.clone());
+
+        async move {
+            let existing_secret = self.get_secret_binary(secret_name).await?;
+
+            let operation_type = match existing_secret {
+                Some(ref existing) if existing.as_slice() == secret_value => {
+                    tracker.record_no_change();
+                    return Ok(false);
+                }
+                Some(_) => "update",
+                None => {
+                    info!(
+                        provider = "gcp",
+                        project = self.project_id(),
+                        secret_name = secret_name,
+                        operation = "create_secret",
+                        "Creating new binary GCP secret: project={}, secret={}",
+                        self.project_id(),
+                        secret_name
+                    );
+                    let create_request = CreateSecretRequest::new(
+                        secret_name.to_string(),
+                        environment.to_string(),
+                        location.to_string(),
+                    );
+                    let path = PathBuilder::new()
+                        .gcp_operation(GcpOperation::CreateSecret)
+                        .project(self.project_id())
+                        .build_http_path()
+                        .context("Failed to build create secret path")?;
+                    let response = self
+                        .make_request("POST", &path, Some(serde_json::to_value(&create_request)?))
+                        .send_timed("gcp", "create_secret")
+                        .await
+                        .context("Failed to create secret")?;
+                    if !response.status().is_success() {
+                        let status = response.status();
+                        let error_text = response.text().await.unwrap_or_default();
+                        tracker.record_error(None, &error_text);
+                        self.handle_error_response(status, error_text)
+                            .context(format!("Failed to create GCP secret: {}", secret_name))?;
+                    }
+                    "create"
+                }
+            };
+
+            // The REST API carries payloads base64-encoded, so binary data needs no
+            // special handling beyond skipping the UTF-8 round trip
+            let add_version_request =
+                AddVersionRequest::new(general_purpose::STANDARD.encode(secret_value));
+            let path = PathBuilder::new()
+                .gcp_operation(GcpOperation::AddVersion)
+                .project(self.project_id())
+                .secret(secret_name)
+                .build_http_path()
+                .context("Failed to build add version path")?;
+            let response = self
+                .make_request(
+                    "POST",
+                    &path,
+                    Some(serde_json::to_value(&add_version_request)?),
+                )
+                .send_timed("gcp", "add_version")
+                .await
+                .context("Failed to add secret version")?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                tracker.record_error(Some(operation_type), &error_text);
+                self.handle_error_response(status, error_text)
+                    .context(format!(
+                        "Failed to add binary version to GCP secret: {}",
+                        secret_name
+                    ))?;
+            }
+
+            tracker.record_success(operation_type);
+
+            // Pruning is best-effort: the new version is already written
+            if let Err(e) = self.prune_versions(secret_name).await {
+                warn!(
+                    "Failed to prune versions of GCP secret {}: {}",
+                    secret_name, e
+                );
+            }
             Ok(true)
         }
         .instrument(span)
         .await
     }
 
-    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<String>> {
+    async fn get_secret_binary(&self, secret_name: &str) -> Result<Option<Vec<u8>>> {
         let span = tracing::debug_span!(
             "gcp.secret.get",
             secret.name = secret_name,
@@ -221,6 +358,7 @@ impl SecretManagerProvider for SecretManagerREST {
                 base_url,
                 project_id: project_id.clone(),
                 access_token,
+                version_retention: None,
             };
 
             let version_path = PathBuilder::new()
@@ -233,7 +371,7 @@ impl SecretManagerProvider for SecretManagerREST {
 
             let response = self_ref
                 .make_request("GET", &version_path, None)
-                .send()
+                .send_timed("gcp", "access_version")
                 .await
                 .context("Failed to access secret version")?;
 
@@ -246,16 +384,14 @@ impl SecretManagerProvider for SecretManagerREST {
 
                     // Decode base64
                     let decoded = general_purpose::STANDARD
-                        .decode(access_response.payload.data.as_bytes())
+                        .decode(access_response.payload.data.expose_secret().as_bytes())
                         .context("Failed to decode base64 secret data")?;
-                    let secret_value =
-                        String::from_utf8(decoded).context("Secret value is not valid UTF-8")?;
 
                     span_clone.record("operation.success", true);
                     span_clone.record("operation.found", true);
                     span_clone.record("operation.duration_ms", start.elapsed().as_millis() as u64);
                     metrics::record_secret_operation("gcp", "get", start.elapsed().as_secs_f64());
-                    Ok(Some(secret_value))
+                    Ok(Some(decoded))
                 }
                 status if status == 404 => {
                     // Secret not found
@@ -294,7 +430,7 @@ impl SecretManagerProvider for SecretManagerREST {
 
         let response = self
             .make_request("DELETE", &secret_path, None)
-            .send()
+            .send_timed("gcp", "delete_secret")
             .await
             .context("Failed to delete secret")?;
 
@@ -321,7 +457,7 @@ impl SecretManagerProvider for SecretManagerREST {
             .context("Failed to build disable secret path")?;
         let response = self
             .make_request("POST", &path, None)
-            .send()
+            .send_timed("gcp", "disable_secret")
             .await
             .context("Failed to disable secret")?;
 
@@ -355,7 +491,7 @@ impl SecretManagerProvider for SecretManagerREST {
             .context("Failed to build enable secret path")?;
         let response = self
             .make_request("POST", &path, None)
-            .send()
+            .send_timed("gcp", "enable_secret")
             .await
             .context("Failed to enable secret")?;
 
@@ -376,4 +512,145 @@ impl SecretManagerProvider for SecretManagerREST {
 
         Ok(true)
     }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        // Version IDs are increasing integers; the highest is the version just written
+        let mut ids: Vec<u64> = self
+            .list_versions(secret_name)
+            .await?
+            .iter()
+            .filter_map(|v| v.name.rsplit('/').next()?.parse().ok())
+            .collect();
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(SecretVersionIds {
+            current: ids.first().map(ToString::to_string),
+            previous: ids.get(1).map(ToString::to_string),
+        })
+    }
+
+    async fn set_secret_labels(
+        &self,
+        secret_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        let Some(secret) = self.get_secret_resource(secret_name).await? else {
+            return Err(anyhow::anyhow!(
+                "Failed to label GCP secret {secret_name}: secret not found"
+            ));
+        };
+        if labels.iter().all(|(k, v)| secret.labels.get(k) == Some(v)) {
+            return Ok(());
+        }
+        // updateMask=labels replaces the whole label set
+        let mut merged = secret.labels;
+        merged.extend(labels.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+        // Secrets are patched on the same path they are read from
+        let path = PathBuilder::new()
+            .gcp_operation(GcpOperation::GetSecret)
+            .project(self.project_id())
+            .secret(secret_name)
+            .build_http_path()
+            .context("Failed to build update secret path")?;
+        let request = UpdateSecretLabelsRequest { labels: merged };
+        let response = self
+            .make_request("PATCH", &path, Some(serde_json::to_value(&request)?))
+            .query(&[("updateMask", "labels")])
+            .send_timed("gcp", "update_secret_labels")
+            .await
+            .context("Failed to update secret labels")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("gcp");
+            return self
+                .handle_error_response(status, error_text)
+                .context(format!("Failed to label GCP secret: {}", secret_name));
+        }
+        Ok(())
+    }
+
+    async fn set_secret_expiration(
+        &self,
+        secret_name: &str,
+        expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        let path = PathBuilder::new()
+            .gcp_operation(GcpOperation::GetSecret)
+            .project(self.project_id())
+            .secret(secret_name)
+            .build_http_path()
+            .context("Failed to build update secret path")?;
+        let request = UpdateSecretExpirationRequest {
+            expire_time: expire_time.to_rfc3339_opts(SecondsFormat::Secs, true),
+        };
+        let response = self
+            .make_request("PATCH", &path, Some(serde_json::to_value(&request)?))
+            .query(&[("updateMask", "expireTime")])
+            .send_timed("gcp", "update_secret_expiration")
+            .await
+            .context("Failed to update secret expiration")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("gcp");
+            return self
+                .handle_error_response(status, error_text)
+                .context(format!(
+                    "Failed to set expiration of GCP secret: {}",
+                    secret_name
+                ));
+        }
+        Ok(())
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .list_secret_resources(None)
+            .await?
+            .iter()
+            .map(|secret| secret.secret_id())
+            .filter(|name| name.starts_with(prefix))
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    async fn get_secret_values(
+        &self,
+        secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        // Secret Manager has no batch access: one filtered listing finds the secrets that
+        // don't exist, so only existing secrets are read one at a time
+        let prefix = common_prefix(secret_names);
+        if prefix.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let existing: HashSet<String> = self
+            .list_secret_resources(Some(&format!("name:{prefix}")))
+            .await?
+            .iter()
+            .map(|secret| secret.secret_id().to_string())
+            .collect();
+        Ok(secret_names
+            .iter()
+            .filter(|name| !existing.contains(*name))
+            .map(|name| (name.clone(), None))
+            .collect())
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        let Some(secret) = self.get_secret_resource(secret_name).await? else {
+            return Ok(None);
+        };
+        let version_count = self.list_versions(secret_name).await?.len();
+        Ok(Some(SecretMetadata {
+            name: secret_name.to_string(),
+            create_time: secret.created_at(),
+            expire_time: secret.expires_at(),
+            labels: secret.labels,
+            version_count,
+        }))
+    }
 }
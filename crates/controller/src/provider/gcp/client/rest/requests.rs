@@ -7,6 +7,7 @@
 //! as documented at:
 //! https://cloud.google.com/secret-manager/docs/reference/rest
 
+use crate::secret::SecretString;
 use serde::Serialize;
 
 use super::responses::{AutomaticReplication, Replication, SecretPayload};
@@ -56,6 +57,31 @@ impl CreateSecretRequest {
     }
 }
 
+/// Request body for updating a secret's labels
+///
+/// Used in `PATCH /v1/projects/{project}/secrets/{secret}?updateMask=labels`. The labels
+/// replace the secret's labels, so callers send the merged set.
+///
+/// API Reference: https://cloud.google.com/secret-manager/docs/reference/rest/v1/projects.secrets/patch
+#[derive(Debug, Serialize)]
+pub struct UpdateSecretLabelsRequest {
+    /// Complete label set of the secret
+    pub labels: std::collections::HashMap<String, String>,
+}
+
+/// Request body for setting a secret's expiry
+///
+/// Used in `PATCH /v1/projects/{project}/secrets/{secret}?updateMask=expireTime`. GCP deletes
+/// the secret, with all its versions, at this time.
+///
+/// API Reference: https://cloud.google.com/secret-manager/docs/reference/rest/v1/projects.secrets/patch
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSecretExpirationRequest {
+    /// Expiry (RFC3339, UTC)
+    pub expire_time: String,
+}
+
 /// Request body for adding a new version to an existing secret
 ///
 /// Used in `POST /v1/projects/{project}/secrets/{secret}:addVersion` to add
@@ -74,7 +100,9 @@ impl AddVersionRequest {
     /// Create a new request with base64-encoded data
     pub fn new(data: String) -> Self {
         Self {
-            payload: SecretPayload { data },
+            payload: SecretPayload {
+                data: SecretString::new(data),
+            },
         }
     }
 }
@@ -6,26 +6,53 @@
 //! They are designed to match the API schema as documented at:
 //! https://cloud.google.com/secret-manager/docs/reference/rest
 
+use crate::secret::SecretString;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Secret resource representation
 ///
-/// Represents a secret in GCP Secret Manager. Used for both requests and responses.
+/// Represents a secret in GCP Secret Manager, as returned by get and list.
 /// Maps to the `Secret` resource in the GCP API.
 ///
-/// **Note**: Currently this struct is defined but not actively used in the implementation.
 /// The codebase uses `CreateSecretRequest` for creating secrets, which contains
-/// the necessary fields. This struct is reserved for future use when we need to
-/// deserialize full secret resource responses.
+/// the necessary fields.
 ///
 /// API Reference: https://cloud.google.com/secret-manager/docs/reference/rest/v1/projects.secrets#Secret
-#[allow(dead_code)] // Reserved for future use
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Secret {
     /// The resource name of the secret in the format `projects/*/secrets/*`
     pub name: String,
     /// Replication configuration for the secret
+    #[allow(dead_code)] // Deserialized for completeness; replication is set on create
+    #[serde(default)]
     pub replication: Replication,
+    /// Creation time (RFC3339)
+    #[serde(default)]
+    pub create_time: Option<String>,
+    /// User-defined labels
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// When GCP deletes the secret (RFC3339), if an expiry is set
+    #[serde(default)]
+    pub expire_time: Option<String>,
+}
+
+/// Response from listing secrets
+///
+/// Returned by `GET /v1/projects/{project}/secrets`.
+///
+/// API Reference: https://cloud.google.com/secret-manager/docs/reference/rest/v1/projects.secrets/list
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSecretsResponse {
+    /// Secrets on this page (omitted by the API when there are none)
+    #[serde(default)]
+    pub secrets: Vec<Secret>,
+    /// Token for the next page, absent on the last page
+    #[serde(default)]
+    pub next_page_token: Option<String>,
 }
 
 /// Replication configuration for a secret
@@ -34,7 +61,7 @@ pub struct Secret {
 /// Currently only supports automatic replication.
 ///
 /// API Reference: https://cloud.google.com/secret-manager/docs/reference/rest/v1/Replication
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Replication {
     /// Automatic replication configuration
     ///
@@ -85,7 +112,8 @@ pub struct SecretPayload {
     ///
     /// When serializing (sending to API): We encode the secret value to base64.
     /// When deserializing (receiving from API): We decode from base64 to get the original value.
-    pub data: String,
+    /// Redacted in `Debug` and zeroized on drop, since base64 is not a protection.
+    pub data: SecretString,
 }
 
 /// Response from accessing a secret version
@@ -109,6 +137,38 @@ pub struct AccessSecretVersionResponse {
     pub payload: SecretPayload,
 }
 
+/// Response from listing secret versions
+///
+/// Returned by `GET /v1/projects/{project}/secrets/{secret}/versions`.
+///
+/// API Reference: https://cloud.google.com/secret-manager/docs/reference/rest/v1/projects.secrets.versions/list
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSecretVersionsResponse {
+    /// Versions on this page (omitted by the API when there are none)
+    #[serde(default)]
+    pub versions: Vec<SecretVersionMetadata>,
+    /// Token for the next page, absent on the last page
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+}
+
+/// Secret version metadata (without payload)
+///
+/// API Reference: https://cloud.google.com/secret-manager/docs/reference/rest/v1/projects.secrets.versions#SecretVersion
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretVersionMetadata {
+    /// The resource name in the format `projects/*/secrets/*/versions/*`
+    pub name: String,
+    /// Creation time (RFC3339)
+    #[serde(default)]
+    pub create_time: Option<String>,
+    /// Version state: ENABLED, DISABLED, or DESTROYED
+    #[serde(default)]
+    pub state: Option<String>,
+}
+
 /// GCP API error response wrapper
 ///
 /// GCP REST API returns errors in a standard format with an `error` field
@@ -133,6 +193,24 @@ pub struct GcpError {
     pub message: String,
     /// Error status string (e.g., "NOT_FOUND", "PERMISSION_DENIED")
     pub status: String,
+    /// `google.rpc` details (`ErrorInfo`, `RetryInfo`, ...)
+    #[serde(default)]
+    pub details: Vec<serde_json::Value>,
+}
+
+impl GcpError {
+    /// Delay from a `google.rpc.RetryInfo` detail (`retryDelay`, e.g. "30s" or "1.5s")
+    pub fn retry_delay(&self) -> Option<std::time::Duration> {
+        self.details
+            .iter()
+            .filter(|detail| {
+                detail.get("@type").and_then(|t| t.as_str())
+                    == Some("type.googleapis.com/google.rpc.RetryInfo")
+            })
+            .find_map(|detail| detail.get("retryDelay")?.as_str()?.strip_suffix('s'))
+            .and_then(|seconds| seconds.parse::<f64>().ok())
+            .and_then(|seconds| std::time::Duration::try_from_secs_f64(seconds).ok())
+    }
 }
 
 /// OAuth2 access token response from GCP metadata server
@@ -146,7 +224,7 @@ pub struct GcpError {
 #[derive(Debug, Deserialize)]
 pub struct TokenResponse {
     /// OAuth2 access token for authenticating with GCP APIs
-    pub access_token: String,
+    pub access_token: SecretString,
     /// Token type (typically "Bearer")
     #[serde(rename = "token_type")]
     #[allow(dead_code)] // Field is required for deserialization but not used after parsing
@@ -155,3 +233,28 @@ pub struct TokenResponse {
     #[allow(dead_code)] // Field is required for deserialization but not used after parsing
     pub expires_in: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_error_retry_delay_from_retry_info() {
+        let response: GcpErrorResponse = serde_json::from_str(
+            r#"{"error": {"code": 429, "message": "Quota exceeded", "status": "RESOURCE_EXHAUSTED",
+                "details": [
+                    {"@type": "type.googleapis.com/google.rpc.ErrorInfo", "reason": "RATE_LIMIT_EXCEEDED"},
+                    {"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "30s"}
+                ]}}"#,
+        )
+        .unwrap_or_else(|e| panic!("Failed to parse error response: {e}"));
+        assert_eq!(response.error.retry_delay(), Some(Duration::from_secs(30)));
+
+        let response: GcpErrorResponse = serde_json::from_str(
+            r#"{"error": {"code": 404, "message": "gone", "status": "NOT_FOUND"}}"#,
+        )
+        .unwrap_or_else(|e| panic!("Failed to parse error response: {e}"));
+        assert_eq!(response.error.retry_delay(), None);
+    }
+}
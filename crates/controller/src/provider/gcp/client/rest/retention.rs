@@ -0,0 +1,347 @@
+//! # Version Retention
+//!
+//! Destroys superseded secret versions according to `gcp.versionRetention`.
+//!
+//! Every write to GCP Secret Manager adds a new version and older versions stay
+//! ENABLED (and billed) forever. After a successful write the controller lists the
+//! secret's versions and destroys the ones that fall outside the retention policy.
+//! Destroyed versions cannot be recovered, so the latest version is always kept.
+//!
+//! References:
+//! - [List versions](https://cloud.google.com/secret-manager/docs/reference/rest/v1/projects.secrets.versions/list)
+//! - [Destroy version](https://cloud.google.com/secret-manager/docs/reference/rest/v1/projects.secrets.versions/destroy)
+
+use crate::crd::{GcpVersionRetention, KubeDuration};
+use crate::observability::metrics;
+use crate::provider::common::TimedSend;
+use crate::provider::gcp::client::common::PageCursor;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream, TryStreamExt};
+use smc_paths::prelude::{GcpOperation, PathBuilder};
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::SecretManagerREST;
+use super::responses::{ListSecretVersionsResponse, SecretVersionMetadata};
+
+/// Version state reported by GCP for destroyed versions
+const DESTROYED_STATE: &str = "DESTROYED";
+
+/// Parsed version retention policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRetention {
+    /// Number of most recent versions to keep
+    pub keep_last: Option<usize>,
+    /// Minimum age before a superseded version is destroyed
+    pub destroy_older_than: Option<Duration>,
+}
+
+impl VersionRetention {
+    /// Build a retention policy from the CRD configuration
+    /// # Errors
+    /// Returns an error if `keepLast` is 0 or `destroyOlderThan` is not a valid duration
+    pub fn from_config(config: &GcpVersionRetention) -> Result<Self> {
+        let keep_last = match config.keep_last {
+            Some(0) => {
+                return Err(anyhow::anyhow!(
+                    "versionRetention.keepLast must be at least 1"
+                ));
+            }
+            Some(n) => Some(n as usize),
+            None => None,
+        };
+        let destroy_older_than = config
+            .destroy_older_than
+            .as_ref()
+            .map(KubeDuration::to_duration)
+            .transpose()
+            .context("Invalid versionRetention.destroyOlderThan")?;
+
+        Ok(Self {
+            keep_last,
+            destroy_older_than,
+        })
+    }
+
+    /// Select versions to destroy
+    /// `versions` may be in any order; the newest version (highest version number) is never selected.
+    /// When both limits are set, a version must be outside `keep_last` AND older than
+    /// `destroy_older_than` to be selected.
+    #[must_use]
+    pub fn select_for_destruction<'a>(
+        &self,
+        versions: &'a [SecretVersionMetadata],
+        now: DateTime<Utc>,
+    ) -> Vec<&'a SecretVersionMetadata> {
+        if self.keep_last.is_none() && self.destroy_older_than.is_none() {
+            return Vec::new();
+        }
+
+        let mut live: Vec<&SecretVersionMetadata> = versions
+            .iter()
+            .filter(|v| v.state.as_deref() != Some(DESTROYED_STATE))
+            .collect();
+        // Newest first: version IDs are monotonically increasing integers
+        live.sort_by_key(|v| std::cmp::Reverse(v.version_number()));
+
+        live.into_iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(index, version)| {
+                let outside_keep_last = self.keep_last.is_none_or(|keep| *index >= keep);
+                let old_enough = self.destroy_older_than.is_none_or(|max_age| {
+                    version.created_at().is_some_and(|created| {
+                        now.signed_duration_since(created)
+                            .to_std()
+                            .is_ok_and(|age| age > max_age)
+                    })
+                });
+                outside_keep_last && old_enough
+            })
+            .map(|(_, version)| version)
+            .collect()
+    }
+}
+
+impl SecretVersionMetadata {
+    /// Numeric version ID parsed from the resource name (`.../versions/{id}`)
+    #[must_use]
+    pub fn version_number(&self) -> u64 {
+        self.name
+            .rsplit('/')
+            .next()
+            .and_then(|id| id.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Version creation time, if reported and parseable
+    #[must_use]
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.create_time
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+    }
+}
+
+impl SecretManagerREST {
+    /// Destroy superseded versions of a secret according to the configured retention policy
+    /// Returns the number of destroyed versions (0 when no policy is configured)
+    /// # Errors
+    /// Returns an error if listing or destroying versions fails
+    pub(crate) async fn prune_versions(&self, secret_name: &str) -> Result<usize> {
+        let Some(retention) = self.version_retention() else {
+            return Ok(0);
+        };
+
+        let versions = self.list_versions(secret_name).await?;
+        let to_destroy = retention.select_for_destruction(&versions, Utc::now());
+        if to_destroy.is_empty() {
+            debug!(
+                "No versions to destroy for GCP secret {} ({} versions)",
+                secret_name,
+                versions.len()
+            );
+            return Ok(0);
+        }
+
+        for version in &to_destroy {
+            let path = format!("{}:destroy", version.name.trim_start_matches('/'));
+            let response = self
+                .make_request("POST", &path, Some(serde_json::json!({})))
+                .send_timed("gcp", "destroy_version")
+                .await
+                .context("Failed to destroy secret version")?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                metrics::increment_provider_operation_errors("gcp");
+                self.handle_error_response(status, error_text)
+                    .context(format!(
+                        "Failed to destroy GCP secret version: {}",
+                        version.name
+                    ))?;
+            }
+        }
+
+        metrics::increment_secret_versions_destroyed("gcp", to_destroy.len() as u64);
+        info!(
+            provider = "gcp",
+            secret_name = secret_name,
+            destroyed = to_destroy.len(),
+            "🗑️  Destroyed {} superseded version(s) of GCP secret {}",
+            to_destroy.len(),
+            secret_name
+        );
+        Ok(to_destroy.len())
+    }
+
+    /// List all versions of a secret, following pagination
+    pub(super) async fn list_versions(
+        &self,
+        secret_name: &str,
+    ) -> Result<Vec<SecretVersionMetadata>> {
+        self.list_versions_stream(secret_name).try_collect().await
+    }
+
+    /// Stream the versions of a secret page by page
+    /// Use instead of `list_versions` for secrets with very many versions; pages are fetched
+    /// lazily as the stream is polled
+    pub fn list_versions_stream<'a>(
+        &'a self,
+        secret_name: &'a str,
+    ) -> impl Stream<Item = Result<SecretVersionMetadata>> + 'a {
+        stream::try_unfold(Some(PageCursor::new()), move |cursor| async move {
+            let Some(mut cursor) = cursor else {
+                return Ok(None);
+            };
+            let page = self.list_versions_page(secret_name, cursor.token()).await?;
+            let next = cursor
+                .advance(page.next_page_token)
+                .context(format!(
+                    "Failed to list versions of GCP secret: {secret_name}"
+                ))?
+                .then_some(cursor);
+            Ok::<_, anyhow::Error>(Some((page.versions, next)))
+        })
+        .map_ok(|versions| stream::iter(versions.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Fetch a single page of a secret's versions
+    async fn list_versions_page(
+        &self,
+        secret_name: &str,
+        page_token: Option<&str>,
+    ) -> Result<ListSecretVersionsResponse> {
+        let secret_path = PathBuilder::new()
+            .gcp_operation(GcpOperation::GetSecret)
+            .project(self.project_id())
+            .secret(secret_name)
+            .build_http_path()
+            .context("Failed to build list versions path")?;
+
+        let mut request = self.make_request("GET", &format!("{secret_path}/versions"), None);
+        if let Some(token) = page_token {
+            request = request.query(&[("pageToken", token)]);
+        }
+        let response = request
+            .send_timed("gcp", "list_versions")
+            .await
+            .context("Failed to list secret versions")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            metrics::increment_provider_operation_errors("gcp");
+            self.handle_error_response(status, error_text)
+                .context(format!(
+                    "Failed to list versions of GCP secret: {}",
+                    secret_name
+                ))?;
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse list versions response")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: u64, state: &str, age_days: i64, now: DateTime<Utc>) -> SecretVersionMetadata {
+        SecretVersionMetadata {
+            name: format!("projects/p/secrets/s/versions/{id}"),
+            create_time: Some((now - chrono::Duration::days(age_days)).to_rfc3339()),
+            state: Some(state.to_string()),
+        }
+    }
+
+    fn ids(selected: &[&SecretVersionMetadata]) -> Vec<u64> {
+        selected.iter().map(|v| v.version_number()).collect()
+    }
+
+    #[test]
+    fn test_keep_last_destroys_older_versions() {
+        let now = Utc::now();
+        let versions: Vec<_> = (1..=5).map(|id| version(id, "ENABLED", 0, now)).collect();
+        let retention = VersionRetention {
+            keep_last: Some(2),
+            destroy_older_than: None,
+        };
+        assert_eq!(
+            ids(&retention.select_for_destruction(&versions, now)),
+            vec![3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn test_destroy_older_than_never_destroys_latest() {
+        let now = Utc::now();
+        let versions = vec![
+            version(1, "ENABLED", 90, now),
+            version(2, "DISABLED", 60, now),
+            version(3, "ENABLED", 45, now),
+        ];
+        let retention = VersionRetention {
+            keep_last: None,
+            destroy_older_than: Some(Duration::from_secs(30 * 24 * 3600)),
+        };
+        assert_eq!(
+            ids(&retention.select_for_destruction(&versions, now)),
+            vec![2, 1]
+        );
+    }
+
+    #[test]
+    fn test_both_limits_must_apply() {
+        let now = Utc::now();
+        let versions = vec![
+            version(1, "ENABLED", 90, now),
+            version(2, "ENABLED", 10, now),
+            version(3, "ENABLED", 5, now),
+            version(4, "ENABLED", 0, now),
+        ];
+        let retention = VersionRetention {
+            keep_last: Some(2),
+            destroy_older_than: Some(Duration::from_secs(30 * 24 * 3600)),
+        };
+        // Version 2 is outside keepLast but too recent
+        assert_eq!(
+            ids(&retention.select_for_destruction(&versions, now)),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn test_destroyed_versions_are_ignored() {
+        let now = Utc::now();
+        let versions = vec![
+            version(1, "DESTROYED", 90, now),
+            version(2, "ENABLED", 90, now),
+            version(3, "ENABLED", 0, now),
+        ];
+        let retention = VersionRetention {
+            keep_last: Some(1),
+            destroy_older_than: None,
+        };
+        assert_eq!(
+            ids(&retention.select_for_destruction(&versions, now)),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_from_config_rejects_zero_keep_last() {
+        let config = GcpVersionRetention {
+            keep_last: Some(0),
+            destroy_older_than: None,
+        };
+        assert!(VersionRetention::from_config(&config).is_err());
+    }
+}
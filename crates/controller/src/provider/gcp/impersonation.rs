@@ -0,0 +1,173 @@
+//! # Service Account Impersonation
+//!
+//! Exchanges the controller's Workload Identity token for an access token of another service
+//! account (`provider.gcp.auth.authType: impersonation`).
+//!
+//! The exchange uses the IAM Credentials `generateAccessToken` API. With `delegates`, IAM
+//! walks the chain from the controller's service account through each delegate to the target,
+//! checking `roles/iam.serviceAccountTokenCreator` at every hop, so a team can grant access to
+//! its own service account without granting anything to the controller directly.
+//!
+//! Reference: https://cloud.google.com/iam/docs/reference/credentials/rest/v1/projects.serviceAccounts/generateAccessToken
+
+use crate::crd::GcpAuthConfig;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+/// IAM Credentials API base URL
+pub const IAM_CREDENTIALS_ENDPOINT: &str = "https://iamcredentials.googleapis.com";
+
+/// Scope requested for impersonated tokens (same as the metadata server token)
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+/// Lifetime of impersonated tokens; clients are created per reconciliation
+const TOKEN_LIFETIME: &str = "3600s";
+
+/// Impersonation target of a resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Impersonation {
+    /// Service account the token is issued for
+    pub target_service_account: String,
+    /// Service accounts between the controller's and the target, in order
+    pub delegates: Vec<String>,
+}
+
+/// `generateAccessToken` response
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateAccessTokenResponse {
+    access_token: String,
+}
+
+impl Impersonation {
+    /// Impersonation configured by a resource's auth, if any
+    #[must_use]
+    pub fn from_auth(auth: Option<&GcpAuthConfig>) -> Option<Self> {
+        match auth? {
+            GcpAuthConfig::Impersonation {
+                target_service_account,
+                delegates,
+            } => Some(Self {
+                target_service_account: target_service_account.clone(),
+                delegates: delegates.clone(),
+            }),
+            GcpAuthConfig::WorkloadIdentity { .. } => None,
+        }
+    }
+
+    /// `generateAccessToken` URL for the target service account
+    fn url(&self) -> String {
+        format!(
+            "{IAM_CREDENTIALS_ENDPOINT}/v1/projects/-/serviceAccounts/{}:generateAccessToken",
+            self.target_service_account
+        )
+    }
+
+    /// `generateAccessToken` request body
+    fn request_body(&self) -> serde_json::Value {
+        serde_json::json!({
+            "delegates": self
+                .delegates
+                .iter()
+                .map(|d| format!("projects/-/serviceAccounts/{d}"))
+                .collect::<Vec<_>>(),
+            "scope": [CLOUD_PLATFORM_SCOPE],
+            "lifetime": TOKEN_LIFETIME,
+        })
+    }
+
+    /// Exchange `source_token` (an `Authorization` header value) for a token of the target
+    /// Returns the new `Authorization` header value. In Pact mode the source token is returned
+    pub async fn exchange(&self, http_client: &Client, source_token: &str) -> Result<String> {
+        if std::env::var("PACT_MODE").is_ok() {
+            warn!(
+                "PACT_MODE: not impersonating {}",
+                self.target_service_account
+            );
+            return Ok(source_token.to_string());
+        }
+
+        info!(
+            "Impersonating GCP service account {} (delegates: {})",
+            self.target_service_account,
+            if self.delegates.is_empty() {
+                "none".to_string()
+            } else {
+                self.delegates.join(" -> ")
+            }
+        );
+        let response = http_client
+            .post(self.url())
+            .header("Authorization", source_token)
+            .json(&self.request_body())
+            .send()
+            .await
+            .context("generateAccessToken request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "Failed to impersonate {}: generateAccessToken returned {status}: {body}. \
+                The controller's service account (or the last delegate) needs \
+                roles/iam.serviceAccountTokenCreator on it",
+                self.target_service_account
+            ));
+        }
+        let token: GenerateAccessTokenResponse = response
+            .json()
+            .await
+            .context("Failed to parse generateAccessToken response")?;
+        Ok(format!("Bearer {}", token.access_token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_auth() {
+        let auth: GcpAuthConfig = serde_json::from_value(serde_json::json!({
+            "authType": "impersonation",
+            "targetServiceAccount": "team-a@team-a-project.iam.gserviceaccount.com",
+            "delegates": ["broker@platform-project.iam.gserviceaccount.com"]
+        }))
+        .unwrap_or_else(|e| panic!("invalid auth: {e}"));
+        let impersonation =
+            Impersonation::from_auth(Some(&auth)).unwrap_or_else(|| panic!("impersonation"));
+        assert_eq!(
+            impersonation.target_service_account,
+            "team-a@team-a-project.iam.gserviceaccount.com"
+        );
+        assert_eq!(
+            impersonation.delegates,
+            vec!["broker@platform-project.iam.gserviceaccount.com".to_string()]
+        );
+
+        let workload_identity = GcpAuthConfig::WorkloadIdentity {
+            service_account_email: "sa@project.iam.gserviceaccount.com".to_string(),
+        };
+        assert_eq!(Impersonation::from_auth(Some(&workload_identity)), None);
+        assert_eq!(Impersonation::from_auth(None), None);
+    }
+
+    #[test]
+    fn test_request() {
+        let impersonation = Impersonation {
+            target_service_account: "team-a@team-a-project.iam.gserviceaccount.com".to_string(),
+            delegates: vec!["broker@platform-project.iam.gserviceaccount.com".to_string()],
+        };
+        assert_eq!(
+            impersonation.url(),
+            "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/team-a@team-a-project.iam.gserviceaccount.com:generateAccessToken"
+        );
+        let body = impersonation.request_body();
+        assert_eq!(
+            body["delegates"][0],
+            "projects/-/serviceAccounts/broker@platform-project.iam.gserviceaccount.com"
+        );
+        assert_eq!(body["scope"][0], CLOUD_PLATFORM_SCOPE);
+    }
+}
@@ -10,11 +10,19 @@
 //! - Easier to troubleshoot and maintain
 
 mod client;
+mod endpoint;
+pub mod impersonation;
 mod parameter_manager;
 
-pub use client::SecretManagerREST;
+pub use client::{SecretManagerREST, VersionRetention};
+pub use endpoint::{
+    DEFAULT_ALLOWED_ENDPOINT_DOMAINS, check_endpoint_override, endpoint_override_host,
+    resource_endpoint_override,
+};
+pub use impersonation::Impersonation;
 pub use parameter_manager::ParameterManagerREST;
 
+use crate::crd::GcpVersionRetention;
 use crate::provider::{ConfigStoreProvider, SecretManagerProvider};
 use anyhow::Result;
 use tracing::info;
@@ -27,6 +35,10 @@ use tracing::info;
 /// - `project_id`: GCP project ID
 /// - `auth_type`: Authentication type (currently only WorkloadIdentity is supported)
 /// - `service_account_email`: Optional service account email for Workload Identity
+/// - `impersonation`: Optional service account to impersonate with the Workload Identity token
+/// - `version_retention`: Optional policy for destroying superseded versions after writes
+/// - `endpoint`: Optional API base URL override (the resource's `endpointOverride.secretManager`,
+///   else the controller configuration)
 ///
 /// # Returns
 /// A boxed `SecretManagerProvider` implementation
@@ -34,10 +46,21 @@ pub async fn create_gcp_provider(
     project_id: String,
     auth_type: Option<&str>,
     service_account_email: Option<&str>,
+    impersonation: Option<&Impersonation>,
+    version_retention: Option<&GcpVersionRetention>,
+    endpoint: Option<&str>,
 ) -> Result<Box<dyn SecretManagerProvider>> {
     info!("Using GCP REST client (native implementation)");
+    let version_retention = version_retention
+        .map(VersionRetention::from_config)
+        .transpose()?;
     Ok(Box::new(
-        SecretManagerREST::new(project_id, auth_type, service_account_email).await?,
+        SecretManagerREST::new(project_id, auth_type, service_account_email)
+            .await?
+            .with_impersonation(impersonation)
+            .await?
+            .with_version_retention(version_retention)
+            .with_base_url(endpoint.map(ToString::to_string)),
     ))
 }
 
@@ -49,6 +72,8 @@ pub async fn create_gcp_provider(
 /// - `project_id`: GCP project ID
 /// - `auth_type`: Authentication type (currently only WorkloadIdentity is supported)
 /// - `service_account_email`: Optional service account email for Workload Identity
+/// - `impersonation`: Optional service account to impersonate with the Workload Identity token
+/// - `endpoint`: Optional API base URL override (`endpointOverride.parameterManager`)
 ///
 /// # Returns
 /// A boxed `ConfigStoreProvider` implementation
@@ -56,9 +81,15 @@ pub async fn create_gcp_parameter_manager_provider(
     project_id: String,
     auth_type: Option<&str>,
     service_account_email: Option<&str>,
+    impersonation: Option<&Impersonation>,
+    endpoint: Option<&str>,
 ) -> Result<Box<dyn ConfigStoreProvider>> {
     info!("Using GCP Parameter Manager REST client (native implementation)");
     Ok(Box::new(
-        ParameterManagerREST::new(project_id, auth_type, service_account_email).await?,
+        ParameterManagerREST::new(project_id, auth_type, service_account_email)
+            .await?
+            .with_impersonation(impersonation)
+            .await?
+            .with_base_url(endpoint.map(ToString::to_string)),
     ))
 }
@@ -8,6 +8,7 @@
 //! This module provides functionality to:
 //! - Create and update parameters in GCP Parameter Manager
 //! - Retrieve parameter values
+//! - List parameters for pruning
 //! - Support Workload Identity authentication
 //!
 //! References:
@@ -19,10 +20,14 @@
 mod requests;
 mod responses;
 
+use crate::observability::redact;
 use crate::provider::ConfigStoreProvider;
+use crate::provider::ProviderError;
+use crate::provider::common::TimedSend;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use base64::{Engine as _, engine::general_purpose};
+use futures::stream::{self, Stream, TryStreamExt};
 use reqwest::Client;
 use tracing::{Instrument, info, info_span};
 
@@ -35,7 +40,10 @@ use responses::{
     ListParametersResponse, Location, Parameter, RenderParameterVersionResponse,
 };
 
-use crate::provider::gcp::client::common::{OperationTracker, determine_operation_type};
+use crate::provider::gcp::client::common::{
+    OperationTracker, PageCursor, determine_operation_type,
+};
+use crate::provider::gcp::impersonation::Impersonation;
 use smc_paths::prelude::{GcpOperation, PathBuilder};
 
 /// GCP Parameter Manager REST client
@@ -139,6 +147,23 @@ impl ParameterManagerREST {
         })
     }
 
+    /// Exchange the Workload Identity token for a token of an impersonated service account
+    /// `None` keeps the Workload Identity token
+    ///
+    /// # Errors
+    /// Returns an error if the IAM Credentials API refuses the exchange
+    pub async fn with_impersonation(
+        mut self,
+        impersonation: Option<&Impersonation>,
+    ) -> Result<Self> {
+        if let Some(impersonation) = impersonation {
+            self.access_token = impersonation
+                .exchange(&self.http_client, &self.access_token)
+                .await?;
+        }
+        Ok(self)
+    }
+
     /// Get OAuth2 access token for GCP API authentication
     ///
     /// Supports:
@@ -243,13 +268,15 @@ impl ParameterManagerREST {
 
     /// Handle error response from GCP API
     fn handle_error_response(&self, status: reqwest::StatusCode, error_text: String) -> Result<()> {
-        match status.as_u16() {
-            404 => Err(anyhow::anyhow!("Parameter not found: {}", error_text)),
-            403 => Err(anyhow::anyhow!("Permission denied: {}", error_text)),
-            401 => Err(anyhow::anyhow!("Unauthorized: {}", error_text)),
-            400 => Err(anyhow::anyhow!("Bad request: {}", error_text)),
-            _ => Err(anyhow::anyhow!("API error ({}): {}", status, error_text)),
-        }
+        let error_text = redact::redact_body(&error_text);
+        let message = match status.as_u16() {
+            404 => format!("Parameter not found: {}", error_text),
+            403 => format!("Permission denied: {}", error_text),
+            401 => format!("Unauthorized: {}", error_text),
+            400 => format!("Bad request: {}", error_text),
+            _ => format!("API error ({}): {}", status, error_text),
+        };
+        Err(ProviderError::from_status(status.as_u16(), message).into())
     }
 
     /// Get project ID
@@ -257,6 +284,20 @@ impl ParameterManagerREST {
         &self.project_id
     }
 
+    /// Override the API base URL (e.g. a Private Service Connect endpoint)
+    /// `None` keeps the default (or Pact mock server) endpoint
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: Option<String>) -> Self {
+        if let Some(base_url) = base_url {
+            info!(
+                "Using GCP Parameter Manager endpoint override: {}",
+                base_url
+            );
+            self.base_url = base_url;
+        }
+        self
+    }
+
     /// Get HTTP client (for testing)
     pub fn http_client(&self) -> &Client {
         &self.http_client
@@ -309,7 +350,7 @@ impl ParameterManagerREST {
                 .context("Failed to build parameter path")?;
             let response = self_ref
                 .make_request("GET", &parameter_path, None)
-                .send()
+                .send_timed("gcp", "get_parameter")
                 .await
                 .context("Failed to get parameter")?;
 
@@ -341,6 +382,8 @@ impl ParameterManagerREST {
     }
 
     /// List all parameters in the project and location
+    /// Follows `nextPageToken` across pages (capped at `MAX_LIST_PAGES`); the returned
+    /// response holds every parameter and no page token
     pub async fn list_parameters(&self) -> Result<ListParametersResponse> {
         let span = info_span!(
             "gcp.parameter.list",
@@ -348,53 +391,90 @@ impl ParameterManagerREST {
             location.id = self.location()
         );
         let span_clone = span.clone();
-        let project_id = self.project_id().to_string();
-        let location = self.location.clone();
-        let http_client = self.http_client().clone();
-        let base_url = self.base_url().to_string();
-        let access_token = self.access_token().to_string();
 
         async move {
             let tracker = OperationTracker::new(span_clone.clone());
-            let self_ref = ParameterManagerREST {
-                http_client,
-                base_url,
-                project_id: project_id.clone(),
-                location: location.clone(),
-                access_token,
-            };
-
-            let parameters_path = self_ref
-                .build_parameter_parent_path(GcpOperation::ListParameters)
-                .context("Failed to build parameter parent path")?;
-            let response = self_ref
-                .make_request("GET", &parameters_path, None)
-                .send()
-                .await
-                .context("Failed to list parameters")?;
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_text = response.text().await.unwrap_or_default();
-                tracker.record_error(Some("list_parameters"), &error_text);
-                return Err(self_ref
-                    .handle_error_response(status, error_text)
-                    .context("Failed to list GCP parameters")
-                    .unwrap_err());
+            let mut parameters = Vec::new();
+            let mut cursor = PageCursor::new();
+
+            loop {
+                let page = match self.list_parameters_page(cursor.token()).await {
+                    Ok(page) => page,
+                    Err(e) => {
+                        tracker.record_error(Some("list_parameters"), &e.to_string());
+                        return Err(e);
+                    }
+                };
+                parameters.extend(page.parameters);
+                if !cursor
+                    .advance(page.next_page_token)
+                    .context("Failed to list GCP parameters")?
+                {
+                    break;
+                }
             }
 
-            let list_response: ListParametersResponse = response
-                .json()
-                .await
-                .context("Failed to deserialize parameters list response")?;
-
             tracker.record_success("list_parameters");
-            Ok(list_response)
+            Ok(ListParametersResponse {
+                parameters,
+                next_page_token: None,
+            })
         }
         .instrument(span)
         .await
     }
 
+    /// Stream parameters page by page
+    /// Use instead of `list_parameters` for very large projects to avoid holding every
+    /// parameter in memory; pages are fetched lazily as the stream is polled
+    pub fn list_parameters_stream(&self) -> impl Stream<Item = Result<Parameter>> + '_ {
+        stream::try_unfold(Some(PageCursor::new()), move |cursor| async move {
+            let Some(mut cursor) = cursor else {
+                return Ok(None);
+            };
+            let page = self.list_parameters_page(cursor.token()).await?;
+            let next = cursor
+                .advance(page.next_page_token)
+                .context("Failed to list GCP parameters")?
+                .then_some(cursor);
+            Ok::<_, anyhow::Error>(Some((page.parameters, next)))
+        })
+        .map_ok(|parameters| stream::iter(parameters.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Fetch a single page of parameters
+    async fn list_parameters_page(
+        &self,
+        page_token: Option<&str>,
+    ) -> Result<ListParametersResponse> {
+        let parameters_path = self
+            .build_parameter_parent_path(GcpOperation::ListParameters)
+            .context("Failed to build parameter parent path")?;
+        let mut request = self.make_request("GET", &parameters_path, None);
+        if let Some(token) = page_token {
+            request = request.query(&[("pageToken", token)]);
+        }
+        let response = request
+            .send_timed("gcp", "list_parameters")
+            .await
+            .context("Failed to list parameters")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(self
+                .handle_error_response(status, error_text)
+                .context("Failed to list GCP parameters")
+                .unwrap_err());
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to deserialize parameters list response")
+    }
+
     /// Update a parameter's metadata (format, labels)
     pub async fn update_parameter(
         &self,
@@ -433,7 +513,7 @@ impl ParameterManagerREST {
                     &parameter_path,
                     Some(serde_json::to_value(&update_request)?),
                 )
-                .send()
+                .send_timed("gcp", "update_parameter")
                 .await
                 .context("Failed to update parameter")?;
 
@@ -501,7 +581,7 @@ impl ParameterManagerREST {
                 .context("Failed to build parameter version path")?;
             let response = self_ref
                 .make_request("GET", &version_path, None)
-                .send()
+                .send_timed("gcp", "get_parameter_version")
                 .await
                 .context("Failed to get parameter version")?;
 
@@ -536,6 +616,7 @@ impl ParameterManagerREST {
     }
 
     /// List all versions of a parameter
+    /// Follows `nextPageToken` across pages (capped at `MAX_LIST_PAGES`)
     pub async fn list_versions(
         &self,
         parameter_name: &str,
@@ -547,54 +628,59 @@ impl ParameterManagerREST {
             location.id = self.location()
         );
         let span_clone = span.clone();
-        let project_id = self.project_id().to_string();
-        let location = self.location.clone();
-        let http_client = self.http_client().clone();
-        let base_url = self.base_url().to_string();
-        let access_token = self.access_token().to_string();
 
         async move {
             let tracker = OperationTracker::new(span_clone.clone());
-            let self_ref = ParameterManagerREST {
-                http_client,
-                base_url,
-                project_id: project_id.clone(),
-                location: location.clone(),
-                access_token,
-            };
-
-            let versions_path = self_ref
+            let versions_path = self
                 .build_parameter_versions_parent_path(
                     GcpOperation::ListParameterVersions,
                     parameter_name,
                 )
                 .context("Failed to build parameter versions parent path")?;
-            let response = self_ref
-                .make_request("GET", &versions_path, None)
-                .send()
-                .await
-                .context("Failed to list parameter versions")?;
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_text = response.text().await.unwrap_or_default();
-                tracker.record_error(Some("list_versions"), &error_text);
-                return Err(self_ref
-                    .handle_error_response(status, error_text)
-                    .context(format!(
-                        "Failed to list versions for GCP parameter: {}",
-                        parameter_name
-                    ))
-                    .unwrap_err());
-            }
+            let mut versions = Vec::new();
+            let mut cursor = PageCursor::new();
+            loop {
+                let mut request = self.make_request("GET", &versions_path, None);
+                if let Some(token) = cursor.token() {
+                    request = request.query(&[("pageToken", token)]);
+                }
+                let response = request
+                    .send_timed("gcp", "list_parameter_versions")
+                    .await
+                    .context("Failed to list parameter versions")?;
 
-            let list_response: ListParameterVersionsResponse = response
-                .json()
-                .await
-                .context("Failed to deserialize versions list response")?;
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let error_text = response.text().await.unwrap_or_default();
+                    tracker.record_error(Some("list_versions"), &error_text);
+                    return Err(self
+                        .handle_error_response(status, error_text)
+                        .context(format!(
+                            "Failed to list versions for GCP parameter: {}",
+                            parameter_name
+                        ))
+                        .unwrap_err());
+                }
+
+                let page: ListParameterVersionsResponse = response
+                    .json()
+                    .await
+                    .context("Failed to deserialize versions list response")?;
+                versions.extend(page.versions);
+
+                if !cursor.advance(page.next_page_token).context(format!(
+                    "Failed to list versions for GCP parameter: {parameter_name}"
+                ))? {
+                    break;
+                }
+            }
 
             tracker.record_success("list_versions");
-            Ok(list_response)
+            Ok(ListParameterVersionsResponse {
+                versions,
+                next_page_token: None,
+            })
         }
         .instrument(span)
         .await
@@ -644,7 +730,7 @@ impl ParameterManagerREST {
                     &version_path,
                     Some(serde_json::to_value(&update_request)?),
                 )
-                .send()
+                .send_timed("gcp", "update_parameter_version")
                 .await
                 .context("Failed to update parameter version")?;
 
@@ -708,7 +794,7 @@ impl ParameterManagerREST {
                 .context("Failed to build parameter version path")?;
             let response = self_ref
                 .make_request("DELETE", &version_path, None)
-                .send()
+                .send_timed("gcp", "delete_parameter_version")
                 .await
                 .context("Failed to delete parameter version")?;
 
@@ -737,7 +823,7 @@ impl ParameterManagerREST {
         .await
     }
 
-    /// Render a parameter version (get decoded value)
+    /// Render a parameter version (decoded value with secret references resolved)
     pub async fn render_version(
         &self,
         parameter_name: &str,
@@ -778,7 +864,7 @@ impl ParameterManagerREST {
                 .context("Failed to build render parameter version path")?;
             let response = self_ref
                 .make_request("GET", &render_path, None)
-                .send()
+                .send_timed("gcp", "render_parameter_version")
                 .await
                 .context("Failed to render parameter version")?;
 
@@ -805,8 +891,14 @@ impl ParameterManagerREST {
                 .await
                 .context("Failed to deserialize render response")?;
 
+            let rendered = general_purpose::STANDARD
+                .decode(&render_response.rendered_payload)
+                .context("Failed to decode rendered parameter payload")?;
+            let value = String::from_utf8(rendered)
+                .context("Failed to convert rendered parameter payload to string")?;
+
             tracker.record_success("render_version");
-            Ok(Some(render_response.rendered_value))
+            Ok(Some(value))
         }
         .instrument(span)
         .await
@@ -843,7 +935,7 @@ impl ParameterManagerREST {
                 .context("Failed to build get location path")?;
             let response = self_ref
                 .make_request("GET", &location_path, None)
-                .send()
+                .send_timed("gcp", "get_location")
                 .await
                 .context("Failed to get location")?;
 
@@ -875,52 +967,60 @@ impl ParameterManagerREST {
     }
 
     /// List all available locations
+    /// Follows `nextPageToken` across pages (capped at `MAX_LIST_PAGES`)
     pub async fn list_locations(&self) -> Result<ListLocationsResponse> {
         let span = info_span!("gcp.location.list", project.id = self.project_id());
         let span_clone = span.clone();
-        let project_id = self.project_id().to_string();
-        let http_client = self.http_client().clone();
-        let base_url = self.base_url().to_string();
-        let access_token = self.access_token().to_string();
 
         async move {
             let tracker = OperationTracker::new(span_clone.clone());
-            let self_ref = ParameterManagerREST {
-                http_client,
-                base_url,
-                project_id: project_id.clone(),
-                location: "global".to_string(), // Not used for location endpoints
-                access_token,
-            };
-
             let locations_path = PathBuilder::new()
                 .gcp_operation(GcpOperation::ListLocations)
-                .project(&project_id)
+                .project(self.project_id())
                 .build_http_path()
                 .context("Failed to build list locations path")?;
-            let response = self_ref
-                .make_request("GET", &locations_path, None)
-                .send()
-                .await
-                .context("Failed to list locations")?;
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_text = response.text().await.unwrap_or_default();
-                tracker.record_error(Some("list_locations"), &error_text);
-                return Err(self_ref
-                    .handle_error_response(status, error_text)
-                    .context("Failed to list GCP locations")
-                    .unwrap_err());
-            }
+            let mut locations = Vec::new();
+            let mut cursor = PageCursor::new();
+            loop {
+                let mut request = self.make_request("GET", &locations_path, None);
+                if let Some(token) = cursor.token() {
+                    request = request.query(&[("pageToken", token)]);
+                }
+                let response = request
+                    .send_timed("gcp", "list_locations")
+                    .await
+                    .context("Failed to list locations")?;
 
-            let list_response: ListLocationsResponse = response
-                .json()
-                .await
-                .context("Failed to deserialize locations list response")?;
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let error_text = response.text().await.unwrap_or_default();
+                    tracker.record_error(Some("list_locations"), &error_text);
+                    return Err(self
+                        .handle_error_response(status, error_text)
+                        .context("Failed to list GCP locations")
+                        .unwrap_err());
+                }
+
+                let page: ListLocationsResponse = response
+                    .json()
+                    .await
+                    .context("Failed to deserialize locations list response")?;
+                locations.extend(page.locations);
+
+                if !cursor
+                    .advance(page.next_page_token)
+                    .context("Failed to list GCP locations")?
+                {
+                    break;
+                }
+            }
 
             tracker.record_success("list_locations");
-            Ok(list_response)
+            Ok(ListLocationsResponse {
+                locations,
+                next_page_token: None,
+            })
         }
         .instrument(span)
         .await
@@ -969,7 +1069,7 @@ impl ConfigStoreProvider for ParameterManagerREST {
                             .context("Failed to build parameter parent path")?,
                         Some(serde_json::to_value(&create_request)?),
                     )
-                    .send()
+                    .send_timed("gcp", "create_parameter")
                     .await
                     .context("Failed to create parameter")?;
 
@@ -985,14 +1085,15 @@ impl ConfigStoreProvider for ParameterManagerREST {
             }
 
             // Check if value changed
-            let operation_type = match determine_operation_type(&existing_parameter, config_value) {
-                Some("no_change") => {
-                    tracker.record_no_change();
-                    return Ok(false);
-                }
-                Some(op) => op,
-                None => "update",
-            };
+            let operation_type =
+                match determine_operation_type(existing_parameter.as_deref(), config_value) {
+                    Some("no_change") => {
+                        tracker.record_no_change();
+                        return Ok(false);
+                    }
+                    Some(op) => op,
+                    None => "update",
+                };
 
             // Add new version with the value
             info!("Adding new version to GCP parameter: {}", config_key);
@@ -1016,7 +1117,7 @@ impl ConfigStoreProvider for ParameterManagerREST {
                         .context("Failed to build parameter versions parent path")?,
                     Some(serde_json::to_value(&add_version_request)?),
                 )
-                .send()
+                .send_timed("gcp", "create_parameter_version")
                 .await
                 .context("Failed to add parameter version")?;
 
@@ -1070,50 +1171,64 @@ impl ConfigStoreProvider for ParameterManagerREST {
             };
 
             // List versions and get the latest one
-            // First, list versions to find the latest
+            // First, list versions (all pages) to find the latest
             let versions_path = self_ref
                 .build_parameter_versions_parent_path(
                     GcpOperation::ListParameterVersions,
                     config_key,
                 )
                 .context("Failed to build parameter versions parent path")?;
-            let response = self_ref
-                .make_request("GET", &versions_path, None)
-                .send()
-                .await
-                .context("Failed to list parameter versions")?;
 
-            if response.status().as_u16() == 404 {
-                tracker.record_success("get");
-                return Ok(None);
-            }
+            let mut versions = Vec::new();
+            let mut cursor = PageCursor::new();
+            loop {
+                let mut request = self_ref.make_request("GET", &versions_path, None);
+                if let Some(token) = cursor.token() {
+                    request = request.query(&[("pageToken", token)]);
+                }
+                let response = request
+                    .send_timed("gcp", "list_parameter_versions")
+                    .await
+                    .context("Failed to list parameter versions")?;
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_text = response.text().await.unwrap_or_default();
-                tracker.record_error(None, &error_text);
-                return Err(self_ref
-                    .handle_error_response(status, error_text)
-                    .context(format!(
-                        "Failed to list versions for GCP parameter: {}",
-                        config_key
-                    ))
-                    .unwrap_err());
-            }
+                if response.status().as_u16() == 404 {
+                    tracker.record_success("get");
+                    return Ok(None);
+                }
 
-            // Parse versions list and get the latest
-            let versions_response: ListParameterVersionsResponse = response
-                .json()
-                .await
-                .context("Failed to deserialize versions list")?;
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let error_text = response.text().await.unwrap_or_default();
+                    tracker.record_error(None, &error_text);
+                    return Err(self_ref
+                        .handle_error_response(status, error_text)
+                        .context(format!(
+                            "Failed to list versions for GCP parameter: {}",
+                            config_key
+                        ))
+                        .unwrap_err());
+                }
+
+                // Parse versions page
+                let page: ListParameterVersionsResponse = response
+                    .json()
+                    .await
+                    .context("Failed to deserialize versions list")?;
+                versions.extend(page.versions);
 
-            if versions_response.versions.is_empty() {
+                if !cursor.advance(page.next_page_token).context(format!(
+                    "Failed to list versions for GCP parameter: {config_key}"
+                ))? {
+                    break;
+                }
+            }
+
+            if versions.is_empty() {
                 return Ok(None);
             }
 
             // Find the latest version (highest createTime or last in list)
-            let latest_version = versions_response
-                .versions
+            let latest_version = versions
                 .iter()
                 .max_by_key(|v| v.create_time.as_deref().unwrap_or(""))
                 .ok_or_else(|| anyhow::anyhow!("No versions found"))?;
@@ -1135,7 +1250,7 @@ impl ConfigStoreProvider for ParameterManagerREST {
                 .context("Failed to build parameter version path")?;
             let response = self_ref
                 .make_request("GET", &version_path, None)
-                .send()
+                .send_timed("gcp", "get_parameter_version")
                 .await
                 .context("Failed to get parameter version")?;
 
@@ -1206,7 +1321,7 @@ impl ConfigStoreProvider for ParameterManagerREST {
 
             let response = self_ref
                 .make_request("DELETE", &path, None)
-                .send()
+                .send_timed("gcp", "delete_parameter")
                 .await
                 .context("Failed to delete parameter")?;
 
@@ -1232,4 +1347,20 @@ impl ConfigStoreProvider for ParameterManagerREST {
         .instrument(span)
         .await
     }
+
+    // Parameter Manager has no bulk read of values, so `bulk_upsert` keeps the
+    // default per-key implementation
+    async fn list_configs(&self, prefix: &str) -> Result<Vec<String>> {
+        let list_response = self.list_parameters().await?;
+        // Parameter name format: projects/{project}/locations/{location}/parameters/{parameter}
+        let mut keys: Vec<String> = list_response
+            .parameters
+            .iter()
+            .filter_map(|parameter| parameter.name.rsplit('/').next())
+            .filter(|key| key.starts_with(prefix))
+            .map(ToString::to_string)
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
 }
@@ -120,8 +120,10 @@ pub struct CreateParameterVersionResponse {
 ///
 /// API Reference: https://docs.cloud.google.com/secret-manager/parameter-manager/docs/reference/rest/v1/projects.locations.parameters.versions/list
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ListParameterVersionsResponse {
-    /// List of parameter versions
+    /// List of parameter versions (omitted by the API when empty)
+    #[serde(default)]
     pub versions: Vec<ParameterVersionListItem>,
     /// Token to retrieve the next page of results
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -130,6 +132,7 @@ pub struct ListParameterVersionsResponse {
 
 /// Parameter version list item
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ParameterVersionListItem {
     /// The resource name of the parameter version
     pub name: String,
@@ -148,8 +151,10 @@ pub struct ParameterVersionListItem {
 ///
 /// API Reference: https://docs.cloud.google.com/secret-manager/parameter-manager/docs/reference/rest/v1/projects.locations.parameters/list
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ListParametersResponse {
-    /// List of parameters
+    /// List of parameters (omitted by the API when empty)
+    #[serde(default)]
     pub parameters: Vec<Parameter>,
     /// Token to retrieve the next page of results
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -163,11 +168,15 @@ pub struct ListParametersResponse {
 ///
 /// API Reference: https://docs.cloud.google.com/secret-manager/parameter-manager/docs/reference/rest/v1/projects.locations.parameters.versions/render
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)] // Fields are part of API contract, may be used in future
 pub struct RenderParameterVersionResponse {
-    /// The rendered parameter value
-    ///
-    /// This is the decoded parameter value, ready for use.
-    pub rendered_value: String,
+    /// The resource name of the rendered parameter version
+    pub parameter_version: String,
+    /// The stored payload, with secret references unresolved
+    pub payload: ParameterPayload,
+    /// Base64-encoded payload with `__REF__(...)` secret references resolved
+    pub rendered_payload: String,
 }
 
 /// Location resource representation
@@ -194,8 +203,10 @@ pub struct Location {
 ///
 /// API Reference: https://docs.cloud.google.com/secret-manager/parameter-manager/docs/reference/rest/v1/projects.locations/list
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ListLocationsResponse {
-    /// List of locations
+    /// List of locations (omitted by the API when empty)
+    #[serde(default)]
     pub locations: Vec<Location>,
     /// Token to retrieve the next page of results
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -6,8 +6,12 @@
 //! - `SecretManagerProvider` trait for secret stores
 //! - `ConfigStoreProvider` trait for config stores
 
-use anyhow::Result;
+use crate::secret::SecretString;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
 /// Provider trait for cloud secret managers
 #[async_trait]
@@ -23,13 +27,58 @@ pub trait SecretManagerProvider: Send + Sync {
     async fn create_or_update_secret(
         &self,
         secret_name: &str,
-        secret_value: &str,
+        secret_value: &SecretString,
         environment: &str,
         location: &str,
     ) -> Result<bool>;
 
     /// Get the latest secret value
-    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<String>>;
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>>;
+
+    /// Create or update a secret with a binary payload (certificates, keystores, p12 bundles)
+    /// Returns true if secret was created/updated, false if no change was needed
+    ///
+    /// The default implementation stores the payload base64-encoded as a string secret.
+    /// Providers with native binary support override this.
+    async fn create_or_update_secret_binary(
+        &self,
+        secret_name: &str,
+        secret_value: &[u8],
+        environment: &str,
+        location: &str,
+    ) -> Result<bool> {
+        let encoded = SecretString::new(general_purpose::STANDARD.encode(secret_value));
+        self.create_or_update_secret(secret_name, &encoded, environment, location)
+            .await
+    }
+
+    /// Get the latest secret value as raw bytes
+    ///
+    /// The default implementation decodes the base64 string written by the default
+    /// `create_or_update_secret_binary` implementation.
+    async fn get_secret_binary(&self, secret_name: &str) -> Result<Option<Vec<u8>>> {
+        match self.get_secret_value(secret_name).await? {
+            Some(encoded) => Ok(Some(
+                general_purpose::STANDARD
+                    .decode(encoded.expose_secret())
+                    .context(format!("Secret {secret_name} is not valid base64"))?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Current values of several secrets, read in as few calls as the provider allows
+    /// Missing secrets map to None. Names left out of the result could not be read in bulk
+    /// (e.g. disabled, or a per-secret error) and are read one at a time by the caller.
+    ///
+    /// The default implementation reads nothing in bulk. Providers with batch reads (AWS
+    /// `BatchGetSecretValue`) or filtered listing (GCP) override this.
+    async fn get_secret_values(
+        &self,
+        _secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        Ok(HashMap::new())
+    }
 
     /// Delete a secret (optional - may not be supported by all providers)
     async fn delete_secret(&self, secret_name: &str) -> Result<()>;
@@ -41,6 +90,101 @@ pub trait SecretManagerProvider: Send + Sync {
     /// Enable a secret (makes it accessible again)
     /// Returns true if secret was enabled, false if it was already enabled or doesn't exist
     async fn enable_secret(&self, secret_name: &str) -> Result<bool>;
+
+    /// List secret names in this store that start with `prefix` (empty prefix lists all)
+    /// Names are returned in the form accepted by `get_secret_value`
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Get a secret's metadata without reading its value
+    /// Returns None if the secret doesn't exist
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>>;
+
+    /// Current and previous version IDs of a secret
+    /// Used by the audit log after a write. Providers without version IDs keep the default,
+    /// which reports neither.
+    async fn version_ids(&self, _secret_name: &str) -> Result<SecretVersionIds> {
+        Ok(SecretVersionIds::default())
+    }
+
+    /// Merge `labels` into a secret's labels (GCP labels, AWS tags, Azure tags of the
+    /// current version); labels not in `labels` are kept
+    /// Used to stamp the last writer on a secret. Providers without labels keep the default,
+    /// which stores nothing.
+    async fn set_secret_labels(
+        &self,
+        _secret_name: &str,
+        _labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Set the expiry of a secret (Azure Key Vault `exp` of the current version, AWS tag
+    /// `secret-manager-expires-at`, GCP `expireTime`)
+    /// Used by `secrets.expiration`. Providers without expiry keep the default, which stores
+    /// nothing.
+    async fn set_secret_expiration(
+        &self,
+        _secret_name: &str,
+        _expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Audit recorder when this provider is wrapped by the audit log
+    /// Lets callers that bypass the provider (config stores) record their own writes
+    fn auditor(&self) -> Option<&crate::observability::audit::Auditor> {
+        None
+    }
+
+    /// Rate limit bucket when this provider is wrapped by the per-resource rate limiter
+    /// Lets callers that bypass the provider (config stores) wait on the same budget
+    fn rate_limiter(&self) -> Option<&crate::controller::reconciler::rate_limit::TokenBucket> {
+        None
+    }
+
+    /// Change planner when this provider only plans writes (`approvalPolicy: Manual`)
+    /// Lets callers that bypass the provider (config stores) plan instead of writing
+    fn planner(&self) -> Option<&crate::controller::reconciler::approval::ChangePlanner> {
+        None
+    }
+
+    /// Key router when this provider sends some keys to other stores (`secrets.routes`)
+    /// Lets the sync loops bind each secret name to its source key before writing it
+    fn router(&self) -> Option<&crate::controller::reconciler::routing::KeyRouter> {
+        None
+    }
+
+    /// Last-writer tracker when this provider stamps its writes (`ConflictDetection` gate)
+    /// Lets diff discovery tell foreign writes from older writes of this resource
+    fn last_writer(
+        &self,
+    ) -> Option<&crate::controller::reconciler::last_writer::LastWriterTracker> {
+        None
+    }
+}
+
+/// Version IDs of a secret around its latest write
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecretVersionIds {
+    /// Version that was current before the latest write
+    pub previous: Option<String>,
+    /// Version that is current now
+    pub current: Option<String>,
+}
+
+/// Metadata of a secret, without its value
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecretMetadata {
+    /// Secret name, as accepted by `get_secret_value`
+    pub name: String,
+    /// Provider labels (GCP labels, AWS tags, Azure tags)
+    pub labels: HashMap<String, String>,
+    /// When the secret was created, if the provider reports it
+    pub create_time: Option<DateTime<Utc>>,
+    /// Number of versions the provider keeps for the secret
+    pub version_count: usize,
+    /// When the secret expires, if an expiry is set
+    pub expire_time: Option<DateTime<Utc>>,
 }
 
 /// Provider trait for cloud config stores
@@ -56,11 +200,43 @@ pub trait ConfigStoreProvider: Send + Sync {
 
     /// Delete a config value (optional - may not be supported by all providers)
     async fn delete_config(&self, config_key: &str) -> Result<()>;
+
+    /// List config keys in this store that start with `prefix` (empty prefix lists all)
+    /// Keys are returned in the form accepted by `create_or_update_config`, so the
+    /// reconciler can diff them against Git and prune keys that were removed
+    async fn list_configs(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Create or update a set of config values
+    /// Returns, per key, true if the config was created/updated and false if no change was needed
+    ///
+    /// The default implementation writes keys one at a time. Providers with bulk reads
+    /// override this to fetch current values once and only write keys that changed.
+    async fn bulk_upsert(&self, entries: Vec<(String, String)>) -> Result<HashMap<String, bool>> {
+        let mut results = HashMap::with_capacity(entries.len());
+        for (config_key, config_value) in entries {
+            let was_updated = self
+                .create_or_update_config(&config_key, &config_value)
+                .await
+                .context(format!("Failed to store config: {config_key}"))?;
+            results.insert(config_key, was_updated);
+        }
+        Ok(results)
+    }
+
+    /// Storage tier a config value is written with, for stores with tiers
+    /// (AWS Parameter Store); reported in status.sync.properties
+    fn storage_tier(&self, _config_key: &str, _config_value: &str) -> Option<String> {
+        None
+    }
 }
 
 // Common utilities shared across providers
 pub mod common;
 
+// Classified provider API errors
+pub mod error;
+pub use error::ProviderError;
+
 // Provider implementations
 pub mod aws;
 pub mod azure;
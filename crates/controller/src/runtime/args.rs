@@ -0,0 +1,95 @@
+//! # Command-Line Arguments
+//!
+//! Command-line flags for the controller binary.
+//!
+//! Flags override the matching setting from the environment, config file, or ConfigMap.
+//! Settings without a flag are left to those sources.
+//!
+//! `crd generate` prints the CRD generated from the compiled types and exits without starting
+//! the controller.
+
+use crate::config::ControllerConfig;
+use clap::{Parser, Subcommand};
+
+/// Secret Manager Controller command-line arguments
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "secret-manager-controller")]
+#[command(about = "Kubernetes controller that syncs secrets from GitOps repositories to cloud secret stores", long_about = None)]
+pub struct ControllerArgs {
+    /// Maximum number of SecretManagerConfig resources reconciled concurrently (0 = unbounded)
+    /// Overrides MAX_CONCURRENT_RECONCILIATIONS
+    #[arg(long, value_name = "N")]
+    pub max_concurrent_reconciles: Option<usize>,
+
+    /// Maximum number of concurrent reconciliations per cloud provider (0 = unbounded)
+    /// Overrides MAX_CONCURRENT_PROVIDER_RECONCILIATIONS
+    #[arg(long, value_name = "N")]
+    pub max_concurrent_provider_reconciles: Option<usize>,
+
+    #[command(subcommand)]
+    pub command: Option<ControllerCommand>,
+}
+
+/// One-off commands run instead of the controller
+#[derive(Debug, Clone, Subcommand)]
+pub enum ControllerCommand {
+    /// CustomResourceDefinition commands
+    Crd {
+        #[command(subcommand)]
+        command: CrdCommand,
+    },
+}
+
+/// `crd` subcommands
+#[derive(Debug, Clone, Subcommand)]
+pub enum CrdCommand {
+    /// Print the SecretManagerConfig CRD generated from the compiled schema
+    /// Pipe to `kubectl apply -f -` or redirect to config/crd/secretmanagerconfig.yaml
+    Generate,
+}
+
+impl ControllerArgs {
+    /// Controller runtime concurrency, preferring the flag over the configured value
+    pub fn max_concurrent_reconciles(&self, config: &ControllerConfig) -> usize {
+        self.max_concurrent_reconciles
+            .unwrap_or(config.max_concurrent_reconciliations)
+    }
+
+    /// Per-provider concurrency, preferring the flag over the configured value
+    pub fn max_concurrent_provider_reconciles(&self, config: &ControllerConfig) -> usize {
+        self.max_concurrent_provider_reconciles
+            .unwrap_or(config.max_concurrent_provider_reconciliations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_override_config() {
+        let config = ControllerConfig::default();
+        let args = ControllerArgs::parse_from([
+            "secret-manager-controller",
+            "--max-concurrent-reconciles",
+            "50",
+        ]);
+        assert_eq!(args.max_concurrent_reconciles(&config), 50);
+        assert_eq!(
+            args.max_concurrent_provider_reconciles(&config),
+            config.max_concurrent_provider_reconciliations
+        );
+        assert!(args.command.is_none());
+    }
+
+    #[test]
+    fn test_crd_generate_subcommand() {
+        let args = ControllerArgs::parse_from(["secret-manager-controller", "crd", "generate"]);
+        assert!(matches!(
+            args.command,
+            Some(ControllerCommand::Crd {
+                command: CrdCommand::Generate
+            })
+        ));
+    }
+}
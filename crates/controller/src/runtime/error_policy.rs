@@ -48,9 +48,13 @@ pub fn handle_reconciliation_error(
                 .or_insert_with(|| BackoffState {
                     backoff: FibonacciBackoff::new(1, 10), // 1 minute min, 10 minutes max
                     error_count: 0,
+                    next_retry_time: None,
                 });
             state.increment_error();
             let backoff = state.backoff.next_backoff_seconds();
+            state.next_retry_time = Some(
+                chrono::Utc::now() + chrono::Duration::seconds(i64::try_from(backoff).unwrap_or(0)),
+            );
             let error_count = state.error_count;
             (backoff, error_count)
         }
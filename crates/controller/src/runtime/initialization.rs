@@ -4,16 +4,25 @@
 //! tracing, metrics, server startup, and Kubernetes client setup.
 
 use crate::config::{
-    SharedControllerConfig, SharedServerConfig, create_shared_config, start_configmap_watch,
+    CONFIG_FILE_ENV, SharedControllerConfig, SharedServerConfig, create_shared_config,
+    start_config_file_watch, start_configmap_watch,
 };
-use crate::controller::reconciler::{Reconciler, TriggerSource, reconcile};
+use crate::controller::reconciler::Reconciler;
+use crate::controller::reconciler::artifact::download::ExtractionLimits;
 use crate::controller::server::{ServerState, start_server};
 use crate::crd::SecretManagerConfig;
 use crate::observability;
+use crate::observability::log_correlation::DatadogLogFormat;
+use crate::observability::resource_logs::{ResourceLogBuffer, ResourceLogLayer};
+use crate::runtime::args::ControllerArgs;
+use crate::runtime::schedule::ReconcileSchedule;
+use crate::runtime::startup::spawn_startup_queue;
 use anyhow::{Context, Result};
 use kube::{Client, api::Api, api::ListParams};
 use std::sync::Arc;
 use tracing::{error, info, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
 /// Initialization result containing all necessary components for the controller
 pub struct InitializationResult {
@@ -25,6 +34,8 @@ pub struct InitializationResult {
     pub reconciler: Arc<Reconciler>,
     /// Server state for health checks
     pub server_state: Arc<ServerState>,
+    /// Reconcile schedule shared by the startup queue and the watch loop
+    pub schedule: Arc<ReconcileSchedule>,
     /// OpenTelemetry tracer provider (if initialized)
     pub otel_tracer_provider: Option<crate::observability::otel::TracerProviderHandle>,
     /// Shared controller configuration (hot-reloadable)
@@ -58,8 +69,8 @@ impl std::fmt::Debug for InitializationResult {
 /// - Kubernetes client creation
 /// - Reconciler setup
 /// - SOPS key watch
-/// - Reconcile existing resources
-pub async fn initialize() -> Result<InitializationResult> {
+/// - Queue existing resources for background reconciliation
+pub async fn initialize(args: &ControllerArgs) -> Result<InitializationResult> {
     // Configure rustls crypto provider FIRST, before any other operations
     // Required for rustls 0.23+ when no default provider is set via features
     // This must be called synchronously before any async operations that use rustls
@@ -75,29 +86,43 @@ pub async fn initialize() -> Result<InitializationResult> {
 
     // Initialize OpenTelemetry first (if configured)
     // This will set up tracing with Otel support
-    // Note: Otel config can come from CRD, but we initialize early from env vars
-    // Per-resource Otel config is handled in the reconciler
+    // The exporter is configured from env vars; each resource's spec.otel sets the
+    // sampling ratio and extra attributes of its own reconcile spans (see reconcile())
     let otel_tracer_provider =
         observability::otel::init_otel(None).context("Failed to initialize OpenTelemetry")?;
 
+    // Per-resource log events for the resources API; sized from the controller config below
+    let resource_logs = Arc::new(ResourceLogBuffer::new(
+        crate::constants::DEFAULT_RESOURCE_LOG_BUFFER_SIZE,
+    ));
+
     // If Otel wasn't initialized, use standard tracing subscriber
-    // When Datadog is configured, datadog-opentelemetry sets up the tracing subscriber automatically
+    // Otherwise the subscriber also exports spans through the Otel tracer (see below)
     if otel_tracer_provider.is_none() {
         tracing_subscriber::fmt()
             .with_env_filter(
                 tracing_subscriber::EnvFilter::try_from_default_env()
                     .unwrap_or_else(|_| "secret_manager_controller=info".into()),
             )
+            .finish()
+            .with(ResourceLogLayer::new(resource_logs.clone()))
             .init();
     } else {
-        // When Otel is initialized, we still need to set up the tracing subscriber
-        // datadog-opentelemetry handles this automatically, but we ensure env filter is applied
-        // The tracing-opentelemetry layer is already set up by datadog-opentelemetry
+        // When Otel is initialized, bridge tracing spans to the exporter's tracer and
+        // append Datadog trace/span IDs to log lines so Datadog links logs to traces
+        let otel_layer = otel_tracer_provider
+            .as_ref()
+            .and_then(observability::otel::TracerProviderHandle::tracer)
+            .map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer));
         if let Err(e) = tracing_subscriber::fmt()
             .with_env_filter(
                 tracing_subscriber::EnvFilter::try_from_default_env()
                     .unwrap_or_else(|_| "secret_manager_controller=info".into()),
             )
+            .event_format(DatadogLogFormat::default())
+            .finish()
+            .with(otel_layer)
+            .with(ResourceLogLayer::new(resource_logs.clone()))
             .try_init()
         {
             // If init fails, it might already be initialized by datadog-opentelemetry
@@ -119,14 +144,27 @@ pub async fn initialize() -> Result<InitializationResult> {
 
     // Initialize metrics
     observability::metrics::register_metrics()?;
+    observability::metrics::spawn_runtime_metrics_sampler(std::time::Duration::from_secs(
+        crate::constants::RUNTIME_METRICS_INTERVAL_SECS,
+    ));
+
+    // Create shared configuration (hot-reloadable) - must be created before server startup
+    let (controller_config, server_config) = create_shared_config();
+    resource_logs.set_capacity(controller_config.read().await.resource_log_buffer_size);
 
     // Create server state
     let server_state = Arc::new(ServerState {
         is_ready: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        controller_config: controller_config.clone(),
+        server_config: server_config.clone(),
+        resource_store: Arc::new(tokio::sync::RwLock::new(None)),
+        backoff_states: std::sync::OnceLock::new(),
+        api_token: crate::controller::resources_api::load_api_token(),
+        resource_logs,
     });
-
-    // Create shared configuration (hot-reloadable) - must be created before server startup
-    let (controller_config, server_config) = create_shared_config();
+    if server_state.api_token.is_none() {
+        info!("Resources API disabled (RESOURCES_API_TOKEN not set)");
+    }
 
     // Start HTTP server for metrics and probes
     // We start it in a background task but wait for it to be ready before proceeding
@@ -156,18 +194,65 @@ pub async fn initialize() -> Result<InitializationResult> {
     let configs: Api<SecretManagerConfig> = Api::all(client.clone());
 
     // Create reconciler context
-    let reconciler = Arc::new(Reconciler::new(client.clone()).await?);
+    // Per-provider concurrency is fixed for the lifetime of the process
+    let provider_concurrency =
+        args.max_concurrent_provider_reconciles(&*controller_config.read().await);
+    info!(
+        "Per-provider reconcile concurrency: {}",
+        if provider_concurrency == 0 {
+            "unbounded".to_string()
+        } else {
+            provider_concurrency.to_string()
+        }
+    );
+    let project_concurrency = controller_config
+        .read()
+        .await
+        .max_concurrent_project_reconciliations;
+    if project_concurrency > 0 {
+        info!("Per-project reconcile concurrency: {}", project_concurrency);
+    }
+    // Artifact cache size and audit sink are also fixed at startup
+    // (the audit hash chain must have a single writer)
+    let artifact_cache_max_entries = controller_config.read().await.artifact_cache_max_entries;
+    let artifact_limits = {
+        let config = controller_config.read().await;
+        ExtractionLimits {
+            max_extracted_bytes: config.artifact_max_extracted_bytes,
+            max_files: config.artifact_max_files,
+        }
+    };
+    let audit_log =
+        observability::audit::AuditLog::from_config(&controller_config.read().await.audit)?;
+    let reconciler = Arc::new(
+        Reconciler::new(client.clone())
+            .await?
+            .with_provider_concurrency(provider_concurrency)
+            .with_project_concurrency(project_concurrency)
+            .with_artifact_cache_max_entries(artifact_cache_max_entries)
+            .with_artifact_limits(artifact_limits)
+            .with_audit_log(audit_log),
+    );
 
-    // Start watching for SOPS private key secret changes
-    // This allows hot-reloading the key without restarting the controller
-    crate::controller::reconciler::start_sops_key_watch(reconciler.clone());
+    // Expose backoff state to the resources API
+    let _ = server_state
+        .backoff_states
+        .set(reconciler.backoff_states.clone());
 
-    // Check if CRD is queryable and reconcile existing resources before starting the watch
+    // Check if CRD is queryable and queue existing resources for reconciliation
     // This ensures existing resources are reconciled when the controller starts
     // CRITICAL: Without this, resources created before controller deployment won't be reconciled
+    // The queue runs in the background (failed/stale resources first) while the watch starts;
+    // healthy resources keep their persisted schedule
     // Also check for hot-reload configuration in SecretManagerConfig resources
-    let hot_reload_config =
-        reconcile_existing_resources(&configs, &reconciler, controller_config.clone()).await?;
+    let schedule = Arc::new(ReconcileSchedule::new());
+    let hot_reload_config = reconcile_existing_resources(
+        &configs,
+        &reconciler,
+        controller_config.clone(),
+        schedule.clone(),
+    )
+    .await?;
 
     // Start ConfigMap watcher for hot-reload if enabled
     if let Some(hot_reload) = hot_reload_config {
@@ -193,6 +278,23 @@ pub async fn initialize() -> Result<InitializationResult> {
         info!("Hot-reload disabled (default) - configuration changes require pod restart");
     }
 
+    // Poll the config file (or mounted ConfigMap directory) when one is configured
+    if let Some(config_path) = std::env::var(CONFIG_FILE_ENV)
+        .ok()
+        .filter(|path| !path.is_empty())
+    {
+        let poll_interval = controller_config
+            .read()
+            .await
+            .config_file_poll_interval_duration();
+        start_config_file_watch(
+            config_path.into(),
+            poll_interval,
+            controller_config.clone(),
+            server_config.clone(),
+        );
+    }
+
     // Note: GitRepository and ArgoCD Application changes are handled by the main controller watch.
     // When SecretManagerConfig resources are reconciled, they fetch the latest source,
     // ensuring source changes are picked up without restarting the controller.
@@ -209,6 +311,7 @@ pub async fn initialize() -> Result<InitializationResult> {
         controller_config,
         server_config,
         server_state,
+        schedule,
         otel_tracer_provider,
     })
 }
@@ -255,13 +358,15 @@ async fn wait_for_server_ready(
     Ok(())
 }
 
-/// Reconcile existing SecretManagerConfig resources before starting the watch
+/// Queue existing SecretManagerConfig resources for reconciliation
 ///
-/// This ensures resources created before controller deployment are processed.
+/// This ensures resources created before controller deployment are processed. Resources are
+/// reconciled in the background by the startup queue, concurrently with the watch.
 async fn reconcile_existing_resources(
     configs: &Api<SecretManagerConfig>,
     reconciler: &Arc<Reconciler>,
     controller_config: SharedControllerConfig,
+    schedule: Arc<ReconcileSchedule>,
 ) -> Result<Option<crate::crd::HotReloadConfig>> {
     let existing_resources_span = tracing::span!(
         tracing::Level::INFO,
@@ -360,66 +465,13 @@ async fn reconcile_existing_resources(
                     );
                 }
                 info!(
-                    "Reconciling {} existing SecretManagerConfig resources before starting watch...",
+                    "Queueing {} existing SecretManagerConfig resources for startup reconciliation (failed first)...",
                     list.items.len()
                 );
 
-                // Explicitly reconcile each existing resource
-                // This ensures resources created before controller deployment are processed
-                for item in &list.items {
-                    let name = item.metadata.name.as_deref().unwrap_or("unknown");
-                    let namespace = item.metadata.namespace.as_deref().unwrap_or("default");
-
-                    info!(
-                        "Reconciling existing resource: {} in namespace {}",
-                        name, namespace
-                    );
-
-                    // Create a reconciliation span for each resource
-                    let resource_span = tracing::span!(
-                        tracing::Level::INFO,
-                        "controller.startup.reconcile_resource",
-                        resource.name = name,
-                        resource.namespace = namespace,
-                        resource.kind = "SecretManagerConfig"
-                    );
-                    let _resource_guard = resource_span.enter();
-
-                    // Startup reconciliation uses timer-based trigger source
-                    match reconcile(
-                        Arc::new(item.clone()),
-                        reconciler.clone(),
-                        TriggerSource::TimerBased,
-                        controller_config.clone(),
-                    )
-                    .await
-                    {
-                        Ok(_action) => {
-                            info!(
-                                "Successfully reconciled existing resource: {} in namespace {}",
-                                name, namespace
-                            );
-                            info!(
-                                resource.name = name,
-                                resource.namespace = namespace,
-                                "reconciliation.success"
-                            );
-                        }
-                        Err(e) => {
-                            error!(
-                                "Failed to reconcile existing resource {} in namespace {}: {}",
-                                name, namespace, e
-                            );
-                            error!(resource.name = name, resource.namespace = namespace, error = %e, "reconciliation.error");
-                            // Continue with other resources even if one fails
-                        }
-                    }
-                }
-
-                info!(
-                    "Completed reconciliation of {} existing resources",
-                    list.items.len()
-                );
+                // Reconcile existing resources in the background, prioritized and rate-limited,
+                // so the watch starts immediately instead of after a full serial pass
+                spawn_startup_queue(list.items, reconciler.clone(), controller_config, schedule);
             } else {
                 info!(
                     "No existing SecretManagerConfig resources found, watch will pick up new resources"
@@ -1,12 +1,19 @@
 //! # Runtime Module
 //!
-//! Runtime components for the Secret Manager Controller, including initialization,
-//! watch loop, and error handling.
+//! Runtime components for the Secret Manager Controller, including command-line arguments,
+//! initialization, the startup reconcile queue, reconcile schedule, watch loop, and error
+//! handling.
 
+pub mod args;
 pub mod error_policy;
 pub mod initialization;
+pub mod schedule;
+pub mod startup;
 pub mod watch_loop;
 
+pub use args::*;
 pub use error_policy::*;
 pub use initialization::*;
+pub use schedule::*;
+pub use startup::*;
 pub use watch_loop::*;
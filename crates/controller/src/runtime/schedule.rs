@@ -0,0 +1,321 @@
+//! # Reconcile Schedule
+//!
+//! Decides which watch events reach the reconciler, and restores the periodic schedule of
+//! existing resources after a restart.
+//!
+//! Periodic reconciles rely on the `Action::requeue(interval)` returned by each reconcile. A
+//! watch event for the same resource replaces its pending requeue, so status-only updates
+//! (which every reconcile writes) are filtered out of the watch stream: only new resources,
+//! spec changes (generation), new `msmctl reconcile` triggers and approvals reach the
+//! reconciler. Every call that does reach it is reconciled.
+//!
+//! After a restart nothing is scheduled yet. The startup queue reconciles failed and stale
+//! resources; for up-to-date resources the watch loop restores the requeue from the persisted
+//! `status.nextReconcileTime`, capped at the reconcile interval so clock skew can't postpone a
+//! reconcile by more than one interval. A restarted watch loses its requeues too, so it
+//! restores the schedule the same way.
+
+use crate::crd::SecretManagerConfig;
+use crate::runtime::startup::{StartupPriority, startup_priority};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Annotation set by `msmctl reconcile` to request a reconcile
+const RECONCILE_ANNOTATION: &str = "secret-management.octopilot.io/reconcile";
+
+/// Fields of a resource whose changes trigger a reconcile
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TriggerState {
+    uid: Option<String>,
+    generation: Option<i64>,
+    manual_trigger: Option<String>,
+    approval: bool,
+}
+
+impl TriggerState {
+    fn of(config: &SecretManagerConfig) -> Self {
+        Self {
+            uid: config.metadata.uid.clone(),
+            generation: config.metadata.generation,
+            manual_trigger: config
+                .metadata
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.get(RECONCILE_ANNOTATION))
+                .cloned(),
+            approval: crate::controller::reconciler::approval::has_pending_approval(config),
+        }
+    }
+}
+
+/// How the watch loop handles the first call for a resource since the controller started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirstSight {
+    /// Reconcile now
+    Reconcile,
+    /// Skip this call and requeue after the delay (schedule restored, or the startup queue
+    /// reconciles the resource)
+    Requeue(Duration),
+}
+
+/// Schedule state shared by the startup queue and the watch loop
+/// Resources are identified by namespace/name and forgotten when they are deleted.
+#[derive(Debug, Default)]
+pub struct ReconcileSchedule {
+    /// Trigger fields of each resource at its last watch event
+    triggers: Mutex<HashMap<String, TriggerState>>,
+    /// Resources reconciled by the startup queue
+    startup: Mutex<HashSet<String>>,
+    /// Resources the watch loop has reconciled or scheduled since the controller started
+    scheduled: Mutex<HashSet<String>>,
+}
+
+fn resource_key(config: &SecretManagerConfig) -> String {
+    format!(
+        "{}/{}",
+        config.metadata.namespace.as_deref().unwrap_or("default"),
+        config.metadata.name.as_deref().unwrap_or("unknown")
+    )
+}
+
+impl ReconcileSchedule {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a watch event for `config` should reach the reconciler
+    /// True for resources not seen before (or recreated), a new generation, a new
+    /// `msmctl reconcile` trigger and a change set that was just approved. Status-only updates
+    /// and cleared triggers return false, so they don't replace the pending requeue.
+    pub fn should_trigger(&self, config: &SecretManagerConfig) -> bool {
+        let state = TriggerState::of(config);
+        let mut triggers = self
+            .triggers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let trigger = match triggers.get(&resource_key(config)) {
+            None => true,
+            Some(previous) => {
+                previous.uid != state.uid
+                    || previous.generation != state.generation
+                    || (state.manual_trigger.is_some()
+                        && previous.manual_trigger != state.manual_trigger)
+                    || (state.approval && !previous.approval)
+            }
+        };
+        triggers.insert(resource_key(config), state);
+        trigger
+    }
+
+    /// Forget every resource, so the next watch restores their schedule
+    /// Called when the watch restarts, which drops its pending requeues.
+    pub fn reset(&self) {
+        self.triggers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+        self.startup
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+        self.scheduled
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Forget a deleted resource, so its state doesn't outlive it
+    pub fn forget(&self, config: &SecretManagerConfig) {
+        let key = resource_key(config);
+        self.triggers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&key);
+        self.startup
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&key);
+        self.scheduled
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&key);
+    }
+
+    /// Record that the startup queue reconciles `config`
+    pub fn claim_startup(&self, config: &SecretManagerConfig) {
+        self.startup
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(resource_key(config));
+    }
+
+    /// How to handle a call for `config`: `Reconcile` for every call after the first
+    pub fn first_sight(&self, config: &SecretManagerConfig, now: DateTime<Utc>) -> FirstSight {
+        let key = resource_key(config);
+        let first = self
+            .scheduled
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(key.clone());
+        if !first {
+            return FirstSight::Reconcile;
+        }
+        let interval = config.spec.reconcile_interval.to_duration().ok();
+        let claimed = self
+            .startup
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .contains(&key);
+        if claimed {
+            return interval.map_or(FirstSight::Reconcile, FirstSight::Requeue);
+        }
+        restored_requeue(config, now).map_or(FirstSight::Reconcile, FirstSight::Requeue)
+    }
+}
+
+/// Delay until the persisted next reconcile of an up-to-date resource, capped at its interval
+/// None when the resource is not up to date, has no valid schedule, or is due.
+#[must_use]
+pub fn restored_requeue(config: &SecretManagerConfig, now: DateTime<Utc>) -> Option<Duration> {
+    if startup_priority(config, now) != StartupPriority::Healthy {
+        return None;
+    }
+    let next = config
+        .status
+        .as_ref()?
+        .next_reconcile_time
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())?
+        .with_timezone(&Utc);
+    let remaining = (next - now).to_std().ok()?;
+    let interval = config.spec.reconcile_interval.to_duration().ok()?;
+    Some(remaining.min(interval))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::SecretManagerConfigStatus;
+    use crate::testing::ConfigFixture;
+    use serde_json::json;
+
+    fn config(name: &str) -> ConfigFixture {
+        ConfigFixture::new(name)
+            .with_uid("1")
+            .with_generation(2)
+            .with_spec(json!({ "reconcileInterval": "5m" }))
+    }
+
+    fn ready_status(next_reconcile_time: DateTime<Utc>) -> SecretManagerConfigStatus {
+        SecretManagerConfigStatus {
+            phase: Some("Ready".to_string()),
+            observed_generation: Some(2),
+            next_reconcile_time: Some(next_reconcile_time.to_rfc3339()),
+            ..SecretManagerConfigStatus::default()
+        }
+    }
+
+    #[test]
+    fn test_should_trigger_ignores_status_updates() {
+        let schedule = ReconcileSchedule::new();
+        let mut config = config("app").build();
+        assert!(schedule.should_trigger(&config));
+
+        config.status = Some(SecretManagerConfigStatus {
+            phase: Some("Ready".to_string()),
+            ..SecretManagerConfigStatus::default()
+        });
+        assert!(!schedule.should_trigger(&config));
+
+        config
+            .metadata
+            .annotations
+            .get_or_insert_default()
+            .insert(RECONCILE_ANNOTATION.to_string(), "1".to_string());
+        assert!(schedule.should_trigger(&config));
+        config.metadata.annotations = None;
+        assert!(!schedule.should_trigger(&config));
+
+        config.metadata.generation = Some(3);
+        assert!(schedule.should_trigger(&config));
+        config.metadata.uid = Some("2".to_string());
+        assert!(schedule.should_trigger(&config));
+    }
+
+    #[test]
+    fn test_forget_drops_deleted_resources() {
+        let now = Utc::now();
+        let schedule = ReconcileSchedule::new();
+        let config = config("app").build();
+        assert!(schedule.should_trigger(&config));
+        assert_eq!(schedule.first_sight(&config, now), FirstSight::Reconcile);
+
+        schedule.forget(&config);
+        assert!(schedule.triggers.lock().is_ok_and(|t| t.is_empty()));
+        assert!(schedule.scheduled.lock().is_ok_and(|s| s.is_empty()));
+        // Recreated under the same name, it is seen as new
+        assert!(schedule.should_trigger(&config));
+    }
+
+    #[test]
+    fn test_restored_requeue() {
+        let now = Utc::now();
+        let due_in = |config: &SecretManagerConfig| restored_requeue(config, now);
+
+        assert_eq!(
+            due_in(
+                &config("app")
+                    .with_status(ready_status(now + chrono::Duration::minutes(2)))
+                    .build()
+            ),
+            Some(Duration::from_secs(120))
+        );
+        // A schedule beyond one interval (clock skew) is capped at the interval
+        assert_eq!(
+            due_in(
+                &config("app")
+                    .with_status(ready_status(now + chrono::Duration::hours(3)))
+                    .build()
+            ),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(
+            due_in(
+                &config("app")
+                    .with_status(ready_status(now - chrono::Duration::minutes(1)))
+                    .build()
+            ),
+            None
+        );
+        assert_eq!(due_in(&config("app").build()), None);
+    }
+
+    #[test]
+    fn test_first_sight() {
+        let now = Utc::now();
+        let schedule = ReconcileSchedule::new();
+        let healthy = config("healthy")
+            .with_status(ready_status(now + chrono::Duration::minutes(2)))
+            .build();
+        assert_eq!(
+            schedule.first_sight(&healthy, now),
+            FirstSight::Requeue(Duration::from_secs(120))
+        );
+        assert_eq!(schedule.first_sight(&healthy, now), FirstSight::Reconcile);
+
+        let stale = config("stale")
+            .with_status(ready_status(now - chrono::Duration::minutes(1)))
+            .build();
+        schedule.claim_startup(&stale);
+        assert_eq!(
+            schedule.first_sight(&stale, now),
+            FirstSight::Requeue(Duration::from_secs(300))
+        );
+
+        let created = config("created").build();
+        assert_eq!(schedule.first_sight(&created, now), FirstSight::Reconcile);
+    }
+}
@@ -0,0 +1,338 @@
+//! # Startup Reconcile Queue
+//!
+//! Reconciles resources that existed before the controller started.
+//!
+//! Failed and stale resources are queued by priority (failed first) and started at a fixed
+//! rate in a background task, so the watch loop starts immediately instead of waiting for
+//! every resource to be reconciled serially. Healthy resources are not reconciled at startup:
+//! the watch loop restores their schedule from `status.nextReconcileTime` (see
+//! `runtime::schedule`).
+
+use crate::config::SharedControllerConfig;
+use crate::controller::reconciler::concurrency::ProjectBusy;
+use crate::controller::reconciler::{Reconciler, TriggerSource, reconcile_or_busy};
+use crate::crd::SecretManagerConfig;
+use crate::runtime::schedule::ReconcileSchedule;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tracing::{Instrument, debug, error, info};
+
+/// Startup reconcile priority, lowest value reconciled first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StartupPriority {
+    /// Last reconcile failed (phase Failed/PartialFailure or Ready condition False)
+    Failed,
+    /// Never reconciled, spec changed since last reconcile, interrupted, or next reconcile
+    /// time has passed
+    Stale,
+    /// Reconciled and not yet due (not reconciled at startup; the watch loop restores its
+    /// schedule)
+    Healthy,
+}
+
+impl StartupPriority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StartupPriority::Failed => "failed",
+            StartupPriority::Stale => "stale",
+            StartupPriority::Healthy => "healthy",
+        }
+    }
+}
+
+/// Classify a resource for the startup queue
+pub fn startup_priority(config: &SecretManagerConfig, now: DateTime<Utc>) -> StartupPriority {
+    let Some(status) = config.status.as_ref() else {
+        return StartupPriority::Stale;
+    };
+
+    let failed_phase = matches!(
+        status.phase.as_deref(),
+        Some("Failed" | "PartialFailure" | "Retrying")
+    );
+    let not_ready = status
+        .conditions
+        .iter()
+        .any(|c| c.r#type == "Ready" && c.status == "False");
+    if failed_phase || not_ready {
+        return StartupPriority::Failed;
+    }
+
+    // Reconciling is only left set when the controller stopped in the middle of a reconcile
+    let interrupted = status
+        .conditions
+        .iter()
+        .any(|c| c.r#type == "Reconciling" && c.status == "True");
+
+    let generation_changed = status.observed_generation.is_none()
+        || status.observed_generation != config.metadata.generation;
+    let overdue = status
+        .next_reconcile_time
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .is_none_or(|next| next.with_timezone(&Utc) <= now);
+    if generation_changed || overdue || interrupted {
+        return StartupPriority::Stale;
+    }
+
+    StartupPriority::Healthy
+}
+
+/// Order resources for startup reconciliation
+/// Sorted by priority, then namespace/name for a stable order
+pub fn order_startup_queue(
+    items: Vec<SecretManagerConfig>,
+    now: DateTime<Utc>,
+) -> Vec<(StartupPriority, SecretManagerConfig)> {
+    let mut queue: Vec<_> = items
+        .into_iter()
+        .map(|item| (startup_priority(&item, now), item))
+        .collect();
+    queue.sort_by(|(a_priority, a), (b_priority, b)| {
+        a_priority
+            .cmp(b_priority)
+            .then_with(|| a.metadata.namespace.cmp(&b.metadata.namespace))
+            .then_with(|| a.metadata.name.cmp(&b.metadata.name))
+    });
+    queue
+}
+
+/// Reconcile existing failed and stale resources in the background
+///
+/// One reconcile is started every `startup_reconcile_interval_ms` (0 = no rate limit); the
+/// per-provider concurrency limit still applies to each reconcile, and a resource whose cloud
+/// project is busy is retried after the project's backoff. Runs concurrently with the
+/// watch loop, which requeues the claimed resources after their interval and restores the
+/// schedule of healthy ones.
+pub fn spawn_startup_queue(
+    items: Vec<SecretManagerConfig>,
+    reconciler: Arc<Reconciler>,
+    controller_config: SharedControllerConfig,
+    schedule: Arc<ReconcileSchedule>,
+) {
+    let (queue, healthy): (Vec<_>, Vec<_>) = order_startup_queue(items, Utc::now())
+        .into_iter()
+        .partition(|(priority, _)| *priority != StartupPriority::Healthy);
+    if !healthy.is_empty() {
+        info!(
+            "Startup queue: {} healthy resources keep their persisted schedule",
+            healthy.len()
+        );
+    }
+    if queue.is_empty() {
+        return;
+    }
+    for (_, item) in &queue {
+        schedule.claim_startup(item);
+    }
+
+    let span = tracing::span!(
+        tracing::Level::INFO,
+        "controller.startup.queue",
+        operation = "startup_queue",
+        resources = queue.len()
+    );
+
+    tokio::spawn(
+        async move {
+            let interval_ms = controller_config.read().await.startup_reconcile_interval_ms;
+            let count_of = |priority| queue.iter().filter(|(p, _)| *p == priority).count();
+            info!(
+                "Startup queue: {} resources ({} failed, {} stale), starting one every {}ms",
+                queue.len(),
+                count_of(StartupPriority::Failed),
+                count_of(StartupPriority::Stale),
+                interval_ms
+            );
+
+            let total = queue.len();
+            let mut ticker = (interval_ms > 0).then(|| {
+                let mut ticker =
+                    tokio::time::interval(std::time::Duration::from_millis(interval_ms));
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                ticker
+            });
+            let mut tasks = JoinSet::new();
+
+            for (priority, item) in queue {
+                if let Some(ticker) = ticker.as_mut() {
+                    ticker.tick().await;
+                }
+
+                let reconciler = reconciler.clone();
+                let controller_config = controller_config.clone();
+                let name = item.metadata.name.clone().unwrap_or_default();
+                let namespace = item
+                    .metadata
+                    .namespace
+                    .clone()
+                    .unwrap_or_else(|| "default".to_string());
+                let resource_span = tracing::span!(
+                    tracing::Level::INFO,
+                    "controller.startup.reconcile_resource",
+                    resource.name = name.as_str(),
+                    resource.namespace = namespace.as_str(),
+                    resource.kind = "SecretManagerConfig",
+                    startup.priority = priority.as_str()
+                );
+
+                tasks.spawn(
+                    async move {
+                        info!(
+                            "Reconciling existing resource: {} in namespace {} (priority: {})",
+                            name,
+                            namespace,
+                            priority.as_str()
+                        );
+                        // Startup reconciliation uses timer-based trigger source
+                        // The watch loop only requeues claimed resources after their interval,
+                        // so a busy cloud project is waited out here instead of dropped
+                        let item = Arc::new(item);
+                        let result = loop {
+                            match reconcile_or_busy(
+                                item.clone(),
+                                reconciler.clone(),
+                                TriggerSource::TimerBased,
+                                controller_config.clone(),
+                            )
+                            .await
+                            {
+                                Ok(result) => break result,
+                                Err(ProjectBusy { retry_after }) => {
+                                    debug!(
+                                        "Cloud project of {} in namespace {} is busy, retrying in {:?}",
+                                        name, namespace, retry_after
+                                    );
+                                    tokio::time::sleep(retry_after).await;
+                                }
+                            }
+                        };
+                        match result {
+                            Ok(_action) => {
+                                info!(
+                                    resource.name = name.as_str(),
+                                    resource.namespace = namespace.as_str(),
+                                    "reconciliation.success"
+                                );
+                            }
+                            Err(e) => {
+                                error!(
+                                    "Failed to reconcile existing resource {} in namespace {}: {}",
+                                    name, namespace, e
+                                );
+                                error!(resource.name = name.as_str(), resource.namespace = namespace.as_str(), error = %e, "reconciliation.error");
+                            }
+                        }
+                    }
+                    .instrument(resource_span),
+                );
+            }
+
+            while tasks.join_next().await.is_some() {}
+            info!("Completed startup reconciliation of {} existing resources", total);
+        }
+        .instrument(span),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::{Condition, SecretManagerConfigStatus};
+    use crate::testing::ConfigFixture;
+
+    fn config(name: &str) -> ConfigFixture {
+        ConfigFixture::new(name).with_generation(2)
+    }
+
+    fn ready_status(next_reconcile_time: &str) -> SecretManagerConfigStatus {
+        SecretManagerConfigStatus {
+            phase: Some("Ready".to_string()),
+            observed_generation: Some(2),
+            next_reconcile_time: Some(next_reconcile_time.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_startup_priority() {
+        let now = Utc::now();
+        let future = (now + chrono::Duration::minutes(5)).to_rfc3339();
+        let past = (now - chrono::Duration::minutes(5)).to_rfc3339();
+
+        assert_eq!(
+            startup_priority(&config("new").build(), now),
+            StartupPriority::Stale
+        );
+        assert_eq!(
+            startup_priority(
+                &config("healthy").with_status(ready_status(&future)).build(),
+                now
+            ),
+            StartupPriority::Healthy
+        );
+        assert_eq!(
+            startup_priority(
+                &config("overdue").with_status(ready_status(&past)).build(),
+                now
+            ),
+            StartupPriority::Stale
+        );
+
+        let mut failed = ready_status(&future);
+        failed.phase = Some("Failed".to_string());
+        assert_eq!(
+            startup_priority(&config("failed").with_status(failed).build(), now),
+            StartupPriority::Failed
+        );
+
+        let mut not_ready = ready_status(&future);
+        not_ready.conditions = vec![Condition {
+            r#type: "Ready".to_string(),
+            status: "False".to_string(),
+            observed_generation: None,
+            last_transition_time: None,
+            reason: None,
+            message: None,
+        }];
+        assert_eq!(
+            startup_priority(&config("not-ready").with_status(not_ready).build(), now),
+            StartupPriority::Failed
+        );
+
+        let mut spec_changed = ready_status(&future);
+        spec_changed.observed_generation = Some(1);
+        assert_eq!(
+            startup_priority(&config("changed").with_status(spec_changed).build(), now),
+            StartupPriority::Stale
+        );
+    }
+
+    #[test]
+    fn test_order_startup_queue() {
+        let now = Utc::now();
+        let future = (now + chrono::Duration::minutes(5)).to_rfc3339();
+        let mut failed = ready_status(&future);
+        failed.phase = Some("PartialFailure".to_string());
+
+        let queue = order_startup_queue(
+            vec![
+                config("b-healthy")
+                    .with_status(ready_status(&future))
+                    .build(),
+                config("a-healthy")
+                    .with_status(ready_status(&future))
+                    .build(),
+                config("new").build(),
+                config("failed").with_status(failed).build(),
+            ],
+            now,
+        );
+        let names: Vec<_> = queue
+            .iter()
+            .map(|(_, c)| c.metadata.name.as_deref().unwrap_or_default())
+            .collect();
+        assert_eq!(names, vec!["failed", "new", "a-healthy", "b-healthy"]);
+    }
+}
@@ -2,15 +2,36 @@
 //!
 //! Controller watch loop that monitors SecretManagerConfig resources and triggers
 //! reconciliation when changes are detected.
+//!
+//! Periodic reconciles are scheduled only by the `Action::requeue` each reconcile returns.
+//! Status-only watch events are filtered out before they reach the controller (see
+//! `runtime::schedule`), so they can't replace a pending requeue.
+//!
+//! FluxCD GitRepository and ArgoCD Application objects are watched too, when their CRDs are
+//! installed: a new revision reconciles every SecretManagerConfig whose `sourceRef` points at
+//! the source, so new commits propagate within seconds instead of at the next interval.
+//! Secrets are watched the same way: a change to a referenced Secret (SOPS key, Git or provider
+//! credentials, mirrored Secret) reconciles only the resources that use it.
 
 use crate::config::SharedControllerConfig;
+use crate::controller::reconciler::approval::has_pending_approval;
+use crate::controller::reconciler::secret_watch::{
+    SecretVersions, referenced_secret_events, secret_mapper,
+};
+use crate::controller::reconciler::sops::verify_rbac_for_sops_watch;
+use crate::controller::reconciler::source::{
+    SourceRevisions, WatchedSource, referencing_configs, source_served,
+};
 use crate::controller::reconciler::{Reconciler, TriggerSource, reconcile};
 use crate::controller::server::ServerState;
 use crate::crd::SecretManagerConfig;
+use crate::runtime::args::ControllerArgs;
 use crate::runtime::error_policy::{handle_reconciliation_error, handle_watch_stream_error};
+use crate::runtime::schedule::{FirstSight, ReconcileSchedule};
 use futures::StreamExt;
-use kube::api::Api;
-use kube_runtime::{Controller, controller::Action, watcher};
+use kube::api::{Api, DynamicObject};
+use kube_runtime::controller::{Action, Config as ControllerRuntimeConfig};
+use kube_runtime::{Controller, WatchStreamExt, reflector, watcher};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
@@ -23,7 +44,9 @@ pub async fn run_watch_loop(
     configs: Api<SecretManagerConfig>,
     reconciler: Arc<Reconciler>,
     server_state: Arc<ServerState>,
+    schedule: Arc<ReconcileSchedule>,
     controller_config: SharedControllerConfig,
+    args: &ControllerArgs,
 ) -> Result<(), anyhow::Error> {
     // Create controller with any_semantic() to watch for all semantic changes (create, update, delete)
     // This ensures the controller picks up newly created resources
@@ -58,6 +81,40 @@ pub async fn run_watch_loop(
         info!("Marked server as not ready, waiting for in-flight reconciliations to complete...");
     });
 
+    // Source CRDs are checked once: FluxCD and ArgoCD are optional
+    let mut watched_sources = Vec::new();
+    for source in WatchedSource::ALL {
+        if source_served(&reconciler.client, source).await {
+            watched_sources.push(source);
+        } else {
+            info!(
+                source.kind = source.kind(),
+                "{} CRD not available, not watching {} revisions",
+                source.kind(),
+                source.kind()
+            );
+        }
+    }
+    // Revisions outlive watch restarts, so a restart only triggers sources that changed meanwhile
+    let source_revisions = Arc::new(SourceRevisions::new());
+
+    // Referenced Secrets (SOPS keys, credentials) need list/watch on secrets in all namespaces
+    let watch_secrets = controller_config.read().await.sops_key_watch_enabled
+        && match verify_rbac_for_sops_watch(&reconciler.client).await {
+            Ok(()) => true,
+            Err(e) => {
+                warn!(
+                    "⚠️  RBAC verification failed: {}. Referenced secret changes (SOPS keys, credentials) won't be picked up until the next reconcile.",
+                    e
+                );
+                warn!(
+                    "⚠️  Test permissions: kubectl auth can-i list secrets --as=system:serviceaccount:octopilot-system:secret-manager-controller --all-namespaces"
+                );
+                false
+            }
+        };
+    let secret_versions = Arc::new(SecretVersions::new());
+
     // Run controller with improved error handling and automatic restart
     loop {
         // Check if we should shut down before starting/restarting watch
@@ -79,56 +136,140 @@ pub async fn run_watch_loop(
         );
         let _watch_guard = watch_span.enter();
 
-        info!("Starting controller watch loop...");
-        let controller_future =
-            Controller::new(configs.clone(), watcher::Config::default().any_semantic())
-                .shutdown_on_signal()
-                .run(
-                    |obj, ctx| {
-                        create_reconcile_fn(obj, ctx, controller_config_for_reconcile.clone())
-                    },
-                    |obj, error, ctx| handle_reconciliation_error(obj, error, ctx),
-                    reconciler.clone(),
-                )
-                .filter_map(move |x| {
-                    let backoff = backoff_clone.clone();
-                    let config_clone = controller_config_for_filter.clone();
-                    async move {
-                        match &x {
-                            Ok(_) => {
-                                // Successful event, reset backoff on success
-                                // Reload config in case it changed
-                                let config = config_clone.read().await;
-                                let backoff_start = config.backoff_start_ms;
-                                drop(config);
-                                backoff.store(backoff_start, std::sync::atomic::Ordering::Relaxed);
-                                debug!("watch.event.success");
-                                Some(x)
-                            }
-                            Err(e) => {
-                                // Convert the controller error to a string for classification
-                                let error_string = format!("{e:?}");
-                                // Reload config in case it changed
-                                let config = config_clone.read().await;
-                                let max_backoff = config.backoff_max_ms;
-                                let watch_restart_delay = config.watch_restart_delay_secs;
-                                drop(config);
-                                match handle_watch_stream_error(
-                                    &error_string,
-                                    &backoff,
-                                    max_backoff,
-                                    watch_restart_delay,
-                                )
-                                .await
-                                {
-                                    Some(_) => Some(x), // Continue with this event
-                                    None => None,       // Filter out to allow restart
-                                }
+        // Concurrency is read on every (re)start so config reloads apply after a watch restart
+        // The --max-concurrent-reconciles flag takes precedence over the config value
+        let max_concurrent_reconciles =
+            args.max_concurrent_reconciles(&*controller_config.read().await);
+        info!(
+            "Starting controller watch loop (max concurrent reconciles: {})...",
+            max_concurrent_reconciles
+        );
+        // Only watch events that need a reconcile reach the controller; status-only updates
+        // would replace the requeue scheduled by the last reconcile
+        let (reader, writer) = reflector::store();
+        let schedule_for_delete = schedule.clone();
+        let not_found_cache = reconciler.not_found_cache.clone();
+        let schedule_for_filter = schedule.clone();
+        let events = reflector(
+            writer,
+            watcher(configs.clone(), watcher::Config::default().any_semantic()).default_backoff(),
+        )
+        .inspect(move |event| {
+            if let Ok(watcher::Event::Delete(obj)) = event {
+                schedule_for_delete.forget(obj);
+                not_found_cache.forget(&format!(
+                    "{}/{}",
+                    obj.metadata.namespace.as_deref().unwrap_or("default"),
+                    obj.metadata.name.as_deref().unwrap_or("unknown")
+                ));
+            }
+        })
+        .applied_objects()
+        .filter(move |event| {
+            futures::future::ready(match event {
+                Ok(obj) => schedule_for_filter.should_trigger(obj),
+                Err(_) => true,
+            })
+        });
+        let mut controller = Controller::for_stream(events, reader.clone()).with_config(
+            ControllerRuntimeConfig::default()
+                .concurrency(u16::try_from(max_concurrent_reconciles).unwrap_or(u16::MAX)),
+        );
+        // New source revisions reconcile the SecretManagerConfigs that reference the source
+        for &source in &watched_sources {
+            let revisions = source_revisions.clone();
+            let source_events = watcher(
+                Api::<DynamicObject>::all_with(reconciler.client.clone(), &source.api_resource()),
+                watcher::Config::default(),
+            )
+            .default_backoff()
+            .applied_objects()
+            .filter(move |event| {
+                futures::future::ready(match event {
+                    Ok(obj) => revisions.changed(source, obj),
+                    Err(_) => true,
+                })
+            });
+            let store = reader.clone();
+            controller = controller.watches_stream_with(
+                source_events,
+                move |obj| {
+                    let configs = referencing_configs(&store.state(), source, &obj);
+                    debug!(
+                        source.kind = source.kind(),
+                        source.name = obj.metadata.name.as_deref().unwrap_or("unknown"),
+                        resources = configs.len(),
+                        "New source revision, reconciling referencing resources"
+                    );
+                    configs
+                },
+                source.api_resource(),
+            );
+        }
+        // Changes to referenced Secrets reconcile the SecretManagerConfigs that use them
+        if watch_secrets {
+            controller = controller.watches_stream_with(
+                referenced_secret_events(reconciler.clone(), secret_versions.clone()),
+                secret_mapper(reader.clone()),
+                (),
+            );
+        }
+        let schedule_for_reconcile = schedule.clone();
+        // Publish this watch's cache so /readyz can summarize resource sync health
+        *server_state.resource_store.write().await = Some(controller.store());
+        let controller_future = controller
+            .shutdown_on_signal()
+            .run(
+                |obj, ctx| {
+                    create_reconcile_fn(
+                        obj,
+                        ctx,
+                        controller_config_for_reconcile.clone(),
+                        schedule_for_reconcile.clone(),
+                    )
+                },
+                |obj, error, ctx| handle_reconciliation_error(obj, error, ctx),
+                reconciler.clone(),
+            )
+            .filter_map(move |x| {
+                let backoff = backoff_clone.clone();
+                let config_clone = controller_config_for_filter.clone();
+                async move {
+                    match &x {
+                        Ok(_) => {
+                            // Successful event, reset backoff on success
+                            // Reload config in case it changed
+                            let config = config_clone.read().await;
+                            let backoff_start = config.backoff_start_ms;
+                            drop(config);
+                            backoff.store(backoff_start, std::sync::atomic::Ordering::Relaxed);
+                            debug!("watch.event.success");
+                            Some(x)
+                        }
+                        Err(e) => {
+                            // Convert the controller error to a string for classification
+                            let error_string = format!("{e:?}");
+                            // Reload config in case it changed
+                            let config = config_clone.read().await;
+                            let max_backoff = config.backoff_max_ms;
+                            let watch_restart_delay = config.watch_restart_delay_secs;
+                            drop(config);
+                            match handle_watch_stream_error(
+                                &error_string,
+                                &backoff,
+                                max_backoff,
+                                watch_restart_delay,
+                            )
+                            .await
+                            {
+                                Some(_) => Some(x), // Continue with this event
+                                None => None,       // Filter out to allow restart
                             }
                         }
                     }
-                })
-                .for_each(|_| futures::future::ready(()));
+                }
+            })
+            .for_each(|_| futures::future::ready(()));
 
         // Run controller - check for shutdown before and after
         controller_future.await;
@@ -143,6 +284,10 @@ pub async fn run_watch_loop(
         }
 
         // Controller stream ended - restart watch
+        // The new controller starts without the pending requeues, so the schedule is restored
+        // from status like at startup
+        schedule.reset();
+
         // Reload config in case it changed
         let config = controller_config.read().await;
         let delay_secs = config.watch_restart_delay_after_end_secs;
@@ -163,6 +308,7 @@ fn create_reconcile_fn(
     obj: Arc<SecretManagerConfig>,
     ctx: Arc<Reconciler>,
     controller_config: SharedControllerConfig,
+    schedule: Arc<ReconcileSchedule>,
 ) -> impl std::future::Future<Output = Result<Action, crate::controller::reconciler::ReconcilerError>>
 + Send {
     let reconciler = ctx.clone();
@@ -229,107 +375,54 @@ fn create_reconcile_fn(
             .and_then(|ann| ann.get("secret-management.octopilot.io/reconcile"))
             .is_some();
 
-        // Check if this is a periodic reconciliation (requeue-triggered)
-        // Periodic reconciliations should run even if generation matches, as they check
-        // for external state changes (secrets in cloud provider, Git repository updates)
-        // We use next_reconcile_time from status to persist the schedule across watch restarts
-        // CRITICAL: If generation matches but next_reconcile_time has passed, this is a periodic reconciliation
-        let is_periodic_reconcile = if generation == observed_generation && observed_generation > 0
-        {
-            if let Some(status) = &obj.status {
-                if let Some(next_reconcile_time) = &status.next_reconcile_time {
-                    // Check if next_reconcile_time has passed (with 2s tolerance for timing)
-                    if let Ok(next_time) = chrono::DateTime::parse_from_rfc3339(next_reconcile_time)
-                    {
-                        let next_time_utc = next_time.with_timezone(&chrono::Utc);
-                        let now = chrono::Utc::now();
-                        // If current time >= next_reconcile_time (with 2s tolerance), this is a periodic reconciliation
-                        let is_periodic = now >= next_time_utc - chrono::Duration::seconds(2);
-                        if is_periodic {
-                            info!(
-                                resource.name = name.as_str(),
-                                resource.namespace = namespace.as_str(),
-                                next_reconcile_time = next_reconcile_time.as_str(),
-                                "Detected periodic reconciliation - next_reconcile_time has passed"
-                            );
-                        }
-                        is_periodic
-                    } else {
-                        false
-                    }
-                } else {
-                    // No next_reconcile_time means first reconciliation or not yet scheduled - not periodic
-                    false
-                }
-            } else {
-                false
-            }
-        } else {
-            // Generation doesn't match, so this is a spec change, not periodic
-            false
-        };
+        // Approving staged changes (approve annotation) applies them right away instead of
+        // waiting for the next periodic reconcile
+        let is_approval = has_pending_approval(&obj);
 
-        // CRITICAL: Only reconcile if spec changed (generation != observed_generation)
-        // This prevents infinite loops from status updates triggering reconciliations
-        // Status-only updates don't change generation, so we skip them
-        // Exceptions:
-        // 1. Always reconcile if observed_generation is 0 (first reconciliation)
-        // 2. Always reconcile if manual trigger annotation is present (msmctl reconcile)
-        // 3. Always reconcile if this is a periodic reconciliation (requeue-triggered)
-        if generation == observed_generation
-            && observed_generation > 0
-            && !is_manual_trigger
-            && !is_periodic_reconcile
+        // The first call for a resource since the watch started comes from the initial list:
+        // the startup queue reconciles failed and stale resources, and up-to-date ones keep the
+        // schedule persisted in status
+        if !is_manual_trigger
+            && !is_approval
+            && let FirstSight::Requeue(delay) = schedule.first_sight(&obj, chrono::Utc::now())
         {
             debug!(
                 resource.name = name.as_str(),
                 resource.namespace = namespace.as_str(),
-                generation = generation,
-                observed_generation = observed_generation,
-                "Skipping reconciliation - only status changed, spec unchanged (no manual trigger, not periodic)"
+                delay_secs = delay.as_secs(),
+                "Restored reconcile schedule"
             );
-            // Return Action::await_change() to wait for next spec change
-            return Ok(Action::await_change());
+            return Ok(Action::requeue(delay));
         }
 
-        if is_periodic_reconcile {
-            info!(
-                resource.name = name.as_str(),
-                resource.namespace = namespace.as_str(),
-                "Periodic reconciliation triggered - proceeding despite generation match"
-            );
-        } else if generation == observed_generation && observed_generation > 0 {
-            // Log why periodic reconciliation wasn't detected
-            debug!(
-                resource.name = name.as_str(),
-                resource.namespace = namespace.as_str(),
-                has_status = obj.status.is_some(),
-                has_next_reconcile_time = obj
-                    .status
-                    .as_ref()
-                    .and_then(|s| s.next_reconcile_time.as_ref())
-                    .is_some(),
-                next_reconcile_time = obj
-                    .status
-                    .as_ref()
-                    .and_then(|s| s.next_reconcile_time.as_ref())
-                    .map(|s| s.as_str())
-                    .unwrap_or("none"),
-                reconcile_interval = obj.spec.reconcile_interval.as_str(),
-                "Periodic reconciliation check - generation matches but next_reconcile_time not yet reached"
-            );
+        // Every other call is a watch event that passed the trigger filter (new resource, spec
+        // change, manual trigger, approval) or a requeue, so it is always reconciled
+        let is_periodic_reconcile =
+            generation == observed_generation && observed_generation > 0 && !is_manual_trigger;
+        if is_periodic_reconcile
+            && !is_approval
+            && let Some(scheduled) = obj
+                .status
+                .as_ref()
+                .and_then(|s| s.next_reconcile_time.as_deref())
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        {
+            // Lag against the persisted schedule is only reported, never used to decide
+            #[allow(
+                clippy::cast_precision_loss,
+                reason = "lag in milliseconds fits in f64"
+            )]
+            let lag_secs = (chrono::Utc::now() - scheduled.with_timezone(&chrono::Utc))
+                .num_milliseconds() as f64
+                / 1000.0;
+            crate::observability::metrics::observe_reconcile_queue_lag(lag_secs);
         }
 
         // Determine trigger source for detailed logging
-        let trigger_source = if is_manual_trigger {
+        let trigger_source = if is_manual_trigger || is_approval {
             TriggerSource::ManualCli
-        } else if is_periodic_reconcile {
-            TriggerSource::TimerBased
-        } else if observed_generation == 0 {
-            // First reconciliation
-            TriggerSource::TimerBased
         } else {
-            // Spec change (generation changed)
+            // Requeue, spec change or first reconciliation
             TriggerSource::TimerBased
         };
 
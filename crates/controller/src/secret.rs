@@ -0,0 +1,108 @@
+//! # Secret Values
+//!
+//! Wrapper for plaintext secret material held by the controller.
+//!
+//! [`SecretString`] is zeroized when dropped and prints as `[REDACTED]` in `Debug`, so a
+//! decrypted value doesn't linger in freed heap memory or end up in a `{:?}` log line. The
+//! value is only reachable through [`SecretString::expose_secret`], which makes every use of
+//! plaintext explicit at the call site.
+//!
+//! Values stay wrapped from the parsers through the `SecretManagerProvider` trait; each
+//! provider client exposes them only to build its API request.
+//!
+//! Copies handed to provider SDKs, HTTP bodies, or the sops process are outside this
+//! guarantee; keep them short-lived. Types holding credentials that can't use the wrapper
+//! implement `Debug` by hand instead (`GitCredentials`, `SopsKey`, the provider clients).
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Plaintext secret, zeroized on drop and redacted in `Debug`
+#[derive(Clone, Default, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct SecretString(String);
+
+impl SecretString {
+    #[must_use]
+    pub fn new(value: String) -> Self {
+        Self(value)
+    }
+
+    /// The plaintext value
+    #[must_use]
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Length of the plaintext in bytes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl std::fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+// Serialized as the plain string, for request and response bodies carrying secret values
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let secret = SecretString::from("hunter2");
+        assert_eq!(format!("{secret:?}"), "[REDACTED]");
+        assert_eq!(format!("{:?}", Some(secret.clone())), "Some([REDACTED])");
+        assert_eq!(secret.expose_secret(), "hunter2");
+    }
+
+    #[test]
+    fn test_serializes_as_plain_string() {
+        let secret: SecretString = serde_json::from_str("\"aGVsbG8=\"")
+            .unwrap_or_else(|e| panic!("Failed to deserialize: {e}"));
+        assert_eq!(secret.expose_secret(), "aGVsbG8=");
+        assert_eq!(
+            serde_json::to_string(&secret).ok().as_deref(),
+            Some("\"aGVsbG8=\"")
+        );
+    }
+
+    #[test]
+    fn test_zeroize_clears_value() {
+        let mut secret = SecretString::from("hunter2");
+        secret.zeroize();
+        assert!(secret.is_empty());
+    }
+}
@@ -0,0 +1,563 @@
+//! # Fake Kubernetes API Server
+//!
+//! In-memory stand-in for the Kubernetes API, served to a real `kube::Client` through a
+//! tower service - no network, no mock server binary.
+//!
+//! Objects are stored as JSON by their API path. It supports what the controller uses:
+//! get, list (with equality and existence label selectors, across namespaces), create,
+//! replace, delete, and merge/apply patches of objects and their `status` subresource.
+//! Watches return an empty stream. Unknown objects return a `404` `Status`, like the
+//! real API server, so "not found" paths in the reconciler behave as in a cluster.
+
+use anyhow::{Context, Result};
+use http::{Method, Request, Response, StatusCode};
+use http_body_util::BodyExt;
+use kube::api::{ApiResource, DynamicObject};
+use kube::client::Body;
+use kube::{Client, Resource};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A request received by the fake API server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiRequest {
+    pub method: String,
+    /// Request path, without the query string
+    pub path: String,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    objects: BTreeMap<String, Value>,
+    requests: Vec<ApiRequest>,
+    resource_version: u64,
+}
+
+/// In-memory Kubernetes API server
+#[derive(Debug, Clone, Default)]
+pub struct FakeApiServer {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeApiServer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// A client whose requests are served by this fake (default namespace `default`)
+    #[must_use]
+    pub fn client(&self) -> Client {
+        let server = self.clone();
+        let service = tower::service_fn(move |request: Request<Body>| {
+            let server = server.clone();
+            async move { Ok::<_, Infallible>(server.handle(request).await) }
+        });
+        Client::new(service, "default")
+    }
+
+    /// Store a typed object (replacing any object with the same name)
+    pub fn insert<K>(&self, object: &K) -> Result<()>
+    where
+        K: Resource<DynamicType = ()> + Serialize,
+    {
+        let path = object_path(
+            &K::url_path(&(), object.meta().namespace.as_deref()),
+            object.meta().name.as_deref(),
+        )?;
+        self.insert_value(path, serde_json::to_value(object)?);
+        Ok(())
+    }
+
+    /// Store a dynamic object (e.g. a FluxCD GitRepository) of resource `ar`
+    pub fn insert_dynamic(&self, ar: &ApiResource, object: &DynamicObject) -> Result<()> {
+        let path = object_path(
+            &DynamicObject::url_path(ar, object.metadata.namespace.as_deref()),
+            object.metadata.name.as_deref(),
+        )?;
+        let mut value = serde_json::to_value(object)?;
+        value["apiVersion"] = json!(ar.api_version);
+        value["kind"] = json!(ar.kind);
+        self.insert_value(path, value);
+        Ok(())
+    }
+
+    fn insert_value(&self, path: String, mut value: Value) {
+        let mut state = self.lock();
+        state.resource_version += 1;
+        value["metadata"]["resourceVersion"] = json!(state.resource_version.to_string());
+        state.objects.insert(path, value);
+    }
+
+    /// A typed object as currently stored
+    #[must_use]
+    pub fn get<K>(&self, namespace: Option<&str>, name: &str) -> Option<K>
+    where
+        K: Resource<DynamicType = ()> + DeserializeOwned,
+    {
+        let path = format!("{}/{}", K::url_path(&(), namespace), name);
+        self.get_value(&path)
+            .and_then(|value| serde_json::from_value(value).ok())
+    }
+
+    /// Raw JSON of the object stored at `path` (e.g. `/api/v1/namespaces/default/secrets/x`)
+    #[must_use]
+    pub fn get_value(&self, path: &str) -> Option<Value> {
+        self.lock().objects.get(path).cloned()
+    }
+
+    /// Requests received so far, oldest first
+    #[must_use]
+    pub fn requests(&self) -> Vec<ApiRequest> {
+        self.lock().requests.clone()
+    }
+
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let (parts, body) = request.into_parts();
+        let path = parts.uri.path().trim_end_matches('/').to_string();
+        let query: BTreeMap<String, String> = parts
+            .uri
+            .query()
+            .map(|q| {
+                url_pairs(q)
+                    .into_iter()
+                    .collect::<BTreeMap<String, String>>()
+            })
+            .unwrap_or_default();
+        self.lock().requests.push(ApiRequest {
+            method: parts.method.to_string(),
+            path: path.clone(),
+        });
+
+        let body = match body.collect().await {
+            Ok(collected) => collected.to_bytes(),
+            Err(e) => return status_response(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+        let body: Option<Value> = if body.is_empty() {
+            None
+        } else {
+            match serde_json::from_slice(&body) {
+                Ok(value) => Some(value),
+                Err(e) => return status_response(StatusCode::BAD_REQUEST, &e.to_string()),
+            }
+        };
+        let content_type = parts
+            .headers
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        let (path, subresource) = match path.strip_suffix("/status") {
+            Some(object) if !is_collection(object) => (object.to_string(), Some("status")),
+            _ => (path, None),
+        };
+
+        if query.get("watch").is_some_and(|w| w == "true" || w == "1") {
+            return json_response(StatusCode::OK, &Value::Null, true);
+        }
+
+        match (parts.method, is_collection(&path)) {
+            (Method::GET, true) => self.list(&path, query.get("labelSelector").map(String::as_str)),
+            (Method::GET, false) => self.read(&path),
+            (Method::POST, true) => self.create(&path, body),
+            (Method::PUT, false) => self.replace(&path, body, subresource),
+            (Method::PATCH, false) => self.patch(&path, body, &content_type, subresource),
+            (Method::DELETE, false) => self.delete(&path),
+            (method, _) => status_response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                &format!("{method} not supported on {path}"),
+            ),
+        }
+    }
+
+    fn read(&self, path: &str) -> Response<Body> {
+        match self.get_value(path) {
+            Some(object) => json_response(StatusCode::OK, &object, false),
+            None => not_found(path),
+        }
+    }
+
+    fn list(&self, collection: &str, label_selector: Option<&str>) -> Response<Body> {
+        let state = self.lock();
+        let items: Vec<Value> = state
+            .objects
+            .iter()
+            .filter(|(path, _)| {
+                path.rsplit_once('/').is_some_and(|(parent, _)| {
+                    parent == collection || strip_namespace(parent) == collection
+                })
+            })
+            .filter(|(_, object)| {
+                label_selector.is_none_or(|selector| matches_labels(object, selector))
+            })
+            .map(|(_, object)| object.clone())
+            .collect();
+        let list = json!({
+            "apiVersion": "v1",
+            "kind": "List",
+            "metadata": { "resourceVersion": state.resource_version.to_string() },
+            "items": items,
+        });
+        json_response(StatusCode::OK, &list, false)
+    }
+
+    fn create(&self, collection: &str, body: Option<Value>) -> Response<Body> {
+        let Some(mut object) = body else {
+            return status_response(StatusCode::BAD_REQUEST, "missing object");
+        };
+        let name = match object["metadata"]["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => {
+                let generate = object["metadata"]["generateName"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+                let name = format!("{generate}{}", self.lock().resource_version + 1);
+                object["metadata"]["name"] = json!(name);
+                name
+            }
+        };
+        let path = format!("{collection}/{name}");
+        if self.get_value(&path).is_some() {
+            return status_response(StatusCode::CONFLICT, &format!("{name} already exists"));
+        }
+        if let Some(namespace) = namespace_of(collection) {
+            object["metadata"]["namespace"] = json!(namespace);
+        }
+        object["metadata"]["uid"] = json!(uuid::Uuid::new_v4().to_string());
+        object["metadata"]["creationTimestamp"] = json!(chrono::Utc::now().to_rfc3339());
+        self.insert_value(path.clone(), object);
+        self.respond_with(StatusCode::CREATED, &path)
+    }
+
+    fn replace(
+        &self,
+        path: &str,
+        body: Option<Value>,
+        subresource: Option<&str>,
+    ) -> Response<Body> {
+        let Some(body) = body else {
+            return status_response(StatusCode::BAD_REQUEST, "missing object");
+        };
+        let object = match (subresource, self.get_value(path)) {
+            (Some(_), Some(mut current)) => {
+                current["status"] = body["status"].clone();
+                current
+            }
+            (Some(_), None) => return not_found(path),
+            (None, _) => body,
+        };
+        self.insert_value(path.to_string(), object);
+        self.respond_with(StatusCode::OK, path)
+    }
+
+    fn patch(
+        &self,
+        path: &str,
+        body: Option<Value>,
+        content_type: &str,
+        subresource: Option<&str>,
+    ) -> Response<Body> {
+        let Some(patch) = body else {
+            return status_response(StatusCode::BAD_REQUEST, "missing patch");
+        };
+        if content_type.starts_with("application/json-patch") {
+            return status_response(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "JSON patches are not supported by the fake API server",
+            );
+        }
+        // The status subresource only takes status changes
+        let patch = match subresource {
+            Some(_) => json!({ "status": patch["status"].clone() }),
+            None => patch,
+        };
+        let mut object = match self.get_value(path) {
+            Some(object) => object,
+            // Server-side apply creates missing objects
+            None if content_type.starts_with("application/apply-patch") => json!({}),
+            None => return not_found(path),
+        };
+        merge_patch(&mut object, &patch);
+        self.insert_value(path.to_string(), object);
+        self.respond_with(StatusCode::OK, path)
+    }
+
+    fn delete(&self, path: &str) -> Response<Body> {
+        match self.lock().objects.remove(path) {
+            Some(object) => json_response(StatusCode::OK, &object, false),
+            None => not_found(path),
+        }
+    }
+
+    fn respond_with(&self, status: StatusCode, path: &str) -> Response<Body> {
+        match self.get_value(path) {
+            Some(object) => json_response(status, &object, false),
+            None => not_found(path),
+        }
+    }
+}
+
+/// Path of object `name` in collection `collection`
+fn object_path(collection: &str, name: Option<&str>) -> Result<String> {
+    let name = name.context("object has no metadata.name")?;
+    Ok(format!("{collection}/{name}"))
+}
+
+/// Whether `path` addresses a collection (`/api/v1/namespaces/ns/secrets`) rather than an
+/// object (`/api/v1/namespaces/ns/secrets/name`)
+fn is_collection(path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    // Core resources live under /api/{version}, everything else under /apis/{group}/{version}
+    let prefix = if segments.first() == Some(&"api") {
+        2
+    } else {
+        3
+    };
+    let rest = segments.len().saturating_sub(prefix);
+    let rest = if segments.get(prefix) == Some(&"namespaces") && rest > 2 {
+        rest - 2
+    } else {
+        rest
+    };
+    rest == 1
+}
+
+/// Namespace of a namespaced collection path
+fn namespace_of(collection: &str) -> Option<&str> {
+    let (_, rest) = collection.split_once("/namespaces/")?;
+    rest.split('/').next()
+}
+
+/// Collection path without its namespace (`/apis/g/v/namespaces/ns/things` -> `/apis/g/v/things`)
+fn strip_namespace(collection: &str) -> String {
+    match collection.split_once("/namespaces/") {
+        Some((prefix, rest)) => match rest.split_once('/') {
+            Some((_, plural)) => format!("{prefix}/{plural}"),
+            None => collection.to_string(),
+        },
+        None => collection.to_string(),
+    }
+}
+
+/// Whether `object` matches a label selector of `key=value`, `key!=value`, `key` and `!key` terms
+fn matches_labels(object: &Value, selector: &str) -> bool {
+    let labels = &object["metadata"]["labels"];
+    selector
+        .split(',')
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .all(|term| {
+            if let Some((key, value)) = term.split_once("!=") {
+                labels[key.trim()].as_str() != Some(value.trim())
+            } else if let Some((key, value)) =
+                term.split_once("==").or_else(|| term.split_once('='))
+            {
+                labels[key.trim()].as_str() == Some(value.trim())
+            } else if let Some(key) = term.strip_prefix('!') {
+                labels.get(key.trim()).is_none()
+            } else {
+                labels.get(term).is_some()
+            }
+        })
+}
+
+/// Apply a JSON merge patch (RFC 7386): objects merge recursively, `null` deletes a field
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = json!({});
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Decode `a=b&c=d` query pairs
+fn url_pairs(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Some((percent_decode(key)?, percent_decode(value)?))
+        })
+        .collect()
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = value.get(i + 1..i + 3)?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn json_response(status: StatusCode, value: &Value, empty: bool) -> Response<Body> {
+    let body = if empty {
+        Vec::new()
+    } else {
+        serde_json::to_vec(value).unwrap_or_default()
+    };
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        http::header::CONTENT_TYPE,
+        http::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+fn status_response(status: StatusCode, message: &str) -> Response<Body> {
+    let reason = match status {
+        StatusCode::NOT_FOUND => "NotFound",
+        StatusCode::CONFLICT => "AlreadyExists",
+        StatusCode::BAD_REQUEST => "BadRequest",
+        StatusCode::METHOD_NOT_ALLOWED => "MethodNotAllowed",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "UnsupportedMediaType",
+        _ => "InternalError",
+    };
+    let body = json!({
+        "apiVersion": "v1",
+        "kind": "Status",
+        "metadata": {},
+        "status": "Failure",
+        "message": message,
+        "reason": reason,
+        "code": status.as_u16(),
+    });
+    json_response(status, &body, false)
+}
+
+fn not_found(path: &str) -> Response<Body> {
+    status_response(StatusCode::NOT_FOUND, &format!("{path} not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::Secret;
+    use kube::Api;
+    use kube::api::{ListParams, Patch, PatchParams, PostParams};
+    use std::collections::BTreeMap;
+
+    fn secret(name: &str, labels: &[(&str, &str)]) -> Secret {
+        let mut secret = Secret::default();
+        secret.metadata.name = Some(name.to_string());
+        secret.metadata.namespace = Some("default".to_string());
+        secret.metadata.labels = Some(
+            labels
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        );
+        secret
+    }
+
+    #[test]
+    fn test_is_collection() {
+        assert!(is_collection("/api/v1/namespaces/default/secrets"));
+        assert!(!is_collection("/api/v1/namespaces/default/secrets/key"));
+        assert!(is_collection("/api/v1/namespaces"));
+        assert!(!is_collection("/api/v1/namespaces/default"));
+        assert!(is_collection(
+            "/apis/source.toolkit.fluxcd.io/v1beta2/namespaces/flux-system/gitrepositories"
+        ));
+        assert!(is_collection(
+            "/apis/source.toolkit.fluxcd.io/v1beta2/gitrepositories"
+        ));
+        assert!(!is_collection(
+            "/apis/source.toolkit.fluxcd.io/v1beta2/namespaces/flux-system/gitrepositories/repo"
+        ));
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let mut target = json!({ "a": 1, "b": { "c": 2, "d": 3 } });
+        merge_patch(
+            &mut target,
+            &json!({ "b": { "c": null, "e": 4 }, "f": [1] }),
+        );
+        assert_eq!(target, json!({ "a": 1, "b": { "d": 3, "e": 4 }, "f": [1] }));
+    }
+
+    #[tokio::test]
+    async fn test_client_get_list_patch() {
+        let server = FakeApiServer::new();
+        server
+            .insert(&secret("sops-key", &[("app", "sops")]))
+            .unwrap_or_else(|e| panic!("insert failed: {e}"));
+        server
+            .insert(&secret("other", &[]))
+            .unwrap_or_else(|e| panic!("insert failed: {e}"));
+        let api: Api<Secret> = Api::namespaced(server.client(), "default");
+
+        let listed = api
+            .list(&ListParams::default().labels("app=sops"))
+            .await
+            .unwrap_or_else(|e| panic!("list failed: {e}"));
+        assert_eq!(listed.items.len(), 1);
+
+        match api.get("missing").await {
+            Err(kube::Error::Api(e)) => assert_eq!(e.code, 404),
+            other => panic!("expected 404, got {other:?}"),
+        }
+
+        api.patch(
+            "other",
+            &PatchParams::default(),
+            &Patch::Merge(json!({ "metadata": { "labels": { "app": "sops" } } })),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("patch failed: {e}"));
+        let listed = api
+            .list(&ListParams::default().labels("app=sops"))
+            .await
+            .unwrap_or_else(|e| panic!("list failed: {e}"));
+        assert_eq!(listed.items.len(), 2);
+
+        api.create(&PostParams::default(), &secret("created", &[]))
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        assert!(server.get::<Secret>(Some("default"), "created").is_some());
+        assert!(
+            server
+                .requests()
+                .iter()
+                .any(|r| r.method == "PATCH" && r.path.ends_with("/secrets/other"))
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! # Fixtures
+//!
+//! Builder for `SecretManagerConfig` test fixtures.
+//!
+//! ```rust,ignore
+//! let config = ConfigFixture::new("my-secrets")
+//!     .with_generation(3)
+//!     .with_spec(json!({ "reconcileInterval": "5m" }))
+//!     .build();
+//! ```
+//!
+//! Every fixture starts as a GCP config in `team-a`, synced from GitRepository
+//! `flux-system/repo` for environment `dev`; tests only spell out what they depend on.
+
+use crate::crd::{SecretManagerConfig, SecretManagerConfigStatus};
+use serde_json::{Value, json};
+
+/// Builder for a `SecretManagerConfig`
+#[derive(Debug, Clone)]
+pub struct ConfigFixture {
+    metadata: Value,
+    spec: Value,
+    status: Option<SecretManagerConfigStatus>,
+}
+
+impl ConfigFixture {
+    /// GCP config `team-a/<name>` reading GitRepository `flux-system/repo`
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            metadata: json!({ "name": name, "namespace": "team-a" }),
+            spec: json!({
+                "sourceRef": { "kind": "GitRepository", "name": "repo", "namespace": "flux-system" },
+                "provider": {
+                    "type": "gcp",
+                    "gcp": { "projectId": "test-project", "location": "us-central1" }
+                },
+                "secrets": { "environment": "dev" }
+            }),
+            status: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.metadata["namespace"] = json!(namespace);
+        self
+    }
+
+    #[must_use]
+    pub fn with_uid(mut self, uid: &str) -> Self {
+        self.metadata["uid"] = json!(uid);
+        self
+    }
+
+    #[must_use]
+    pub fn with_generation(mut self, generation: i64) -> Self {
+        self.metadata["generation"] = json!(generation);
+        self
+    }
+
+    /// Read from `sourceRef` `<kind>` `flux-system/<name>`
+    #[must_use]
+    pub fn with_source(mut self, kind: &str, name: &str) -> Self {
+        self.spec["sourceRef"] = json!({ "kind": kind, "name": name, "namespace": "flux-system" });
+        self
+    }
+
+    /// Replace top-level spec fields (`provider`, `secrets`, `reconcileInterval`, ...)
+    #[must_use]
+    pub fn with_spec(mut self, overrides: Value) -> Self {
+        if let (Some(spec), Value::Object(overrides)) = (self.spec.as_object_mut(), overrides) {
+            spec.extend(overrides);
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn with_status(mut self, status: SecretManagerConfigStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// # Panics
+    /// Panics if the overrides don't form a valid `SecretManagerConfig`
+    #[must_use]
+    pub fn build(self) -> SecretManagerConfig {
+        let mut config: SecretManagerConfig = serde_json::from_value(json!({
+            "apiVersion": "secret-management.octopilot.io/v1beta1",
+            "kind": "SecretManagerConfig",
+            "metadata": self.metadata,
+            "spec": self.spec
+        }))
+        .unwrap_or_else(|e| panic!("Failed to build SecretManagerConfig: {e}"));
+        config.status = self.status;
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::ProviderConfig;
+
+    #[test]
+    fn test_config_fixture_defaults_and_overrides() {
+        let config = ConfigFixture::new("app").build();
+        assert_eq!(config.metadata.namespace.as_deref(), Some("team-a"));
+        assert_eq!(config.metadata.generation, None);
+        assert!(matches!(config.spec.provider, ProviderConfig::Gcp(_)));
+        assert!(config.status.is_none());
+
+        let config = ConfigFixture::new("app")
+            .with_namespace("team-b")
+            .with_generation(3)
+            .with_source("Application", "argo-app")
+            .with_spec(json!({ "provider": { "type": "aws", "aws": { "region": "us-east-1" } } }))
+            .with_status(SecretManagerConfigStatus::default())
+            .build();
+        assert_eq!(config.metadata.namespace.as_deref(), Some("team-b"));
+        assert_eq!(config.metadata.generation, Some(3));
+        assert_eq!(config.spec.source_ref.name, "argo-app");
+        assert!(matches!(config.spec.provider, ProviderConfig::Aws(_)));
+        assert_eq!(config.spec.secrets.environment, "dev");
+        assert!(config.status.is_some());
+    }
+}
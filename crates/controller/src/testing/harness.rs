@@ -0,0 +1,551 @@
+//! # Reconcile Harness
+//!
+//! Runs the real `reconcile()` against a `FakeApiServer` and the fake providers.
+//!
+//! ```rust,ignore
+//! let harness = ReconcileHarness::new().await?;
+//! harness
+//!     .add_git_repository("flux-system", "repo", "main@sha1:abc", &[(
+//!         "deployment-configuration/profiles/dev/application.secrets.env",
+//!         "DB_PASSWORD=hunter2\n",
+//!     )])
+//!     .await?;
+//! harness.apply(&config)?;
+//! harness.reconcile("team-a", "my-secrets").await?;
+//! assert_eq!(harness.providers.secrets.values().len(), 1);
+//! ```
+//!
+//! GitRepository artifacts are placed straight into the reconciler's artifact cache, so no
+//! source-controller or download is needed. Each `reconcile` reads the resource back from
+//! the fake API server, so status written by one run is seen by the next.
+
+use crate::config::{ControllerConfig, SharedControllerConfig};
+use crate::controller::reconciler::artifact::cache::ArtifactCache;
+use crate::controller::reconciler::reconcile::reconcile;
+use crate::controller::reconciler::types::{Reconciler, TriggerSource};
+use crate::crd::{SecretManagerConfig, SecretManagerConfigStatus};
+use crate::testing::api_server::FakeApiServer;
+use crate::testing::providers::FakeProviders;
+use anyhow::{Context, Result};
+use kube::api::{ApiResource, DynamicObject};
+use kube::core::GroupVersionKind;
+use kube_runtime::controller::Action;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+/// Real reconciler wired to a fake API server and fake providers
+pub struct ReconcileHarness {
+    pub api: FakeApiServer,
+    pub providers: FakeProviders,
+    pub reconciler: Arc<Reconciler>,
+    pub controller_config: SharedControllerConfig,
+    // Holds the artifact cache; removed when the harness is dropped
+    _artifact_root: TempDir,
+}
+
+impl ReconcileHarness {
+    /// Harness with an empty API server, empty fake stores and the default controller config
+    pub async fn new() -> Result<Self> {
+        let api = FakeApiServer::new();
+        let providers = FakeProviders::default();
+        let artifact_root = tempfile::tempdir().context("Failed to create artifact cache dir")?;
+
+        let mut reconciler = Reconciler::new(api.client())
+            .await?
+            .with_provider_factory(Arc::new(providers.clone()));
+        reconciler.artifact_cache = Arc::new(ArtifactCache::new(
+            artifact_root.path().to_path_buf(),
+            crate::constants::DEFAULT_ARTIFACT_CACHE_MAX_ENTRIES,
+        ));
+
+        Ok(Self {
+            api,
+            providers,
+            reconciler: Arc::new(reconciler),
+            controller_config: Arc::new(RwLock::new(ControllerConfig::default())),
+            _artifact_root: artifact_root,
+        })
+    }
+
+    /// Add a ready FluxCD GitRepository whose artifact holds `files` (path, contents)
+    pub async fn add_git_repository(
+        &self,
+        namespace: &str,
+        name: &str,
+        revision: &str,
+        files: &[(&str, &str)],
+    ) -> Result<()> {
+        let digest = format!(
+            "sha256:{:x}",
+            Sha256::digest(format!("{namespace}/{name}@{revision}").as_bytes())
+        );
+        let url = format!(
+            "http://source-controller.flux-system.svc.cluster.local/gitrepository/{namespace}/{name}/artifact.tar.gz"
+        );
+
+        let cache = &self.reconciler.artifact_cache;
+        let key = ArtifactCache::key(Some(&digest), revision, &url);
+        let staged = cache.staging_dir(&key).join("content");
+        for (path, contents) in files {
+            let file = staged.join(path);
+            if let Some(parent) = file.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&file, contents).await?;
+        }
+        tokio::fs::create_dir_all(&staged).await?;
+        cache.insert(&key, &staged).await?;
+
+        let ar = ApiResource::from_gvk(&GroupVersionKind {
+            group: "source.toolkit.fluxcd.io".to_string(),
+            version: "v1beta2".to_string(),
+            kind: "GitRepository".to_string(),
+        });
+        let mut git_repository = DynamicObject::new(name, &ar).within(namespace);
+        git_repository.data = json!({
+            "spec": { "interval": "1m", "url": "https://example.com/repo.git" },
+            "status": {
+                "artifact": { "url": url, "revision": revision, "digest": digest },
+                "conditions": [{ "type": "Ready", "status": "True", "reason": "Succeeded" }],
+            },
+        });
+        self.api.insert_dynamic(&ar, &git_repository)
+    }
+
+    /// Create or replace a SecretManagerConfig
+    pub fn apply(&self, config: &SecretManagerConfig) -> Result<()> {
+        self.api.insert(config)
+    }
+
+    /// Reconcile a SecretManagerConfig as stored in the fake API server
+    pub async fn reconcile(&self, namespace: &str, name: &str) -> Result<Action> {
+        self.reconcile_with(namespace, name, TriggerSource::TimerBased)
+            .await
+    }
+
+    /// Reconcile a SecretManagerConfig with an explicit trigger source
+    pub async fn reconcile_with(
+        &self,
+        namespace: &str,
+        name: &str,
+        trigger_source: TriggerSource,
+    ) -> Result<Action> {
+        let config = self
+            .config(namespace, name)
+            .with_context(|| format!("SecretManagerConfig {namespace}/{name} not found"))?;
+        Ok(reconcile(
+            Arc::new(config),
+            Arc::clone(&self.reconciler),
+            trigger_source,
+            Arc::clone(&self.controller_config),
+        )
+        .await?)
+    }
+
+    /// A SecretManagerConfig as currently stored
+    #[must_use]
+    pub fn config(&self, namespace: &str, name: &str) -> Option<SecretManagerConfig> {
+        self.api.get(Some(namespace), name)
+    }
+
+    /// Status of a SecretManagerConfig as currently stored
+    #[must_use]
+    pub fn status(&self, namespace: &str, name: &str) -> Option<SecretManagerConfigStatus> {
+        self.config(namespace, name).and_then(|c| c.status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::ProviderError;
+    use crate::secret::SecretString;
+    use crate::testing::ConfigFixture;
+
+    fn config(name: &str) -> SecretManagerConfig {
+        ConfigFixture::new(name)
+            .with_uid("test-uid")
+            .with_spec(json!({ "secrets": { "environment": "dev", "prefix": "app" } }))
+            .build()
+    }
+
+    async fn harness() -> ReconcileHarness {
+        let harness = ReconcileHarness::new()
+            .await
+            .unwrap_or_else(|e| panic!("Failed to create harness: {e}"));
+        harness
+            .add_git_repository(
+                "flux-system",
+                "repo",
+                "main@sha1:0123456789abcdef",
+                &[(
+                    "deployment-configuration/profiles/dev/application.secrets.env",
+                    "DB_PASSWORD=hunter2\nAPI_KEY=abc123\n",
+                )],
+            )
+            .await
+            .unwrap_or_else(|e| panic!("Failed to add GitRepository: {e}"));
+        harness
+    }
+
+    fn last_outcome(harness: &ReconcileHarness, name: &str) -> Option<String> {
+        harness
+            .status("team-a", name)
+            .and_then(|s| s.recent_reconciles)
+            .and_then(|history| history.last().map(|r| r.outcome.clone()))
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_syncs_secrets_to_fake_provider() {
+        let harness = harness().await;
+        harness
+            .apply(&config("sync"))
+            .unwrap_or_else(|e| panic!("Failed to apply config: {e}"));
+
+        harness
+            .reconcile("team-a", "sync")
+            .await
+            .unwrap_or_else(|e| panic!("Reconcile failed: {e}"));
+
+        let values = harness.providers.secrets.values();
+        assert!(values.values().any(|v| v == "hunter2"), "{values:?}");
+        assert!(values.values().any(|v| v == "abc123"), "{values:?}");
+        assert_eq!(last_outcome(&harness, "sync").as_deref(), Some("Succeeded"));
+
+        // Nothing changed in Git, so a second run writes nothing new
+        let versions: usize = values
+            .keys()
+            .map(|name| harness.providers.secrets.version_count(name))
+            .sum();
+        harness
+            .reconcile("team-a", "sync")
+            .await
+            .unwrap_or_else(|e| panic!("Reconcile failed: {e}"));
+        let versions_after: usize = values
+            .keys()
+            .map(|name| harness.providers.secrets.version_count(name))
+            .sum();
+        assert_eq!(versions, versions_after);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_reports_provider_failures() {
+        let harness = harness().await;
+        harness
+            .apply(&config("failing"))
+            .unwrap_or_else(|e| panic!("Failed to apply config: {e}"));
+        harness
+            .providers
+            .secrets
+            .fail_next(ProviderError::PermissionDenied("denied".to_string()));
+
+        let _ = harness.reconcile("team-a", "failing").await;
+
+        assert!(!harness.providers.secrets.calls().is_empty());
+        assert_ne!(
+            last_outcome(&harness, "failing").as_deref(),
+            Some("Succeeded")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_stops_at_value_limits() {
+        let harness = harness().await;
+        let mut config = config("limited");
+        config.spec.secrets.limits = Some(crate::crd::SecretLimits {
+            max_value_bytes: Some(6),
+            ..Default::default()
+        });
+        harness
+            .apply(&config)
+            .unwrap_or_else(|e| panic!("Failed to apply config: {e}"));
+
+        harness
+            .reconcile("team-a", "limited")
+            .await
+            .unwrap_or_else(|e| panic!("Reconcile failed: {e}"));
+
+        // hunter2 is 7 bytes, so nothing from the profile is written
+        assert!(harness.providers.secrets.values().is_empty());
+        assert_eq!(
+            last_outcome(&harness, "limited").as_deref(),
+            Some("LimitExceeded")
+        );
+        let status = harness
+            .status("team-a", "limited")
+            .unwrap_or_else(|| panic!("No status written"));
+        let ready = status
+            .conditions
+            .iter()
+            .find(|c| c.r#type == "Ready")
+            .unwrap_or_else(|| panic!("No Ready condition: {:?}", status.conditions));
+        assert_eq!(ready.reason.as_deref(), Some("LimitExceeded"));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_rejects_policy_violations() {
+        let harness = harness().await;
+        let mut config = config("policy");
+        config.spec.secrets.policy = Some(crate::crd::SecretPolicy {
+            require_sops_for_keys: Some(vec!["*_PASSWORD".to_string()]),
+            ..Default::default()
+        });
+        harness
+            .apply(&config)
+            .unwrap_or_else(|e| panic!("Failed to apply config: {e}"));
+
+        harness
+            .reconcile("team-a", "policy")
+            .await
+            .unwrap_or_else(|e| panic!("Reconcile failed: {e}"));
+
+        // DB_PASSWORD is committed in plain text, so nothing from the profile is written
+        assert!(harness.providers.secrets.values().is_empty());
+        assert_eq!(
+            last_outcome(&harness, "policy").as_deref(),
+            Some("PolicyViolation")
+        );
+        let status = harness
+            .status("team-a", "policy")
+            .unwrap_or_else(|| panic!("No status written"));
+        let ready = status
+            .conditions
+            .iter()
+            .find(|c| c.r#type == "Ready")
+            .unwrap_or_else(|| panic!("No Ready condition: {:?}", status.conditions));
+        assert_eq!(ready.reason.as_deref(), Some("PolicyViolation"));
+        assert!(
+            ready
+                .message
+                .as_deref()
+                .is_some_and(|m| m.contains("DB_PASSWORD")),
+            "{ready:?}"
+        );
+        assert!(
+            harness
+                .api
+                .requests()
+                .iter()
+                .any(|r| r.method == "POST" && r.path.ends_with("/events")),
+            "No event published"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_mirrors_secret_source() {
+        let harness = ReconcileHarness::new()
+            .await
+            .unwrap_or_else(|e| panic!("Failed to create harness: {e}"));
+        let mut secret = k8s_openapi::api::core::v1::Secret {
+            metadata: kube::api::ObjectMeta {
+                name: Some("generated-credentials".to_string()),
+                namespace: Some("team-a".to_string()),
+                ..Default::default()
+            },
+            data: Some(
+                [(
+                    "DB_PASSWORD".to_string(),
+                    k8s_openapi::ByteString(b"generated".to_vec()),
+                )]
+                .into(),
+            ),
+            ..Default::default()
+        };
+        harness
+            .api
+            .insert(&secret)
+            .unwrap_or_else(|e| panic!("Failed to add Secret: {e}"));
+        let mut config = config("mirror");
+        config.spec.source_ref.kind = "Secret".to_string();
+        config.spec.source_ref.name = "generated-credentials".to_string();
+        config.spec.source_ref.namespace = "team-a".to_string();
+        harness
+            .apply(&config)
+            .unwrap_or_else(|e| panic!("Failed to apply config: {e}"));
+
+        harness
+            .reconcile("team-a", "mirror")
+            .await
+            .unwrap_or_else(|e| panic!("Reconcile failed: {e}"));
+        let values = harness.providers.secrets.values();
+        assert!(values.values().any(|v| v == "generated"), "{values:?}");
+        let first_revision = harness
+            .status("team-a", "mirror")
+            .and_then(|s| s.last_synced_revision);
+        assert!(first_revision.is_some());
+
+        // Rotating the Secret bumps its resourceVersion and is mirrored on the next run
+        secret.data = Some(
+            [(
+                "DB_PASSWORD".to_string(),
+                k8s_openapi::ByteString(b"rotated".to_vec()),
+            )]
+            .into(),
+        );
+        harness
+            .api
+            .insert(&secret)
+            .unwrap_or_else(|e| panic!("Failed to update Secret: {e}"));
+        harness
+            .reconcile("team-a", "mirror")
+            .await
+            .unwrap_or_else(|e| panic!("Reconcile failed: {e}"));
+        let values = harness.providers.secrets.values();
+        assert!(values.values().any(|v| v == "rotated"), "{values:?}");
+        let status = harness
+            .status("team-a", "mirror")
+            .unwrap_or_else(|| panic!("No status written"));
+        assert_ne!(status.last_synced_revision, first_revision);
+        assert_eq!(
+            last_outcome(&harness, "mirror").as_deref(),
+            Some("Succeeded")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_reports_conflicting_writer() {
+        use crate::controller::reconciler::last_writer::{
+            CONFLICT_DETECTION_GATE, OWNER_UID_LABEL,
+        };
+        use crate::controller::reconciler::status::CONFLICTING_WRITER_CONDITION;
+        use crate::provider::SecretManagerProvider;
+
+        let harness = harness().await;
+        harness
+            .controller_config
+            .write()
+            .await
+            .feature_gates
+            .insert(CONFLICT_DETECTION_GATE.to_string(), true);
+        harness
+            .apply(&config("conflict"))
+            .unwrap_or_else(|e| panic!("Failed to apply config: {e}"));
+        harness
+            .reconcile("team-a", "conflict")
+            .await
+            .unwrap_or_else(|e| panic!("Reconcile failed: {e}"));
+
+        let secrets = &harness.providers.secrets;
+        let name = secrets
+            .values()
+            .into_iter()
+            .find(|(_, value)| value == "hunter2")
+            .map(|(name, _)| name)
+            .unwrap_or_else(|| panic!("DB_PASSWORD not synced: {:?}", secrets.values()));
+        assert_eq!(
+            secrets
+                .labels(&name)
+                .get(OWNER_UID_LABEL)
+                .map(String::as_str),
+            Some("test-uid")
+        );
+
+        // A write outside the controller adds a version the stamp doesn't know about
+        secrets
+            .create_or_update_secret(&name, &SecretString::from("tampered"), "dev", "us-central1")
+            .await
+            .unwrap_or_else(|e| panic!("Foreign write failed: {e}"));
+        harness
+            .reconcile("team-a", "conflict")
+            .await
+            .unwrap_or_else(|e| panic!("Reconcile failed: {e}"));
+
+        assert_eq!(secrets.value(&name).as_deref(), Some("hunter2"));
+        let condition = |harness: &ReconcileHarness| {
+            harness
+                .status("team-a", "conflict")
+                .and_then(|s| {
+                    s.conditions
+                        .into_iter()
+                        .find(|c| c.r#type == CONFLICTING_WRITER_CONDITION)
+                })
+                .unwrap_or_else(|| panic!("No ConflictingWriter condition"))
+        };
+        let raised = condition(&harness);
+        assert_eq!(raised.status, "True");
+        assert_eq!(raised.reason.as_deref(), Some("ForeignWrite"));
+
+        // The drifted value was overwritten and re-stamped, so the next sync clears it
+        harness
+            .reconcile("team-a", "conflict")
+            .await
+            .unwrap_or_else(|e| panic!("Reconcile failed: {e}"));
+        assert_eq!(condition(&harness).status, "False");
+        assert_eq!(
+            last_outcome(&harness, "conflict").as_deref(),
+            Some("Succeeded")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_waits_for_missing_source() {
+        let harness = ReconcileHarness::new()
+            .await
+            .unwrap_or_else(|e| panic!("Failed to create harness: {e}"));
+        harness
+            .apply(&config("no-source"))
+            .unwrap_or_else(|e| panic!("Failed to apply config: {e}"));
+
+        harness
+            .reconcile("team-a", "no-source")
+            .await
+            .unwrap_or_else(|e| panic!("Reconcile failed: {e}"));
+
+        assert!(harness.providers.secrets.calls().is_empty());
+        assert_eq!(
+            last_outcome(&harness, "no-source").as_deref(),
+            Some("WaitingForSource")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_reports_missing_git_credentials() {
+        let harness = ReconcileHarness::new()
+            .await
+            .unwrap_or_else(|e| panic!("Failed to create harness: {e}"));
+        let mut config = config("git-direct");
+        config.spec.source_ref.kind = "GitDirect".to_string();
+        config.spec.source_ref.name = "service-config".to_string();
+        config.spec.source_ref.namespace = "team-a".to_string();
+        config.spec.source_ref.url = Some("https://git.example.com/org/private.git".to_string());
+        config.spec.source_ref.git_credentials = Some(crate::crd::GitCredentialsRef {
+            name: "missing-credentials".to_string(),
+            namespace: None,
+        });
+        harness
+            .apply(&config)
+            .unwrap_or_else(|e| panic!("Failed to apply config: {e}"));
+
+        let _ = harness.reconcile("team-a", "git-direct").await;
+
+        assert!(harness.providers.secrets.calls().is_empty());
+        let status = harness
+            .status("team-a", "git-direct")
+            .unwrap_or_else(|| panic!("No status written"));
+        let ready = status
+            .conditions
+            .iter()
+            .find(|c| c.r#type == "Ready")
+            .unwrap_or_else(|| panic!("No Ready condition: {:?}", status.conditions));
+        assert_eq!(ready.status, "False");
+        assert_eq!(ready.reason.as_deref(), Some("CredentialsInvalid"));
+        for condition_type in ["Stalled", "SourceAvailable"] {
+            let condition = status
+                .conditions
+                .iter()
+                .find(|c| c.r#type == condition_type)
+                .unwrap_or_else(|| {
+                    panic!("No {condition_type} condition: {:?}", status.conditions)
+                });
+            assert_eq!(condition.reason.as_deref(), Some("CredentialsInvalid"));
+        }
+        assert!(
+            !status.conditions.iter().any(|c| c.r#type == "Reconciling"),
+            "Reconciling left set: {:?}",
+            status.conditions
+        );
+    }
+}
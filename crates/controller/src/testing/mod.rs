@@ -0,0 +1,22 @@
+//! # Testing
+//!
+//! Test doubles for running the reconciler without a cluster or cloud provider, for our own
+//! tests and for downstream forks. Enabled with the `testing` feature.
+//!
+//! - `providers`: `FakeSecretManagerProvider` and `FakeConfigStoreProvider` - in-memory stores
+//!   that record calls and can be scripted to fail
+//! - `api_server`: `FakeApiServer` - in-memory Kubernetes API behind a real `kube::Client`
+//! - `harness`: `ReconcileHarness` - runs `reconcile()` against both
+//! - `fixtures`: `ConfigFixture` - builds `SecretManagerConfig`s with test defaults
+
+pub mod api_server;
+pub mod fixtures;
+pub mod harness;
+pub mod providers;
+
+pub use api_server::{ApiRequest, FakeApiServer};
+pub use fixtures::ConfigFixture;
+pub use harness::ReconcileHarness;
+pub use providers::{
+    FakeConfigStoreProvider, FakeProviders, FakeSecretManagerProvider, FakeStore, ProviderCall,
+};
@@ -0,0 +1,604 @@
+//! # Fake Providers
+//!
+//! In-memory `SecretManagerProvider` and `ConfigStoreProvider` implementations.
+//!
+//! Both fakes are cheap to clone; clones share the same store, so a test can keep one handle
+//! while the reconciler writes through another. Every call is recorded in order, and calls can
+//! be scripted to fail with a `ProviderError` - either the next call (`fail_next`) or every
+//! call touching a name (`fail_on`). Bulk reads never fail: they leave out names scripted with
+//! `fail_on` and don't consume `fail_next`, like a provider whose per-secret read reports the
+//! error.
+
+use crate::controller::reconciler::reconcile::ProviderFactory;
+use crate::crd::SecretManagerConfig;
+use crate::provider::{
+    ConfigStoreProvider, ProviderError, SecretManagerProvider, SecretMetadata, SecretVersionIds,
+};
+use crate::secret::SecretString;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A call made to a fake provider
+/// Config store calls use the config key as `name`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProviderCall {
+    CreateOrUpdate { name: String, value: String },
+    Get { name: String },
+    GetMany { names: Vec<String> },
+    Delete { name: String },
+    Disable { name: String },
+    Enable { name: String },
+    List { prefix: String },
+    GetMetadata { name: String },
+    SetLabels { name: String },
+    SetExpiration { name: String },
+}
+
+impl ProviderCall {
+    /// Name (or list prefix) the call refers to
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            ProviderCall::CreateOrUpdate { name, .. }
+            | ProviderCall::Get { name }
+            | ProviderCall::Delete { name }
+            | ProviderCall::Disable { name }
+            | ProviderCall::Enable { name }
+            | ProviderCall::GetMetadata { name }
+            | ProviderCall::SetLabels { name }
+            | ProviderCall::SetExpiration { name } => name,
+            ProviderCall::List { prefix } => prefix,
+            ProviderCall::GetMany { .. } => "",
+        }
+    }
+
+    /// Whether the call changes the store
+    #[must_use]
+    pub fn is_write(&self) -> bool {
+        matches!(
+            self,
+            ProviderCall::CreateOrUpdate { .. }
+                | ProviderCall::Delete { .. }
+                | ProviderCall::Disable { .. }
+                | ProviderCall::Enable { .. }
+                | ProviderCall::SetLabels { .. }
+                | ProviderCall::SetExpiration { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    value: String,
+    enabled: bool,
+    versions: usize,
+    created: DateTime<Utc>,
+    labels: HashMap<String, String>,
+    expire_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: BTreeMap<String, Entry>,
+    calls: Vec<ProviderCall>,
+    fail_next: VecDeque<ProviderError>,
+    fail_on: HashMap<String, ProviderError>,
+}
+
+/// Store shared by the fake providers
+#[derive(Debug, Clone, Default)]
+pub struct FakeStore {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeStore {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record `call` and return the scripted failure for it, if any
+    fn record(&self, call: ProviderCall) -> Result<()> {
+        let mut state = self.lock();
+        let scripted = state
+            .fail_next
+            .pop_front()
+            .or_else(|| state.fail_on.get(call.name()).cloned());
+        state.calls.push(call);
+        match scripted {
+            Some(error) => Err(error.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Seed a value without recording a call
+    pub fn insert(&self, name: impl Into<String>, value: impl Into<String>) {
+        self.lock().entries.insert(
+            name.into(),
+            Entry {
+                value: value.into(),
+                enabled: true,
+                versions: 1,
+                created: Utc::now(),
+                labels: HashMap::new(),
+                expire_time: None,
+            },
+        );
+    }
+
+    /// Current value of `name`
+    #[must_use]
+    pub fn value(&self, name: &str) -> Option<String> {
+        self.lock().entries.get(name).map(|e| e.value.clone())
+    }
+
+    /// Whether `name` is enabled (`None` if it doesn't exist)
+    #[must_use]
+    pub fn is_enabled(&self, name: &str) -> Option<bool> {
+        self.lock().entries.get(name).map(|e| e.enabled)
+    }
+
+    /// Number of versions written for `name` (0 if it doesn't exist)
+    #[must_use]
+    pub fn version_count(&self, name: &str) -> usize {
+        self.lock().entries.get(name).map_or(0, |e| e.versions)
+    }
+
+    /// Labels of `name` (empty if it doesn't exist)
+    #[must_use]
+    pub fn labels(&self, name: &str) -> HashMap<String, String> {
+        self.lock()
+            .entries
+            .get(name)
+            .map(|e| e.labels.clone())
+            .unwrap_or_default()
+    }
+
+    /// All stored values, by name
+    #[must_use]
+    pub fn values(&self) -> BTreeMap<String, String> {
+        self.lock()
+            .entries
+            .iter()
+            .map(|(name, e)| (name.clone(), e.value.clone()))
+            .collect()
+    }
+
+    /// Calls made so far, oldest first
+    #[must_use]
+    pub fn calls(&self) -> Vec<ProviderCall> {
+        self.lock().calls.clone()
+    }
+
+    /// Calls that changed the store, oldest first
+    #[must_use]
+    pub fn writes(&self) -> Vec<ProviderCall> {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|c| c.is_write())
+            .cloned()
+            .collect()
+    }
+
+    /// Forget the recorded calls
+    pub fn clear_calls(&self) {
+        self.lock().calls.clear();
+    }
+
+    /// Fail the next call with `error` (queued failures are used in order)
+    pub fn fail_next(&self, error: ProviderError) {
+        self.lock().fail_next.push_back(error);
+    }
+
+    /// Fail every call touching `name` with `error` until `clear_failures`
+    pub fn fail_on(&self, name: impl Into<String>, error: ProviderError) {
+        self.lock().fail_on.insert(name.into(), error);
+    }
+
+    /// Remove all scripted failures
+    pub fn clear_failures(&self) {
+        let mut state = self.lock();
+        state.fail_next.clear();
+        state.fail_on.clear();
+    }
+
+    fn upsert(&self, name: &str, value: &str) -> bool {
+        let mut state = self.lock();
+        match state.entries.get_mut(name) {
+            Some(entry) if entry.value == value => false,
+            Some(entry) => {
+                entry.value = value.to_string();
+                entry.versions += 1;
+                true
+            }
+            None => {
+                state.entries.insert(
+                    name.to_string(),
+                    Entry {
+                        value: value.to_string(),
+                        enabled: true,
+                        versions: 1,
+                        created: Utc::now(),
+                        labels: HashMap::new(),
+                        expire_time: None,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        self.lock()
+            .entries
+            .get(name)
+            .filter(|e| e.enabled)
+            .map(|e| e.value.clone())
+    }
+
+    fn remove(&self, name: &str) -> Result<()> {
+        match self.lock().entries.remove(name) {
+            Some(_) => Ok(()),
+            None => Err(ProviderError::NotFound(format!("{name} not found")).into()),
+        }
+    }
+
+    fn set_enabled(&self, name: &str, enabled: bool) -> bool {
+        match self.lock().entries.get_mut(name) {
+            Some(entry) if entry.enabled != enabled => {
+                entry.enabled = enabled;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn names(&self, prefix: &str) -> Vec<String> {
+        self.lock()
+            .entries
+            .keys()
+            .filter(|name| name.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+}
+
+/// In-memory secret store
+#[derive(Debug, Clone, Default)]
+pub struct FakeSecretManagerProvider {
+    store: FakeStore,
+}
+
+impl FakeSecretManagerProvider {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed a secret (not recorded as a call)
+    #[must_use]
+    pub fn with_secret(self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.store.insert(name, value);
+        self
+    }
+}
+
+impl Deref for FakeSecretManagerProvider {
+    type Target = FakeStore;
+
+    fn deref(&self) -> &FakeStore {
+        &self.store
+    }
+}
+
+#[async_trait]
+impl SecretManagerProvider for FakeSecretManagerProvider {
+    async fn create_or_update_secret(
+        &self,
+        secret_name: &str,
+        secret_value: &SecretString,
+        _environment: &str,
+        _location: &str,
+    ) -> Result<bool> {
+        self.store.record(ProviderCall::CreateOrUpdate {
+            name: secret_name.to_string(),
+            value: secret_value.expose_secret().to_string(),
+        })?;
+        Ok(self.store.upsert(secret_name, secret_value.expose_secret()))
+    }
+
+    async fn get_secret_value(&self, secret_name: &str) -> Result<Option<SecretString>> {
+        self.store.record(ProviderCall::Get {
+            name: secret_name.to_string(),
+        })?;
+        Ok(self.store.get(secret_name).map(SecretString::new))
+    }
+
+    async fn get_secret_values(
+        &self,
+        secret_names: &[String],
+    ) -> Result<HashMap<String, Option<SecretString>>> {
+        let mut state = self.store.lock();
+        state.calls.push(ProviderCall::GetMany {
+            names: secret_names.to_vec(),
+        });
+        Ok(secret_names
+            .iter()
+            .filter(|name| !state.fail_on.contains_key(*name))
+            .filter_map(|name| match state.entries.get(name) {
+                Some(entry) if entry.enabled => {
+                    Some((name.clone(), Some(SecretString::from(entry.value.as_str()))))
+                }
+                // Disabled secrets can't be read in bulk either
+                Some(_) => None,
+                None => Some((name.clone(), None)),
+            })
+            .collect())
+    }
+
+    async fn delete_secret(&self, secret_name: &str) -> Result<()> {
+        self.store.record(ProviderCall::Delete {
+            name: secret_name.to_string(),
+        })?;
+        self.store.remove(secret_name)
+    }
+
+    async fn disable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.store.record(ProviderCall::Disable {
+            name: secret_name.to_string(),
+        })?;
+        Ok(self.store.set_enabled(secret_name, false))
+    }
+
+    async fn enable_secret(&self, secret_name: &str) -> Result<bool> {
+        self.store.record(ProviderCall::Enable {
+            name: secret_name.to_string(),
+        })?;
+        Ok(self.store.set_enabled(secret_name, true))
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        self.store.record(ProviderCall::List {
+            prefix: prefix.to_string(),
+        })?;
+        Ok(self.store.names(prefix))
+    }
+
+    async fn get_secret_metadata(&self, secret_name: &str) -> Result<Option<SecretMetadata>> {
+        self.store.record(ProviderCall::GetMetadata {
+            name: secret_name.to_string(),
+        })?;
+        Ok(self
+            .store
+            .lock()
+            .entries
+            .get(secret_name)
+            .map(|e| SecretMetadata {
+                name: secret_name.to_string(),
+                labels: e.labels.clone(),
+                create_time: Some(e.created),
+                version_count: e.versions,
+                expire_time: e.expire_time,
+            }))
+    }
+
+    async fn version_ids(&self, secret_name: &str) -> Result<SecretVersionIds> {
+        let versions = self.store.version_count(secret_name);
+        Ok(SecretVersionIds {
+            previous: versions
+                .checked_sub(1)
+                .filter(|v| *v > 0)
+                .map(|v| v.to_string()),
+            current: Some(versions).filter(|v| *v > 0).map(|v| v.to_string()),
+        })
+    }
+
+    async fn set_secret_labels(
+        &self,
+        secret_name: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        self.store.record(ProviderCall::SetLabels {
+            name: secret_name.to_string(),
+        })?;
+        match self.store.lock().entries.get_mut(secret_name) {
+            Some(entry) => {
+                entry.labels.extend(labels.clone());
+                Ok(())
+            }
+            None => Err(ProviderError::NotFound(format!("{secret_name} not found")).into()),
+        }
+    }
+
+    async fn set_secret_expiration(
+        &self,
+        secret_name: &str,
+        expire_time: DateTime<Utc>,
+    ) -> Result<()> {
+        self.store.record(ProviderCall::SetExpiration {
+            name: secret_name.to_string(),
+        })?;
+        match self.store.lock().entries.get_mut(secret_name) {
+            Some(entry) => {
+                entry.expire_time = Some(expire_time);
+                Ok(())
+            }
+            None => Err(ProviderError::NotFound(format!("{secret_name} not found")).into()),
+        }
+    }
+}
+
+/// In-memory config store
+#[derive(Debug, Clone, Default)]
+pub struct FakeConfigStoreProvider {
+    store: FakeStore,
+}
+
+impl FakeConfigStoreProvider {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed a config value (not recorded as a call)
+    #[must_use]
+    pub fn with_config(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.store.insert(key, value);
+        self
+    }
+}
+
+impl Deref for FakeConfigStoreProvider {
+    type Target = FakeStore;
+
+    fn deref(&self) -> &FakeStore {
+        &self.store
+    }
+}
+
+#[async_trait]
+impl ConfigStoreProvider for FakeConfigStoreProvider {
+    async fn create_or_update_config(&self, config_key: &str, config_value: &str) -> Result<bool> {
+        self.store.record(ProviderCall::CreateOrUpdate {
+            name: config_key.to_string(),
+            value: config_value.to_string(),
+        })?;
+        Ok(self.store.upsert(config_key, config_value))
+    }
+
+    async fn get_config_value(&self, config_key: &str) -> Result<Option<String>> {
+        self.store.record(ProviderCall::Get {
+            name: config_key.to_string(),
+        })?;
+        Ok(self.store.get(config_key))
+    }
+
+    async fn delete_config(&self, config_key: &str) -> Result<()> {
+        self.store.record(ProviderCall::Delete {
+            name: config_key.to_string(),
+        })?;
+        self.store.remove(config_key)
+    }
+
+    async fn list_configs(&self, prefix: &str) -> Result<Vec<String>> {
+        self.store.record(ProviderCall::List {
+            prefix: prefix.to_string(),
+        })?;
+        Ok(self.store.names(prefix))
+    }
+}
+
+/// Provider factory handing out the same fake stores for every resource
+#[derive(Debug, Clone, Default)]
+pub struct FakeProviders {
+    pub secrets: FakeSecretManagerProvider,
+    pub configs: FakeConfigStoreProvider,
+}
+
+impl ProviderFactory for FakeProviders {
+    fn secret_manager(&self, _config: &SecretManagerConfig) -> Box<dyn SecretManagerProvider> {
+        Box::new(self.secrets.clone())
+    }
+
+    fn config_store(&self, _config: &SecretManagerConfig) -> Box<dyn ConfigStoreProvider> {
+        Box::new(self.configs.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fake_secret_manager_records_writes() {
+        let provider = FakeSecretManagerProvider::new().with_secret("existing", "v1");
+
+        let created = provider
+            .create_or_update_secret("new", &SecretString::from("value"), "dev", "us-central1")
+            .await
+            .unwrap_or_else(|e| panic!("create failed: {e}"));
+        let unchanged = provider
+            .create_or_update_secret("existing", &SecretString::from("v1"), "dev", "us-central1")
+            .await
+            .unwrap_or_else(|e| panic!("update failed: {e}"));
+        assert!(created);
+        assert!(!unchanged);
+        assert_eq!(provider.value("new").as_deref(), Some("value"));
+        assert_eq!(provider.writes().len(), 2);
+
+        assert!(
+            provider
+                .disable_secret("existing")
+                .await
+                .unwrap_or_else(|e| panic!("disable failed: {e}"))
+        );
+        assert_eq!(
+            provider
+                .get_secret_value("existing")
+                .await
+                .unwrap_or_else(|e| panic!("get failed: {e}")),
+            None
+        );
+        assert_eq!(provider.is_enabled("existing"), Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_fake_secret_manager_scripted_errors() {
+        let provider = FakeSecretManagerProvider::new();
+        provider.fail_next(ProviderError::Transient("unavailable".to_string()));
+        provider.fail_on(
+            "locked",
+            ProviderError::PermissionDenied("denied".to_string()),
+        );
+
+        let error = provider
+            .create_or_update_secret("any", &SecretString::from("value"), "dev", "")
+            .await
+            .err()
+            .unwrap_or_else(|| panic!("expected scripted failure"));
+        assert!(ProviderError::find(&error).is_some_and(ProviderError::is_retryable));
+        assert!(
+            provider
+                .create_or_update_secret("any", &SecretString::from("value"), "dev", "")
+                .await
+                .is_ok()
+        );
+        assert!(provider.get_secret_value("locked").await.is_err());
+        assert_eq!(provider.calls().len(), 3);
+
+        provider.clear_failures();
+        assert!(provider.get_secret_value("locked").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fake_config_store_bulk_upsert() {
+        let store = FakeConfigStoreProvider::new().with_config("app.port", "8080");
+        let results = store
+            .bulk_upsert(vec![
+                ("app.port".to_string(), "8080".to_string()),
+                ("app.name".to_string(), "demo".to_string()),
+            ])
+            .await
+            .unwrap_or_else(|e| panic!("bulk upsert failed: {e}"));
+        assert_eq!(results.get("app.port"), Some(&false));
+        assert_eq!(results.get("app.name"), Some(&true));
+        assert_eq!(
+            store
+                .list_configs("app.")
+                .await
+                .unwrap_or_else(|e| panic!("list failed: {e}")),
+            vec!["app.name".to_string(), "app.port".to_string()]
+        );
+        assert!(ProviderError::is_not_found(
+            &store
+                .delete_config("missing")
+                .await
+                .err()
+                .unwrap_or_else(|| panic!("expected not found"))
+        ));
+    }
+}
@@ -0,0 +1,58 @@
+-- SQLite equivalent of migrations/aws/001-011 (tables only)
+-- The schema is an attached database named "aws", so the entities' schema_name resolves
+-- JSON columns are stored as TEXT; the filter functions are plain queries in DbSecretStore
+
+CREATE TABLE IF NOT EXISTS aws.secrets (
+    name TEXT PRIMARY KEY,
+    disabled BOOLEAN NOT NULL DEFAULT FALSE,
+    metadata TEXT NOT NULL DEFAULT '{}',
+    environment TEXT CHECK (environment != ''),
+    location TEXT CHECK (location != ''),
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    updated_at BIGINT NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE TABLE IF NOT EXISTS aws.versions (
+    secret_name TEXT NOT NULL,
+    version_id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    PRIMARY KEY (secret_name, version_id),
+    FOREIGN KEY (secret_name) REFERENCES secrets(name) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS aws.staging_labels (
+    secret_name TEXT NOT NULL,
+    label TEXT NOT NULL,
+    version_id TEXT NOT NULL,
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    updated_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    PRIMARY KEY (secret_name, label),
+    FOREIGN KEY (secret_name) REFERENCES secrets(name) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS aws.parameters (
+    name TEXT PRIMARY KEY,
+    parameter_type TEXT NOT NULL,
+    description TEXT,
+    metadata TEXT NOT NULL DEFAULT '{}',
+    environment TEXT CHECK (environment != ''),
+    location TEXT CHECK (location != ''),
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    updated_at BIGINT NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE TABLE IF NOT EXISTS aws.parameter_versions (
+    parameter_name TEXT NOT NULL,
+    version_id TEXT NOT NULL,
+    value TEXT NOT NULL,
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    PRIMARY KEY (parameter_name, version_id),
+    FOREIGN KEY (parameter_name) REFERENCES parameters(name) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS aws.idx_aws_versions_created_at ON versions(secret_name, created_at);
+CREATE INDEX IF NOT EXISTS aws.idx_aws_secrets_environment ON secrets(environment);
+CREATE INDEX IF NOT EXISTS aws.idx_aws_secrets_location ON secrets(location);
+CREATE INDEX IF NOT EXISTS aws.idx_aws_parameters_environment ON parameters(environment);
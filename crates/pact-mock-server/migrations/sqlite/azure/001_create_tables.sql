@@ -0,0 +1,59 @@
+-- SQLite equivalent of migrations/azure/001-011 (tables only)
+-- The schema is an attached database named "azure", so the entities' schema_name resolves
+-- JSON columns are stored as TEXT; the filter functions are plain queries in DbSecretStore
+
+CREATE TABLE IF NOT EXISTS azure.secrets (
+    name TEXT PRIMARY KEY,
+    disabled BOOLEAN NOT NULL DEFAULT FALSE,
+    metadata TEXT NOT NULL DEFAULT '{}',
+    environment TEXT CHECK (environment != ''),
+    location TEXT CHECK (location != ''),
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    updated_at BIGINT NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE TABLE IF NOT EXISTS azure.versions (
+    secret_name TEXT NOT NULL,
+    version_id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    PRIMARY KEY (secret_name, version_id),
+    FOREIGN KEY (secret_name) REFERENCES secrets(name) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS azure.deleted_secrets (
+    secret_name TEXT PRIMARY KEY,
+    deleted_date BIGINT NOT NULL,
+    scheduled_purge_date BIGINT NOT NULL,
+    FOREIGN KEY (secret_name) REFERENCES secrets(name) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS azure.app_config (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    content_type TEXT,
+    label TEXT,
+    tags TEXT NOT NULL DEFAULT '{}',
+    environment TEXT CHECK (environment != ''),
+    location TEXT CHECK (location != ''),
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    updated_at BIGINT NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE TABLE IF NOT EXISTS azure.app_config_versions (
+    config_key TEXT NOT NULL,
+    version_id TEXT NOT NULL,
+    value TEXT NOT NULL,
+    content_type TEXT,
+    label TEXT,
+    tags TEXT NOT NULL DEFAULT '{}',
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    PRIMARY KEY (config_key, version_id),
+    FOREIGN KEY (config_key) REFERENCES app_config(key) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS azure.idx_azure_versions_created_at ON versions(secret_name, created_at);
+CREATE INDEX IF NOT EXISTS azure.idx_azure_secrets_environment ON secrets(environment);
+CREATE INDEX IF NOT EXISTS azure.idx_azure_secrets_location ON secrets(location);
+CREATE INDEX IF NOT EXISTS azure.idx_azure_app_config_environment ON app_config(environment);
@@ -0,0 +1,46 @@
+-- SQLite equivalent of migrations/gcp/001-012 (tables only)
+-- The schema is an attached database named "gcp", so the entities' schema_name resolves
+-- JSON columns are stored as TEXT; the filter functions are plain queries in DbSecretStore
+
+CREATE TABLE IF NOT EXISTS gcp.secrets (
+    key TEXT PRIMARY KEY,
+    disabled BOOLEAN NOT NULL DEFAULT FALSE,
+    metadata TEXT NOT NULL DEFAULT '{}',
+    environment TEXT CHECK (environment != ''),
+    location TEXT CHECK (location != ''),
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    updated_at BIGINT NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE TABLE IF NOT EXISTS gcp.versions (
+    secret_key TEXT NOT NULL,
+    version_id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    PRIMARY KEY (secret_key, version_id),
+    FOREIGN KEY (secret_key) REFERENCES secrets(key) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS gcp.parameters (
+    key TEXT PRIMARY KEY,
+    metadata TEXT NOT NULL DEFAULT '{}',
+    environment TEXT CHECK (environment != ''),
+    location TEXT CHECK (location != ''),
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    updated_at BIGINT NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE TABLE IF NOT EXISTS gcp.parameter_versions (
+    parameter_key TEXT NOT NULL,
+    version_id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    created_at BIGINT NOT NULL DEFAULT (unixepoch()),
+    PRIMARY KEY (parameter_key, version_id),
+    FOREIGN KEY (parameter_key) REFERENCES parameters(key) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS gcp.idx_gcp_versions_created_at ON versions(secret_key, created_at);
+CREATE INDEX IF NOT EXISTS gcp.idx_gcp_secrets_environment ON secrets(environment);
+CREATE INDEX IF NOT EXISTS gcp.idx_gcp_secrets_location ON secrets(location);
+CREATE INDEX IF NOT EXISTS gcp.idx_gcp_parameters_environment ON parameters(environment);
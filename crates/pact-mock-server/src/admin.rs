@@ -0,0 +1,356 @@
+//! # Admin
+//!
+//! State management endpoints for the provider mock servers.
+//!
+//! Integration tests often need preexisting provider state (secrets with several versions,
+//! disabled versions, parameters under a path) before the controller runs. Instead of
+//! creating that state call-by-call through the provider API, a test can load it from a
+//! fixture, dump the state after a run, and reset between tests.
+//!
+//! A fixture maps a store section (`secrets`, `parameters`) to entries keyed by the
+//! provider's store key, which is the secret name for AWS and Azure and the resource name
+//! (`projects/{project}/secrets/{secret}`) for GCP:
+//!
+//! ```json
+//! {
+//!   "secrets": {
+//!     "projects/test-project/secrets/db-password": {
+//!       "versions": [
+//!         { "version_id": "1", "data": { "payload": { "data": "czNjcjN0" } }, "enabled": true, "created_at": 1700000000 }
+//!       ],
+//!       "metadata": { "replication": { "automatic": {} } }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! `GET /admin/snapshot` returns the same format, so a snapshot can be seeded back.
+//!
+//! Configuration:
+//! - `SEED_FILE`: Fixture seeded at startup
+//! - `POST /admin/seed`: Seed a fixture (`?reset=true` clears the stores first)
+//! - `GET /admin/snapshot`: Dump all stores
+//! - `POST /admin/reset`: Clear all stores
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::secrets::common::SecretEntry;
+
+/// Store section name -> store key -> entry
+pub type Fixture = BTreeMap<String, BTreeMap<String, SecretEntry>>;
+
+/// A provider store that can be seeded, dumped and cleared
+#[async_trait]
+pub trait AdminStore: Send + Sync {
+    /// All entries, keyed by store key
+    async fn snapshot(&self) -> BTreeMap<String, SecretEntry>;
+    /// Insert entries, replacing existing entries with the same key
+    async fn seed(&self, entries: BTreeMap<String, SecretEntry>) -> Result<()>;
+    /// Remove all entries and any provider-specific state
+    async fn reset(&self);
+    /// Labels pointing at versions (label -> version ID), keyed by store key
+    ///
+    /// Only AWS has them (staging labels).
+    async fn labels(&self) -> BTreeMap<String, BTreeMap<String, String>> {
+        BTreeMap::new()
+    }
+}
+
+/// Stores exposed by a mock server's admin API
+#[derive(Clone, Default)]
+pub struct AdminState {
+    stores: Vec<(String, Arc<dyn AdminStore>)>,
+}
+
+impl std::fmt::Debug for AdminState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminState")
+            .field(
+                "stores",
+                &self.stores.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl AdminState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expose a store under a fixture section name (e.g. "secrets")
+    #[must_use]
+    pub fn with_store(mut self, section: &str, store: impl AdminStore + 'static) -> Self {
+        self.stores.push((section.to_string(), Arc::new(store)));
+        self
+    }
+
+    fn store(&self, section: &str) -> Option<&Arc<dyn AdminStore>> {
+        self.stores
+            .iter()
+            .find(|(name, _)| name == section)
+            .map(|(_, store)| store)
+    }
+
+    /// Seed a fixture, returning the number of entries seeded per section
+    ///
+    /// With `reset`, all stores are cleared first. Fails without changing any store if the
+    /// fixture names an unknown section.
+    pub async fn seed(&self, fixture: Fixture, reset: bool) -> Result<BTreeMap<String, usize>> {
+        if let Some(unknown) = fixture.keys().find(|section| self.store(section).is_none()) {
+            return Err(anyhow!(
+                "Unknown store section '{}' (expected one of: {})",
+                unknown,
+                self.section_names().join(", ")
+            ));
+        }
+        if reset {
+            self.reset().await;
+        }
+
+        let mut seeded = BTreeMap::new();
+        for (section, entries) in fixture {
+            let count = entries.len();
+            if let Some(store) = self.store(&section) {
+                store
+                    .seed(entries)
+                    .await
+                    .with_context(|| format!("Failed to seed '{section}'"))?;
+            }
+            seeded.insert(section, count);
+        }
+        Ok(seeded)
+    }
+
+    /// Dump all stores
+    pub async fn snapshot(&self) -> Fixture {
+        let mut fixture = Fixture::new();
+        for (section, store) in &self.stores {
+            fixture.insert(section.clone(), store.snapshot().await);
+        }
+        fixture
+    }
+
+    /// Version labels of all stores, keyed by section
+    pub async fn labels(&self) -> BTreeMap<String, BTreeMap<String, BTreeMap<String, String>>> {
+        let mut labels = BTreeMap::new();
+        for (section, store) in &self.stores {
+            labels.insert(section.clone(), store.labels().await);
+        }
+        labels
+    }
+
+    /// Clear all stores
+    pub async fn reset(&self) {
+        for (_, store) in &self.stores {
+            store.reset().await;
+        }
+    }
+
+    /// Seed the fixture in SEED_FILE, if set
+    pub async fn seed_from_env(&self) -> Result<()> {
+        let Ok(path) = std::env::var("SEED_FILE") else {
+            return Ok(());
+        };
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read SEED_FILE '{path}'"))?;
+        let fixture: Fixture = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse SEED_FILE '{path}'"))?;
+        let seeded = self.seed(fixture, false).await?;
+        info!("Seeded {:?} from {}", seeded, path);
+        Ok(())
+    }
+
+    fn section_names(&self) -> Vec<&str> {
+        self.stores.iter().map(|(name, _)| name.as_str()).collect()
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SeedQuery {
+    #[serde(default)]
+    reset: bool,
+}
+
+async fn seed(
+    State(admin): State<AdminState>,
+    Query(query): Query<SeedQuery>,
+    Json(fixture): Json<Fixture>,
+) -> Response {
+    match admin.seed(fixture, query.reset).await {
+        Ok(seeded) => {
+            info!("Admin: seeded {:?}", seeded);
+            Json(json!({ "seeded": seeded })).into_response()
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": { "code": 400, "message": format!("{e:#}") } })),
+        )
+            .into_response(),
+    }
+}
+
+async fn snapshot(State(admin): State<AdminState>) -> Json<Fixture> {
+    Json(admin.snapshot().await)
+}
+
+async fn reset(State(admin): State<AdminState>) -> StatusCode {
+    admin.reset().await;
+    info!("Admin: all stores reset");
+    StatusCode::NO_CONTENT
+}
+
+/// Admin routes for seeding and snapshotting state, merged into each mock server's router
+pub fn admin_router(admin: AdminState) -> Router {
+    Router::new()
+        .route("/admin/seed", post(seed))
+        .route("/admin/snapshot", get(snapshot))
+        .route("/admin/reset", post(reset))
+        .with_state(admin)
+}
+
+#[async_trait]
+impl AdminStore for crate::secrets::common::SecretStore {
+    async fn snapshot(&self) -> BTreeMap<String, SecretEntry> {
+        crate::secrets::common::SecretStore::snapshot(self)
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    async fn seed(&self, entries: BTreeMap<String, SecretEntry>) -> Result<()> {
+        for (key, entry) in entries {
+            self.insert_entry(key, entry).await;
+        }
+        Ok(())
+    }
+
+    async fn reset(&self) {
+        self.clear().await;
+    }
+}
+
+#[async_trait]
+impl AdminStore for crate::secrets::common::SecretStoreEnum {
+    async fn snapshot(&self) -> BTreeMap<String, SecretEntry> {
+        crate::secrets::common::SecretStoreEnum::snapshot(self)
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    async fn seed(&self, entries: BTreeMap<String, SecretEntry>) -> Result<()> {
+        for (key, entry) in entries {
+            self.insert_entry(key, entry).await?;
+        }
+        Ok(())
+    }
+
+    async fn reset(&self) {
+        self.clear().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::common::SecretStore;
+    use axum_test::TestServer;
+
+    fn fixture() -> serde_json::Value {
+        json!({
+            "secrets": {
+                "db-password": {
+                    "versions": [
+                        { "version_id": "1", "data": { "value": "old" }, "enabled": false, "created_at": 1 },
+                        { "version_id": "2", "data": { "value": "new" }, "enabled": true, "created_at": 2 }
+                    ]
+                }
+            }
+        })
+    }
+
+    fn test_server() -> (TestServer, SecretStore) {
+        let secrets = SecretStore::new();
+        let admin = AdminState::new()
+            .with_store("secrets", secrets.clone())
+            .with_store("parameters", SecretStore::new());
+        (TestServer::new(admin_router(admin)).unwrap(), secrets)
+    }
+
+    #[tokio::test]
+    async fn test_seed_snapshot_reset() {
+        let (server, secrets) = test_server();
+
+        let response = server.post("/admin/seed").json(&fixture()).await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<serde_json::Value>()["seeded"]["secrets"], 1);
+
+        let latest = secrets.get_latest("db-password").await.unwrap();
+        assert_eq!(latest.version_id, "2");
+        assert_eq!(latest.created_at, 2);
+        assert_eq!(secrets.get_metadata("db-password").await, Some(json!({})));
+
+        let snapshot: serde_json::Value = server.get("/admin/snapshot").await.json();
+        assert_eq!(
+            snapshot["secrets"]["db-password"]["versions"][0]["enabled"],
+            false
+        );
+        assert_eq!(snapshot["parameters"], json!({}));
+
+        server
+            .post("/admin/reset")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        assert!(!secrets.exists("db-password").await);
+    }
+
+    #[tokio::test]
+    async fn test_seed_with_reset_replaces_state() {
+        let (server, secrets) = test_server();
+        secrets
+            .add_version("stale".to_string(), json!({}), None, |_, _| "1".to_string())
+            .await;
+
+        server
+            .post("/admin/seed?reset=true")
+            .json(&fixture())
+            .await
+            .assert_status_ok();
+        assert!(!secrets.exists("stale").await);
+        assert!(secrets.exists("db-password").await);
+    }
+
+    #[tokio::test]
+    async fn test_seed_rejects_unknown_section() {
+        let (server, secrets) = test_server();
+        let mut body = fixture();
+        body["certificates"] = json!({});
+
+        secrets
+            .add_version("kept".to_string(), json!({}), None, |_, _| "1".to_string())
+            .await;
+
+        server
+            .post("/admin/seed?reset=true")
+            .json(&body)
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        // Nothing is reset or seeded when the fixture is rejected
+        assert!(secrets.exists("kept").await);
+        assert!(!secrets.exists("db-password").await);
+    }
+}
@@ -2,14 +2,29 @@
 //!
 //! A lightweight Axum-based HTTP server that serves as a mock for the AWS Secrets Manager API.
 //! All requests go to POST "/" with x-amz-target header specifying the operation.
+//! Systems Manager Parameter Store operations (`AmazonSSM.*`) are served from the same endpoint.
 //!
 //! Environment Variables:
 //! - PACT_BROKER_URL: URL of the Pact broker (default: http://pact-broker:9292)
 //! - PACT_BROKER_USERNAME: Username for broker authentication (default: pact)
 //! - PACT_BROKER_PASSWORD: Password for broker authentication (default: pact)
+//! - PACT_BROKER_TOKEN: Bearer token for broker authentication (used instead of username/password)
+//! - PACT_BROKER_MAX_RETRIES: Retries for transient broker failures (default: 3, see `pact_mock_server::broker`)
 //! - PACT_PROVIDER: Provider name in contracts (default: AWS-Secrets-Manager)
 //! - PACT_CONSUMER: Consumer name in contracts (default: Secret-Manager-Controller)
 //! - PORT: Port to listen on (default: 1234)
+//! - TENANT_ISOLATION / TENANT_ACCESS: Isolate state per account (see `pact_mock_server::tenant`)
+//! - CHAOS_CONFIG / CHAOS_CONFIG_FILE: Latency and fault injection rules (see `pact_mock_server::chaos`)
+//! - QUOTA_CONFIG / QUOTA_CONFIG_FILE: Per-account quota rules returning throttling errors (see `pact_mock_server::quota`)
+//! - SCENARIO_MODE / SCENARIO_FILE: Record or replay provider call sequences (see `pact_mock_server::scenario`)
+//! - PACT_VERIFICATION_MODE: Check requests against the loaded contracts: off, report or enforce (see `pact_mock_server::verification`)
+//! - CONTRACTS_RELOAD_TOKEN / CONTRACTS_WEBHOOK_URL: Contract hot-reload via POST /contracts/reload (see `pact_mock_server::contracts`)
+//! - REQUEST_JOURNAL_LIMIT: Requests kept for /admin/requests and /admin/verify (see `pact_mock_server::journal`)
+//! - GET /metrics: Prometheus request counts and latencies per provider operation (see `pact_mock_server::metrics`)
+//! - SEED_FILE: Fixture seeded at startup; see `pact_mock_server::admin` for /admin/seed, /admin/snapshot and /admin/reset
+//! - MOCK_UI_ENABLED: Serve a web UI for browsing stored secrets at /ui/ (see `pact_mock_server::ui`)
+//! - DATABASE_URL: Persist state in PostgreSQL or SQLite (`sqlite::memory:`, `sqlite://mock.db?mode=rwc`); schema migrations run on startup, or only with --migrate-only
+//! - TLS_ENABLED: Serve HTTPS (see `pact_mock_server::tls` for certificate options)
 
 use axum::{
     Router,
@@ -19,10 +34,14 @@ use axum::{
     routing::post,
 };
 use pact_mock_server::prelude::*;
+use pact_mock_server::secrets::aws::account_id;
+use pact_mock_server::secrets::aws::parameter_store::handle_parameter_store_operation;
 use pact_mock_server::secrets::common::errors::aws_error_types;
+use pact_mock_server::secrets::common::migrations;
 use serde_json::json;
 use smc_paths::aws::routes::secrets_manager as aws_routes;
 use smc_paths::aws::secrets_manager;
+use std::collections::HashMap;
 use std::env;
 use std::net::SocketAddr;
 use tower::ServiceBuilder;
@@ -37,6 +56,8 @@ struct AwsAppState {
         std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, serde_json::Value>>>,
     #[allow(dead_code)] // Will be used when AWS handlers are fully implemented
     secrets: AwsSecretStore,
+    /// Systems Manager Parameter Store (AmazonSSM.* operations)
+    parameters: AwsParameterStore,
 }
 
 /// Format Unix timestamp to AWS API format (seconds since epoch as float)
@@ -63,6 +84,10 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
         target
     );
 
+    // ARNs name the account of the request (its tenant, see pact_mock_server::tenant)
+    let account_id = account_id();
+    let account = account_id.as_str();
+
     // Parse request body once and extract secret name
     let (secret_name, body_json) = match axum::body::to_bytes(request.into_body(), usize::MAX).await
     {
@@ -72,7 +97,12 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
                     .get("Name")
                     .or_else(|| json.get("SecretId"))
                     .and_then(|v| v.as_str())
-                    .unwrap_or("test-secret-name")
+                    .unwrap_or("test-secret-name");
+                // A SecretId may be the secret's ARN
+                let name = name
+                    .strip_prefix("arn:")
+                    .and_then(|arn| arn.split_once(":secret:"))
+                    .map_or(name, |(_, name)| name)
                     .to_string();
                 (name, Some(json))
             } else {
@@ -88,6 +118,17 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
         }
     };
 
+    // Parameter Store operations (AmazonSSM.*) share the endpoint with Secrets Manager
+    if let Some(response) = handle_parameter_store_operation(
+        &app_state.parameters,
+        &target,
+        body_json.as_ref().unwrap_or(&serde_json::Value::Null),
+    )
+    .await
+    {
+        return response;
+    }
+
     // Use PathBuilder to validate and get operation constants
     // Match against constants from paths::aws::secrets_manager
     match target.as_str() {
@@ -138,7 +179,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
             (
                 StatusCode::OK,
                 Json(json!({
-                    "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                    "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                     "Name": secret_name,
                     "VersionId": current_version.as_ref().map(|v| v.version_id.clone()).unwrap_or_else(|| "test-version-id".to_string()),
                     "CreatedDate": created_date
@@ -232,7 +273,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
             (
                 StatusCode::OK,
                 Json(json!({
-                    "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                    "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                     "Name": secret_name,
                     "SecretString": secret_value,
                     "VersionId": version_id_used,
@@ -265,7 +306,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
             (
                 StatusCode::OK,
                 Json(json!({
-                    "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                    "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                     "Name": secret_name,
                     "VersionIdToStages": {
                         current_version.as_ref().map(|v| v.version_id.clone()).unwrap_or_else(|| "current-version-id".to_string()): ["AWSCURRENT"]
@@ -315,7 +356,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
             (
                 StatusCode::OK,
                 Json(json!({
-                    "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                    "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                     "Name": secret_name,
                     "VersionId": current_version.as_ref().map(|v| v.version_id.clone()).unwrap_or_else(|| "new-version-id".to_string()),
                     "VersionStages": ["AWSCURRENT"],
@@ -348,7 +389,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
                 (
                     StatusCode::OK,
                     Json(json!({
-                        "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                        "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                         "Name": secret_name,
                         "DeletionDate": deletion_date
                     })),
@@ -369,7 +410,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
                 (
                     StatusCode::OK,
                     Json(json!({
-                        "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                        "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                         "Name": secret_name
                     })),
                 )
@@ -431,7 +472,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
                 (
                     StatusCode::OK,
                     Json(json!({
-                        "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                        "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                         "Name": secret_name,
                         "Versions": version_list
                     })),
@@ -442,7 +483,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
                 (
                     StatusCode::OK,
                     Json(json!({
-                        "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                        "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                         "Name": secret_name,
                         "Versions": []
                     })),
@@ -470,7 +511,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
             (
                 StatusCode::OK,
                 Json(json!({
-                    "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                    "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                     "Name": secret_name,
                     "VersionId": current_version.as_ref().map(|v| v.version_id.clone()).unwrap_or_else(|| "current-version-id".to_string()),
                     "CreatedDate": created_date
@@ -504,7 +545,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
                                 .collect();
 
                             Some(json!({
-                                "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                                "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                                 "Name": secret_name,
                                 "Description": "",
                                 "LastChangedDate": format_timestamp_aws(version.created_at),
@@ -610,7 +651,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
             (
                 StatusCode::OK,
                 Json(json!({
-                    "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                    "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                     "Name": secret_name
                 })),
             )
@@ -683,7 +724,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
                 "Statement": [{
                     "Effect": "Allow",
                     "Principal": {
-                        "AWS": "arn:aws:iam::123456789012:root"
+                        "AWS": format!("arn:aws:iam::{}:root", account)
                     },
                     "Action": "secretsmanager:GetSecretValue",
                     "Resource": "*"
@@ -693,7 +734,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
             (
                 StatusCode::OK,
                 Json(json!({
-                    "ARN": format!("arn:aws:secretsmanager:us-east-1:123456789012:secret:{}", secret_name),
+                    "ARN": format!("arn:aws:secretsmanager:us-east-1:{}:secret:{}", account, secret_name),
                     "Name": secret_name,
                     "ResourcePolicy": serde_json::to_string(&default_policy).unwrap_or_else(|_| "{}".to_string())
                 })),
@@ -704,7 +745,7 @@ async fn handle_aws_request(State(app_state): State<AwsAppState>, request: Reque
             warn!("  ⚠️  Unknown x-amz-target: {}", target);
             aws_error_response(
                 StatusCode::BAD_REQUEST,
-                aws_error_types::INVALID_REQUEST,
+                aws_error_types::UNKNOWN_OPERATION,
                 format!("Unknown target: {}", target),
             )
         }
@@ -719,11 +760,25 @@ async fn main() {
         .with_target(false)
         .init();
 
+    // Apply the database schema migrations and exit (e.g. from an init container)
+    if env::args().any(|arg| arg == "--migrate-only") {
+        match migrations::migrate_from_env("aws").await {
+            Ok(applied) => {
+                info!(
+                    "✅ Schema aws is up to date ({} migration(s) applied)",
+                    applied
+                );
+                return;
+            }
+            Err(e) => {
+                eprintln!("❌ Migration failed: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Load configuration from environment
-    let broker_url =
-        env::var("PACT_BROKER_URL").unwrap_or_else(|_| "http://pact-broker:9292".to_string());
-    let username = env::var("PACT_BROKER_USERNAME").unwrap_or_else(|_| "pact".to_string());
-    let password = env::var("PACT_BROKER_PASSWORD").unwrap_or_else(|_| "pact".to_string());
+    let broker = BrokerClient::from_env();
     let provider = env::var("PACT_PROVIDER").unwrap_or_else(|_| "AWS-Secrets-Manager".to_string());
     let consumer =
         env::var("PACT_CONSUMER").unwrap_or_else(|_| "Secret-Manager-Controller".to_string());
@@ -733,7 +788,7 @@ async fn main() {
         .expect("PORT must be a valid u16");
 
     info!("Starting AWS Secrets Manager Mock Server...");
-    info!("Broker URL: {}", broker_url);
+    info!("Broker URL: {}", broker.base_url());
     info!("Provider: {}, Consumer: {}", provider, consumer);
 
     // Wait for manager to be ready and our provider's pact to be published
@@ -754,17 +809,55 @@ async fn main() {
     }
 
     // Load contracts from broker
-    let contracts =
-        load_contracts_from_broker(&broker_url, &username, &password, &provider, &consumer).await;
-    if contracts.is_empty() {
-        warn!("⚠️  No contracts loaded, using default mock responses");
-    }
+    let contracts = match broker.load_contracts(&provider, &consumer).await {
+        Ok(contracts) => {
+            info!("✅ Loaded contracts from broker");
+            contracts
+        }
+        Err(e) => {
+            log_load_error(&e);
+            warn!("⚠️  No contracts loaded, using default mock responses");
+            HashMap::new()
+        }
+    };
 
     let contracts_state = AppState::new(contracts);
     let app_state = AwsAppState {
         contracts: contracts_state.contracts,
         secrets: AwsSecretStore::new().await,
+        parameters: AwsParameterStore::new(),
+    };
+
+    // Contract hot-reload (POST /contracts/reload, or a broker webhook via CONTRACTS_WEBHOOK_URL)
+    let broker = BrokerSettings {
+        client: broker,
+        provider,
+        consumer,
     };
+    register_broker_webhook_from_env(&broker).await;
+    let reloader = ContractReloader::from_env(broker, app_state.contracts.clone());
+    // Pact verification of incoming requests (PACT_VERIFICATION_MODE, GET /pact/verification)
+    let verification = PactVerificationState::from_env(app_state.contracts.clone());
+    // Request journal (GET /admin/requests, POST /admin/verify)
+    let journal = RequestJournal::from_env();
+    // Request metrics (GET /metrics)
+    let metrics = match MockMetrics::new("aws") {
+        Ok(metrics) => metrics,
+        Err(e) => {
+            eprintln!("❌ Failed to create metrics: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    // Seeding, snapshotting and resetting state (SEED_FILE, or /admin/seed, /admin/snapshot, /admin/reset)
+    let admin = AdminState::new()
+        .with_store("secrets", app_state.secrets.clone())
+        .with_store("parameters", app_state.parameters.clone());
+    if let Err(e) = admin.seed_from_env().await {
+        eprintln!("❌ Failed to seed state: {:#}", e);
+        std::process::exit(1);
+    }
+    // Web UI for browsing stored state (MOCK_UI_ENABLED, /ui/)
+    let ui = UiState::from_env(admin.clone(), TenantKind::AwsAccount);
 
     // Build router - all AWS requests go to POST "/"
     // Build router with AWS Secrets Manager API endpoints
@@ -786,6 +879,27 @@ async fn main() {
         Json(json!({ "projects": projects })).into_response()
     }
 
+    // Tenant isolation (TENANT_ISOLATION, TENANT_ACCESS / TENANT_ACCESS_FILE)
+    let tenants = match TenantState::from_env(TenantKind::AwsAccount) {
+        Ok(tenants) => tenants,
+        Err(e) => {
+            eprintln!("❌ Invalid tenant configuration: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    // Latency and fault injection (CHAOS_CONFIG / CHAOS_CONFIG_FILE, or PUT /chaos/config)
+    let chaos = ChaosState::from_env();
+    // Quota and rate-limit simulation (QUOTA_CONFIG / QUOTA_CONFIG_FILE, or PUT /quota/config)
+    let quota = QuotaState::from_env(TenantKind::AwsAccount);
+    // Scenario recording and replay (SCENARIO_MODE / SCENARIO_FILE)
+    let scenario = match ScenarioState::from_env() {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            eprintln!("❌ Invalid scenario configuration: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
     let app = Router::new()
         // Health check endpoints
         .route("/", axum::routing::get(health_check))
@@ -800,17 +914,56 @@ async fn main() {
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
+                .layer(axum::middleware::from_fn_with_state(
+                    metrics.clone(),
+                    metrics_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    journal.clone(),
+                    journal_middleware,
+                ))
                 .layer(axum::middleware::from_fn(auth_failure_middleware))
                 .layer(axum::middleware::from_fn(service_unavailable_middleware))
                 .layer(axum::middleware::from_fn(rate_limit_middleware))
-                .layer(axum::middleware::from_fn(logging_middleware)),
+                .layer(axum::middleware::from_fn(logging_middleware))
+                .layer(axum::middleware::from_fn_with_state(
+                    verification.clone(),
+                    pact_verification_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    tenants,
+                    tenant_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    quota.clone(),
+                    quota_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    chaos.clone(),
+                    chaos_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    scenario.clone(),
+                    scenario_middleware,
+                )),
         )
-        .with_state(app_state);
+        .with_state(app_state)
+        // Chaos, quota, scenario, state, verification, contract admin and metrics endpoints are not subject to the middlewares above
+        .merge(chaos_router(chaos))
+        .merge(quota_router(quota))
+        .merge(scenario_router(scenario))
+        .merge(admin_router(admin))
+        .merge(ui_router(ui))
+        .merge(journal_router(journal))
+        .merge(metrics_router(metrics))
+        .merge(pact_verification_router(verification))
+        .merge(contracts_router(reloader));
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     info!("Listening on port {}", port);
-    info!("✅ AWS Mock server ready at http://{}", addr);
 
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    if let Err(e) = pact_mock_server::tls::serve(app, addr, "AWS Mock server").await {
+        eprintln!("❌ AWS Mock server failed: {:#}", e);
+        std::process::exit(1);
+    }
 }
@@ -7,9 +7,25 @@
 //! - PACT_BROKER_URL: URL of the Pact broker (default: http://pact-broker:9292)
 //! - PACT_BROKER_USERNAME: Username for broker authentication (default: pact)
 //! - PACT_BROKER_PASSWORD: Password for broker authentication (default: pact)
+//! - PACT_BROKER_TOKEN: Bearer token for broker authentication (used instead of username/password)
+//! - PACT_BROKER_MAX_RETRIES: Retries for transient broker failures (default: 3, see `pact_mock_server::broker`)
 //! - PACT_PROVIDER: Provider name in contracts (default: Azure-Key-Vault)
 //! - PACT_CONSUMER: Consumer name in contracts (default: Secret-Manager-Controller)
 //! - PORT: Port to listen on (default: 1234)
+//! - TENANT_ISOLATION / TENANT_ACCESS: Isolate state per vault (see `pact_mock_server::tenant`)
+//! - CHAOS_CONFIG / CHAOS_CONFIG_FILE: Latency and fault injection rules (see `pact_mock_server::chaos`)
+//! - QUOTA_CONFIG / QUOTA_CONFIG_FILE: Per-vault quota rules returning throttling errors (see `pact_mock_server::quota`)
+//! - SCENARIO_MODE / SCENARIO_FILE: Record or replay provider call sequences (see `pact_mock_server::scenario`)
+//! - PACT_VERIFICATION_MODE: Check requests against the loaded contracts: off, report or enforce (see `pact_mock_server::verification`)
+//! - CONTRACTS_RELOAD_TOKEN / CONTRACTS_WEBHOOK_URL: Contract hot-reload via POST /contracts/reload (see `pact_mock_server::contracts`)
+//! - REQUEST_JOURNAL_LIMIT: Requests kept for /admin/requests and /admin/verify (see `pact_mock_server::journal`)
+//! - GET /metrics: Prometheus request counts and latencies per provider operation (see `pact_mock_server::metrics`)
+//! - SEED_FILE: Fixture seeded at startup; see `pact_mock_server::admin` for /admin/seed, /admin/snapshot and /admin/reset
+//! - MOCK_UI_ENABLED: Serve a web UI for browsing stored secrets at /ui/ (see `pact_mock_server::ui`)
+//! - AZURE_SOFT_DELETE_RETENTION_DAYS: Days deleted secrets stay recoverable (7-90, default: 90)
+//! - AZURE_PURGE_PROTECTION: Reject purges of deleted secrets (default: false)
+//! - DATABASE_URL: Persist state in PostgreSQL or SQLite (`sqlite::memory:`, `sqlite://mock.db?mode=rwc`); schema migrations run on startup, or only with --migrate-only
+//! - TLS_ENABLED: Serve HTTPS (see `pact_mock_server::tls` for certificate options)
 
 use axum::{
     Router,
@@ -20,9 +36,12 @@ use axum::{
 };
 use base64::{Engine as _, engine::general_purpose::STANDARD};
 use pact_mock_server::prelude::*;
+use pact_mock_server::secrets::azure::{PurgeError, vault_name};
 use pact_mock_server::secrets::common::errors::azure_error_codes;
+use pact_mock_server::secrets::common::migrations;
 use serde_json::json;
 use smc_paths::azure::routes::key_vault as azure_routes;
+use std::collections::HashMap;
 use std::env;
 use std::net::SocketAddr;
 use tower::ServiceBuilder;
@@ -51,12 +70,41 @@ fn format_timestamp_azure(timestamp: u64) -> i64 {
     timestamp as i64
 }
 
+/// 404 returned by Azure for secrets in the deleted (recoverable) state
+fn deleted_secret_not_found(name: &str) -> Response {
+    azure_error_response(
+        StatusCode::NOT_FOUND,
+        azure_error_codes::SECRET_NOT_FOUND,
+        format!(
+            "A secret with (name/id) {} was not found in this key vault. If you recently deleted this secret you may be able to recover it using the correct recovery command.",
+            name
+        ),
+    )
+}
+
+/// 409 returned by Azure when writing to a name held by a deleted (recoverable) secret
+fn deleted_secret_conflict(name: &str) -> Response {
+    azure_error_response_with_inner(
+        StatusCode::CONFLICT,
+        azure_error_codes::CONFLICT,
+        format!(
+            "Secret {} is currently in a deleted but recoverable state, and its name cannot be reused; in this state, the secret can only be recovered or purged.",
+            name
+        ),
+        Some(azure_error_codes::OBJECT_IS_DELETED_BUT_RECOVERABLE),
+    )
+}
+
 /// GET secret
 /// Path: /secrets/{name}/ (with trailing slash)
 /// Query: api-version=2025-07-01
 async fn get_secret(State(app_state): State<AzureAppState>, Path(name): Path<String>) -> Response {
     info!("  GET secret: name={}", name);
 
+    if app_state.secrets.is_deleted(&name).await {
+        return deleted_secret_not_found(&name);
+    }
+
     // Check if secret is disabled
     if !app_state.secrets.is_enabled(&name).await {
         return azure_error_response(
@@ -104,12 +152,12 @@ async fn get_secret(State(app_state): State<AzureAppState>, Path(name): Path<Str
 
     Json(json!({
         "value": value,
-        "id": format!("https://test-vault.vault.azure.net/secrets/{}/{}", name, version_id),
+        "id": format!("https://{}.vault.azure.net/secrets/{}/{}", vault_name(), name, version_id),
         "attributes": {
             "enabled": true,
             "created": created,
             "updated": updated,
-            "recoveryLevel": "Recoverable+Purgeable"
+            "recoveryLevel": app_state.secrets.soft_delete().recovery_level()
         }
     }))
     .into_response()
@@ -127,6 +175,10 @@ async fn get_secret_version(
         name, version_id
     );
 
+    if app_state.secrets.is_deleted(&name).await {
+        return deleted_secret_not_found(&name);
+    }
+
     // Check if secret is disabled
     if !app_state.secrets.is_enabled(&name).await {
         return azure_error_response(
@@ -170,12 +222,12 @@ async fn get_secret_version(
 
     Json(json!({
         "value": value,
-        "id": format!("https://test-vault.vault.azure.net/secrets/{}/{}", name, version_id),
+        "id": format!("https://{}.vault.azure.net/secrets/{}/{}", vault_name(), name, version_id),
         "attributes": {
             "enabled": true,
             "created": created,
             "updated": updated,
-            "recoveryLevel": "Recoverable+Purgeable"
+            "recoveryLevel": app_state.secrets.soft_delete().recovery_level()
         }
     }))
     .into_response()
@@ -190,6 +242,10 @@ async fn list_secret_versions(
 ) -> Response {
     info!("  GET secret versions list: name={}", name);
 
+    if app_state.secrets.is_deleted(&name).await {
+        return deleted_secret_not_found(&name);
+    }
+
     // Check if secret exists
     if !app_state.secrets.exists(&name).await {
         warn!("  Secret not found: {}", name);
@@ -206,12 +262,12 @@ async fn list_secret_versions(
             .iter()
             .map(|v| {
                 json!({
-                    "id": format!("https://test-vault.vault.azure.net/secrets/{}/{}", name, v.version_id),
+                    "id": format!("https://{}.vault.azure.net/secrets/{}/{}", vault_name(), name, v.version_id),
                     "attributes": {
                         "enabled": v.enabled,
                         "created": format_timestamp_azure(v.created_at),
                         "updated": format_timestamp_azure(v.created_at),
-                        "recoveryLevel": "Recoverable+Purgeable"
+                        "recoveryLevel": app_state.secrets.soft_delete().recovery_level()
                     }
                 })
             })
@@ -249,12 +305,12 @@ async fn list_all_secrets(State(app_state): State<AzureAppState>) -> Response {
                 // Get latest version for metadata
                 if let Some(version) = secrets.get_latest(&secret_name).await {
                     Some(json!({
-                        "id": format!("https://test-vault.vault.azure.net/secrets/{}", secret_name),
+                        "id": format!("https://{}.vault.azure.net/secrets/{}", vault_name(), secret_name),
                         "attributes": {
                             "enabled": version.enabled,
                             "created": format_timestamp_azure(version.created_at),
                             "updated": format_timestamp_azure(version.created_at),
-                            "recoveryLevel": "Recoverable+Purgeable"
+                            "recoveryLevel": app_state.secrets.soft_delete().recovery_level()
                         }
                     }))
                 } else {
@@ -299,6 +355,11 @@ async fn set_secret(
         body.value.len()
     );
 
+    // A deleted (recoverable) secret holds its name until it is recovered or purged
+    if app_state.secrets.is_deleted(&name).await {
+        return deleted_secret_conflict(&name);
+    }
+
     // Validate secret size (Azure limit: 25KB)
     if let Err(size_error) = validate_azure_secret_size(&body.value) {
         warn!(
@@ -422,12 +483,12 @@ async fn set_secret(
 
     Json(json!({
         "value": body.value,
-        "id": format!("https://test-vault.vault.azure.net/secrets/{}/{}", name, version_id),
+        "id": format!("https://{}.vault.azure.net/secrets/{}/{}", vault_name(), name, version_id),
         "attributes": {
             "enabled": true,
             "created": created,
             "updated": updated,
-            "recoveryLevel": "Recoverable+Purgeable"
+            "recoveryLevel": app_state.secrets.soft_delete().recovery_level()
         }
     }))
     .into_response()
@@ -459,6 +520,10 @@ async fn update_secret(
         name
     );
 
+    if app_state.secrets.is_deleted(&name).await {
+        return deleted_secret_not_found(&name);
+    }
+
     // Check if secret exists
     if !app_state.secrets.exists(&name).await {
         warn!(
@@ -534,12 +599,12 @@ async fn update_secret(
     let is_enabled = app_state.secrets.is_enabled(&name).await;
 
     Json(json!({
-        "id": format!("https://test-vault.vault.azure.net/secrets/{}/{}", name, version_id),
+        "id": format!("https://{}.vault.azure.net/secrets/{}/{}", vault_name(), name, version_id),
         "attributes": {
             "enabled": is_enabled,
             "created": created,
             "updated": updated,
-            "recoveryLevel": "Recoverable+Purgeable"
+            "recoveryLevel": app_state.secrets.soft_delete().recovery_level()
         }
     }))
     .into_response()
@@ -549,16 +614,18 @@ async fn update_secret(
 /// Path: /secrets/{name}
 /// Query: api-version=2025-07-01
 ///
-/// Azure Key Vault uses soft-delete by default, but for simplicity in the mock server,
-/// we implement immediate deletion (no soft-delete recovery period).
-/// In production, Azure Key Vault would soft-delete the secret and allow recovery
-/// within the retention period (7-90 days).
+/// Azure Key Vault soft-deletes the secret: it stays recoverable (and its name reserved)
+/// under /deletedsecrets until it is purged or the retention period (7-90 days) ends.
 async fn delete_secret(
     State(app_state): State<AzureAppState>,
     Path(name): Path<String>,
 ) -> Response {
     info!("  DELETE secret: name={}", name);
 
+    if app_state.secrets.is_deleted(&name).await {
+        return deleted_secret_not_found(&name);
+    }
+
     // Check if secret exists
     if !app_state.secrets.exists(&name).await {
         return azure_error_response(
@@ -568,23 +635,9 @@ async fn delete_secret(
         );
     }
 
-    // Delete the secret (all versions)
+    // Soft-delete the secret (all versions)
     if app_state.secrets.delete_secret(&name).await {
-        // Azure Key Vault returns 200 OK with the deleted secret's attributes
-        // For simplicity, we return a minimal response matching Azure's soft-delete format
-        Json(json!({
-            "id": format!("https://test-vault.vault.azure.net/secrets/{}", name),
-            "recoveryId": format!("https://test-vault.vault.azure.net/deletedsecrets/{}", name),
-            "deletedDate": std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-            "scheduledPurgeDate": std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs() + (90 * 24 * 60 * 60), // 90 days from now (default retention)
-        }))
-        .into_response()
+        deleted_secret_response(&app_state, &name).await
     } else {
         // Should not happen since we checked existence, but handle gracefully
         azure_error_response(
@@ -604,6 +657,10 @@ async fn backup_secret(
 ) -> Response {
     info!("  BACKUP secret: name={}", name);
 
+    if app_state.secrets.is_deleted(&name).await {
+        return deleted_secret_not_found(&name);
+    }
+
     // Check if secret exists
     if !app_state.secrets.exists(&name).await {
         return azure_error_response(
@@ -692,6 +749,10 @@ async fn restore_secret(
         .and_then(|n| n.as_str())
         .unwrap_or("restored-secret");
 
+    if app_state.secrets.is_deleted(secret_name).await {
+        return deleted_secret_conflict(secret_name);
+    }
+
     // Restore versions (simplified - just restore the latest value)
     if let Some(versions) = backup_json.get("versions").and_then(|v| v.as_array()) {
         if let Some(last_version) = versions.last() {
@@ -729,12 +790,12 @@ async fn restore_secret(
                     });
 
                 return Json(json!({
-                    "id": format!("https://test-vault.vault.azure.net/secrets/{}/{}", secret_name, version_id),
+                    "id": format!("https://{}.vault.azure.net/secrets/{}/{}", vault_name(), secret_name, version_id),
                     "attributes": {
                         "enabled": true,
                         "created": created,
                         "updated": created,
-                        "recoveryLevel": "Recoverable+Purgeable"
+                        "recoveryLevel": app_state.secrets.soft_delete().recovery_level()
                     }
                 }))
                 .into_response();
@@ -749,6 +810,44 @@ async fn restore_secret(
     )
 }
 
+/// DeletedSecretBundle for a secret in the deleted state, or 404 if it isn't deleted
+async fn deleted_secret_response(app_state: &AzureAppState, name: &str) -> Response {
+    let Some((deleted_date, scheduled_purge_date)) =
+        app_state.secrets.get_deleted_secret(name).await
+    else {
+        return azure_error_response(
+            StatusCode::NOT_FOUND,
+            azure_error_codes::SECRET_NOT_FOUND,
+            format!("Deleted Secret not found: {}", name),
+        );
+    };
+    let latest_version = app_state.secrets.get_latest(name).await;
+    let version_id = latest_version
+        .as_ref()
+        .map(|v| v.version_id.clone())
+        .unwrap_or_else(|| "abc123".to_string());
+    let created = latest_version
+        .as_ref()
+        .map_or(format_timestamp_azure(deleted_date), |v| {
+            format_timestamp_azure(v.created_at)
+        });
+
+    Json(json!({
+        "id": format!("https://{}.vault.azure.net/secrets/{}/{}", vault_name(), name, version_id),
+        "recoveryId": format!("https://{}.vault.azure.net/deletedsecrets/{}", vault_name(), name),
+        "deletedDate": format_timestamp_azure(deleted_date),
+        "scheduledPurgeDate": format_timestamp_azure(scheduled_purge_date),
+        "attributes": {
+            "enabled": false,
+            "created": created,
+            "updated": created,
+            "recoveryLevel": app_state.secrets.soft_delete().recovery_level(),
+            "recoverableDays": app_state.secrets.soft_delete().retention_days
+        }
+    }))
+    .into_response()
+}
+
 /// GET deleted secret
 /// Path: /deletedsecrets/{name}
 /// Query: api-version=7.4
@@ -757,24 +856,7 @@ async fn get_deleted_secret(
     Path(name): Path<String>,
 ) -> Response {
     info!("  GET deleted secret: name={}", name);
-
-    if let Some((deleted_date, scheduled_purge_date)) =
-        app_state.secrets.get_deleted_secret(&name).await
-    {
-        Json(json!({
-            "recoveryId": format!("https://test-vault.vault.azure.net/deletedsecrets/{}", name),
-            "deletedDate": deleted_date,
-            "scheduledPurgeDate": scheduled_purge_date,
-            "recoveryLevel": "Recoverable+Purgeable"
-        }))
-        .into_response()
-    } else {
-        azure_error_response(
-            StatusCode::NOT_FOUND,
-            azure_error_codes::SECRET_NOT_FOUND,
-            format!("Deleted secret {} not found", name),
-        )
-    }
+    deleted_secret_response(&app_state, &name).await
 }
 
 /// GET list deleted secrets
@@ -783,20 +865,23 @@ async fn get_deleted_secret(
 async fn list_deleted_secrets(State(app_state): State<AzureAppState>) -> Response {
     info!("  GET deleted secrets list");
 
-    let deleted_names = app_state.secrets.list_deleted_secrets().await;
-    let deleted_list: Vec<serde_json::Value> = deleted_names
-        .iter()
-        .filter_map(|name| {
-            let (deleted_date, scheduled_purge_date) = {
-                let rt = tokio::runtime::Handle::current();
-                rt.block_on(app_state.secrets.get_deleted_secret(name))?
-            };
-
-            Some(json!({
-                "recoveryId": format!("https://test-vault.vault.azure.net/deletedsecrets/{}", name),
-                "deletedDate": deleted_date,
-                "scheduledPurgeDate": scheduled_purge_date
-            }))
+    let recovery_level = app_state.secrets.soft_delete().recovery_level();
+    let deleted_list: Vec<serde_json::Value> = app_state
+        .secrets
+        .list_deleted_secrets()
+        .await
+        .into_iter()
+        .map(|(name, deleted_date, scheduled_purge_date)| {
+            json!({
+                "id": format!("https://{}.vault.azure.net/secrets/{}", vault_name(), name),
+                "recoveryId": format!("https://{}.vault.azure.net/deletedsecrets/{}", vault_name(), name),
+                "deletedDate": format_timestamp_azure(deleted_date),
+                "scheduledPurgeDate": format_timestamp_azure(scheduled_purge_date),
+                "attributes": {
+                    "enabled": false,
+                    "recoveryLevel": recovery_level
+                }
+            })
         })
         .collect();
 
@@ -836,12 +921,12 @@ async fn recover_deleted_secret(
             });
 
         Json(json!({
-            "id": format!("https://test-vault.vault.azure.net/secrets/{}/{}", name, version_id),
+            "id": format!("https://{}.vault.azure.net/secrets/{}/{}", vault_name(), name, version_id),
             "attributes": {
                 "enabled": true,
                 "created": created,
                 "updated": created,
-                "recoveryLevel": "Recoverable+Purgeable"
+                "recoveryLevel": app_state.secrets.soft_delete().recovery_level()
             }
         }))
         .into_response()
@@ -857,16 +942,30 @@ async fn recover_deleted_secret(
 /// DELETE purge deleted secret
 /// Path: /deletedsecrets/{name}
 /// Query: api-version=7.4
+///
+/// Vaults with purge protection (AZURE_PURGE_PROTECTION) reject purges with 403;
+/// their deleted secrets are only removed when the retention period ends.
 async fn purge_deleted_secret(
     State(app_state): State<AzureAppState>,
     Path(name): Path<String>,
-) -> StatusCode {
+) -> Response {
     info!("  PURGE deleted secret: name={}", name);
 
-    if app_state.secrets.purge_deleted_secret(&name).await {
-        StatusCode::NO_CONTENT // Azure returns 204 for purge
-    } else {
-        StatusCode::NOT_FOUND
+    match app_state.secrets.purge_deleted_secret(&name).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(), // Azure returns 204 for purge
+        Err(PurgeError::PurgeProtected) => azure_error_response(
+            StatusCode::FORBIDDEN,
+            azure_error_codes::FORBIDDEN,
+            format!(
+                "Operation \"purge\" is not allowed because purge protection is enabled for this vault. Secret {} will be purged at its scheduled purge date.",
+                name
+            ),
+        ),
+        Err(PurgeError::NotDeleted) => azure_error_response(
+            StatusCode::NOT_FOUND,
+            azure_error_codes::SECRET_NOT_FOUND,
+            format!("Deleted Secret not found: {}", name),
+        ),
     }
 }
 
@@ -878,11 +977,25 @@ async fn main() {
         .with_target(false)
         .init();
 
+    // Apply the database schema migrations and exit (e.g. from an init container)
+    if env::args().any(|arg| arg == "--migrate-only") {
+        match migrations::migrate_from_env("azure").await {
+            Ok(applied) => {
+                info!(
+                    "✅ Schema azure is up to date ({} migration(s) applied)",
+                    applied
+                );
+                return;
+            }
+            Err(e) => {
+                eprintln!("❌ Migration failed: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Load configuration from environment
-    let broker_url =
-        env::var("PACT_BROKER_URL").unwrap_or_else(|_| "http://pact-broker:9292".to_string());
-    let username = env::var("PACT_BROKER_USERNAME").unwrap_or_else(|_| "pact".to_string());
-    let password = env::var("PACT_BROKER_PASSWORD").unwrap_or_else(|_| "pact".to_string());
+    let broker = BrokerClient::from_env();
     let provider = env::var("PACT_PROVIDER").unwrap_or_else(|_| "Azure-Key-Vault".to_string());
     let consumer =
         env::var("PACT_CONSUMER").unwrap_or_else(|_| "Secret-Manager-Controller".to_string());
@@ -892,7 +1005,7 @@ async fn main() {
         .expect("PORT must be a valid u16");
 
     info!("Starting Azure Key Vault Mock Server...");
-    info!("Broker URL: {}", broker_url);
+    info!("Broker URL: {}", broker.base_url());
     info!("Provider: {}, Consumer: {}", provider, consumer);
 
     // Wait for manager to be ready and our provider's pact to be published
@@ -913,11 +1026,17 @@ async fn main() {
     }
 
     // Load contracts from broker
-    let contracts =
-        load_contracts_from_broker(&broker_url, &username, &password, &provider, &consumer).await;
-    if contracts.is_empty() {
-        warn!("⚠️  No contracts loaded, using default mock responses");
-    }
+    let contracts = match broker.load_contracts(&provider, &consumer).await {
+        Ok(contracts) => {
+            info!("✅ Loaded contracts from broker");
+            contracts
+        }
+        Err(e) => {
+            log_load_error(&e);
+            warn!("⚠️  No contracts loaded, using default mock responses");
+            HashMap::new()
+        }
+    };
 
     let contracts_state = AppState::new(contracts);
     let app_state = AzureAppState {
@@ -925,6 +1044,35 @@ async fn main() {
         secrets: AzureSecretStore::new().await,
     };
 
+    // Contract hot-reload (POST /contracts/reload, or a broker webhook via CONTRACTS_WEBHOOK_URL)
+    let broker = BrokerSettings {
+        client: broker,
+        provider,
+        consumer,
+    };
+    register_broker_webhook_from_env(&broker).await;
+    let reloader = ContractReloader::from_env(broker, app_state.contracts.clone());
+    // Pact verification of incoming requests (PACT_VERIFICATION_MODE, GET /pact/verification)
+    let verification = PactVerificationState::from_env(app_state.contracts.clone());
+    // Request journal (GET /admin/requests, POST /admin/verify)
+    let journal = RequestJournal::from_env();
+    // Request metrics (GET /metrics)
+    let metrics = match MockMetrics::new("azure") {
+        Ok(metrics) => metrics,
+        Err(e) => {
+            eprintln!("❌ Failed to create metrics: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    // Seeding, snapshotting and resetting state (SEED_FILE, or /admin/seed, /admin/snapshot, /admin/reset)
+    let admin = AdminState::new().with_store("secrets", app_state.secrets.clone());
+    if let Err(e) = admin.seed_from_env().await {
+        eprintln!("❌ Failed to seed state: {:#}", e);
+        std::process::exit(1);
+    }
+    // Web UI for browsing stored state (MOCK_UI_ENABLED, /ui/)
+    let ui = UiState::from_env(admin.clone(), TenantKind::AzureVault);
+
     // Build router with Azure Key Vault API endpoints
     // Note: GET uses trailing slash, PUT does not
     // All paths are defined in paths::azure::key_vault
@@ -939,6 +1087,27 @@ async fn main() {
         Json(json!({ "locations": locations })).into_response()
     }
 
+    // Tenant isolation (TENANT_ISOLATION, TENANT_ACCESS / TENANT_ACCESS_FILE)
+    let tenants = match TenantState::from_env(TenantKind::AzureVault) {
+        Ok(tenants) => tenants,
+        Err(e) => {
+            eprintln!("❌ Invalid tenant configuration: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    // Latency and fault injection (CHAOS_CONFIG / CHAOS_CONFIG_FILE, or PUT /chaos/config)
+    let chaos = ChaosState::from_env();
+    // Quota and rate-limit simulation (QUOTA_CONFIG / QUOTA_CONFIG_FILE, or PUT /quota/config)
+    let quota = QuotaState::from_env(TenantKind::AzureVault);
+    // Scenario recording and replay (SCENARIO_MODE / SCENARIO_FILE)
+    let scenario = match ScenarioState::from_env() {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            eprintln!("❌ Invalid scenario configuration: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
     let app = Router::new()
         // Health check endpoints
         .route("/", get(health_check))
@@ -989,17 +1158,56 @@ async fn main() {
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
+                .layer(axum::middleware::from_fn_with_state(
+                    metrics.clone(),
+                    metrics_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    journal.clone(),
+                    journal_middleware,
+                ))
                 .layer(axum::middleware::from_fn(auth_failure_middleware))
                 .layer(axum::middleware::from_fn(service_unavailable_middleware))
                 .layer(axum::middleware::from_fn(rate_limit_middleware))
-                .layer(axum::middleware::from_fn(logging_middleware)),
+                .layer(axum::middleware::from_fn(logging_middleware))
+                .layer(axum::middleware::from_fn_with_state(
+                    verification.clone(),
+                    pact_verification_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    tenants,
+                    tenant_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    quota.clone(),
+                    quota_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    chaos.clone(),
+                    chaos_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    scenario.clone(),
+                    scenario_middleware,
+                )),
         )
-        .with_state(app_state);
+        .with_state(app_state)
+        // Chaos, quota, scenario, state, verification, contract admin and metrics endpoints are not subject to the middlewares above
+        .merge(chaos_router(chaos))
+        .merge(quota_router(quota))
+        .merge(scenario_router(scenario))
+        .merge(admin_router(admin))
+        .merge(ui_router(ui))
+        .merge(journal_router(journal))
+        .merge(metrics_router(metrics))
+        .merge(pact_verification_router(verification))
+        .merge(contracts_router(reloader));
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     info!("Listening on port {}", port);
-    info!("✅ Azure Mock server ready at http://{}", addr);
 
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    if let Err(e) = pact_mock_server::tls::serve(app, addr, "Azure Mock server").await {
+        eprintln!("❌ Azure Mock server failed: {:#}", e);
+        std::process::exit(1);
+    }
 }
@@ -7,9 +7,24 @@
 //! - PACT_BROKER_URL: URL of the Pact broker (default: http://pact-broker:9292)
 //! - PACT_BROKER_USERNAME: Username for broker authentication (default: pact)
 //! - PACT_BROKER_PASSWORD: Password for broker authentication (default: pact)
+//! - PACT_BROKER_TOKEN: Bearer token for broker authentication (used instead of username/password)
+//! - PACT_BROKER_MAX_RETRIES: Retries for transient broker failures (default: 3, see `pact_mock_server::broker`)
 //! - PACT_PROVIDER: Provider name in contracts (default: GCP-Secret-Manager)
 //! - PACT_CONSUMER: Consumer name in contracts (default: Secret-Manager-Controller)
 //! - PORT: Port to listen on (default: 1234)
+//! - TENANT_ISOLATION / TENANT_ACCESS: Isolate state per project (see `pact_mock_server::tenant`)
+//! - CHAOS_CONFIG / CHAOS_CONFIG_FILE: Latency and fault injection rules (see `pact_mock_server::chaos`)
+//! - QUOTA_CONFIG / QUOTA_CONFIG_FILE: Per-project quota rules returning throttling errors (see `pact_mock_server::quota`)
+//! - SCENARIO_MODE / SCENARIO_FILE: Record or replay provider call sequences (see `pact_mock_server::scenario`)
+//! - PACT_VERIFICATION_MODE: Check requests against the loaded contracts: off, report or enforce (see `pact_mock_server::verification`)
+//! - CONTRACTS_RELOAD_TOKEN / CONTRACTS_WEBHOOK_URL: Contract hot-reload via POST /contracts/reload (see `pact_mock_server::contracts`)
+//! - REQUEST_JOURNAL_LIMIT: Requests kept for /admin/requests and /admin/verify (see `pact_mock_server::journal`)
+//! - GET /metrics: Prometheus request counts and latencies per provider operation (see `pact_mock_server::metrics`)
+//! - SEED_FILE: Fixture seeded at startup; see `pact_mock_server::admin` for /admin/seed, /admin/snapshot and /admin/reset
+//! - MOCK_UI_ENABLED: Serve a web UI for browsing stored secrets at /ui/ (see `pact_mock_server::ui`)
+//! - GCP_IAM_POLICY / GCP_IAM_POLICY_FILE: Simulated IAM permissions per bearer token (see `pact_mock_server::secrets::gcp::iam`)
+//! - DATABASE_URL: Persist state in PostgreSQL or SQLite (`sqlite::memory:`, `sqlite://mock.db?mode=rwc`); schema migrations run on startup, or only with --migrate-only
+//! - TLS_ENABLED: Serve HTTPS (see `pact_mock_server::tls` for certificate options)
 
 use axum::{
     Router,
@@ -22,10 +37,14 @@ use axum::{
 // base64 encoding is handled by the secret store
 use futures::future;
 use pact_mock_server::prelude::*;
+use pact_mock_server::secrets::common::migrations;
+use pact_mock_server::secrets::gcp::iam::{GcpIamState, gcp_iam_middleware, gcp_iam_router};
+use pact_mock_server::secrets::gcp::render;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use smc_paths::gcp::routes;
 use smc_paths::prelude::{GcpOperation, PathBuilder};
+use std::collections::HashMap;
 use std::env;
 use std::net::SocketAddr;
 use tower::ServiceBuilder;
@@ -1614,6 +1633,15 @@ async fn get_parameter_version(
     State(app_state): State<GcpAppState>,
     Path((project, location, parameter, version)): Path<(String, String, String, String)>,
 ) -> Response {
+    // "{version}:render" matches this route's "{version}" segment
+    if let Some(version) = version.strip_suffix(":render") {
+        return render_parameter_version(
+            State(app_state),
+            Path((project, location, parameter, version.to_string())),
+        )
+        .await;
+    }
+
     info!(
         "  GET parameter version: project={}, location={}, parameter={}, version={}",
         project, location, parameter, version
@@ -2017,6 +2045,7 @@ async fn delete_parameter_version(
 
 /// RENDER parameter version
 /// Path: /v1/projects/{project}/locations/{location}/parameters/{parameter}/versions/{version}:render
+/// Secret Manager `__REF__(...)` references in the payload are resolved from the secret store.
 async fn render_parameter_version(
     State(app_state): State<GcpAppState>,
     Path((project, location, parameter, version)): Path<(String, String, String, String)>,
@@ -2026,36 +2055,56 @@ async fn render_parameter_version(
         project, location, parameter, version
     );
 
-    if let Some(version_data) = app_state
+    let name = format!(
+        "projects/{}/locations/{}/parameters/{}/versions/{}",
+        project, location, parameter, version
+    );
+    let Some(data) = app_state
         .parameters
         .get_version(&project, &location, &parameter, &version)
         .await
-    {
-        // Extract and decode the payload
-        if let Some(payload_obj) = version_data.data.get("payload") {
-            if let Some(data) = payload_obj.get("data").and_then(|v| v.as_str()) {
-                // Decode base64
-                use base64::{Engine as _, engine::general_purpose};
-                if let Ok(decoded) = general_purpose::STANDARD.decode(data) {
-                    if let Ok(rendered_value) = String::from_utf8(decoded) {
-                        return Json(json!({
-                            "renderedValue": rendered_value
-                        }))
-                        .into_response();
-                    }
-                }
-            }
+        .and_then(|v| {
+            v.data
+                .get("payload")
+                .and_then(|payload| payload.get("data"))
+                .and_then(|data| data.as_str())
+                .map(str::to_string)
+        })
+    else {
+        warn!("  Parameter version not found: {}", name);
+        return gcp_error_response(
+            StatusCode::NOT_FOUND,
+            format!("Parameter version not found: {}", name),
+            Some("NOT_FOUND"),
+        );
+    };
+
+    use base64::{Engine as _, engine::general_purpose};
+    let Some(payload) = general_purpose::STANDARD
+        .decode(&data)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+    else {
+        return gcp_error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Parameter version payload is not UTF-8 text: {}", name),
+            Some("FAILED_PRECONDITION"),
+        );
+    };
+
+    match render::render_payload(&app_state.secrets, &payload).await {
+        Ok(rendered) => Json(json!({
+            "parameterVersion": name,
+            "payload": { "data": data },
+            "renderedPayload": general_purpose::STANDARD.encode(rendered)
+        }))
+        .into_response(),
+        Err(e) => {
+            warn!("  Failed to render {}: {}", name, e);
+            let (status, status_string) = e.status();
+            gcp_error_response(status, e.to_string(), Some(status_string))
         }
     }
-
-    gcp_error_response(
-        StatusCode::NOT_FOUND,
-        format!(
-            "Parameter version not found: projects/{}/locations/{}/parameters/{}/versions/{}",
-            project, location, parameter, version
-        ),
-        Some("NOT_FOUND"),
-    )
 }
 
 // ============================================================================
@@ -2129,11 +2178,25 @@ async fn main() {
         .with_target(false)
         .init();
 
+    // Apply the database schema migrations and exit (e.g. from an init container)
+    if env::args().any(|arg| arg == "--migrate-only") {
+        match migrations::migrate_from_env("gcp").await {
+            Ok(applied) => {
+                info!(
+                    "✅ Schema gcp is up to date ({} migration(s) applied)",
+                    applied
+                );
+                return;
+            }
+            Err(e) => {
+                eprintln!("❌ Migration failed: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Load configuration from environment
-    let broker_url =
-        env::var("PACT_BROKER_URL").unwrap_or_else(|_| "http://pact-broker:9292".to_string());
-    let username = env::var("PACT_BROKER_USERNAME").unwrap_or_else(|_| "pact".to_string());
-    let password = env::var("PACT_BROKER_PASSWORD").unwrap_or_else(|_| "pact".to_string());
+    let broker = BrokerClient::from_env();
     let provider = env::var("PACT_PROVIDER").unwrap_or_else(|_| "GCP-Secret-Manager".to_string());
     let consumer =
         env::var("PACT_CONSUMER").unwrap_or_else(|_| "Secret-Manager-Controller".to_string());
@@ -2143,7 +2206,7 @@ async fn main() {
         .expect("PORT must be a valid u16");
 
     info!("Starting GCP Secret Manager Mock Server...");
-    info!("Broker URL: {}", broker_url);
+    info!("Broker URL: {}", broker.base_url());
     info!("Provider: {}, Consumer: {}", provider, consumer);
 
     // Wait for manager to be ready and our provider's pact to be published
@@ -2164,11 +2227,17 @@ async fn main() {
     }
 
     // Load contracts from broker
-    let contracts =
-        load_contracts_from_broker(&broker_url, &username, &password, &provider, &consumer).await;
-    if contracts.is_empty() {
-        warn!("⚠️  No contracts loaded, using default mock responses");
-    }
+    let contracts = match broker.load_contracts(&provider, &consumer).await {
+        Ok(contracts) => {
+            info!("✅ Loaded contracts from broker");
+            contracts
+        }
+        Err(e) => {
+            log_load_error(&e);
+            warn!("⚠️  No contracts loaded, using default mock responses");
+            HashMap::new()
+        }
+    };
 
     let contracts_state = AppState::new(contracts);
     let app_state = GcpAppState {
@@ -2177,6 +2246,60 @@ async fn main() {
         parameters: GcpParameterStore::new(),
     };
 
+    // Contract hot-reload (POST /contracts/reload, or a broker webhook via CONTRACTS_WEBHOOK_URL)
+    let broker = BrokerSettings {
+        client: broker,
+        provider,
+        consumer,
+    };
+    register_broker_webhook_from_env(&broker).await;
+    let reloader = ContractReloader::from_env(broker, app_state.contracts.clone());
+    // Pact verification of incoming requests (PACT_VERIFICATION_MODE, GET /pact/verification)
+    let verification = PactVerificationState::from_env(app_state.contracts.clone());
+    // Request journal (GET /admin/requests, POST /admin/verify)
+    let journal = RequestJournal::from_env();
+    // Request metrics (GET /metrics)
+    let metrics = match MockMetrics::new("gcp") {
+        Ok(metrics) => metrics,
+        Err(e) => {
+            eprintln!("❌ Failed to create metrics: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    // Seeding, snapshotting and resetting state (SEED_FILE, or /admin/seed, /admin/snapshot, /admin/reset)
+    let admin = AdminState::new()
+        .with_store("secrets", app_state.secrets.clone())
+        .with_store("parameters", app_state.parameters.clone());
+    if let Err(e) = admin.seed_from_env().await {
+        eprintln!("❌ Failed to seed state: {:#}", e);
+        std::process::exit(1);
+    }
+    // Web UI for browsing stored state (MOCK_UI_ENABLED, /ui/)
+    let ui = UiState::from_env(admin.clone(), TenantKind::GcpProject);
+
+    // IAM permission simulation (GCP_IAM_POLICY / GCP_IAM_POLICY_FILE, or PUT /iam/policy)
+    let iam = GcpIamState::from_env();
+    // Tenant isolation (TENANT_ISOLATION, TENANT_ACCESS / TENANT_ACCESS_FILE)
+    let tenants = match TenantState::from_env(TenantKind::GcpProject) {
+        Ok(tenants) => tenants,
+        Err(e) => {
+            eprintln!("❌ Invalid tenant configuration: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    // Latency and fault injection (CHAOS_CONFIG / CHAOS_CONFIG_FILE, or PUT /chaos/config)
+    let chaos = ChaosState::from_env();
+    // Quota and rate-limit simulation (QUOTA_CONFIG / QUOTA_CONFIG_FILE, or PUT /quota/config)
+    let quota = QuotaState::from_env(TenantKind::GcpProject);
+    // Scenario recording and replay (SCENARIO_MODE / SCENARIO_FILE)
+    let scenario = match ScenarioState::from_env() {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            eprintln!("❌ Invalid scenario configuration: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
     // Build router with explicit routes for all GCP Secret Manager and Parameter Manager API endpoints
     let app = Router::new()
         // Health check endpoints
@@ -2295,17 +2418,61 @@ async fn main() {
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
+                .layer(axum::middleware::from_fn_with_state(
+                    metrics.clone(),
+                    metrics_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    journal.clone(),
+                    journal_middleware,
+                ))
                 .layer(axum::middleware::from_fn(auth_failure_middleware))
                 .layer(axum::middleware::from_fn(service_unavailable_middleware))
                 .layer(axum::middleware::from_fn(rate_limit_middleware))
-                .layer(axum::middleware::from_fn(logging_middleware)),
+                .layer(axum::middleware::from_fn(logging_middleware))
+                .layer(axum::middleware::from_fn_with_state(
+                    verification.clone(),
+                    pact_verification_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    iam.clone(),
+                    gcp_iam_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    tenants,
+                    tenant_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    quota.clone(),
+                    quota_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    chaos.clone(),
+                    chaos_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    scenario.clone(),
+                    scenario_middleware,
+                )),
         )
-        .with_state(app_state);
+        .with_state(app_state)
+        // IAM, chaos, quota, scenario, state, verification, contract admin and metrics endpoints are not subject to the middlewares above
+        .merge(gcp_iam_router(iam))
+        .merge(chaos_router(chaos))
+        .merge(quota_router(quota))
+        .merge(scenario_router(scenario))
+        .merge(admin_router(admin))
+        .merge(ui_router(ui))
+        .merge(journal_router(journal))
+        .merge(metrics_router(metrics))
+        .merge(pact_verification_router(verification))
+        .merge(contracts_router(reloader));
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     info!("Listening on port {}", port);
-    info!("✅ GCP Mock server ready at http://{}", addr);
 
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    if let Err(e) = pact_mock_server::tls::serve(app, addr, "GCP Mock server").await {
+        eprintln!("❌ GCP Mock server failed: {:#}", e);
+        std::process::exit(1);
+    }
 }
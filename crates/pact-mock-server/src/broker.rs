@@ -0,0 +1,454 @@
+//! # Pact Broker client
+//!
+//! Shared HTTP client for the Pact Broker, used for contract loading, readiness checks,
+//! contract reloads and webhook registration.
+//!
+//! Failures are reported as a [`BrokerError`], so callers can tell a rejected credential
+//! from a pact that hasn't been published yet. Network errors, 5xx responses and 429s
+//! are retried with exponential backoff; other failures are returned immediately.
+//!
+//! Configuration:
+//! - `PACT_BROKER_URL`: URL of the Pact broker (default: `http://pact-broker:9292`)
+//! - `PACT_BROKER_TOKEN`: Bearer token (takes precedence over basic auth)
+//! - `PACT_BROKER_USERNAME` / `PACT_BROKER_PASSWORD`: Basic auth (default: `pact` / `pact`)
+//! - `PACT_BROKER_MAX_RETRIES`: Retries for transient failures (default: 3)
+
+use reqwest::{RequestBuilder, StatusCode};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::sleep;
+use tracing::{debug, warn};
+
+/// Broker request failure
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BrokerError {
+    /// The broker rejected the credentials (401 or 403)
+    #[error("Broker rejected credentials for {url} ({status})")]
+    Unauthorized { url: String, status: u16 },
+    /// The resource doesn't exist, e.g. no pact published for the provider and consumer
+    #[error("Not found on broker: {url}")]
+    NotFound { url: String },
+    /// The broker answered, but the body isn't the expected JSON
+    #[error("Malformed broker response from {url}: {message}")]
+    Malformed { url: String, message: String },
+    /// The broker couldn't be reached
+    #[error("Could not connect to broker at {url}: {message}")]
+    Network { url: String, message: String },
+    /// Any other non-success status
+    #[error("Broker returned {status} for {url}")]
+    UnexpectedStatus { url: String, status: u16 },
+}
+
+impl BrokerError {
+    /// Whether the request may succeed if retried
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Network { .. } => true,
+            Self::UnexpectedStatus { status, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS.as_u16() || *status >= 500
+            }
+            Self::Unauthorized { .. } | Self::NotFound { .. } | Self::Malformed { .. } => false,
+        }
+    }
+
+    fn from_status(url: &str, status: StatusCode) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Unauthorized {
+                url: url.to_string(),
+                status: status.as_u16(),
+            },
+            StatusCode::NOT_FOUND => Self::NotFound {
+                url: url.to_string(),
+            },
+            _ => Self::UnexpectedStatus {
+                url: url.to_string(),
+                status: status.as_u16(),
+            },
+        }
+    }
+}
+
+/// Broker credentials
+#[derive(Clone, PartialEq, Eq)]
+pub enum BrokerAuth {
+    None,
+    Basic { username: String, password: String },
+    Bearer(String),
+}
+
+impl std::fmt::Debug for BrokerAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+            Self::Bearer(_) => f.write_str("Bearer(<redacted>)"),
+        }
+    }
+}
+
+impl BrokerAuth {
+    /// Bearer auth from PACT_BROKER_TOKEN if set, otherwise basic auth
+    pub fn from_env() -> Self {
+        match std::env::var("PACT_BROKER_TOKEN") {
+            Ok(token) if !token.is_empty() => Self::Bearer(token),
+            _ => Self::Basic {
+                username: std::env::var("PACT_BROKER_USERNAME")
+                    .unwrap_or_else(|_| "pact".to_string()),
+                password: std::env::var("PACT_BROKER_PASSWORD")
+                    .unwrap_or_else(|_| "pact".to_string()),
+            },
+        }
+    }
+
+    fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Self::None => request,
+            Self::Basic { username, password } => request.basic_auth(username, Some(password)),
+            Self::Bearer(token) => request.bearer_auth(token),
+        }
+    }
+}
+
+/// Retry behaviour for transient failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each further retry
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Fail on the first error
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+/// Pact Broker client
+#[derive(Debug, Clone)]
+pub struct BrokerClient {
+    base_url: String,
+    auth: BrokerAuth,
+    retry: RetryPolicy,
+    http: reqwest::Client,
+}
+
+impl BrokerClient {
+    pub fn new(base_url: impl Into<String>, auth: BrokerAuth) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            auth,
+            retry: RetryPolicy::default(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Client configured from PACT_BROKER_URL, PACT_BROKER_TOKEN or PACT_BROKER_USERNAME and
+    /// PACT_BROKER_PASSWORD, and PACT_BROKER_MAX_RETRIES
+    pub fn from_env() -> Self {
+        let base_url = std::env::var("PACT_BROKER_URL")
+            .unwrap_or_else(|_| "http://pact-broker:9292".to_string());
+        let mut retry = RetryPolicy::default();
+        if let Some(max_retries) = std::env::var("PACT_BROKER_MAX_RETRIES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            retry.max_retries = max_retries;
+        }
+        Self::new(base_url, BrokerAuth::from_env()).with_retry(retry)
+    }
+
+    #[must_use]
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn auth(&self) -> &BrokerAuth {
+        &self.auth
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// GET a JSON resource (path relative to the broker URL)
+    pub async fn get_json(&self, path: &str) -> Result<Value, BrokerError> {
+        let url = self.url(path);
+        self.with_retries(&url, || self.auth.apply(self.http.get(&url)))
+            .await
+    }
+
+    /// POST a JSON body, returning the response body (`Value::Null` if empty)
+    pub async fn post_json(&self, path: &str, body: &Value) -> Result<Value, BrokerError> {
+        let url = self.url(path);
+        self.with_retries(&url, || self.auth.apply(self.http.post(&url).json(body)))
+            .await
+    }
+
+    /// Check the broker's heartbeat endpoint
+    pub async fn heartbeat(&self) -> Result<(), BrokerError> {
+        self.get_json("/diagnostic/status/heartbeat")
+            .await
+            .map(drop)
+    }
+
+    /// Latest pact between a provider and consumer
+    pub async fn latest_pact(&self, provider: &str, consumer: &str) -> Result<Value, BrokerError> {
+        self.get_json(&format!(
+            "/pacts/provider/{provider}/consumer/{consumer}/latest"
+        ))
+        .await
+    }
+
+    /// Latest pact, keyed the way `AppState.contracts` expects
+    pub async fn load_contracts(
+        &self,
+        provider: &str,
+        consumer: &str,
+    ) -> Result<HashMap<String, Value>, BrokerError> {
+        let pact = self.latest_pact(provider, consumer).await?;
+        Ok(HashMap::from([("contracts".to_string(), pact)]))
+    }
+
+    async fn with_retries(
+        &self,
+        url: &str,
+        request: impl Fn() -> RequestBuilder,
+    ) -> Result<Value, BrokerError> {
+        let mut retry = 0;
+        loop {
+            match Self::send(url, request()).await {
+                Err(e) if e.is_retryable() && retry < self.retry.max_retries => {
+                    let backoff = self.retry.backoff(retry);
+                    retry += 1;
+                    debug!(
+                        "{} (retry {}/{} in {:?})",
+                        e, retry, self.retry.max_retries, backoff
+                    );
+                    sleep(backoff).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send(url: &str, request: RequestBuilder) -> Result<Value, BrokerError> {
+        let response = request.send().await.map_err(|e| BrokerError::Network {
+            url: url.to_string(),
+            message: e.to_string(),
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(BrokerError::from_status(url, status));
+        }
+        let body = response.bytes().await.map_err(|e| BrokerError::Network {
+            url: url.to_string(),
+            message: e.to_string(),
+        })?;
+        if body.is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_slice(&body).map_err(|e| BrokerError::Malformed {
+            url: url.to_string(),
+            message: e.to_string(),
+        })
+    }
+}
+
+/// Log a failed contract load at a level matching its cause
+pub fn log_load_error(error: &BrokerError) {
+    match error {
+        BrokerError::NotFound { .. } => warn!("No pact published yet: {}", error),
+        BrokerError::Unauthorized { .. } => {
+            warn!(
+                "❌ {} - check PACT_BROKER_TOKEN or PACT_BROKER_USERNAME/PASSWORD",
+                error
+            );
+        }
+        _ => warn!("Could not load contracts from broker: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Json, Router,
+        http::{HeaderMap, StatusCode as AxumStatus},
+        response::IntoResponse,
+        routing::get,
+    };
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const PACT_PATH: &str = "/pacts/provider/{provider}/consumer/{consumer}/latest";
+
+    /// Serve a fake broker on a local port, returning its URL
+    async fn fake_broker(app: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{addr}")
+    }
+
+    fn fast_retry() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        }
+    }
+
+    /// Broker that requires the given Authorization header and counts requests
+    fn authenticated_broker(expected: &'static str, hits: Arc<AtomicUsize>) -> Router {
+        Router::new().route(
+            PACT_PATH,
+            get(move |headers: HeaderMap| async move {
+                hits.fetch_add(1, Ordering::SeqCst);
+                if headers.get("authorization").and_then(|v| v.to_str().ok()) == Some(expected) {
+                    Json(json!({ "interactions": [] })).into_response()
+                } else {
+                    AxumStatus::UNAUTHORIZED.into_response()
+                }
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_bearer_and_basic_auth() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let url = fake_broker(authenticated_broker("Bearer t0ken", hits.clone())).await;
+
+        let bearer = BrokerClient::new(&url, BrokerAuth::Bearer("t0ken".to_string()));
+        let contracts = bearer.load_contracts("p", "c").await.unwrap();
+        assert_eq!(contracts["contracts"], json!({ "interactions": [] }));
+
+        // Wrong credentials are reported as such, and not retried
+        let basic = BrokerClient::new(
+            &url,
+            BrokerAuth::Basic {
+                username: "pact".to_string(),
+                password: "pact".to_string(),
+            },
+        )
+        .with_retry(fast_retry());
+        assert!(matches!(
+            basic.latest_pact("p", "c").await,
+            Err(BrokerError::Unauthorized { status: 401, .. })
+        ));
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_not_found_and_malformed_are_distinguished() {
+        let url = fake_broker(
+            Router::new()
+                .route(
+                    "/pacts/provider/missing/consumer/{consumer}/latest",
+                    get(|| async { AxumStatus::NOT_FOUND }),
+                )
+                .route(
+                    "/pacts/provider/broken/consumer/{consumer}/latest",
+                    get(|| async { "<html>proxy error</html>" }),
+                ),
+        )
+        .await;
+        let client = BrokerClient::new(&url, BrokerAuth::None);
+
+        assert!(matches!(
+            client.latest_pact("missing", "c").await,
+            Err(BrokerError::NotFound { .. })
+        ));
+        assert!(matches!(
+            client.latest_pact("broken", "c").await,
+            Err(BrokerError::Malformed { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let url = fake_broker(Router::new().route(
+            PACT_PATH,
+            get(move || async move {
+                if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                    AxumStatus::SERVICE_UNAVAILABLE.into_response()
+                } else {
+                    Json(json!({ "interactions": [] })).into_response()
+                }
+            }),
+        ))
+        .await;
+
+        let client = BrokerClient::new(&url, BrokerAuth::None).with_retry(fast_retry());
+        assert!(client.latest_pact("p", "c").await.is_ok());
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+        // Without retries the first 503 is returned
+        hits.store(0, Ordering::SeqCst);
+        let client = client.with_retry(RetryPolicy::none());
+        assert!(matches!(
+            client.latest_pact("p", "c").await,
+            Err(BrokerError::UnexpectedStatus { status: 503, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_network_error() {
+        // Bind and drop a listener so nothing serves the port
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let client = BrokerClient::new(url, BrokerAuth::None).with_retry(fast_retry());
+        let error = client.heartbeat().await.unwrap_err();
+        assert!(matches!(error, BrokerError::Network { .. }));
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn test_debug_redacts_credentials() {
+        let auth = BrokerAuth::Basic {
+            username: "pact".to_string(),
+            password: "s3cr3t".to_string(),
+        };
+        assert!(!format!("{auth:?}").contains("s3cr3t"));
+        assert!(!format!("{:?}", BrokerAuth::Bearer("t0ken".to_string())).contains("t0ken"));
+    }
+}
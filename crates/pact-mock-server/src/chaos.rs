@@ -0,0 +1,639 @@
+//! # Chaos
+//!
+//! Latency and fault injection for the provider mock servers.
+//!
+//! The header-driven middlewares (`X-Rate-Limit`, `X-Service-Unavailable`, `X-Auth-Failure`)
+//! fail a single request on demand. The chaos middleware fails requests *probabilistically*,
+//! so the controller's retry and backoff behavior can be exercised without changing its
+//! requests. Rules are matched per route (path prefix, method, and `x-amz-target` for AWS) and
+//! can add latency, return 5xx errors, abort the connection mid-response, or stream the
+//! response body slowly.
+//!
+//! Randomness comes from a seeded generator, so a CI run with the same seed and the same
+//! request order injects the same faults.
+//!
+//! Configuration:
+//! - `CHAOS_CONFIG`: JSON config applied at startup
+//! - `CHAOS_CONFIG_FILE`: Path to a JSON config file applied at startup
+//! - `GET /chaos/config`: Current config
+//! - `PUT /chaos/config`: Replace the config (also reseeds the generator)
+//! - `DELETE /chaos/config`: Disable chaos
+//!
+//! ```json
+//! {
+//!   "seed": 42,
+//!   "rules": [
+//!     {
+//!       "pathPrefix": "/v1/projects",
+//!       "method": "POST",
+//!       "latency": { "type": "uniform", "minMs": 50, "maxMs": 500 },
+//!       "errorRate": 0.2,
+//!       "errorStatus": 503
+//!     },
+//!     { "target": "secretsmanager.GetSecretValue", "resetRate": 0.1 }
+//!   ]
+//! }
+//! ```
+
+use anyhow::{Context, Result};
+use axum::{
+    Json, Router,
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::secrets::common::errors::provider_error_response;
+
+/// Seed used when the config doesn't set one
+pub const DEFAULT_CHAOS_SEED: u64 = 0x5EED;
+
+/// Latency added before a request is handled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum LatencyDistribution {
+    /// Always the same delay
+    Fixed { ms: u64 },
+    /// Uniformly distributed between `min_ms` and `max_ms`
+    Uniform { min_ms: u64, max_ms: u64 },
+    /// Normally distributed, clamped at zero
+    Normal { mean_ms: u64, stddev_ms: u64 },
+}
+
+impl LatencyDistribution {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn sample(&self, rng: &mut ChaosRng) -> Duration {
+        let ms = match *self {
+            LatencyDistribution::Fixed { ms } => ms,
+            LatencyDistribution::Uniform { min_ms, max_ms } => {
+                let (low, high) = (min_ms.min(max_ms), min_ms.max(max_ms));
+                low + rng.next_u64() % (high - low).saturating_add(1)
+            }
+            LatencyDistribution::Normal { mean_ms, stddev_ms } => {
+                // Box-Muller transform
+                let u1 = rng.next_f64().max(f64::MIN_POSITIVE);
+                let u2 = rng.next_f64();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                (mean_ms as f64 + z * stddev_ms as f64).max(0.0).round() as u64
+            }
+        };
+        Duration::from_millis(ms)
+    }
+}
+
+/// Response body streamed in small chunks with a delay between them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowBody {
+    /// Bytes per chunk (default: 16)
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: usize,
+    /// Delay between chunks in milliseconds
+    pub delay_ms: u64,
+}
+
+fn default_chunk_size() -> usize {
+    16
+}
+
+fn default_error_status() -> u16 {
+    503
+}
+
+/// Faults injected into requests matching the rule
+/// Empty match fields match every request; the first matching rule applies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChaosRule {
+    /// Request path prefix, e.g. "/v1/projects" or "/secrets"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_prefix: Option<String>,
+    /// HTTP method (case-insensitive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// AWS operation from the `x-amz-target` header, e.g. "secretsmanager.GetSecretValue"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyDistribution>,
+    /// Probability (0.0-1.0) of returning `error_status` instead of calling the handler
+    #[serde(default)]
+    pub error_rate: f64,
+    /// Status returned for injected errors (default: 503)
+    #[serde(default = "default_error_status")]
+    pub error_status: u16,
+    /// Probability (0.0-1.0) of aborting the connection mid-response
+    #[serde(default)]
+    pub reset_rate: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_body: Option<SlowBody>,
+}
+
+impl ChaosRule {
+    fn matches(&self, method: &str, path: &str, target: Option<&str>) -> bool {
+        self.path_prefix
+            .as_deref()
+            .is_none_or(|prefix| path.starts_with(prefix))
+            && self
+                .method
+                .as_deref()
+                .is_none_or(|m| m.eq_ignore_ascii_case(method))
+            && self.target.as_deref().is_none_or(|t| target == Some(t))
+    }
+}
+
+/// Chaos configuration (no rules disables chaos)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChaosConfig {
+    /// Seed for the random generator (default: DEFAULT_CHAOS_SEED)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub rules: Vec<ChaosRule>,
+}
+
+impl ChaosConfig {
+    /// Load the config from CHAOS_CONFIG or CHAOS_CONFIG_FILE (empty if neither is set)
+    pub fn from_env() -> Result<Self> {
+        if let Ok(config) = std::env::var("CHAOS_CONFIG") {
+            return serde_json::from_str(&config).context("CHAOS_CONFIG is not a valid config");
+        }
+        if let Ok(path) = std::env::var("CHAOS_CONFIG_FILE") {
+            let config = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read CHAOS_CONFIG_FILE '{path}'"))?;
+            return serde_json::from_str(&config)
+                .with_context(|| format!("CHAOS_CONFIG_FILE '{path}' is not a valid config"));
+        }
+        Ok(Self::default())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            for (name, rate) in [
+                ("errorRate", rule.error_rate),
+                ("resetRate", rule.reset_rate),
+            ] {
+                if !(0.0..=1.0).contains(&rate) {
+                    return Err(format!("rules[{index}].{name} must be between 0.0 and 1.0"));
+                }
+            }
+            if !(500..=599).contains(&rule.error_status) {
+                return Err(format!("rules[{index}].errorStatus must be a 5xx status"));
+            }
+            if rule.slow_body.as_ref().is_some_and(|s| s.chunk_size == 0) {
+                return Err(format!(
+                    "rules[{index}].slowBody.chunkSize must be positive"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// SplitMix64: small, fast and reproducible for a given seed
+#[derive(Debug)]
+struct ChaosRng(u64);
+
+impl ChaosRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    #[allow(clippy::cast_precision_loss)]
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn chance(&mut self, rate: f64) -> bool {
+        rate > 0.0 && self.next_f64() < rate
+    }
+}
+
+/// Faults decided for one request
+#[derive(Debug, Default, PartialEq)]
+struct ChaosDecision {
+    latency: Option<Duration>,
+    error_status: Option<u16>,
+    reset: bool,
+    slow_body: Option<SlowBody>,
+}
+
+/// Shared chaos state (config and random generator)
+#[derive(Debug, Clone)]
+pub struct ChaosState {
+    config: Arc<RwLock<ChaosConfig>>,
+    rng: Arc<Mutex<ChaosRng>>,
+}
+
+impl ChaosState {
+    #[must_use]
+    pub fn new(config: ChaosConfig) -> Self {
+        let seed = config.seed.unwrap_or(DEFAULT_CHAOS_SEED);
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            rng: Arc::new(Mutex::new(ChaosRng(seed))),
+        }
+    }
+
+    /// Chaos state from CHAOS_CONFIG / CHAOS_CONFIG_FILE, disabled if the config is invalid
+    pub fn from_env() -> Self {
+        match ChaosConfig::from_env().and_then(|config| {
+            config.validate().map_err(anyhow::Error::msg)?;
+            Ok(config)
+        }) {
+            Ok(config) => {
+                if !config.rules.is_empty() {
+                    info!("Chaos enabled with {} rule(s)", config.rules.len());
+                }
+                Self::new(config)
+            }
+            Err(e) => {
+                warn!("⚠️  Ignoring chaos config: {:#}", e);
+                Self::new(ChaosConfig::default())
+            }
+        }
+    }
+
+    /// Replace the config and reseed the generator
+    pub async fn set_config(&self, config: ChaosConfig) -> Result<(), String> {
+        config.validate()?;
+        let seed = config.seed.unwrap_or(DEFAULT_CHAOS_SEED);
+        let mut current = self.config.write().await;
+        *self.lock_rng() = ChaosRng(seed);
+        *current = config;
+        Ok(())
+    }
+
+    pub async fn config(&self) -> ChaosConfig {
+        self.config.read().await.clone()
+    }
+
+    async fn decide(&self, method: &str, path: &str, target: Option<&str>) -> ChaosDecision {
+        let config = self.config.read().await;
+        let Some(rule) = config
+            .rules
+            .iter()
+            .find(|rule| rule.matches(method, path, target))
+        else {
+            return ChaosDecision::default();
+        };
+
+        let mut rng = self.lock_rng();
+        let latency = rule.latency.as_ref().map(|l| l.sample(&mut rng));
+        let error_status = rng.chance(rule.error_rate).then_some(rule.error_status);
+        let reset = error_status.is_none() && rng.chance(rule.reset_rate);
+        ChaosDecision {
+            latency,
+            error_status,
+            reset,
+            slow_body: rule.slow_body.clone(),
+        }
+    }
+
+    fn lock_rng(&self) -> std::sync::MutexGuard<'_, ChaosRng> {
+        self.rng
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Chaos middleware
+/// Applies the first matching rule: latency, then an injected error or the real handler,
+/// then a connection reset or slow body on the way out. Health checks are never affected.
+pub async fn chaos_middleware(
+    State(chaos): State<ChaosState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    if path == "/health" || (path == "/" && request.method() == axum::http::Method::GET) {
+        return next.run(request).await;
+    }
+    let method = request.method().to_string();
+    let target = request
+        .headers()
+        .get("x-amz-target")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let decision = chaos.decide(&method, &path, target.as_deref()).await;
+
+    if let Some(latency) = decision.latency {
+        info!(
+            "Chaos: delaying {} {} by {}ms",
+            method,
+            path,
+            latency.as_millis()
+        );
+        tokio::time::sleep(latency).await;
+    }
+
+    if let Some(status) = decision.error_status {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::SERVICE_UNAVAILABLE);
+        warn!("Chaos: injecting {} for {} {}", status, method, path);
+        return provider_error_response(
+            request.headers(),
+            &path,
+            status,
+            "Injected fault (chaos)".to_string(),
+        );
+    }
+
+    let response = next.run(request).await;
+
+    if decision.reset {
+        warn!("Chaos: aborting connection for {} {}", method, path);
+        // Send the headers, then fail the body so the server drops the connection
+        let (parts, _) = response.into_parts();
+        let body = Body::from_stream(futures::stream::once(async {
+            Err::<Bytes, std::io::Error>(std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "connection reset (chaos)",
+            ))
+        }));
+        return Response::from_parts(parts, body);
+    }
+
+    if let Some(slow_body) = decision.slow_body {
+        let (mut parts, body) = response.into_parts();
+        let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!("Chaos: failed to buffer response body: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+        parts.headers.remove(axum::http::header::CONTENT_LENGTH);
+        return Response::from_parts(parts, slow_body_stream(bytes, slow_body));
+    }
+
+    response
+}
+
+/// Stream `bytes` in chunks, sleeping before each chunk after the first
+fn slow_body_stream(bytes: Bytes, slow_body: SlowBody) -> Body {
+    let delay = Duration::from_millis(slow_body.delay_ms);
+    let chunks: Vec<Bytes> = bytes
+        .chunks(slow_body.chunk_size)
+        .map(Bytes::copy_from_slice)
+        .collect();
+    let stream = futures::stream::unfold(
+        (chunks.into_iter(), true),
+        move |(mut chunks, first)| async move {
+            let chunk = chunks.next()?;
+            if !first {
+                tokio::time::sleep(delay).await;
+            }
+            Some((Ok::<Bytes, std::io::Error>(chunk), (chunks, false)))
+        },
+    );
+    Body::from_stream(stream)
+}
+
+async fn get_chaos_config(State(chaos): State<ChaosState>) -> Json<ChaosConfig> {
+    Json(chaos.config().await)
+}
+
+async fn put_chaos_config(
+    State(chaos): State<ChaosState>,
+    Json(config): Json<ChaosConfig>,
+) -> Response {
+    let rules = config.rules.len();
+    match chaos.set_config(config).await {
+        Ok(()) => {
+            info!("Chaos config updated ({} rule(s))", rules);
+            Json(chaos.config().await).into_response()
+        }
+        Err(message) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": { "code": 400, "message": message } })),
+        )
+            .into_response(),
+    }
+}
+
+async fn delete_chaos_config(State(chaos): State<ChaosState>) -> StatusCode {
+    // An empty config is always valid
+    let _ = chaos.set_config(ChaosConfig::default()).await;
+    info!("Chaos disabled");
+    StatusCode::NO_CONTENT
+}
+
+/// Admin routes for the chaos config, merged into each mock server's router
+pub fn chaos_router(chaos: ChaosState) -> Router {
+    Router::new()
+        .route(
+            "/chaos/config",
+            get(get_chaos_config)
+                .put(put_chaos_config)
+                .delete(delete_chaos_config),
+        )
+        .with_state(chaos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum_test::TestServer;
+
+    fn rule() -> ChaosRule {
+        ChaosRule {
+            path_prefix: None,
+            method: None,
+            target: None,
+            latency: None,
+            error_rate: 0.0,
+            error_status: 503,
+            reset_rate: 0.0,
+            slow_body: None,
+        }
+    }
+
+    fn test_server(config: ChaosConfig) -> TestServer {
+        let chaos = ChaosState::new(config);
+        let app = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/v1/secrets", post(|| async { "created" }))
+            .layer(axum::middleware::from_fn_with_state(
+                chaos.clone(),
+                chaos_middleware,
+            ))
+            .merge(chaos_router(chaos));
+        TestServer::new(app).unwrap()
+    }
+
+    #[test]
+    fn test_config_parses_camel_case() {
+        let config: ChaosConfig = serde_json::from_value(json!({
+            "seed": 7,
+            "rules": [{
+                "pathPrefix": "/v1",
+                "latency": { "type": "uniform", "minMs": 10, "maxMs": 20 },
+                "errorRate": 0.5,
+                "slowBody": { "delayMs": 5 }
+            }]
+        }))
+        .unwrap();
+        assert_eq!(config.seed, Some(7));
+        let rule = &config.rules[0];
+        assert_eq!(
+            rule.latency,
+            Some(LatencyDistribution::Uniform {
+                min_ms: 10,
+                max_ms: 20
+            })
+        );
+        assert_eq!(rule.error_status, 503);
+        assert_eq!(rule.slow_body.as_ref().map(|s| s.chunk_size), Some(16));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_rejects_invalid_rates() {
+        let config = ChaosConfig {
+            seed: None,
+            rules: vec![ChaosRule {
+                error_rate: 1.5,
+                ..rule()
+            }],
+        };
+        assert!(config.validate().is_err());
+        let config = ChaosConfig {
+            seed: None,
+            rules: vec![ChaosRule {
+                error_status: 404,
+                ..rule()
+            }],
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_rule_matching() {
+        let gcp = ChaosRule {
+            path_prefix: Some("/v1/projects".to_string()),
+            method: Some("post".to_string()),
+            ..rule()
+        };
+        assert!(gcp.matches("POST", "/v1/projects/p/secrets", None));
+        assert!(!gcp.matches("GET", "/v1/projects/p/secrets", None));
+        assert!(!gcp.matches("POST", "/secrets/s", None));
+
+        let aws = ChaosRule {
+            target: Some("secretsmanager.GetSecretValue".to_string()),
+            ..rule()
+        };
+        assert!(aws.matches("POST", "/", Some("secretsmanager.GetSecretValue")));
+        assert!(!aws.matches("POST", "/", Some("secretsmanager.CreateSecret")));
+    }
+
+    #[tokio::test]
+    async fn test_decisions_are_reproducible_for_a_seed() {
+        let config = ChaosConfig {
+            seed: Some(42),
+            rules: vec![ChaosRule {
+                latency: Some(LatencyDistribution::Normal {
+                    mean_ms: 100,
+                    stddev_ms: 30,
+                }),
+                error_rate: 0.3,
+                reset_rate: 0.2,
+                ..rule()
+            }],
+        };
+        let sample = |chaos: ChaosState| async move {
+            let mut decisions = vec![];
+            for _ in 0..50 {
+                decisions.push(chaos.decide("GET", "/v1/secrets", None).await);
+            }
+            decisions
+        };
+        let first = sample(ChaosState::new(config.clone())).await;
+        let second = sample(ChaosState::new(config)).await;
+        assert_eq!(first, second);
+        assert!(first.iter().any(|d| d.error_status.is_some()));
+        assert!(first.iter().any(|d| d.error_status.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_injects_errors_and_skips_health() {
+        let server = test_server(ChaosConfig {
+            seed: None,
+            rules: vec![ChaosRule {
+                error_rate: 1.0,
+                error_status: 500,
+                ..rule()
+            }],
+        });
+        server
+            .post("/v1/secrets")
+            .await
+            .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        server.get("/health").await.assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_slow_body_keeps_response_content() {
+        let server = test_server(ChaosConfig {
+            seed: None,
+            rules: vec![ChaosRule {
+                slow_body: Some(SlowBody {
+                    chunk_size: 2,
+                    delay_ms: 1,
+                }),
+                ..rule()
+            }],
+        });
+        server.post("/v1/secrets").await.assert_text("created");
+    }
+
+    #[tokio::test]
+    async fn test_config_endpoint() {
+        let server = test_server(ChaosConfig::default());
+        server
+            .put("/chaos/config")
+            .json(&json!({ "rules": [{ "errorRate": 1.0 }] }))
+            .await
+            .assert_status_ok();
+        server
+            .post("/v1/secrets")
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+
+        server
+            .put("/chaos/config")
+            .json(&json!({ "rules": [{ "errorRate": 2.0 }] }))
+            .await
+            .assert_status_bad_request();
+
+        server
+            .delete("/chaos/config")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server.post("/v1/secrets").await.assert_text("created");
+    }
+}
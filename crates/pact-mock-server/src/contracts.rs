@@ -0,0 +1,412 @@
+//! # Contract reload
+//!
+//! Hot-reloads the Pact contracts a mock server serves, so pacts published while a
+//! long-running test environment is up take effect without restarting the mock servers.
+//!
+//! `POST /contracts/reload` fetches the latest pact for the server's provider and consumer
+//! and replaces `AppState.contracts`. If the broker can't provide a pact, the current
+//! contracts are kept and the cause (e.g. rejected credentials vs. no pact published) is
+//! reported in `lastReload`. The endpoint is meant to be called by a Pact Broker webhook;
+//! `register_broker_webhook` creates one that fires when a new pact is published.
+//!
+//! Configuration:
+//! - `CONTRACTS_RELOAD_TOKEN`: Bearer token required by `POST /contracts/reload` (optional)
+//! - `CONTRACTS_WEBHOOK_URL`: Reload URL to register as a broker webhook at startup (optional,
+//!   e.g. `http://aws-mock-server:1234/contracts/reload`)
+//! - `GET /contracts`: Provider, consumer, loaded interactions and the last reload
+
+use anyhow::{Context, Result};
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::broker::{BrokerClient, BrokerError};
+
+/// Broker client and the pact a mock server serves
+#[derive(Debug, Clone)]
+pub struct BrokerSettings {
+    pub client: BrokerClient,
+    pub provider: String,
+    pub consumer: String,
+}
+
+/// Outcome of the last reload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadStatus {
+    /// Unix timestamp of the reload attempt
+    pub timestamp: u64,
+    pub success: bool,
+    /// Interactions loaded (on success)
+    pub interactions: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Reloads a mock server's contracts from the broker
+#[derive(Clone, Debug)]
+pub struct ContractReloader {
+    settings: BrokerSettings,
+    contracts: Arc<RwLock<HashMap<String, Value>>>,
+    reload_token: Option<String>,
+    last_reload: Arc<RwLock<Option<ReloadStatus>>>,
+}
+
+impl ContractReloader {
+    pub fn new(settings: BrokerSettings, contracts: Arc<RwLock<HashMap<String, Value>>>) -> Self {
+        Self {
+            settings,
+            contracts,
+            reload_token: None,
+            last_reload: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Require `Authorization: Bearer <token>` on `POST /contracts/reload`
+    #[must_use]
+    pub fn with_reload_token(mut self, token: Option<String>) -> Self {
+        self.reload_token = token.filter(|token| !token.is_empty());
+        self
+    }
+
+    /// Create the reloader with the token in CONTRACTS_RELOAD_TOKEN
+    pub fn from_env(
+        settings: BrokerSettings,
+        contracts: Arc<RwLock<HashMap<String, Value>>>,
+    ) -> Self {
+        Self::new(settings, contracts)
+            .with_reload_token(std::env::var("CONTRACTS_RELOAD_TOKEN").ok())
+    }
+
+    /// Fetch the latest pact and replace the contracts, returning the number of interactions
+    /// The current contracts are kept if no pact could be loaded
+    pub async fn reload(&self) -> Result<usize, BrokerError> {
+        let settings = &self.settings;
+        let result = match settings
+            .client
+            .load_contracts(&settings.provider, &settings.consumer)
+            .await
+        {
+            Ok(loaded) => {
+                let interactions = count_interactions(&loaded);
+                *self.contracts.write().await = loaded;
+                info!(
+                    "✅ Reloaded contracts for {} ({} interaction(s))",
+                    settings.provider, interactions
+                );
+                Ok(interactions)
+            }
+            Err(e) => {
+                crate::broker::log_load_error(&e);
+                Err(e)
+            }
+        };
+
+        *self.last_reload.write().await = Some(ReloadStatus {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            success: result.is_ok(),
+            interactions: *result.as_ref().unwrap_or(&0),
+            error: result.as_ref().err().map(ToString::to_string),
+        });
+        result
+    }
+
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        self.reload_token.as_ref().is_none_or(|token| {
+            headers
+                .get(header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "))
+                .is_some_and(|provided| provided == token)
+        })
+    }
+}
+
+fn count_interactions(contracts: &HashMap<String, Value>) -> usize {
+    contracts
+        .values()
+        .filter_map(|pact| pact.get("interactions").and_then(Value::as_array))
+        .map(Vec::len)
+        .sum()
+}
+
+/// Description of the reload webhook, used to find an existing registration
+fn webhook_description(settings: &BrokerSettings, callback_url: &str) -> String {
+    format!(
+        "Reload {} mock server contracts ({})",
+        settings.provider, callback_url
+    )
+}
+
+/// Register a broker webhook that calls `callback_url` when a pact for the provider and
+/// consumer is published with changed content
+///
+/// Does nothing if a webhook for the same callback is already registered. Returns whether
+/// a webhook was created.
+pub async fn register_broker_webhook(
+    settings: &BrokerSettings,
+    callback_url: &str,
+    reload_token: Option<&str>,
+) -> Result<bool> {
+    let description = webhook_description(settings, callback_url);
+
+    let existing = settings
+        .client
+        .get_json(&format!(
+            "/webhooks/provider/{}/consumer/{}",
+            settings.provider, settings.consumer
+        ))
+        .await;
+    // The broker answers 404 until the provider or consumer has any webhooks
+    let existing = match existing {
+        Err(BrokerError::NotFound { .. }) => None,
+        other => Some(other.context("Failed to list broker webhooks")?),
+    };
+    if let Some(body) = existing {
+        let registered = body
+            .pointer("/_links/pb:webhooks")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .any(|webhook| {
+                webhook.get("title").and_then(Value::as_str) == Some(description.as_str())
+            });
+        if registered {
+            info!("Broker webhook already registered: {}", description);
+            return Ok(false);
+        }
+    }
+
+    let mut headers = json!({ "Content-Type": "application/json" });
+    if let Some(token) = reload_token {
+        headers["Authorization"] = json!(format!("Bearer {token}"));
+    }
+    let webhook = json!({
+        "description": description,
+        "provider": { "name": settings.provider },
+        "consumer": { "name": settings.consumer },
+        "events": [{ "name": "contract_content_changed" }],
+        "request": {
+            "method": "POST",
+            "url": callback_url,
+            "headers": headers,
+            "body": { "pactUrl": "${pactbroker.pactUrl}" }
+        }
+    });
+    settings
+        .client
+        .post_json("/webhooks", &webhook)
+        .await
+        .context("Failed to register broker webhook")?;
+    info!("✅ Registered broker webhook: {}", description);
+    Ok(true)
+}
+
+/// Register the webhook in CONTRACTS_WEBHOOK_URL, if set (failures are logged, not fatal)
+pub async fn register_broker_webhook_from_env(settings: &BrokerSettings) {
+    let Ok(callback_url) = std::env::var("CONTRACTS_WEBHOOK_URL") else {
+        return;
+    };
+    let token = std::env::var("CONTRACTS_RELOAD_TOKEN").ok();
+    if let Err(e) = register_broker_webhook(settings, &callback_url, token.as_deref()).await {
+        warn!("⚠️  Could not register broker webhook: {:#}", e);
+    }
+}
+
+async fn reload_contracts(
+    State(reloader): State<ContractReloader>,
+    headers: HeaderMap,
+) -> Response {
+    if !reloader.authorized(&headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": { "code": 401, "message": "Invalid or missing reload token" } })),
+        )
+            .into_response();
+    }
+    match reloader.reload().await {
+        Ok(interactions) => Json(json!({
+            "reloaded": true,
+            "interactions": interactions
+        }))
+        .into_response(),
+        Err(e) => {
+            warn!("Contract reload failed: {}", e);
+            (
+                StatusCode::BAD_GATEWAY,
+                Json(json!({ "error": { "code": 502, "message": e.to_string() } })),
+            )
+                .into_response()
+        }
+    }
+}
+
+async fn contracts_status(State(reloader): State<ContractReloader>) -> Json<Value> {
+    let interactions = count_interactions(&*reloader.contracts.read().await);
+    Json(json!({
+        "provider": reloader.settings.provider,
+        "consumer": reloader.settings.consumer,
+        "interactions": interactions,
+        "lastReload": *reloader.last_reload.read().await
+    }))
+}
+
+/// Admin routes for contract reloads, merged into each mock server's router
+pub fn contracts_router(reloader: ContractReloader) -> Router {
+    Router::new()
+        .route("/contracts", get(contracts_status))
+        .route("/contracts/reload", post(reload_contracts))
+        .with_state(reloader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broker::{BrokerAuth, RetryPolicy};
+    use axum_test::TestServer;
+
+    /// Serve a fake broker on a local port, returning its URL
+    async fn fake_broker(app: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{addr}")
+    }
+
+    fn settings(broker_url: String) -> BrokerSettings {
+        BrokerSettings {
+            client: BrokerClient::new(broker_url, BrokerAuth::None).with_retry(RetryPolicy::none()),
+            provider: "AWS-Secrets-Manager".to_string(),
+            consumer: "Secret-Manager-Controller".to_string(),
+        }
+    }
+
+    fn pact_broker(pact: Arc<RwLock<Option<Value>>>) -> Router {
+        Router::new().route(
+            "/pacts/provider/{provider}/consumer/{consumer}/latest",
+            get(move || async move {
+                match pact.read().await.clone() {
+                    Some(pact) => Json(pact).into_response(),
+                    None => StatusCode::NOT_FOUND.into_response(),
+                }
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_reload_replaces_contracts_and_keeps_them_on_failure() {
+        let pact = Arc::new(RwLock::new(Some(json!({
+            "interactions": [{ "description": "a" }, { "description": "b" }]
+        }))));
+        let broker_url = fake_broker(pact_broker(pact.clone())).await;
+        let contracts = Arc::new(RwLock::new(HashMap::new()));
+        let reloader = ContractReloader::new(settings(broker_url), contracts.clone());
+        let server = TestServer::new(contracts_router(reloader)).unwrap();
+
+        let response = server.post("/contracts/reload").await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["interactions"], 2);
+        assert_eq!(count_interactions(&*contracts.read().await), 2);
+
+        // The broker loses the pact: reload fails and the loaded contracts stay
+        *pact.write().await = None;
+        server
+            .post("/contracts/reload")
+            .await
+            .assert_status(StatusCode::BAD_GATEWAY);
+        assert_eq!(count_interactions(&*contracts.read().await), 2);
+
+        let status: Value = server.get("/contracts").await.json();
+        assert_eq!(status["interactions"], 2);
+        assert_eq!(status["lastReload"]["success"], false);
+        // The cause is reported: no pact, rather than e.g. rejected credentials
+        assert!(
+            status["lastReload"]["error"]
+                .as_str()
+                .unwrap()
+                .starts_with("Not found on broker")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reload_requires_token_when_configured() {
+        let pact = Arc::new(RwLock::new(Some(json!({ "interactions": [] }))));
+        let broker_url = fake_broker(pact_broker(pact)).await;
+        let reloader = ContractReloader::new(settings(broker_url), Arc::default())
+            .with_reload_token(Some("s3cr3t".to_string()));
+        let server = TestServer::new(contracts_router(reloader)).unwrap();
+
+        server
+            .post("/contracts/reload")
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .post("/contracts/reload")
+            .authorization_bearer("s3cr3t")
+            .await
+            .assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_register_webhook_is_idempotent() {
+        let registered: Arc<RwLock<Vec<Value>>> = Arc::default();
+        let store = registered.clone();
+        let list = registered.clone();
+        let broker = Router::new()
+            .route(
+                "/webhooks",
+                post(move |Json(webhook): Json<Value>| async move {
+                    store.write().await.push(webhook);
+                    StatusCode::CREATED
+                }),
+            )
+            .route(
+                "/webhooks/provider/{provider}/consumer/{consumer}",
+                get(move || async move {
+                    let webhooks: Vec<Value> = list
+                        .read()
+                        .await
+                        .iter()
+                        .map(|webhook| json!({ "title": webhook["description"] }))
+                        .collect();
+                    Json(json!({ "_links": { "pb:webhooks": webhooks } }))
+                }),
+            );
+        let settings = settings(fake_broker(broker).await);
+        let callback = "http://aws-mock-server:1234/contracts/reload";
+
+        assert!(
+            register_broker_webhook(&settings, callback, Some("s3cr3t"))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !register_broker_webhook(&settings, callback, Some("s3cr3t"))
+                .await
+                .unwrap()
+        );
+
+        let webhooks = registered.read().await;
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(webhooks[0]["request"]["url"], callback);
+        assert_eq!(
+            webhooks[0]["request"]["headers"]["Authorization"],
+            "Bearer s3cr3t"
+        );
+        assert_eq!(webhooks[0]["provider"]["name"], "AWS-Secrets-Manager");
+    }
+}
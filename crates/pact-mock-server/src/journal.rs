@@ -0,0 +1,505 @@
+//! # Request journal
+//!
+//! Records every provider API request a mock server receives, so tests can assert on the
+//! calls the controller made ("exactly one UpdateSecret call") instead of parsing logs.
+//!
+//! Each entry keeps the method, path, query, `x-amz-target`, request headers (credentials
+//! redacted), the SHA-256 of the body and the response status. Health checks and admin
+//! endpoints are not recorded.
+//!
+//! Configuration:
+//! - `REQUEST_JOURNAL_LIMIT`: Maximum number of entries kept, oldest dropped first (default: 10000)
+//! - `GET /admin/requests`: Recorded requests, filtered by query parameters
+//!   (`method`, `path`, `pathPrefix`, `target`, `bodySha256`, `status`, `after`)
+//! - `DELETE /admin/requests`: Clear the journal
+//! - `POST /admin/verify`: Check expected request counts (200 when all match, 409 otherwise)
+//!
+//! ```json
+//! {
+//!   "expectations": [
+//!     { "method": "POST", "target": "secretsmanager.UpdateSecret", "count": 1 },
+//!     { "pathPrefix": "/v1/projects/test-project/secrets", "minCount": 1 }
+//!   ]
+//! }
+//! ```
+
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Query, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// Default maximum number of journal entries
+pub const DEFAULT_JOURNAL_LIMIT: usize = 10_000;
+
+/// Headers whose values are never stored
+const REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "x-amz-security-token",
+    "x-api-key",
+];
+
+/// A recorded request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    /// Position in the journal (starts at 1, never reused after a clear)
+    pub sequence: u64,
+    /// Unix timestamp (milliseconds) when the request was received
+    pub timestamp_ms: u64,
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// AWS operation (`x-amz-target` header)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub headers: BTreeMap<String, String>,
+    /// Hex SHA-256 of the request body (absent for empty bodies)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_sha256: Option<String>,
+    /// Response status
+    pub status: u16,
+}
+
+/// Request filter, used by `GET /admin/requests` and in verify expectations
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestFilter {
+    /// HTTP method (case-insensitive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// Exact path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_prefix: Option<String>,
+    /// `x-amz-target` header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Only entries with a sequence greater than this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<u64>,
+}
+
+impl RequestFilter {
+    pub fn matches(&self, entry: &JournalEntry) -> bool {
+        self.method
+            .as_ref()
+            .is_none_or(|m| m.eq_ignore_ascii_case(&entry.method))
+            && self.path.as_ref().is_none_or(|p| *p == entry.path)
+            && self
+                .path_prefix
+                .as_ref()
+                .is_none_or(|p| entry.path.starts_with(p.as_str()))
+            && self
+                .target
+                .as_ref()
+                .is_none_or(|t| entry.target.as_ref() == Some(t))
+            && self
+                .body_sha256
+                .as_ref()
+                .is_none_or(|h| entry.body_sha256.as_ref() == Some(h))
+            && self.status.is_none_or(|s| s == entry.status)
+            && self.after.is_none_or(|after| entry.sequence > after)
+    }
+}
+
+/// Expected number of requests matching a filter
+///
+/// `count` requires an exact number; `minCount` / `maxCount` bound it. With none of them
+/// set, at least one matching request is expected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Expectation {
+    #[serde(flatten)]
+    pub filter: RequestFilter,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<usize>,
+}
+
+impl Expectation {
+    fn is_met(&self, actual: usize) -> bool {
+        match self.count {
+            Some(count) => actual == count,
+            None if self.min_count.is_none() && self.max_count.is_none() => actual >= 1,
+            None => {
+                self.min_count.is_none_or(|min| actual >= min)
+                    && self.max_count.is_none_or(|max| actual <= max)
+            }
+        }
+    }
+}
+
+/// Body of `POST /admin/verify`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VerifyRequest {
+    #[serde(default)]
+    pub expectations: Vec<Expectation>,
+}
+
+/// Outcome of one expectation
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpectationResult {
+    pub expectation: Expectation,
+    pub actual: usize,
+    pub met: bool,
+}
+
+/// Shared request journal
+#[derive(Clone, Debug)]
+pub struct RequestJournal {
+    inner: Arc<RwLock<JournalInner>>,
+    limit: usize,
+}
+
+#[derive(Debug, Default)]
+struct JournalInner {
+    entries: VecDeque<JournalEntry>,
+    next_sequence: u64,
+}
+
+impl Default for RequestJournal {
+    fn default() -> Self {
+        Self::new(DEFAULT_JOURNAL_LIMIT)
+    }
+}
+
+impl RequestJournal {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(JournalInner {
+                entries: VecDeque::new(),
+                next_sequence: 1,
+            })),
+            limit: limit.max(1),
+        }
+    }
+
+    /// Create the journal with the limit in REQUEST_JOURNAL_LIMIT
+    pub fn from_env() -> Self {
+        let limit = std::env::var("REQUEST_JOURNAL_LIMIT")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_JOURNAL_LIMIT);
+        Self::new(limit)
+    }
+
+    /// Append an entry, assigning its sequence number
+    pub async fn record(&self, mut entry: JournalEntry) {
+        let mut inner = self.inner.write().await;
+        entry.sequence = inner.next_sequence;
+        inner.next_sequence += 1;
+        if inner.entries.len() >= self.limit {
+            inner.entries.pop_front();
+        }
+        inner.entries.push_back(entry);
+    }
+
+    /// Entries matching the filter, oldest first
+    pub async fn requests(&self, filter: &RequestFilter) -> Vec<JournalEntry> {
+        self.inner
+            .read()
+            .await
+            .entries
+            .iter()
+            .filter(|entry| filter.matches(entry))
+            .cloned()
+            .collect()
+    }
+
+    /// Check each expectation against the journal
+    pub async fn verify(&self, expectations: Vec<Expectation>) -> Vec<ExpectationResult> {
+        let inner = self.inner.read().await;
+        expectations
+            .into_iter()
+            .map(|expectation| {
+                let actual = inner
+                    .entries
+                    .iter()
+                    .filter(|entry| expectation.filter.matches(entry))
+                    .count();
+                let met = expectation.is_met(actual);
+                ExpectationResult {
+                    expectation,
+                    actual,
+                    met,
+                }
+            })
+            .collect()
+    }
+
+    pub async fn clear(&self) {
+        self.inner.write().await.entries.clear();
+    }
+}
+
+/// Hex SHA-256 of a body, as stored in `JournalEntry::body_sha256`
+pub fn body_sha256(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn recorded_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+                "[REDACTED]".to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.as_str().to_string(), value)
+        })
+        .collect()
+}
+
+/// Journal middleware
+/// Records each provider request with the status of its response
+pub async fn journal_middleware(
+    State(journal): State<RequestJournal>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if crate::is_admin_path(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Journal: failed to read request body: {}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+    let timestamp_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+    let mut entry = JournalEntry {
+        sequence: 0,
+        timestamp_ms,
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        query: parts.uri.query().map(str::to_string),
+        target: parts
+            .headers
+            .get("x-amz-target")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        headers: recorded_headers(&parts.headers),
+        body_sha256: (!bytes.is_empty()).then(|| body_sha256(&bytes)),
+        status: 0,
+    };
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+    entry.status = response.status().as_u16();
+    journal.record(entry).await;
+    response
+}
+
+async fn list_requests(
+    State(journal): State<RequestJournal>,
+    Query(filter): Query<RequestFilter>,
+) -> Json<serde_json::Value> {
+    let requests = journal.requests(&filter).await;
+    Json(json!({
+        "total": requests.len(),
+        "requests": requests
+    }))
+}
+
+async fn clear_requests(State(journal): State<RequestJournal>) -> StatusCode {
+    journal.clear().await;
+    info!("Request journal cleared");
+    StatusCode::NO_CONTENT
+}
+
+async fn verify_requests(
+    State(journal): State<RequestJournal>,
+    Json(body): Json<VerifyRequest>,
+) -> Response {
+    let results = journal.verify(body.expectations).await;
+    let verified = results.iter().all(|result| result.met);
+    let code = if verified {
+        StatusCode::OK
+    } else {
+        warn!(
+            "Request verification failed: {} of {} expectation(s) not met",
+            results.iter().filter(|result| !result.met).count(),
+            results.len()
+        );
+        StatusCode::CONFLICT
+    };
+    (
+        code,
+        Json(json!({
+            "verified": verified,
+            "results": results
+        })),
+    )
+        .into_response()
+}
+
+/// Admin routes for the request journal, merged into each mock server's router
+pub fn journal_router(journal: RequestJournal) -> Router {
+    Router::new()
+        .route("/admin/requests", get(list_requests).delete(clear_requests))
+        .route("/admin/verify", post(verify_requests))
+        .with_state(journal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::put;
+    use axum_test::TestServer;
+
+    fn test_server(journal: RequestJournal) -> TestServer {
+        let app = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/", post(|| async { "aws" }))
+            .route("/v1/secrets/{name}", put(|| async { "updated" }))
+            .layer(axum::middleware::from_fn_with_state(
+                journal.clone(),
+                journal_middleware,
+            ))
+            .merge(journal_router(journal));
+        TestServer::new(app).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_records_requests_with_redacted_credentials() {
+        let journal = RequestJournal::default();
+        let server = test_server(journal.clone());
+
+        server.get("/health").await.assert_status_ok();
+        server
+            .put("/v1/secrets/db-password?api-version=7.4")
+            .add_header("authorization", "Bearer s3cr3t")
+            .text("value")
+            .await
+            .assert_status_ok();
+
+        let requests = journal.requests(&RequestFilter::default()).await;
+        assert_eq!(requests.len(), 1);
+        let request = &requests[0];
+        assert_eq!(request.sequence, 1);
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.path, "/v1/secrets/db-password");
+        assert_eq!(request.query.as_deref(), Some("api-version=7.4"));
+        assert_eq!(request.headers["authorization"], "[REDACTED]");
+        assert_eq!(request.body_sha256, Some(body_sha256(b"value")));
+        assert_eq!(request.status, 200);
+    }
+
+    #[tokio::test]
+    async fn test_filter_requests_by_target() {
+        let journal = RequestJournal::default();
+        let server = test_server(journal);
+
+        for target in [
+            "secretsmanager.UpdateSecret",
+            "secretsmanager.GetSecretValue",
+            "secretsmanager.UpdateSecret",
+        ] {
+            server
+                .post("/")
+                .add_header("x-amz-target", target)
+                .await
+                .assert_status_ok();
+        }
+
+        let body: serde_json::Value = server
+            .get("/admin/requests")
+            .add_query_param("target", "secretsmanager.UpdateSecret")
+            .await
+            .json();
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["requests"][1]["sequence"], 3);
+
+        let body: serde_json::Value = server
+            .get("/admin/requests")
+            .add_query_param("after", 2)
+            .await
+            .json();
+        assert_eq!(body["total"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_verify_expectations() {
+        let journal = RequestJournal::default();
+        let server = test_server(journal);
+        server
+            .post("/")
+            .add_header("x-amz-target", "secretsmanager.UpdateSecret")
+            .await
+            .assert_status_ok();
+
+        server
+            .post("/admin/verify")
+            .json(&json!({
+                "expectations": [
+                    { "method": "post", "target": "secretsmanager.UpdateSecret", "count": 1 },
+                    { "target": "secretsmanager.DeleteSecret", "maxCount": 0 }
+                ]
+            }))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .post("/admin/verify")
+            .json(&json!({
+                "expectations": [{ "target": "secretsmanager.GetSecretValue" }]
+            }))
+            .await;
+        response.assert_status(StatusCode::CONFLICT);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["verified"], false);
+        assert_eq!(body["results"][0]["actual"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_journal_limit_and_clear() {
+        let journal = RequestJournal::new(2);
+        let server = test_server(journal.clone());
+        for _ in 0..3 {
+            server.post("/").await.assert_status_ok();
+        }
+
+        let sequences: Vec<u64> = journal
+            .requests(&RequestFilter::default())
+            .await
+            .iter()
+            .map(|entry| entry.sequence)
+            .collect();
+        assert_eq!(sequences, vec![2, 3]);
+
+        server
+            .delete("/admin/requests")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        assert!(journal.requests(&RequestFilter::default()).await.is_empty());
+    }
+}
@@ -1,10 +1,20 @@
 //! Shared library for Pact Mock Servers
 //!
 //! Provides common functionality for GCP, AWS, and Azure mock servers:
-//! - Contract loading from Pact broker
-//! - Request logging middleware
+//! - Contract loading from Pact broker (`broker`)
+//! - Request logging middleware, with optional redacted body capture (`LOG_BODIES`, `redact`)
 //! - Health check endpoints
 //! - App state management
+//! - Optional TLS termination (`TLS_ENABLED`)
+//! - Latency and fault injection (`chaos`)
+//! - Per-tenant quota and rate-limit simulation (`quota`)
+//! - Scenario recording and replay (`scenario`)
+//! - Seeding, snapshotting and resetting provider state (`admin`)
+//! - Request journal and call-count verification (`journal`)
+//! - Prometheus request metrics per provider operation (`metrics`)
+//! - Live verification of requests against the loaded Pact interactions (`verification`)
+//! - Webhook-triggered contract reloads (`contracts`)
+//! - Per-project, per-account and per-vault isolation (`tenant`)
 //! - API path definitions (source of truth for GCP API paths)
 //!
 //! ## Quick Start
@@ -15,18 +25,33 @@
 //!
 //! This brings commonly used types and functions into scope.
 
+pub mod admin;
+pub mod broker;
+pub mod chaos;
+pub mod contracts;
+pub mod journal;
+pub mod metrics;
 pub mod prelude;
+pub mod quota;
+pub mod redact;
+pub mod scenario;
 pub mod secrets;
+pub mod tenant;
+pub mod tls;
+pub mod ui;
+pub mod verification;
 
+use crate::secrets::common::errors::provider_error_response;
 use axum::{
+    body::Body,
     extract::Request,
-    http::{HeaderValue, Method, StatusCode},
+    http::{HeaderMap, HeaderValue, Method, StatusCode},
     middleware::Next,
     response::{IntoResponse, Json, Response},
 };
 use serde_json::{Value, json};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::time::sleep;
@@ -50,12 +75,44 @@ impl AppState {
     }
 }
 
+/// Default cap on the logged size of a body (`LOG_BODY_LIMIT`)
+pub const DEFAULT_LOG_BODY_LIMIT: usize = 16 * 1024;
+
+/// Body capture settings for [`logging_middleware`], read once from the environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyLogging {
+    /// Log JSON bodies (`LOG_BODIES`)
+    pub enabled: bool,
+    /// Bytes of each (redacted) body logged, the rest is elided (`LOG_BODY_LIMIT`)
+    pub limit: usize,
+}
+
+impl BodyLogging {
+    /// Settings from LOG_BODIES and LOG_BODY_LIMIT
+    pub fn from_env() -> Self {
+        let enabled =
+            std::env::var("LOG_BODIES").is_ok_and(|v| v.eq_ignore_ascii_case("true") || v == "1");
+        let limit = std::env::var("LOG_BODY_LIMIT")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_LOG_BODY_LIMIT);
+        Self { enabled, limit }
+    }
+}
+
+static BODY_LOGGING: LazyLock<BodyLogging> = LazyLock::new(BodyLogging::from_env);
+
 /// Request logging middleware
 /// Logs all incoming requests with method, path, client IP, response status, and duration
+///
+/// With `LOG_BODIES=true`, JSON request bodies of POST/PUT/PATCH requests and JSON response
+/// bodies are buffered, logged with secret values masked (see [`redact`]) and passed on
+/// unchanged. Logged bodies are cut at `LOG_BODY_LIMIT` bytes.
 pub async fn logging_middleware(request: Request, next: Next) -> axum::response::Response {
     let method = request.method().clone();
     let path = request.uri().path().to_string();
     let start = std::time::Instant::now();
+    let body_logging = *BODY_LOGGING;
 
     // Log request
     info!(
@@ -70,18 +127,21 @@ pub async fn logging_middleware(request: Request, next: Next) -> axum::response:
     );
 
     // Log request body for POST/PUT/PATCH
-    if matches!(method, Method::POST | Method::PUT | Method::PATCH) {
-        if let Some(content_type) = request.headers().get("content-type") {
-            if content_type
-                .to_str()
-                .unwrap_or("")
-                .contains("application/json")
-            {
-                // Note: Body is consumed by the handler, so we can't log it here
-                // The handler will log it if needed
+    let request = if body_logging.enabled
+        && matches!(method, Method::POST | Method::PUT | Method::PATCH)
+        && is_json(request.headers())
+    {
+        let (parts, body) = request.into_parts();
+        match log_json_body(&format!("→ {method} {path}"), body, body_logging.limit).await {
+            Ok(body) => Request::from_parts(parts, body),
+            Err(e) => {
+                warn!("Failed to read request body: {}", e);
+                return StatusCode::BAD_REQUEST.into_response();
             }
         }
-    }
+    } else {
+        request
+    };
 
     let response = next.run(request).await;
     let duration = start.elapsed();
@@ -95,9 +155,71 @@ pub async fn logging_middleware(request: Request, next: Next) -> axum::response:
         duration.as_secs_f64()
     );
 
+    if body_logging.enabled && is_json(response.headers()) {
+        let (parts, body) = response.into_parts();
+        return match log_json_body(&format!("← {method} {path}"), body, body_logging.limit).await
+        {
+            Ok(body) => Response::from_parts(parts, body),
+            Err(e) => {
+                warn!("Failed to read response body: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        };
+    }
+
     response
 }
 
+/// Whether a request or response carries a JSON body (including AWS `x-amz-json-1.1`)
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|content_type| content_type.contains("json"))
+}
+
+/// Buffer a JSON body, log it with secret values redacted, and return it for re-injection
+async fn log_json_body(label: &str, body: Body, limit: usize) -> Result<Body, axum::Error> {
+    let bytes = axum::body::to_bytes(body, usize::MAX).await?;
+    if !bytes.is_empty() {
+        info!("{} body: {}", label, body_excerpt(&bytes, limit));
+    }
+    Ok(Body::from(bytes))
+}
+
+/// Redacted body text, cut at `limit` bytes
+/// Redaction runs on the whole body: a truncated document wouldn't parse as JSON.
+fn body_excerpt(bytes: &[u8], limit: usize) -> String {
+    let mut text = redact::redact_body(&String::from_utf8_lossy(bytes));
+    if text.len() > limit {
+        let mut end = limit;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let total = text.len();
+        text.truncate(end);
+        text.push_str(&format!("… ({total} bytes)"));
+    }
+    text
+}
+
+/// Health checks and admin endpoints, which are never recorded, replayed or journaled
+pub(crate) fn is_admin_path(method: &Method, path: &str) -> bool {
+    path == "/health"
+        || (path == "/" && method == Method::GET)
+        || path.starts_with("/scenario/")
+        || path.starts_with("/chaos/")
+        || path.starts_with("/quota/")
+        || path.starts_with("/iam/")
+        || path.starts_with("/admin/")
+        || path.starts_with("/pact/")
+        || path == "/contracts"
+        || path == "/metrics"
+        || path.starts_with("/contracts/")
+        || path == "/ui"
+        || path.starts_with("/ui/")
+}
+
 /// Health check endpoint
 /// Returns a simple JSON response indicating the service is healthy
 pub async fn health_check() -> Json<Value> {
@@ -133,17 +255,12 @@ pub async fn rate_limit_middleware(request: Request, next: Next) -> Response {
                     retry_after
                 );
 
-                let mut response = (
+                let mut response = provider_error_response(
+                    request.headers(),
+                    request.uri().path(),
                     StatusCode::TOO_MANY_REQUESTS,
-                    Json(json!({
-                        "error": {
-                            "code": 429,
-                            "message": "Rate limit exceeded",
-                            "retry_after": retry_after
-                        }
-                    })),
-                )
-                    .into_response();
+                    "Rate limit exceeded".to_string(),
+                );
 
                 // Add Retry-After header
                 if let Ok(header_value) = HeaderValue::from_str(&retry_after.to_string()) {
@@ -174,16 +291,12 @@ pub async fn service_unavailable_middleware(request: Request, next: Next) -> Res
         if let Ok(header_str) = header_value.to_str() {
             if header_str.to_lowercase() == "true" {
                 warn!("Service unavailable header detected - returning 503");
-                return (
+                return provider_error_response(
+                    request.headers(),
+                    request.uri().path(),
                     StatusCode::SERVICE_UNAVAILABLE,
-                    Json(json!({
-                        "error": {
-                            "code": 503,
-                            "message": "Service temporarily unavailable"
-                        }
-                    })),
-                )
-                    .into_response();
+                    "Service temporarily unavailable".to_string(),
+                );
             }
         }
     }
@@ -214,20 +327,17 @@ pub async fn auth_failure_middleware(request: Request, next: Next) -> Response {
 
             if let Some(status_code) = status {
                 warn!("Auth failure header detected - returning {}", status_code);
-                return (
+                let message = if status_code == StatusCode::UNAUTHORIZED {
+                    "Unauthorized: Invalid or missing authentication credentials"
+                } else {
+                    "Forbidden: Insufficient permissions"
+                };
+                return provider_error_response(
+                    request.headers(),
+                    request.uri().path(),
                     status_code,
-                    Json(json!({
-                        "error": {
-                            "code": status_code.as_u16(),
-                            "message": if status_code == StatusCode::UNAUTHORIZED {
-                                "Unauthorized: Invalid or missing authentication credentials"
-                            } else {
-                                "Forbidden: Insufficient permissions"
-                            }
-                        }
-                    })),
-                )
-                    .into_response();
+                    message.to_string(),
+                );
             }
         }
     }
@@ -237,6 +347,8 @@ pub async fn auth_failure_middleware(request: Request, next: Next) -> Response {
 
 /// Wait for Pact broker to be ready and pacts to be published
 /// This ensures the broker is accessible and contracts are available before starting the mock server
+///
+/// Gives up immediately if the broker rejects the credentials, since waiting won't help.
 pub async fn wait_for_broker_and_pacts(
     broker_url: &str,
     username: &str,
@@ -245,7 +357,15 @@ pub async fn wait_for_broker_and_pacts(
     consumer: &str,
     max_wait_seconds: u64,
 ) -> Result<(), String> {
-    let client = reqwest::Client::new();
+    // Polling below is the retry loop, so individual requests aren't retried
+    let client = broker::BrokerClient::new(
+        broker_url,
+        broker::BrokerAuth::Basic {
+            username: username.to_string(),
+            password: password.to_string(),
+        },
+    )
+    .with_retry(broker::RetryPolicy::none());
     let start_time = std::time::Instant::now();
     let mut attempt = 0;
     let max_attempts = max_wait_seconds / 2; // Check every 2 seconds
@@ -267,55 +387,30 @@ pub async fn wait_for_broker_and_pacts(
             ));
         }
 
-        // First, check if broker is accessible
-        let heartbeat_url = format!("{}/diagnostic/status/heartbeat", broker_url);
-        match client.get(&heartbeat_url).send().await {
-            Ok(response) if response.status().is_success() => {
-                // Broker is ready, now check for pacts
-                let pact_url = format!(
-                    "{}/pacts/provider/{}/consumer/{}/latest",
-                    broker_url, provider, consumer
-                );
-
-                match client
-                    .get(&pact_url)
-                    .basic_auth(username, Some(password))
-                    .send()
-                    .await
-                {
-                    Ok(response) if response.status().is_success() => {
-                        info!("✅ Broker is ready and pacts are published!");
-                        return Ok(());
-                    }
-                    Ok(response) if response.status() == 404 => {
-                        if attempt % 5 == 0 {
-                            info!(
-                                "Broker is ready, waiting for pacts to be published... (attempt {}/{})",
-                                attempt, max_attempts
-                            );
-                        }
-                    }
-                    Ok(response) => {
-                        warn!(
-                            "Unexpected status when checking for pacts: {}",
-                            response.status()
-                        );
-                    }
-                    Err(e) => {
-                        if attempt % 5 == 0 {
-                            warn!("Error checking for pacts: {} (will retry)", e);
-                        }
-                    }
-                }
+        // First, check if broker is accessible, then check for pacts
+        let result = match client.heartbeat().await {
+            Ok(()) => client.latest_pact(provider, consumer).await.map(drop),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => {
+                info!("✅ Broker is ready and pacts are published!");
+                return Ok(());
             }
-            Ok(response) => {
+            Err(e @ broker::BrokerError::Unauthorized { .. }) => {
+                return Err(e.to_string());
+            }
+            Err(broker::BrokerError::NotFound { .. }) => {
                 if attempt % 5 == 0 {
-                    warn!("Broker returned status {} (will retry)", response.status());
+                    info!(
+                        "Broker is ready, waiting for pacts to be published... (attempt {}/{})",
+                        attempt, max_attempts
+                    );
                 }
             }
             Err(e) => {
                 if attempt % 5 == 0 {
-                    warn!("Broker not yet accessible: {} (will retry)", e);
+                    warn!("{} (will retry)", e);
                 }
             }
         }
@@ -452,6 +547,9 @@ pub async fn wait_for_manager_ready(
 
 /// Load contracts from Pact broker
 /// Fetches the latest contracts for a given provider and consumer
+///
+/// Returns an empty map if the pact can't be loaded; the cause is logged. Use
+/// `broker::BrokerClient::load_contracts` to handle the typed error instead.
 pub async fn load_contracts_from_broker(
     broker_url: &str,
     username: &str,
@@ -459,40 +557,39 @@ pub async fn load_contracts_from_broker(
     provider: &str,
     consumer: &str,
 ) -> HashMap<String, Value> {
-    let url = format!(
-        "{}/pacts/provider/{}/consumer/{}/latest",
-        broker_url, provider, consumer
-    );
-
-    let client = reqwest::Client::new();
-    match client
-        .get(&url)
-        .basic_auth(username, Some(password))
-        .send()
-        .await
-    {
-        Ok(response) if response.status().is_success() => match response.json::<Value>().await {
-            Ok(contracts) => {
-                info!("✅ Loaded contracts from broker");
-                let mut map = HashMap::new();
-                map.insert("contracts".to_string(), contracts);
-                map
-            }
-            Err(e) => {
-                warn!("Failed to parse contracts: {}", e);
-                HashMap::new()
-            }
+    let client = broker::BrokerClient::new(
+        broker_url,
+        broker::BrokerAuth::Basic {
+            username: username.to_string(),
+            password: password.to_string(),
         },
-        Ok(response) => {
-            warn!(
-                "Could not load contracts from broker: {}",
-                response.status()
-            );
-            HashMap::new()
+    );
+    match client.load_contracts(provider, consumer).await {
+        Ok(contracts) => {
+            info!("✅ Loaded contracts from broker");
+            contracts
         }
         Err(e) => {
-            warn!("Could not connect to broker: {}", e);
+            broker::log_load_error(&e);
             HashMap::new()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_excerpt_redacts_before_truncating() {
+        let body = br#"{"SecretString":"hunter2hunter2","name":"db-password"}"#;
+        assert_eq!(
+            body_excerpt(body, 1024),
+            r#"{"SecretString":"[REDACTED]","name":"db-password"}"#
+        );
+
+        let excerpt = body_excerpt(body, 20);
+        assert_eq!(excerpt, r#"{"SecretString":"[RE… (50 bytes)"#);
+        assert!(!excerpt.contains("hunter2"));
+    }
+}
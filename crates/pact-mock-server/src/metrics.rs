@@ -0,0 +1,208 @@
+//! # Metrics
+//!
+//! Prometheus metrics for the mock servers, so long-running Pact environments can be monitored
+//! and load tests can report provider-side request counts and latencies.
+//!
+//! Requests are labelled by provider operation:
+//! - AWS: the `x-amz-target` header (`secretsmanager.GetSecretValue`)
+//! - GCP colon routes: method and verb (`POST :addVersion`)
+//! - Other routed requests: method and route template from the `smc_paths` route constants
+//!   (`GET /v1/projects/{project}/secrets/{secret}`)
+//!
+//! Health checks and admin endpoints are not counted.
+//!
+//! Metrics (`GET /metrics`, Prometheus text format):
+//! - `mock_server_requests_total{provider, operation, status}`
+//! - `mock_server_request_duration_seconds{provider, operation}`
+
+use axum::{
+    Router,
+    extract::{MatchedPath, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::time::{Duration, Instant};
+use tracing::error;
+
+/// Request metrics of one mock server
+#[derive(Clone, Debug)]
+pub struct MockMetrics {
+    provider: String,
+    registry: Registry,
+    requests: IntCounterVec,
+    duration: HistogramVec,
+}
+
+impl MockMetrics {
+    /// Metrics for a provider ("gcp", "aws", "azure"), in their own registry
+    pub fn new(provider: &str) -> Result<Self, prometheus::Error> {
+        let requests = IntCounterVec::new(
+            Opts::new(
+                "mock_server_requests_total",
+                "Total number of provider API requests served by the mock server",
+            ),
+            &["provider", "operation", "status"],
+        )?;
+        let duration = HistogramVec::new(
+            HistogramOpts::new(
+                "mock_server_request_duration_seconds",
+                "Duration of provider API requests served by the mock server in seconds",
+            )
+            .buckets(vec![0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0]),
+            &["provider", "operation"],
+        )?;
+        let registry = Registry::new();
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(duration.clone()))?;
+        Ok(Self {
+            provider: provider.to_string(),
+            registry,
+            requests,
+            duration,
+        })
+    }
+
+    /// Count a served request
+    pub fn observe(&self, operation: &str, status: StatusCode, elapsed: Duration) {
+        self.requests
+            .with_label_values(&[self.provider.as_str(), operation, status.as_str()])
+            .inc();
+        self.duration
+            .with_label_values(&[self.provider.as_str(), operation])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Metrics in the Prometheus text format
+    pub fn encode(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
+
+/// Provider operation a request is counted under
+fn operation_label(request: &Request) -> String {
+    if let Some(target) = request
+        .headers()
+        .get("x-amz-target")
+        .and_then(|v| v.to_str().ok())
+    {
+        return target.to_string();
+    }
+    let method = request.method();
+    // The verb first: "{secret}:addVersion" can also match a "{secret}" route
+    let last_segment = request.uri().path().rsplit('/').next().unwrap_or_default();
+    match (
+        last_segment.rsplit_once(':'),
+        request.extensions().get::<MatchedPath>(),
+    ) {
+        (Some((_, verb)), _) if !verb.is_empty() => format!("{method} :{verb}"),
+        (_, Some(matched)) => format!("{} {}", method, matched.as_str()),
+        _ => format!("{method} unmatched"),
+    }
+}
+
+/// Metrics middleware
+/// Counts each provider request with its status and duration
+pub async fn metrics_middleware(
+    State(metrics): State<MockMetrics>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if crate::is_admin_path(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let operation = operation_label(&request);
+    let start = Instant::now();
+    let response = next.run(request).await;
+    metrics.observe(&operation, response.status(), start.elapsed());
+    response
+}
+
+async fn serve_metrics(State(metrics): State<MockMetrics>) -> Response {
+    match metrics.encode() {
+        Ok(body) => (
+            [("content-type", "text/plain; version=0.0.4; charset=utf-8")],
+            body,
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to encode metrics: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to encode metrics: {e}"),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Metrics endpoint (`GET /metrics`)
+pub fn metrics_router(metrics: MockMetrics) -> Router {
+    Router::new()
+        .route("/metrics", get(serve_metrics))
+        .with_state(metrics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum_test::TestServer;
+
+    #[tokio::test]
+    async fn test_counts_requests_by_operation() {
+        let metrics = MockMetrics::new("gcp").unwrap();
+        let app = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/", post(|| async { "aws" }))
+            .route(
+                "/v1/projects/{project}/secrets/{secret}",
+                get(|| async { "secret" }),
+            )
+            .fallback(|| async { StatusCode::NOT_FOUND })
+            .layer(axum::middleware::from_fn_with_state(
+                metrics.clone(),
+                metrics_middleware,
+            ))
+            .merge(metrics_router(metrics));
+        let server = TestServer::new(app).unwrap();
+
+        server.get("/health").await.assert_status_ok();
+        server
+            .get("/v1/projects/p/secrets/a")
+            .await
+            .assert_status_ok();
+        server
+            .get("/v1/projects/p/secrets/b")
+            .await
+            .assert_status_ok();
+        server
+            .post("/")
+            .add_header("x-amz-target", "secretsmanager.GetSecretValue")
+            .await
+            .assert_status_ok();
+        server
+            .post("/v1/projects/p/parameters/a:render")
+            .await
+            .assert_status_not_found();
+
+        let body = server.get("/metrics").await.text();
+        assert!(body.contains(
+            r#"mock_server_requests_total{operation="GET /v1/projects/{project}/secrets/{secret}",provider="gcp",status="200"} 2"#
+        ));
+        assert!(body.contains(
+            r#"mock_server_requests_total{operation="secretsmanager.GetSecretValue",provider="gcp",status="200"} 1"#
+        ));
+        assert!(body.contains(
+            r#"mock_server_requests_total{operation="POST :render",provider="gcp",status="404"} 1"#
+        ));
+        assert!(!body.contains("/health"));
+    }
+}
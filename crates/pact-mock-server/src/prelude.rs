@@ -19,7 +19,7 @@
 //!
 //! This brings into scope:
 //! - `AppState` - Application state for mock servers
-//! - Middleware functions (logging, rate limiting, auth, etc.)
+//! - Middleware functions (logging, rate limiting, auth, chaos, etc.)
 //! - Secret store types (`AwsSecretStore`, `GcpSecretStore`, `AzureSecretStore`, etc.)
 //! - Common utilities (error responses, validation functions)
 
@@ -33,8 +33,45 @@ pub use crate::{
     wait_for_manager_ready,
 };
 
+// Seeding, snapshotting and resetting provider state
+pub use crate::admin::{AdminState, AdminStore, admin_router};
+
+// Web UI for browsing stored state
+pub use crate::ui::{UiState, ui_router};
+
+// Request journal and call-count verification
+pub use crate::journal::{RequestJournal, journal_middleware, journal_router};
+
+// Prometheus request metrics
+pub use crate::metrics::{MockMetrics, metrics_middleware, metrics_router};
+
+// Pact verification of incoming requests
+pub use crate::verification::{
+    PactVerificationState, pact_verification_middleware, pact_verification_router,
+};
+
+// Pact Broker client
+pub use crate::broker::{BrokerAuth, BrokerClient, BrokerError, RetryPolicy, log_load_error};
+
+// Contract hot-reload
+pub use crate::contracts::{
+    BrokerSettings, ContractReloader, contracts_router, register_broker_webhook_from_env,
+};
+
+// Tenant isolation
+pub use crate::tenant::{TenantKind, TenantState, tenant_middleware};
+
+// Latency and fault injection
+pub use crate::chaos::{ChaosState, chaos_middleware, chaos_router};
+
+// Quota and rate-limit simulation
+pub use crate::quota::{QuotaState, quota_middleware, quota_router};
+
+// Scenario recording and replay
+pub use crate::scenario::{ScenarioState, scenario_middleware, scenario_router};
+
 // Secret store types - provider-specific implementations
-pub use crate::secrets::aws::AwsSecretStore;
+pub use crate::secrets::aws::{AwsParameterStore, AwsSecretStore};
 pub use crate::secrets::azure::AzureSecretStore;
 pub use crate::secrets::common::{SecretEntry, SecretStore, SecretVersion};
 pub use crate::secrets::gcp::{GcpParameterStore, GcpSecretStore};
@@ -42,7 +79,8 @@ pub use crate::secrets::gcp::{GcpParameterStore, GcpSecretStore};
 // Common utilities - error responses and validation
 pub use crate::secrets::common::errors::{
     aws_error_response, aws_error_type_from_status, azure_error_code_from_status,
-    azure_error_response, gcp_error_response,
+    azure_error_response, azure_error_response_with_inner, gcp_error_info, gcp_error_response,
+    gcp_error_response_with_details, gcp_retry_info, provider_error_response,
 };
 pub use crate::secrets::common::limits::{
     validate_aws_secret_size, validate_azure_secret_size, validate_gcp_secret_size,
@@ -0,0 +1,674 @@
+//! # Quota
+//!
+//! Stateful quota simulation for the provider mock servers.
+//!
+//! The `X-Rate-Limit` header fails a single request on demand; quota rules instead count
+//! requests per tenant (GCP project, AWS account, Azure vault) in fixed windows and throttle
+//! the ones over the limit, the way the real APIs do. This exercises the controller's pacing,
+//! retry and circuit-breaker behavior end to end.
+//!
+//! Requests are counted as reads (GCP and Azure `GET`, AWS `Get*`, `List*`, `Describe*` and
+//! `BatchGet*` operations) or writes (everything else). The tenant is the one tenant isolation
+//! resolved; without isolation it is the GCP project in the path, the Azure vault in the `Host`
+//! header, or the default AWS account. Throttled requests are not counted and get the
+//! provider's error:
+//! - GCP: 429 `RESOURCE_EXHAUSTED` with a `Retry-After` header and `google.rpc.RetryInfo`
+//! - AWS: 400 `ThrottlingException` ("Rate exceeded")
+//! - Azure: 429 `Throttled` with a `Retry-After` header
+//!
+//! Configuration:
+//! - `QUOTA_CONFIG`: JSON config applied at startup
+//! - `QUOTA_CONFIG_FILE`: Path to a JSON config file applied at startup
+//! - `GET /quota/config`: Current config
+//! - `PUT /quota/config`: Replace the config (also resets the counters)
+//! - `DELETE /quota/config`: Disable quotas
+//! - `GET /quota/usage`: Requests counted in the current window of each tenant and rule
+//!
+//! ```json
+//! {
+//!   "rules": [
+//!     { "tenant": "project-a", "operations": "write", "limit": 10, "windowSeconds": 60 },
+//!     { "operations": "all", "limit": 600 }
+//!   ]
+//! }
+//! ```
+
+use anyhow::{Context, Result};
+use axum::{
+    Json, Router,
+    extract::{Request, State},
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::secrets::common::errors::{
+    aws_error_response, aws_error_types, azure_error_codes, azure_error_response, gcp_error_info,
+    gcp_error_response_with_details, gcp_retry_info,
+};
+use crate::tenant::{self, TenantKind};
+
+/// Window used when a rule doesn't set one
+pub const DEFAULT_QUOTA_WINDOW_SECS: u64 = 60;
+
+fn default_window_seconds() -> u64 {
+    DEFAULT_QUOTA_WINDOW_SECS
+}
+
+/// Requests a quota rule counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuotaOperations {
+    #[default]
+    Write,
+    Read,
+    All,
+}
+
+impl QuotaOperations {
+    fn counts(self, write: bool) -> bool {
+        match self {
+            Self::Write => write,
+            Self::Read => !write,
+            Self::All => true,
+        }
+    }
+
+    /// Quota metric name, as GCP reports it
+    fn metric(self) -> &'static str {
+        match self {
+            Self::Write => "Write requests",
+            Self::Read => "Read requests",
+            Self::All => "Requests",
+        }
+    }
+}
+
+/// Requests allowed per tenant and window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaRule {
+    /// Tenant the rule applies to; every tenant gets its own budget when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    /// Requests counted (default: write)
+    #[serde(default)]
+    pub operations: QuotaOperations,
+    /// Requests allowed per window
+    pub limit: u32,
+    /// Window length in seconds (default: 60)
+    #[serde(default = "default_window_seconds")]
+    pub window_seconds: u64,
+}
+
+impl QuotaRule {
+    fn applies(&self, tenant: &str, write: bool) -> bool {
+        self.tenant.as_deref().is_none_or(|t| t == tenant) && self.operations.counts(write)
+    }
+
+    fn window(&self) -> Duration {
+        Duration::from_secs(self.window_seconds)
+    }
+}
+
+/// Quota configuration (no rules disables quotas)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaConfig {
+    #[serde(default)]
+    pub rules: Vec<QuotaRule>,
+}
+
+impl QuotaConfig {
+    /// Load the config from QUOTA_CONFIG or QUOTA_CONFIG_FILE (empty if neither is set)
+    pub fn from_env() -> Result<Self> {
+        if let Ok(config) = std::env::var("QUOTA_CONFIG") {
+            return serde_json::from_str(&config).context("QUOTA_CONFIG is not a valid config");
+        }
+        if let Ok(path) = std::env::var("QUOTA_CONFIG_FILE") {
+            let config = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read QUOTA_CONFIG_FILE '{path}'"))?;
+            return serde_json::from_str(&config)
+                .with_context(|| format!("QUOTA_CONFIG_FILE '{path}' is not a valid config"));
+        }
+        Ok(Self::default())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.window_seconds == 0 {
+                return Err(format!("rules[{index}].windowSeconds must be positive"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Requests counted in the current window of a rule and tenant
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    start: Instant,
+    count: u32,
+}
+
+/// Requests counted for a tenant under a rule, as served by `GET /quota/usage`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaUsage {
+    /// Index of the rule in the config
+    pub rule: usize,
+    pub tenant: String,
+    pub used: u32,
+    pub limit: u32,
+    /// Seconds until the window resets
+    pub resets_in_seconds: u64,
+}
+
+/// A throttled request: the exhausted rule and when its window resets
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Throttle {
+    rule: QuotaRule,
+    retry_after: Duration,
+}
+
+#[derive(Debug, Default)]
+struct QuotaInner {
+    config: QuotaConfig,
+    /// Keyed by rule index and tenant
+    windows: HashMap<(usize, String), Window>,
+}
+
+/// Shared quota state (config and counters)
+#[derive(Debug, Clone)]
+pub struct QuotaState {
+    kind: TenantKind,
+    inner: Arc<Mutex<QuotaInner>>,
+}
+
+impl QuotaState {
+    #[must_use]
+    pub fn new(kind: TenantKind, config: QuotaConfig) -> Self {
+        Self {
+            kind,
+            inner: Arc::new(Mutex::new(QuotaInner {
+                config,
+                windows: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Quota state from QUOTA_CONFIG / QUOTA_CONFIG_FILE, disabled if the config is invalid
+    pub fn from_env(kind: TenantKind) -> Self {
+        match QuotaConfig::from_env().and_then(|config| {
+            config.validate().map_err(anyhow::Error::msg)?;
+            Ok(config)
+        }) {
+            Ok(config) => {
+                if !config.rules.is_empty() {
+                    info!("Quotas enabled with {} rule(s)", config.rules.len());
+                }
+                Self::new(kind, config)
+            }
+            Err(e) => {
+                warn!("⚠️  Ignoring quota config: {:#}", e);
+                Self::new(kind, QuotaConfig::default())
+            }
+        }
+    }
+
+    /// Replace the config and reset the counters
+    pub fn set_config(&self, config: QuotaConfig) -> Result<(), String> {
+        config.validate()?;
+        let mut inner = self.lock();
+        inner.config = config;
+        inner.windows.clear();
+        Ok(())
+    }
+
+    pub fn config(&self) -> QuotaConfig {
+        self.lock().config.clone()
+    }
+
+    /// Requests counted in the windows that haven't expired
+    pub fn usage(&self) -> Vec<QuotaUsage> {
+        let now = Instant::now();
+        let inner = self.lock();
+        let mut usage: Vec<QuotaUsage> = inner
+            .windows
+            .iter()
+            .filter_map(|((index, tenant), window)| {
+                let rule = inner.config.rules.get(*index)?;
+                let remaining = rule.window().checked_sub(now - window.start)?;
+                Some(QuotaUsage {
+                    rule: *index,
+                    tenant: tenant.clone(),
+                    used: window.count,
+                    limit: rule.limit,
+                    resets_in_seconds: remaining.as_secs(),
+                })
+            })
+            .collect();
+        usage.sort_by(|a, b| (a.rule, &a.tenant).cmp(&(b.rule, &b.tenant)));
+        usage
+    }
+
+    /// Count a request against every rule that applies to it
+    /// Returns the exhausted rule instead, without counting, when any of them is used up.
+    fn consume(&self, tenant: &str, write: bool, now: Instant) -> Option<Throttle> {
+        let mut inner = self.lock();
+        let QuotaInner { config, windows } = &mut *inner;
+        let rules: Vec<(usize, &QuotaRule)> = config
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.applies(tenant, write))
+            .collect();
+
+        for (index, rule) in &rules {
+            let window = windows
+                .entry((*index, tenant.to_string()))
+                .or_insert(Window {
+                    start: now,
+                    count: 0,
+                });
+            if now - window.start >= rule.window() {
+                *window = Window {
+                    start: now,
+                    count: 0,
+                };
+            }
+            if window.count >= rule.limit {
+                return Some(Throttle {
+                    rule: (*rule).clone(),
+                    retry_after: rule.window() - (now - window.start),
+                });
+            }
+        }
+        for (index, _) in &rules {
+            if let Some(window) = windows.get_mut(&(*index, tenant.to_string())) {
+                window.count += 1;
+            }
+        }
+        None
+    }
+
+    /// Tenant a request is counted for
+    fn tenant(&self, request: &Request) -> String {
+        if let Some(tenant) = tenant::current_tenant() {
+            return tenant;
+        }
+        match self.kind {
+            TenantKind::GcpProject => tenant::gcp_project(request.uri().path())
+                .unwrap_or_default()
+                .to_string(),
+            TenantKind::AwsAccount => self.kind.default_tenant().to_string(),
+            TenantKind::AzureVault => request
+                .headers()
+                .get(header::HOST)
+                .and_then(|v| v.to_str().ok())
+                .and_then(tenant::azure_vault)
+                .unwrap_or(self.kind.default_tenant())
+                .to_string(),
+        }
+    }
+
+    /// The provider's throttling error
+    fn throttled(&self, tenant: &str, path: &str, throttle: &Throttle) -> Response {
+        let retry_after = throttle.retry_after.as_secs().max(1);
+        let mut response = match self.kind {
+            TenantKind::GcpProject => {
+                let service = if path.contains("/parameters") {
+                    "parametermanager.googleapis.com"
+                } else {
+                    "secretmanager.googleapis.com"
+                };
+                let metric = throttle.rule.operations.metric();
+                let limit = format!(
+                    "{metric} per {} per project",
+                    window_label(throttle.rule.window_seconds)
+                );
+                gcp_error_response_with_details(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    format!(
+                        "Quota exceeded for quota metric '{metric}' and limit '{limit}' of service '{service}' for consumer 'project:{tenant}'."
+                    ),
+                    Some("RESOURCE_EXHAUSTED"),
+                    vec![
+                        gcp_error_info(
+                            "RATE_LIMIT_EXCEEDED",
+                            "googleapis.com",
+                            json!({
+                                "consumer": format!("projects/{tenant}"),
+                                "quota_limit": limit,
+                                "quota_metric": metric,
+                                "service": service
+                            }),
+                        ),
+                        gcp_retry_info(Duration::from_secs(retry_after)),
+                    ],
+                )
+            }
+            // AWS throttles with a 400 and no Retry-After
+            TenantKind::AwsAccount => {
+                return aws_error_response(
+                    StatusCode::BAD_REQUEST,
+                    aws_error_types::THROTTLING,
+                    "Rate exceeded".to_string(),
+                );
+            }
+            TenantKind::AzureVault => azure_error_response(
+                StatusCode::TOO_MANY_REQUESTS,
+                azure_error_codes::THROTTLED,
+                format!(
+                    "Request was not processed because too many requests were received for vault '{tenant}'. Retry after {retry_after} seconds."
+                ),
+            ),
+        };
+        if let Ok(value) = HeaderValue::from_str(&retry_after.to_string()) {
+            response.headers_mut().insert(header::RETRY_AFTER, value);
+        }
+        response
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QuotaInner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// "minute" for 60 seconds, "30 seconds" otherwise
+fn window_label(seconds: u64) -> String {
+    match seconds {
+        60 => "minute".to_string(),
+        3600 => "hour".to_string(),
+        _ => format!("{seconds} seconds"),
+    }
+}
+
+/// Whether a request is counted as a write
+fn is_write(method: &Method, target: Option<&str>) -> bool {
+    match target {
+        Some(target) => {
+            let operation = target.rsplit('.').next().unwrap_or(target);
+            !["Get", "List", "Describe", "BatchGet"]
+                .iter()
+                .any(|prefix| operation.starts_with(prefix))
+        }
+        None => !matches!(*method, Method::GET | Method::HEAD),
+    }
+}
+
+/// Quota middleware
+/// Counts provider requests per tenant and returns the provider's throttling error once a
+/// rule's limit is used up for the current window
+pub async fn quota_middleware(
+    State(quota): State<QuotaState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if crate::is_admin_path(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let tenant = quota.tenant(&request);
+    let target = request
+        .headers()
+        .get("x-amz-target")
+        .and_then(|v| v.to_str().ok());
+    let write = is_write(request.method(), target);
+    if let Some(throttle) = quota.consume(&tenant, write, Instant::now()) {
+        warn!(
+            tenant = %tenant,
+            "Quota: throttling {} {} for {} {} ({} per {}s)",
+            request.method(),
+            request.uri().path(),
+            quota.kind.label(),
+            tenant,
+            throttle.rule.limit,
+            throttle.rule.window_seconds
+        );
+        return quota.throttled(&tenant, request.uri().path(), &throttle);
+    }
+    next.run(request).await
+}
+
+async fn get_quota_config(State(quota): State<QuotaState>) -> Json<QuotaConfig> {
+    Json(quota.config())
+}
+
+async fn put_quota_config(
+    State(quota): State<QuotaState>,
+    Json(config): Json<QuotaConfig>,
+) -> Response {
+    let rules = config.rules.len();
+    match quota.set_config(config) {
+        Ok(()) => {
+            info!("Quota config updated ({} rule(s))", rules);
+            Json(quota.config()).into_response()
+        }
+        Err(message) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": { "code": 400, "message": message } })),
+        )
+            .into_response(),
+    }
+}
+
+async fn delete_quota_config(State(quota): State<QuotaState>) -> StatusCode {
+    // An empty config is always valid
+    let _ = quota.set_config(QuotaConfig::default());
+    info!("Quotas disabled");
+    StatusCode::NO_CONTENT
+}
+
+async fn get_quota_usage(State(quota): State<QuotaState>) -> Json<serde_json::Value> {
+    Json(json!({ "usage": quota.usage() }))
+}
+
+/// Admin routes for the quota config, merged into each mock server's router
+pub fn quota_router(quota: QuotaState) -> Router {
+    Router::new()
+        .route(
+            "/quota/config",
+            get(get_quota_config)
+                .put(put_quota_config)
+                .delete(delete_quota_config),
+        )
+        .route("/quota/usage", get(get_quota_usage))
+        .with_state(quota)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::{any, post};
+    use axum_test::TestServer;
+
+    fn rule(tenant: Option<&str>, operations: QuotaOperations, limit: u32) -> QuotaRule {
+        QuotaRule {
+            tenant: tenant.map(str::to_string),
+            operations,
+            limit,
+            window_seconds: 60,
+        }
+    }
+
+    fn test_server(kind: TenantKind, config: QuotaConfig) -> TestServer {
+        let quota = QuotaState::new(kind, config);
+        let app = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/", post(|| async { "aws" }))
+            .fallback(any(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                quota.clone(),
+                quota_middleware,
+            ))
+            .merge(quota_router(quota));
+        TestServer::new(app).unwrap()
+    }
+
+    #[test]
+    fn test_config_parses_camel_case() {
+        let config: QuotaConfig = serde_json::from_value(json!({
+            "rules": [
+                { "tenant": "project-a", "limit": 10 },
+                { "operations": "all", "limit": 600, "windowSeconds": 3600 }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            config.rules,
+            vec![
+                rule(Some("project-a"), QuotaOperations::Write, 10),
+                QuotaRule {
+                    window_seconds: 3600,
+                    ..rule(None, QuotaOperations::All, 600)
+                }
+            ]
+        );
+        assert!(config.validate().is_ok());
+
+        let config = QuotaConfig {
+            rules: vec![QuotaRule {
+                window_seconds: 0,
+                ..rule(None, QuotaOperations::All, 1)
+            }],
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_is_write() {
+        assert!(is_write(&Method::POST, None));
+        assert!(is_write(&Method::DELETE, None));
+        assert!(!is_write(&Method::GET, None));
+        assert!(is_write(
+            &Method::POST,
+            Some("secretsmanager.PutSecretValue")
+        ));
+        assert!(!is_write(
+            &Method::POST,
+            Some("secretsmanager.GetSecretValue")
+        ));
+        assert!(!is_write(
+            &Method::POST,
+            Some("AmazonSSM.DescribeParameters")
+        ));
+    }
+
+    #[test]
+    fn test_windows_are_per_tenant_and_reset() {
+        let quota = QuotaState::new(
+            TenantKind::GcpProject,
+            QuotaConfig {
+                rules: vec![rule(None, QuotaOperations::Write, 2)],
+            },
+        );
+        let start = Instant::now();
+        assert_eq!(quota.consume("project-a", true, start), None);
+        assert_eq!(quota.consume("project-a", true, start), None);
+        // Reads don't count against a write quota
+        assert_eq!(quota.consume("project-a", false, start), None);
+
+        let throttle = quota.consume("project-a", true, start + Duration::from_secs(15));
+        assert_eq!(
+            throttle.map(|t| t.retry_after),
+            Some(Duration::from_secs(45))
+        );
+        assert_eq!(quota.consume("project-b", true, start), None);
+
+        let next_window = start + Duration::from_secs(60);
+        assert_eq!(quota.consume("project-a", true, next_window), None);
+    }
+
+    #[tokio::test]
+    async fn test_gcp_throttles_with_resource_exhausted() {
+        let server = test_server(
+            TenantKind::GcpProject,
+            QuotaConfig {
+                rules: vec![rule(Some("project-a"), QuotaOperations::Write, 1)],
+            },
+        );
+
+        server
+            .post("/v1/projects/project-a/secrets")
+            .await
+            .assert_status_ok();
+        let response = server.post("/v1/projects/project-a/secrets").await;
+        response.assert_status(StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.header(header::RETRY_AFTER).to_str().is_ok());
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["error"]["status"], "RESOURCE_EXHAUSTED");
+        assert!(
+            body["error"]["message"]
+                .as_str()
+                .is_some_and(|m| m.contains("'Write requests per minute per project'"))
+        );
+
+        // Reads, other projects and health checks are not throttled
+        server
+            .get("/v1/projects/project-a/secrets")
+            .await
+            .assert_status_ok();
+        server
+            .post("/v1/projects/project-b/secrets")
+            .await
+            .assert_status_ok();
+        server.get("/health").await.assert_status_ok();
+
+        let usage: serde_json::Value = server.get("/quota/usage").await.json();
+        assert_eq!(usage["usage"][0]["tenant"], "project-a");
+        assert_eq!(usage["usage"][0]["used"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_aws_throttles_with_throttling_exception() {
+        let server = test_server(
+            TenantKind::AwsAccount,
+            QuotaConfig {
+                rules: vec![rule(None, QuotaOperations::Read, 1)],
+            },
+        );
+
+        for _ in 0..2 {
+            server
+                .post("/")
+                .add_header("x-amz-target", "secretsmanager.PutSecretValue")
+                .await
+                .assert_status_ok();
+        }
+        server
+            .post("/")
+            .add_header("x-amz-target", "secretsmanager.GetSecretValue")
+            .await
+            .assert_status_ok();
+        let response = server
+            .post("/")
+            .add_header("x-amz-target", "secretsmanager.GetSecretValue")
+            .await;
+        response.assert_status_bad_request();
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["__type"], "ThrottlingException");
+        assert_eq!(body["message"], "Rate exceeded");
+
+        // Replacing the config resets the counters
+        server
+            .put("/quota/config")
+            .json(&json!({ "rules": [{ "operations": "read", "limit": 1 }] }))
+            .await
+            .assert_status_ok();
+        server
+            .post("/")
+            .add_header("x-amz-target", "secretsmanager.GetSecretValue")
+            .await
+            .assert_status_ok();
+    }
+}
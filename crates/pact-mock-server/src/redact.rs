@@ -0,0 +1,113 @@
+//! # Redaction
+//!
+//! Masks secret values in request and response bodies before the mock servers log them, so
+//! debug logging can be enabled in shared environments.
+//!
+//! Masked fields (keys compared case-insensitively):
+//! - `payload.data` (GCP Secret Manager and Parameter Manager versions)
+//! - `SecretString`, `SecretBinary` (AWS Secrets Manager)
+//! - `value` (Azure Key Vault, App Configuration; `Parameter.Value` in AWS Parameter Store)
+//!
+//! Only scalar values are masked: Azure list responses keep their `value` array, and the
+//! items in it are redacted in turn. The controller uses the same rules for its provider
+//! clients (`controller::observability::redact`); keep the two field lists in sync.
+
+use serde_json::Value;
+
+/// Replacement for masked values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Fields masked wherever they appear
+const SENSITIVE_FIELDS: &[&str] = &["secretstring", "secretbinary", "value"];
+
+/// Fields masked inside an object of the given key (`payload.data`)
+const SENSITIVE_NESTED_FIELDS: &[(&str, &str)] = &[("payload", "data")];
+
+/// Mask secret values in a JSON document, in place
+pub fn redact_json(value: &mut Value) {
+    redact_value(value, None);
+}
+
+/// Body text with secret values masked, for logging
+/// Bodies that aren't JSON are returned unchanged.
+#[must_use]
+pub fn redact_body(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            redact_json(&mut value);
+            value.to_string()
+        }
+        Err(_) => body.to_string(),
+    }
+}
+
+fn redact_value(value: &mut Value, parent: Option<&str>) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let scalar = !matches!(field, Value::Object(_) | Value::Array(_) | Value::Null);
+                if scalar && is_sensitive(key, parent) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(field, Some(key));
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact_value(item, parent);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_sensitive(key: &str, parent: Option<&str>) -> bool {
+    SENSITIVE_FIELDS
+        .iter()
+        .any(|field| key.eq_ignore_ascii_case(field))
+        || parent.is_some_and(|parent| {
+            SENSITIVE_NESTED_FIELDS.iter().any(|(object, field)| {
+                parent.eq_ignore_ascii_case(object) && key.eq_ignore_ascii_case(field)
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_json_masks_provider_secret_fields() {
+        let mut body = json!({
+            "payload": {"data": "aGVsbG8=", "dataCrc32c": "123"},
+            "data": "not a payload",
+            "SecretString": "s3cr3t",
+            "Parameter": {"Name": "/app/db", "Value": "p@ss"},
+            "value": [{"id": "https://vault/secrets/a", "value": "a"}],
+            "nextLink": null
+        });
+        redact_json(&mut body);
+        assert_eq!(
+            body,
+            json!({
+                "payload": {"data": REDACTED, "dataCrc32c": "123"},
+                "data": "not a payload",
+                "SecretString": REDACTED,
+                "Parameter": {"Name": "/app/db", "Value": REDACTED},
+                "value": [{"id": "https://vault/secrets/a", "value": REDACTED}],
+                "nextLink": null
+            })
+        );
+    }
+
+    #[test]
+    fn test_redact_body_keeps_non_json_text() {
+        assert_eq!(
+            redact_body(r#"{"value":"s3cr3t"}"#),
+            format!(r#"{{"value":"{REDACTED}"}}"#)
+        );
+        assert_eq!(redact_body("upstream timeout"), "upstream timeout");
+    }
+}
@@ -0,0 +1,582 @@
+//! # Scenarios
+//!
+//! Recording and replay of provider call sequences.
+//!
+//! In record mode the mock server appends every request and the response it returned to a
+//! scenario file (JSON), rewriting the file after each exchange so it survives the process
+//! being killed at the end of a test. In replay mode the server answers from the recorded
+//! scenario instead of its handlers: each request must match the next recorded request
+//! (method, path and query, `x-amz-target`, JSON body), so a golden-path test fails as soon as
+//! the reconciler's call sequence changes. Mismatches are answered with 409 Conflict and
+//! reported by the verify endpoint.
+//!
+//! Environment Variables:
+//! - SCENARIO_MODE: `record` or `replay` (default: off)
+//! - SCENARIO_FILE: Scenario file to write (record) or read (replay)
+//! - SCENARIO_MATCH_BODY: Compare request bodies in replay mode (default: true)
+//!
+//! Endpoints:
+//! - `GET /scenario/status`: Mode, position and mismatches
+//! - `GET /scenario/verify`: 200 if the whole scenario was replayed without mismatches, 409 otherwise
+//! - `POST /scenario/reset`: Start over (record: clear the file, replay: rewind)
+
+use anyhow::{Context, Result, anyhow};
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Scenario file format version
+pub const SCENARIO_VERSION: u32 = 1;
+
+/// What the scenario middleware does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScenarioMode {
+    Off,
+    Record,
+    Replay,
+}
+
+/// A recorded request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path including the query string
+    pub path: String,
+    /// AWS operation (`x-amz-target` header)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// JSON body, or the raw body as a string when it isn't JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+impl RecordedRequest {
+    fn describe(&self) -> String {
+        match &self.target {
+            Some(target) => format!("{} {} ({})", self.method, self.path, target),
+            None => format!("{} {}", self.method, self.path),
+        }
+    }
+
+    /// Why `actual` doesn't match this (expected) request, if it doesn't
+    fn mismatch(&self, actual: &RecordedRequest, match_body: bool) -> Option<String> {
+        if self.method != actual.method || self.path != actual.path || self.target != actual.target
+        {
+            return Some(format!(
+                "expected {}, got {}",
+                self.describe(),
+                actual.describe()
+            ));
+        }
+        if match_body && self.body != actual.body {
+            return Some(format!(
+                "{}: request body differs from the recording",
+                self.describe()
+            ));
+        }
+        None
+    }
+}
+
+/// A recorded response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// JSON body, or the raw body as a string when it isn't JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+impl RecordedResponse {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+        let body = match self.body {
+            None => Body::empty(),
+            Some(Value::String(text))
+                if !self
+                    .content_type
+                    .as_deref()
+                    .is_some_and(|ct| ct.contains("json")) =>
+            {
+                Body::from(text)
+            }
+            Some(value) => Body::from(value.to_string()),
+        };
+        let mut response = Response::new(body);
+        *response.status_mut() = status;
+        if let Some(content_type) = self
+            .content_type
+            .and_then(|ct| HeaderValue::from_str(&ct).ok())
+        {
+            response
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, content_type);
+        }
+        response
+    }
+}
+
+/// One request and the response served for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioExchange {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// A recorded call sequence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub version: u32,
+    pub exchanges: Vec<ScenarioExchange>,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            version: SCENARIO_VERSION,
+            exchanges: Vec::new(),
+        }
+    }
+}
+
+impl Scenario {
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read scenario '{}'", path.display()))?;
+        let scenario: Scenario = serde_json::from_str(&content)
+            .with_context(|| format!("Scenario '{}' is not valid", path.display()))?;
+        if scenario.version != SCENARIO_VERSION {
+            return Err(anyhow!(
+                "Scenario '{}' has version {}, expected {}",
+                path.display(),
+                scenario.version,
+                SCENARIO_VERSION
+            ));
+        }
+        Ok(scenario)
+    }
+}
+
+#[derive(Debug, Default)]
+struct ScenarioProgress {
+    scenario: Scenario,
+    /// Next exchange to replay
+    position: usize,
+    mismatches: Vec<String>,
+}
+
+/// Shared scenario state
+#[derive(Debug, Clone)]
+pub struct ScenarioState {
+    mode: ScenarioMode,
+    file: Option<PathBuf>,
+    match_body: bool,
+    progress: Arc<Mutex<ScenarioProgress>>,
+}
+
+impl ScenarioState {
+    /// Scenario state for `mode`
+    /// Replay mode expects `scenario` to be the recording; record mode starts from it
+    #[must_use]
+    pub fn new(mode: ScenarioMode, file: Option<PathBuf>, scenario: Scenario) -> Self {
+        Self {
+            mode,
+            file,
+            match_body: true,
+            progress: Arc::new(Mutex::new(ScenarioProgress {
+                scenario,
+                ..ScenarioProgress::default()
+            })),
+        }
+    }
+
+    /// Scenario state from SCENARIO_MODE / SCENARIO_FILE / SCENARIO_MATCH_BODY
+    pub fn from_env() -> Result<Self> {
+        let mode = match std::env::var("SCENARIO_MODE")
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "" | "off" => return Ok(Self::new(ScenarioMode::Off, None, Scenario::default())),
+            "record" => ScenarioMode::Record,
+            "replay" => ScenarioMode::Replay,
+            other => {
+                return Err(anyhow!(
+                    "SCENARIO_MODE must be 'record' or 'replay', got '{other}'"
+                ));
+            }
+        };
+        let file = std::env::var("SCENARIO_FILE")
+            .map(PathBuf::from)
+            .map_err(|_| anyhow!("SCENARIO_FILE is required when SCENARIO_MODE is set"))?;
+
+        let scenario = if mode == ScenarioMode::Replay {
+            let scenario = Scenario::load(&file)?;
+            info!(
+                "Scenario: replaying {} exchange(s) from {}",
+                scenario.exchanges.len(),
+                file.display()
+            );
+            scenario
+        } else {
+            info!("Scenario: recording to {}", file.display());
+            Scenario::default()
+        };
+
+        let mut state = Self::new(mode, Some(file), scenario);
+        state.match_body = std::env::var("SCENARIO_MATCH_BODY")
+            .map(|v| !matches!(v.trim().to_lowercase().as_str(), "false" | "0" | "no"))
+            .unwrap_or(true);
+        Ok(state)
+    }
+
+    #[must_use]
+    pub fn mode(&self) -> ScenarioMode {
+        self.mode
+    }
+
+    /// The recorded (record mode) or loaded (replay mode) scenario
+    pub async fn scenario(&self) -> Scenario {
+        self.progress.lock().await.scenario.clone()
+    }
+
+    async fn record(&self, exchange: ScenarioExchange) {
+        let mut progress = self.progress.lock().await;
+        progress.scenario.exchanges.push(exchange);
+        self.save(&progress.scenario).await;
+    }
+
+    /// Next recorded response, or a description of why the request doesn't match
+    async fn replay(&self, request: &RecordedRequest) -> Result<RecordedResponse, String> {
+        let mut progress = self.progress.lock().await;
+        let position = progress.position;
+        let result = match progress.scenario.exchanges.get(position) {
+            None => Err(format!(
+                "unexpected request {} after the end of the scenario ({} exchange(s))",
+                request.describe(),
+                progress.scenario.exchanges.len()
+            )),
+            Some(exchange) => match exchange.request.mismatch(request, self.match_body) {
+                Some(reason) => Err(format!("exchange {}: {reason}", position + 1)),
+                None => Ok(exchange.response.clone()),
+            },
+        };
+        match &result {
+            Ok(_) => progress.position += 1,
+            Err(reason) => progress.mismatches.push(reason.clone()),
+        }
+        result
+    }
+
+    async fn save(&self, scenario: &Scenario) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        let content = match serde_json::to_string_pretty(scenario) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Scenario: failed to serialize recording: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = tokio::fs::write(file, content).await {
+            warn!(
+                "Scenario: failed to write recording to {}: {}",
+                file.display(),
+                e
+            );
+        }
+    }
+
+    async fn status(&self) -> Value {
+        let progress = self.progress.lock().await;
+        let total = progress.scenario.exchanges.len();
+        json!({
+            "mode": self.mode,
+            "file": self.file,
+            "exchanges": total,
+            "position": progress.position,
+            "mismatches": progress.mismatches,
+            "complete": self.mode == ScenarioMode::Replay
+                && progress.position == total
+                && progress.mismatches.is_empty(),
+        })
+    }
+
+    async fn reset(&self) {
+        let mut progress = self.progress.lock().await;
+        progress.position = 0;
+        progress.mismatches.clear();
+        if self.mode == ScenarioMode::Record {
+            progress.scenario = Scenario::default();
+            self.save(&progress.scenario).await;
+        }
+    }
+}
+
+/// Body as JSON, or as a string when it isn't JSON
+fn body_value(bytes: &[u8]) -> Option<Value> {
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(
+        serde_json::from_slice(bytes)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned())),
+    )
+}
+
+/// Scenario middleware
+/// Record mode captures each exchange; replay mode answers from the recording
+pub async fn scenario_middleware(
+    State(scenario): State<ScenarioState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if scenario.mode == ScenarioMode::Off
+        || crate::is_admin_path(request.method(), request.uri().path())
+    {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Scenario: failed to read request body: {}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+    let recorded_request = RecordedRequest {
+        method: parts.method.to_string(),
+        path: parts
+            .uri
+            .path_and_query()
+            .map_or_else(|| parts.uri.path().to_string(), ToString::to_string),
+        target: parts
+            .headers
+            .get("x-amz-target")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        body: body_value(&bytes),
+    };
+
+    if scenario.mode == ScenarioMode::Replay {
+        return match scenario.replay(&recorded_request).await {
+            Ok(response) => response.into_response(),
+            Err(reason) => {
+                warn!("Scenario: {}", reason);
+                (
+                    StatusCode::CONFLICT,
+                    Json(json!({
+                        "error": {
+                            "code": 409,
+                            "message": format!("Scenario mismatch: {reason}")
+                        }
+                    })),
+                )
+                    .into_response()
+            }
+        };
+    }
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+    let (parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Scenario: failed to read response body: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    scenario
+        .record(ScenarioExchange {
+            request: recorded_request,
+            response: RecordedResponse {
+                status: parts.status.as_u16(),
+                content_type: parts
+                    .headers
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string),
+                body: body_value(&bytes),
+            },
+        })
+        .await;
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+async fn scenario_status(State(scenario): State<ScenarioState>) -> Json<Value> {
+    Json(scenario.status().await)
+}
+
+async fn scenario_verify(State(scenario): State<ScenarioState>) -> Response {
+    let status = scenario.status().await;
+    let code = if status["complete"] == json!(true) {
+        StatusCode::OK
+    } else {
+        StatusCode::CONFLICT
+    };
+    (code, Json(status)).into_response()
+}
+
+async fn scenario_reset(State(scenario): State<ScenarioState>) -> Json<Value> {
+    scenario.reset().await;
+    info!("Scenario reset");
+    Json(scenario.status().await)
+}
+
+/// Admin routes for scenarios, merged into each mock server's router
+pub fn scenario_router(scenario: ScenarioState) -> Router {
+    Router::new()
+        .route("/scenario/status", get(scenario_status))
+        .route("/scenario/verify", get(scenario_verify))
+        .route("/scenario/reset", post(scenario_reset))
+        .with_state(scenario)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum_test::TestServer;
+
+    fn test_server(scenario: ScenarioState) -> TestServer {
+        let app = Router::new()
+            .route(
+                "/v1/secrets",
+                post(|Json(body): Json<Value>| async move {
+                    (
+                        StatusCode::CREATED,
+                        Json(json!({ "created": body["name"] })),
+                    )
+                }),
+            )
+            .route("/v1/secrets/{name}", get(|| async { "plain text" }))
+            .layer(axum::middleware::from_fn_with_state(
+                scenario.clone(),
+                scenario_middleware,
+            ))
+            .merge(scenario_router(scenario));
+        TestServer::new(app).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let recorder = ScenarioState::new(ScenarioMode::Record, None, Scenario::default());
+        let server = test_server(recorder.clone());
+        server
+            .post("/v1/secrets")
+            .json(&json!({ "name": "db" }))
+            .await
+            .assert_status(StatusCode::CREATED);
+        server
+            .get("/v1/secrets/db?view=full")
+            .await
+            .assert_text("plain text");
+
+        let recording = recorder.scenario().await;
+        assert_eq!(recording.exchanges.len(), 2);
+        assert_eq!(
+            recording.exchanges[1].request.path,
+            "/v1/secrets/db?view=full"
+        );
+        assert_eq!(
+            recording.exchanges[1].response.body,
+            Some(Value::String("plain text".to_string()))
+        );
+
+        // Survives a round trip through the file format
+        let recording: Scenario =
+            serde_json::from_str(&serde_json::to_string(&recording).unwrap()).unwrap();
+
+        let replay = test_server(ScenarioState::new(ScenarioMode::Replay, None, recording));
+        let response = replay
+            .post("/v1/secrets")
+            .json(&json!({ "name": "db" }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        response.assert_json(&json!({ "created": "db" }));
+        replay
+            .get("/scenario/verify")
+            .await
+            .assert_status(StatusCode::CONFLICT);
+        replay
+            .get("/v1/secrets/db?view=full")
+            .await
+            .assert_text("plain text");
+        replay.get("/scenario/verify").await.assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_replay_rejects_out_of_order_requests() {
+        let scenario = Scenario {
+            version: SCENARIO_VERSION,
+            exchanges: vec![ScenarioExchange {
+                request: RecordedRequest {
+                    method: "POST".to_string(),
+                    path: "/v1/secrets".to_string(),
+                    target: None,
+                    body: Some(json!({ "name": "db" })),
+                },
+                response: RecordedResponse {
+                    status: 201,
+                    content_type: Some("application/json".to_string()),
+                    body: Some(json!({})),
+                },
+            }],
+        };
+        let server = test_server(ScenarioState::new(ScenarioMode::Replay, None, scenario));
+
+        server
+            .get("/v1/secrets/db")
+            .await
+            .assert_status(StatusCode::CONFLICT);
+        server
+            .post("/v1/secrets")
+            .json(&json!({ "name": "other" }))
+            .await
+            .assert_status(StatusCode::CONFLICT);
+        server
+            .post("/v1/secrets")
+            .json(&json!({ "name": "db" }))
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        // Mismatches are remembered even though the scenario was completed
+        let verify = server.get("/scenario/verify").await;
+        verify.assert_status(StatusCode::CONFLICT);
+        assert_eq!(
+            verify.json::<Value>()["mismatches"]
+                .as_array()
+                .map(Vec::len),
+            Some(2)
+        );
+
+        server.post("/scenario/reset").await.assert_status_ok();
+        server
+            .post("/v1/secrets")
+            .json(&json!({ "name": "db" }))
+            .await
+            .assert_status(StatusCode::CREATED);
+        server.get("/scenario/verify").await.assert_status_ok();
+    }
+}
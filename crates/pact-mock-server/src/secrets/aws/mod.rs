@@ -5,15 +5,30 @@
 //! - Staging labels (AWSCURRENT, AWSPREVIOUS)
 //! - Secret key format: secret name (no path prefix)
 
+pub mod parameter_store;
+
+pub use parameter_store::AwsParameterStore;
+
 use super::common::{
-    SecretStore, SecretStoreBackend, SecretStoreEnum, SecretVersion, db_store::DbSecretStore,
+    SecretEntry, SecretStore, SecretStoreBackend, SecretStoreEnum, SecretVersion,
+    db_store::DbSecretStore,
 };
+use crate::admin::AdminStore;
+use crate::tenant;
 use anyhow::Result;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Account used in ARNs outside tenant isolation (see `crate::tenant`)
+pub const DEFAULT_ACCOUNT_ID: &str = "123456789012";
+
+/// Account of the request being handled
+pub fn account_id() -> String {
+    tenant::current_tenant().unwrap_or_else(|| DEFAULT_ACCOUNT_ID.to_string())
+}
+
 /// AWS staging labels
 pub const AWS_CURRENT: &str = "AWSCURRENT";
 pub const AWS_PREVIOUS: &str = "AWSPREVIOUS";
@@ -88,6 +103,7 @@ impl AwsSecretStore {
         version_data: Value,
         version_id: Option<String>,
     ) -> Result<String> {
+        let secret_name = &tenant::scoped_key(secret_name);
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -139,6 +155,7 @@ impl AwsSecretStore {
         secret_name: &str,
         label: &str,
     ) -> Option<SecretVersion> {
+        let secret_name = &tenant::scoped_key(secret_name);
         let labels = self.staging_labels.read().await;
         if let Some(secret_labels) = labels.get(secret_name) {
             if let Some(version_id) = secret_labels.get(label) {
@@ -165,16 +182,19 @@ impl AwsSecretStore {
 
     /// Get a specific version by version ID
     pub async fn get_version(&self, secret_name: &str, version_id: &str) -> Option<SecretVersion> {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.get_version(secret_name, version_id).await
     }
 
     /// List all versions of a secret
     pub async fn list_versions(&self, secret_name: &str) -> Option<Vec<SecretVersion>> {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.list_versions(secret_name).await
     }
 
     /// Get staging labels for a secret
     pub async fn get_staging_labels(&self, secret_name: &str) -> Option<HashMap<String, String>> {
+        let secret_name = &tenant::scoped_key(secret_name);
         let labels = self.staging_labels.read().await;
         labels.get(secret_name).cloned()
     }
@@ -188,6 +208,7 @@ impl AwsSecretStore {
         remove_from_version_id: Option<&str>,
         move_to_version_id: &str,
     ) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         // Verify both versions exist
         if let Some(remove_vid) = remove_from_version_id {
             if self
@@ -241,11 +262,13 @@ impl AwsSecretStore {
 
     /// Get secret metadata
     pub async fn get_metadata(&self, secret_name: &str) -> Option<Value> {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.get_metadata(secret_name).await
     }
 
     /// Delete a secret (all versions and labels)
     pub async fn delete_secret(&self, secret_name: &str) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         let deleted = self.store.delete_secret(secret_name).await;
         if deleted {
             let mut labels = self.staging_labels.write().await;
@@ -256,12 +279,18 @@ impl AwsSecretStore {
 
     /// Check if a secret exists
     pub async fn exists(&self, secret_name: &str) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.exists(secret_name).await
     }
 
     /// List all secret names
     pub async fn list_all_secrets(&self) -> Vec<String> {
-        self.store.list_all_keys().await
+        self.store
+            .list_all_keys()
+            .await
+            .into_iter()
+            .filter_map(|key| tenant::visible_name(&key))
+            .collect()
     }
 
     /// Delete a secret (marks for deletion, can be restored)
@@ -272,6 +301,7 @@ impl AwsSecretStore {
         secret_name: &str,
         _recovery_window_days: Option<u32>,
     ) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         // Mark secret as disabled (deleted) but keep it for recovery
         // TODO: Store recovery_window_days and implement automatic cleanup
         self.store.disable_secret(secret_name).await
@@ -279,80 +309,44 @@ impl AwsSecretStore {
 
     /// Restore a deleted secret
     pub async fn restore_secret(&self, secret_name: &str) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.enable_secret(secret_name).await
     }
 
     /// Check if a secret is deleted (disabled)
     pub async fn is_deleted(&self, secret_name: &str) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         !self.store.is_enabled(secret_name).await
     }
 
     /// List unique environments for secrets using database function
     /// Returns empty vec if database is not available
     pub async fn list_environments(&self, resource_type: &str) -> Vec<String> {
-        // Try to get DATABASE_URL from environment
-        if let Ok(database_url) = std::env::var("DATABASE_URL") {
-            if let Ok(db) = sea_orm::Database::connect(&database_url).await {
-                use sea_orm::ConnectionTrait;
-                let function_name = if resource_type == "secrets" {
-                    "aws.get_secret_environments"
-                } else {
-                    "aws.get_parameter_environments"
-                };
-
-                let stmt = sea_orm::Statement::from_string(
-                    sea_orm::DatabaseBackend::Postgres,
-                    format!("SELECT * FROM {}()", function_name),
-                );
-
-                if let Ok(rows) = db.query_all(stmt).await {
-                    return rows
-                        .into_iter()
-                        .filter_map(|row| {
-                            // Get the environment column value by column name
-                            row.try_get::<Option<String>>("", "environment")
-                                .or_else(|_| row.try_get::<String>("", "environment").map(Some))
-                                .ok()
-                                .flatten()
-                        })
-                        .collect();
-                }
-            }
-        }
-        vec![]
+        self.list_filter_values(resource_type, "environments", "environment")
+            .await
     }
 
     /// List unique locations for secrets using database function
     /// Returns empty vec if database is not available
     pub async fn list_locations(&self, resource_type: &str) -> Vec<String> {
-        // Try to get DATABASE_URL from environment
-        if let Ok(database_url) = std::env::var("DATABASE_URL") {
-            if let Ok(db) = sea_orm::Database::connect(&database_url).await {
-                use sea_orm::ConnectionTrait;
-                let function_name = if resource_type == "secrets" {
-                    "aws.get_secret_locations"
-                } else {
-                    "aws.get_parameter_locations"
-                };
-
-                let stmt = sea_orm::Statement::from_string(
-                    sea_orm::DatabaseBackend::Postgres,
-                    format!("SELECT * FROM {}()", function_name),
-                );
+        self.list_filter_values(resource_type, "locations", "location")
+            .await
+    }
 
-                if let Ok(rows) = db.query_all(stmt).await {
-                    return rows
-                        .into_iter()
-                        .filter_map(|row| {
-                            // Get the location column value by column name
-                            row.try_get::<Option<String>>("", "location")
-                                .or_else(|_| row.try_get::<String>("", "location").map(Some))
-                                .ok()
-                                .flatten()
-                        })
-                        .collect();
-                }
-            }
+    /// Call `aws.get_{secret,parameter}_{kind}()` on the database store's connection
+    async fn list_filter_values(
+        &self,
+        resource_type: &str,
+        kind: &str,
+        column: &str,
+    ) -> Vec<String> {
+        if let SecretStoreEnum::Database(db_store) = self.store.as_ref() {
+            let function = if resource_type == "secrets" {
+                format!("get_secret_{}", kind)
+            } else {
+                format!("get_parameter_{}", kind)
+            };
+            return db_store.list_filter_values(&function, column, vec![]).await;
         }
         vec![]
     }
@@ -361,31 +355,33 @@ impl AwsSecretStore {
     /// AWS doesn't have projects like GCP, but we can extract account IDs from ARNs
     /// Returns empty vec if database is not available
     pub async fn list_all_projects(&self) -> Vec<String> {
-        // Try to use database function first
-        if let Ok(database_url) = std::env::var("DATABASE_URL") {
-            if let Ok(db) = sea_orm::Database::connect(&database_url).await {
-                use sea_orm::ConnectionTrait;
-                let stmt = sea_orm::Statement::from_string(
-                    sea_orm::DatabaseBackend::Postgres,
-                    "SELECT DISTINCT (metadata->>'ARN')::text as arn FROM aws.secrets WHERE metadata->>'ARN' IS NOT NULL".to_string(),
-                );
-
-                if let Ok(rows) = db.query_all(stmt).await {
-                    let mut accounts = std::collections::HashSet::new();
-                    for row in rows {
-                        if let Ok(Some(arn)) = row.try_get::<Option<String>>("", "arn") {
-                            // Extract account ID from ARN: arn:aws:secretsmanager:region:account-id:secret:name
-                            let parts: Vec<&str> = arn.split(':').collect();
-                            if parts.len() >= 5 {
-                                accounts.insert(parts[4].to_string());
-                            }
+        // Try to use the database store's connection first
+        if let SecretStoreEnum::Database(db_store) = self.store.as_ref() {
+            let sql = match db_store.backend() {
+                sea_orm::DatabaseBackend::Sqlite => {
+                    "SELECT DISTINCT json_extract(metadata, '$.ARN') as arn FROM aws.secrets WHERE json_extract(metadata, '$.ARN') IS NOT NULL"
+                }
+                _ => {
+                    "SELECT DISTINCT (metadata->>'ARN')::text as arn FROM aws.secrets WHERE metadata->>'ARN' IS NOT NULL"
+                }
+            };
+            let stmt = sea_orm::Statement::from_string(db_store.backend(), sql.to_string());
+
+            if let Ok(rows) = db_store.query_all(stmt).await {
+                let mut accounts = std::collections::HashSet::new();
+                for row in rows {
+                    if let Ok(Some(arn)) = row.try_get::<Option<String>>("", "arn") {
+                        // Extract account ID from ARN: arn:aws:secretsmanager:region:account-id:secret:name
+                        let parts: Vec<&str> = arn.split(':').collect();
+                        if parts.len() >= 5 {
+                            accounts.insert(parts[4].to_string());
                         }
                     }
-                    let mut account_list: Vec<String> = accounts.into_iter().collect();
-                    account_list.sort();
-                    if !account_list.is_empty() {
-                        return account_list;
-                    }
+                }
+                let mut account_list: Vec<String> = accounts.into_iter().collect();
+                account_list.sort();
+                if !account_list.is_empty() {
+                    return account_list;
                 }
             }
         }
@@ -411,3 +407,43 @@ impl AwsSecretStore {
 }
 
 // Note: Cannot implement Default for async new() - use AwsSecretStore::new().await instead
+
+#[async_trait::async_trait]
+impl AdminStore for AwsSecretStore {
+    async fn snapshot(&self) -> BTreeMap<String, SecretEntry> {
+        AdminStore::snapshot(self.store.as_ref()).await
+    }
+
+    /// Seeded secrets get AWSCURRENT on their last version and AWSPREVIOUS on the one before
+    async fn seed(&self, entries: BTreeMap<String, SecretEntry>) -> Result<()> {
+        let mut seeded_labels = HashMap::new();
+        for (name, entry) in &entries {
+            let mut versions = entry.versions.iter().rev();
+            let mut secret_labels = HashMap::new();
+            if let Some(current) = versions.next() {
+                secret_labels.insert(AWS_CURRENT.to_string(), current.version_id.clone());
+            }
+            if let Some(previous) = versions.next() {
+                secret_labels.insert(AWS_PREVIOUS.to_string(), previous.version_id.clone());
+            }
+            seeded_labels.insert(name.clone(), secret_labels);
+        }
+        AdminStore::seed(self.store.as_ref(), entries).await?;
+        self.staging_labels.write().await.extend(seeded_labels);
+        Ok(())
+    }
+
+    async fn reset(&self) {
+        AdminStore::reset(self.store.as_ref()).await;
+        self.staging_labels.write().await.clear();
+    }
+
+    async fn labels(&self) -> BTreeMap<String, BTreeMap<String, String>> {
+        self.staging_labels
+            .read()
+            .await
+            .iter()
+            .map(|(name, labels)| (name.clone(), labels.clone().into_iter().collect()))
+            .collect()
+    }
+}
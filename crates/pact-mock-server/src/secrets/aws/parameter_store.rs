@@ -0,0 +1,812 @@
+//! AWS Systems Manager Parameter Store implementation
+//!
+//! Wraps the common SecretStore with Parameter Store-specific behavior:
+//! - Sequential version numbers (1, 2, 3, ...), one per PutParameter
+//! - Parameter key format: full parameter name (e.g. "/my-service/dev/database_url")
+//! - Hierarchy APIs: GetParametersByPath (recursive or one level), DescribeParameters with
+//!   filters, DeleteParameters batches, all paginated with NextToken
+//!
+//! Requests are POST "/" with `x-amz-target: AmazonSSM.<Operation>`, like Secrets Manager.
+
+use super::super::common::{SecretEntry, SecretStore};
+use crate::admin::AdminStore;
+use crate::secrets::common::errors::aws_error_response;
+use crate::tenant;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use base64::Engine;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+
+/// Parameter Store operations (`x-amz-target` values)
+pub mod operations {
+    pub const PREFIX: &str = "AmazonSSM.";
+    pub const PUT_PARAMETER: &str = "AmazonSSM.PutParameter";
+    pub const GET_PARAMETER: &str = "AmazonSSM.GetParameter";
+    pub const GET_PARAMETERS: &str = "AmazonSSM.GetParameters";
+    pub const GET_PARAMETERS_BY_PATH: &str = "AmazonSSM.GetParametersByPath";
+    pub const DESCRIBE_PARAMETERS: &str = "AmazonSSM.DescribeParameters";
+    pub const DELETE_PARAMETER: &str = "AmazonSSM.DeleteParameter";
+    pub const DELETE_PARAMETERS: &str = "AmazonSSM.DeleteParameters";
+}
+
+/// Parameter Store error types
+pub mod ssm_error_types {
+    pub const PARAMETER_NOT_FOUND: &str = "ParameterNotFound";
+    pub const PARAMETER_ALREADY_EXISTS: &str = "ParameterAlreadyExists";
+    pub const INVALID_NEXT_TOKEN: &str = "InvalidNextToken";
+    pub const INVALID_FILTER_KEY: &str = "InvalidFilterKey";
+    pub const INVALID_FILTER_OPTION: &str = "InvalidFilterOption";
+    pub const VALIDATION: &str = "ValidationException";
+}
+
+/// Page size limits (default = max)
+const MAX_RESULTS_BY_PATH: usize = 10;
+const MAX_RESULTS_DESCRIBE: usize = 50;
+/// DeleteParameters / GetParameters accept at most 10 names
+const MAX_NAMES_PER_BATCH: usize = 10;
+
+const REGION: &str = "us-east-1";
+
+/// A parameter's current state
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterRecord {
+    pub name: String,
+    pub value: String,
+    pub parameter_type: String,
+    pub description: Option<String>,
+    pub tier: String,
+    pub data_type: String,
+    pub version: u64,
+    pub last_modified: u64,
+}
+
+impl ParameterRecord {
+    fn from_entry(name: &str, entry: &SecretEntry) -> Option<Self> {
+        let latest = entry.versions.last()?;
+        let metadata = &entry.metadata;
+        let text = |key: &str, default: &str| {
+            metadata
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or(default)
+                .to_string()
+        };
+        Some(Self {
+            name: name.to_string(),
+            value: latest
+                .data
+                .get("Value")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            parameter_type: text("Type", "String"),
+            description: metadata
+                .get("Description")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            tier: text("Tier", "Standard"),
+            data_type: text("DataType", "text"),
+            version: latest.version_id.parse().unwrap_or(1),
+            last_modified: latest.created_at,
+        })
+    }
+
+    fn arn(&self) -> String {
+        format!(
+            "arn:aws:ssm:{REGION}:{}:parameter/{}",
+            super::account_id(),
+            self.name.trim_start_matches('/')
+        )
+    }
+
+    /// `Parameter` shape (GetParameter, GetParametersByPath)
+    fn to_parameter(&self) -> Value {
+        json!({
+            "Name": self.name,
+            "Type": self.parameter_type,
+            "Value": self.value,
+            "Version": self.version,
+            "LastModifiedDate": self.last_modified,
+            "ARN": self.arn(),
+            "DataType": self.data_type,
+        })
+    }
+
+    /// `ParameterMetadata` shape (DescribeParameters), without the value
+    fn to_metadata(&self) -> Value {
+        let mut metadata = json!({
+            "Name": self.name,
+            "ARN": self.arn(),
+            "Type": self.parameter_type,
+            "Version": self.version,
+            "LastModifiedDate": self.last_modified,
+            "LastModifiedUser": format!("arn:aws:iam::{}:user/mock", super::account_id()),
+            "Tier": self.tier,
+            "DataType": self.data_type,
+            "Policies": [],
+        });
+        if let Some(description) = &self.description {
+            metadata["Description"] = json!(description);
+        }
+        metadata
+    }
+}
+
+/// AWS Parameter Store wrapper
+#[derive(Clone, Debug)]
+pub struct AwsParameterStore {
+    store: SecretStore,
+}
+
+impl Default for AwsParameterStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AwsParameterStore {
+    pub fn new() -> Self {
+        Self {
+            store: SecretStore::new(),
+        }
+    }
+
+    /// Create or overwrite a parameter
+    /// Returns the new version, or None if the parameter exists and `overwrite` is false
+    pub async fn put(
+        &self,
+        name: &str,
+        value: &str,
+        metadata: Value,
+        overwrite: bool,
+    ) -> Option<u64> {
+        let name = &tenant::scoped_key(name);
+        if self.store.exists(name).await && !overwrite {
+            return None;
+        }
+        let version_id = self
+            .store
+            .add_version(
+                name.to_string(),
+                json!({ "Value": value }),
+                None,
+                |store, key| {
+                    (store.get(key).map_or(0, |entry| entry.versions.len()) + 1).to_string()
+                },
+            )
+            .await;
+        // Keep existing metadata fields the request didn't set
+        let mut merged = self.store.get_metadata(name).await.unwrap_or(json!({}));
+        if let (Some(merged), Some(metadata)) = (merged.as_object_mut(), metadata.as_object()) {
+            for (key, value) in metadata {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+        self.store.update_metadata(name.to_string(), merged).await;
+        version_id.parse().ok()
+    }
+
+    /// Current state of a parameter
+    pub async fn get(&self, name: &str) -> Option<ParameterRecord> {
+        self.all()
+            .await
+            .into_iter()
+            .find(|parameter| parameter.name == name)
+    }
+
+    /// Delete a parameter, returning whether it existed
+    pub async fn delete(&self, name: &str) -> bool {
+        self.store.delete_secret(&tenant::scoped_key(name)).await
+    }
+
+    /// All parameters, sorted by name (stable order for pagination)
+    pub async fn all(&self) -> Vec<ParameterRecord> {
+        let snapshot: HashMap<String, SecretEntry> = self.store.snapshot().await;
+        let mut parameters: Vec<ParameterRecord> = snapshot
+            .iter()
+            .filter_map(|(key, entry)| {
+                ParameterRecord::from_entry(&tenant::visible_name(key)?, entry)
+            })
+            .collect();
+        parameters.sort_by(|a, b| a.name.cmp(&b.name));
+        parameters
+    }
+
+    /// Parameters under `path`, directly (one level) or at any depth (recursive)
+    pub async fn by_path(&self, path: &str, recursive: bool) -> Vec<ParameterRecord> {
+        self.all()
+            .await
+            .into_iter()
+            .filter(|parameter| is_under_path(&parameter.name, path, recursive))
+            .collect()
+    }
+}
+
+/// Whether `name` is below `path` (one level unless `recursive`)
+fn is_under_path(name: &str, path: &str, recursive: bool) -> bool {
+    let prefix = if path.ends_with('/') {
+        path.to_string()
+    } else {
+        format!("{path}/")
+    };
+    name.strip_prefix(&prefix)
+        .is_some_and(|rest| !rest.is_empty() && (recursive || !rest.contains('/')))
+}
+
+/// Opaque pagination token (base64 of the next offset)
+fn encode_next_token(offset: usize) -> String {
+    base64::engine::general_purpose::STANDARD.encode(format!("offset:{offset}"))
+}
+
+fn decode_next_token(token: &str) -> Option<usize> {
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(token)
+        .ok()?;
+    String::from_utf8(decoded)
+        .ok()?
+        .strip_prefix("offset:")?
+        .parse()
+        .ok()
+}
+
+/// One page of `items` starting at the request's NextToken
+fn paginate<T>(
+    items: Vec<T>,
+    body: &Value,
+    max_results_limit: usize,
+) -> Result<(Vec<T>, Option<String>), Response> {
+    let max_results = match body.get("MaxResults").and_then(Value::as_u64) {
+        None => max_results_limit,
+        Some(n) if (1..=max_results_limit as u64).contains(&n) => {
+            usize::try_from(n).unwrap_or(max_results_limit)
+        }
+        Some(n) => {
+            return Err(ssm_error(
+                ssm_error_types::VALIDATION,
+                format!(
+                    "1 validation error detected: Value '{n}' at 'maxResults' failed to satisfy constraint: \
+                     Member must have value less than or equal to {max_results_limit}"
+                ),
+            ));
+        }
+    };
+    let offset = match body.get("NextToken").and_then(Value::as_str) {
+        None | Some("") => 0,
+        Some(token) => match decode_next_token(token) {
+            Some(offset) if offset <= items.len() => offset,
+            _ => {
+                return Err(ssm_error(
+                    ssm_error_types::INVALID_NEXT_TOKEN,
+                    "The specified token isn't valid.".to_string(),
+                ));
+            }
+        },
+    };
+    let end = (offset + max_results).min(items.len());
+    let next_token = (end < items.len()).then(|| encode_next_token(end));
+    let page = items.into_iter().skip(offset).take(end - offset).collect();
+    Ok((page, next_token))
+}
+
+fn ssm_error(error_type: &str, message: String) -> Response {
+    aws_error_response(StatusCode::BAD_REQUEST, error_type, message)
+}
+
+fn required_str<'a>(body: &'a Value, field: &str) -> Result<&'a str, Response> {
+    body.get(field)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| {
+            ssm_error(
+                ssm_error_types::VALIDATION,
+                format!("1 validation error detected: Value null at '{field}' failed to satisfy constraint: Member must not be null"),
+            )
+        })
+}
+
+fn names(body: &Value) -> Result<Vec<String>, Response> {
+    let names: Vec<String> = body
+        .get("Names")
+        .and_then(Value::as_array)
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    if names.is_empty() || names.len() > MAX_NAMES_PER_BATCH {
+        return Err(ssm_error(
+            ssm_error_types::VALIDATION,
+            format!(
+                "1 validation error detected: Value at 'names' failed to satisfy constraint: \
+                 Member must have length between 1 and {MAX_NAMES_PER_BATCH}"
+            ),
+        ));
+    }
+    Ok(names)
+}
+
+/// A DescribeParameters filter (`Filters` or `ParameterFilters`)
+struct DescribeFilter {
+    key: String,
+    option: String,
+    values: Vec<String>,
+}
+
+impl DescribeFilter {
+    fn parse(body: &Value) -> Result<Vec<Self>, Response> {
+        let mut filters = vec![];
+        // Legacy `Filters` (Name, Type, KeyId) match by prefix for Name and exactly otherwise
+        for filter in body
+            .get("Filters")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let key = filter
+                .get("Key")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if !matches!(key, "Name" | "Type" | "KeyId") {
+                return Err(ssm_error(
+                    ssm_error_types::INVALID_FILTER_KEY,
+                    format!("The filter key '{key}' isn't valid."),
+                ));
+            }
+            filters.push(Self {
+                key: key.to_string(),
+                option: if key == "Name" {
+                    "BeginsWith"
+                } else {
+                    "Equals"
+                }
+                .to_string(),
+                values: Self::values(filter),
+            });
+        }
+        for filter in body
+            .get("ParameterFilters")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let key = filter
+                .get("Key")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let option = filter.get("Option").and_then(Value::as_str);
+            let valid_options: &[&str] = match key {
+                "Name" => &["Equals", "BeginsWith", "Contains"],
+                "Path" => &["Recursive", "OneLevel"],
+                "Type" | "KeyId" | "Tier" | "DataType" => &["Equals", "BeginsWith"],
+                _ => {
+                    return Err(ssm_error(
+                        ssm_error_types::INVALID_FILTER_KEY,
+                        format!("The filter key '{key}' isn't valid."),
+                    ));
+                }
+            };
+            let option = option.unwrap_or(valid_options[0]);
+            if !valid_options.contains(&option) {
+                return Err(ssm_error(
+                    ssm_error_types::INVALID_FILTER_OPTION,
+                    format!(
+                        "The filter option '{option}' isn't valid for key '{key}'. Valid options: {}",
+                        valid_options.join(", ")
+                    ),
+                ));
+            }
+            filters.push(Self {
+                key: key.to_string(),
+                option: option.to_string(),
+                values: Self::values(filter),
+            });
+        }
+        Ok(filters)
+    }
+
+    fn values(filter: &Value) -> Vec<String> {
+        filter
+            .get("Values")
+            .and_then(Value::as_array)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// A filter matches if any of its values matches
+    fn matches(&self, parameter: &ParameterRecord) -> bool {
+        let field = match self.key.as_str() {
+            "Name" => parameter.name.as_str(),
+            "Type" => parameter.parameter_type.as_str(),
+            "Tier" => parameter.tier.as_str(),
+            "DataType" => parameter.data_type.as_str(),
+            "Path" => {
+                let recursive = self.option == "Recursive";
+                return self
+                    .values
+                    .iter()
+                    .any(|path| is_under_path(&parameter.name, path, recursive));
+            }
+            // Mock parameters aren't encrypted with customer keys
+            _ => "alias/aws/ssm",
+        };
+        self.values.iter().any(|value| match self.option.as_str() {
+            "BeginsWith" => field.starts_with(value.as_str()),
+            "Contains" => field.contains(value.as_str()),
+            _ => field == value,
+        })
+    }
+}
+
+/// Handle a Parameter Store operation
+/// Returns None if `target` isn't a Parameter Store operation
+pub async fn handle_parameter_store_operation(
+    store: &AwsParameterStore,
+    target: &str,
+    body: &Value,
+) -> Option<Response> {
+    if !target.starts_with(operations::PREFIX) {
+        return None;
+    }
+    let response = match target {
+        operations::PUT_PARAMETER => put_parameter(store, body).await,
+        operations::GET_PARAMETER => get_parameter(store, body).await,
+        operations::GET_PARAMETERS => get_parameters(store, body).await,
+        operations::GET_PARAMETERS_BY_PATH => get_parameters_by_path(store, body).await,
+        operations::DESCRIBE_PARAMETERS => describe_parameters(store, body).await,
+        operations::DELETE_PARAMETER => delete_parameter(store, body).await,
+        operations::DELETE_PARAMETERS => delete_parameters(store, body).await,
+        _ => Err(aws_error_response(
+            StatusCode::BAD_REQUEST,
+            "UnknownOperationException",
+            format!("Unsupported Parameter Store operation: {target}"),
+        )),
+    };
+    Some(response.unwrap_or_else(|error| error))
+}
+
+async fn put_parameter(store: &AwsParameterStore, body: &Value) -> Result<Response, Response> {
+    let name = required_str(body, "Name")?;
+    let value = required_str(body, "Value")?;
+    let overwrite = body
+        .get("Overwrite")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let mut metadata = json!({});
+    for field in ["Type", "Description", "Tier", "DataType"] {
+        if let Some(value) = body.get(field).and_then(Value::as_str) {
+            metadata[field] = json!(value);
+        }
+    }
+    let Some(version) = store.put(name, value, metadata, overwrite).await else {
+        return Err(ssm_error(
+            ssm_error_types::PARAMETER_ALREADY_EXISTS,
+            "The parameter already exists. To overwrite this value, set the overwrite option in the request to true.".to_string(),
+        ));
+    };
+    let tier = store
+        .get(name)
+        .await
+        .map_or_else(|| "Standard".to_string(), |p| p.tier);
+    Ok(Json(json!({ "Version": version, "Tier": tier })).into_response())
+}
+
+async fn get_parameter(store: &AwsParameterStore, body: &Value) -> Result<Response, Response> {
+    let name = required_str(body, "Name")?;
+    match store.get(name).await {
+        Some(parameter) => {
+            Ok(Json(json!({ "Parameter": parameter.to_parameter() })).into_response())
+        }
+        None => Err(ssm_error(
+            ssm_error_types::PARAMETER_NOT_FOUND,
+            format!("Parameter {name} not found."),
+        )),
+    }
+}
+
+async fn get_parameters(store: &AwsParameterStore, body: &Value) -> Result<Response, Response> {
+    let mut parameters = vec![];
+    let mut invalid = vec![];
+    for name in names(body)? {
+        match store.get(&name).await {
+            Some(parameter) => parameters.push(parameter.to_parameter()),
+            None => invalid.push(name),
+        }
+    }
+    Ok(Json(json!({ "Parameters": parameters, "InvalidParameters": invalid })).into_response())
+}
+
+async fn get_parameters_by_path(
+    store: &AwsParameterStore,
+    body: &Value,
+) -> Result<Response, Response> {
+    let path = required_str(body, "Path")?;
+    if !path.starts_with('/') {
+        return Err(ssm_error(
+            ssm_error_types::VALIDATION,
+            format!("The parameter path '{path}' must begin with a forward slash (/)."),
+        ));
+    }
+    let recursive = body
+        .get("Recursive")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    // ParameterFilters narrow the result further (Path filters aren't allowed here)
+    let filters = DescribeFilter::parse(&json!({
+        "ParameterFilters": body.get("ParameterFilters").cloned().unwrap_or(json!([]))
+    }))?;
+    if filters.iter().any(|filter| filter.key == "Path") {
+        return Err(ssm_error(
+            ssm_error_types::INVALID_FILTER_KEY,
+            "The filter key 'Path' isn't valid for GetParametersByPath.".to_string(),
+        ));
+    }
+    let parameters: Vec<ParameterRecord> = store
+        .by_path(path, recursive)
+        .await
+        .into_iter()
+        .filter(|parameter| filters.iter().all(|filter| filter.matches(parameter)))
+        .collect();
+
+    let (page, next_token) = paginate(parameters, body, MAX_RESULTS_BY_PATH)?;
+    let mut response = json!({
+        "Parameters": page.iter().map(ParameterRecord::to_parameter).collect::<Vec<_>>()
+    });
+    if let Some(token) = next_token {
+        response["NextToken"] = json!(token);
+    }
+    Ok(Json(response).into_response())
+}
+
+async fn describe_parameters(
+    store: &AwsParameterStore,
+    body: &Value,
+) -> Result<Response, Response> {
+    let filters = DescribeFilter::parse(body)?;
+    let parameters: Vec<ParameterRecord> = store
+        .all()
+        .await
+        .into_iter()
+        .filter(|parameter| filters.iter().all(|filter| filter.matches(parameter)))
+        .collect();
+
+    let (page, next_token) = paginate(parameters, body, MAX_RESULTS_DESCRIBE)?;
+    let mut response = json!({
+        "Parameters": page.iter().map(ParameterRecord::to_metadata).collect::<Vec<_>>()
+    });
+    if let Some(token) = next_token {
+        response["NextToken"] = json!(token);
+    }
+    Ok(Json(response).into_response())
+}
+
+async fn delete_parameter(store: &AwsParameterStore, body: &Value) -> Result<Response, Response> {
+    let name = required_str(body, "Name")?;
+    if store.delete(name).await {
+        Ok(Json(json!({})).into_response())
+    } else {
+        Err(ssm_error(
+            ssm_error_types::PARAMETER_NOT_FOUND,
+            format!("Parameter {name} not found."),
+        ))
+    }
+}
+
+async fn delete_parameters(store: &AwsParameterStore, body: &Value) -> Result<Response, Response> {
+    let mut deleted = vec![];
+    let mut invalid = vec![];
+    for name in names(body)? {
+        if store.delete(&name).await {
+            deleted.push(name);
+        } else {
+            invalid.push(name);
+        }
+    }
+    Ok(Json(json!({ "DeletedParameters": deleted, "InvalidParameters": invalid })).into_response())
+}
+
+#[async_trait::async_trait]
+impl AdminStore for AwsParameterStore {
+    async fn snapshot(&self) -> BTreeMap<String, SecretEntry> {
+        AdminStore::snapshot(&self.store).await
+    }
+
+    async fn seed(&self, entries: BTreeMap<String, SecretEntry>) -> Result<()> {
+        AdminStore::seed(&self.store, entries).await
+    }
+
+    async fn reset(&self) {
+        AdminStore::reset(&self.store).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn call(store: &AwsParameterStore, target: &str, body: Value) -> (StatusCode, Value) {
+        let response = handle_parameter_store_operation(store, target, &body)
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    async fn seeded_store() -> AwsParameterStore {
+        let store = AwsParameterStore::new();
+        for name in [
+            "/app/dev/a",
+            "/app/dev/b",
+            "/app/dev/c",
+            "/app/dev/nested/d",
+            "/app/prod/a",
+        ] {
+            store.put(name, "value", json!({}), false).await;
+        }
+        store
+    }
+
+    #[test]
+    fn test_is_under_path() {
+        assert!(is_under_path("/app/dev/a", "/app/dev", false));
+        assert!(is_under_path("/app/dev/a", "/app/dev/", false));
+        assert!(!is_under_path("/app/dev/nested/d", "/app/dev", false));
+        assert!(is_under_path("/app/dev/nested/d", "/app/dev", true));
+        assert!(!is_under_path("/app/development/a", "/app/dev", true));
+    }
+
+    #[tokio::test]
+    async fn test_put_parameter_versions_and_overwrite() {
+        let store = AwsParameterStore::new();
+        let body = json!({ "Name": "/app/dev/a", "Value": "1", "Type": "String" });
+        let (status, response) = call(&store, operations::PUT_PARAMETER, body.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["Version"], 1);
+
+        let (status, response) = call(&store, operations::PUT_PARAMETER, body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response["__type"],
+            ssm_error_types::PARAMETER_ALREADY_EXISTS
+        );
+
+        let (_, response) = call(
+            &store,
+            operations::PUT_PARAMETER,
+            json!({ "Name": "/app/dev/a", "Value": "2", "Overwrite": true }),
+        )
+        .await;
+        assert_eq!(response["Version"], 2);
+        let (_, response) = call(
+            &store,
+            operations::GET_PARAMETER,
+            json!({ "Name": "/app/dev/a" }),
+        )
+        .await;
+        assert_eq!(response["Parameter"]["Value"], "2");
+        assert_eq!(response["Parameter"]["Type"], "String");
+    }
+
+    #[tokio::test]
+    async fn test_get_parameters_by_path_paginates() {
+        let store = seeded_store().await;
+        let mut names = vec![];
+        let mut next_token: Option<String> = None;
+        loop {
+            let mut body = json!({ "Path": "/app/dev", "MaxResults": 2 });
+            if let Some(token) = next_token.take() {
+                body["NextToken"] = json!(token);
+            }
+            let (status, response) = call(&store, operations::GET_PARAMETERS_BY_PATH, body).await;
+            assert_eq!(status, StatusCode::OK);
+            for parameter in response["Parameters"].as_array().unwrap() {
+                names.push(parameter["Name"].as_str().unwrap().to_string());
+            }
+            match response["NextToken"].as_str() {
+                Some(token) => next_token = Some(token.to_string()),
+                None => break,
+            }
+        }
+        assert_eq!(names, vec!["/app/dev/a", "/app/dev/b", "/app/dev/c"]);
+
+        let (_, response) = call(
+            &store,
+            operations::GET_PARAMETERS_BY_PATH,
+            json!({ "Path": "/app", "Recursive": true }),
+        )
+        .await;
+        assert_eq!(response["Parameters"].as_array().map(Vec::len), Some(5));
+
+        let (status, response) = call(
+            &store,
+            operations::GET_PARAMETERS_BY_PATH,
+            json!({ "Path": "/app/dev", "NextToken": "not-a-token" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(response["__type"], ssm_error_types::INVALID_NEXT_TOKEN);
+    }
+
+    #[tokio::test]
+    async fn test_describe_parameters_filters() {
+        let store = seeded_store().await;
+        store
+            .put(
+                "/app/dev/secret",
+                "s",
+                json!({ "Type": "SecureString" }),
+                false,
+            )
+            .await;
+
+        let (_, response) = call(
+            &store,
+            operations::DESCRIBE_PARAMETERS,
+            json!({ "ParameterFilters": [
+                { "Key": "Path", "Option": "Recursive", "Values": ["/app/dev"] },
+                { "Key": "Type", "Values": ["SecureString"] }
+            ]}),
+        )
+        .await;
+        let parameters = response["Parameters"].as_array().unwrap();
+        assert_eq!(parameters.len(), 1);
+        assert_eq!(parameters[0]["Name"], "/app/dev/secret");
+        assert!(parameters[0].get("Value").is_none());
+
+        let (_, response) = call(
+            &store,
+            operations::DESCRIBE_PARAMETERS,
+            json!({ "Filters": [{ "Key": "Name", "Values": ["/app/prod"] }] }),
+        )
+        .await;
+        assert_eq!(response["Parameters"].as_array().map(Vec::len), Some(1));
+
+        let (status, response) = call(
+            &store,
+            operations::DESCRIBE_PARAMETERS,
+            json!({ "ParameterFilters": [{ "Key": "Path", "Option": "Equals", "Values": ["/app"] }] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(response["__type"], ssm_error_types::INVALID_FILTER_OPTION);
+    }
+
+    #[tokio::test]
+    async fn test_delete_parameters_batch() {
+        let store = seeded_store().await;
+        let (status, response) = call(
+            &store,
+            operations::DELETE_PARAMETERS,
+            json!({ "Names": ["/app/dev/a", "/app/dev/missing"] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["DeletedParameters"], json!(["/app/dev/a"]));
+        assert_eq!(response["InvalidParameters"], json!(["/app/dev/missing"]));
+        assert!(store.get("/app/dev/a").await.is_none());
+
+        let too_many: Vec<String> = (0..11).map(|i| format!("/p{i}")).collect();
+        let (status, _) = call(
+            &store,
+            operations::DELETE_PARAMETERS,
+            json!({ "Names": too_many }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -4,13 +4,18 @@
 //! - UUID-like version IDs
 //! - Secret key format: secret name (no path prefix)
 //! - Each update creates a new version automatically
+//! - Soft-delete lifecycle: deleted secrets stay recoverable until purged or the retention
+//!   period ends, and their names can't be reused meanwhile
 
 use super::common::{
-    SecretStore, SecretStoreBackend, SecretStoreEnum, SecretVersion, db_store::DbSecretStore,
+    SecretEntry, SecretStore, SecretStoreBackend, SecretStoreEnum, SecretVersion,
+    db_store::DbSecretStore,
 };
+use crate::admin::AdminStore;
+use crate::tenant;
 use anyhow::Result;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -22,6 +27,86 @@ pub struct AzureSecretStore {
     /// Key: secret name, Value: (deleted_date, scheduled_purge_date)
     /// Note: For database store, deleted secrets are stored in azure.deleted_secrets table
     deleted_secrets: Arc<RwLock<HashMap<String, (u64, u64)>>>,
+    soft_delete: SoftDeleteSettings,
+}
+
+/// Vault used in secret IDs outside tenant isolation (see `crate::tenant`)
+pub const DEFAULT_VAULT_NAME: &str = "test-vault";
+
+/// Vault of the request being handled
+pub fn vault_name() -> String {
+    tenant::current_tenant().unwrap_or_else(|| DEFAULT_VAULT_NAME.to_string())
+}
+
+/// Soft-delete settings of the simulated vault
+///
+/// Environment Variables:
+/// - AZURE_SOFT_DELETE_RETENTION_DAYS: Days deleted secrets stay recoverable (7-90, default: 90)
+/// - AZURE_PURGE_PROTECTION: Reject purges; deleted secrets are only removed when the
+///   retention period ends (default: false)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SoftDeleteSettings {
+    pub retention_days: u64,
+    pub purge_protection: bool,
+}
+
+impl Default for SoftDeleteSettings {
+    fn default() -> Self {
+        Self {
+            retention_days: 90,
+            purge_protection: false,
+        }
+    }
+}
+
+impl SoftDeleteSettings {
+    pub fn from_env() -> Self {
+        let retention_days = std::env::var("AZURE_SOFT_DELETE_RETENTION_DAYS")
+            .ok()
+            .and_then(|days| days.parse::<u64>().ok())
+            .map_or(90, |days| days.clamp(7, 90));
+        let purge_protection = std::env::var("AZURE_PURGE_PROTECTION")
+            .is_ok_and(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes"));
+        Self {
+            retention_days,
+            purge_protection,
+        }
+    }
+
+    /// Azure `recoveryLevel` attribute for this vault
+    pub fn recovery_level(&self) -> &'static str {
+        if self.purge_protection {
+            "Recoverable"
+        } else {
+            "Recoverable+Purgeable"
+        }
+    }
+}
+
+/// Lifecycle state of a secret: active -> deleted (recoverable) -> purged (not found)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecretState {
+    Active,
+    Deleted {
+        deleted_date: u64,
+        scheduled_purge_date: u64,
+    },
+    NotFound,
+}
+
+/// Why a purge was rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PurgeError {
+    /// The secret isn't in the deleted state
+    NotDeleted,
+    /// The vault has purge protection enabled
+    PurgeProtected,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
 
 impl AzureSecretStore {
@@ -43,6 +128,7 @@ impl AzureSecretStore {
                 return Self {
                     store: Arc::new(SecretStoreEnum::Database(db_store)),
                     deleted_secrets: Arc::new(RwLock::new(HashMap::new())),
+                    soft_delete: SoftDeleteSettings::from_env(),
                 };
             }
             // If database connection fails, fall back to in-memory store
@@ -63,9 +149,16 @@ impl AzureSecretStore {
         Self {
             store: Arc::new(SecretStoreEnum::InMemory(SecretStore::new())),
             deleted_secrets: Arc::new(RwLock::new(HashMap::new())),
+            soft_delete: SoftDeleteSettings::from_env(),
         }
     }
 
+    /// Override the soft-delete settings read from the environment
+    pub fn with_soft_delete(mut self, soft_delete: SoftDeleteSettings) -> Self {
+        self.soft_delete = soft_delete;
+        self
+    }
+
     /// Generate UUID-like version ID for Azure
     fn generate_version_id(secret_name: &str, timestamp: u64) -> String {
         use std::collections::hash_map::DefaultHasher;
@@ -84,6 +177,7 @@ impl AzureSecretStore {
         version_data: Value,
         version_id: Option<String>,
     ) -> Result<String> {
+        let secret_name = &tenant::scoped_key(secret_name);
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -113,46 +207,51 @@ impl AzureSecretStore {
 
     /// Get the latest version of a secret
     pub async fn get_latest(&self, secret_name: &str) -> Option<SecretVersion> {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.get_latest(secret_name).await
     }
 
     /// Get a specific version by version ID
     pub async fn get_version(&self, secret_name: &str, version_id: &str) -> Option<SecretVersion> {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.get_version(secret_name, version_id).await
     }
 
     /// List all versions of a secret
     pub async fn list_versions(&self, secret_name: &str) -> Option<Vec<SecretVersion>> {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.list_versions(secret_name).await
     }
 
     /// Get secret metadata
     pub async fn get_metadata(&self, secret_name: &str) -> Option<Value> {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.get_metadata(secret_name).await
     }
 
     /// Update secret metadata (tags, etc.)
     pub async fn update_metadata(&self, secret_name: &str, metadata: Value) -> Result<()> {
+        let secret_name = &tenant::scoped_key(secret_name);
         SecretStoreBackend::update_metadata(self.store.as_ref(), secret_name.to_string(), metadata)
             .await
     }
 
     /// Delete a secret (all versions) - soft delete
     /// Azure uses soft-delete, so we mark it as deleted but keep it for recovery
+    /// until the retention period ends. Returns false if the secret doesn't exist or is
+    /// already deleted.
     pub async fn delete_secret(&self, secret_name: &str) -> bool {
-        if !self.store.exists(secret_name).await {
+        let secret_name = &tenant::scoped_key(secret_name);
+        if !self.exists(secret_name).await {
             return false;
         }
 
         // Mark as disabled (soft-delete)
         self.store.disable_secret(secret_name).await;
 
-        // Track deletion date and scheduled purge date (90 days default)
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let purge_date = now + (90 * 24 * 60 * 60); // 90 days from now
+        // Track deletion date and scheduled purge date (retention period)
+        let now = unix_now();
+        let purge_date = now + self.soft_delete.retention_days * 24 * 60 * 60;
 
         let mut deleted = self.deleted_secrets.write().await;
         deleted.insert(secret_name.to_string(), (now, purge_date));
@@ -160,20 +259,74 @@ impl AzureSecretStore {
         true
     }
 
-    /// Get deleted secret info
+    /// Permanently remove deleted secrets whose scheduled purge date has passed
+    async fn purge_expired(&self) {
+        let now = unix_now();
+        let expired: Vec<String> = {
+            let mut deleted = self.deleted_secrets.write().await;
+            let expired: Vec<String> = deleted
+                .iter()
+                .filter(|(_, (_, scheduled_purge_date))| *scheduled_purge_date <= now)
+                .map(|(name, _)| name.clone())
+                .collect();
+            for name in &expired {
+                deleted.remove(name);
+            }
+            expired
+        };
+        for name in expired {
+            tracing::info!(
+                provider = "azure",
+                "Retention period ended, purging secret: {}",
+                name
+            );
+            self.store.delete_secret(&name).await;
+        }
+    }
+
+    /// Lifecycle state of a secret
+    pub async fn state(&self, secret_name: &str) -> SecretState {
+        let secret_name = &tenant::scoped_key(secret_name);
+        if let Some((deleted_date, scheduled_purge_date)) =
+            self.get_deleted_secret(secret_name).await
+        {
+            SecretState::Deleted {
+                deleted_date,
+                scheduled_purge_date,
+            }
+        } else if self.store.exists(secret_name).await {
+            SecretState::Active
+        } else {
+            SecretState::NotFound
+        }
+    }
+
+    /// Get deleted secret info: (deleted_date, scheduled_purge_date)
     pub async fn get_deleted_secret(&self, secret_name: &str) -> Option<(u64, u64)> {
+        let secret_name = &tenant::scoped_key(secret_name);
+        self.purge_expired().await;
         let deleted = self.deleted_secrets.read().await;
         deleted.get(secret_name).copied()
     }
 
-    /// List all deleted secret names
-    pub async fn list_deleted_secrets(&self) -> Vec<String> {
+    /// List all deleted secrets with their (deleted_date, scheduled_purge_date), sorted by name
+    pub async fn list_deleted_secrets(&self) -> Vec<(String, u64, u64)> {
+        self.purge_expired().await;
         let deleted = self.deleted_secrets.read().await;
-        deleted.keys().cloned().collect()
+        let mut secrets: Vec<(String, u64, u64)> = deleted
+            .iter()
+            .filter_map(|(key, (deleted_date, purge_date))| {
+                tenant::visible_name(key).map(|name| (name, *deleted_date, *purge_date))
+            })
+            .collect();
+        secrets.sort();
+        secrets
     }
 
     /// Recover a deleted secret
     pub async fn recover_secret(&self, secret_name: &str) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
+        self.purge_expired().await;
         // Remove from deleted secrets
         let mut deleted = self.deleted_secrets.write().await;
         if deleted.remove(secret_name).is_some() {
@@ -186,128 +339,248 @@ impl AzureSecretStore {
     }
 
     /// Purge a deleted secret (permanent deletion)
-    pub async fn purge_deleted_secret(&self, secret_name: &str) -> bool {
+    pub async fn purge_deleted_secret(&self, secret_name: &str) -> Result<(), PurgeError> {
+        let secret_name = &tenant::scoped_key(secret_name);
+        self.purge_expired().await;
+        if self.soft_delete.purge_protection {
+            return if self.is_deleted(secret_name).await {
+                Err(PurgeError::PurgeProtected)
+            } else {
+                Err(PurgeError::NotDeleted)
+            };
+        }
         // Remove from deleted secrets
         let mut deleted = self.deleted_secrets.write().await;
         if deleted.remove(secret_name).is_some() {
             // Permanently delete from store
             self.store.delete_secret(secret_name).await;
-            true
+            Ok(())
         } else {
-            false
+            Err(PurgeError::NotDeleted)
         }
     }
 
     /// Check if a secret is deleted (in soft-delete state)
     pub async fn is_deleted(&self, secret_name: &str) -> bool {
-        let deleted = self.deleted_secrets.read().await;
-        deleted.contains_key(secret_name)
+        let secret_name = &tenant::scoped_key(secret_name);
+        self.get_deleted_secret(secret_name).await.is_some()
     }
 
-    /// Check if a secret exists
+    /// Check if a secret exists (deleted secrets don't, until recovered)
     pub async fn exists(&self, secret_name: &str) -> bool {
-        self.store.exists(secret_name).await
+        let secret_name = &tenant::scoped_key(secret_name);
+        !self.is_deleted(secret_name).await && self.store.exists(secret_name).await
+    }
+
+    /// Soft-delete settings of the simulated vault
+    pub fn soft_delete(&self) -> SoftDeleteSettings {
+        self.soft_delete
     }
 
     /// Disable a secret (disables all versions, but keeps them for history)
     pub async fn disable_secret(&self, secret_name: &str) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.disable_secret(secret_name).await
     }
 
     /// Enable a secret (re-enables the secret, versions remain in their current state)
     pub async fn enable_secret(&self, secret_name: &str) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.enable_secret(secret_name).await
     }
 
     /// Disable a specific version
     pub async fn disable_version(&self, secret_name: &str, version_id: &str) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.disable_version(secret_name, version_id).await
     }
 
     /// Enable a specific version
     pub async fn enable_version(&self, secret_name: &str, version_id: &str) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.enable_version(secret_name, version_id).await
     }
 
     /// Check if a secret is enabled
     pub async fn is_enabled(&self, secret_name: &str) -> bool {
+        let secret_name = &tenant::scoped_key(secret_name);
         self.store.is_enabled(secret_name).await
     }
 
     /// List all secret names
+    /// Deleted secrets are listed by `list_deleted_secrets` instead
     pub async fn list_all_secrets(&self) -> Vec<String> {
-        self.store.list_all_keys().await
+        self.purge_expired().await;
+        let deleted = self.deleted_secrets.read().await;
+        self.store
+            .list_all_keys()
+            .await
+            .into_iter()
+            .filter(|key| !deleted.contains_key(key))
+            .filter_map(|key| tenant::visible_name(&key))
+            .collect()
     }
 
     /// List unique environments for secrets using database function
     /// Returns empty vec if database is not available
     pub async fn list_environments(&self, resource_type: &str) -> Vec<String> {
-        // Try to get DATABASE_URL from environment
-        if let Ok(database_url) = std::env::var("DATABASE_URL") {
-            if let Ok(db) = sea_orm::Database::connect(&database_url).await {
-                use sea_orm::ConnectionTrait;
-                let function_name = if resource_type == "secrets" {
-                    "azure.get_secret_environments"
-                } else {
-                    "azure.get_app_config_environments"
-                };
+        self.list_filter_values(resource_type, "environments", "environment")
+            .await
+    }
 
-                let stmt = sea_orm::Statement::from_string(
-                    sea_orm::DatabaseBackend::Postgres,
-                    format!("SELECT * FROM {}()", function_name),
-                );
+    /// List unique locations for secrets using database function
+    /// Returns empty vec if database is not available
+    pub async fn list_locations(&self, resource_type: &str) -> Vec<String> {
+        self.list_filter_values(resource_type, "locations", "location")
+            .await
+    }
 
-                if let Ok(rows) = db.query_all(stmt).await {
-                    return rows
-                        .into_iter()
-                        .filter_map(|row| {
-                            // Get the environment column value by column name
-                            row.try_get::<Option<String>>("", "environment")
-                                .or_else(|_| row.try_get::<String>("", "environment").map(Some))
-                                .ok()
-                                .flatten()
-                        })
-                        .collect();
-                }
-            }
+    /// Call `azure.get_{secret,app_config}_{kind}()` on the database store's connection
+    async fn list_filter_values(
+        &self,
+        resource_type: &str,
+        kind: &str,
+        column: &str,
+    ) -> Vec<String> {
+        if let SecretStoreEnum::Database(db_store) = self.store.as_ref() {
+            let function = if resource_type == "secrets" {
+                format!("get_secret_{}", kind)
+            } else {
+                format!("get_app_config_{}", kind)
+            };
+            return db_store.list_filter_values(&function, column, vec![]).await;
         }
         vec![]
     }
+}
 
-    /// List unique locations for secrets using database function
-    /// Returns empty vec if database is not available
-    pub async fn list_locations(&self, resource_type: &str) -> Vec<String> {
-        // Try to get DATABASE_URL from environment
-        if let Ok(database_url) = std::env::var("DATABASE_URL") {
-            if let Ok(db) = sea_orm::Database::connect(&database_url).await {
-                use sea_orm::ConnectionTrait;
-                let function_name = if resource_type == "secrets" {
-                    "azure.get_secret_locations"
-                } else {
-                    "azure.get_app_config_locations"
-                };
+// Note: Cannot implement Default for async new() - use AzureSecretStore::new().await instead
 
-                let stmt = sea_orm::Statement::from_string(
-                    sea_orm::DatabaseBackend::Postgres,
-                    format!("SELECT * FROM {}()", function_name),
-                );
+#[async_trait::async_trait]
+impl AdminStore for AzureSecretStore {
+    /// Deleted (recoverable) secrets are not part of the snapshot
+    async fn snapshot(&self) -> BTreeMap<String, SecretEntry> {
+        let mut entries = AdminStore::snapshot(self.store.as_ref()).await;
+        let deleted = self.deleted_secrets.read().await;
+        entries.retain(|name, _| !deleted.contains_key(name));
+        entries
+    }
 
-                if let Ok(rows) = db.query_all(stmt).await {
-                    return rows
-                        .into_iter()
-                        .filter_map(|row| {
-                            // Get the location column value by column name
-                            row.try_get::<Option<String>>("", "location")
-                                .or_else(|_| row.try_get::<String>("", "location").map(Some))
-                                .ok()
-                                .flatten()
-                        })
-                        .collect();
-                }
+    /// Seeding a deleted secret's name replaces the deleted secret
+    async fn seed(&self, entries: BTreeMap<String, SecretEntry>) -> Result<()> {
+        {
+            let mut deleted = self.deleted_secrets.write().await;
+            for name in entries.keys() {
+                deleted.remove(name);
             }
         }
-        vec![]
+        AdminStore::seed(self.store.as_ref(), entries).await
+    }
+
+    async fn reset(&self) {
+        AdminStore::reset(self.store.as_ref()).await;
+        self.deleted_secrets.write().await.clear();
     }
 }
 
-// Note: Cannot implement Default for async new() - use AzureSecretStore::new().await instead
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(soft_delete: SoftDeleteSettings) -> AzureSecretStore {
+        AzureSecretStore {
+            store: Arc::new(SecretStoreEnum::InMemory(SecretStore::new())),
+            deleted_secrets: Arc::new(RwLock::new(HashMap::new())),
+            soft_delete: SoftDeleteSettings::default(),
+        }
+        .with_soft_delete(soft_delete)
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_and_recover() {
+        let secrets = store(SoftDeleteSettings::default());
+        secrets
+            .set_secret("db-password", "v1".to_string())
+            .await
+            .unwrap();
+        assert_eq!(secrets.state("db-password").await, SecretState::Active);
+
+        assert!(secrets.delete_secret("db-password").await);
+        assert!(!secrets.exists("db-password").await);
+        assert!(secrets.list_all_secrets().await.is_empty());
+        assert!(matches!(
+            secrets.state("db-password").await,
+            SecretState::Deleted { deleted_date, scheduled_purge_date }
+                if scheduled_purge_date == deleted_date + 90 * 24 * 60 * 60
+        ));
+        // Deleting again is rejected: the secret is already in the deleted state
+        assert!(!secrets.delete_secret("db-password").await);
+
+        assert!(secrets.recover_secret("db-password").await);
+        assert_eq!(secrets.state("db-password").await, SecretState::Active);
+        assert!(secrets.is_enabled("db-password").await);
+        assert_eq!(
+            secrets.get_latest("db-password").await.unwrap().data["value"],
+            "v1"
+        );
+        assert!(!secrets.recover_secret("db-password").await);
+    }
+
+    #[tokio::test]
+    async fn test_purge_deleted_secret() {
+        let secrets = store(SoftDeleteSettings::default());
+        secrets
+            .set_secret("api-key", "v1".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            secrets.purge_deleted_secret("api-key").await,
+            Err(PurgeError::NotDeleted)
+        );
+
+        secrets.delete_secret("api-key").await;
+        assert_eq!(secrets.purge_deleted_secret("api-key").await, Ok(()));
+        assert_eq!(secrets.state("api-key").await, SecretState::NotFound);
+        assert!(secrets.list_deleted_secrets().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_purge_protection_rejects_purge() {
+        let secrets = store(SoftDeleteSettings {
+            retention_days: 7,
+            purge_protection: true,
+        });
+        assert_eq!(secrets.soft_delete().recovery_level(), "Recoverable");
+        secrets
+            .set_secret("api-key", "v1".to_string())
+            .await
+            .unwrap();
+        secrets.delete_secret("api-key").await;
+
+        assert_eq!(
+            secrets.purge_deleted_secret("api-key").await,
+            Err(PurgeError::PurgeProtected)
+        );
+        assert_eq!(
+            secrets.purge_deleted_secret("missing").await,
+            Err(PurgeError::NotDeleted)
+        );
+        assert!(secrets.is_deleted("api-key").await);
+    }
+
+    #[tokio::test]
+    async fn test_expired_deleted_secrets_are_purged() {
+        let secrets = store(SoftDeleteSettings {
+            retention_days: 0,
+            purge_protection: true,
+        });
+        secrets
+            .set_secret("api-key", "v1".to_string())
+            .await
+            .unwrap();
+        secrets.delete_secret("api-key").await;
+
+        assert!(secrets.list_deleted_secrets().await.is_empty());
+        assert_eq!(secrets.state("api-key").await, SecretState::NotFound);
+    }
+}
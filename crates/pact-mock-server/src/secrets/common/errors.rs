@@ -1,71 +1,140 @@
 //! Provider-specific error response formatting
 //!
 //! Each cloud provider has a different error response format:
-//! - GCP: Uses `{"error": {"code": 404, "message": "...", "status": "NOT_FOUND"}}`
-//! - AWS: Uses `{"__type": "ResourceNotFoundException", "message": "..."}`
-//! - Azure: Uses `{"error": {"code": "BadParameter", "message": "..."}}`
+//! - GCP: `google.rpc.Status`, `{"error": {"code": 404, "message": "...", "status": "NOT_FOUND", "details": [...]}}`
+//! - AWS: `{"__type": "ResourceNotFoundException", "message": "..."}` with an `x-amzn-ErrorType` header
+//! - Azure: `{"error": {"code": "Forbidden", "message": "...", "innererror": {"code": "..."}}}`
+//!
+//! The shapes follow the real APIs closely enough for the controller's error classification
+//! (`ProviderError::from_code`) to be tested against them.
 
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
 use axum::response::{IntoResponse, Json, Response};
-use serde_json::json;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Service named in GCP error details
+const GCP_SERVICE: &str = "secretmanager.googleapis.com";
 
 /// GCP error response format
 ///
 /// Format: `{"error": {"code": 404, "message": "...", "status": "NOT_FOUND"}}`
+/// 401, 403 and 429 responses carry a `google.rpc.ErrorInfo` detail, like the real API.
 /// Reference: https://cloud.google.com/apis/design/errors
 pub fn gcp_error_response(
     status: StatusCode,
     message: String,
     status_string: Option<&str>,
+) -> Response {
+    let details = match status {
+        StatusCode::UNAUTHORIZED => vec![gcp_error_info(
+            "ACCESS_TOKEN_TYPE_UNSUPPORTED",
+            "googleapis.com",
+            json!({ "service": GCP_SERVICE }),
+        )],
+        StatusCode::FORBIDDEN => vec![gcp_error_info(
+            "IAM_PERMISSION_DENIED",
+            "iam.googleapis.com",
+            json!({}),
+        )],
+        StatusCode::TOO_MANY_REQUESTS => vec![gcp_error_info(
+            "RATE_LIMIT_EXCEEDED",
+            "googleapis.com",
+            json!({ "service": GCP_SERVICE }),
+        )],
+        _ => vec![],
+    };
+    gcp_error_response_with_details(status, message, status_string, details)
+}
+
+/// GCP error response with explicit `google.rpc` details (see [`gcp_error_info`],
+/// [`gcp_retry_info`]); `details` is omitted when empty, as the real API does
+pub fn gcp_error_response_with_details(
+    status: StatusCode,
+    message: String,
+    status_string: Option<&str>,
+    details: Vec<Value>,
 ) -> Response {
     let status_str = status_string.unwrap_or_else(|| match status {
         StatusCode::NOT_FOUND => "NOT_FOUND",
         StatusCode::UNAUTHORIZED => "UNAUTHENTICATED",
         StatusCode::FORBIDDEN => "PERMISSION_DENIED",
         StatusCode::BAD_REQUEST => "INVALID_ARGUMENT",
+        StatusCode::CONFLICT => "ALREADY_EXISTS",
         StatusCode::TOO_MANY_REQUESTS => "RESOURCE_EXHAUSTED",
         StatusCode::SERVICE_UNAVAILABLE => "UNAVAILABLE",
         StatusCode::INTERNAL_SERVER_ERROR => "INTERNAL",
         _ => "UNKNOWN",
     });
 
-    (
-        status,
-        Json(json!({
-            "error": {
-                "code": status.as_u16(),
-                "message": message,
-                "status": status_str
-            }
-        })),
-    )
-        .into_response()
+    let mut error = json!({
+        "code": status.as_u16(),
+        "message": message,
+        "status": status_str
+    });
+    if !details.is_empty() {
+        error["details"] = Value::Array(details);
+    }
+    (status, Json(json!({ "error": error }))).into_response()
+}
+
+/// `google.rpc.ErrorInfo` detail
+pub fn gcp_error_info(reason: &str, domain: &str, metadata: Value) -> Value {
+    json!({
+        "@type": "type.googleapis.com/google.rpc.ErrorInfo",
+        "reason": reason,
+        "domain": domain,
+        "metadata": metadata
+    })
+}
+
+/// `google.rpc.RetryInfo` detail (`retryDelay` as a protobuf duration, e.g. "30s")
+pub fn gcp_retry_info(delay: Duration) -> Value {
+    json!({
+        "@type": "type.googleapis.com/google.rpc.RetryInfo",
+        "retryDelay": format!("{}s", delay.as_secs())
+    })
 }
 
 /// AWS error response format
 ///
-/// Format: `{"__type": "ResourceNotFoundException", "message": "..."}`
-/// Reference: https://docs.aws.amazon.com/apigateway/latest/developerguide/handle-errors-in-lambda.html
+/// Format: `{"__type": "ResourceNotFoundException", "message": "..."}`, with the error type
+/// also in the `x-amzn-ErrorType` header and the `application/x-amz-json-1.1` content type
+/// Reference: https://smithy.io/2.0/aws/protocols/aws-json-1_1-protocol.html#operation-error-serialization
 pub fn aws_error_response(status: StatusCode, error_type: &str, message: String) -> Response {
-    (
+    let mut response = (
         status,
         Json(json!({
             "__type": error_type,
             "message": message
         })),
     )
-        .into_response()
+        .into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/x-amz-json-1.1"),
+    );
+    if let Ok(value) = HeaderValue::from_str(error_type) {
+        headers.insert("x-amzn-errortype", value);
+    }
+    response
 }
 
 /// AWS error type constants
 pub mod aws_error_types {
     pub const RESOURCE_NOT_FOUND: &str = "ResourceNotFoundException";
+    pub const RESOURCE_EXISTS: &str = "ResourceExistsException";
     pub const INVALID_PARAMETER: &str = "InvalidParameterException";
     pub const INVALID_REQUEST: &str = "InvalidRequestException";
     pub const LIMIT_EXCEEDED: &str = "LimitExceededException";
     pub const INTERNAL_SERVICE: &str = "InternalServiceError";
     pub const INVALID_NEXT_TOKEN: &str = "InvalidNextTokenException";
     pub const DECRYPTION_FAILURE: &str = "DecryptionFailureException";
+    pub const ACCESS_DENIED: &str = "AccessDeniedException";
+    pub const UNRECOGNIZED_CLIENT: &str = "UnrecognizedClientException";
+    pub const UNKNOWN_OPERATION: &str = "UnknownOperationException";
+    pub const THROTTLING: &str = "ThrottlingException";
 }
 
 /// Map HTTP status code to AWS error type
@@ -73,9 +142,10 @@ pub fn aws_error_type_from_status(status: StatusCode) -> &'static str {
     match status {
         StatusCode::NOT_FOUND => aws_error_types::RESOURCE_NOT_FOUND,
         StatusCode::BAD_REQUEST => aws_error_types::INVALID_PARAMETER,
-        StatusCode::UNAUTHORIZED => aws_error_types::INVALID_REQUEST,
-        StatusCode::FORBIDDEN => aws_error_types::INVALID_REQUEST,
-        StatusCode::TOO_MANY_REQUESTS => aws_error_types::LIMIT_EXCEEDED,
+        StatusCode::UNAUTHORIZED => aws_error_types::UNRECOGNIZED_CLIENT,
+        StatusCode::FORBIDDEN => aws_error_types::ACCESS_DENIED,
+        StatusCode::CONFLICT => aws_error_types::RESOURCE_EXISTS,
+        StatusCode::TOO_MANY_REQUESTS => aws_error_types::THROTTLING,
         StatusCode::SERVICE_UNAVAILABLE => aws_error_types::INTERNAL_SERVICE,
         StatusCode::INTERNAL_SERVER_ERROR => aws_error_types::INTERNAL_SERVICE,
         _ => aws_error_types::INVALID_REQUEST,
@@ -85,18 +155,29 @@ pub fn aws_error_type_from_status(status: StatusCode) -> &'static str {
 /// Azure error response format
 ///
 /// Format: `{"error": {"code": "BadParameter", "message": "..."}}`
+/// `Forbidden` responses carry an `innererror`, like Key Vault's access policy denials.
 /// Reference: https://learn.microsoft.com/en-us/rest/api/azure/
 pub fn azure_error_response(status: StatusCode, error_code: &str, message: String) -> Response {
-    (
-        status,
-        Json(json!({
-            "error": {
-                "code": error_code,
-                "message": message
-            }
-        })),
-    )
-        .into_response()
+    let inner_code = (error_code == azure_error_codes::FORBIDDEN)
+        .then_some(azure_error_codes::FORBIDDEN_BY_POLICY);
+    azure_error_response_with_inner(status, error_code, message, inner_code)
+}
+
+/// Azure error response with an `innererror` code naming the specific cause
+pub fn azure_error_response_with_inner(
+    status: StatusCode,
+    error_code: &str,
+    message: String,
+    inner_code: Option<&str>,
+) -> Response {
+    let mut error = json!({
+        "code": error_code,
+        "message": message
+    });
+    if let Some(inner_code) = inner_code {
+        error["innererror"] = json!({ "code": inner_code });
+    }
+    (status, Json(json!({ "error": error }))).into_response()
 }
 
 /// Azure error code constants
@@ -105,9 +186,14 @@ pub mod azure_error_codes {
     pub const BAD_PARAMETER: &str = "BadParameter";
     pub const UNAUTHORIZED: &str = "Unauthorized";
     pub const FORBIDDEN: &str = "Forbidden";
-    pub const THROTTLED: &str = "ThrottledRequests";
+    pub const CONFLICT: &str = "Conflict";
+    pub const THROTTLED: &str = "Throttled";
     pub const SERVICE_UNAVAILABLE: &str = "ServiceUnavailable";
     pub const INTERNAL_ERROR: &str = "InternalError";
+
+    // `innererror` codes
+    pub const FORBIDDEN_BY_POLICY: &str = "ForbiddenByPolicy";
+    pub const OBJECT_IS_DELETED_BUT_RECOVERABLE: &str = "ObjectIsDeletedButRecoverable";
 }
 
 /// Map HTTP status code to Azure error code
@@ -117,9 +203,104 @@ pub fn azure_error_code_from_status(status: StatusCode) -> &'static str {
         StatusCode::BAD_REQUEST => azure_error_codes::BAD_PARAMETER,
         StatusCode::UNAUTHORIZED => azure_error_codes::UNAUTHORIZED,
         StatusCode::FORBIDDEN => azure_error_codes::FORBIDDEN,
+        StatusCode::CONFLICT => azure_error_codes::CONFLICT,
         StatusCode::TOO_MANY_REQUESTS => azure_error_codes::THROTTLED,
         StatusCode::SERVICE_UNAVAILABLE => azure_error_codes::SERVICE_UNAVAILABLE,
         StatusCode::INTERNAL_SERVER_ERROR => azure_error_codes::INTERNAL_ERROR,
         _ => azure_error_codes::BAD_PARAMETER,
     }
 }
+
+/// Error response in the shape of the provider a request was made to, for faults that
+/// aren't tied to one mock server (header-triggered failures, chaos)
+///
+/// AWS when the request has an `x-amz-target` header, GCP for `/v1/` paths, Azure otherwise.
+pub fn provider_error_response(
+    headers: &HeaderMap,
+    path: &str,
+    status: StatusCode,
+    message: String,
+) -> Response {
+    if headers.contains_key("x-amz-target") {
+        aws_error_response(status, aws_error_type_from_status(status), message)
+    } else if path.starts_with("/v1/") {
+        gcp_error_response(status, message, None)
+    } else {
+        azure_error_response(status, azure_error_code_from_status(status), message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body(response: Response) -> Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_gcp_error_details() {
+        let response = gcp_error_response(StatusCode::NOT_FOUND, "gone".to_string(), None);
+        assert_eq!(
+            body(response).await,
+            json!({ "error": { "code": 404, "message": "gone", "status": "NOT_FOUND" } })
+        );
+
+        let response = gcp_error_response_with_details(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Quota exceeded".to_string(),
+            None,
+            vec![gcp_retry_info(Duration::from_secs(30))],
+        );
+        let error = &body(response).await["error"];
+        assert_eq!(error["status"], "RESOURCE_EXHAUSTED");
+        assert_eq!(error["details"][0]["retryDelay"], "30s");
+    }
+
+    #[tokio::test]
+    async fn test_provider_error_response_shapes() {
+        let mut aws_headers = HeaderMap::new();
+        aws_headers.insert(
+            "x-amz-target",
+            HeaderValue::from_static("secretsmanager.GetSecretValue"),
+        );
+        let response = provider_error_response(
+            &aws_headers,
+            "/",
+            StatusCode::FORBIDDEN,
+            "denied".to_string(),
+        );
+        assert_eq!(
+            response.headers()["x-amzn-errortype"],
+            "AccessDeniedException"
+        );
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/x-amz-json-1.1"
+        );
+        assert_eq!(body(response).await["__type"], "AccessDeniedException");
+
+        let response = provider_error_response(
+            &HeaderMap::new(),
+            "/v1/projects/p/secrets/s",
+            StatusCode::FORBIDDEN,
+            "denied".to_string(),
+        );
+        let error = &body(response).await["error"];
+        assert_eq!(error["status"], "PERMISSION_DENIED");
+        assert_eq!(error["details"][0]["reason"], "IAM_PERMISSION_DENIED");
+
+        let response = provider_error_response(
+            &HeaderMap::new(),
+            "/secrets/s",
+            StatusCode::FORBIDDEN,
+            "denied".to_string(),
+        );
+        let error = &body(response).await["error"];
+        assert_eq!(error["code"], "Forbidden");
+        assert_eq!(error["innererror"]["code"], "ForbiddenByPolicy");
+    }
+}
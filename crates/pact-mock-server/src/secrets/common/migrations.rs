@@ -0,0 +1,215 @@
+//! Embedded schema migrations for the database-backed store
+//!
+//! The SQL files in `migrations/{gcp,aws,azure}` are compiled into the binary and applied
+//! with sea-orm-migration when a `DbSecretStore` connects, so a fresh database gets its
+//! schema, tables and helper functions without the postgres-manager sidecar. Every file is
+//! idempotent, so databases the sidecar already migrated are brought under version tracking
+//! without changes.
+//!
+//! Each schema records its applied migrations in its own table (`seaql_migrations_{schema}`),
+//! since each mock server only migrates its own schema. The `010_insert_test_data` files are
+//! not embedded; use `SEED_FILE` or `/admin/seed` for test data.
+//!
+//! SQLite databases (`DATABASE_URL=sqlite://...`) get the table-only equivalents in
+//! `migrations/sqlite/{schema}` instead; the Postgres filter functions are replaced by
+//! plain queries in `DbSecretStore::list_filter_values`.
+//!
+//! Run with `--migrate-only` to apply migrations and exit (e.g. from an init container).
+
+use anyhow::{Context, Result, bail};
+use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr};
+use sea_orm_migration::prelude::*;
+use tracing::info;
+
+/// A migration applying one embedded SQL file
+struct SqlMigration {
+    name: &'static str,
+    sql: &'static str,
+}
+
+impl MigrationName for SqlMigration {
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for SqlMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(self.sql)
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Err(DbErr::Migration(format!(
+            "Migration {} can't be reverted",
+            self.name
+        )))
+    }
+}
+
+/// Embed `migrations/{dir}/{file}.sql`
+macro_rules! sql_migrations {
+    ($dir:literal: $($file:literal),+ $(,)?) => {
+        vec![$(
+            Box::new(SqlMigration {
+                name: concat!("m", $file),
+                sql: include_str!(concat!("../../../migrations/", $dir, "/", $file, ".sql")),
+            }) as Box<dyn MigrationTrait>
+        ),+]
+    };
+}
+
+/// Define a migrator for one schema, tracked in `seaql_migrations_{schema}`
+macro_rules! schema_migrator {
+    ($migrator:ident, $schema:literal, $migrations:expr) => {
+        pub struct $migrator;
+
+        impl MigratorTrait for $migrator {
+            fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+                $migrations
+            }
+
+            fn migration_table_name() -> DynIden {
+                Alias::new(concat!("seaql_migrations_", $schema)).into_iden()
+            }
+        }
+    };
+}
+
+schema_migrator!(
+    GcpMigrator,
+    "gcp",
+    sql_migrations!("gcp":
+        "001_create_schema",
+        "002_create_secrets_table",
+        "003_create_versions_table",
+        "004_create_parameters_table",
+        "005_create_parameter_versions_table",
+        "006_add_environment_location_columns",
+        "007_add_environment_location_to_parameters",
+        "008_create_filter_functions",
+        "011_fix_automatic_location_to_null",
+        "012_enforce_location_environment_not_null",
+    )
+);
+
+schema_migrator!(
+    AwsMigrator,
+    "aws",
+    sql_migrations!("aws":
+        "001_create_schema",
+        "002_create_secrets_table",
+        "003_create_versions_table",
+        "004_create_staging_labels_table",
+        "005_create_parameters_table",
+        "006_create_parameter_versions_table",
+        "007_add_environment_location_columns",
+        "008_add_environment_location_to_parameters",
+        "009_create_filter_functions",
+        "011_enforce_location_environment_not_null",
+    )
+);
+
+schema_migrator!(
+    AzureMigrator,
+    "azure",
+    sql_migrations!("azure":
+        "001_create_schema",
+        "002_create_secrets_table",
+        "003_create_versions_table",
+        "004_create_deleted_secrets_table",
+        "005_create_app_config_table",
+        "006_create_app_config_versions_table",
+        "007_add_environment_location_columns",
+        "008_add_environment_location_to_app_config",
+        "009_create_filter_functions",
+        "011_enforce_location_environment_not_null",
+    )
+);
+
+schema_migrator!(
+    SqliteGcpMigrator,
+    "gcp",
+    sql_migrations!("sqlite/gcp": "001_create_tables")
+);
+
+schema_migrator!(
+    SqliteAwsMigrator,
+    "aws",
+    sql_migrations!("sqlite/aws": "001_create_tables")
+);
+
+schema_migrator!(
+    SqliteAzureMigrator,
+    "azure",
+    sql_migrations!("sqlite/azure": "001_create_tables")
+);
+
+/// Apply pending migrations for a provider schema, returning how many were applied
+///
+/// The migration set is picked by the connection's backend (Postgres or SQLite).
+pub async fn run_migrations(db: &DatabaseConnection, schema: &str) -> Result<usize> {
+    let sqlite = db.get_database_backend() == DatabaseBackend::Sqlite;
+    let pending = match (schema, sqlite) {
+        ("gcp", false) => GcpMigrator::get_pending_migrations(db).await,
+        ("aws", false) => AwsMigrator::get_pending_migrations(db).await,
+        ("azure", false) => AzureMigrator::get_pending_migrations(db).await,
+        ("gcp", true) => SqliteGcpMigrator::get_pending_migrations(db).await,
+        ("aws", true) => SqliteAwsMigrator::get_pending_migrations(db).await,
+        ("azure", true) => SqliteAzureMigrator::get_pending_migrations(db).await,
+        _ => bail!("Unknown schema: {}", schema),
+    }
+    .with_context(|| format!("Failed to read migration status for schema {schema}"))?
+    .len();
+
+    if pending == 0 {
+        return Ok(0);
+    }
+    match (schema, sqlite) {
+        ("gcp", false) => GcpMigrator::up(db, None).await,
+        ("aws", false) => AwsMigrator::up(db, None).await,
+        ("gcp", true) => SqliteGcpMigrator::up(db, None).await,
+        ("aws", true) => SqliteAwsMigrator::up(db, None).await,
+        ("azure", true) => SqliteAzureMigrator::up(db, None).await,
+        _ => AzureMigrator::up(db, None).await,
+    }
+    .with_context(|| format!("Failed to migrate schema {schema}"))?;
+    info!("Applied {} migration(s) to schema {}", pending, schema);
+    Ok(pending)
+}
+
+/// Connect to DATABASE_URL, apply the schema's migrations and return
+///
+/// Used by the mock servers' `--migrate-only` flag. Goes through `DbSecretStore::new` so
+/// SQLite URLs get their schema database attached first.
+pub async fn migrate_from_env(schema: &str) -> Result<usize> {
+    let database_url =
+        std::env::var("DATABASE_URL").context("DATABASE_URL is required for --migrate-only")?;
+    let store = super::db_store::DbSecretStore::connect(&database_url, schema).await?;
+    run_migrations(store.connection(), schema).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrations_are_ordered_and_skip_test_data() {
+        for migrations in [
+            GcpMigrator::migrations(),
+            AwsMigrator::migrations(),
+            AzureMigrator::migrations(),
+        ] {
+            let names: Vec<&str> = migrations.iter().map(|m| m.name()).collect();
+            let mut sorted = names.clone();
+            sorted.sort_unstable();
+            assert_eq!(names, sorted);
+            assert!(names[0].ends_with("_create_schema"));
+            assert!(!names.iter().any(|name| name.contains("test_data")));
+        }
+    }
+}
@@ -7,6 +7,7 @@ pub mod db_store;
 pub mod entities;
 pub mod errors;
 pub mod limits;
+pub mod migrations;
 pub mod store;
 pub mod store_enum;
 pub mod store_trait;
@@ -28,16 +28,22 @@ pub struct SecretVersion {
 
 /// Secret entry containing all versions
 /// Versions are stored in order (oldest first)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecretEntry {
     /// Ordered list of versions (oldest first)
     pub versions: Vec<SecretVersion>,
     /// Whether the secret itself is disabled (all versions disabled)
+    #[serde(default)]
     pub disabled: bool,
     /// Secret metadata (replication config, etc.)
+    #[serde(default = "empty_metadata")]
     pub metadata: Value,
 }
 
+fn empty_metadata() -> Value {
+    json!({})
+}
+
 /// In-memory secret store with versioning support
 ///
 /// Stores secrets with version history:
@@ -62,6 +68,17 @@ impl SecretStore {
     pub async fn snapshot(&self) -> HashMap<String, SecretEntry> {
         self.store.read().await.clone()
     }
+
+    /// Insert an entry as-is, replacing any existing entry with the same key
+    /// Used to seed fixtures (keeps version IDs, timestamps and enabled flags)
+    pub async fn insert_entry(&self, key: String, entry: SecretEntry) {
+        self.store.write().await.insert(key, entry);
+    }
+
+    /// Remove all secrets
+    pub async fn clear(&self) {
+        self.store.write().await.clear();
+    }
 }
 
 impl SecretStore {
@@ -157,6 +157,75 @@ impl SecretStoreBackend for SecretStoreEnum {
     }
 }
 
+impl SecretStoreEnum {
+    /// All secrets with their versions and metadata
+    pub async fn snapshot(&self) -> HashMap<String, SecretEntry> {
+        match self {
+            SecretStoreEnum::InMemory(store) => store.snapshot().await,
+            SecretStoreEnum::Database(_) => {
+                let mut entries = HashMap::new();
+                for key in self.list_all_keys().await {
+                    let entry = SecretEntry {
+                        versions: self.list_versions(&key).await.unwrap_or_default(),
+                        disabled: !self.is_enabled(&key).await,
+                        metadata: self
+                            .get_metadata(&key)
+                            .await
+                            .unwrap_or_else(|| serde_json::json!({})),
+                    };
+                    entries.insert(key, entry);
+                }
+                entries
+            }
+        }
+    }
+
+    /// Replace a secret with the given entry
+    ///
+    /// The database backend re-creates the versions, so their creation timestamps are reset.
+    pub async fn insert_entry(&self, key: String, entry: SecretEntry) -> Result<()> {
+        match self {
+            SecretStoreEnum::InMemory(store) => {
+                store.insert_entry(key, entry).await;
+                Ok(())
+            }
+            SecretStoreEnum::Database(_) => {
+                self.delete_secret(&key).await;
+                for version in &entry.versions {
+                    let version_id = version.version_id.clone();
+                    self.add_version(
+                        key.clone(),
+                        version.data.clone(),
+                        Some(version_id.clone()),
+                        move |_, _| version_id,
+                    )
+                    .await?;
+                    if !version.enabled {
+                        self.disable_version(&key, &version.version_id).await;
+                    }
+                }
+                self.update_metadata(key.clone(), entry.metadata).await?;
+                if entry.disabled {
+                    self.disable_secret(&key).await;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove all secrets
+    pub async fn clear(&self) {
+        match self {
+            SecretStoreEnum::InMemory(store) => store.clear().await,
+            SecretStoreEnum::Database(_) => {
+                for key in self.list_all_keys().await {
+                    self.delete_secret(&key).await;
+                }
+            }
+        }
+    }
+}
+
 impl std::fmt::Debug for SecretStoreEnum {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
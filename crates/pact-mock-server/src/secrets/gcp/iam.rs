@@ -0,0 +1,481 @@
+//! GCP IAM permission simulation
+//!
+//! Maps bearer tokens to service accounts and service accounts to IAM permissions, so a
+//! request made with a given token gets `403 PERMISSION_DENIED` for operations its service
+//! account isn't granted (e.g. allowed to access versions but not to create secrets). This
+//! exercises the controller's permission-error handling without real GCP.
+//!
+//! Requests are mapped to the permission real GCP checks (`secretmanager.secrets.create`,
+//! `secretmanager.versions.access`, `parametermanager.parameters.get`, ...). Tokens that don't
+//! belong to a configured service account are allowed unless `denyUnknownTokens` is set, so
+//! the simulation is opt-in per token.
+//!
+//! Configuration:
+//! - `GCP_IAM_POLICY`: JSON policy applied at startup
+//! - `GCP_IAM_POLICY_FILE`: Path to a JSON policy file applied at startup
+//! - `GET|PUT|DELETE /iam/policy`: Read, replace or clear the policy at runtime
+//!
+//! ```json
+//! {
+//!   "serviceAccounts": {
+//!     "reader@my-project.iam.gserviceaccount.com": {
+//!       "tokens": ["reader-token"],
+//!       "permissions": ["secretmanager.versions.access", "secretmanager.secrets.get"]
+//!     },
+//!     "admin@my-project.iam.gserviceaccount.com": {
+//!       "tokens": ["admin-token"],
+//!       "permissions": ["secretmanager.*"]
+//!     }
+//!   },
+//!   "denyUnknownTokens": false
+//! }
+//! ```
+
+use crate::secrets::common::errors::{
+    gcp_error_info, gcp_error_response, gcp_error_response_with_details,
+};
+use anyhow::{Context, Result};
+use axum::{
+    Json, Router,
+    extract::{Request, State},
+    http::{Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// Permissions granted to a simulated service account
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceAccountGrant {
+    /// Bearer tokens that authenticate as this service account
+    #[serde(default)]
+    pub tokens: Vec<String>,
+    /// Granted permissions; `service.*` grants every permission of a service, `*` grants all
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+impl ServiceAccountGrant {
+    fn allows(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|granted| {
+            granted == "*"
+                || granted == permission
+                || granted
+                    .strip_suffix('*')
+                    .is_some_and(|prefix| prefix.ends_with('.') && permission.starts_with(prefix))
+        })
+    }
+}
+
+/// Simulated IAM policy (empty allows everything)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcpIamPolicy {
+    /// Service account email -> tokens and permissions
+    #[serde(default)]
+    pub service_accounts: BTreeMap<String, ServiceAccountGrant>,
+    /// Reject requests without a token or with a token no service account owns (401)
+    #[serde(default)]
+    pub deny_unknown_tokens: bool,
+}
+
+impl GcpIamPolicy {
+    /// Load the policy from GCP_IAM_POLICY or GCP_IAM_POLICY_FILE (empty if neither is set)
+    pub fn from_env() -> Result<Self> {
+        if let Ok(policy) = std::env::var("GCP_IAM_POLICY") {
+            return serde_json::from_str(&policy).context("GCP_IAM_POLICY is not a valid policy");
+        }
+        if let Ok(path) = std::env::var("GCP_IAM_POLICY_FILE") {
+            let policy = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read GCP_IAM_POLICY_FILE '{path}'"))?;
+            return serde_json::from_str(&policy)
+                .with_context(|| format!("GCP_IAM_POLICY_FILE '{path}' is not a valid policy"));
+        }
+        Ok(Self::default())
+    }
+
+    /// Service account owning `token`
+    fn service_account(&self, token: &str) -> Option<(&str, &ServiceAccountGrant)> {
+        self.service_accounts
+            .iter()
+            .find(|(_, grant)| grant.tokens.iter().any(|t| t == token))
+            .map(|(email, grant)| (email.as_str(), grant))
+    }
+
+    /// Check a request's token against the permission it needs
+    fn check(&self, token: Option<&str>, permission: &str) -> IamDecision {
+        match token.and_then(|token| self.service_account(token)) {
+            Some((_, grant)) if grant.allows(permission) => IamDecision::Allow,
+            Some((email, _)) => IamDecision::Deny {
+                service_account: email.to_string(),
+            },
+            None if self.deny_unknown_tokens => IamDecision::Unauthenticated,
+            None => IamDecision::Allow,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum IamDecision {
+    Allow,
+    Deny { service_account: String },
+    Unauthenticated,
+}
+
+/// Permission GCP checks for a request, with the resource it applies to
+/// Returns `None` for requests that need no permission (locations, mock-only endpoints)
+#[must_use]
+pub fn required_permission(method: &Method, path: &str) -> Option<(&'static str, String)> {
+    let path = path.strip_prefix("/v1/")?;
+    let (path, verb) = match path.rsplit_once(':') {
+        Some((path, verb)) if !verb.contains('/') => (path, Some(verb)),
+        _ => (path, None),
+    };
+    let segments: Vec<&str> = path.split('/').collect();
+
+    let (service, collection_index) = if let Some(i) = segments.iter().position(|s| *s == "secrets")
+    {
+        ("secretmanager", i)
+    } else if let Some(i) = segments.iter().position(|s| *s == "parameters") {
+        ("parametermanager", i)
+    } else {
+        return None;
+    };
+    let resource = segments.join("/");
+    let rest = &segments[collection_index + 1..];
+
+    // Mock-only filter endpoints used by the UI
+    if matches!(rest, ["environments" | "locations"]) {
+        return None;
+    }
+
+    let permission = match (service, rest, verb, method.as_str()) {
+        // Secret Manager
+        ("secretmanager", [], None, "POST") => "secretmanager.secrets.create",
+        ("secretmanager", [], None, "GET") => "secretmanager.secrets.list",
+        ("secretmanager", [_], None, "GET") => "secretmanager.secrets.get",
+        ("secretmanager", [_], None, "PATCH") => "secretmanager.secrets.update",
+        ("secretmanager", [_], None, "DELETE") => "secretmanager.secrets.delete",
+        ("secretmanager", [_], Some("addVersion"), _) => "secretmanager.versions.add",
+        ("secretmanager", [_], Some("enable" | "disable"), _) => "secretmanager.secrets.update",
+        ("secretmanager", [_, "versions"], None, _) => "secretmanager.versions.list",
+        ("secretmanager", [_, "versions", _], Some("access"), _) => "secretmanager.versions.access",
+        ("secretmanager", [_, "versions", _], Some("enable"), _) => "secretmanager.versions.enable",
+        ("secretmanager", [_, "versions", _], Some("disable"), _) => {
+            "secretmanager.versions.disable"
+        }
+        ("secretmanager", [_, "versions", _], Some("destroy"), _) => {
+            "secretmanager.versions.destroy"
+        }
+        ("secretmanager", [_, "versions", _], None, _) => "secretmanager.versions.get",
+        // Parameter Manager
+        ("parametermanager", [], None, "POST") => "parametermanager.parameters.create",
+        ("parametermanager", [], None, "GET") => "parametermanager.parameters.list",
+        ("parametermanager", [_], None, "GET") => "parametermanager.parameters.get",
+        ("parametermanager", [_], None, "PATCH") => "parametermanager.parameters.update",
+        ("parametermanager", [_], None, "DELETE") => "parametermanager.parameters.delete",
+        ("parametermanager", [_], Some("addVersion"), _)
+        | ("parametermanager", [_, "versions"], None, "POST") => {
+            "parametermanager.parameterVersions.create"
+        }
+        ("parametermanager", [_, "versions"], None, _) => "parametermanager.parameterVersions.list",
+        ("parametermanager", [_, "versions", _], Some("render"), _) => {
+            "parametermanager.parameterVersions.render"
+        }
+        ("parametermanager", [_, "versions", _], None, "PATCH") => {
+            "parametermanager.parameterVersions.update"
+        }
+        ("parametermanager", [_, "versions", _], None, "DELETE") => {
+            "parametermanager.parameterVersions.delete"
+        }
+        ("parametermanager", [_, "versions", _], None, _) => {
+            "parametermanager.parameterVersions.get"
+        }
+        _ => return None,
+    };
+    Some((permission, resource))
+}
+
+/// Shared IAM simulation state
+#[derive(Debug, Clone, Default)]
+pub struct GcpIamState {
+    policy: Arc<RwLock<GcpIamPolicy>>,
+}
+
+impl GcpIamState {
+    #[must_use]
+    pub fn new(policy: GcpIamPolicy) -> Self {
+        Self {
+            policy: Arc::new(RwLock::new(policy)),
+        }
+    }
+
+    /// IAM state from GCP_IAM_POLICY / GCP_IAM_POLICY_FILE, allowing everything if invalid
+    pub fn from_env() -> Self {
+        match GcpIamPolicy::from_env() {
+            Ok(policy) => {
+                if !policy.service_accounts.is_empty() {
+                    info!(
+                        "IAM simulation enabled for {} service account(s)",
+                        policy.service_accounts.len()
+                    );
+                }
+                Self::new(policy)
+            }
+            Err(e) => {
+                warn!("⚠️  Ignoring IAM policy: {:#}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// IAM simulation middleware
+/// Returns 403 PERMISSION_DENIED when the token's service account lacks the permission the
+/// request needs, and 401 UNAUTHENTICATED for unknown tokens when `denyUnknownTokens` is set
+pub async fn gcp_iam_middleware(
+    State(iam): State<GcpIamState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some((permission, resource)) = required_permission(request.method(), request.uri().path())
+    else {
+        return next.run(request).await;
+    };
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    let decision = iam.policy.read().await.check(token, permission);
+    match decision {
+        IamDecision::Allow => next.run(request).await,
+        IamDecision::Deny { service_account } => {
+            warn!(
+                provider = "gcp",
+                service_account = service_account,
+                permission = permission,
+                "IAM: denying {} on {}",
+                permission,
+                resource
+            );
+            gcp_error_response_with_details(
+                StatusCode::FORBIDDEN,
+                format!(
+                    "Permission '{permission}' denied on resource '{resource}' (or it may not exist)."
+                ),
+                Some("PERMISSION_DENIED"),
+                vec![gcp_error_info(
+                    "IAM_PERMISSION_DENIED",
+                    "iam.googleapis.com",
+                    json!({ "permission": permission, "resource": resource }),
+                )],
+            )
+        }
+        IamDecision::Unauthenticated => gcp_error_response(
+            StatusCode::UNAUTHORIZED,
+            "Request had invalid authentication credentials. Expected OAuth 2 access token."
+                .to_string(),
+            Some("UNAUTHENTICATED"),
+        ),
+    }
+}
+
+async fn get_iam_policy(State(iam): State<GcpIamState>) -> Json<GcpIamPolicy> {
+    Json(iam.policy.read().await.clone())
+}
+
+async fn put_iam_policy(
+    State(iam): State<GcpIamState>,
+    Json(policy): Json<GcpIamPolicy>,
+) -> Json<GcpIamPolicy> {
+    info!(
+        "IAM policy updated ({} service account(s))",
+        policy.service_accounts.len()
+    );
+    *iam.policy.write().await = policy.clone();
+    Json(policy)
+}
+
+async fn delete_iam_policy(State(iam): State<GcpIamState>) -> impl IntoResponse {
+    *iam.policy.write().await = GcpIamPolicy::default();
+    info!("IAM policy cleared");
+    (StatusCode::OK, Json(json!({ "status": "cleared" })))
+}
+
+/// Admin routes for the simulated IAM policy
+pub fn gcp_iam_router(iam: GcpIamState) -> Router {
+    Router::new()
+        .route(
+            "/iam/policy",
+            get(get_iam_policy)
+                .put(put_iam_policy)
+                .delete(delete_iam_policy),
+        )
+        .with_state(iam)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum_test::TestServer;
+
+    fn policy() -> GcpIamPolicy {
+        serde_json::from_value(json!({
+            "serviceAccounts": {
+                "reader@p.iam.gserviceaccount.com": {
+                    "tokens": ["reader-token"],
+                    "permissions": ["secretmanager.versions.access", "secretmanager.secrets.get"]
+                },
+                "admin@p.iam.gserviceaccount.com": {
+                    "tokens": ["admin-token"],
+                    "permissions": ["secretmanager.*"]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_required_permission() {
+        let cases = [
+            (
+                Method::POST,
+                "/v1/projects/p/secrets",
+                Some("secretmanager.secrets.create"),
+            ),
+            (
+                Method::GET,
+                "/v1/projects/p/secrets/s",
+                Some("secretmanager.secrets.get"),
+            ),
+            (
+                Method::DELETE,
+                "/v1/projects/p/secrets/s",
+                Some("secretmanager.secrets.delete"),
+            ),
+            (
+                Method::POST,
+                "/v1/projects/p/secrets/s:addVersion",
+                Some("secretmanager.versions.add"),
+            ),
+            (
+                Method::GET,
+                "/v1/projects/p/secrets/s/versions/latest:access",
+                Some("secretmanager.versions.access"),
+            ),
+            (
+                Method::POST,
+                "/v1/projects/p/secrets/s/versions/3:destroy",
+                Some("secretmanager.versions.destroy"),
+            ),
+            (
+                Method::POST,
+                "/v1/projects/p/locations/global/parameters/cfg/versions",
+                Some("parametermanager.parameterVersions.create"),
+            ),
+            (
+                Method::GET,
+                "/v1/projects/p/locations/global/parameters/cfg/versions/v1:render",
+                Some("parametermanager.parameterVersions.render"),
+            ),
+            (Method::GET, "/v1/projects/p/locations", None),
+            (Method::GET, "/v1/projects/p/secrets/environments", None),
+            (Method::GET, "/health", None),
+        ];
+        for (method, path, expected) in cases {
+            assert_eq!(
+                required_permission(&method, path).map(|(permission, _)| permission),
+                expected,
+                "{method} {path}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_policy_check() {
+        let policy = policy();
+        assert_eq!(
+            policy.check(Some("reader-token"), "secretmanager.versions.access"),
+            IamDecision::Allow
+        );
+        assert_eq!(
+            policy.check(Some("reader-token"), "secretmanager.secrets.create"),
+            IamDecision::Deny {
+                service_account: "reader@p.iam.gserviceaccount.com".to_string()
+            }
+        );
+        assert_eq!(
+            policy.check(Some("admin-token"), "secretmanager.versions.destroy"),
+            IamDecision::Allow
+        );
+        assert_eq!(
+            policy.check(Some("admin-token"), "parametermanager.parameters.get"),
+            IamDecision::Deny {
+                service_account: "admin@p.iam.gserviceaccount.com".to_string()
+            }
+        );
+        // Opt-in per token unless unknown tokens are denied
+        assert_eq!(
+            policy.check(None, "secretmanager.secrets.create"),
+            IamDecision::Allow
+        );
+        let strict = GcpIamPolicy {
+            deny_unknown_tokens: true,
+            ..policy
+        };
+        assert_eq!(
+            strict.check(Some("other"), "secretmanager.secrets.create"),
+            IamDecision::Unauthenticated
+        );
+    }
+
+    #[tokio::test]
+    async fn test_middleware_returns_permission_denied() {
+        let iam = GcpIamState::new(policy());
+        let app = Router::new()
+            .route(
+                "/v1/projects/{project}/secrets",
+                post(|| async { "created" }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                iam.clone(),
+                gcp_iam_middleware,
+            ))
+            .merge(gcp_iam_router(iam));
+        let server = TestServer::new(app).unwrap();
+
+        let denied = server
+            .post("/v1/projects/p/secrets")
+            .authorization_bearer("reader-token")
+            .await;
+        denied.assert_status_forbidden();
+        assert_eq!(
+            denied.json::<serde_json::Value>()["error"]["status"],
+            "PERMISSION_DENIED"
+        );
+
+        server
+            .post("/v1/projects/p/secrets")
+            .authorization_bearer("admin-token")
+            .await
+            .assert_text("created");
+
+        server.delete("/iam/policy").await.assert_status_ok();
+        server
+            .post("/v1/projects/p/secrets")
+            .authorization_bearer("reader-token")
+            .await
+            .assert_text("created");
+    }
+}
@@ -4,15 +4,20 @@
 //! - Sequential version IDs (1, 2, 3, ...)
 //! - Secret key format: "projects/{project}/secrets/{secret}"
 
+pub mod iam;
 pub mod parameter_store;
+pub mod render;
 
 pub use parameter_store::GcpParameterStore;
 
 use super::common::{
-    SecretStore, SecretStoreBackend, SecretStoreEnum, SecretVersion, db_store::DbSecretStore,
+    SecretEntry, SecretStore, SecretStoreBackend, SecretStoreEnum, SecretVersion,
+    db_store::DbSecretStore,
 };
+use crate::admin::AdminStore;
 use anyhow::Result;
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 /// GCP-specific secret store wrapper
@@ -189,37 +194,35 @@ impl GcpSecretStore {
         environment: Option<&str>,
         location: Option<&str>,
     ) -> Vec<String> {
-        // Try to use database if available
-        if let Ok(database_url) = std::env::var("DATABASE_URL") {
-            if let Ok(db) = sea_orm::Database::connect(&database_url).await {
-                use crate::secrets::common::entities::gcp::secret::Column as GcpSecretColumn;
-                use crate::secrets::common::entities::gcp::secret::Entity as GcpSecret;
-                use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+        // Use the database store's connection if available
+        if let SecretStoreEnum::Database(db_store) = self.store.as_ref() {
+            use crate::secrets::common::entities::gcp::secret::Column as GcpSecretColumn;
+            use crate::secrets::common::entities::gcp::secret::Entity as GcpSecret;
+            use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 
-                let prefix = format!("projects/{}/secrets/", project);
+            let prefix = format!("projects/{}/secrets/", project);
 
-                let mut query = GcpSecret::find().filter(GcpSecretColumn::Key.starts_with(&prefix));
+            let mut query = GcpSecret::find().filter(GcpSecretColumn::Key.starts_with(&prefix));
 
-                // Apply environment filter if provided
-                if let Some(env) = environment {
-                    if !env.is_empty() {
-                        query = query.filter(GcpSecretColumn::Environment.eq(env));
-                    }
+            // Apply environment filter if provided
+            if let Some(env) = environment {
+                if !env.is_empty() {
+                    query = query.filter(GcpSecretColumn::Environment.eq(env));
                 }
+            }
 
-                // Apply location filter if provided
-                if let Some(loc) = location {
-                    if !loc.is_empty() {
-                        query = query.filter(GcpSecretColumn::Location.eq(loc));
-                    }
+            // Apply location filter if provided
+            if let Some(loc) = location {
+                if !loc.is_empty() {
+                    query = query.filter(GcpSecretColumn::Location.eq(loc));
                 }
+            }
 
-                if let Ok(secrets) = query.all(&db).await {
-                    return secrets
-                        .into_iter()
-                        .filter_map(|s| s.key.strip_prefix(&prefix).map(|s| s.to_string()))
-                        .collect();
-                }
+            if let Ok(secrets) = query.all(db_store.connection()).await {
+                return secrets
+                    .into_iter()
+                    .filter_map(|s| s.key.strip_prefix(&prefix).map(|s| s.to_string()))
+                    .collect();
             }
         }
 
@@ -276,33 +279,13 @@ impl GcpSecretStore {
             }
 
             // Location is required for secrets too - different locations can have different environments
-            let location_value = match location {
-                Some(loc) => sea_orm::Value::String(Some(Box::new(loc.to_string()))),
-                None => sea_orm::Value::String(None),
-            };
-
-            // Use parameterized query to prevent SQL injection
-            let stmt = sea_orm::Statement::from_sql_and_values(
-                sea_orm::DatabaseBackend::Postgres,
-                "SELECT * FROM gcp.get_secret_environments($1, $2)",
-                vec![
-                    sea_orm::Value::String(Some(Box::new(project.to_string()))),
-                    location_value,
-                ],
-            );
-
-            if let Ok(rows) = db_store.query_all(stmt).await {
-                return rows
-                    .into_iter()
-                    .filter_map(|row| {
-                        // Get the environment column value by column name
-                        row.try_get::<Option<String>>("", "environment")
-                            .or_else(|_| row.try_get::<String>("", "environment").map(Some))
-                            .ok()
-                            .flatten()
-                    })
-                    .collect();
-            }
+            return db_store
+                .list_filter_values(
+                    "get_secret_environments",
+                    "environment",
+                    vec![Some(project.to_string()), location.map(str::to_string)],
+                )
+                .await;
         }
         vec![]
     }
@@ -312,34 +295,37 @@ impl GcpSecretStore {
     pub async fn list_locations(&self, project: &str, resource_type: &str) -> Vec<String> {
         // Try to use existing database store connection first (much faster)
         if let SecretStoreEnum::Database(db_store) = self.store.as_ref() {
-            let stmt = if resource_type == "secrets" {
-                // Use parameterized query to prevent SQL injection
-                sea_orm::Statement::from_sql_and_values(
-                    sea_orm::DatabaseBackend::Postgres,
-                    "SELECT * FROM gcp.get_secret_locations($1)",
-                    vec![sea_orm::Value::String(Some(Box::new(project.to_string())))],
-                )
-            } else {
+            if resource_type != "secrets" {
                 // For parameters, we need location too - but this method doesn't have it
                 // This should be called from parameter_store.rs instead
                 return vec![];
-            };
-
-            if let Ok(rows) = db_store.query_all(stmt).await {
-                return rows
-                    .into_iter()
-                    .filter_map(|row| {
-                        // Get the location column value by column name
-                        row.try_get::<Option<String>>("", "location")
-                            .or_else(|_| row.try_get::<String>("", "location").map(Some))
-                            .ok()
-                            .flatten()
-                    })
-                    .collect();
             }
+
+            return db_store
+                .list_filter_values(
+                    "get_secret_locations",
+                    "location",
+                    vec![Some(project.to_string())],
+                )
+                .await;
         }
         vec![]
     }
 }
 
 // Note: Cannot implement Default for async new() - use GcpSecretStore::new().await instead
+
+#[async_trait::async_trait]
+impl AdminStore for GcpSecretStore {
+    async fn snapshot(&self) -> BTreeMap<String, SecretEntry> {
+        AdminStore::snapshot(self.store.as_ref()).await
+    }
+
+    async fn seed(&self, entries: BTreeMap<String, SecretEntry>) -> Result<()> {
+        AdminStore::seed(self.store.as_ref(), entries).await
+    }
+
+    async fn reset(&self) {
+        AdminStore::reset(self.store.as_ref()).await;
+    }
+}
@@ -4,8 +4,12 @@
 //! - User-provided version IDs (e.g., "v1234567890")
 //! - Parameter key format: "projects/{project}/parameters/{parameter}"
 
-use super::super::common::{SecretStore, SecretVersion};
+use super::super::common::db_store::query_filter_values;
+use super::super::common::{SecretEntry, SecretStore, SecretVersion};
+use crate::admin::AdminStore;
+use anyhow::Result;
 use serde_json::Value;
+use std::collections::BTreeMap;
 
 /// GCP Parameter Manager-specific parameter store wrapper
 #[derive(Clone, Debug)]
@@ -226,29 +230,14 @@ impl GcpParameterStore {
     pub async fn list_environments(&self, project: &str, location: &str) -> Vec<String> {
         if let Ok(database_url) = std::env::var("DATABASE_URL") {
             if let Ok(db) = sea_orm::Database::connect(&database_url).await {
-                use sea_orm::ConnectionTrait;
-                // Use parameterized query to prevent SQL injection
-                let stmt = sea_orm::Statement::from_sql_and_values(
-                    sea_orm::DatabaseBackend::Postgres,
-                    "SELECT * FROM gcp.get_parameter_environments($1, $2)",
-                    vec![
-                        sea_orm::Value::String(Some(Box::new(project.to_string()))),
-                        sea_orm::Value::String(Some(Box::new(location.to_string()))),
-                    ],
-                );
-
-                if let Ok(rows) = db.query_all(stmt).await {
-                    return rows
-                        .into_iter()
-                        .filter_map(|row| {
-                            // Get the environment column value by column name
-                            row.try_get::<Option<String>>("", "environment")
-                                .or_else(|_| row.try_get::<String>("", "environment").map(Some))
-                                .ok()
-                                .flatten()
-                        })
-                        .collect();
-                }
+                return query_filter_values(
+                    &db,
+                    "gcp",
+                    "get_parameter_environments",
+                    "environment",
+                    vec![Some(project.to_string()), Some(location.to_string())],
+                )
+                .await;
             }
         }
         vec![]
@@ -258,28 +247,31 @@ impl GcpParameterStore {
     pub async fn list_locations(&self, project: &str) -> Vec<String> {
         if let Ok(database_url) = std::env::var("DATABASE_URL") {
             if let Ok(db) = sea_orm::Database::connect(&database_url).await {
-                use sea_orm::ConnectionTrait;
-                // Use parameterized query to prevent SQL injection
-                let stmt = sea_orm::Statement::from_sql_and_values(
-                    sea_orm::DatabaseBackend::Postgres,
-                    "SELECT * FROM gcp.get_parameter_locations($1)",
-                    vec![sea_orm::Value::String(Some(Box::new(project.to_string())))],
-                );
-
-                if let Ok(rows) = db.query_all(stmt).await {
-                    return rows
-                        .into_iter()
-                        .filter_map(|row| {
-                            // Get the location column value by column name
-                            row.try_get::<Option<String>>("", "location")
-                                .or_else(|_| row.try_get::<String>("", "location").map(Some))
-                                .ok()
-                                .flatten()
-                        })
-                        .collect();
-                }
+                return query_filter_values(
+                    &db,
+                    "gcp",
+                    "get_parameter_locations",
+                    "location",
+                    vec![Some(project.to_string())],
+                )
+                .await;
             }
         }
         vec![]
     }
 }
+
+#[async_trait::async_trait]
+impl AdminStore for GcpParameterStore {
+    async fn snapshot(&self) -> BTreeMap<String, SecretEntry> {
+        AdminStore::snapshot(&self.store).await
+    }
+
+    async fn seed(&self, entries: BTreeMap<String, SecretEntry>) -> Result<()> {
+        AdminStore::seed(&self.store, entries).await
+    }
+
+    async fn reset(&self) {
+        AdminStore::reset(&self.store).await;
+    }
+}
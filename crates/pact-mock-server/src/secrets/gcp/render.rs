@@ -0,0 +1,194 @@
+//! GCP Parameter Manager rendering
+//!
+//! `:render` resolves Secret Manager references embedded in a parameter payload:
+//! `__REF__("//secretmanager.googleapis.com/projects/{project}/secrets/{secret}/versions/{version}")`
+//! is replaced by the plaintext of that secret version (`latest` resolves to the newest enabled
+//! version). The quotes are optional and may be escaped inside JSON strings. References are
+//! looked up in the mock's Secret Manager store, so a parameter can point at secrets created
+//! through the same server.
+//!
+//! Reference: https://cloud.google.com/secret-manager/parameter-manager/docs/reference-secrets-in-parameter
+
+use super::GcpSecretStore;
+use axum::http::StatusCode;
+use base64::{Engine as _, engine::general_purpose};
+use regex::Regex;
+use std::sync::LazyLock;
+use thiserror::Error;
+
+/// Marker that starts a secret reference in a parameter payload
+const REFERENCE_MARKER: &str = "__REF__(";
+
+static REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"__REF__\(\s*(?:\\?")?//secretmanager\.googleapis\.com/projects/([^/"\\]+)/secrets/([^/"\\]+)/versions/([^/"\\)\s]+)(?:\\?")?\s*\)"#,
+    )
+    .expect("secret reference pattern is valid")
+});
+
+/// Rendering failure, mapped to a GCP error response by the handler
+#[derive(Debug, Error)]
+pub enum RenderError {
+    #[error("Invalid secret reference in parameter payload: {0}")]
+    InvalidReference(String),
+    #[error("Referenced secret version not found or disabled: {0}")]
+    SecretVersionNotFound(String),
+    #[error("Referenced secret version payload is not UTF-8 text: {0}")]
+    InvalidSecretPayload(String),
+}
+
+impl RenderError {
+    /// HTTP status and `google.rpc.Code` name of the error
+    pub fn status(&self) -> (StatusCode, &'static str) {
+        match self {
+            Self::InvalidReference(_) => (StatusCode::BAD_REQUEST, "INVALID_ARGUMENT"),
+            Self::SecretVersionNotFound(_) | Self::InvalidSecretPayload(_) => {
+                (StatusCode::BAD_REQUEST, "FAILED_PRECONDITION")
+            }
+        }
+    }
+}
+
+/// Secret version named by a `__REF__` expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretReference {
+    pub project: String,
+    pub secret: String,
+    pub version: String,
+}
+
+impl SecretReference {
+    /// Resource name of the referenced version
+    pub fn name(&self) -> String {
+        format!(
+            "projects/{}/secrets/{}/versions/{}",
+            self.project, self.secret, self.version
+        )
+    }
+}
+
+/// Secret references in a parameter payload, in order of appearance
+///
+/// Fails if a `__REF__(` marker isn't a well-formed Secret Manager reference.
+pub fn secret_references(payload: &str) -> Result<Vec<SecretReference>, RenderError> {
+    let references: Vec<SecretReference> = REFERENCE
+        .captures_iter(payload)
+        .map(|captures| SecretReference {
+            project: captures[1].to_string(),
+            secret: captures[2].to_string(),
+            version: captures[3].to_string(),
+        })
+        .collect();
+
+    if payload.matches(REFERENCE_MARKER).count() != references.len() {
+        let unparsed = REFERENCE.replace_all(payload, "");
+        let start = unparsed.find(REFERENCE_MARKER).unwrap_or_default();
+        let expression: String = unparsed[start..].chars().take(120).collect();
+        return Err(RenderError::InvalidReference(expression));
+    }
+    Ok(references)
+}
+
+/// Parameter payload with every secret reference replaced by the secret's plaintext
+pub async fn render_payload(
+    secrets: &GcpSecretStore,
+    payload: &str,
+) -> Result<String, RenderError> {
+    let references = secret_references(payload)?;
+    let mut values = Vec::with_capacity(references.len());
+    for reference in &references {
+        values.push(resolve(secrets, reference).await?);
+    }
+
+    let mut values = values.into_iter();
+    Ok(REFERENCE
+        .replace_all(payload, |_: &regex::Captures| {
+            values.next().unwrap_or_default()
+        })
+        .into_owned())
+}
+
+async fn resolve(
+    secrets: &GcpSecretStore,
+    reference: &SecretReference,
+) -> Result<String, RenderError> {
+    let version = if reference.version == "latest" {
+        secrets
+            .get_latest(&reference.project, &reference.secret)
+            .await
+    } else {
+        secrets
+            .get_version(&reference.project, &reference.secret, &reference.version)
+            .await
+    }
+    .ok_or_else(|| RenderError::SecretVersionNotFound(reference.name()))?;
+
+    version
+        .data
+        .get("payload")
+        .and_then(|payload| payload.get("data"))
+        .and_then(|data| data.as_str())
+        .and_then(|data| general_purpose::STANDARD.decode(data).ok())
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| RenderError::InvalidSecretPayload(reference.name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn store_with_secret(value: &str) -> GcpSecretStore {
+        let secrets = GcpSecretStore::new().await;
+        secrets
+            .add_version(
+                "p",
+                "db-password",
+                json!({"payload": {"data": general_purpose::STANDARD.encode(value)}}),
+                None,
+            )
+            .await
+            .unwrap();
+        secrets
+    }
+
+    #[test]
+    fn test_secret_references() {
+        let reference = SecretReference {
+            project: "p".to_string(),
+            secret: "db-password".to_string(),
+            version: "latest".to_string(),
+        };
+        for payload in [
+            r#"password: __REF__("//secretmanager.googleapis.com/projects/p/secrets/db-password/versions/latest")"#,
+            r#"{"password": "__REF__(\"//secretmanager.googleapis.com/projects/p/secrets/db-password/versions/latest\")"}"#,
+            r#"{"password": "__REF__(//secretmanager.googleapis.com/projects/p/secrets/db-password/versions/latest)"}"#,
+        ] {
+            assert_eq!(secret_references(payload).unwrap(), vec![reference.clone()]);
+        }
+        assert!(secret_references("plain: value").unwrap().is_empty());
+        assert!(matches!(
+            secret_references(r#"password: __REF__("projects/p/secrets/db-password")"#),
+            Err(RenderError::InvalidReference(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_render_payload_resolves_references() {
+        let secrets = store_with_secret("s3cr3t").await;
+        let payload = r#"password: __REF__("//secretmanager.googleapis.com/projects/p/secrets/db-password/versions/1")
+again: __REF__("//secretmanager.googleapis.com/projects/p/secrets/db-password/versions/latest")"#;
+        assert_eq!(
+            render_payload(&secrets, payload).await.unwrap(),
+            "password: s3cr3t\nagain: s3cr3t"
+        );
+
+        let missing = r#"__REF__("//secretmanager.googleapis.com/projects/p/secrets/db-password/versions/2")"#;
+        let error = render_payload(&secrets, missing).await.unwrap_err();
+        assert!(matches!(error, RenderError::SecretVersionNotFound(_)));
+        assert_eq!(
+            error.status(),
+            (StatusCode::BAD_REQUEST, "FAILED_PRECONDITION")
+        );
+    }
+}
@@ -0,0 +1,519 @@
+//! # Tenant isolation
+//!
+//! Isolates provider state per tenant (GCP project, AWS account, Azure vault), so tests can
+//! exercise the controller's multi-project fan-out against a single mock server.
+//!
+//! With isolation enabled, each request is resolved to the tenant it targets:
+//! - GCP: the project in the path (`/v1/projects/{project}/...`)
+//! - AWS: the account in an ARN `SecretId`, otherwise the caller's account
+//! - Azure: the vault in the `Host` header (`{vault}.vault.azure.net`), otherwise the default vault
+//!
+//! A credential (AWS access key ID, or bearer token for GCP and Azure) listed in the access
+//! map may only reach its tenants; requests for any other tenant get the provider's 403. The
+//! first tenant listed for an AWS access key is the caller's account. Credentials that aren't
+//! listed reach every tenant, and their AWS account is the default tenant.
+//!
+//! Requests that pass run with their tenant in scope, and the AWS and Azure stores key
+//! entries by tenant (`{tenant}::{name}`), so a secret created in one tenant is not found
+//! (404) from another. GCP keys already include the project. Admin endpoints see the raw
+//! keys, so fixtures for `/admin/seed` use the scoped form.
+//!
+//! Configuration:
+//! - `TENANT_ISOLATION`: Enable tenant isolation (default: false)
+//! - `TENANT_ACCESS`: JSON map of credential to the tenants it may access (`"*"` for all)
+//! - `TENANT_ACCESS_FILE`: Path to a JSON access map
+//! - `TENANT_DEFAULT`: Tenant for requests that don't name one (default: `123456789012` for
+//!   AWS, `test-vault` for Azure)
+//!
+//! ```json
+//! {
+//!   "AKIAPROJECTA": ["111111111111"],
+//!   "AKIAFANOUT": ["111111111111", "222222222222"],
+//!   "vault-a-token": ["vault-a"]
+//! }
+//! ```
+
+use anyhow::{Context, Result};
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::secrets::common::errors::{
+    aws_error_response, aws_error_types, azure_error_codes, azure_error_response,
+    gcp_error_response,
+};
+
+/// Separator between the tenant and the name in scoped store keys
+/// (not valid in AWS or Azure secret names)
+const SEPARATOR: &str = "::";
+
+tokio::task_local! {
+    static CURRENT_TENANT: String;
+}
+
+/// Tenant of the request being handled, if isolation is enabled
+pub fn current_tenant() -> Option<String> {
+    CURRENT_TENANT.try_with(Clone::clone).ok()
+}
+
+/// Store key for `name` in the current tenant
+///
+/// Returns `name` unchanged outside a tenant scope, or if it is already scoped.
+pub fn scoped_key(name: &str) -> String {
+    match current_tenant() {
+        Some(tenant) if !name.starts_with(&format!("{tenant}{SEPARATOR}")) => {
+            format!("{tenant}{SEPARATOR}{name}")
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// Name of a store key as seen by the current tenant, or `None` if it belongs to another
+pub fn visible_name(key: &str) -> Option<String> {
+    match current_tenant() {
+        Some(tenant) => key
+            .strip_prefix(&format!("{tenant}{SEPARATOR}"))
+            .map(str::to_string),
+        None => Some(key.to_string()),
+    }
+}
+
+/// How a provider's requests name their tenant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TenantKind {
+    GcpProject,
+    AwsAccount,
+    AzureVault,
+}
+
+impl TenantKind {
+    pub(crate) fn default_tenant(self) -> &'static str {
+        match self {
+            Self::GcpProject => "",
+            Self::AwsAccount => crate::secrets::aws::DEFAULT_ACCOUNT_ID,
+            Self::AzureVault => crate::secrets::azure::DEFAULT_VAULT_NAME,
+        }
+    }
+
+    /// What the provider calls a tenant ("project", "account", "vault")
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::GcpProject => "project",
+            Self::AwsAccount => "account",
+            Self::AzureVault => "vault",
+        }
+    }
+
+    /// Split a raw store key into its tenant and the name within the tenant
+    ///
+    /// GCP keys carry the project (`projects/{project}/secrets/{secret}` -> `secrets/{secret}`);
+    /// unscoped AWS and Azure keys belong to the default tenant.
+    pub fn split_key(self, key: &str) -> (String, String) {
+        if self == Self::GcpProject {
+            return key
+                .strip_prefix("projects/")
+                .and_then(|rest| rest.split_once('/'))
+                .map_or_else(
+                    || (String::new(), key.to_string()),
+                    |(project, name)| (project.to_string(), name.to_string()),
+                );
+        }
+        key.split_once(SEPARATOR).map_or_else(
+            || (self.default_tenant().to_string(), key.to_string()),
+            |(tenant, name)| (tenant.to_string(), name.to_string()),
+        )
+    }
+}
+
+/// Tenant isolation settings
+#[derive(Debug, Clone)]
+pub struct TenantState {
+    kind: TenantKind,
+    enabled: bool,
+    default_tenant: String,
+    /// Credential -> tenants it may access
+    access: Arc<BTreeMap<String, Vec<String>>>,
+}
+
+impl TenantState {
+    /// Isolation disabled
+    pub fn new(kind: TenantKind) -> Self {
+        Self {
+            kind,
+            enabled: false,
+            default_tenant: kind.default_tenant().to_string(),
+            access: Arc::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_isolation(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    #[must_use]
+    pub fn with_access(mut self, access: BTreeMap<String, Vec<String>>) -> Self {
+        self.access = Arc::new(access);
+        self
+    }
+
+    #[must_use]
+    pub fn with_default_tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.default_tenant = tenant.into();
+        self
+    }
+
+    /// Settings from TENANT_ISOLATION, TENANT_ACCESS / TENANT_ACCESS_FILE and TENANT_DEFAULT
+    pub fn from_env(kind: TenantKind) -> Result<Self> {
+        let enabled = std::env::var("TENANT_ISOLATION")
+            .is_ok_and(|v| v.eq_ignore_ascii_case("true") || v == "1");
+        let mut state = Self::new(kind).with_isolation(enabled);
+        if let Ok(tenant) = std::env::var("TENANT_DEFAULT") {
+            state = state.with_default_tenant(tenant);
+        }
+        if let Ok(access) = std::env::var("TENANT_ACCESS") {
+            state = state.with_access(
+                serde_json::from_str(&access).context("TENANT_ACCESS is not a valid access map")?,
+            );
+        } else if let Ok(path) = std::env::var("TENANT_ACCESS_FILE") {
+            let access = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read TENANT_ACCESS_FILE '{path}'"))?;
+            state = state.with_access(serde_json::from_str(&access).with_context(|| {
+                format!("TENANT_ACCESS_FILE '{path}' is not a valid access map")
+            })?);
+        }
+        if enabled {
+            info!(
+                "Tenant isolation enabled ({} credential(s) restricted)",
+                state.access.len()
+            );
+        }
+        Ok(state)
+    }
+
+    /// Whether `credential` may access `tenant`
+    fn allows(&self, credential: Option<&str>, tenant: &str) -> bool {
+        match credential.and_then(|credential| self.access.get(credential)) {
+            Some(tenants) => tenants.iter().any(|t| t == "*" || t == tenant),
+            None => true,
+        }
+    }
+
+    /// The tenant a credential acts in when the request doesn't name one (AWS)
+    fn home_tenant(&self, credential: Option<&str>) -> String {
+        credential
+            .and_then(|credential| self.access.get(credential))
+            .and_then(|tenants| tenants.iter().find(|t| *t != "*"))
+            .cloned()
+            .unwrap_or_else(|| self.default_tenant.clone())
+    }
+
+    /// Credential identifying the caller
+    fn credential(&self, headers: &HeaderMap) -> Option<String> {
+        let authorization = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+        match self.kind {
+            // AWS4-HMAC-SHA256 Credential=AKID/20240101/us-east-1/secretsmanager/aws4_request, ...
+            TenantKind::AwsAccount => authorization
+                .split_once("Credential=")
+                .and_then(|(_, rest)| rest.split('/').next())
+                .map(str::to_string),
+            TenantKind::GcpProject | TenantKind::AzureVault => authorization
+                .strip_prefix("Bearer ")
+                .map(|token| token.trim().to_string()),
+        }
+    }
+
+    /// Tenant a request targets, or `None` if it isn't tenant-scoped
+    fn tenant(&self, request: &Request, body: &[u8], credential: Option<&str>) -> Option<String> {
+        match self.kind {
+            TenantKind::GcpProject => gcp_project(request.uri().path()).map(str::to_string),
+            TenantKind::AwsAccount => {
+                Some(aws_arn_account(body).unwrap_or_else(|| self.home_tenant(credential)))
+            }
+            TenantKind::AzureVault => Some(
+                request
+                    .headers()
+                    .get(header::HOST)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(azure_vault)
+                    .map_or_else(|| self.default_tenant.clone(), str::to_string),
+            ),
+        }
+    }
+
+    fn forbidden(&self, tenant: &str) -> Response {
+        match self.kind {
+            TenantKind::GcpProject => gcp_error_response(
+                StatusCode::FORBIDDEN,
+                format!("Permission denied on resource project {tenant} (or it may not exist)."),
+                Some("PERMISSION_DENIED"),
+            ),
+            TenantKind::AwsAccount => aws_error_response(
+                StatusCode::FORBIDDEN,
+                aws_error_types::ACCESS_DENIED,
+                format!("User is not authorized to access resources in account {tenant}"),
+            ),
+            TenantKind::AzureVault => azure_error_response(
+                StatusCode::FORBIDDEN,
+                azure_error_codes::FORBIDDEN,
+                format!("The caller does not have secrets permissions on key vault '{tenant}'"),
+            ),
+        }
+    }
+}
+
+/// Project in a GCP API path (`/v1/projects/{project}/...`)
+pub(crate) fn gcp_project(path: &str) -> Option<&str> {
+    let mut segments = path.split('/');
+    segments.find(|segment| *segment == "projects")?;
+    segments.next().filter(|project| !project.is_empty())
+}
+
+/// Vault in a Key Vault host (`{vault}.vault.azure.net[:port]`)
+pub(crate) fn azure_vault(host: &str) -> Option<&str> {
+    let (vault, domain) = host.split_once('.')?;
+    domain.starts_with("vault.").then_some(vault)
+}
+
+/// Account in an ARN `SecretId` (`arn:aws:secretsmanager:{region}:{account}:secret:{name}`)
+fn aws_arn_account(body: &[u8]) -> Option<String> {
+    let body: serde_json::Value = serde_json::from_slice(body).ok()?;
+    let arn = body.get("SecretId")?.as_str()?.strip_prefix("arn:")?;
+    arn.split(':')
+        .nth(3)
+        .filter(|account| !account.is_empty())
+        .map(str::to_string)
+}
+
+/// Tenant isolation middleware
+/// Returns the provider's 403 for tenants the caller's credential isn't granted, and runs
+/// the request with its tenant in scope
+pub async fn tenant_middleware(
+    State(tenants): State<TenantState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !tenants.enabled || crate::is_admin_path(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Tenant isolation: failed to read request body: {}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+    let request = Request::from_parts(parts, Body::from(bytes.clone()));
+
+    let credential = tenants.credential(request.headers());
+    let Some(tenant) = tenants.tenant(&request, &bytes, credential.as_deref()) else {
+        return next.run(request).await;
+    };
+    if !tenants.allows(credential.as_deref(), &tenant) {
+        warn!(
+            tenant = %tenant,
+            "Tenant isolation: denying {} {} for tenant {}",
+            request.method(),
+            request.uri().path(),
+            tenant
+        );
+        return tenants.forbidden(&tenant);
+    }
+    CURRENT_TENANT.scope(tenant, next.run(request)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, http::HeaderValue, middleware, routing::any};
+    use axum_test::TestServer;
+    use serde_json::json;
+
+    fn access() -> BTreeMap<String, Vec<String>> {
+        BTreeMap::from([
+            ("AKIAA".to_string(), vec!["111111111111".to_string()]),
+            ("token-a".to_string(), vec!["vault-a".to_string()]),
+            ("project-a-token".to_string(), vec!["project-a".to_string()]),
+        ])
+    }
+
+    /// Echoes the scoped key for "db-password"
+    fn test_server(kind: TenantKind) -> TestServer {
+        let tenants = TenantState::new(kind)
+            .with_isolation(true)
+            .with_access(access());
+        let app = Router::new()
+            .fallback(any(|| async { scoped_key("db-password") }))
+            .layer(middleware::from_fn_with_state(tenants, tenant_middleware));
+        TestServer::new(app).unwrap()
+    }
+
+    fn sigv4(access_key: &str) -> HeaderValue {
+        HeaderValue::from_str(&format!(
+            "AWS4-HMAC-SHA256 Credential={access_key}/20240101/us-east-1/secretsmanager/aws4_request, SignedHeaders=host, Signature=abc"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tenant_parsing() {
+        assert_eq!(
+            gcp_project("/v1/projects/project-a/secrets/db"),
+            Some("project-a")
+        );
+        assert_eq!(gcp_project("/v1/projects"), None);
+        assert_eq!(azure_vault("vault-a.vault.azure.net:443"), Some("vault-a"));
+        assert_eq!(azure_vault("localhost:1234"), None);
+        assert_eq!(
+            aws_arn_account(
+                br#"{"SecretId":"arn:aws:secretsmanager:us-east-1:222222222222:secret:db"}"#
+            ),
+            Some("222222222222".to_string())
+        );
+        assert_eq!(aws_arn_account(br#"{"SecretId":"db"}"#), None);
+    }
+
+    #[tokio::test]
+    async fn test_aws_scopes_by_caller_account_and_denies_other_accounts() {
+        let server = test_server(TenantKind::AwsAccount);
+
+        let response = server
+            .post("/")
+            .add_header(header::AUTHORIZATION, sigv4("AKIAA"))
+            .json(&json!({ "SecretId": "db-password" }))
+            .await;
+        assert_eq!(response.text(), "111111111111::db-password");
+
+        // Unrestricted credentials act in the default account
+        let response = server
+            .post("/")
+            .add_header(header::AUTHORIZATION, sigv4("AKIAOTHER"))
+            .json(&json!({ "SecretId": "db-password" }))
+            .await;
+        assert_eq!(response.text(), "123456789012::db-password");
+
+        let response = server
+            .post("/")
+            .add_header(header::AUTHORIZATION, sigv4("AKIAA"))
+            .json(&json!({ "SecretId": "arn:aws:secretsmanager:us-east-1:222222222222:secret:db-password" }))
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.json::<serde_json::Value>()["__type"],
+            "AccessDeniedException"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_azure_scopes_by_vault_host() {
+        let server = test_server(TenantKind::AzureVault);
+
+        let response = server
+            .get("/secrets/db-password")
+            .add_header(
+                header::HOST,
+                HeaderValue::from_static("vault-a.vault.azure.net"),
+            )
+            .authorization_bearer("token-a")
+            .await;
+        assert_eq!(response.text(), "vault-a::db-password");
+
+        server
+            .get("/secrets/db-password")
+            .add_header(
+                header::HOST,
+                HeaderValue::from_static("vault-b.vault.azure.net"),
+            )
+            .authorization_bearer("token-a")
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+
+        // Hosts that don't name a vault use the default vault
+        let response = server.get("/secrets/db-password").await;
+        assert_eq!(response.text(), "test-vault::db-password");
+    }
+
+    #[tokio::test]
+    async fn test_gcp_denies_projects_outside_the_access_map() {
+        let server = test_server(TenantKind::GcpProject);
+
+        server
+            .get("/v1/projects/project-a/secrets")
+            .authorization_bearer("project-a-token")
+            .await
+            .assert_status_ok();
+        let response = server
+            .get("/v1/projects/project-b/secrets")
+            .authorization_bearer("project-a-token")
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.json::<serde_json::Value>()["error"]["status"],
+            "PERMISSION_DENIED"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_secrets_are_invisible_to_other_tenants() {
+        let store = crate::secrets::azure::AzureSecretStore::new().await;
+        CURRENT_TENANT
+            .scope("vault-a".to_string(), async {
+                store
+                    .set_secret("db-password", "a".to_string())
+                    .await
+                    .unwrap();
+            })
+            .await;
+
+        CURRENT_TENANT
+            .scope("vault-b".to_string(), async {
+                assert!(!store.exists("db-password").await);
+                assert!(store.list_all_secrets().await.is_empty());
+                // The same name in another tenant is a separate secret
+                store
+                    .set_secret("db-password", "b".to_string())
+                    .await
+                    .unwrap();
+            })
+            .await;
+
+        CURRENT_TENANT
+            .scope("vault-a".to_string(), async {
+                assert_eq!(store.list_all_secrets().await, vec!["db-password"]);
+                let latest = store.get_latest("db-password").await.unwrap();
+                assert_eq!(latest.data["value"], "a");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_keys_outside_a_tenant_scope_are_unchanged() {
+        assert_eq!(scoped_key("db-password"), "db-password");
+        assert_eq!(
+            visible_name("vault-a::db-password"),
+            Some("vault-a::db-password".to_string())
+        );
+
+        CURRENT_TENANT
+            .scope("vault-a".to_string(), async {
+                assert_eq!(scoped_key("db-password"), "vault-a::db-password");
+                assert_eq!(scoped_key("vault-a::db-password"), "vault-a::db-password");
+                assert_eq!(
+                    visible_name("vault-a::db-password"),
+                    Some("db-password".to_string())
+                );
+                assert_eq!(visible_name("vault-b::db-password"), None);
+            })
+            .await;
+    }
+}
@@ -0,0 +1,249 @@
+//! # TLS
+//!
+//! Optional TLS termination for the provider mock servers.
+//!
+//! Real provider SDKs refuse plain-HTTP endpoints in some configurations, so the mock servers
+//! can serve HTTPS directly. With `TLS_ENABLED=true` a server uses the certificate and key
+//! from `TLS_CERT_PATH` / `TLS_KEY_PATH`, or generates a self-signed certificate at startup
+//! for the names in `TLS_SUBJECT_ALT_NAMES`. A generated certificate can be written to
+//! `TLS_GENERATED_CERT_PATH` so clients can add it to their trust store.
+//!
+//! Environment Variables:
+//! - TLS_ENABLED: Serve HTTPS instead of HTTP (default: false)
+//! - TLS_CERT_PATH: PEM certificate chain (requires TLS_KEY_PATH)
+//! - TLS_KEY_PATH: PEM private key (requires TLS_CERT_PATH)
+//! - TLS_SUBJECT_ALT_NAMES: Comma-separated names for the self-signed certificate
+//!   (default: localhost,127.0.0.1)
+//! - TLS_GENERATED_CERT_PATH: Where to write the self-signed certificate (optional)
+
+use anyhow::{Context, Result, anyhow};
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tracing::info;
+
+/// Names the self-signed certificate is valid for when TLS_SUBJECT_ALT_NAMES is not set
+pub const DEFAULT_SUBJECT_ALT_NAMES: &str = "localhost,127.0.0.1";
+
+/// Where the server certificate comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlsCertSource {
+    /// Certificate and key loaded from PEM files
+    Files {
+        cert_path: PathBuf,
+        key_path: PathBuf,
+    },
+    /// Self-signed certificate generated at startup
+    SelfSigned {
+        subject_alt_names: Vec<String>,
+        /// Write the generated certificate here (PEM)
+        output_path: Option<PathBuf>,
+    },
+}
+
+/// TLS settings for a mock server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsSettings {
+    pub source: TlsCertSource,
+}
+
+impl TlsSettings {
+    /// Read TLS settings from the environment
+    /// Returns `Ok(None)` when TLS_ENABLED is not set to true
+    pub fn from_env() -> Result<Option<Self>> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Read TLS settings from a key lookup (used by `from_env` and tests)
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Option<Self>> {
+        let enabled = lookup("TLS_ENABLED")
+            .is_some_and(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1" | "yes"));
+        if !enabled {
+            return Ok(None);
+        }
+
+        let non_empty = |key: &str| lookup(key).filter(|v| !v.trim().is_empty());
+        let source = match (non_empty("TLS_CERT_PATH"), non_empty("TLS_KEY_PATH")) {
+            (Some(cert_path), Some(key_path)) => TlsCertSource::Files {
+                cert_path: PathBuf::from(cert_path),
+                key_path: PathBuf::from(key_path),
+            },
+            (None, None) => {
+                let names = non_empty("TLS_SUBJECT_ALT_NAMES")
+                    .unwrap_or_else(|| DEFAULT_SUBJECT_ALT_NAMES.to_string());
+                let subject_alt_names: Vec<String> = names
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                if subject_alt_names.is_empty() {
+                    return Err(anyhow!("TLS_SUBJECT_ALT_NAMES must list at least one name"));
+                }
+                TlsCertSource::SelfSigned {
+                    subject_alt_names,
+                    output_path: non_empty("TLS_GENERATED_CERT_PATH").map(PathBuf::from),
+                }
+            }
+            _ => {
+                return Err(anyhow!(
+                    "TLS_CERT_PATH and TLS_KEY_PATH must be set together"
+                ));
+            }
+        };
+
+        Ok(Some(Self { source }))
+    }
+
+    /// Build the rustls configuration, generating a certificate if needed
+    pub async fn rustls_config(&self) -> Result<RustlsConfig> {
+        // rustls needs a process-wide crypto provider; ignore the error if one is installed
+        let _ = rustls::crypto::ring::default_provider().install_default();
+
+        match &self.source {
+            TlsCertSource::Files {
+                cert_path,
+                key_path,
+            } => {
+                info!(
+                    "TLS: loading certificate from {} and key from {}",
+                    cert_path.display(),
+                    key_path.display()
+                );
+                RustlsConfig::from_pem_file(cert_path, key_path)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to load TLS certificate '{}' / key '{}'",
+                            cert_path.display(),
+                            key_path.display()
+                        )
+                    })
+            }
+            TlsCertSource::SelfSigned {
+                subject_alt_names,
+                output_path,
+            } => {
+                let (cert_pem, key_pem) = generate_self_signed(subject_alt_names)?;
+                info!(
+                    "TLS: generated self-signed certificate for {}",
+                    subject_alt_names.join(", ")
+                );
+                if let Some(path) = output_path {
+                    std::fs::write(path, &cert_pem).with_context(|| {
+                        format!("Failed to write certificate to '{}'", path.display())
+                    })?;
+                    info!("TLS: wrote self-signed certificate to {}", path.display());
+                }
+                RustlsConfig::from_pem(cert_pem.into_bytes(), key_pem.into_bytes())
+                    .await
+                    .context("Failed to build TLS configuration from generated certificate")
+            }
+        }
+    }
+}
+
+/// Generate a self-signed certificate, returning (certificate PEM, private key PEM)
+pub fn generate_self_signed(subject_alt_names: &[String]) -> Result<(String, String)> {
+    let rcgen::CertifiedKey { cert, key_pair } =
+        rcgen::generate_simple_self_signed(subject_alt_names.to_vec())
+            .context("Failed to generate self-signed certificate")?;
+    Ok((cert.pem(), key_pair.serialize_pem()))
+}
+
+/// Serve `app` on `addr`, over HTTPS when TLS_ENABLED=true and plain HTTP otherwise
+///
+/// `name` is used in the startup log line (e.g. "GCP Mock server").
+pub async fn serve(app: Router, addr: SocketAddr, name: &str) -> Result<()> {
+    match TlsSettings::from_env()? {
+        Some(settings) => {
+            let config = settings.rustls_config().await?;
+            info!("✅ {} ready at https://{}", name, addr);
+            axum_server::bind_rustls(addr, config)
+                .serve(app.into_make_service())
+                .await
+                .context("HTTPS server failed")
+        }
+        None => {
+            info!("✅ {} ready at http://{}", name, addr);
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("Failed to bind {addr}"))?;
+            axum::serve(listener, app)
+                .await
+                .context("HTTP server failed")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn settings(vars: &[(&str, &str)]) -> Result<Option<TlsSettings>> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect();
+        TlsSettings::from_lookup(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn test_tls_disabled_by_default() {
+        assert_eq!(settings(&[]).unwrap(), None);
+        assert_eq!(settings(&[("TLS_ENABLED", "false")]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_tls_self_signed_settings() {
+        let tls = settings(&[
+            ("TLS_ENABLED", "true"),
+            ("TLS_SUBJECT_ALT_NAMES", "localhost, gcp-mock-server ,"),
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            tls.source,
+            TlsCertSource::SelfSigned {
+                subject_alt_names: vec!["localhost".to_string(), "gcp-mock-server".to_string()],
+                output_path: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_tls_cert_and_key_required_together() {
+        assert!(settings(&[("TLS_ENABLED", "true"), ("TLS_CERT_PATH", "/tls/tls.crt")]).is_err());
+        let tls = settings(&[
+            ("TLS_ENABLED", "true"),
+            ("TLS_CERT_PATH", "/tls/tls.crt"),
+            ("TLS_KEY_PATH", "/tls/tls.key"),
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            tls.source,
+            TlsCertSource::Files {
+                cert_path: PathBuf::from("/tls/tls.crt"),
+                key_path: PathBuf::from("/tls/tls.key"),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generated_certificate_builds_rustls_config() {
+        let (cert, key) = generate_self_signed(&["localhost".to_string()]).unwrap();
+        assert!(cert.starts_with("-----BEGIN CERTIFICATE-----"));
+        assert!(key.contains("PRIVATE KEY"));
+
+        let tls = TlsSettings {
+            source: TlsCertSource::SelfSigned {
+                subject_alt_names: vec!["localhost".to_string()],
+                output_path: None,
+            },
+        };
+        assert!(tls.rustls_config().await.is_ok());
+    }
+}
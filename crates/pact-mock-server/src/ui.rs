@@ -0,0 +1,276 @@
+//! # Web UI
+//!
+//! Optional browser UI for inspecting a mock server's state while debugging an integration
+//! environment, instead of curl-ing the provider API. It lists the projects (GCP), accounts
+//! (AWS) or vaults (Azure) with their secrets, versions and AWS staging labels.
+//!
+//! Version values are redacted unless revealed with the page's toggle (`?reveal=true`). The
+//! page is embedded in the binary and reads the same state as `GET /admin/snapshot`.
+//!
+//! Configuration:
+//! - `MOCK_UI_ENABLED`: Serve the UI (default: false)
+//! - `GET /ui`: The UI page
+//! - `GET /ui/api/state`: Stored entries grouped by project/account/vault
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    response::{Html, Redirect},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::admin::AdminState;
+use crate::tenant::TenantKind;
+
+/// Value shown in place of version data that hasn't been revealed
+pub const REDACTED: &str = "<redacted>";
+
+const INDEX_HTML: &str = include_str!("ui.html");
+
+/// Web UI settings
+#[derive(Debug, Clone)]
+pub struct UiState {
+    admin: AdminState,
+    kind: TenantKind,
+    enabled: bool,
+}
+
+impl UiState {
+    /// UI over the stores of `admin`, grouped by `kind`'s tenants (disabled)
+    pub fn new(admin: AdminState, kind: TenantKind) -> Self {
+        Self {
+            admin,
+            kind,
+            enabled: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Settings from MOCK_UI_ENABLED
+    pub fn from_env(admin: AdminState, kind: TenantKind) -> Self {
+        let enabled = std::env::var("MOCK_UI_ENABLED")
+            .is_ok_and(|v| v.eq_ignore_ascii_case("true") || v == "1");
+        Self::new(admin, kind).with_enabled(enabled)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Stored entries grouped by tenant, with version data redacted unless `reveal`
+    pub async fn view(&self, reveal: bool) -> UiView {
+        let labels = self.admin.labels().await;
+        let mut tenants: BTreeMap<String, Vec<UiEntry>> = BTreeMap::new();
+
+        for (section, entries) in self.admin.snapshot().await {
+            let section_labels = labels.get(&section);
+            for (key, entry) in entries {
+                let (tenant, name) = self.kind.split_key(&key);
+                let entry_labels = section_labels.and_then(|labels| labels.get(&key));
+                let versions = entry
+                    .versions
+                    .into_iter()
+                    .map(|version| UiVersion {
+                        labels: entry_labels
+                            .map(|labels| {
+                                labels
+                                    .iter()
+                                    .filter(|(_, version_id)| **version_id == version.version_id)
+                                    .map(|(label, _)| label.clone())
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                        data: if reveal {
+                            version.data
+                        } else {
+                            Value::String(REDACTED.to_string())
+                        },
+                        version_id: version.version_id,
+                        enabled: version.enabled,
+                        created_at: version.created_at,
+                    })
+                    .collect();
+                tenants.entry(tenant).or_default().push(UiEntry {
+                    section: section.clone(),
+                    name,
+                    key,
+                    enabled: !entry.disabled,
+                    metadata: entry.metadata,
+                    versions,
+                });
+            }
+        }
+
+        UiView {
+            tenant_label: self.kind.label(),
+            revealed: reveal,
+            tenants: tenants
+                .into_iter()
+                .map(|(name, entries)| UiTenant { name, entries })
+                .collect(),
+        }
+    }
+}
+
+/// State shown by the UI
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiView {
+    /// What the provider calls a tenant ("project", "account", "vault")
+    pub tenant_label: &'static str,
+    pub revealed: bool,
+    pub tenants: Vec<UiTenant>,
+}
+
+/// A project, account or vault
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiTenant {
+    pub name: String,
+    pub entries: Vec<UiEntry>,
+}
+
+/// A secret or parameter
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiEntry {
+    /// Store section ("secrets", "parameters")
+    pub section: String,
+    /// Name within the tenant
+    pub name: String,
+    /// Raw store key, as used by `/admin/seed`
+    pub key: String,
+    pub enabled: bool,
+    pub metadata: Value,
+    pub versions: Vec<UiVersion>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiVersion {
+    pub version_id: String,
+    pub enabled: bool,
+    pub created_at: u64,
+    /// Labels pointing at this version (AWS staging labels)
+    pub labels: Vec<String>,
+    pub data: Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ViewQuery {
+    #[serde(default)]
+    reveal: bool,
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+async fn view(State(ui): State<UiState>, Query(query): Query<ViewQuery>) -> Json<UiView> {
+    Json(ui.view(query.reveal).await)
+}
+
+/// UI routes, merged into each mock server's router (no routes unless enabled)
+pub fn ui_router(ui: UiState) -> Router {
+    if !ui.enabled {
+        return Router::new();
+    }
+    Router::new()
+        .route("/ui", get(|| async { Redirect::permanent("/ui/") }))
+        .route("/ui/", get(index))
+        .route("/ui/api/state", get(view))
+        .with_state(ui)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::common::SecretStore;
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use serde_json::json;
+
+    async fn test_server(kind: TenantKind, fixture: Value) -> TestServer {
+        let admin = AdminState::new().with_store("secrets", SecretStore::new());
+        admin
+            .seed(serde_json::from_value(fixture).unwrap(), false)
+            .await
+            .unwrap();
+        TestServer::new(ui_router(UiState::new(admin, kind).with_enabled(true))).unwrap()
+    }
+
+    fn version(version_id: &str, data: Value) -> Value {
+        json!({ "version_id": version_id, "data": data, "enabled": true, "created_at": 1700000000 })
+    }
+
+    #[tokio::test]
+    async fn test_view_groups_by_tenant_and_redacts() {
+        let server = test_server(
+            TenantKind::AzureVault,
+            json!({
+                "secrets": {
+                    "db-password": { "versions": [version("v1", json!({"value": "s3cr3t"}))], "metadata": {} },
+                    "vault-b::api-key": { "versions": [version("v1", json!({"value": "k3y"}))], "metadata": {} }
+                }
+            }),
+        )
+        .await;
+
+        let body: Value = server.get("/ui/api/state").await.json();
+        assert_eq!(body["tenantLabel"], "vault");
+        assert_eq!(body["tenants"][0]["name"], "test-vault");
+        assert_eq!(body["tenants"][0]["entries"][0]["name"], "db-password");
+        assert_eq!(body["tenants"][1]["name"], "vault-b");
+        assert_eq!(body["tenants"][1]["entries"][0]["key"], "vault-b::api-key");
+        assert_eq!(
+            body["tenants"][1]["entries"][0]["versions"][0]["data"],
+            REDACTED
+        );
+        assert!(!body.to_string().contains("k3y"));
+
+        let body: Value = server.get("/ui/api/state?reveal=true").await.json();
+        assert_eq!(
+            body["tenants"][1]["entries"][0]["versions"][0]["data"]["value"],
+            "k3y"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_view_splits_gcp_projects() {
+        let server = test_server(
+            TenantKind::GcpProject,
+            json!({
+                "secrets": {
+                    "projects/project-a/secrets/db-password": { "versions": [version("1", json!({}))], "metadata": {} }
+                }
+            }),
+        )
+        .await;
+
+        let body: Value = server.get("/ui/api/state").await.json();
+        assert_eq!(body["tenants"][0]["name"], "project-a");
+        assert_eq!(
+            body["tenants"][0]["entries"][0]["name"],
+            "secrets/db-password"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disabled_ui_has_no_routes() {
+        let admin = AdminState::new().with_store("secrets", SecretStore::new());
+        let server =
+            TestServer::new(ui_router(UiState::new(admin, TenantKind::AwsAccount))).unwrap();
+        server
+            .get("/ui/")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,1257 @@
+//! # Pact verification
+//!
+//! Validates incoming requests against the Pact interactions loaded from the broker, turning
+//! the mock servers into live provider-verification tools: a request the controller makes
+//! that no contract covers (or that breaks a contract's matching rules) is reported instead
+//! of silently getting a default mock response.
+//!
+//! A request matches an interaction when its method, path, query, the headers the interaction
+//! expects, and its body all match. Matching rules from V2, V3 and V4 pacts are applied
+//! (`type`, `regex`, `equality`, `include`, `integer`, `decimal`, `number`, `boolean`, `null`,
+//! with `min` / `max` array lengths); rules cascade to child values, as in Pact. Request bodies
+//! may not contain keys the interaction doesn't expect.
+//!
+//! Configuration:
+//! - `PACT_VERIFICATION_MODE`: `off` (default), `report` (log mismatches and add an
+//!   `x-pact-verification: mismatch` response header) or `enforce` (reject mismatching
+//!   requests with 400 and a mismatch report)
+//! - `GET /pact/verification`: Mode, counters and the most recent mismatch reports
+//! - `DELETE /pact/verification`: Reset the counters
+
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// Number of mismatch reports kept for `GET /pact/verification`
+const RECENT_MISMATCHES: usize = 20;
+
+/// What to do with requests that match no interaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationMode {
+    Off,
+    Report,
+    Enforce,
+}
+
+impl VerificationMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "" | "off" | "false" => Some(Self::Off),
+            "report" => Some(Self::Report),
+            "enforce" | "true" => Some(Self::Enforce),
+            _ => None,
+        }
+    }
+}
+
+/// A single difference between a request and an interaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Mismatch {
+    /// "method", "path", "query", "header" or "body"
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    /// Body path (`$.Tags[0].Key`), header or query parameter name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub mismatch: String,
+}
+
+impl Mismatch {
+    fn new(kind: &'static str, path: Option<String>, mismatch: String) -> Self {
+        Self {
+            kind,
+            path,
+            mismatch,
+        }
+    }
+}
+
+/// Mismatches between a request and one candidate interaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InteractionMismatch {
+    pub interaction: String,
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// Why a request failed verification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MismatchReport {
+    pub method: String,
+    pub path: String,
+    /// Interactions with the same method and path, and how the request differs from each
+    pub candidates: Vec<InteractionMismatch>,
+}
+
+/// One rule from a `matchingRules` block
+#[derive(Debug, Clone)]
+enum Matcher {
+    Equality,
+    Regex(String),
+    Type {
+        min: Option<usize>,
+        max: Option<usize>,
+    },
+    Include(String),
+    Integer,
+    Decimal,
+    Number,
+    Boolean,
+    Null,
+}
+
+impl Matcher {
+    fn from_json(value: &Value) -> Option<Self> {
+        let kind = value
+            .get("match")
+            .and_then(Value::as_str)
+            .or_else(|| value.get("regex").map(|_| "regex"))
+            .unwrap_or("type");
+        let bound = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_u64)
+                .and_then(|v| usize::try_from(v).ok())
+        };
+        Some(match kind {
+            "equality" => Self::Equality,
+            "regex" => Self::Regex(value.get("regex")?.as_str()?.to_string()),
+            "include" => Self::Include(value.get("value")?.as_str()?.to_string()),
+            "integer" => Self::Integer,
+            "decimal" => Self::Decimal,
+            "number" => Self::Number,
+            "boolean" => Self::Boolean,
+            "null" => Self::Null,
+            // type, min, max, and formats (date, time, timestamp, contentType, ...) this
+            // mock server can't check beyond the value's type
+            _ => Self::Type {
+                min: bound("min"),
+                max: bound("max"),
+            },
+        })
+    }
+
+    fn is_type(&self) -> bool {
+        matches!(self, Self::Type { .. })
+    }
+
+    /// Check a value, returning the reason it doesn't match
+    fn check(&self, expected: &Value, actual: &Value) -> Result<(), String> {
+        let ok = |matched: bool, reason: String| if matched { Ok(()) } else { Err(reason) };
+        match self {
+            Self::Equality => ok(
+                expected == actual,
+                format!("Expected {expected} to equal {actual}"),
+            ),
+            Self::Regex(pattern) => {
+                let text = scalar_text(actual);
+                let matched = Regex::new(&format!("^(?:{pattern})$"))
+                    .ok()
+                    .zip(text.as_ref())
+                    .is_some_and(|(regex, text)| regex.is_match(text));
+                ok(matched, format!("Expected {actual} to match '{pattern}'"))
+            }
+            Self::Type { min, max } => {
+                if !same_type(expected, actual) {
+                    return Err(format!(
+                        "Expected {} ({}) but received {} ({})",
+                        expected,
+                        type_name(expected),
+                        actual,
+                        type_name(actual)
+                    ));
+                }
+                if let Value::Array(items) = actual {
+                    if let Some(min) = min.filter(|min| items.len() < *min) {
+                        return Err(format!(
+                            "Expected at least {min} item(s) but received {}",
+                            items.len()
+                        ));
+                    }
+                    if let Some(max) = max.filter(|max| items.len() > *max) {
+                        return Err(format!(
+                            "Expected at most {max} item(s) but received {}",
+                            items.len()
+                        ));
+                    }
+                }
+                Ok(())
+            }
+            Self::Include(part) => ok(
+                scalar_text(actual).is_some_and(|text| text.contains(part.as_str())),
+                format!("Expected {actual} to include '{part}'"),
+            ),
+            Self::Integer => ok(
+                actual.is_i64() || actual.is_u64(),
+                format!("Expected an integer but received {actual}"),
+            ),
+            Self::Decimal => ok(
+                actual.is_f64(),
+                format!("Expected a decimal number but received {actual}"),
+            ),
+            Self::Number => ok(
+                actual.is_number(),
+                format!("Expected a number but received {actual}"),
+            ),
+            Self::Boolean => ok(
+                actual.is_boolean(),
+                format!("Expected a boolean but received {actual}"),
+            ),
+            Self::Null => ok(
+                actual.is_null(),
+                format!("Expected null but received {actual}"),
+            ),
+        }
+    }
+}
+
+/// Matchers for one location; all must match (Pact's default `AND` combination)
+#[derive(Debug, Clone, Default)]
+struct RuleSet {
+    matchers: Vec<Matcher>,
+    combine_or: bool,
+}
+
+impl RuleSet {
+    fn from_json(value: &Value) -> Self {
+        // V3/V4: { "matchers": [...], "combine": "AND" }; V2: a single matcher object
+        let matchers = match value.get("matchers").and_then(Value::as_array) {
+            Some(matchers) => matchers.iter().filter_map(Matcher::from_json).collect(),
+            None => Matcher::from_json(value).into_iter().collect(),
+        };
+        Self {
+            matchers,
+            combine_or: value.get("combine").and_then(Value::as_str) == Some("OR"),
+        }
+    }
+
+    fn check(&self, expected: &Value, actual: &Value) -> Result<(), String> {
+        let mut errors = self
+            .matchers
+            .iter()
+            .filter_map(|matcher| matcher.check(expected, actual).err());
+        if self.combine_or {
+            let errors: Vec<String> = errors.collect();
+            if errors.len() < self.matchers.len() {
+                Ok(())
+            } else {
+                Err(errors.join("; "))
+            }
+        } else {
+            errors.next().map_or(Ok(()), Err)
+        }
+    }
+
+    /// Whether the rules match values by type (array items against a template)
+    fn is_type(&self) -> bool {
+        self.matchers.iter().any(Matcher::is_type)
+    }
+}
+
+/// A segment of a body path
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Field(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// Parse a matching rule path (`$.a.b[0]`, `$.a[*]`, `$['a.b']`)
+fn parse_rule_path(path: &str) -> Option<Vec<Segment>> {
+    let mut rest = path.strip_prefix('$')?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("['") {
+            let end = after.find("']")?;
+            segments.push(Segment::Field(after[..end].to_string()));
+            rest = &after[end + 2..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']')?;
+            let index = &after[..end];
+            segments.push(if index == "*" {
+                Segment::Wildcard
+            } else {
+                Segment::Index(index.parse().ok()?)
+            });
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            let field = &after[..end];
+            segments.push(if field == "*" {
+                Segment::Wildcard
+            } else {
+                Segment::Field(field.to_string())
+            });
+            rest = &after[end..];
+        } else {
+            return None;
+        }
+    }
+    Some(segments)
+}
+
+fn format_path(path: &[Segment]) -> String {
+    let mut text = "$".to_string();
+    for segment in path {
+        match segment {
+            Segment::Field(name) => {
+                text.push('.');
+                text.push_str(name);
+            }
+            Segment::Index(index) => text.push_str(&format!("[{index}]")),
+            Segment::Wildcard => text.push_str("[*]"),
+        }
+    }
+    text
+}
+
+/// Matching rules of an interaction's request
+#[derive(Debug, Clone, Default)]
+struct RequestRules {
+    path: Option<RuleSet>,
+    query: HashMap<String, RuleSet>,
+    header: HashMap<String, RuleSet>,
+    body: Vec<(Vec<Segment>, RuleSet)>,
+}
+
+impl RequestRules {
+    fn from_json(rules: Option<&Value>) -> Self {
+        let mut parsed = Self::default();
+        let Some(rules) = rules.and_then(Value::as_object) else {
+            return parsed;
+        };
+        for (key, value) in rules {
+            match key.as_str() {
+                // V3/V4 categories
+                "path" => parsed.path = Some(RuleSet::from_json(value)),
+                "query" | "header" | "headers" | "body" => {
+                    for (name, rule) in value.as_object().into_iter().flatten() {
+                        parsed.add(key, name, rule);
+                    }
+                }
+                // V2: flat "$.body.a", "$.query.k", "$.headers.Name", "$.path"
+                v2 => {
+                    if v2 == "$.path" {
+                        parsed.path = Some(RuleSet::from_json(value));
+                    } else if let Some(rest) = v2.strip_prefix("$.body") {
+                        parsed.add("body", &format!("${rest}"), value);
+                    } else if let Some(name) = v2.strip_prefix("$.query.") {
+                        parsed.add("query", name, value);
+                    } else if let Some(name) = v2.strip_prefix("$.headers.") {
+                        parsed.add("header", name, value);
+                    }
+                }
+            }
+        }
+        parsed
+    }
+
+    fn add(&mut self, category: &str, name: &str, rule: &Value) {
+        let rules = RuleSet::from_json(rule);
+        match category {
+            "query" => {
+                self.query.insert(name.to_string(), rules);
+            }
+            "header" | "headers" => {
+                self.header.insert(name.to_lowercase(), rules);
+            }
+            _ => {
+                if let Some(path) = parse_rule_path(name) {
+                    self.body.push((path, rules));
+                }
+            }
+        }
+    }
+
+    /// The most specific body rule for `path`: rules on the path itself or an ancestor apply
+    /// (they cascade), the longest wins, and literal segments beat wildcards
+    fn body_rule(&self, path: &[Segment]) -> Option<(&RuleSet, bool)> {
+        self.body
+            .iter()
+            .filter(|(rule_path, _)| {
+                rule_path.len() <= path.len()
+                    && rule_path
+                        .iter()
+                        .zip(path)
+                        .all(|(rule, actual)| *rule == Segment::Wildcard || rule == actual)
+            })
+            .max_by_key(|(rule_path, _)| {
+                let literals = rule_path
+                    .iter()
+                    .filter(|segment| **segment != Segment::Wildcard)
+                    .count();
+                (rule_path.len(), literals)
+            })
+            .map(|(rule_path, rules)| (rules, rule_path.len() == path.len()))
+    }
+}
+
+/// A Pact interaction's expected request
+#[derive(Debug, Clone)]
+struct Interaction {
+    description: String,
+    method: String,
+    path: String,
+    query: Option<HashMap<String, Vec<String>>>,
+    headers: Vec<(String, String)>,
+    body: Option<Value>,
+    rules: RequestRules,
+}
+
+impl Interaction {
+    fn from_json(interaction: &Value) -> Option<Self> {
+        // V4 interactions carry a type; only HTTP interactions describe requests
+        if let Some(kind) = interaction.get("type").and_then(Value::as_str) {
+            if kind != "Synchronous/HTTP" {
+                return None;
+            }
+        }
+        let request = interaction.get("request")?;
+        let query = request.get("query").map(|query| match query {
+            Value::String(text) => parse_query(text),
+            Value::Object(params) => params
+                .iter()
+                .map(|(name, values)| {
+                    let values = match values {
+                        Value::Array(values) => values.iter().filter_map(scalar_text).collect(),
+                        value => scalar_text(value).into_iter().collect(),
+                    };
+                    (name.clone(), values)
+                })
+                .collect(),
+            _ => HashMap::new(),
+        });
+        let headers = request
+            .get("headers")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, value)| {
+                let value = match value {
+                    Value::Array(values) => values
+                        .iter()
+                        .filter_map(scalar_text)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    value => scalar_text(value)?,
+                };
+                Some((name.to_lowercase(), value))
+            })
+            .collect();
+        // V4 wraps the body as { "content": ..., "contentType": ... }
+        let body = request.get("body").map(|body| match body {
+            Value::Object(v4) if v4.contains_key("content") && v4.contains_key("contentType") => {
+                v4["content"].clone()
+            }
+            body => body.clone(),
+        });
+
+        Some(Self {
+            description: interaction
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or("unnamed interaction")
+                .to_string(),
+            method: request.get("method")?.as_str()?.to_uppercase(),
+            path: request.get("path")?.as_str()?.to_string(),
+            query,
+            headers,
+            body,
+            rules: RequestRules::from_json(request.get("matchingRules")),
+        })
+    }
+
+    fn matches_route(&self, method: &Method, path: &str) -> bool {
+        if !self.method.eq_ignore_ascii_case(method.as_str()) {
+            return false;
+        }
+        match &self.rules.path {
+            Some(rules) => rules
+                .check(
+                    &Value::String(self.path.clone()),
+                    &Value::String(path.to_string()),
+                )
+                .is_ok(),
+            None => self.path == path,
+        }
+    }
+
+    /// Differences between the request and this interaction (method and path already match)
+    fn mismatches(
+        &self,
+        query: &HashMap<String, Vec<String>>,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+        self.compare_query(query, &mut mismatches);
+        self.compare_headers(headers, &mut mismatches);
+        self.compare_body(headers, body, &mut mismatches);
+        mismatches
+    }
+
+    fn compare_query(&self, actual: &HashMap<String, Vec<String>>, mismatches: &mut Vec<Mismatch>) {
+        let Some(expected) = &self.query else {
+            return;
+        };
+        for (name, expected_values) in expected {
+            let Some(actual_values) = actual.get(name) else {
+                mismatches.push(Mismatch::new(
+                    "query",
+                    Some(name.clone()),
+                    format!("Expected query parameter '{name}' but it was missing"),
+                ));
+                continue;
+            };
+            let result = match self.rules.query.get(name) {
+                Some(rules) => expected_values.first().map_or(Ok(()), |template| {
+                    actual_values.iter().try_for_each(|value| {
+                        rules.check(
+                            &Value::String(template.clone()),
+                            &Value::String(value.clone()),
+                        )
+                    })
+                }),
+                None if expected_values == actual_values => Ok(()),
+                None => Err(format!(
+                    "Expected {expected_values:?} but received {actual_values:?}"
+                )),
+            };
+            if let Err(reason) = result {
+                mismatches.push(Mismatch::new("query", Some(name.clone()), reason));
+            }
+        }
+        for name in actual.keys().filter(|name| !expected.contains_key(*name)) {
+            mismatches.push(Mismatch::new(
+                "query",
+                Some(name.clone()),
+                format!("Unexpected query parameter '{name}'"),
+            ));
+        }
+    }
+
+    fn compare_headers(&self, actual: &HeaderMap, mismatches: &mut Vec<Mismatch>) {
+        for (name, expected) in &self.headers {
+            let values: Vec<&str> = actual
+                .get_all(name.as_str())
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .collect();
+            if values.is_empty() {
+                mismatches.push(Mismatch::new(
+                    "header",
+                    Some(name.clone()),
+                    format!("Expected header '{name}' but it was missing"),
+                ));
+                continue;
+            }
+            let value = values.join(", ");
+            let result = match self.rules.header.get(name) {
+                Some(rules) => rules.check(
+                    &Value::String(expected.clone()),
+                    &Value::String(value.clone()),
+                ),
+                None if header_values(expected) == header_values(&value) => Ok(()),
+                None => Err(format!("Expected '{expected}' but received '{value}'")),
+            };
+            if let Err(reason) = result {
+                mismatches.push(Mismatch::new("header", Some(name.clone()), reason));
+            }
+        }
+    }
+
+    fn compare_body(&self, headers: &HeaderMap, body: &[u8], mismatches: &mut Vec<Mismatch>) {
+        let Some(expected) = &self.body else {
+            return;
+        };
+        if body.is_empty() {
+            if !expected.is_null() && *expected != Value::String(String::new()) {
+                mismatches.push(Mismatch::new(
+                    "body",
+                    None,
+                    "Expected a body but the request had none".to_string(),
+                ));
+            }
+            return;
+        }
+
+        let is_json = headers
+            .get(axum::http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_none_or(|content_type| content_type.contains("json"));
+        // Contracts written with a raw JSON string body compare as JSON
+        let expected = match expected {
+            Value::String(text) if is_json => {
+                serde_json::from_str(text).unwrap_or_else(|_| expected.clone())
+            }
+            expected => expected.clone(),
+        };
+        let actual = if is_json || expected.is_object() || expected.is_array() {
+            match serde_json::from_slice::<Value>(body) {
+                Ok(actual) => actual,
+                Err(e) => {
+                    mismatches.push(Mismatch::new(
+                        "body",
+                        None,
+                        format!("Expected a JSON body but it could not be parsed: {e}"),
+                    ));
+                    return;
+                }
+            }
+        } else {
+            Value::String(String::from_utf8_lossy(body).into_owned())
+        };
+        compare_value(&expected, &actual, &mut Vec::new(), &self.rules, mismatches);
+    }
+}
+
+/// Compare a body value recursively, applying the most specific matching rule at each path
+fn compare_value(
+    expected: &Value,
+    actual: &Value,
+    path: &mut Vec<Segment>,
+    rules: &RequestRules,
+    mismatches: &mut Vec<Mismatch>,
+) {
+    let rule = rules.body_rule(path);
+    if let Some((rule, exact)) = rule {
+        // Cascaded rules only check array lengths on the array they were declared for
+        let result = if exact || !rule.is_type() {
+            rule.check(expected, actual)
+        } else {
+            RuleSet {
+                matchers: vec![Matcher::Type {
+                    min: None,
+                    max: None,
+                }],
+                combine_or: false,
+            }
+            .check(expected, actual)
+        };
+        if let Err(reason) = result {
+            mismatches.push(body_mismatch(path, reason));
+            return;
+        }
+        if !rule.is_type() {
+            // Equality, regex and value matchers decide for the whole value
+            return;
+        }
+    }
+    let by_type = rule.is_some_and(|(rule, _)| rule.is_type());
+
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            compare_object(expected, actual, path, rules, mismatches);
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            if by_type {
+                // Every item matches the first expected item as a template
+                let Some(template) = expected.first() else {
+                    return;
+                };
+                for (index, item) in actual.iter().enumerate() {
+                    path.push(Segment::Index(index));
+                    compare_value(template, item, path, rules, mismatches);
+                    path.pop();
+                }
+                return;
+            }
+            if expected.len() != actual.len() {
+                mismatches.push(body_mismatch(
+                    path,
+                    format!(
+                        "Expected {} item(s) but received {}",
+                        expected.len(),
+                        actual.len()
+                    ),
+                ));
+                return;
+            }
+            for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                path.push(Segment::Index(index));
+                compare_value(expected, actual, path, rules, mismatches);
+                path.pop();
+            }
+        }
+        _ if by_type => {}
+        _ if expected == actual => {}
+        _ => mismatches.push(body_mismatch(
+            path,
+            format!("Expected {expected} but received {actual}"),
+        )),
+    }
+}
+
+fn body_mismatch(path: &[Segment], reason: String) -> Mismatch {
+    Mismatch::new("body", Some(format_path(path)), reason)
+}
+
+fn compare_object(
+    expected: &Map<String, Value>,
+    actual: &Map<String, Value>,
+    path: &mut Vec<Segment>,
+    rules: &RequestRules,
+    mismatches: &mut Vec<Mismatch>,
+) {
+    for (key, expected_value) in expected {
+        path.push(Segment::Field(key.clone()));
+        match actual.get(key) {
+            Some(actual_value) => {
+                compare_value(expected_value, actual_value, path, rules, mismatches)
+            }
+            None => mismatches.push(body_mismatch(
+                path,
+                format!("Expected key '{key}' but it was missing"),
+            )),
+        }
+        path.pop();
+    }
+    for key in actual.keys().filter(|key| !expected.contains_key(*key)) {
+        path.push(Segment::Field(key.clone()));
+        mismatches.push(body_mismatch(path, format!("Unexpected key '{key}'")));
+        path.pop();
+    }
+}
+
+/// Strings, numbers and booleans as text (what regex and include matchers see)
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+fn same_type(expected: &Value, actual: &Value) -> bool {
+    std::mem::discriminant(expected) == std::mem::discriminant(actual)
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Header values compared as comma-separated lists, ignoring whitespace
+fn header_values(value: &str) -> Vec<&str> {
+    value.split(',').map(str::trim).collect()
+}
+
+fn parse_query(query: &str) -> HashMap<String, Vec<String>> {
+    let uri: Option<Uri> = format!("/?{query}").parse().ok();
+    let pairs = uri
+        .and_then(|uri| {
+            axum::extract::Query::<Vec<(String, String)>>::try_from_uri(&uri)
+                .ok()
+                .map(|query| query.0)
+        })
+        .unwrap_or_default();
+    let mut params: HashMap<String, Vec<String>> = HashMap::new();
+    for (name, value) in pairs {
+        params.entry(name).or_default().push(value);
+    }
+    params
+}
+
+/// Interactions from the loaded contracts (a single pact or a list of pacts)
+fn interactions_from_contracts(contracts: &HashMap<String, Value>) -> Vec<Interaction> {
+    let mut pacts = Vec::new();
+    for contract in contracts.values() {
+        match contract {
+            Value::Array(list) => pacts.extend(list.iter()),
+            pact => pacts.push(pact),
+        }
+    }
+    pacts
+        .into_iter()
+        .filter_map(|pact| pact.get("interactions").and_then(Value::as_array))
+        .flatten()
+        .filter_map(Interaction::from_json)
+        .collect()
+}
+
+/// Verify a request against the interactions
+/// Returns `Ok(description)` of the matching interaction, or the mismatch report
+fn verify_request(
+    interactions: &[Interaction],
+    method: &Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<String, MismatchReport> {
+    let path = uri.path();
+    let query = uri.query().map(parse_query).unwrap_or_default();
+    let mut candidates = Vec::new();
+    for interaction in interactions
+        .iter()
+        .filter(|interaction| interaction.matches_route(method, path))
+    {
+        let mismatches = interaction.mismatches(&query, headers, body);
+        if mismatches.is_empty() {
+            return Ok(interaction.description.clone());
+        }
+        candidates.push(InteractionMismatch {
+            interaction: interaction.description.clone(),
+            mismatches,
+        });
+    }
+    Err(MismatchReport {
+        method: method.to_string(),
+        path: path.to_string(),
+        candidates,
+    })
+}
+
+#[derive(Debug, Default)]
+struct VerificationStats {
+    verified: u64,
+    mismatched: u64,
+    recent: VecDeque<MismatchReport>,
+}
+
+/// Shared Pact verification state
+#[derive(Clone, Debug)]
+pub struct PactVerificationState {
+    mode: VerificationMode,
+    /// The server's loaded contracts (same map as the app state's `contracts`)
+    contracts: Arc<RwLock<HashMap<String, Value>>>,
+    stats: Arc<RwLock<VerificationStats>>,
+}
+
+impl PactVerificationState {
+    pub fn new(mode: VerificationMode, contracts: Arc<RwLock<HashMap<String, Value>>>) -> Self {
+        Self {
+            mode,
+            contracts,
+            stats: Arc::new(RwLock::new(VerificationStats::default())),
+        }
+    }
+
+    /// Create the state with the mode in PACT_VERIFICATION_MODE
+    pub fn from_env(contracts: Arc<RwLock<HashMap<String, Value>>>) -> Self {
+        let mode = std::env::var("PACT_VERIFICATION_MODE").map_or(VerificationMode::Off, |value| {
+            VerificationMode::parse(&value).unwrap_or_else(|| {
+                warn!(
+                    "Invalid PACT_VERIFICATION_MODE '{}', expected off, report or enforce; verification disabled",
+                    value
+                );
+                VerificationMode::Off
+            })
+        });
+        if mode != VerificationMode::Off {
+            info!("Pact verification enabled ({:?})", mode);
+        }
+        Self::new(mode, contracts)
+    }
+
+    async fn record(&self, result: Result<(), MismatchReport>) {
+        let mut stats = self.stats.write().await;
+        match result {
+            Ok(()) => stats.verified += 1,
+            Err(report) => {
+                stats.mismatched += 1;
+                if stats.recent.len() >= RECENT_MISMATCHES {
+                    stats.recent.pop_front();
+                }
+                stats.recent.push_back(report);
+            }
+        }
+    }
+}
+
+/// Pact verification middleware
+/// Checks each provider request against the loaded interactions
+pub async fn pact_verification_middleware(
+    State(verification): State<PactVerificationState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if verification.mode == VerificationMode::Off
+        || crate::is_admin_path(request.method(), request.uri().path())
+    {
+        return next.run(request).await;
+    }
+
+    let interactions = interactions_from_contracts(&*verification.contracts.read().await);
+    if interactions.is_empty() {
+        // Nothing to verify against (broker unavailable or no pacts published)
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Pact verification: failed to read request body: {}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    match verify_request(
+        &interactions,
+        &parts.method,
+        &parts.uri,
+        &parts.headers,
+        &bytes,
+    ) {
+        Ok(interaction) => {
+            info!("Pact verification: matched '{}'", interaction);
+            verification.record(Ok(())).await;
+            next.run(Request::from_parts(parts, Body::from(bytes)))
+                .await
+        }
+        Err(report) => {
+            warn!(
+                "Pact verification: {} {} matches no interaction ({} candidate(s))",
+                report.method,
+                report.path,
+                report.candidates.len()
+            );
+            let body = json!({
+                "error": {
+                    "code": 400,
+                    "message": format!(
+                        "Request {} {} does not match any Pact interaction",
+                        report.method, report.path
+                    )
+                },
+                "mismatches": report.candidates
+            });
+            verification.record(Err(report)).await;
+            if verification.mode == VerificationMode::Enforce {
+                return (StatusCode::BAD_REQUEST, Json(body)).into_response();
+            }
+            let mut response = next
+                .run(Request::from_parts(parts, Body::from(bytes)))
+                .await;
+            response
+                .headers_mut()
+                .insert("x-pact-verification", HeaderValue::from_static("mismatch"));
+            response
+        }
+    }
+}
+
+async fn verification_status(State(verification): State<PactVerificationState>) -> Json<Value> {
+    let interactions = interactions_from_contracts(&*verification.contracts.read().await).len();
+    let stats = verification.stats.read().await;
+    Json(json!({
+        "mode": verification.mode,
+        "interactions": interactions,
+        "verified": stats.verified,
+        "mismatched": stats.mismatched,
+        "recentMismatches": stats.recent
+    }))
+}
+
+async fn reset_verification(State(verification): State<PactVerificationState>) -> StatusCode {
+    *verification.stats.write().await = VerificationStats::default();
+    StatusCode::NO_CONTENT
+}
+
+/// Admin routes for Pact verification, merged into each mock server's router
+pub fn pact_verification_router(verification: PactVerificationState) -> Router {
+    Router::new()
+        .route(
+            "/pact/verification",
+            get(verification_status).delete(reset_verification),
+        )
+        .with_state(verification)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum_test::TestServer;
+
+    fn pact() -> Value {
+        json!({
+            "consumer": { "name": "Secret-Manager-Controller" },
+            "provider": { "name": "AWS-Secrets-Manager" },
+            "interactions": [
+                {
+                    "description": "update a secret",
+                    "request": {
+                        "method": "POST",
+                        "path": "/",
+                        "headers": { "x-amz-target": "secretsmanager.PutSecretValue" },
+                        "body": {
+                            "SecretId": "test-secret-name",
+                            "SecretString": "value",
+                            "Tags": [{ "Key": "environment", "Value": "test" }]
+                        },
+                        "matchingRules": {
+                            "body": {
+                                "$.SecretString": { "matchers": [{ "match": "type" }] },
+                                "$.Tags": { "matchers": [{ "match": "type", "min": 1 }] },
+                                "$.Tags[*].Key": { "matchers": [{ "match": "regex", "regex": "environment|location" }] }
+                            }
+                        }
+                    },
+                    "response": { "status": 200 }
+                },
+                {
+                    "description": "get a secret",
+                    "request": {
+                        "method": "GET",
+                        "path": "/v1/projects/test-project/secrets/db-password",
+                        "query": { "api-version": ["7.4"] }
+                    },
+                    "response": { "status": 200 }
+                }
+            ],
+            "metadata": { "pactSpecification": { "version": "3.0.0" } }
+        })
+    }
+
+    fn verify(
+        method: Method,
+        uri: &str,
+        headers: &[(&str, &str)],
+        body: &Value,
+    ) -> Result<String, MismatchReport> {
+        let interactions =
+            interactions_from_contracts(&HashMap::from([("contracts".to_string(), pact())]));
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(
+                axum::http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        let body = if body.is_null() {
+            Vec::new()
+        } else {
+            serde_json::to_vec(body).unwrap()
+        };
+        verify_request(
+            &interactions,
+            &method,
+            &uri.parse().unwrap(),
+            &header_map,
+            &body,
+        )
+    }
+
+    fn aws_body() -> Value {
+        json!({
+            "SecretId": "test-secret-name",
+            "SecretString": "another value",
+            "Tags": [
+                { "Key": "environment", "Value": "test" },
+                { "Key": "location", "Value": "test" }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_rule_paths() {
+        assert_eq!(
+            parse_rule_path("$.Tags[*].Key"),
+            Some(vec![
+                Segment::Field("Tags".to_string()),
+                Segment::Wildcard,
+                Segment::Field("Key".to_string())
+            ])
+        );
+        assert_eq!(
+            parse_rule_path("$['a.b'][0]"),
+            Some(vec![Segment::Field("a.b".to_string()), Segment::Index(0)])
+        );
+        assert_eq!(parse_rule_path("body.a"), None);
+    }
+
+    #[test]
+    fn test_matching_rules_accept_conforming_request() {
+        let headers = [
+            ("x-amz-target", "secretsmanager.PutSecretValue"),
+            ("content-type", "application/x-amz-json-1.1"),
+        ];
+        assert_eq!(
+            verify(Method::POST, "/", &headers, &aws_body()),
+            Ok("update a secret".to_string())
+        );
+    }
+
+    #[test]
+    fn test_mismatches_are_reported_with_paths() {
+        let mut body = aws_body();
+        body["SecretString"] = json!(42);
+        body["Tags"][1]["Key"] = json!("team");
+        body["Unexpected"] = json!(true);
+        let report = verify(
+            Method::POST,
+            "/",
+            &[("x-amz-target", "secretsmanager.PutSecretValue")],
+            &body,
+        )
+        .unwrap_err();
+
+        assert_eq!(report.candidates.len(), 1);
+        let paths: Vec<Option<String>> = report.candidates[0]
+            .mismatches
+            .iter()
+            .map(|m| m.path.clone())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                Some("$.SecretString".to_string()),
+                Some("$.Tags[1].Key".to_string()),
+                Some("$.Unexpected".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_min_length_and_headers() {
+        let mut body = aws_body();
+        body["Tags"] = json!([]);
+        let report = verify(
+            Method::POST,
+            "/",
+            &[("x-amz-target", "secretsmanager.GetSecretValue")],
+            &body,
+        )
+        .unwrap_err();
+        let kinds: Vec<&str> = report.candidates[0]
+            .mismatches
+            .iter()
+            .map(|m| m.kind)
+            .collect();
+        assert_eq!(kinds, vec!["header", "body"]);
+    }
+
+    #[test]
+    fn test_query_and_unknown_route() {
+        assert!(
+            verify(
+                Method::GET,
+                "/v1/projects/test-project/secrets/db-password?api-version=7.4",
+                &[],
+                &Value::Null
+            )
+            .is_ok()
+        );
+        let report = verify(
+            Method::GET,
+            "/v1/projects/test-project/secrets/db-password?api-version=7.3&extra=1",
+            &[],
+            &Value::Null,
+        )
+        .unwrap_err();
+        assert_eq!(report.candidates[0].mismatches.len(), 2);
+
+        let report = verify(Method::DELETE, "/v1/unknown", &[], &Value::Null).unwrap_err();
+        assert!(report.candidates.is_empty());
+    }
+
+    #[test]
+    fn test_v2_and_v4_formats() {
+        let v2 = json!({
+            "interactions": [{
+                "description": "v2",
+                "request": {
+                    "method": "PUT",
+                    "path": "/secrets/a",
+                    "query": "api-version=7.4",
+                    "body": { "value": "x" },
+                    "matchingRules": { "$.body.value": { "match": "type" }, "$.path": { "match": "regex", "regex": "/secrets/[a-z]+" } }
+                }
+            }]
+        });
+        let v4 = json!({
+            "interactions": [{
+                "type": "Synchronous/HTTP",
+                "description": "v4",
+                "request": {
+                    "method": "PUT",
+                    "path": "/v4",
+                    "body": { "content": { "value": "x" }, "contentType": "application/json", "encoded": false }
+                }
+            }]
+        });
+        let interactions = interactions_from_contracts(&HashMap::from([(
+            "contracts".to_string(),
+            json!([v2, v4]),
+        )]));
+        let body = serde_json::to_vec(&json!({ "value": "y" })).unwrap();
+        assert_eq!(
+            verify_request(
+                &interactions,
+                &Method::PUT,
+                &"/secrets/other?api-version=7.4".parse().unwrap(),
+                &HeaderMap::new(),
+                &body
+            ),
+            Ok("v2".to_string())
+        );
+        assert!(
+            verify_request(
+                &interactions,
+                &Method::PUT,
+                &"/v4".parse().unwrap(),
+                &HeaderMap::new(),
+                &body
+            )
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_enforce_mode_rejects_mismatches() {
+        let contracts = Arc::new(RwLock::new(HashMap::from([(
+            "contracts".to_string(),
+            pact(),
+        )])));
+        let verification = PactVerificationState::new(VerificationMode::Enforce, contracts);
+        let app = Router::new()
+            .route("/", post(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                verification.clone(),
+                pact_verification_middleware,
+            ))
+            .merge(pact_verification_router(verification));
+        let server = TestServer::new(app).unwrap();
+
+        server
+            .post("/")
+            .add_header("x-amz-target", "secretsmanager.PutSecretValue")
+            .json(&aws_body())
+            .await
+            .assert_status_ok();
+        let response = server
+            .post("/")
+            .add_header("x-amz-target", "secretsmanager.DeleteSecret")
+            .json(&aws_body())
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: Value = response.json();
+        assert_eq!(body["mismatches"][0]["interaction"], "update a secret");
+
+        let status: Value = server.get("/pact/verification").await.json();
+        assert_eq!(status["mode"], "enforce");
+        assert_eq!(status["verified"], 1);
+        assert_eq!(status["mismatched"], 1);
+    }
+}
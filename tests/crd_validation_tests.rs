@@ -5,8 +5,8 @@
 //! that sample resources match the expected schema.
 
 use controller::crd::{
-    AwsAuthConfig, AzureAuthConfig, ConfigStoreType, GcpAuthConfig, OtelConfig, ProviderConfig,
-    SecretManagerConfig,
+    AwsAuthConfig, AzureAuthConfig, ConfigStoreType, FailedSecret, GcpAuthConfig, OtelConfig,
+    ProviderConfig, SecretManagerConfig,
 };
 
 /// Test GCP provider configuration with all fields
@@ -62,6 +62,7 @@ spec:
                 } => {
                     assert_eq!(service_account_email, "sa@project.iam.gserviceaccount.com");
                 }
+                other => panic!("Expected WorkloadIdentity auth, got {other:?}"),
             }
         }
         _ => panic!("Expected GCP provider"),
@@ -272,9 +273,10 @@ spec:
             assert_eq!(azure.vault_name, "my-vault");
             assert!(azure.auth.is_some());
             match azure.auth.as_ref().unwrap() {
-                AzureAuthConfig::WorkloadIdentity { client_id } => {
+                AzureAuthConfig::WorkloadIdentity { client_id, .. } => {
                     assert_eq!(client_id, "12345678-1234-1234-1234-123456789012");
                 }
+                other => panic!("Expected WorkloadIdentity auth, got {other:?}"),
             }
         }
         _ => panic!("Expected Azure provider"),
@@ -435,6 +437,7 @@ spec:
             service_name,
             service_version,
             environment,
+            ..
         } => {
             assert_eq!(endpoint, "http://otel-collector:4317");
             // Optional fields - they should be Some when provided in YAML
@@ -485,6 +488,7 @@ spec:
             service_name,
             service_version,
             environment,
+            ..
         } => {
             // Optional fields - they should be Some when provided in YAML
             assert_eq!(site.as_deref(), Some("datadoghq.com"));
@@ -958,3 +962,48 @@ spec:
     assert_eq!(config.spec.reconcile_interval, "30s");
     assert_eq!(config.spec.git_repository_pull_interval, "10m");
 }
+
+/// Test partial failure status with failedSecrets
+#[test]
+fn test_status_failed_secrets() {
+    let yaml = r#"
+apiVersion: secret-management.octopilot.io/v1beta1
+kind: SecretManagerConfig
+metadata:
+  name: test-partial
+  namespace: default
+spec:
+  sourceRef:
+    name: my-repo
+    namespace: octopilot-system
+  provider:
+    gcp:
+      projectId: my-gcp-project
+      location: us-central1
+  secrets:
+    environment: dev
+status:
+  phase: PartialFailure
+  secretsSynced: 2
+  failedSecrets:
+    - name: my-service-db-password
+      reason: permission denied
+      lastAttemptTime: "2024-01-15T10:30:45Z"
+    - name: my-service-api-key
+      reason: quota exceeded
+"#;
+
+    let config: SecretManagerConfig =
+        serde_yaml::from_str(yaml).expect("Should deserialize status with failedSecrets");
+
+    let status = config.status.as_ref().unwrap();
+    assert_eq!(status.phase.as_deref(), Some("PartialFailure"));
+    let failed = status.failed_secrets.as_ref().unwrap();
+    assert_eq!(failed.len(), 2);
+    assert_eq!(failed[0].name, "my-service-db-password");
+    assert_eq!(failed[0].reason, "permission denied");
+    assert!(failed[1].last_attempt_time.is_none());
+
+    let failure = FailedSecret::new("my-secret", "boom");
+    assert!(failure.last_attempt_time.is_some());
+}
@@ -38,6 +38,8 @@ async fn test_get_flux_git_repository_missing() {
         kind: "GitRepository".to_string(),
         name: "non-existent-repo".to_string(),
         namespace: "default".to_string(),
+        url: None,
+        revision: None,
         git_credentials: None,
     };
 
@@ -65,6 +67,8 @@ async fn test_get_flux_git_repository_no_artifact() {
         kind: "GitRepository".to_string(),
         name: "repo-without-artifact".to_string(),
         namespace: "default".to_string(),
+        url: None,
+        revision: None,
         git_credentials: None,
     };
 
@@ -113,6 +117,8 @@ async fn test_get_argocd_artifact_path_missing_application() {
         kind: "Application".to_string(),
         name: "non-existent-app".to_string(),
         namespace: "default".to_string(),
+        url: None,
+        revision: None,
         git_credentials: None,
     };
 
@@ -140,6 +146,8 @@ async fn test_get_argocd_artifact_path_no_repo() {
         kind: "Application".to_string(),
         name: "app-without-repo".to_string(),
         namespace: "default".to_string(),
+        url: None,
+        revision: None,
         git_credentials: None,
     };
 
@@ -163,6 +171,8 @@ fn test_source_ref_validation() {
         kind: "GitRepository".to_string(),
         name: "test-repo".to_string(),
         namespace: "default".to_string(),
+        url: None,
+        revision: None,
         git_credentials: None,
     };
 
@@ -175,6 +185,8 @@ fn test_source_ref_validation() {
         kind: "Application".to_string(),
         name: "test-app".to_string(),
         namespace: "argocd".to_string(),
+        url: None,
+        revision: None,
         git_credentials: None,
     };
 
@@ -42,12 +42,16 @@ mod tests {
                     kind: "GitRepository".to_string(),
                     name: gitrepo_name.to_string(),
                     namespace: gitrepo_namespace.to_string(),
-                    git_credentials_ref: None,
+                    url: None,
+                    revision: None,
+                    git_credentials: None,
                 },
                 provider: ProviderConfig::Gcp(GcpConfig {
                     project_id: "test-project".to_string(),
                     location: "us-central1".to_string(),
                     auth: None,
+                    version_retention: None,
+                    endpoint_override: None,
                 }),
                 secrets: SecretsConfig {
                     environment: "test".to_string(),
@@ -55,11 +59,19 @@ mod tests {
                     suffix: None,
                     kustomize_path: None,
                     base_path: None,
+                    binary_files: None,
+                    routes: None,
+                    limits: None,
+                    policy: None,
+                    environments: None,
+                    name_template: None,
+                    sanitization: None,
+                    grouping: None,
                 },
                 configs: None,
                 otel: None,
-                git_repository_pull_interval: "5m".to_string(),
-                reconcile_interval: "1m".to_string(),
+                git_repository_pull_interval: "5m".into(),
+                reconcile_interval: "1m".into(),
                 diff_discovery: true,
                 trigger_update: true,
                 suspend: false,
@@ -67,6 +79,9 @@ mod tests {
                 notifications: None,
             hot_reload: None,
             logging: None,
+                sops_key_secret_ref: None,
+                provider_rate_limit: None,
+                approval_policy: None,
             },
             status: None,
         }
@@ -235,12 +235,16 @@ pub fn create_gcp_test_config(
                 kind: "GitRepository".to_string(),
                 name: "test-repo".to_string(),
                 namespace: "default".to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             provider: ProviderConfig::Gcp(GcpConfig {
                 project_id: project_id.to_string(),
                 location: "us-central1".to_string(),
                 auth: None,
+                version_retention: None,
+                endpoint_override: None,
             }),
             secrets: SecretsConfig {
                 environment: "test".to_string(),
@@ -248,11 +252,19 @@ pub fn create_gcp_test_config(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -260,6 +272,9 @@ pub fn create_gcp_test_config(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -283,11 +298,17 @@ pub fn create_aws_test_config(
                 kind: "GitRepository".to_string(),
                 name: "test-repo".to_string(),
                 namespace: "default".to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             provider: ProviderConfig::Aws(AwsConfig {
                 region: region.to_string(),
                 auth: None,
+                version_stages: None,
+                endpoint_url: None,
+                use_fips: false,
+                assume_role_arn: None,
             }),
             secrets: SecretsConfig {
                 environment: "test".to_string(),
@@ -295,11 +316,19 @@ pub fn create_aws_test_config(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -307,6 +336,9 @@ pub fn create_aws_test_config(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -330,12 +362,17 @@ pub fn create_azure_test_config(
                 kind: "GitRepository".to_string(),
                 name: "test-repo".to_string(),
                 namespace: "default".to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             provider: ProviderConfig::Azure(AzureConfig {
                 vault_name: vault_name.to_string(),
                 location: "eastus".to_string(),
                 auth: None,
+                certificates: None,
+                environment: AzureEnvironment::Public,
+                role_assignments: None,
             }),
             secrets: SecretsConfig {
                 environment: "test".to_string(),
@@ -343,11 +380,19 @@ pub fn create_azure_test_config(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -355,6 +400,9 @@ pub fn create_azure_test_config(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -62,6 +62,8 @@ pub fn create_test_secret_manager_config_flux_with_options(
                 kind: "GitRepository".to_string(),
                 name: git_repo_name.to_string(),
                 namespace: git_repo_namespace.to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             secrets: SecretsConfig {
@@ -70,16 +72,26 @@ pub fn create_test_secret_manager_config_flux_with_options(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             provider: ProviderConfig::Gcp(GcpConfig {
                 project_id: project.to_string(),
                 location: "us-central1".to_string(),
                 auth: None,
+                version_retention: None,
+                endpoint_override: None,
             }),
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery,
             trigger_update,
             suspend: false,
@@ -87,6 +99,9 @@ pub fn create_test_secret_manager_config_flux_with_options(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -120,6 +135,8 @@ pub fn create_test_secret_manager_config_argocd(
                 kind: "Application".to_string(),
                 name: app_name.to_string(),
                 namespace: app_namespace.to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             secrets: SecretsConfig {
@@ -128,16 +145,26 @@ pub fn create_test_secret_manager_config_argocd(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             provider: ProviderConfig::Gcp(GcpConfig {
                 project_id: project.to_string(),
                 location: "us-central1".to_string(),
                 auth: None,
+                version_retention: None,
+                endpoint_override: None,
             }),
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -145,6 +172,9 @@ pub fn create_test_secret_manager_config_argocd(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -178,6 +208,8 @@ pub fn create_test_secret_manager_config_aws_flux(
                 kind: "GitRepository".to_string(),
                 name: git_repo_name.to_string(),
                 namespace: git_repo_namespace.to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             secrets: SecretsConfig {
@@ -186,15 +218,27 @@ pub fn create_test_secret_manager_config_aws_flux(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             provider: ProviderConfig::Aws(AwsConfig {
                 region: region.to_string(),
                 auth: None,
+                version_stages: None,
+                endpoint_url: None,
+                use_fips: false,
+                assume_role_arn: None,
             }),
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -202,6 +246,9 @@ pub fn create_test_secret_manager_config_aws_flux(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -235,6 +282,8 @@ pub fn create_test_secret_manager_config_aws_argocd(
                 kind: "Application".to_string(),
                 name: app_name.to_string(),
                 namespace: app_namespace.to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             secrets: SecretsConfig {
@@ -243,15 +292,27 @@ pub fn create_test_secret_manager_config_aws_argocd(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             provider: ProviderConfig::Aws(AwsConfig {
                 region: region.to_string(),
                 auth: None,
+                version_stages: None,
+                endpoint_url: None,
+                use_fips: false,
+                assume_role_arn: None,
             }),
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -259,6 +320,9 @@ pub fn create_test_secret_manager_config_aws_argocd(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -292,6 +356,8 @@ pub fn create_test_secret_manager_config_azure_flux(
                 kind: "GitRepository".to_string(),
                 name: git_repo_name.to_string(),
                 namespace: git_repo_namespace.to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             secrets: SecretsConfig {
@@ -300,16 +366,27 @@ pub fn create_test_secret_manager_config_azure_flux(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             provider: ProviderConfig::Azure(AzureConfig {
                 vault_name: vault_name.to_string(),
                 location: "eastus".to_string(),
                 auth: None,
+                certificates: None,
+                environment: AzureEnvironment::Public,
+                role_assignments: None,
             }),
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -317,6 +394,9 @@ pub fn create_test_secret_manager_config_azure_flux(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -350,6 +430,8 @@ pub fn create_test_secret_manager_config_azure_argocd(
                 kind: "Application".to_string(),
                 name: app_name.to_string(),
                 namespace: app_namespace.to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             secrets: SecretsConfig {
@@ -358,16 +440,27 @@ pub fn create_test_secret_manager_config_azure_argocd(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             provider: ProviderConfig::Azure(AzureConfig {
                 vault_name: vault_name.to_string(),
                 location: "eastus".to_string(),
                 auth: None,
+                certificates: None,
+                environment: AzureEnvironment::Public,
+                role_assignments: None,
             }),
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -375,6 +468,9 @@ pub fn create_test_secret_manager_config_azure_argocd(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
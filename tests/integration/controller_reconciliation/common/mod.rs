@@ -302,6 +302,8 @@ pub fn create_gcp_reconciliation_config(
                 kind: "GitRepository".to_string(),
                 name: git_repo_name.to_string(),
                 namespace: git_repo_namespace.to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             secrets: SecretsConfig {
@@ -310,16 +312,26 @@ pub fn create_gcp_reconciliation_config(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             provider: ProviderConfig::Gcp(GcpConfig {
                 project_id: project.to_string(),
                 location: "us-central1".to_string(),
                 auth: None,
+                version_retention: None,
+                endpoint_override: None,
             }),
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -327,6 +339,9 @@ pub fn create_gcp_reconciliation_config(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -358,6 +373,8 @@ pub fn create_aws_reconciliation_config(
                 kind: "GitRepository".to_string(),
                 name: git_repo_name.to_string(),
                 namespace: git_repo_namespace.to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             secrets: SecretsConfig {
@@ -366,15 +383,27 @@ pub fn create_aws_reconciliation_config(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             provider: ProviderConfig::Aws(AwsConfig {
                 region: region.to_string(),
                 auth: None,
+                version_stages: None,
+                endpoint_url: None,
+                use_fips: false,
+                assume_role_arn: None,
             }),
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -382,6 +411,9 @@ pub fn create_aws_reconciliation_config(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -413,6 +445,8 @@ pub fn create_azure_reconciliation_config(
                 kind: "GitRepository".to_string(),
                 name: git_repo_name.to_string(),
                 namespace: git_repo_namespace.to_string(),
+                url: None,
+                revision: None,
                 git_credentials: None,
             },
             secrets: SecretsConfig {
@@ -421,16 +455,27 @@ pub fn create_azure_reconciliation_config(
                 suffix: None,
                 kustomize_path: None,
                 base_path: None,
+                binary_files: None,
+                routes: None,
+                limits: None,
+                policy: None,
+                environments: None,
+                name_template: None,
+                sanitization: None,
+                grouping: None,
             },
             provider: ProviderConfig::Azure(AzureConfig {
                 vault_name: vault_name.to_string(),
                 location: "eastus".to_string(),
                 auth: None,
+                certificates: None,
+                environment: AzureEnvironment::Public,
+                role_assignments: None,
             }),
             configs: None,
             otel: None,
-            git_repository_pull_interval: "1m".to_string(),
-            reconcile_interval: "1m".to_string(),
+            git_repository_pull_interval: "1m".into(),
+            reconcile_interval: "1m".into(),
             diff_discovery: true,
             trigger_update: true,
             suspend: false,
@@ -438,6 +483,9 @@ pub fn create_azure_reconciliation_config(
             notifications: None,
             hot_reload: None,
             logging: None,
+            sops_key_secret_ref: None,
+            provider_rate_limit: None,
+            approval_policy: None,
         },
         status: None,
     }
@@ -277,6 +277,10 @@ async fn test_aws_provider_create_secret_with_pact() {
     let config = AwsConfig {
         region: "us-east-1".to_string(),
         auth: None,
+        version_stages: None,
+        endpoint_url: None,
+        use_fips: false,
+        assume_role_arn: None,
     };
 
     let provider = AwsSecretManager::new(&config, &kube_client)
@@ -405,6 +409,10 @@ async fn test_aws_provider_update_secret_with_pact() {
     let config = AwsConfig {
         region: "us-east-1".to_string(),
         auth: None,
+        version_stages: None,
+        endpoint_url: None,
+        use_fips: false,
+        assume_role_arn: None,
     };
 
     eprintln!("🔧 Creating AWS Secrets Manager provider...");
@@ -526,6 +534,10 @@ async fn test_aws_provider_no_change_with_pact() {
     let config = AwsConfig {
         region: "us-east-1".to_string(),
         auth: None,
+        version_stages: None,
+        endpoint_url: None,
+        use_fips: false,
+        assume_role_arn: None,
     };
 
     eprintln!("🔧 Creating AWS Secrets Manager provider...");
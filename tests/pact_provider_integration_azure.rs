@@ -262,6 +262,9 @@ async fn test_azure_provider_create_secret_with_pact() {
         vault_name: "test-vault".to_string(),
         location: "eastus".to_string(),
         auth: None, // Use default (Managed Identity) - won't matter for Pact
+        certificates: None,
+        environment: AzureEnvironment::Public,
+        role_assignments: None,
     };
 
     let provider = AzureKeyVault::new(&config, &kube_client)
@@ -369,6 +372,9 @@ async fn test_azure_provider_update_secret_with_pact() {
         vault_name: "test-vault".to_string(),
         location: "eastus".to_string(),
         auth: None,
+        certificates: None,
+        environment: AzureEnvironment::Public,
+        role_assignments: None,
     };
 
     let provider = AzureKeyVault::new(&config, &kube_client)
@@ -444,6 +450,9 @@ async fn test_azure_provider_no_change_with_pact() {
         vault_name: "test-vault".to_string(),
         location: "eastus".to_string(),
         auth: None,
+        certificates: None,
+        environment: AzureEnvironment::Public,
+        role_assignments: None,
     };
 
     let provider = AzureKeyVault::new(&config, &kube_client)
@@ -128,6 +128,7 @@ async fn test_decrypt_dotenv_file() {
     let decrypted = decrypt_sops_content(&encrypted_content, Some(&file_path), sops_key.as_deref())
         .await
         .expect("Failed to decrypt SOPS file");
+    let decrypted = decrypted.expose_secret();
 
     // Verify decrypted content
     assert!(!decrypted.is_empty());
@@ -169,6 +170,7 @@ async fn test_decrypt_yaml_file() {
     let decrypted = decrypt_sops_content(&encrypted_content, Some(&file_path), sops_key.as_deref())
         .await
         .expect("Failed to decrypt SOPS file");
+    let decrypted = decrypted.expose_secret();
 
     // Verify decrypted content
     assert!(!decrypted.is_empty());
@@ -357,6 +359,8 @@ fn test_error_reason_strings() {
         SopsDecryptionFailureReason::NetworkTimeout,
         SopsDecryptionFailureReason::ProviderUnavailable,
         SopsDecryptionFailureReason::PermissionDenied,
+        SopsDecryptionFailureReason::KmsCredentialsMissing,
+        SopsDecryptionFailureReason::KmsAccessDenied,
         SopsDecryptionFailureReason::Unknown,
     ];
 
@@ -368,6 +372,18 @@ fn test_error_reason_strings() {
     }
 }
 
+#[test]
+fn test_key_errors_allow_key_ring_fallback() {
+    // Only key-related failures should cause the next key in the ring to be tried
+    assert!(SopsDecryptionFailureReason::KeyNotFound.is_key_error());
+    assert!(SopsDecryptionFailureReason::WrongKey.is_key_error());
+    assert!(SopsDecryptionFailureReason::InvalidKeyFormat.is_key_error());
+    assert!(!SopsDecryptionFailureReason::CorruptedFile.is_key_error());
+    assert!(!SopsDecryptionFailureReason::UnsupportedFormat.is_key_error());
+    assert!(!SopsDecryptionFailureReason::NetworkTimeout.is_key_error());
+    assert!(!SopsDecryptionFailureReason::Unknown.is_key_error());
+}
+
 #[test]
 fn test_content_based_type_detection() {
     // Test that file type detection works from content when path is not available
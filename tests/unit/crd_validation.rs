@@ -62,6 +62,7 @@ spec:
                 GcpAuthConfig::WorkloadIdentity { service_account_email } => {
                     assert_eq!(service_account_email, "sa@project.iam.gserviceaccount.com");
                 }
+                other => panic!("Expected WorkloadIdentity auth, got {other:?}"),
             }
         }
         _ => panic!("Expected GCP provider"),
@@ -270,9 +271,10 @@ spec:
             assert_eq!(azure.vault_name, "my-vault");
             assert!(azure.auth.is_some());
             match azure.auth.as_ref().unwrap() {
-                AzureAuthConfig::WorkloadIdentity { client_id } => {
+                AzureAuthConfig::WorkloadIdentity { client_id, .. } => {
                     assert_eq!(client_id, "12345678-1234-1234-1234-123456789012");
                 }
+                other => panic!("Expected WorkloadIdentity auth, got {other:?}"),
             }
         }
         _ => panic!("Expected Azure provider"),
@@ -433,6 +435,7 @@ spec:
             service_name,
             service_version,
             environment,
+            ..
         } => {
             assert_eq!(endpoint, &Some("http://otel-collector:4317".to_string()));
             assert_eq!(service_name, &Some("secret-manager-controller".to_string()));
@@ -482,6 +485,7 @@ spec:
             service_name,
             service_version,
             environment,
+            ..
         } => {
             assert_eq!(site, &Some("datadoghq.com".to_string()));
             assert_eq!(api_key, &Some("my-api-key".to_string()));
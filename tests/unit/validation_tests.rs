@@ -19,7 +19,7 @@ use controller::controller::reconciler::validation::{
     provider::validate_provider_config,
     secrets::validate_secret_name_component,
 };
-use controller::crd::{AwsConfig, AzureConfig, GcpConfig, ProviderConfig};
+use controller::crd::{AwsConfig, AzureConfig, AzureEnvironment, GcpConfig, ProviderConfig};
 
 #[test]
 fn test_validate_kubernetes_name_valid() {
@@ -284,6 +284,8 @@ fn test_validate_provider_config_gcp() {
         project_id: "my-project-123".to_string(),
         location: "us-central1".to_string(),
         auth: None,
+        version_retention: None,
+        endpoint_override: None,
     });
     assert!(validate_provider_config(&valid_config).is_ok());
 
@@ -292,6 +294,8 @@ fn test_validate_provider_config_gcp() {
         project_id: "".to_string(),
         location: "us-central1".to_string(),
         auth: None,
+        version_retention: None,
+        endpoint_override: None,
     });
     assert!(validate_provider_config(&invalid_config).is_err());
 
@@ -300,6 +304,8 @@ fn test_validate_provider_config_gcp() {
         project_id: "INVALID-PROJECT".to_string(), // Uppercase
         location: "us-central1".to_string(),
         auth: None,
+        version_retention: None,
+        endpoint_override: None,
     });
     assert!(validate_provider_config(&invalid_config2).is_err());
 }
@@ -310,6 +316,10 @@ fn test_validate_provider_config_aws() {
     let valid_config = ProviderConfig::Aws(AwsConfig {
         region: "us-east-1".to_string(),
         auth: None,
+        version_stages: None,
+        endpoint_url: None,
+        use_fips: false,
+        assume_role_arn: None,
     });
     assert!(validate_provider_config(&valid_config).is_ok());
 
@@ -317,6 +327,10 @@ fn test_validate_provider_config_aws() {
     let valid_config2 = ProviderConfig::Aws(AwsConfig {
         region: "us-gov-west-1".to_string(),
         auth: None,
+        version_stages: None,
+        endpoint_url: None,
+        use_fips: false,
+        assume_role_arn: None,
     });
     assert!(validate_provider_config(&valid_config2).is_ok());
 
@@ -324,6 +338,10 @@ fn test_validate_provider_config_aws() {
     let invalid_config = ProviderConfig::Aws(AwsConfig {
         region: "".to_string(),
         auth: None,
+        version_stages: None,
+        endpoint_url: None,
+        use_fips: false,
+        assume_role_arn: None,
     });
     assert!(validate_provider_config(&invalid_config).is_err());
 
@@ -331,6 +349,10 @@ fn test_validate_provider_config_aws() {
     let invalid_config2 = ProviderConfig::Aws(AwsConfig {
         region: "invalid-region".to_string(),
         auth: None,
+        version_stages: None,
+        endpoint_url: None,
+        use_fips: false,
+        assume_role_arn: None,
     });
     assert!(validate_provider_config(&invalid_config2).is_err());
 }
@@ -342,6 +364,9 @@ fn test_validate_provider_config_azure() {
         vault_name: "my-vault".to_string(),
         location: "eastus".to_string(),
         auth: None,
+        certificates: None,
+        environment: AzureEnvironment::Public,
+        role_assignments: None,
     });
     assert!(validate_provider_config(&valid_config).is_ok());
 
@@ -350,6 +375,9 @@ fn test_validate_provider_config_azure() {
         vault_name: "".to_string(),
         location: "eastus".to_string(),
         auth: None,
+        certificates: None,
+        environment: AzureEnvironment::Public,
+        role_assignments: None,
     });
     assert!(validate_provider_config(&invalid_config).is_err());
 
@@ -358,6 +386,9 @@ fn test_validate_provider_config_azure() {
         vault_name: "ab".to_string(), // Too short (min 3)
         location: "eastus".to_string(),
         auth: None,
+        certificates: None,
+        environment: AzureEnvironment::Public,
+        role_assignments: None,
     });
     assert!(validate_provider_config(&invalid_config2).is_err());
 
@@ -366,6 +397,9 @@ fn test_validate_provider_config_azure() {
         vault_name: "my--vault".to_string(),
         location: "eastus".to_string(),
         auth: None,
+        certificates: None,
+        environment: AzureEnvironment::Public,
+        role_assignments: None,
     });
     assert!(validate_provider_config(&invalid_config3).is_err());
 }
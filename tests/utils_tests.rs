@@ -6,8 +6,17 @@
 //! - Secret name construction
 //! - Secret name sanitization
 //! - Path component sanitization
+//! - Secret name template rendering
+//! - Provider-specific name sanitization
 
-use controller::controller::reconciler::{construct_secret_name, sanitize_secret_name};
+use controller::controller::reconciler::{
+    construct_secret_name, render_name_template, sanitize_for_provider, sanitize_secret_name,
+};
+use controller::crd::ProviderConfig;
+
+fn provider(value: serde_json::Value) -> ProviderConfig {
+    serde_json::from_value(value).unwrap_or_else(|e| panic!("invalid provider config: {e}"))
+}
 
 #[test]
 fn test_construct_secret_name_with_all_components() {
@@ -121,3 +130,72 @@ fn test_sanitize_secret_name_only_invalid_chars() {
 // Since we're testing through construct_secret_name which uses sanitize_secret_name,
 // and sanitize_secret_name is tested directly, we have good coverage.
 // Path component sanitization is tested indirectly through secret name construction.
+
+#[test]
+fn test_render_name_template_substitutes_variables() {
+    let variables = [("service", "idam"), ("env", "dev"), ("key", "db_password")];
+    let result = render_name_template("{service}-{env}-{key}", &variables);
+    assert_eq!(result, "idam-dev-db_password");
+}
+
+#[test]
+fn test_render_name_template_sanitizes_result() {
+    let variables = [("repo", "my.repo"), ("key", "api/key")];
+    let result = render_name_template("{repo}--{key}", &variables);
+    assert_eq!(result, "my_repo-api_key");
+}
+
+#[test]
+fn test_render_name_template_empty_variable_collapses_dashes() {
+    let variables = [("key", "token"), ("suffix", "")];
+    let result = render_name_template("{key}-{suffix}", &variables);
+    assert_eq!(result, "token");
+}
+
+#[test]
+fn test_sanitize_for_provider_gcp_keeps_underscores() {
+    let gcp = provider(serde_json::json!({
+        "gcp": { "projectId": "my-project", "location": "us-central1" }
+    }));
+    assert_eq!(
+        sanitize_for_provider("app-db_password", &gcp, None),
+        "app-db_password"
+    );
+    assert_eq!(
+        sanitize_for_provider("app-db.password", &gcp, None),
+        "app-db_password"
+    );
+}
+
+#[test]
+fn test_sanitize_for_provider_azure_uses_dashes() {
+    let azure = provider(serde_json::json!({
+        "azure": { "vaultName": "my-vault", "location": "eastus" }
+    }));
+    assert_eq!(
+        sanitize_for_provider("app-db_password", &azure, None),
+        "app-db-password"
+    );
+    // "_" is not allowed on Azure, so the default replacement is used instead
+    assert_eq!(
+        sanitize_for_provider("app_-_key", &azure, Some('_')),
+        "app-key"
+    );
+    assert_eq!(
+        sanitize_for_provider(&"a".repeat(200), &azure, None).len(),
+        127
+    );
+}
+
+#[test]
+fn test_sanitize_for_provider_aws_allows_path_characters() {
+    let aws = provider(serde_json::json!({ "aws": { "region": "us-east-1" } }));
+    assert_eq!(
+        sanitize_for_provider("app/db.password", &aws, None),
+        "app/db.password"
+    );
+    assert_eq!(
+        sanitize_for_provider("app db password", &aws, Some('-')),
+        "app-db-password"
+    );
+}